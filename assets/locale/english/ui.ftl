@@ -0,0 +1,46 @@
+# UI strings for shock2quest - anything that isn't in the original game's string resources.
+# Each language lives in locale/<language>/ui.ftl, and falls back to this file for missing messages.
+
+## Mission summary
+
+summary-title = Mission complete: { $mission }
+summary-kills = Kills: { $count }
+summary-kills-by-type = { "  " }{ $creature }: { $count }
+summary-damage-taken = Damage taken: { $amount }
+summary-damage-absorbed = Damage absorbed by armor: { $amount }
+summary-shots = Shots fired: { $fired } (hit: { $hit }, accuracy: { $accuracy })
+summary-nanites-spent = Nanites spent: { $amount }
+summary-hacks = Hacks: { $attempted } attempted, { $succeeded } succeeded
+summary-secrets = Secrets found: { $count }
+
+## Inventory
+
+equip-armor = Armor
+equip-implant = Implant
+
+## Elevator / GamePig
+
+gamepig-title = GamePig
+elevator-deck-1 = 1: Engineering
+elevator-deck-2 = 2: Med/Sci
+elevator-deck-3 = 3: Hydroponics
+elevator-deck-4 = 4: Operations
+elevator-deck-5 = 5: Recreation
+
+## Interaction prompts
+
+interact-pick-up = Pick up
+interact-hack = Hack
+interact-open = Open
+interact-use = Use
+
+## Examine
+
+examine-unknown-item = Unknown item
+examine-condition = Condition: { $value }
+examine-ammo = Ammo: { $count }
+
+## Hints
+
+hint-hold-item = Keep squeezing the grip to hold on - pull the trigger to use it, or let go to drop it
+hint-hack-lock = Locked out? Point at the lock and pull the trigger to hack it
@@ -0,0 +1,44 @@
+# German UI strings - missing messages fall back to locale/english/ui.ftl
+
+## Mission summary
+
+summary-title = Mission abgeschlossen: { $mission }
+summary-kills = Abschüsse: { $count }
+summary-kills-by-type = { "  " }{ $creature }: { $count }
+summary-damage-taken = Erlittener Schaden: { $amount }
+summary-damage-absorbed = Von der Panzerung absorbierter Schaden: { $amount }
+summary-shots = Schüsse: { $fired } (Treffer: { $hit }, Genauigkeit: { $accuracy })
+summary-nanites-spent = Ausgegebene Naniten: { $amount }
+summary-hacks = Hacks: { $attempted } versucht, { $succeeded } erfolgreich
+summary-secrets = Gefundene Geheimnisse: { $count }
+
+## Inventory
+
+equip-armor = Panzerung
+equip-implant = Implantat
+
+## Elevator / GamePig
+
+elevator-deck-1 = 1: Technik
+elevator-deck-2 = 2: Med/Wiss
+elevator-deck-3 = 3: Hydroponik
+elevator-deck-4 = 4: Operationen
+elevator-deck-5 = 5: Erholung
+
+## Interaction prompts
+
+interact-pick-up = Aufheben
+interact-hack = Hacken
+interact-open = Öffnen
+interact-use = Benutzen
+
+## Examine
+
+examine-unknown-item = Unbekannter Gegenstand
+examine-condition = Zustand: { $value }
+examine-ammo = Munition: { $count }
+
+## Hints
+
+hint-hold-item = Griff gedrückt halten, um festzuhalten - Abzug zum Benutzen, loslassen zum Fallenlassen
+hint-hack-lock = Verschlossen? Auf das Schloss zeigen und den Abzug ziehen, um es zu hacken
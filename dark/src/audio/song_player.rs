@@ -22,8 +22,9 @@ impl SongPlayer {
         let wav_files = song.all_wav_files();
 
         for file in &wav_files {
-            let audio = asset_cache.get(&AUDIO_IMPORTER, file);
-            name_to_clip.insert(file.to_ascii_lowercase(), audio);
+            if let Some(audio) = asset_cache.get_or_warn(&AUDIO_IMPORTER, file) {
+                name_to_clip.insert(file.to_ascii_lowercase(), audio);
+            }
         }
 
         let all_schemas = song.all_schemas();
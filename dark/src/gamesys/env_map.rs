@@ -30,6 +30,8 @@ impl EnvMap {
         // }
 
         // trace!("tag_database: {:#?}", tag_database);
-        TagDatabase::read(reader)
+        let tag_database = TagDatabase::read(reader);
+        table_of_contents.finish_chunk("ENV_SOUND", reader);
+        tag_database
     }
 }
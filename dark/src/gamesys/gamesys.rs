@@ -5,7 +5,7 @@ use rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng};
 use crate::{
     EnvMap, EnvSoundQuery, SoundSchema, SpeechDB, TagDatabase,
     properties::{LinkDefinition, LinkDefinitionWithData, PropertyDefinition},
-    ss2_chunk_file_reader::{self},
+    ss2_chunk_file_reader::{self, ChunkFileTableOfContents, ChunkReport},
     ss2_entity_info::{self, SystemShock2EntityInfo},
 };
 
@@ -63,19 +63,55 @@ pub fn read<T: io::Read + io::Seek>(
     properties: &Vec<Box<dyn PropertyDefinition<T>>>,
 ) -> Gamesys {
     let table_of_contents = ss2_chunk_file_reader::read_table_of_contents(reader);
+    read_chunks(
+        &table_of_contents,
+        reader,
+        links,
+        links_with_data,
+        properties,
+    )
+}
 
-    let entity_info = ss2_entity_info::new(
+///
+/// read_chunk_report
+///
+/// Parse the gamesys in strict mode, and report which chunks were parsed, and how much of each
+pub fn read_chunk_report<T: io::Read + io::Seek>(
+    reader: &mut T,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> ChunkReport {
+    let table_of_contents = ss2_chunk_file_reader::read_table_of_contents_strict(reader);
+    let _ = read_chunks(
         &table_of_contents,
+        reader,
+        links,
+        links_with_data,
+        properties,
+    );
+    table_of_contents.report().unwrap_or_default()
+}
+
+fn read_chunks<T: io::Read + io::Seek>(
+    table_of_contents: &ChunkFileTableOfContents,
+    reader: &mut T,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> Gamesys {
+    let entity_info = ss2_entity_info::new(
+        table_of_contents,
         links,
         links_with_data,
         properties,
         reader,
     );
 
-    let sound_schema = SoundSchema::read(&table_of_contents, reader, &entity_info);
+    let sound_schema = SoundSchema::read(table_of_contents, reader, &entity_info);
 
-    let env_tag_map = EnvMap::read(&table_of_contents, reader);
-    let speech_db = SpeechDB::read(&table_of_contents, reader);
+    let env_tag_map = EnvMap::read(table_of_contents, reader);
+    let speech_db = SpeechDB::read(table_of_contents, reader);
 
     // Uncomment to output debug info for voices:
     // debug_print_voices(&sound_schema, &speech_db);
@@ -0,0 +1,156 @@
+// Material sound table
+//
+// Which sound material (the `Material` class tag - ie, "metal", "carpet", "flesh") applies to each
+// object archetype and texture family. Footstep and impact sounds are looked up in the env sound
+// schema with the material of whatever's being stepped on or hit.
+
+use std::collections::HashMap;
+
+use shipyard::{IntoIter, View, World};
+
+use crate::{
+    properties::{PropClassTag, PropTemplateId},
+    ss2_entity_info::SystemShock2EntityInfo,
+};
+
+// Prefix of texture archetype names - ie, `t_fam/metal/floor1`
+const TEXTURE_FAMILY_PREFIX: &str = "t_fam/";
+
+#[derive(Clone, Debug, Default)]
+pub struct MaterialSoundTable {
+    archetype_materials: HashMap<i32, String>,
+    family_materials: HashMap<String, String>,
+    // Keyed by (family, texture)
+    texture_materials: HashMap<(String, String), String>,
+}
+
+impl MaterialSoundTable {
+    ///
+    /// from_entity_info
+    ///
+    /// Build the table from the entity info (merged with the gamesys, so archetypes inherit their
+    /// parents' class tags), and the names of the objects - texture archetypes are only known by name
+    pub fn from_entity_info(
+        entity_info: &SystemShock2EntityInfo,
+        obj_map: &HashMap<i32, String>,
+    ) -> MaterialSoundTable {
+        let mut world = World::new();
+        entity_info.initialize_world_with_entities(&mut world, HashMap::new(), |_| true);
+
+        let mut archetype_materials = HashMap::new();
+        world.run(
+            |v_class_tag: View<PropClassTag>, v_template_id: View<PropTemplateId>| {
+                for (class_tag, template_id) in (&v_class_tag, &v_template_id).iter() {
+                    if let Some(material) = material_from_class_tag(class_tag) {
+                        archetype_materials.insert(template_id.template_id, material);
+                    }
+                }
+            },
+        );
+
+        let mut names = obj_map.iter().collect::<Vec<_>>();
+        names.sort_by_key(|(id, _)| **id);
+        let names = names
+            .into_iter()
+            .map(|(id, name)| (*id, name.as_str()))
+            .collect::<Vec<_>>();
+
+        MaterialSoundTable::from_materials(archetype_materials, &names)
+    }
+
+    fn from_materials(
+        archetype_materials: HashMap<i32, String>,
+        names: &[(i32, &str)],
+    ) -> MaterialSoundTable {
+        let mut family_materials = HashMap::new();
+        let mut texture_materials = HashMap::new();
+
+        for (id, name) in names {
+            let Some(material) = archetype_materials.get(id) else {
+                continue;
+            };
+            let name = name.to_ascii_lowercase();
+            let Some(texture_path) = name.strip_prefix(TEXTURE_FAMILY_PREFIX) else {
+                continue;
+            };
+
+            match texture_path.split_once('/') {
+                Some((family, texture)) => {
+                    texture_materials
+                        .insert((family.to_owned(), texture.to_owned()), material.clone());
+                    // Families without their own material take their first texture's
+                    family_materials
+                        .entry(family.to_owned())
+                        .or_insert_with(|| material.clone());
+                }
+                None => {
+                    family_materials.insert(texture_path.to_owned(), material.clone());
+                }
+            }
+        }
+
+        MaterialSoundTable {
+            archetype_materials,
+            family_materials,
+            texture_materials,
+        }
+    }
+
+    /// The material of an object, by its template id
+    pub fn material_for_archetype(&self, template_id: i32) -> Option<&str> {
+        self.archetype_materials
+            .get(&template_id)
+            .map(|material| material.as_str())
+    }
+
+    /// The material of a texture family (ie, "metal" for `t_fam/metal/*`)
+    pub fn material_for_texture_family(&self, family: &str) -> Option<&str> {
+        self.family_materials
+            .get(&family.to_ascii_lowercase())
+            .map(|material| material.as_str())
+    }
+
+    /// The material of a texture - its own archetype's, falling back to its family's
+    pub fn material_for_texture(&self, family: &str, texture: &str) -> Option<&str> {
+        let key = (family.to_ascii_lowercase(), texture.to_ascii_lowercase());
+        self.texture_materials
+            .get(&key)
+            .map(|material| material.as_str())
+            .or_else(|| self.material_for_texture_family(family))
+    }
+}
+
+fn material_from_class_tag(class_tag: &PropClassTag) -> Option<String> {
+    class_tag
+        .class_tags()
+        .into_iter()
+        .find(|(tag, _)| *tag == "material")
+        .map(|(_, value)| value.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texture_materials() {
+        let archetype_materials = HashMap::from([
+            (-10, "metal".to_owned()),
+            (-11, "carpet".to_owned()),
+            (-12, "glass".to_owned()),
+        ]);
+        let names = [
+            (-10, "t_fam/Metal"),
+            (-11, "t_fam/metal/Rug1"),
+            (-12, "t_fam/window/pane"),
+            (-13, "t_fam/metal/floor1"),
+        ];
+        let table = MaterialSoundTable::from_materials(archetype_materials, &names);
+
+        assert_eq!(table.material_for_texture("metal", "rug1"), Some("carpet"));
+        assert_eq!(table.material_for_texture("METAL", "floor1"), Some("metal"));
+        assert_eq!(table.material_for_texture_family("window"), Some("glass"));
+        assert_eq!(table.material_for_texture("stone", "wall"), None);
+        assert_eq!(table.material_for_archetype(-11), Some("carpet"));
+    }
+}
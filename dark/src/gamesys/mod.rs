@@ -1,11 +1,13 @@
 pub mod env_map;
 pub mod env_sound_query;
 pub mod gamesys;
+pub mod material_sound_table;
 pub mod sound_schema;
 pub mod speech_db;
 
 pub use env_map::*;
 pub use env_sound_query::*;
 pub use gamesys::*;
+pub use material_sound_table::*;
 pub use sound_schema::*;
 pub use speech_db::*;
@@ -39,6 +39,12 @@ impl SoundSchema {
         }
     }
 
+    pub fn get_samples(&self, schema: &str) -> Option<Vec<String>> {
+        self.name_to_samples
+            .get(&schema.to_ascii_lowercase())
+            .map(|samples| samples.iter().map(|s| s.sample_name.to_owned()).collect())
+    }
+
     pub fn read<T: io::Read + io::Seek>(
         table_of_contents: &ChunkFileTableOfContents,
         reader: &mut T,
@@ -73,6 +79,7 @@ impl SoundSchema {
 
             id_to_samples.insert(entity_id, samples);
         }
+        table_of_contents.finish_chunk("SchSamp", reader);
 
         // 2) Create database of entities - initializing the props, so we can read the sym name.
         // This will let us get the symname <-> EntityId relationship
@@ -61,6 +61,7 @@ impl SpeechDB {
             let voice = Voice::read(reader, num_concepts);
             voices.push(voice);
         }
+        table_of_contents.finish_chunk("Speech_DB", reader);
 
         SpeechDB {
             concept_map,
@@ -3,7 +3,7 @@ use once_cell::sync::Lazy;
 
 use crate::motion::{AnimationClip, MotionClip};
 
-use super::MOTIONDB_IMPORTER;
+use super::{ANIMATION_EVENT_MARKUP_IMPORTER, MOTIONDB_IMPORTER};
 
 fn import_animation_cliip(
     name: String,
@@ -11,7 +11,7 @@ fn import_animation_cliip(
     assets: &mut AssetCache,
     _config: &(),
 ) -> AnimationClip {
-    let motiondb = assets.get(&MOTIONDB_IMPORTER, "motiondb.bin");
+    let motiondb = assets.get(&MOTIONDB_IMPORTER, "motiondb.bin").unwrap();
 
     // To look up in motion db, we need to remove the extension "_.mc" (4 characters):
     let name_without_extra_stuff = &name[..name.len() - 4];
@@ -20,7 +20,15 @@ fn import_animation_cliip(
     let motion_stuff = motiondb.get_motion_stuff(name_without_extra_stuff.to_owned());
     let motion_clip = MotionClip::read(reader, mps_motion);
 
-    AnimationClip::create(&motion_clip, mps_motion, motion_stuff)
+    let mut clip = AnimationClip::create(&motion_clip, mps_motion, motion_stuff);
+
+    // Custom events are optional - most motions won't have any
+    if let Some(markup) = assets.get_opt(&ANIMATION_EVENT_MARKUP_IMPORTER, "animation_events.json")
+    {
+        clip.markers = markup.markers_for(name_without_extra_stuff);
+    }
+
+    clip
 }
 
 fn process_animation_clip(
@@ -0,0 +1,42 @@
+use std::io::Read;
+
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+use crate::motion::AnimationEventMarkup;
+
+fn import_animation_event_markup(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> AnimationEventMarkup {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read animation event markup {}: {}", name, err);
+        return AnimationEventMarkup::default();
+    }
+
+    AnimationEventMarkup::from_json(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse animation event markup {}: {}", name, err);
+        AnimationEventMarkup::default()
+    })
+}
+
+fn process_animation_event_markup(
+    content: AnimationEventMarkup,
+    _asset_cache: &mut AssetCache,
+    _config: &(),
+) -> AnimationEventMarkup {
+    content
+}
+
+pub static ANIMATION_EVENT_MARKUP_IMPORTER: Lazy<
+    AssetImporter<AnimationEventMarkup, AnimationEventMarkup, ()>,
+> = Lazy::new(|| {
+    AssetImporter::define(
+        import_animation_event_markup,
+        process_animation_event_markup,
+    )
+});
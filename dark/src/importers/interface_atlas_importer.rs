@@ -0,0 +1,61 @@
+use std::io::Read;
+
+use engine::{
+    assets::{asset_cache::AssetCache, asset_importer::AssetImporter},
+    texture::TextureOptions,
+    texture_format::RawTextureData,
+};
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+use crate::interface_atlas::InterfaceAtlas;
+
+use super::RAW_TEXTURE_IMPORTER;
+
+///
+/// INTERFACE_ATLAS_IMPORTER
+///
+/// Loads an interface atlas from a manifest - a JSON list of the images to pack, ie:
+/// `["cursor.pcx", "brack0.pcx", "invback.pcx"]`. Images that can't be found are skipped.
+pub static INTERFACE_ATLAS_IMPORTER: Lazy<
+    AssetImporter<Vec<(String, RawTextureData)>, InterfaceAtlas, ()>,
+> = Lazy::new(|| {
+    AssetImporter::define(load_interface_images, |images, _assets, _config| {
+        InterfaceAtlas::from_images(images)
+    })
+});
+
+fn load_interface_images(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    assets: &mut AssetCache,
+    _config: &(),
+) -> Vec<(String, RawTextureData)> {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("unable to read interface atlas manifest {}: {}", name, err);
+        return vec![];
+    }
+
+    let image_names: Vec<String> = match serde_json::from_str(&json) {
+        Ok(image_names) => image_names,
+        Err(err) => {
+            warn!("unable to parse interface atlas manifest {}: {}", name, err);
+            return vec![];
+        }
+    };
+
+    let options = TextureOptions { wrap: false };
+    image_names
+        .into_iter()
+        .filter_map(|image_name| {
+            match assets.get_ext_opt(&RAW_TEXTURE_IMPORTER, &image_name, &options) {
+                Some(raw) => Some((image_name, raw.as_ref().clone())),
+                None => {
+                    warn!("interface atlas: missing image {}", image_name);
+                    None
+                }
+            }
+        })
+        .collect()
+}
@@ -1,24 +1,30 @@
 mod animation_clip_importer;
+mod animation_event_markup_importer;
 mod audio_importer;
 mod bitmap_animation_importer;
 mod font_importer;
 mod glb_model_importer;
+mod interface_atlas_importer;
 mod map_position_importer;
 mod model_importer;
 mod motiondb_importer;
+mod motiondb_overlay_importer;
 mod skeleton_importer;
 mod song_importer;
 mod strings_importer;
 mod texture_importer;
 
 pub use animation_clip_importer::*;
+pub use animation_event_markup_importer::*;
 pub use audio_importer::*;
 pub use bitmap_animation_importer::*;
 pub use font_importer::*;
 pub use glb_model_importer::*;
+pub use interface_atlas_importer::*;
 pub use map_position_importer::*;
 pub use model_importer::*;
 pub use motiondb_importer::*;
+pub use motiondb_overlay_importer::*;
 pub use skeleton_importer::*;
 pub use song_importer::*;
 pub use strings_importer::*;
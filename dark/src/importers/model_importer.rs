@@ -1,4 +1,8 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    io::{Read, Seek},
+    path::PathBuf,
+    rc::Rc,
+};
 
 use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
 use once_cell::sync::Lazy;
@@ -21,27 +25,64 @@ pub enum SystemShockContentModel {
     Obj(SystemShock2ObjectMesh),
 }
 
-fn load_model(
-    _name: String,
-    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
-    _assets: &mut AssetCache,
-    _config: &(),
-) -> SystemShockContentModel {
+///
+/// RawModel
+///
+/// A model .BIN parsed without the asset cache - plain data, so models can be read in parallel
+/// and processed into a `Model` on the main thread with `process_raw_model`
+pub enum RawModel {
+    Mesh(SystemShock2AIMesh),
+    Obj(SystemShock2ObjectMesh),
+}
+
+pub fn read_raw_model<T: Read + Seek>(reader: &mut T) -> RawModel {
     let common_header = ss2_bin_header::read(reader);
     match common_header.bin_type {
         ss2_bin_header::BinFileType::Obj => {
-            SystemShockContentModel::Obj(ss2_bin_obj_loader::read(reader, &common_header))
+            RawModel::Obj(ss2_bin_obj_loader::read(reader, &common_header))
         }
         ss2_bin_header::BinFileType::Mesh => {
-            let mut pathbuf = PathBuf::from(_name);
+            RawModel::Mesh(ss2_bin_ai_loader::read(reader, &common_header))
+        }
+    }
+}
+
+///
+/// process_raw_model
+///
+/// Create the model from a raw model - loading the skeleton for meshes, and creating the GPU resources
+pub fn process_raw_model(name: &str, raw_model: RawModel, assets: &mut AssetCache) -> Model {
+    let content_model = with_skeleton(name, raw_model, assets);
+    process_model(content_model, assets, &())
+}
+
+fn with_skeleton(
+    name: &str,
+    raw_model: RawModel,
+    assets: &mut AssetCache,
+) -> SystemShockContentModel {
+    match raw_model {
+        RawModel::Obj(obj) => SystemShockContentModel::Obj(obj),
+        RawModel::Mesh(mesh) => {
+            let mut pathbuf = PathBuf::from(name);
             pathbuf.set_extension("cal");
             let cal_path = pathbuf.to_string_lossy();
-            let skeleton = _assets.get(&SKELETON_IMPORTER, &cal_path);
-            SystemShockContentModel::Mesh(ss2_bin_ai_loader::read(reader, &common_header), skeleton)
+            let skeleton = assets.get(&SKELETON_IMPORTER, &cal_path).unwrap();
+            SystemShockContentModel::Mesh(mesh, skeleton)
         }
     }
 }
 
+fn load_model(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    assets: &mut AssetCache,
+    _config: &(),
+) -> SystemShockContentModel {
+    let raw_model = read_raw_model(reader);
+    with_skeleton(&name, raw_model, assets)
+}
+
 fn process_model(
     mesh: SystemShockContentModel,
     asset_cache: &mut AssetCache,
@@ -0,0 +1,36 @@
+use std::io::Read;
+
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+use crate::motion::MotionDbOverlay;
+
+fn import_motion_db_overlay(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> MotionDbOverlay {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read motion db overlay {}: {}", name, err);
+        return MotionDbOverlay::default();
+    }
+
+    MotionDbOverlay::from_json(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse motion db overlay {}: {}", name, err);
+        MotionDbOverlay::default()
+    })
+}
+
+fn process_motion_db_overlay(
+    content: MotionDbOverlay,
+    _asset_cache: &mut AssetCache,
+    _config: &(),
+) -> MotionDbOverlay {
+    content
+}
+
+pub static MOTIONDB_OVERLAY_IMPORTER: Lazy<AssetImporter<MotionDbOverlay, MotionDbOverlay, ()>> =
+    Lazy::new(|| AssetImporter::define(import_motion_db_overlay, process_motion_db_overlay));
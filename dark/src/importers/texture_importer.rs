@@ -32,3 +32,9 @@ pub(crate) fn process_texture(
 
 pub static TEXTURE_IMPORTER: Lazy<AssetImporter<RawTextureData, Texture, TextureOptions>> =
     Lazy::new(|| AssetImporter::define(load_texture, process_texture));
+
+/// Texture pixels, without uploading them - for assets that are combined before they're drawn,
+/// like the interface atlas
+pub static RAW_TEXTURE_IMPORTER: Lazy<
+    AssetImporter<RawTextureData, RawTextureData, TextureOptions>,
+> = Lazy::new(|| AssetImporter::define(load_texture, |raw, _assets, _config| raw));
@@ -0,0 +1,110 @@
+///
+/// interface_atlas.rs
+///
+/// The original interface art (from intrface.crf - the cursor, HUD frames and brackets, MFD backgrounds)
+/// packed into a single texture, with a named region for each image - so the HUD and GUI can draw
+/// the authentic art without a texture per quad.
+///
+use std::{collections::HashMap, rc::Rc};
+
+use engine::{
+    texture::Texture,
+    texture_atlas::{TexturePackResult, TexturePacker},
+    texture_format::{PixelFormat, RawTextureData},
+};
+use image::{ImageBuffer, Rgba};
+use tracing::warn;
+
+const ATLAS_SIZE: u32 = 2048;
+
+///
+/// InterfaceRegion
+///
+/// Where an image ended up in the atlas - UVs for drawing it, and its original size in pixels
+#[derive(Debug, Clone, Copy)]
+pub struct InterfaceRegion {
+    pub uv: TexturePackResult,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl InterfaceRegion {
+    pub fn uv_min(&self) -> (f32, f32) {
+        (self.uv.uv_offset_x, self.uv.uv_offset_y)
+    }
+
+    pub fn uv_max(&self) -> (f32, f32) {
+        (
+            self.uv.uv_offset_x + self.uv.uv_width,
+            self.uv.uv_offset_y + self.uv.uv_height,
+        )
+    }
+}
+
+pub struct InterfaceAtlas {
+    texture: Rc<Texture>,
+    regions: HashMap<String, InterfaceRegion>,
+}
+
+impl InterfaceAtlas {
+    ///
+    /// from_images
+    ///
+    /// Pack the images into the atlas, by name (case-insensitive). Images that don't fit are
+    /// left out, with a warning - callers fall back to loading them on their own.
+    pub fn from_images(mut images: Vec<(String, RawTextureData)>) -> InterfaceAtlas {
+        // Tallest first, so the packer's rows waste less space
+        images.sort_by_key(|(_, raw)| std::cmp::Reverse(raw.height));
+
+        let mut packer = TexturePacker::<Rgba<u8>>::new_rgba(ATLAS_SIZE, ATLAS_SIZE);
+        let mut regions = HashMap::new();
+        for (name, raw) in &images {
+            match packer.try_pack(&to_rgba_image(raw)) {
+                Some(uv) => {
+                    regions.insert(
+                        name.to_ascii_lowercase(),
+                        InterfaceRegion {
+                            uv,
+                            width: raw.width,
+                            height: raw.height,
+                        },
+                    );
+                }
+                None => warn!("no room in the interface atlas for {}", name),
+            }
+        }
+
+        let texture = packer.generate_textures()[0].clone();
+        InterfaceAtlas { texture, regions }
+    }
+
+    pub fn texture(&self) -> Rc<Texture> {
+        self.texture.clone()
+    }
+
+    pub fn region(&self, name: &str) -> Option<InterfaceRegion> {
+        self.regions.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.regions.contains_key(&name.to_ascii_lowercase())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.regions.keys()
+    }
+}
+
+fn to_rgba_image(raw: &RawTextureData) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    match raw.format {
+        PixelFormat::RGBA => ImageBuffer::from_raw(raw.width, raw.height, raw.bytes.clone())
+            .unwrap_or_else(|| ImageBuffer::new(raw.width, raw.height)),
+        PixelFormat::RGB => ImageBuffer::from_fn(raw.width, raw.height, |x, y| {
+            let idx = ((y * raw.width + x) * 3) as usize;
+            match raw.bytes.get(idx..idx + 3) {
+                Some(rgb) => Rgba([rgb[0], rgb[1], rgb[2], 255]),
+                None => Rgba([0, 0, 0, 0]),
+            }
+        }),
+    }
+}
@@ -4,6 +4,8 @@ pub mod font;
 pub mod gamesys;
 pub mod glb_model;
 pub mod glb_skeleton;
+pub mod interface_atlas;
+pub mod log_text;
 pub mod map;
 pub mod mission;
 pub mod model;
@@ -16,6 +18,7 @@ pub mod ss2_cal_loader;
 pub mod ss2_chunk_file_reader;
 pub mod ss2_common;
 pub mod ss2_entity_info;
+pub mod ss2_save_game;
 pub mod ss2_skeleton;
 pub mod tag_database;
 pub mod util;
@@ -0,0 +1,161 @@
+// Log text - the subjects and transcripts of emails, logs, notes and videos
+//
+// Each deck's text lives in its own string resource (`log<deck>.str` in strings.crf), parsed by
+// the STRINGS_IMPORTER into key -> text. Keys name the kind of entry, the field and the number -
+// ie, `EmailName3` / `EmailText3`, or `Log_Name_4` - so they're matched loosely: separators and
+// case are ignored, and the number can come before or after the field.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LogKind {
+    Email,
+    Log,
+    Note,
+    Video,
+}
+
+impl LogKind {
+    const ALL: [LogKind; 4] = [LogKind::Email, LogKind::Log, LogKind::Note, LogKind::Video];
+
+    fn prefix(&self) -> &'static str {
+        match self {
+            LogKind::Email => "email",
+            LogKind::Log => "log",
+            LogKind::Note => "note",
+            LogKind::Video => "video",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Title shown in the list - the email subject, or log name
+    pub subject: Option<String>,
+    /// The full text, or the transcript for audio logs
+    pub body: Option<String>,
+}
+
+enum LogField {
+    Subject,
+    Body,
+}
+
+///
+/// LogTexts
+///
+/// Log text for every deck, by kind / deck / number
+#[derive(Debug, Clone, Default)]
+pub struct LogTexts {
+    entries: HashMap<(LogKind, u32, u32), LogEntry>,
+}
+
+impl LogTexts {
+    pub fn new() -> LogTexts {
+        LogTexts::default()
+    }
+
+    ///
+    /// add_deck
+    ///
+    /// Add the strings from a deck's string resource - keys that aren't log text are skipped
+    pub fn add_deck(&mut self, deck: u32, strings: &HashMap<String, String>) {
+        for (key, text) in strings {
+            let Some((kind, field, number)) = parse_key(key) else {
+                continue;
+            };
+
+            let entry = self.entries.entry((kind, deck, number)).or_default();
+            match field {
+                LogField::Subject => entry.subject = Some(text.clone()),
+                LogField::Body => entry.body = Some(text.clone()),
+            }
+        }
+    }
+
+    pub fn get(&self, kind: LogKind, deck: u32, number: u32) -> Option<&LogEntry> {
+        self.entries.get(&(kind, deck, number))
+    }
+
+    pub fn email(&self, deck: u32, number: u32) -> Option<&LogEntry> {
+        self.get(LogKind::Email, deck, number)
+    }
+
+    pub fn log(&self, deck: u32, number: u32) -> Option<&LogEntry> {
+        self.get(LogKind::Log, deck, number)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn parse_key(key: &str) -> Option<(LogKind, LogField, u32)> {
+    let key = key
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    // Longest prefix first isn't needed - none of the prefixes start another
+    let kind = LogKind::ALL
+        .into_iter()
+        .find(|kind| key.starts_with(kind.prefix()))?;
+    let rest = &key[kind.prefix().len()..];
+
+    let field_name = rest
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .collect::<String>();
+    let digits = rest
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>();
+    let number = digits.parse::<u32>().ok()?;
+
+    let field = match field_name.as_str() {
+        "name" | "title" | "subject" => LogField::Subject,
+        "" | "text" | "body" => LogField::Body,
+        _ => return None,
+    };
+
+    Some((kind, field, number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_deck() {
+        let strings = HashMap::from([
+            ("emailname3".to_owned(), "Welcome aboard".to_owned()),
+            ("email_text_3".to_owned(), "Report to medical.".to_owned()),
+            ("log4name".to_owned(), "Ops override".to_owned()),
+            ("objdesc".to_owned(), "not log text".to_owned()),
+        ]);
+
+        let mut texts = LogTexts::new();
+        texts.add_deck(1, &strings);
+
+        assert_eq!(
+            texts.email(1, 3),
+            Some(&LogEntry {
+                subject: Some("Welcome aboard".to_owned()),
+                body: Some("Report to medical.".to_owned()),
+            })
+        );
+        assert_eq!(
+            texts.log(1, 4).and_then(|log| log.subject.as_deref()),
+            Some("Ops override")
+        );
+        assert!(texts.email(2, 3).is_none());
+        assert_eq!(texts.len(), 2);
+    }
+}
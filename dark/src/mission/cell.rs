@@ -23,6 +23,8 @@ use super::Plane;
 #[derive(Debug, Clone)]
 pub struct Cell {
     pub idx: u32,
+    // What fills the cell - see MEDIUM_*
+    pub medium: u8,
     pub center: Vector3<f32>,
     pub radius: f32,
     pub portal_count: u8,
@@ -35,7 +37,33 @@ pub struct Cell {
     pub lights: Vec<LightInfo>,
 }
 
+// Cell media, from the world rep
+pub const MEDIUM_SOLID: u8 = 0;
+pub const MEDIUM_AIR: u8 = 1;
+pub const MEDIUM_WATER: u8 = 2;
+
 impl Cell {
+    pub fn is_water(&self) -> bool {
+        self.medium == MEDIUM_WATER
+    }
+
+    ///
+    /// light_level
+    ///
+    /// How brightly lit the cell is, from 0.0 (pitch black) to 1.0 - the average brightness of its
+    /// static lightmaps
+    pub fn light_level(&self) -> f32 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+
+        self.lights
+            .iter()
+            .map(|light| light.brightness)
+            .sum::<f32>()
+            / self.lights.len() as f32
+    }
+
     pub fn read<T: io::Read>(
         reader: &mut T,
         packer: &mut TexturePacker<image::Rgb<u8>>,
@@ -48,7 +76,7 @@ impl Cell {
         let cell_num_render_polys = reader.read_u8().unwrap();
         let portal_count = reader.read_u8().unwrap();
         let cell_num_planes = reader.read_u8().unwrap();
-        let _cell_medium = reader.read_u8().unwrap();
+        let medium = reader.read_u8().unwrap();
         let _cell_flags = reader.read_u8().unwrap();
 
         let _nxn = reader.read_u32::<byteorder::LittleEndian>().unwrap();
@@ -112,6 +140,7 @@ impl Cell {
 
         let cell = Cell {
             idx: cell_idx,
+            medium,
             portal_count,
             portals,
             center,
@@ -372,6 +401,7 @@ fn read_lights<T: io::Read>(
                     }
                 });
 
+                li.brightness = average_brightness(&img);
                 li.texture_pack_result = packer.pack(&img);
             }
         }
@@ -385,6 +415,20 @@ fn read_lights<T: io::Read>(
     light_infos
 }
 
+/// Average luminance of a lightmap, from 0.0 to 1.0
+fn average_brightness(img: &image::RgbImage) -> f32 {
+    let pixel_count = img.width() * img.height();
+    if pixel_count == 0 {
+        return 0.0;
+    }
+
+    let total = img
+        .pixels()
+        .map(|image::Rgb([r, g, b])| 0.2126 * *r as f32 + 0.7152 * *g as f32 + 0.0722 * *b as f32)
+        .sum::<f32>();
+    total / (pixel_count as f32 * 255.0)
+}
+
 #[derive(Debug, Clone)]
 pub struct LightInfo {
     pub debug_idx: u32,
@@ -397,6 +441,8 @@ pub struct LightInfo {
     pub dynamic_lightmap_pointer: u32,
     pub animation_flags: u32,
     pub texture_pack_result: TexturePackResult,
+    // Average brightness of the static lightmap, from 0.0 to 1.0
+    pub brightness: f32,
 }
 
 fn read_light_info<T: io::Read>(debug_idx: u32, reader: &mut T) -> LightInfo {
@@ -422,5 +468,6 @@ fn read_light_info<T: io::Read>(debug_idx: u32, reader: &mut T) -> LightInfo {
         dynamic_lightmap_pointer,
         animation_flags,
         texture_pack_result: TexturePackResult::DEFAULT,
+        brightness: 0.0,
     }
 }
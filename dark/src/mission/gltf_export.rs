@@ -0,0 +1,442 @@
+///
+/// gltf_export.rs
+///
+/// Export a level's world geometry as glTF 2.0 - for inspecting it in external tools (Blender and
+/// friends), or feeding it to a light baker. Every texture becomes a material, and the polygons
+/// using it a primitive of the single "world" mesh, so the per-face texture assignments survive
+/// the trip. Positions are in the runtime's units: y up, scaled down by `SCALE_FACTOR`.
+///
+/// The export is a `.gltf` document, a `.bin` buffer with the vertex data, and a PNG per texture
+/// under `textures/` - see `dark_query export-gltf`.
+///
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use engine::{
+    assets::asset_cache::AssetCache, scene::VertexPositionTextureLightmapAtlasNormal,
+    texture_format::PixelFormat,
+};
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::importers::RAW_TEXTURE_IMPORTER;
+
+use super::{SystemShock2Level, texture_list::SystemShock2Texture};
+
+// glTF constants - component type, buffer view target, and sampler wrap mode
+const FLOAT: u32 = 5126;
+const ARRAY_BUFFER: u32 = 34962;
+const REPEAT: u32 = 10497;
+
+///
+/// GltfImage
+///
+/// A texture the document points at - to be written as a PNG at `uri`
+pub struct GltfImage {
+    pub uri: String,
+    pub asset_name: String,
+}
+
+pub struct GltfExport {
+    pub name: String,
+    pub document: Value,
+    pub buffer: Vec<u8>,
+    pub images: Vec<GltfImage>,
+}
+
+///
+/// export
+///
+/// Build the glTF for the level's world geometry - `name` is used for the document, and its buffer.
+/// Returns None if the level has no textured geometry, as a glTF mesh needs at least one
+/// primitive.
+pub fn export(level: &SystemShock2Level, name: &str) -> Option<GltfExport> {
+    // Sorted by texture, so exporting the same level twice gives the same file
+    let mut texture_to_vertices: BTreeMap<u16, Vec<VertexPositionTextureLightmapAtlasNormal>> =
+        BTreeMap::new();
+    for geometry in &level.all_geometry {
+        // Skip empty texture, as the renderer does
+        if geometry.texture_idx == 0 {
+            continue;
+        }
+
+        texture_to_vertices
+            .entry(geometry.texture_idx)
+            .or_default()
+            .extend(geometry.verts.iter().cloned());
+    }
+
+    build(&level.textures.0, &texture_to_vertices, name)
+}
+
+fn build(
+    textures: &[SystemShock2Texture],
+    texture_to_vertices: &BTreeMap<u16, Vec<VertexPositionTextureLightmapAtlasNormal>>,
+    name: &str,
+) -> Option<GltfExport> {
+    let mut buffer = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut materials = Vec::new();
+    let mut gltf_textures = Vec::new();
+    let mut images = Vec::new();
+    let mut primitives = Vec::new();
+
+    for (texture_idx, vertices) in texture_to_vertices {
+        if vertices.is_empty() {
+            continue;
+        }
+
+        let Some(tex_info) = textures.get(*texture_idx as usize) else {
+            warn!(
+                "glTF export: no texture {} in the texture list",
+                texture_idx
+            );
+            continue;
+        };
+
+        let asset_name = tex_info.asset_name();
+        let mut material = json!({
+            "name": asset_name,
+            "doubleSided": true,
+            "pbrMetallicRoughness": { "metallicFactor": 0.0, "roughnessFactor": 1.0 },
+        });
+        if tex_info.texture_filename != "null" {
+            material["pbrMetallicRoughness"]["baseColorTexture"] =
+                json!({ "index": gltf_textures.len() });
+            gltf_textures.push(json!({ "sampler": 0, "source": images.len() }));
+            images.push(GltfImage {
+                uri: format!(
+                    "textures/{}_{}.png",
+                    tex_info.family.to_ascii_lowercase(),
+                    tex_info.texture_filename.to_ascii_lowercase()
+                ),
+                asset_name,
+            });
+        }
+        let material_idx = materials.len();
+        materials.push(material);
+
+        let positions: Vec<[f32; 3]> = vertices
+            .iter()
+            .map(|v| [v.position.x, v.position.y, v.position.z])
+            .collect();
+        let normals: Vec<[f32; 3]> = vertices
+            .iter()
+            .map(|v| [v.normal.x, v.normal.y, v.normal.z])
+            .collect();
+        let uvs: Vec<[f32; 2]> = vertices.iter().map(|v| [v.uv.x, v.uv.y]).collect();
+
+        // Positions are the one attribute glTF requires bounds for
+        let (min, max) = positions.iter().fold(
+            ([f32::MAX; 3], [f32::MIN; 3]),
+            |(mut min, mut max), position| {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(position[axis]);
+                    max[axis] = max[axis].max(position[axis]);
+                }
+                (min, max)
+            },
+        );
+
+        let position_idx = accessors.len();
+        push_attribute(&mut buffer, &mut buffer_views, &positions);
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": FLOAT,
+            "count": positions.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }));
+
+        push_attribute(&mut buffer, &mut buffer_views, &normals);
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": FLOAT,
+            "count": normals.len(),
+            "type": "VEC3",
+        }));
+
+        push_attribute(&mut buffer, &mut buffer_views, &uvs);
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": FLOAT,
+            "count": uvs.len(),
+            "type": "VEC2",
+        }));
+
+        primitives.push(json!({
+            "attributes": {
+                "POSITION": position_idx,
+                "NORMAL": position_idx + 1,
+                "TEXCOORD_0": position_idx + 2,
+            },
+            "material": material_idx,
+        }));
+    }
+
+    if primitives.is_empty() {
+        return None;
+    }
+
+    let mut document = json!({
+        "asset": { "version": "2.0", "generator": "shock2quest dark_query" },
+        "scene": 0,
+        "scenes": [{ "name": name, "nodes": [0] }],
+        "nodes": [{ "name": "world", "mesh": 0 }],
+        "meshes": [{ "name": "world", "primitives": primitives }],
+        "materials": materials,
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{ "uri": format!("{}.bin", name), "byteLength": buffer.len() }],
+    });
+    if !images.is_empty() {
+        document["samplers"] = json!([{ "wrapS": REPEAT, "wrapT": REPEAT }]);
+        document["textures"] = Value::Array(gltf_textures);
+        document["images"] = images
+            .iter()
+            .map(|image| json!({ "uri": image.uri }))
+            .collect();
+    }
+
+    Some(GltfExport {
+        name: name.to_owned(),
+        document,
+        buffer,
+        images,
+    })
+}
+
+// Append an attribute's values to the buffer, tightly packed, with a buffer view over them
+fn push_attribute<const N: usize>(
+    buffer: &mut Vec<u8>,
+    buffer_views: &mut Vec<Value>,
+    values: &[[f32; N]],
+) {
+    let byte_offset = buffer.len();
+    for value in values {
+        for component in value {
+            buffer.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+    buffer_views.push(json!({
+        "buffer": 0,
+        "byteOffset": byte_offset,
+        "byteLength": buffer.len() - byte_offset,
+        "target": ARRAY_BUFFER,
+    }));
+}
+
+impl GltfExport {
+    ///
+    /// write
+    ///
+    /// Write `<name>.gltf`, `<name>.bin` and the textures into `directory` - returns the path of
+    /// the document, and the number of textures written. Textures that fail to load or write are
+    /// left out of the document, with a warning.
+    pub fn write(
+        &self,
+        asset_cache: &mut AssetCache,
+        directory: &Path,
+    ) -> io::Result<(PathBuf, usize)> {
+        fs::create_dir_all(directory.join("textures"))?;
+
+        let written: Vec<bool> = self
+            .images
+            .iter()
+            .map(|image| write_image(asset_cache, directory, image))
+            .collect();
+
+        fs::write(directory.join(format!("{}.bin", self.name)), &self.buffer)?;
+
+        let document = without_missing_images(&self.document, &written);
+        let document_path = directory.join(format!("{}.gltf", self.name));
+        let json = serde_json::to_string_pretty(&document).map_err(io::Error::other)?;
+        fs::write(&document_path, json)?;
+
+        let written_count = written.iter().filter(|written| **written).count();
+        Ok((document_path, written_count))
+    }
+}
+
+// Write a texture as a PNG at its uri - returns false if it couldn't be loaded or written
+fn write_image(asset_cache: &mut AssetCache, directory: &Path, image: &GltfImage) -> bool {
+    let Some(texture) = asset_cache.get_or_warn(&RAW_TEXTURE_IMPORTER, &image.asset_name) else {
+        return false;
+    };
+
+    let color_type = match texture.format {
+        PixelFormat::RGB => image::ColorType::Rgb8,
+        PixelFormat::RGBA => image::ColorType::Rgba8,
+    };
+    if let Err(err) = image::save_buffer(
+        directory.join(&image.uri),
+        &texture.bytes,
+        texture.width,
+        texture.height,
+        color_type,
+    ) {
+        warn!("Unable to write texture {}: {}", image.uri, err);
+        return false;
+    }
+    true
+}
+
+// Drop the images that weren't written from the document, along with their textures and the
+// materials' references to them - each image has the texture at the same index
+fn without_missing_images(document: &Value, written: &[bool]) -> Value {
+    let mut document = document.clone();
+    if written.iter().all(|written| *written) {
+        return document;
+    }
+
+    let mut remapped = Vec::new();
+    let mut kept = 0;
+    for written in written {
+        remapped.push(if *written { Some(kept) } else { None });
+        if *written {
+            kept += 1;
+        }
+    }
+
+    if let Some(materials) = document["materials"].as_array_mut() {
+        for material in materials {
+            let pbr = &mut material["pbrMetallicRoughness"];
+            let Some(index) = pbr["baseColorTexture"]["index"].as_u64() else {
+                continue;
+            };
+            match remapped.get(index as usize).copied().flatten() {
+                Some(new_index) => pbr["baseColorTexture"]["index"] = json!(new_index),
+                None => {
+                    if let Some(pbr) = pbr.as_object_mut() {
+                        pbr.remove("baseColorTexture");
+                    }
+                }
+            }
+        }
+    }
+
+    let object = document.as_object_mut().unwrap();
+    if kept == 0 {
+        object.remove("samplers");
+        object.remove("textures");
+        object.remove("images");
+        return document;
+    }
+
+    for key in ["textures", "images"] {
+        if let Some(Value::Array(values)) = object.get_mut(key) {
+            let mut written = written.iter();
+            values.retain(|_| written.next().copied().unwrap_or(false));
+        }
+    }
+    if let Some(Value::Array(textures)) = object.get_mut("textures") {
+        for (new_index, texture) in textures.iter_mut().enumerate() {
+            texture["source"] = json!(new_index);
+        }
+    }
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{vec2, vec3, vec4};
+
+    use crate::properties::RenderType;
+
+    use super::*;
+
+    fn texture(family: &str, texture_filename: &str) -> SystemShock2Texture {
+        SystemShock2Texture {
+            family: family.to_owned(),
+            texture_filename: texture_filename.to_owned(),
+            render_type: RenderType::Normal,
+            animation_info: None,
+        }
+    }
+
+    fn triangle(x: f32) -> Vec<VertexPositionTextureLightmapAtlasNormal> {
+        [
+            vec3(x, 0.0, 0.0),
+            vec3(x + 1.0, 0.0, 0.0),
+            vec3(x, 1.0, 0.0),
+        ]
+        .into_iter()
+        .map(|position| VertexPositionTextureLightmapAtlasNormal {
+            position,
+            uv: vec2(position.x, position.y),
+            lightmap_uv: vec2(0.0, 0.0),
+            lightmap_atlas: vec4(0.0, 0.0, 1.0, 1.0),
+            normal: vec3(0.0, 0.0, 1.0),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_primitive_and_material_per_texture() {
+        let textures = vec![
+            texture("null", "null"),
+            texture("medsci", "floor1"),
+            texture("medsci", "wall2"),
+        ];
+        let texture_to_vertices = BTreeMap::from([(1, triangle(0.0)), (2, triangle(5.0))]);
+
+        let export = build(&textures, &texture_to_vertices, "medsci1").unwrap();
+
+        let primitives = export.document["meshes"][0]["primitives"]
+            .as_array()
+            .unwrap();
+        assert_eq!(primitives.len(), 2);
+        assert_eq!(export.document["materials"][1]["name"], "MEDSCI/wall2.PCX");
+        assert_eq!(export.images[1].uri, "textures/medsci_wall2.png");
+
+        // Position, normal and uv for 3 vertices, per triangle
+        assert_eq!(export.buffer.len(), 2 * 3 * (12 + 12 + 8));
+        assert_eq!(export.document["buffers"][0]["uri"], "medsci1.bin");
+
+        let second_positions = &export.document["accessors"][3];
+        assert_eq!(second_positions["min"], json!([5.0, 0.0, 0.0]));
+        assert_eq!(second_positions["max"], json!([6.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_untextured_level_has_no_export() {
+        let textures = vec![texture("null", "null"), texture("medsci", "floor1")];
+        let texture_to_vertices = BTreeMap::from([(1, Vec::new()), (7, triangle(0.0))]);
+
+        assert!(build(&textures, &texture_to_vertices, "empty").is_none());
+    }
+
+    #[test]
+    fn test_missing_images_are_dropped() {
+        let textures = vec![
+            texture("null", "null"),
+            texture("medsci", "floor1"),
+            texture("medsci", "wall2"),
+        ];
+        let texture_to_vertices = BTreeMap::from([(1, triangle(0.0)), (2, triangle(5.0))]);
+        let export = build(&textures, &texture_to_vertices, "medsci1").unwrap();
+
+        let document = without_missing_images(&export.document, &[false, true]);
+
+        let materials = &document["materials"];
+        assert!(materials[0]["pbrMetallicRoughness"]["baseColorTexture"].is_null());
+        assert_eq!(
+            materials[1]["pbrMetallicRoughness"]["baseColorTexture"]["index"],
+            0
+        );
+        assert_eq!(document["textures"], json!([{ "sampler": 0, "source": 0 }]));
+        assert_eq!(
+            document["images"],
+            json!([{ "uri": "textures/medsci_wall2.png" }])
+        );
+
+        let document = without_missing_images(&export.document, &[false, false]);
+        assert!(document.get("images").is_none());
+        assert!(document.get("samplers").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 mod bsp_tree;
 mod cell;
 mod cell_portal;
+pub mod gltf_export;
 pub mod path_database;
 mod plane;
 pub mod render_params;
@@ -20,7 +21,7 @@ use crate::properties::LinkDefinitionWithData;
 
 use crate::Gamesys;
 use crate::SCALE_FACTOR;
-use crate::ss2_chunk_file_reader::ChunkFileTableOfContents;
+use crate::ss2_chunk_file_reader::{ChunkFileTableOfContents, ChunkReport};
 use crate::ss2_common::read_bytes;
 use render_params::*;
 use room_database::*;
@@ -31,14 +32,16 @@ use texture_list::*;
 use crate::properties::LinkDefinition;
 use crate::ss2_common::read_i32;
 
-use crate::importers::TEXTURE_IMPORTER;
+use crate::importers::{RAW_TEXTURE_IMPORTER, TEXTURE_IMPORTER};
 use crate::ss2_common::read_u32;
 use cgmath::Vector4;
 use cgmath::vec4;
 use engine::assets::asset_cache::AssetCache;
+use engine::assets::placeholders;
 use engine::scene::VertexPositionTextureLightmapAtlasNormal;
 pub use scene_builder::to_scene;
 
+use crate::MaterialSoundTable;
 use crate::properties::PropertyDefinition;
 use crate::ss2_chunk_file_reader;
 use crate::ss2_entity_info;
@@ -79,6 +82,8 @@ pub struct SystemShock2Level {
 
     pub entity_info: SystemShock2EntityInfo,
     pub obj_map: HashMap<i32, String>,
+    /// Sound materials of the level's textures and objects, for footsteps and impacts
+    pub material_sounds: MaterialSoundTable,
 
     pub room_database: RoomDatabase,
     pub song_params: SongParams,
@@ -129,9 +134,135 @@ pub fn read<T: io::Read + io::Seek>(
     links: &Vec<Box<dyn LinkDefinition>>,
     links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
     properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> SystemShock2Level {
+    read_level(
+        asset_cache,
+        reader,
+        gamesys,
+        links,
+        links_with_data,
+        properties,
+        texture_dimensions,
+    )
+}
+
+///
+/// read_headless
+///
+/// Like `read`, but sizes the textures from their decoded images instead of uploading them - for
+/// tools working on a level without a GL context, like the glTF export
+pub fn read_headless<T: io::Read + io::Seek>(
+    asset_cache: &mut AssetCache,
+    reader: &mut T,
+    gamesys: &Gamesys,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> SystemShock2Level {
+    read_level(
+        asset_cache,
+        reader,
+        gamesys,
+        links,
+        links_with_data,
+        properties,
+        raw_texture_dimensions,
+    )
+}
+
+// How the size of a texture is found - it's needed to work out the texture coordinates
+type TextureSizer = fn(&mut AssetCache, &SystemShock2Texture) -> TextureSize;
+
+fn read_level<T: io::Read + io::Seek>(
+    asset_cache: &mut AssetCache,
+    reader: &mut T,
+    gamesys: &Gamesys,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+    texture_sizer: TextureSizer,
 ) -> SystemShock2Level {
     let table_of_contents = ss2_chunk_file_reader::read_table_of_contents(reader);
+    let chunks = read_chunks(
+        &table_of_contents,
+        reader,
+        gamesys,
+        links,
+        links_with_data,
+        properties,
+    );
 
+    let all_geometry = create_geometry(
+        asset_cache,
+        &chunks.cells,
+        &chunks.textures.0,
+        texture_sizer,
+    );
+    let material_sounds = MaterialSoundTable::from_entity_info(
+        &ss2_entity_info::merge_with_gamesys(&chunks.entity_info, gamesys),
+        &chunks.obj_map,
+    );
+
+    SystemShock2Level {
+        bsp_tree: chunks.bsp_tree,
+        all_geometry,
+        textures: chunks.textures,
+        lightmap_atlas: chunks.lightmap_atlas,
+        obj_map: chunks.obj_map,
+        material_sounds,
+        cells: chunks.cells,
+        entity_info: chunks.entity_info,
+        room_database: chunks.room_database,
+        song_params: chunks.song_params,
+        path_database: chunks.path_database,
+    }
+}
+
+///
+/// read_chunk_report
+///
+/// Parse a mission in strict mode - everything `read` does, short of building geometry - and report
+/// which chunks were parsed, and how much of each
+pub fn read_chunk_report<T: io::Read + io::Seek>(
+    reader: &mut T,
+    gamesys: &Gamesys,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> ChunkReport {
+    let table_of_contents = ss2_chunk_file_reader::read_table_of_contents_strict(reader);
+    let _ = read_chunks(
+        &table_of_contents,
+        reader,
+        gamesys,
+        links,
+        links_with_data,
+        properties,
+    );
+    table_of_contents.report().unwrap_or_default()
+}
+
+// Everything parsed from the mission file, before geometry is built
+struct MissionChunks {
+    bsp_tree: BspTree,
+    cells: Vec<Cell>,
+    lightmap_atlas: TexturePacker<image::Rgb<u8>>,
+    obj_map: HashMap<i32, String>,
+    textures: TextureList,
+    entity_info: SystemShock2EntityInfo,
+    room_database: RoomDatabase,
+    song_params: SongParams,
+    path_database: Option<PathDatabase>,
+}
+
+fn read_chunks<T: io::Read + io::Seek>(
+    table_of_contents: &ChunkFileTableOfContents,
+    reader: &mut T,
+    gamesys: &Gamesys,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> MissionChunks {
     let wr_ext = table_of_contents.has_chunk("WREXT".to_string()); // Extended representation
     let wr_rgb = table_of_contents.has_chunk("WRRGB".to_string()); // RGB representation
     let mut light_size = 1;
@@ -191,10 +322,11 @@ pub fn read<T: io::Read + io::Seek>(
         num_static_lights,
         num_dynamic_lights
     );
+    table_of_contents.finish_chunk(world_chunk_name, reader);
 
-    let (obj_map, obj_texture_families) = read_obj_map(&table_of_contents, reader);
+    let (obj_map, obj_texture_families) = read_obj_map(table_of_contents, reader);
     let entity_info = ss2_entity_info::new(
-        &table_of_contents,
+        table_of_contents,
         links,
         links_with_data,
         properties,
@@ -202,18 +334,16 @@ pub fn read<T: io::Read + io::Seek>(
     );
 
     let textures = TextureList::read(
-        &table_of_contents,
+        table_of_contents,
         gamesys,
         &entity_info,
         obj_texture_families,
         reader,
     );
-    let all_geometry = create_geometry(asset_cache, &cells, &textures.0);
-
-    let _render_params = RenderParams::read(&table_of_contents, reader);
-    let room_database = RoomDatabase::read(&table_of_contents, reader);
-    let song_params = SongParams::read(&table_of_contents, reader);
-    let path_database = PathDatabase::read(&table_of_contents, reader);
+    let _render_params = RenderParams::read(table_of_contents, reader);
+    let room_database = RoomDatabase::read(table_of_contents, reader);
+    let song_params = SongParams::read(table_of_contents, reader);
+    let path_database = PathDatabase::read(table_of_contents, reader);
 
     // Log AIPATH data if loaded
     if let Some(ref pathdb) = path_database {
@@ -238,13 +368,12 @@ pub fn read<T: io::Read + io::Seek>(
         }
     }
 
-    SystemShock2Level {
+    MissionChunks {
         bsp_tree,
-        all_geometry,
-        textures,
+        cells,
         lightmap_atlas: packer,
         obj_map,
-        cells,
+        textures,
         entity_info,
         room_database,
         song_params,
@@ -277,6 +406,7 @@ fn read_obj_map<T: io::Read + io::Seek>(
 
         obj_map.insert(obj_id, str);
     }
+    table_of_contents.finish_chunk("OBJ_MAP", reader);
     (obj_map, texture_families)
 }
 
@@ -284,6 +414,7 @@ fn create_geometry(
     asset_cache: &mut AssetCache,
     cells: &Vec<Cell>,
     textures: &Vec<SystemShock2Texture>,
+    texture_sizer: TextureSizer,
 ) -> Vec<SystemShock2Geometry> {
     let mut all_geometry: Vec<SystemShock2Geometry> = Vec::new();
     let mut cell_idx = 0;
@@ -321,7 +452,7 @@ fn create_geometry(
             let sh_v = render_poly.v / 4096.0;
 
             let tex_info = &textures[render_poly.texture_num as usize];
-            let texture_dim = texture_dimensions(asset_cache, tex_info);
+            let texture_dim = texture_sizer(asset_cache, tex_info);
 
             let rs_x = (texture_dim.width as f32) / 64.0;
             let rs_y = (texture_dim.height as f32) / 64.0;
@@ -484,12 +615,9 @@ fn texture_dimensions(asset_cache: &mut AssetCache, tex_info: &SystemShock2Textu
             height: 1,
         }
     } else {
-        let tex_name = format!(
-            "{}/{}.PCX",
-            tex_info.family.to_uppercase(),
-            tex_info.texture_filename
-        );
-        let texture = asset_cache.get(&TEXTURE_IMPORTER, &tex_name);
+        let texture = asset_cache
+            .get_or_warn(&TEXTURE_IMPORTER, &tex_info.asset_name())
+            .unwrap_or_else(placeholders::texture);
 
         TextureSize {
             width: texture.width(),
@@ -497,3 +625,27 @@ fn texture_dimensions(asset_cache: &mut AssetCache, tex_info: &SystemShock2Textu
         }
     }
 }
+
+fn raw_texture_dimensions(
+    asset_cache: &mut AssetCache,
+    tex_info: &SystemShock2Texture,
+) -> TextureSize {
+    if tex_info.texture_filename == "null" {
+        return TextureSize {
+            width: 1,
+            height: 1,
+        };
+    }
+
+    match asset_cache.get_or_warn(&RAW_TEXTURE_IMPORTER, &tex_info.asset_name()) {
+        Some(texture) => TextureSize {
+            width: texture.width,
+            height: texture.height,
+        },
+        // The size of the placeholder checker texture
+        None => TextureSize {
+            width: 64,
+            height: 64,
+        },
+    }
+}
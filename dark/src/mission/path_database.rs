@@ -334,6 +334,8 @@ impl PathDatabase {
             num_cell_vertices
         );
 
+        table_of_contents.finish_chunk("AIPATH", reader);
+
         Some(PathDatabase {
             cells,
             vertices,
@@ -25,6 +25,7 @@ impl RenderParams {
 
         let _palette = read_string_with_size(reader, 16);
         let ambient = read_single(reader);
+        table_of_contents.finish_chunk("RENDPARAMS", reader);
 
         RenderParams {
             ambient_color: vec3(ambient, ambient, ambient),
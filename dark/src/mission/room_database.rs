@@ -33,6 +33,7 @@ impl RoomDatabase {
         for _ in 0..count {
             rooms.push(Room::read(reader));
         }
+        table_of_contents.finish_chunk("ROOM_DB", reader);
 
         RoomDatabase { rooms }
     }
@@ -1,9 +1,9 @@
 use std::{cell::RefCell, collections::HashMap, env, rc::Rc, time::Duration};
 
 use engine::{
-    assets::asset_cache::AssetCache,
+    assets::{asset_cache::AssetCache, placeholders},
     scene::{SceneObject, VertexPositionTextureLightmapAtlasNormal, VertexPositionTextureNormal},
-    texture::{AnimatedTexture, Texture, TextureTrait},
+    texture::{AnimatedTexture, TextureTrait},
 };
 
 use crate::{
@@ -64,18 +64,16 @@ pub fn to_scene(
         }
 
         let tex_info = &level.textures.0[*texture_id as usize];
-        let initial_texture: &Rc<Texture> = {
-            &asset_cache
-                .get(
-                    &TEXTURE_IMPORTER,
-                    &format!(
-                        "{}/{}.PCX",
-                        tex_info.family.to_uppercase(),
-                        tex_info.texture_filename
-                    ),
-                )
-                .clone()
-        };
+        let initial_texture = asset_cache
+            .get_or_warn(
+                &TEXTURE_IMPORTER,
+                &format!(
+                    "{}/{}.PCX",
+                    tex_info.family.to_uppercase(),
+                    tex_info.texture_filename
+                ),
+            )
+            .unwrap_or_else(placeholders::texture);
 
         let animated_texture: Rc<dyn TextureTrait> =
             if let Some(animation_info) = &tex_info.animation_info {
@@ -21,6 +21,7 @@ impl SongParams {
         reader.seek(SeekFrom::Start(chunk.offset)).unwrap();
 
         let song = read_string_with_size(reader, 32);
+        table_of_contents.finish_chunk("SONGPARAMS", reader);
 
         SongParams { song }
     }
@@ -1,6 +1,6 @@
 use crate::Gamesys;
 use crate::properties::{AnimTexFlags, PropAnimTex, PropRenderType, RenderType};
-use crate::ss2_chunk_file_reader::ChunkFileTableOfContents;
+use crate::ss2_chunk_file_reader::{self, ChunkFileTableOfContents};
 use crate::ss2_entity_info::{self, SystemShock2EntityInfo};
 use byteorder::ReadBytesExt;
 use shipyard::{Get, View, World};
@@ -26,6 +26,24 @@ pub struct SystemShock2Texture {
     pub animation_info: Option<TextureAnimationInfo>,
 }
 
+impl SystemShock2Texture {
+    /// The texture's asset name, ie "FAMILY/name.PCX"
+    pub fn asset_name(&self) -> String {
+        format!(
+            "{}/{}.PCX",
+            self.family.to_uppercase(),
+            self.texture_filename
+        )
+    }
+}
+
+// Data pulled from a texture's archetype
+#[derive(Clone, Debug)]
+struct TextureArchetypeInfo {
+    render_type: RenderType,
+    animation_info: Option<TextureAnimationInfo>,
+}
+
 pub struct TextureList(pub Vec<SystemShock2Texture>);
 
 impl TextureList {
@@ -49,8 +67,40 @@ impl TextureList {
 fn read_txlist_chunk<T: io::Read + io::Seek>(
     table_of_contents: &ChunkFileTableOfContents,
     reader: &mut T,
-    name_to_info: HashMap<String, (RenderType, Option<TextureAnimationInfo>)>,
+    name_to_info: HashMap<String, TextureArchetypeInfo>,
 ) -> TextureList {
+    let mut textures = Vec::new();
+    for (family, name) in read_txlist_entries(table_of_contents, reader) {
+        let entity_name = format!("t_fam/{}/{}", family, name);
+
+        let info = {
+            if let Some(info) = name_to_info.get(&entity_name) {
+                info!("texture info for: {} is {:?}", entity_name, info);
+                info.clone()
+            } else {
+                warn!("no texture info for: {}", entity_name);
+                TextureArchetypeInfo {
+                    render_type: RenderType::Normal,
+                    animation_info: None,
+                }
+            }
+        };
+
+        textures.push(SystemShock2Texture {
+            family,
+            texture_filename: name,
+            render_type: info.render_type,
+            animation_info: info.animation_info,
+        })
+    }
+    TextureList(textures)
+}
+
+// Read the (family, texture name) pairs from the TXLIST chunk
+fn read_txlist_entries<T: io::Read + io::Seek>(
+    table_of_contents: &ChunkFileTableOfContents,
+    reader: &mut T,
+) -> Vec<(String, String)> {
     let txlist = table_of_contents
         .get_chunk("TXLIST".to_string())
         .unwrap()
@@ -62,7 +112,7 @@ fn read_txlist_chunk<T: io::Read + io::Seek>(
     let fam_count = reader.read_u32::<byteorder::LittleEndian>().unwrap();
 
     let mut texture_fams = Vec::new();
-    let mut textures = Vec::new();
+    let mut entries = Vec::new();
 
     // Texture families are top-level folders in the res/fam zip file,
     // and share the same palette (which is of no consequence here...)
@@ -82,33 +132,31 @@ fn read_txlist_chunk<T: io::Read + io::Seek>(
             family = texture_fams[(fam - 1) as usize].to_owned();
         }
 
-        let entity_name = format!("t_fam/{}/{}", family, name);
-
-        let (render_type, maybe_animation_info) = {
-            if let Some(info) = name_to_info.get(&entity_name) {
-                info!("texture info for: {} is {:?}", entity_name, info);
-                (info.0.clone(), info.1.clone())
-            } else {
-                warn!("no texture info for: {}", entity_name);
-                (RenderType::Normal, None)
-            }
-        };
-
-        textures.push(SystemShock2Texture {
-            family,
-            texture_filename: name,
-            render_type,
-            animation_info: maybe_animation_info,
-        })
+        entries.push((family, name));
     }
-    TextureList(textures)
+    table_of_contents.finish_chunk("TXLIST", reader);
+    entries
+}
+
+///
+/// read_texture_asset_names
+///
+/// The texture assets ("FAMILY/name.PCX") a mission uses, straight from its TXLIST chunk.
+/// Unlike `TextureList::read`, this doesn't need the gamesys, so it can run off the main thread.
+pub fn read_texture_asset_names<T: io::Read + io::Seek>(reader: &mut T) -> Vec<String> {
+    let table_of_contents = ss2_chunk_file_reader::read_table_of_contents(reader);
+    read_txlist_entries(&table_of_contents, reader)
+        .into_iter()
+        .filter(|(_, name)| name != "null")
+        .map(|(family, name)| format!("{}/{}.PCX", family.to_uppercase(), name))
+        .collect()
 }
 
 fn read_texture_archetypes(
     obj_texture_families: Vec<(String, i32)>,
     entity_info: &SystemShock2EntityInfo,
     gamesys: &Gamesys,
-) -> HashMap<String, (RenderType, Option<TextureAnimationInfo>)> {
+) -> HashMap<String, TextureArchetypeInfo> {
     let mut world = World::new();
     let name_map_override = HashMap::new();
 
@@ -152,9 +200,13 @@ fn read_texture_archetypes(
                     None
                 }
             };
+
             name_to_info.insert(
                 family_name.clone(),
-                (render_type, maybe_texture_animation_info),
+                TextureArchetypeInfo {
+                    render_type,
+                    animation_info: maybe_texture_animation_info,
+                },
             );
 
             // if let Ok(anim_tex) = maybe_anim_tex {
@@ -6,9 +6,12 @@ use crate::{
     ss2_bin_obj_loader::{self, SystemShock2ObjectMesh, Vhot},
     ss2_skeleton::{self, AnimationInfo, Bone, Skeleton},
 };
-use cgmath::{Matrix4, SquareMatrix, Vector2};
+use cgmath::{Matrix4, SquareMatrix, Vector2, point3};
 use collision::Aabb3;
-use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use engine::{
+    assets::{asset_cache::AssetCache, placeholders},
+    scene::SceneObject,
+};
 
 #[derive(Clone)]
 pub struct StaticModel {
@@ -223,6 +226,25 @@ impl Model {
         }
     }
 
+    ///
+    /// placeholder
+    ///
+    /// Stands in for a model that failed to load
+    pub fn placeholder() -> Model {
+        let half_size = placeholders::CUBE_SIZE / 2.0;
+        Model {
+            transform: Matrix4::identity(),
+            inner: InnerModel::Static(StaticModel {
+                scene_objects: vec![placeholders::cube()],
+                bounding_box: Aabb3::new(
+                    point3(-half_size, -half_size, -half_size),
+                    point3(half_size, half_size, half_size),
+                ),
+                vhots: vec![],
+            }),
+        }
+    }
+
     pub fn to_scene_objects(&self) -> &Vec<SceneObject> {
         match &self.inner {
             InnerModel::Animated(animated_model) => animated_model.to_scene_objects(),
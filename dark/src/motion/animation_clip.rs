@@ -1,10 +1,27 @@
 use std::{collections::HashMap, time::Duration};
 
-use cgmath::{Deg, Matrix4, Vector3};
+use cgmath::{Deg, InnerSpace, Matrix4, Vector3, vec3};
 
 use crate::motion::{JointId, MpsMotion};
 
-use super::{FrameFlags, MotionClip, MotionStuff};
+use super::{AnimationMarkerFrame, FrameFlags, MotionClip, MotionStuff};
+
+// Clips that move less than this (horizontally, over the whole clip) are treated as in-place
+const IN_PLACE_TRANSLATION_THRESHOLD: f32 = 0.05;
+
+///
+/// RootMotionMode
+///
+/// How the horizontal root translation of a clip is turned into creature velocity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootMotionMode {
+    /// Move at a constant velocity - the clip's total translation over its duration
+    SlidingVelocity,
+    /// Follow the root translation frame-by-frame, for clips with uneven motion (lunges, stumbles)
+    PerFrame,
+    /// Ignore the clip's translation entirely - for standing / idle clips with a little drift
+    InPlace,
+}
 
 #[derive(Clone)]
 pub struct AnimationClip {
@@ -17,8 +34,11 @@ pub struct AnimationClip {
     pub translation: Vector3<f32>,
     pub joint_to_frame: HashMap<JointId, Vec<Matrix4<f32>>>,
     pub root_transforms: Vec<Matrix4<f32>>, // root transforms per frame
+    pub root_translations: Vec<Vector3<f32>>, // full root translation per frame
+    pub root_motion: RootMotionMode,
     pub motion_flags: Vec<FrameFlags>,
-    pub name: Option<String>, // Added for GLB animation support
+    pub markers: Vec<AnimationMarkerFrame>, // custom events from AnimationEventMarkup
+    pub name: Option<String>,               // Added for GLB animation support
 }
 
 impl AnimationClip {
@@ -44,6 +64,7 @@ impl AnimationClip {
         let duration = time_per_frame * (mps_motion.frame_count as u32);
 
         let sliding_velocity = motion_stuff.translation / duration.as_secs_f32();
+        let root_motion = default_root_motion_mode(motion_stuff.translation);
         let end_rotation = motion_stuff.end_direction;
 
         let mut joint_to_frame = HashMap::new();
@@ -63,12 +84,48 @@ impl AnimationClip {
             blend_length: Duration::from_millis(motion_stuff.blend_length as u64),
             joint_to_frame,
             root_transforms: motion_clip.root_transforms.clone(),
+            root_translations: motion_clip.root_translations.clone(),
+            root_motion,
             time_per_frame,
             motion_flags: mps_motion.motion_flags.clone(),
+            markers: Vec::new(),
             sliding_velocity,
             translation: motion_stuff.translation,
             end_rotation,
             name: Some(mps_motion.name.clone()), // Use motion name for traditional SS2 animations
         }
     }
+
+    ///
+    /// root_velocity
+    ///
+    /// Velocity (in the clip's space) from root motion at `frame`, using `mode`.
+    /// The vertical component is always zero - that's handled by the root transforms.
+    pub fn root_velocity(&self, mode: RootMotionMode, frame: u32) -> Vector3<f32> {
+        match mode {
+            RootMotionMode::InPlace => vec3(0.0, 0.0, 0.0),
+            RootMotionMode::SlidingVelocity => {
+                vec3(self.sliding_velocity.x, 0.0, self.sliding_velocity.z)
+            }
+            RootMotionMode::PerFrame => {
+                let len = self.root_translations.len();
+                let time_per_frame = self.time_per_frame.as_secs_f32();
+                if len < 2 || time_per_frame <= 0.0 {
+                    return self.root_velocity(RootMotionMode::SlidingVelocity, frame);
+                }
+
+                let idx = (frame as usize).min(len - 2);
+                let delta = self.root_translations[idx + 1] - self.root_translations[idx];
+                vec3(delta.x, 0.0, delta.z) / time_per_frame
+            }
+        }
+    }
+}
+
+fn default_root_motion_mode(translation: Vector3<f32>) -> RootMotionMode {
+    if vec3(translation.x, 0.0, translation.z).magnitude() < IN_PLACE_TRANSLATION_THRESHOLD {
+        RootMotionMode::InPlace
+    } else {
+        RootMotionMode::SlidingVelocity
+    }
 }
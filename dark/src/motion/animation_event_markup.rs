@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+///
+/// AnimationMarker
+///
+/// A custom event marked up on a frame of a motion - things the motion DB's built-in flags don't cover
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationMarker {
+    FootstepLeft,
+    FootstepRight,
+    AttackHit,
+    ReloadClick,
+    Custom(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AnimationMarkerFrame {
+    pub frame: u32,
+    pub marker: AnimationMarker,
+}
+
+///
+/// AnimationEventMarkup
+///
+/// Custom animation events per motion name, loaded from `animation_events.json` alongside the motion DB.
+/// The file maps (case-insensitive) motion names to a list of marked frames:
+///
+/// ```json
+/// {
+///     "bb100101": [
+///         { "frame": 4, "marker": "footstep_left" },
+///         { "frame": 12, "marker": "footstep_right" },
+///         { "frame": 20, "marker": { "custom": "roar" } }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct AnimationEventMarkup {
+    motions: HashMap<String, Vec<AnimationMarkerFrame>>,
+}
+
+impl AnimationEventMarkup {
+    pub fn from_json(json: &str) -> Result<AnimationEventMarkup, serde_json::Error> {
+        let markup: AnimationEventMarkup = serde_json::from_str(json)?;
+        let motions = markup
+            .motions
+            .into_iter()
+            .map(|(name, mut frames)| {
+                frames.sort_by_key(|f| f.frame);
+                (name.to_ascii_lowercase(), frames)
+            })
+            .collect();
+        Ok(AnimationEventMarkup { motions })
+    }
+
+    pub fn markers_for(&self, motion_name: &str) -> Vec<AnimationMarkerFrame> {
+        self.motions
+            .get(&motion_name.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markup() {
+        let json = r#"{
+            "BB100101": [
+                { "frame": 12, "marker": "footstep_right" },
+                { "frame": 4, "marker": "footstep_left" },
+                { "frame": 20, "marker": { "custom": "roar" } }
+            ]
+        }"#;
+
+        let markup = AnimationEventMarkup::from_json(json).unwrap();
+        let markers = markup.markers_for("bb100101");
+        assert_eq!(markers.len(), 3);
+        assert_eq!(markers[0].marker, AnimationMarker::FootstepLeft);
+        assert_eq!(markers[1].frame, 12);
+        assert_eq!(
+            markers[2].marker,
+            AnimationMarker::Custom("roar".to_owned())
+        );
+        assert!(markup.markers_for("unknown").is_empty());
+    }
+}
@@ -6,7 +6,7 @@ use rpds as immutable;
 
 use crate::ss2_skeleton::{self, AnimationInfo, Skeleton};
 
-use super::{AnimationClip, MotionFlags};
+use super::{AnimationClip, AnimationMarker, MotionFlags};
 pub enum AnimationFlags {
     Loop,
     PlayOnce,
@@ -15,6 +15,8 @@ pub enum AnimationFlags {
 pub enum AnimationEvent {
     DirectionChanged(Deg<f32>),
     VelocityChanged(Vector3<f32>),
+    /// A custom event from the clip's markup was passed
+    Marker(AnimationMarker),
     Completed,
 }
 
@@ -94,6 +96,16 @@ impl AnimationPlayer {
         }
     }
 
+    ///
+    /// current_clip
+    ///
+    /// The clip currently playing, along with the frame it's on
+    pub fn current_clip(&self) -> Option<(&Rc<AnimationClip>, u32)> {
+        self.animation
+            .first()
+            .map(|(clip, _)| (clip, self.current_frame))
+    }
+
     pub fn set_additional_joint_transform(
         player: &AnimationPlayer,
         joint_idx: u32,
@@ -157,13 +169,13 @@ impl AnimationPlayer {
             (updated_player, motion_flags, vec![], vec3(0.0, 0.0, 0.0))
         } else {
             let (current_clip, flags) = maybe_current_clip.unwrap();
-            let velocity = current_clip.sliding_velocity;
             let mut next_frame = player.current_frame;
             let time_per_frame = current_clip.time_per_frame.as_secs_f32();
             while remaining_duration >= time_per_frame {
                 remaining_duration -= time_per_frame;
                 next_frame += 1;
             }
+            let velocity = current_clip.root_velocity(current_clip.root_motion, next_frame);
 
             let motion_flags = {
                 let mut output = MotionFlags::empty();
@@ -175,8 +187,16 @@ impl AnimationPlayer {
                 output
             };
 
+            // Like motion flags, markers fire when playback moves past their frame
+            let marker_events = current_clip
+                .markers
+                .iter()
+                .filter(|m| m.frame > player.current_frame && m.frame <= next_frame)
+                .map(|m| AnimationEvent::Marker(m.marker.clone()))
+                .collect::<Vec<AnimationEvent>>();
+
             if next_frame >= current_clip.num_frames {
-                let mut events = Vec::new();
+                let mut events = marker_events;
 
                 events.push(AnimationEvent::Completed);
 
@@ -219,7 +239,7 @@ impl AnimationPlayer {
                     }
                 }
             } else {
-                let events = if !player.animation.is_empty()
+                let mut events = if !player.animation.is_empty()
                     && player.current_frame == 0
                     && next_frame > 0
                 {
@@ -230,6 +250,7 @@ impl AnimationPlayer {
                 } else {
                     vec![]
                 };
+                events.extend(marker_events);
                 (
                     AnimationPlayer {
                         additional_joint_transforms: player.additional_joint_transforms.clone(),
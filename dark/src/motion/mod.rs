@@ -1,15 +1,19 @@
 mod animation_clip;
+mod animation_event_markup;
 mod animation_player;
 mod glb_animation;
 mod motion_clip;
+mod motion_db_json;
 mod motion_info;
 pub mod motion_query;
 mod motion_schema;
 
 pub use animation_clip::*;
+pub use animation_event_markup::*;
 pub use animation_player::*;
 pub use glb_animation::*;
 pub use motion_clip::*;
+pub use motion_db_json::*;
 pub use motion_info::*;
 pub use motion_query::*;
 pub use motion_schema::*;
@@ -37,6 +41,11 @@ pub struct MotionDB {
 
     // Dictionary to resolve values -> strings for animation names
     tag_value_to_animations: HashMap<i32, Vec<String>>,
+    // Schemas, indexed by the values in the tag databases
+    motion_schemas: Vec<MotionSchema>,
+
+    // Added / retagged animations, applied on top of query results
+    overlay: MotionDbOverlay,
 }
 
 impl MotionDB {
@@ -125,7 +134,7 @@ impl MotionDB {
 
         let mut tag_value_to_animation_name = HashMap::new();
         let mut tag_value_to_animations = HashMap::new();
-        let mut tag_value_to_motion_schema = HashMap::new();
+        let mut motion_schemas = Vec::new();
         let schemas = read_u32(reader);
         trace!("motion_schema_count: {schemas}");
         for i in 0..schemas {
@@ -150,7 +159,7 @@ impl MotionDB {
                 .join(", ");
 
             tag_value_to_animation_name.insert(i as i32, summary_str);
-            tag_value_to_motion_schema.insert(i as i32, schema);
+            motion_schemas.push(schema);
             tag_value_to_animations.insert(i as i32, animations);
         }
 
@@ -168,10 +177,18 @@ impl MotionDB {
             tag_databases,
             tag_name_map: name_map,
             tag_value_to_animations,
+            motion_schemas,
+            overlay: MotionDbOverlay::default(),
         }
     }
     fn query_options(&self, query: &MotionQuery) -> Vec<String> {
         info!("motion_query: {:?}", query);
+        let options = self.overlay.apply(query, self.query_tag_database(query));
+        info!("options: {:?}", options);
+        options
+    }
+
+    fn query_tag_database(&self, query: &MotionQuery) -> Vec<String> {
         let creature_type = query.creature_type;
 
         if creature_type >= self.tag_databases.len() as u32 {
@@ -191,15 +208,12 @@ impl MotionDB {
             return vec![];
         }
 
-        let options = query_result
+        query_result
             .into_iter()
             .filter_map(|idx| self.tag_value_to_animations.get(&idx))
             .flatten()
             .cloned()
-            .collect::<Vec<String>>();
-
-        info!("options: {:?}", options);
-        options
+            .collect::<Vec<String>>()
     }
 
     ///
@@ -1,6 +1,6 @@
 use std::io::{self, SeekFrom};
 
-use cgmath::{Matrix4, SquareMatrix, vec3};
+use cgmath::{Matrix4, SquareMatrix, Vector3, vec3};
 
 use crate::{
     SCALE_FACTOR,
@@ -12,7 +12,8 @@ use crate::{
 pub struct MotionClip {
     pub num_joints: u32,
     pub root_transforms: Vec<Matrix4<f32>>, // root transforms across frames
-    pub animation: Vec<Vec<Matrix4<f32>>>,  // joint -> animations across frames
+    pub root_translations: Vec<Vector3<f32>>, // full root translation across frames, for per-frame root motion
+    pub animation: Vec<Vec<Matrix4<f32>>>,    // joint -> animations across frames
 }
 
 impl MotionClip {
@@ -26,6 +27,7 @@ impl MotionClip {
         // Read transforms for root joint
         let mut animation = Vec::new();
         let mut root_transforms = Vec::new();
+        let mut root_translations = Vec::new();
         let mut frame_transforms = Vec::new();
         for _frame in 0..num_frames {
             // We handle the root transforms in a special way,
@@ -41,6 +43,7 @@ impl MotionClip {
                 xform.y / SCALE_FACTOR,
                 0.0,
             )));
+            root_translations.push(xform / SCALE_FACTOR);
         }
         animation.push(frame_transforms);
 
@@ -58,6 +61,7 @@ impl MotionClip {
 
         MotionClip {
             root_transforms: root_transforms,
+            root_translations,
             num_joints,
             animation,
         }
@@ -0,0 +1,379 @@
+///
+/// motion_db_json.rs
+///
+/// The motion database as JSON - an export of everything we read from `motiondb.bin`, and an overlay
+/// that's applied on top of it, so animations can be added or retagged without editing the original file.
+///
+/// The overlay (`motiondb_overlay.json` alongside the motion DB) binds animations to tags per creature type,
+/// and can remove animations from query results:
+///
+/// ```json
+/// {
+///     "bindings": [
+///         {
+///             "creature_type": 0,
+///             "tags": [{ "tag": "locourgent" }, { "tag": "direction", "value": 0 }],
+///             "animations": ["my_run"],
+///             "replace": false
+///         }
+///     ],
+///     "remove": ["bh212223"]
+/// }
+/// ```
+///
+/// A binding applies to a query for its creature type when every required (non-optional) tag in the query
+/// is one of the binding's tags - a binding tag without a value matches any value. Its animations are added
+/// to the results, or replace them when `replace` is set. Retagging an animation is a `remove` plus a binding.
+/// Bound animations are loaded like any other (ie, `<name>_.mc`), so new animations need to be loadable.
+///
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{MotionDB, MotionQuery, MotionQueryItem};
+use crate::{NameMap, TagDatabase};
+
+/// Version of the export format - bumped when fields change meaning
+pub const MOTION_DB_EXPORT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MotionDbExport {
+    pub version: u32,
+    /// Every tag name known to the motion DB
+    pub tags: Vec<String>,
+    pub motions: Vec<MotionExport>,
+    /// Schemas, by index - the values stored in the tag trees
+    pub schemas: Vec<MotionSchemaExport>,
+    /// Tag tree per creature type (actor type), by index
+    pub creature_types: Vec<TagNodeExport>,
+    pub overlay: MotionDbOverlay,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MotionExport {
+    pub name: String,
+    pub frame_count: f32,
+    pub frame_rate: i32,
+    /// Seconds
+    pub duration: f32,
+    pub blend_length: u16,
+    pub end_direction_degrees: f32,
+    /// In engine units
+    pub translation: [f32; 3],
+    pub frame_flags: Vec<FrameFlagsExport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FrameFlagsExport {
+    pub frame: u32,
+    /// Raw MotionFlags bits
+    pub flags: u32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MotionSchemaExport {
+    pub index: u32,
+    pub archetype_index: i32,
+    pub schema_id: u32,
+    pub flags: u32,
+    pub time_modifier: f32,
+    pub dist_modifier: f32,
+    pub animations: Vec<String>,
+}
+
+///
+/// TagNodeExport
+///
+/// A node of a tag tree - the tag (and value range) to reach it, the schemas stored at it, and its children.
+/// The root of each tree has no tag.
+#[derive(Clone, Debug, Serialize)]
+pub struct TagNodeExport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i32>,
+    pub schemas: Vec<i32>,
+    pub children: Vec<TagNodeExport>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MotionDbOverlay {
+    #[serde(default)]
+    pub bindings: Vec<MotionBinding>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MotionBinding {
+    pub creature_type: u32,
+    pub tags: Vec<MotionBindingTag>,
+    pub animations: Vec<String>,
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MotionBindingTag {
+    pub tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<i32>,
+}
+
+impl MotionDbOverlay {
+    pub fn from_json(json: &str) -> Result<MotionDbOverlay, serde_json::Error> {
+        let overlay: MotionDbOverlay = serde_json::from_str(json)?;
+        Ok(overlay.normalized())
+    }
+
+    /// Add another overlay's bindings and removals - later overlays apply after earlier ones
+    pub fn merge(&mut self, other: MotionDbOverlay) {
+        let other = other.normalized();
+        self.bindings.extend(other.bindings);
+        self.remove.extend(other.remove);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty() && self.remove.is_empty()
+    }
+
+    /// Apply the overlay to the animations the tag database returned for a query
+    pub(crate) fn apply(&self, query: &MotionQuery, mut options: Vec<String>) -> Vec<String> {
+        options.retain(|animation| !self.remove.contains(animation));
+
+        for binding in self.bindings.iter().filter(|b| b.matches(query)) {
+            if binding.replace {
+                options.clear();
+            }
+            options.extend(binding.animations.iter().cloned());
+        }
+
+        options
+    }
+
+    // Tags and names are case-insensitive, like the rest of the motion DB
+    fn normalized(self) -> MotionDbOverlay {
+        let lowercase = |names: Vec<String>| {
+            names
+                .into_iter()
+                .map(|name| name.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+        };
+        MotionDbOverlay {
+            bindings: self
+                .bindings
+                .into_iter()
+                .map(|binding| MotionBinding {
+                    tags: binding
+                        .tags
+                        .into_iter()
+                        .map(|tag| MotionBindingTag {
+                            tag: tag.tag.to_ascii_lowercase(),
+                            value: tag.value,
+                        })
+                        .collect(),
+                    animations: lowercase(binding.animations),
+                    ..binding
+                })
+                .collect(),
+            remove: lowercase(self.remove),
+        }
+    }
+}
+
+impl MotionBinding {
+    fn matches(&self, query: &MotionQuery) -> bool {
+        self.creature_type == query.creature_type
+            && query
+                .items
+                .iter()
+                .filter(|item| !is_optional(item))
+                .all(|item| self.has_tag(item))
+    }
+
+    fn has_tag(&self, item: &MotionQueryItem) -> bool {
+        self.tags.iter().any(|tag| {
+            tag.tag == item.tag_name()
+                && match (item, tag.value) {
+                    (MotionQueryItem::TagWithValue(_, value, _), Some(tag_value)) => {
+                        *value == tag_value
+                    }
+                    _ => true,
+                }
+        })
+    }
+}
+
+fn is_optional(item: &MotionQueryItem) -> bool {
+    match item {
+        MotionQueryItem::Tag(_, optional) => *optional,
+        MotionQueryItem::TagWithValue(_, _, optional) => *optional,
+    }
+}
+
+impl MotionDB {
+    ///
+    /// export
+    ///
+    /// Everything in the motion DB, in the documented JSON form (see MotionDbExport)
+    pub fn export(&self) -> MotionDbExport {
+        let index_to_name = self
+            .animation_name_to_index
+            .iter()
+            .map(|(name, idx)| (*idx, name.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let mut motions = self
+            .mps_motions
+            .iter()
+            .zip(self.motion_stuffs.iter())
+            .enumerate()
+            .map(|(idx, (mps_motion, motion_stuff))| MotionExport {
+                name: index_to_name
+                    .get(&(idx as u32))
+                    .cloned()
+                    .unwrap_or_else(|| mps_motion.name.to_ascii_lowercase()),
+                frame_count: mps_motion.frame_count,
+                frame_rate: mps_motion.frame_rate,
+                duration: motion_stuff.duration,
+                blend_length: motion_stuff.blend_length,
+                end_direction_degrees: motion_stuff.end_direction.0,
+                translation: motion_stuff.translation.into(),
+                frame_flags: mps_motion
+                    .motion_flags
+                    .iter()
+                    .map(|flags| FrameFlagsExport {
+                        frame: flags.frame,
+                        flags: flags.flags.bits(),
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        motions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let schemas = self
+            .motion_schemas
+            .iter()
+            .enumerate()
+            .map(|(index, schema)| MotionSchemaExport {
+                index: index as u32,
+                archetype_index: schema.archetype_index,
+                schema_id: schema.schema_id,
+                flags: schema.flags,
+                time_modifier: schema.time_modifier,
+                dist_modifier: schema.dist_modifier,
+                animations: self
+                    .tag_value_to_animations
+                    .get(&(index as i32))
+                    .cloned()
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let creature_types = self
+            .tag_databases
+            .iter()
+            .map(|tag_database| export_tag_tree(tag_database, &self.tag_name_map, None))
+            .collect();
+
+        MotionDbExport {
+            version: MOTION_DB_EXPORT_VERSION,
+            tags: self.get_all_tag_names(),
+            motions,
+            schemas,
+            creature_types,
+            overlay: self.overlay.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.export())
+    }
+
+    ///
+    /// apply_overlay
+    ///
+    /// Add an overlay's bindings and removals to the motion DB's queries
+    pub fn apply_overlay(&mut self, overlay: MotionDbOverlay) {
+        self.overlay.merge(overlay);
+    }
+}
+
+fn export_tag_tree(
+    tag_database: &TagDatabase,
+    tag_names: &NameMap,
+    key: Option<(&str, i32, i32)>,
+) -> TagNodeExport {
+    let mut children = tag_database
+        .branches()
+        .map(|(key, branch)| {
+            let name = tag_names
+                .get_name(key.key_type)
+                .map(|name| name.as_str())
+                .unwrap_or("?");
+            export_tag_tree(branch, tag_names, Some((name, key.min, key.max)))
+        })
+        .collect::<Vec<_>>();
+    // Branches are stored in a map - sort them, so exports are stable
+    children.sort_by(|a, b| (&a.tag, a.min, a.max).cmp(&(&b.tag, b.min, b.max)));
+
+    TagNodeExport {
+        tag: key.map(|(name, _, _)| name.to_owned()),
+        min: key.map(|(_, min, _)| min),
+        max: key.map(|(_, _, max)| max),
+        schemas: tag_database.data().iter().map(|data| data.data).collect(),
+        children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_bindings() {
+        let overlay = MotionDbOverlay::from_json(
+            r#"{
+                "bindings": [
+                    { "creature_type": 0, "tags": [{ "tag": "LocoUrgent" }, { "tag": "direction", "value": 0 }], "animations": ["My_Run"] },
+                    { "creature_type": 0, "tags": [{ "tag": "idle" }], "animations": ["my_idle"], "replace": true }
+                ],
+                "remove": ["BH212223"]
+            }"#,
+        )
+        .unwrap();
+
+        let run = MotionQuery::new(
+            0,
+            vec![
+                MotionQueryItem::new("locourgent"),
+                MotionQueryItem::with_value("direction", 0),
+                MotionQueryItem::new("crouching").optional(),
+            ],
+        );
+        assert_eq!(
+            overlay.apply(&run, vec!["bh212223".to_owned(), "bh212224".to_owned()]),
+            vec!["bh212224".to_owned(), "my_run".to_owned()]
+        );
+
+        let wrong_direction = MotionQuery::new(
+            0,
+            vec![
+                MotionQueryItem::new("locourgent"),
+                MotionQueryItem::with_value("direction", 1),
+            ],
+        );
+        assert!(overlay.apply(&wrong_direction, vec![]).is_empty());
+
+        let idle = MotionQuery::new(0, vec![MotionQueryItem::new("idle")]);
+        assert_eq!(
+            overlay.apply(&idle, vec!["bh100101".to_owned()]),
+            vec!["my_idle".to_owned()]
+        );
+
+        let other_creature = MotionQuery::new(1, vec![MotionQueryItem::new("idle")]);
+        assert!(overlay.apply(&other_creature, vec![]).is_empty());
+    }
+}
@@ -5,8 +5,12 @@ mod prop_ai_alertness;
 mod prop_ai_aware_delay;
 mod prop_ai_camera;
 mod prop_ai_device;
+mod prop_ai_flee_conditions;
 mod prop_ai_mode;
+mod prop_ai_ranged_combat;
+mod prop_ai_rating;
 mod prop_ambient_hacked;
+mod prop_anim_light;
 mod prop_anim_tex;
 mod prop_bitmap_animation;
 mod prop_collision_type;
@@ -17,6 +21,7 @@ mod prop_frob_info;
 mod prop_hit_points;
 mod prop_key;
 mod prop_log;
+mod prop_loot;
 mod prop_particles;
 mod prop_phys_attr;
 mod prop_phys_initial_velocity;
@@ -38,8 +43,12 @@ pub use prop_ai_alertness::*;
 pub use prop_ai_aware_delay::*;
 pub use prop_ai_camera::*;
 pub use prop_ai_device::*;
+pub use prop_ai_flee_conditions::*;
 pub use prop_ai_mode::*;
+pub use prop_ai_ranged_combat::*;
+pub use prop_ai_rating::*;
 pub use prop_ambient_hacked::*;
+pub use prop_anim_light::*;
 pub use prop_anim_tex::*;
 pub use prop_bitmap_animation::*;
 pub use prop_collision_type::*;
@@ -50,6 +59,7 @@ pub use prop_frob_info::*;
 pub use prop_hit_points::*;
 pub use prop_key::*;
 pub use prop_log::*;
+pub use prop_loot::*;
 pub use prop_particles::*;
 pub use prop_phys_attr::*;
 pub use prop_phys_initial_velocity::*;
@@ -71,6 +81,7 @@ use serde::{
 };
 
 use std::{
+    any::{Any, TypeId},
     collections::HashMap,
     convert::identity,
     fmt,
@@ -223,6 +234,9 @@ impl PropMapObjRotate {
 #[derive(Debug, Component, Clone, Serialize, Deserialize)]
 pub struct PropSymName(pub String);
 
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropDesignNote(pub String);
+
 #[derive(Debug, Component, Clone, Serialize, Deserialize)]
 pub struct PropMotionActorTags {
     pub tags: Vec<String>,
@@ -239,6 +253,21 @@ impl PropMotionActorTags {
 #[derive(Debug, Component, Clone, Serialize, Deserialize)]
 pub struct PropSelfIllumination(pub f32);
 
+// Light added to (or taken away from) an object, on top of the light it receives
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropExtraLight {
+    pub amount: f32,
+    pub additive: bool,
+}
+
+impl PropExtraLight {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, _len: u32) -> PropExtraLight {
+        let amount = read_single(reader);
+        let additive = read_bool(reader);
+        PropExtraLight { amount, additive }
+    }
+}
+
 #[derive(Debug, Component, Clone, Serialize, Deserialize)]
 pub struct PropInventoryDimensions {
     pub width: u32,
@@ -372,6 +401,9 @@ pub enum AITargetMethod {
     Radius = 4,
 }
 
+// The link data structs below keep every field they read - even the ones we don't use yet - so a link
+// can be saved and restored, or written back out, without changing its behavior.
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AIProjectileOptions {
     pub targeting_method: AITargetMethod,
@@ -382,34 +414,32 @@ pub struct AIProjectileOptions {
     pub select_time: f32,
     pub joint: u32, // joint to use for projectile
     pub vhot: u32,  // vhot to use for projectile
+    #[serde(default)]
+    pub unknown: [u32; 5],
 }
 
 impl AIProjectileOptions {
     pub fn read(reader: &mut Box<dyn ReadAndSeek>, _len: u32) -> AIProjectileOptions {
-        let _unk = read_u32(reader);
-        let _unk = read_u32(reader);
+        let unk0 = read_u32(reader);
+        let unk1 = read_u32(reader);
 
         let targeting_method_u32 = read_u32(reader);
         let targeting_method = AITargetMethod::from_u32(targeting_method_u32).unwrap();
 
-        let _unk = read_u32(reader);
+        let unk2 = read_u32(reader);
         let delay = read_single(reader);
         let should_lead_target = read_bool(reader);
         let ammo = read_u32(reader);
 
-        let _unk = read_u32(reader);
+        let unk3 = read_u32(reader);
         let accuracy = read_u32(reader);
 
         let joint = read_u32(reader);
         let vhot = read_u32(reader);
 
         let select_time = read_single(reader);
-        let _unk = read_u32(reader);
+        let unk4 = read_u32(reader);
 
-        // let speed = read_single(reader) / SCALE_FACTOR;
-        // let time = read_single(reader);
-        // let limit = read_bool(reader);
-        // let paused = read_u32(reader);
         AIProjectileOptions {
             targeting_method,
             delay,
@@ -419,22 +449,58 @@ impl AIProjectileOptions {
             select_time,
             joint,
             vhot,
+            unknown: [unk0, unk1, unk2, unk3, unk4],
         }
     }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        let [unk0, unk1, unk2, unk3, unk4] = self.unknown;
+        write_u32(writer, unk0);
+        write_u32(writer, unk1);
+        write_u32(writer, self.targeting_method as u32);
+        write_u32(writer, unk2);
+        write_single(writer, self.delay);
+        write_bool(writer, self.should_lead_target);
+        write_u32(writer, self.ammo);
+        write_u32(writer, unk3);
+        write_u32(writer, self.accuracy);
+        write_u32(writer, self.joint);
+        write_u32(writer, self.vhot);
+        write_single(writer, self.select_time);
+        write_u32(writer, unk4);
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TPathData {
     pub speed: f32,
+    #[serde(default)]
+    pub time: f32,
+    #[serde(default)]
+    pub limit: bool,
+    #[serde(default)]
+    pub paused: u32,
 }
 
 impl TPathData {
     pub fn read(reader: &mut Box<dyn ReadAndSeek>, _len: u32) -> TPathData {
         let speed = read_single(reader) / SCALE_FACTOR;
-        let _time = read_single(reader);
-        let _limit = read_bool(reader);
-        let _paused = read_u32(reader);
-        TPathData { speed }
+        let time = read_single(reader);
+        let limit = read_bool(reader);
+        let paused = read_u32(reader);
+        TPathData {
+            speed,
+            time,
+            limit,
+            paused,
+        }
+    }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        write_single(writer, self.speed * SCALE_FACTOR);
+        write_single(writer, self.time);
+        write_bool(writer, self.limit);
+        write_u32(writer, self.paused);
     }
 }
 
@@ -450,11 +516,16 @@ impl ProjectileOptions {
         let setting = read_i32(reader);
         ProjectileOptions { order, setting }
     }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        write_i32(writer, self.order);
+        write_i32(writer, self.setting);
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CorpseOptions {
-    propagate_scale: bool,
+    pub propagate_scale: bool,
 }
 
 impl CorpseOptions {
@@ -462,7 +533,12 @@ impl CorpseOptions {
         let propagate_scale = read_bool(reader);
         CorpseOptions { propagate_scale }
     }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        write_bool(writer, self.propagate_scale);
+    }
 }
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GunFlashOptions {
     pub vhot: u32,
@@ -475,14 +551,19 @@ impl GunFlashOptions {
         let flags = read_u32(reader);
         GunFlashOptions { vhot, flags }
     }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        write_u32(writer, self.vhot);
+        write_u32(writer, self.flags);
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FlinderizeOptions {
-    count: u32,
-    impulse: f32,
-    scatter: bool,
-    offset: Vector3<f32>,
+    pub count: u32,
+    pub impulse: f32,
+    pub scatter: bool,
+    pub offset: Vector3<f32>,
 }
 
 impl FlinderizeOptions {
@@ -498,6 +579,13 @@ impl FlinderizeOptions {
             offset,
         }
     }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        write_u32(writer, self.count);
+        write_single(writer, self.impulse);
+        write_bool(writer, self.scatter);
+        write_vec3(writer, self.offset * SCALE_FACTOR);
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -505,24 +593,53 @@ pub struct AIWatchOptions {
     pub radius: f32,
     pub height: f32,
     pub scripted_actions: Vec<AIScriptedAction>,
-}
+    #[serde(default)]
+    pub trigger: u32,
+    #[serde(default)]
+    pub awareness: u32,
+    #[serde(default)]
+    pub visibility: u32,
+    #[serde(default)]
+    pub kill_condition: u32,
+    #[serde(default)]
+    pub kill_like_links: bool,
+    #[serde(default)]
+    pub once_only: bool,
+    #[serde(default)]
+    pub reuse_time: i32,
+    #[serde(default)]
+    pub reset_time: i32,
+    #[serde(default)]
+    pub min_alertness: u32,
+    #[serde(default)]
+    pub max_alertness: u32,
+    #[serde(default)]
+    pub priority: u32,
+    #[serde(default)]
+    pub unknown: Vec<u8>,
+    #[serde(default)]
+    pub unknown2: i32,
+}
+
+// Bytes at the start of the watch link data that we don't interpret
+const AI_WATCH_UNKNOWN_SIZE: usize = 60;
 
 impl AIWatchOptions {
     pub fn read(reader: &mut Box<dyn ReadAndSeek>, _len: u32) -> AIWatchOptions {
-        let _unknown = read_bytes(reader, 60);
-
-        let _trigger = read_u32(reader);
-        let _awareness = read_u32(reader);
-        let _ai_watch_visibility = read_u32(reader);
-        let _unknown2 = read_i32(reader);
-        let _ai_watch_kill_condition = read_u32(reader);
-        let _kill_like_links = read_bool(reader);
-        let _once_only = read_bool(reader);
-        let _reuse_time = read_i32(reader);
-        let _reset_time = read_i32(reader);
-        let _min_alertness = read_u32(reader);
-        let _max_alertness = read_u32(reader);
-        let _ai_priority = read_u32(reader);
+        let unknown = read_bytes(reader, AI_WATCH_UNKNOWN_SIZE);
+
+        let trigger = read_u32(reader);
+        let awareness = read_u32(reader);
+        let visibility = read_u32(reader);
+        let unknown2 = read_i32(reader);
+        let kill_condition = read_u32(reader);
+        let kill_like_links = read_bool(reader);
+        let once_only = read_bool(reader);
+        let reuse_time = read_i32(reader);
+        let reset_time = read_i32(reader);
+        let min_alertness = read_u32(reader);
+        let max_alertness = read_u32(reader);
+        let priority = read_u32(reader);
         let radius = read_i32(reader) as f32 / SCALE_FACTOR;
         let height = read_i32(reader) as f32 / SCALE_FACTOR;
 
@@ -536,6 +653,69 @@ impl AIWatchOptions {
             radius,
             height,
             scripted_actions,
+            trigger,
+            awareness,
+            visibility,
+            kill_condition,
+            kill_like_links,
+            once_only,
+            reuse_time,
+            reset_time,
+            min_alertness,
+            max_alertness,
+            priority,
+            unknown,
+            unknown2,
+        }
+    }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        let mut unknown = self.unknown.clone();
+        unknown.resize(AI_WATCH_UNKNOWN_SIZE, 0);
+        write_bytes(writer, &unknown);
+
+        write_u32(writer, self.trigger);
+        write_u32(writer, self.awareness);
+        write_u32(writer, self.visibility);
+        write_i32(writer, self.unknown2);
+        write_u32(writer, self.kill_condition);
+        write_bool(writer, self.kill_like_links);
+        write_bool(writer, self.once_only);
+        write_i32(writer, self.reuse_time);
+        write_i32(writer, self.reset_time);
+        write_u32(writer, self.min_alertness);
+        write_u32(writer, self.max_alertness);
+        write_u32(writer, self.priority);
+        write_i32(writer, (self.radius * SCALE_FACTOR).round() as i32);
+        write_i32(writer, (self.height * SCALE_FACTOR).round() as i32);
+
+        for action in &self.scripted_actions {
+            action.write(writer);
+        }
+    }
+}
+
+impl Link {
+    ///
+    /// write_data
+    ///
+    /// Write the link's data (the `LD$` chunk entry) in its original format - nothing, for links without data
+    pub fn write_data<T: io::Write>(&self, writer: &mut T) {
+        match self {
+            Link::AIProjectile(options) => options.write(writer),
+            Link::AIWatchObj(options) => options.write(writer),
+            Link::Contains(value) => write_u32(writer, *value),
+            Link::Corpse(options) => options.write(writer),
+            Link::Flinderize(options) => options.write(writer),
+            Link::GunFlash(options) => options.write(writer),
+            Link::Projectile(options) => options.write(writer),
+            Link::TPath(data) => data.write(writer),
+            Link::AIRangedWeapon
+            | Link::LandingPoint
+            | Link::Replicator
+            | Link::SwitchLink
+            | Link::MissSpang
+            | Link::TPathInit => (),
         }
     }
 }
@@ -754,6 +934,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             |str| PropAI(str),
             accumulator::latest,
         ),
+        define_prop(
+            "P$AI_Aggress",
+            PropAIAggression::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$AI_AlertCap",
             PropAIAlertCap::read,
@@ -784,19 +970,49 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$AI_FleeCo",
+            PropAIFleeConditions::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$AI_Hearing",
+            PropAIHearing::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop("P$AI_Mode", PropAIMode::read, identity, accumulator::latest),
+        define_prop(
+            "P$AI_RngCbt",
+            PropAIRangedCombat::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$AI_SigRsp",
             PropAISignalResponse::read,
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$AI_Vision",
+            PropAIVision::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$AmbientHa",
             PropAmbientHacked::read,
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$AnimLight",
+            PropAnimLight::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$AnimTex",
             PropAnimTex::read,
@@ -863,6 +1079,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             PropExp,
             accumulator::latest,
         ),
+        define_prop(
+            "P$ExtraLigh",
+            PropExtraLight::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$FrameAniC",
             PropFrameAnimConfig::read,
@@ -1113,6 +1335,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$GunState",
+            PropGunState::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$PGLaunchI",
             PropParticleLaunchInfo::read,
@@ -1164,6 +1392,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             PropSymName,
             accumulator::latest,
         ),
+        define_prop(
+            "P$DesignNot",
+            read_variable_length_string,
+            PropDesignNote,
+            accumulator::latest,
+        ),
         define_prop(
             "P$HasRefs",
             |reader, _len| read_bool(reader),
@@ -1188,6 +1422,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$LootInfo",
+            PropLootInfo::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$RepConten",
             PropReplicatorContents::read,
@@ -1231,6 +1471,24 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$CfgTweqRo",
+            PropTweqRotateConfig::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$CfgTweqSc",
+            PropTweqScaleConfig::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$CfgTweqLo",
+            PropTweqLockConfig::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$SignalTyp",
             read_variable_length_string,
@@ -1261,6 +1519,18 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$StTweqSca",
+            PropTweqScaleState::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$StTweqLoc",
+            PropTweqLockState::read,
+            identity,
+            accumulator::latest,
+        ),
         // Internal properties
         // These are not properties that are provided by shock2 game,
         // but are used internally for save/restore.
@@ -1440,6 +1710,10 @@ where
     fn initialize(&self, world: &mut World, entity: EntityId) {
         world.add_component(entity, self.clone());
     }
+
+    fn merge(&self, merged: &mut PropertyMerge) {
+        merged.set(self.clone());
+    }
 }
 
 #[derive(Debug)]
@@ -1475,13 +1749,86 @@ where
         drop(view);
         world.add_component(entity, value_to_set);
     }
+
+    fn merge(&self, merged: &mut PropertyMerge) {
+        merged.accumulate(self.inner_property.clone(), self.accumulator);
+    }
 }
 
-pub trait Property: fmt::Debug {
+pub trait Property: fmt::Debug + Send + Sync {
     fn initialize(&self, world: &mut World, entity: EntityId);
+
+    ///
+    /// merge
+    ///
+    /// Like `initialize`, but into a PropertyMerge instead of the world - so the properties
+    /// of a template hierarchy can be merged off the main thread
+    fn merge(&self, merged: &mut PropertyMerge);
+}
+
+trait MergedProperty: Send {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    fn add_to_entity(self: Box<Self>, world: &mut World, entity: EntityId);
+}
+
+impl<C> MergedProperty for C
+where
+    C: Component + TupleAddComponent + std::marker::Send + std::marker::Sync,
+{
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn add_to_entity(self: Box<Self>, world: &mut World, entity: EntityId) {
+        world.add_component(entity, *self);
+    }
+}
+
+///
+/// PropertyMerge
+///
+/// The properties of an entity, merged down its template hierarchy - one value per property type,
+/// with the most specific template winning (or accumulated, for wrapped properties). Send, so
+/// entities can be merged in parallel, and then added to the world in one go.
+#[derive(Default)]
+pub struct PropertyMerge {
+    properties: HashMap<TypeId, Box<dyn MergedProperty>>,
+}
+
+impl PropertyMerge {
+    pub fn new() -> PropertyMerge {
+        PropertyMerge::default()
+    }
+
+    pub fn set<C>(&mut self, value: C)
+    where
+        C: Component + TupleAddComponent + std::marker::Send + std::marker::Sync,
+    {
+        self.properties.insert(TypeId::of::<C>(), Box::new(value));
+    }
+
+    pub fn accumulate<C>(&mut self, value: C, accumulator: fn(C, C) -> C)
+    where
+        C: Component + TupleAddComponent + std::marker::Send + std::marker::Sync,
+    {
+        let merged_value = match self.properties.remove(&TypeId::of::<C>()) {
+            Some(previous) => match previous.into_any().downcast::<C>() {
+                Ok(previous_value) => accumulator(*previous_value, value),
+                Err(_) => value,
+            },
+            None => value,
+        };
+        self.set(merged_value);
+    }
+
+    pub fn add_to_entity(self, world: &mut World, entity: EntityId) {
+        for (_, property) in self.properties {
+            property.add_to_entity(world, entity);
+        }
+    }
 }
 
-pub trait PropertyDefinition<R: io::Read + io::Seek> {
+pub trait PropertyDefinition<R: io::Read + io::Seek>: Send + Sync {
     fn name(&self) -> String;
 
     fn read(&self, reader: &mut R, prop_len: u32) -> Box<dyn Property>;
@@ -1494,6 +1841,18 @@ pub trait PropertyDefinition<R: io::Read + io::Seek> {
         world: &mut World,
         entity_id_map: &HashMap<EntityId, EntityId>,
     );
+
+    /// The serialized property of a single entity, or None if it doesn't have one
+    fn serialize_entity(&self, world: &World, entity_id: EntityId) -> Option<serde_json::Value>;
+
+    /// Replace a single entity's property - unlike `deserialize`, a value that doesn't fit the
+    /// property is an error rather than a panic
+    fn deserialize_entity(
+        &self,
+        value: serde_json::Value,
+        world: &mut World,
+        entity_id: EntityId,
+    ) -> Result<(), serde_json::Error>;
 }
 
 pub trait LinkDefinition {
@@ -1625,6 +1984,23 @@ where
             }
         }
     }
+
+    fn serialize_entity(&self, world: &World, entity_id: EntityId) -> Option<serde_json::Value> {
+        let view: View<ROutput> = world.borrow::<View<ROutput>>().unwrap();
+        let prop = view.get(entity_id).ok()?;
+        serde_json::to_value(prop).ok()
+    }
+
+    fn deserialize_entity(
+        &self,
+        value: serde_json::Value,
+        world: &mut World,
+        entity_id: EntityId,
+    ) -> Result<(), serde_json::Error> {
+        let prop: ROutput = serde_json::from_value(value)?;
+        world.add_component(entity_id, prop);
+        Ok(())
+    }
 }
 
 pub fn define_prop<
@@ -1668,3 +2044,122 @@ pub fn define_link_with_data<TData: 'static + fmt::Debug + Send + Sync + Clone>(
         converter,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<TData>(
+        bytes: Vec<u8>,
+        read: fn(&mut Box<dyn ReadAndSeek>, u32) -> TData,
+        to_link: fn(TData) -> Link,
+    ) {
+        let len = bytes.len() as u32;
+        let mut reader: Box<dyn ReadAndSeek> = Box::new(Cursor::new(bytes.clone()));
+        let link = to_link(read(&mut reader, len));
+
+        let mut written = Vec::new();
+        link.write_data(&mut written);
+        assert_eq!(written, bytes, "{:?} should write back unchanged", link);
+
+        let json = serde_json::to_value(&link).unwrap();
+        let restored: Link = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, link);
+    }
+
+    #[test]
+    fn test_link_data_round_trip() {
+        let mut tpath = Vec::new();
+        write_single(&mut tpath, 10.0);
+        write_single(&mut tpath, 1.5);
+        write_bool(&mut tpath, true);
+        write_u32(&mut tpath, 1);
+        round_trip(tpath, TPathData::read, Link::TPath);
+
+        let mut flinderize = Vec::new();
+        write_u32(&mut flinderize, 4);
+        write_single(&mut flinderize, 2.0);
+        write_bool(&mut flinderize, false);
+        write_vec3(&mut flinderize, vec3(5.0, 10.0, -2.5));
+        round_trip(flinderize, FlinderizeOptions::read, Link::Flinderize);
+
+        let mut projectile = Vec::new();
+        for value in [7, 9, 1, 3] {
+            write_u32(&mut projectile, value);
+        }
+        write_single(&mut projectile, 0.5);
+        for value in [1, 12, 2, 80, 3, 4] {
+            write_u32(&mut projectile, value);
+        }
+        write_single(&mut projectile, 0.25);
+        write_u32(&mut projectile, 6);
+        round_trip(projectile, AIProjectileOptions::read, Link::AIProjectile);
+
+        // An argument that fills its field, with no terminator
+        let full_arg = "x".repeat(64);
+        let mut watch = (0..60).collect::<Vec<u8>>();
+        for value in [1, 2, 3, -1, 4, 1, 0, 1000, 2000, 1, 3, 4, 25, 10] {
+            write_i32(&mut watch, value);
+        }
+        for (action_id, args) in [
+            (8, ["1500", "", "", ""]),
+            (3, [full_arg.as_str(), "", "", ""]),
+            (14, ["gogogo", "bot_1", "", ""]),
+        ] {
+            write_u32(&mut watch, action_id);
+            for arg in args {
+                write_string_with_size(&mut watch, arg, 64);
+            }
+        }
+        for _ in 0..5 {
+            write_u32(&mut watch, 0);
+            watch.extend(std::iter::repeat_n(0, 4 * 64));
+        }
+        round_trip(watch, AIWatchOptions::read, Link::AIWatchObj);
+    }
+
+    #[test]
+    fn test_property_merge_matches_initialize() {
+        fn join_names(ancestor: PropSymName, newest: PropSymName) -> PropSymName {
+            PropSymName(format!("{}/{}", ancestor.0, newest.0))
+        }
+
+        let props: Vec<Box<dyn Property>> = vec![
+            Box::new(PropCreature(1)),
+            Box::new(WrappedProperty {
+                inner_property: PropSymName("base".to_owned()),
+                accumulator: join_names,
+            }),
+            Box::new(PropCreature(2)),
+            Box::new(WrappedProperty {
+                inner_property: PropSymName("derived".to_owned()),
+                accumulator: join_names,
+            }),
+        ];
+
+        let mut initialized_world = World::new();
+        let initialized_entity = initialized_world.add_entity(());
+        for prop in &props {
+            prop.initialize(&mut initialized_world, initialized_entity);
+        }
+
+        let mut merged_world = World::new();
+        let merged_entity = merged_world.add_entity(());
+        let mut merged = PropertyMerge::new();
+        for prop in &props {
+            prop.merge(&mut merged);
+        }
+        merged.add_to_entity(&mut merged_world, merged_entity);
+
+        for (world, entity) in [
+            (&initialized_world, initialized_entity),
+            (&merged_world, merged_entity),
+        ] {
+            let (v_creature, v_sym_name) = world
+                .borrow::<(View<PropCreature>, View<PropSymName>)>()
+                .unwrap();
+            assert_eq!(v_creature.get(entity).unwrap().0, 2);
+            assert_eq!(v_sym_name.get(entity).unwrap().0, "base/derived");
+        }
+    }
+}
@@ -4,8 +4,11 @@ use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use shipyard::Component;
 
-use crate::ss2_common::{read_bytes, read_string_with_size, read_u32};
+use crate::ss2_common::{
+    read_bytes, read_string_with_size, read_u32, write_string_with_size, write_u32,
+};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 #[derive(Debug, Component, Clone, Deserialize, Serialize)]
 pub struct PropAI(pub String);
@@ -62,28 +65,34 @@ pub enum AIScriptedActionType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AIScriptedAction {
     pub action_type: AIScriptedActionType,
+    // The action as stored - its type and all four arguments, not just the ones `action_type` uses -
+    // so it can be written back unchanged
+    #[serde(default)]
+    pub action_id: u32,
+    #[serde(default)]
+    pub args: [String; 4],
 }
 
 impl AIScriptedAction {
     pub fn read<T: io::Read + io::Seek>(reader: &mut T) -> AIScriptedAction {
-        let action_type_u32 = read_u32(reader);
-        let sz0 = read_string_with_size(reader, 64);
-        let sz1 = read_string_with_size(reader, 64);
-        let sz2 = read_string_with_size(reader, 64);
-        let sz3 = read_string_with_size(reader, 64);
+        let action_id = read_u32(reader);
+        let args: [String; 4] = std::array::from_fn(|_| read_string_with_size(reader, 64));
+        let [sz0, sz1, sz2, sz3] = args.clone();
 
-        let action_type = match action_type_u32 {
+        let action_type = match action_id {
             0 => AIScriptedActionType::Nothing,
             1 => AIScriptedActionType::ScriptMessage(sz0),
             2 => AIScriptedActionType::Play(sz2),
+            3 => AIScriptedActionType::Alert,
             4 => AIScriptedActionType::BecomeHostile,
+            5 => AIScriptedActionType::EnableInvestigate,
             6 => AIScriptedActionType::Goto {
                 waypoint_name: sz0,
                 speed: sz1,
             },
             7 => AIScriptedActionType::Frob(sz0),
             8 => {
-                let milliseconds = u64::from_str_radix(&sz0, 10).unwrap();
+                let milliseconds = sz0.trim().parse::<u64>().unwrap_or(0);
                 AIScriptedActionType::Wait(Duration::from_millis(milliseconds))
             }
             9 => AIScriptedActionType::Mprint(sz0),
@@ -105,13 +114,28 @@ impl AIScriptedAction {
                 signal: sz0,
                 entity_name: sz1,
             },
-            _ => panic!(
-                "Unhandled action type: {} |{}|{}|{}|{}",
-                action_type_u32, &sz0, &sz1, &sz2, &sz3
-            ),
+            15 => AIScriptedActionType::DestScript,
+            _ => {
+                warn!(
+                    "Unhandled action type: {} |{}|{}|{}|{}",
+                    action_id, &sz0, &sz1, &sz2, &sz3
+                );
+                AIScriptedActionType::Nothing
+            }
         };
 
-        AIScriptedAction { action_type }
+        AIScriptedAction {
+            action_type,
+            action_id,
+            args,
+        }
+    }
+
+    pub fn write<T: io::Write>(&self, writer: &mut T) {
+        write_u32(writer, self.action_id);
+        for arg in &self.args {
+            write_string_with_size(writer, arg, 64);
+        }
     }
 }
 
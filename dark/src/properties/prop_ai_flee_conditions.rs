@@ -0,0 +1,43 @@
+use std::io;
+
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use shipyard::Component;
+
+use crate::ss2_common::{read_bytes, read_u32};
+
+use super::AIPriority;
+
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAIFleeConditions {
+    pub priority: AIPriority,
+    /// Percentage (0-100) of max hit points below which the AI will flee
+    pub hit_point_percent: u32,
+    /// How long to keep fleeing before re-evaluating, in seconds
+    pub duration: f32,
+}
+
+impl PropAIFleeConditions {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropAIFleeConditions {
+        let priority = AIPriority::from_u32(read_u32(reader)).unwrap_or(AIPriority::None);
+        let hit_point_percent = read_u32(reader).min(100);
+        let duration = read_u32(reader) as f32 / 1000.0;
+
+        const EXPECTED_SIZE: u32 = 12;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        PropAIFleeConditions {
+            priority,
+            hit_point_percent,
+            duration,
+        }
+    }
+
+    /// Whether the flee condition is active at all
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self.priority, AIPriority::None) && self.hit_point_percent > 0
+    }
+}
@@ -0,0 +1,67 @@
+use std::io;
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use shipyard::Component;
+
+use crate::{
+    SCALE_FACTOR,
+    ss2_common::{read_bool, read_bytes, read_single, read_u32},
+};
+
+/// How strongly an AI prefers to break line-of-sight between volleys
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromPrimitive, ToPrimitive)]
+pub enum AICoverDesire {
+    None = 0,
+    Low = 1,
+    Moderate = 2,
+    High = 3,
+}
+
+impl AICoverDesire {
+    pub fn from_raw(raw: u32) -> AICoverDesire {
+        AICoverDesire::from_u32(raw).unwrap_or(AICoverDesire::None)
+    }
+}
+
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAIRangedCombat {
+    /// Closest the AI wants to be to its target before backing off (scaled to world units)
+    pub min_distance: f32,
+    /// Preferred engagement distance (scaled to world units)
+    pub ideal_distance: f32,
+    /// Furthest the AI will attempt to engage from (scaled to world units)
+    pub max_distance: f32,
+    /// Minimum time between shots, in seconds
+    pub fire_delay: f32,
+    pub cover_desire: AICoverDesire,
+    pub fire_while_moving: bool,
+}
+
+impl PropAIRangedCombat {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropAIRangedCombat {
+        let min_distance = read_single(reader) / SCALE_FACTOR;
+        let ideal_distance = read_single(reader) / SCALE_FACTOR;
+        let max_distance = read_single(reader) / SCALE_FACTOR;
+        let fire_delay = read_u32(reader) as f32 / 1000.0;
+        let cover_desire = AICoverDesire::from_raw(read_u32(reader));
+        let fire_while_moving = read_bool(reader);
+
+        const EXPECTED_SIZE: u32 = 24;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        PropAIRangedCombat {
+            min_distance,
+            ideal_distance,
+            max_distance,
+            fire_delay,
+            cover_desire,
+            fire_while_moving,
+        }
+    }
+}
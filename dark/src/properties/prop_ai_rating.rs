@@ -0,0 +1,88 @@
+use std::io;
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use shipyard::Component;
+
+use crate::ss2_common::{read_bytes, read_u32};
+
+/// Five-point scale the gamesys uses to rate AI abilities (vision, hearing, aggression...)
+#[repr(u32)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    FromPrimitive,
+    ToPrimitive,
+)]
+pub enum AIRating {
+    Null = 0,
+    WellBelowAverage = 1,
+    BelowAverage = 2,
+    Average = 3,
+    AboveAverage = 4,
+    WellAboveAverage = 5,
+}
+
+impl AIRating {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> AIRating {
+        let rating = AIRating::from_u32(read_u32(reader)).unwrap_or(AIRating::Average);
+
+        const EXPECTED_SIZE: u32 = 4;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        rating
+    }
+
+    ///
+    /// scale
+    ///
+    /// Multiplier relative to an average AI - 0 for a Null rating (the ability is switched off)
+    pub fn scale(&self) -> f32 {
+        match self {
+            AIRating::Null => 0.0,
+            AIRating::WellBelowAverage => 0.5,
+            AIRating::BelowAverage => 0.75,
+            AIRating::Average => 1.0,
+            AIRating::AboveAverage => 1.25,
+            AIRating::WellAboveAverage => 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAIVision(pub AIRating);
+
+impl PropAIVision {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropAIVision {
+        PropAIVision(AIRating::read(reader, len))
+    }
+}
+
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAIHearing(pub AIRating);
+
+impl PropAIHearing {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropAIHearing {
+        PropAIHearing(AIRating::read(reader, len))
+    }
+}
+
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAIAggression(pub AIRating);
+
+impl PropAIAggression {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropAIAggression {
+        PropAIAggression(AIRating::read(reader, len))
+    }
+}
@@ -0,0 +1,81 @@
+use std::{io, time::Duration};
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use shipyard::Component;
+
+use crate::ss2_common::{read_bytes, read_i32, read_single};
+
+/// How an animated light's brightness changes over time
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromPrimitive)]
+pub enum AnimLightMode {
+    /// Snap between min and max brightness
+    Flip = 0,
+    /// Ramp up and down between min and max brightness
+    Smooth = 1,
+    /// Jump to a random brightness each period
+    Random = 2,
+    /// Hold at min brightness
+    Minimum = 3,
+    /// Hold at max brightness
+    Maximum = 4,
+    /// Off
+    Extinguish = 5,
+    /// Ramp up to max brightness once, then hold
+    SmoothBrighten = 6,
+    /// Ramp down to min brightness once, then hold
+    SmoothDim = 7,
+    /// Ramp towards a new random brightness each period
+    RandomCoherent = 8,
+    /// Mostly at max brightness, with short drops to min
+    Flicker = 9,
+}
+
+impl AnimLightMode {
+    fn from_raw(raw: i32) -> AnimLightMode {
+        AnimLightMode::from_i32(raw).unwrap_or(AnimLightMode::Maximum)
+    }
+}
+
+///
+/// PropAnimLight
+///
+/// An object's animated light - flickering fluorescents, pulsing warning lights. The designer-set
+/// fields lead the record: mode, rise and fall times (ms), and the brightness range. The rest is
+/// runtime housekeeping from the original renderer, which we skip.
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAnimLight {
+    pub mode: AnimLightMode,
+    /// Time to go from min to max brightness
+    pub rise_time: Duration,
+    /// Time to go from max to min brightness
+    pub fall_time: Duration,
+    pub min_brightness: f32,
+    pub max_brightness: f32,
+}
+
+impl PropAnimLight {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropAnimLight {
+        let mode = AnimLightMode::from_raw(read_i32(reader));
+        let rise_time = Duration::from_millis(read_i32(reader).max(0) as u64);
+        let fall_time = Duration::from_millis(read_i32(reader).max(0) as u64);
+        let min_brightness = read_single(reader);
+        let max_brightness = read_single(reader);
+
+        const EXPECTED_SIZE: u32 = 20;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        PropAnimLight {
+            mode,
+            rise_time,
+            fall_time,
+            min_brightness,
+            max_brightness,
+        }
+    }
+}
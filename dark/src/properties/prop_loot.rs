@@ -0,0 +1,54 @@
+use std::io;
+
+use shipyard::Component;
+
+use crate::ss2_common::{read_string_with_size, read_u32};
+use serde::{Deserialize, Serialize};
+
+const NUM_LOOT_ITEMS: usize = 10;
+
+// PropLootInfo - what a container or corpse can hold: `num_picks` rolls against the weighted
+// item list, done the first time it's opened. Like the replicator, items are archetype names.
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropLootInfo {
+    pub num_picks: u32,
+    pub worth: u32,
+    pub items: Vec<LootItem>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct LootItem {
+    pub object_name: String,
+    pub weight: u32,
+}
+
+impl PropLootInfo {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropLootInfo {
+        let num_picks = read_u32(reader);
+        let worth = read_u32(reader);
+
+        let object_names = (0..NUM_LOOT_ITEMS)
+            .map(|_| read_string_with_size(reader, 64).to_ascii_lowercase())
+            .collect::<Vec<String>>();
+        let weights = (0..NUM_LOOT_ITEMS)
+            .map(|_| read_u32(reader))
+            .collect::<Vec<u32>>();
+
+        // Unused slots are left blank
+        let items = object_names
+            .into_iter()
+            .zip(weights)
+            .filter(|(object_name, weight)| !object_name.is_empty() && *weight > 0)
+            .map(|(object_name, weight)| LootItem {
+                object_name,
+                weight,
+            })
+            .collect();
+
+        PropLootInfo {
+            num_picks,
+            worth,
+            items,
+        }
+    }
+}
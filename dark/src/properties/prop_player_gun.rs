@@ -3,7 +3,7 @@ use std::io;
 use cgmath::Vector3;
 use shipyard::Component;
 
-use crate::ss2_common::{read_string_with_size, read_u16, read_u32, read_vec3};
+use crate::ss2_common::{read_single, read_string_with_size, read_u16, read_u32, read_vec3};
 
 use serde::{Deserialize, Serialize};
 
@@ -45,3 +45,32 @@ impl PropPlayerGun {
         }
     }
 }
+
+// PropGunState - per-weapon state: loaded ammo, condition (0 - 100, degrades as the gun is used),
+// the current setting (ie, standard / armor piercing), and how far the gun has been modified
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropGunState {
+    pub ammo_count: u32,
+    pub condition: f32,
+    pub setting: u32,
+    pub modification: u32,
+}
+
+impl PropGunState {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, len: u32) -> PropGunState {
+        let ammo_count = read_u32(reader);
+        let condition = read_single(reader);
+        let (setting, modification) = if len >= 16 {
+            (read_u32(reader), read_u32(reader))
+        } else {
+            (0, 0)
+        };
+
+        PropGunState {
+            ammo_count,
+            condition,
+            setting,
+            modification,
+        }
+    }
+}
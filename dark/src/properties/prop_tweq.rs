@@ -1,6 +1,6 @@
 use std::{io, time::Duration};
 
-use cgmath::Vector3;
+use cgmath::{Quaternion, Vector3};
 use num_derive::FromPrimitive;
 use shipyard::Component;
 
@@ -28,6 +28,14 @@ bitflags! {
         const SIMLARGERAD = 1 << 5;
         const OFFSCREEN = 1 << 6;
     }
+    #[derive(Serialize, Deserialize)]
+    pub struct TweqCurve: u32 {
+        const JITTER_LOW = 1 << 0;
+        const JITTER_HIGH = 1 << 1;
+        const MUL = 1 << 2;
+        const PENDULUM = 1 << 3;
+        const BOUNCE = 1 << 4;
+    }
 }
 
 #[derive(FromPrimitive, Clone, Debug, Deserialize, Serialize)]
@@ -39,37 +47,251 @@ pub enum TweqHalt {
     SlayObj = 4,
 }
 
+/// Per-axis animation limits for vector tweqs (rotate / scale)
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct TweqVectorLimits {
+    /// Units per second - degrees for rotate tweqs, scale factor for scale tweqs
+    pub rate: f32,
+    pub low: f32,
+    pub high: f32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TweqVectorConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub curve: TweqCurve,
+    pub halt: TweqHalt,
+    pub primary_axis: u8,
+    pub limits: [TweqVectorLimits; 3],
+}
+
+impl TweqVectorConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> TweqVectorConfig {
+        let _unk = read_u8(reader);
+        let curve_bits = read_u8(reader);
+        let curve = TweqCurve::from_bits_truncate(curve_bits.into());
+        let animation_config_bits = read_u8(reader);
+        let animation_config =
+            TweqAnimationConfig::from_bits_truncate(animation_config_bits.into());
+        let halt_bits = read_u8(reader);
+        let halt: TweqHalt =
+            num_traits::FromPrimitive::from_u8(halt_bits).unwrap_or(TweqHalt::StopTweq);
+
+        let _misc = read_u16(reader);
+        let _rate = read_u16(reader);
+
+        let primary_axis = read_u8(reader);
+        let _pad = read_bytes(reader, 3);
+
+        // Each axis is stored as a (rate, low, high) vector
+        let mut limits = [TweqVectorLimits {
+            rate: 0.0,
+            low: 0.0,
+            high: 0.0,
+        }; 3];
+        for limit in limits.iter_mut() {
+            let rate_low_high = read_vec3(reader);
+            *limit = TweqVectorLimits {
+                rate: rate_low_high.x,
+                low: rate_low_high.y,
+                high: rate_low_high.z,
+            };
+        }
+
+        const EXPECTED_SIZE: u32 = 48;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        TweqVectorConfig {
+            animation_config,
+            curve,
+            halt,
+            primary_axis,
+            limits,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TweqVectorState {
+    pub animation_state: TweqAnimationState,
+    pub axis_animation_states: [TweqAnimationState; 3],
+}
+
+impl TweqVectorState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> TweqVectorState {
+        let animation_state_bits = read_u16(reader);
+        let animation_state = TweqAnimationState::from_bits_truncate(animation_state_bits.into());
+        let _unk2 = read_u16(reader); // misc state, is this used?
+
+        let axis1_animation_state = TweqAnimationState::from_bits_truncate(read_u32(reader));
+        let axis2_animation_state = TweqAnimationState::from_bits_truncate(read_u32(reader));
+        let axis3_animation_state = TweqAnimationState::from_bits_truncate(read_u32(reader));
+
+        TweqVectorState {
+            animation_state,
+            axis_animation_states: [
+                axis1_animation_state,
+                axis2_animation_state,
+                axis3_animation_state,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqRotateConfig(pub TweqVectorConfig);
+
+impl PropTweqRotateConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqRotateConfig {
+        PropTweqRotateConfig(TweqVectorConfig::read(reader, len))
+    }
+}
+
 #[derive(Debug, Component, Clone, Deserialize, Serialize)]
 pub struct PropTweqRotateState {
     pub animation_state: TweqAnimationState,
     pub axis1_animation_state: TweqAnimationState,
     pub axis2_animation_state: TweqAnimationState,
     pub axis3_animation_state: TweqAnimationState,
+
+    /// Current rotation offset (in degrees) of each axis, relative to `base_rotation`
+    pub current_angles: Vector3<f32>,
+    /// Orientation of the object when the tweq first ran
+    pub base_rotation: Option<Quaternion<f32>>,
 }
 
 impl PropTweqRotateState {
-    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropTweqRotateState {
-        // TODO: Look at the rotate state in earth, see if it works?
-        let animation_state_bits = read_u16(reader);
-        let animation_state = TweqAnimationState::from_bits(animation_state_bits.into()).unwrap();
-        let _unk2 = read_u16(reader); // misc state, is this used?
-
-        let axis1_animation_state_bits = read_u32(reader);
-        let axis2_animation_state_bits = read_u32(reader);
-        let axis3_animation_state_bits = read_u32(reader);
-
-        let axis1_animation_state =
-            TweqAnimationState::from_bits(axis1_animation_state_bits).unwrap();
-        let axis2_animation_state =
-            TweqAnimationState::from_bits(axis2_animation_state_bits).unwrap();
-        let axis3_animation_state =
-            TweqAnimationState::from_bits(axis3_animation_state_bits).unwrap();
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqRotateState {
+        let state = TweqVectorState::read(reader, len);
+        let [
+            axis1_animation_state,
+            axis2_animation_state,
+            axis3_animation_state,
+        ] = state.axis_animation_states;
 
         PropTweqRotateState {
-            animation_state,
+            animation_state: state.animation_state,
             axis1_animation_state,
             axis2_animation_state,
             axis3_animation_state,
+            current_angles: Vector3::new(0.0, 0.0, 0.0),
+            base_rotation: None,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqScaleConfig(pub TweqVectorConfig);
+
+impl PropTweqScaleConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqScaleConfig {
+        PropTweqScaleConfig(TweqVectorConfig::read(reader, len))
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqScaleState {
+    pub animation_state: TweqAnimationState,
+    pub axis_animation_states: [TweqAnimationState; 3],
+
+    /// Current scale of each axis. Initialized from the low limits the first time the tweq runs.
+    pub current_scale: Option<Vector3<f32>>,
+}
+
+impl PropTweqScaleState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqScaleState {
+        let state = TweqVectorState::read(reader, len);
+
+        PropTweqScaleState {
+            animation_state: state.animation_state,
+            axis_animation_states: state.axis_animation_states,
+            current_scale: None,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqLockConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub curve: TweqCurve,
+    pub halt: TweqHalt,
+
+    /// Joint driven by the tweq
+    pub lock_joint: u32,
+    /// Rotation speed of the joint, in degrees / s, and its limits
+    pub limits: TweqVectorLimits,
+}
+
+impl PropTweqLockConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqLockConfig {
+        let _unk = read_u8(reader);
+        let curve_bits = read_u8(reader);
+        let curve = TweqCurve::from_bits_truncate(curve_bits.into());
+        let animation_config_bits = read_u8(reader);
+        let animation_config =
+            TweqAnimationConfig::from_bits_truncate(animation_config_bits.into());
+        let halt_bits = read_u8(reader);
+        let halt: TweqHalt =
+            num_traits::FromPrimitive::from_u8(halt_bits).unwrap_or(TweqHalt::StopTweq);
+
+        let _misc = read_u16(reader);
+        let _rate = read_u16(reader);
+
+        let rate_low_high = read_vec3(reader);
+        let lock_joint = read_u8(reader) as u32;
+        let _pad = read_bytes(reader, 3);
+
+        const EXPECTED_SIZE: u32 = 24;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        PropTweqLockConfig {
+            animation_config,
+            curve,
+            halt,
+            lock_joint,
+            limits: TweqVectorLimits {
+                rate: rate_low_high.x,
+                low: rate_low_high.y,
+                high: rate_low_high.z,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqLockState {
+    pub animation_state: TweqAnimationState,
+    /// Angle (in degrees) the joint is moving towards
+    pub target_angle: f32,
+    /// Current angle (in degrees) of the joint
+    pub current_angle: f32,
+}
+
+impl PropTweqLockState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqLockState {
+        let animation_state_bits = read_u16(reader);
+        let animation_state = TweqAnimationState::from_bits_truncate(animation_state_bits.into());
+        let _misc = read_u16(reader);
+        let target_angle = read_single(reader);
+        let _cur_joint = read_u8(reader);
+        let _pad = read_bytes(reader, 3);
+
+        const EXPECTED_SIZE: u32 = 12;
+        if len > EXPECTED_SIZE {
+            let remaining = (len - EXPECTED_SIZE) as usize;
+            read_bytes(reader, remaining);
+        }
+
+        PropTweqLockState {
+            animation_state,
+            target_angle,
+            current_angle: 0.0,
         }
     }
 }
@@ -10,7 +10,7 @@ use cgmath::prelude::*;
 use cgmath::{Point3, Vector2, Vector3};
 use collision::{Aabb, Aabb3};
 use engine::{
-    assets::asset_cache::AssetCache,
+    assets::{asset_cache::AssetCache, placeholders},
     scene::{SceneObject, VertexPositionTextureSkinnedNormal},
     texture::{AnimatedTexture, TextureTrait},
 };
@@ -272,6 +272,12 @@ pub struct AIMaterial {
     weight_start: u16,
 }
 
+impl AIMaterial {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 pub fn read_material<T: Read + Seek>(reader: &mut T, version: u32) -> AIMaterial {
     let name = read_string_with_size(reader, 16);
     let mut dw_caps = 0;
@@ -426,7 +432,9 @@ pub fn to_scene_objects(
         let geometry: Rc<Box<dyn engine::scene::Geometry>> =
             Rc::new(Box::new(engine::scene::mesh::create(vertices)));
 
-        let texture = asset_cache.get(&TEXTURE_IMPORTER, &material_name).clone();
+        let texture = asset_cache
+            .get_or_warn(&TEXTURE_IMPORTER, &material_name)
+            .unwrap_or_else(placeholders::texture);
         let diffuse_texture: Rc<dyn TextureTrait> = {
             let mut animation_frames =
                 load_multiple_textures_for_model(asset_cache, &material_name);
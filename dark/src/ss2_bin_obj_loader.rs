@@ -11,7 +11,7 @@ use cgmath::{Matrix4, Vector2, Vector3, Vector4, vec4};
 use cgmath::{Point3, point3, prelude::*, vec3};
 use collision::Aabb3;
 use engine::{
-    assets::asset_cache::AssetCache,
+    assets::{asset_cache::AssetCache, placeholders},
     scene::{SceneObject, VertexPositionTextureNormal, VertexPositionTextureSkinnedNormal},
     texture::{AnimatedTexture, TextureTrait},
 };
@@ -151,13 +151,9 @@ pub fn to_scene_objects(
                 tex_path = "soft12 .pcx".to_owned();
             }
 
-            let maybe_texture = asset_cache.get_opt(&TEXTURE_IMPORTER, &tex_path);
-
-            if maybe_texture.is_none() {
-                return None;
-            }
-
-            let texture = maybe_texture.unwrap();
+            let texture = asset_cache
+                .get_or_warn(&TEXTURE_IMPORTER, &tex_path)
+                .unwrap_or_else(placeholders::texture);
 
             let geometry: Rc<Box<dyn engine::scene::Geometry>> = if is_skinned {
                 Rc::new(Box::new(engine::scene::mesh::create(verts)))
@@ -252,7 +248,6 @@ pub fn to_scene_objects(
 #[derive(Debug, Clone)]
 pub struct SystemShock2MeshMaterial {
     pub name: String,
-    #[allow(dead_code)]
     material_type: u8, // TODO: Add real type
     pub slot_num: u8,
     #[allow(dead_code)]
@@ -269,6 +264,13 @@ pub struct SystemShock2MeshMaterial {
     pub emissivity: f32,
 }
 
+impl SystemShock2MeshMaterial {
+    // Texture-mapped (MD_MAT_TMAP), rather than a flat color - the name is a texture file
+    pub fn is_texture_map(&self) -> bool {
+        self.material_type == 0
+    }
+}
+
 fn read_material<T: Read>(reader: &mut T) -> SystemShock2MeshMaterial {
     let name = ss2_common::read_string_with_size(reader, 16);
     let material_type = ss2_common::read_u8(reader);
@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
     io::{self, SeekFrom},
 };
 
@@ -12,9 +13,8 @@ use std::{
 pub const CHUNK_HEADER_SIZE: u32 = 24;
 
 use byteorder::ReadBytesExt;
-use tracing::info;
-
-use crate::ss2_common::read_string_with_size;
+use serde::Serialize;
+use tracing::{info, warn};
 
 // Chunk describes a chunk of data for the consumer
 #[derive(Debug, Clone)]
@@ -27,6 +27,14 @@ pub struct Chunk {
 pub struct ChunkFileTableOfContents {
     // Table of contents, storing where entries can be found
     table_of_contents: HashMap<String, Chunk>,
+    // Only tracked in strict mode - see `read_table_of_contents_strict`
+    diagnostics: Option<RefCell<ChunkDiagnostics>>,
+}
+
+#[derive(Debug, Default)]
+struct ChunkDiagnostics {
+    located: HashSet<String>,
+    consumed: HashMap<String, u64>,
 }
 
 impl ChunkFileTableOfContents {
@@ -35,21 +43,191 @@ impl ChunkFileTableOfContents {
     }
 
     pub fn get_chunk(&self, chunk_name: String) -> Option<Chunk> {
-        self.table_of_contents.get(&chunk_name).cloned()
+        let chunk = self.table_of_contents.get(&chunk_name).cloned();
+        if let (Some(_), Some(diagnostics)) = (&chunk, &self.diagnostics) {
+            diagnostics.borrow_mut().located.insert(chunk_name);
+        }
+        chunk
     }
 
     pub fn chunk_names(&self) -> impl Iterator<Item = &String> + '_ {
         self.table_of_contents.keys()
     }
+
+    pub fn is_strict(&self) -> bool {
+        self.diagnostics.is_some()
+    }
+
+    ///
+    /// finish_chunk
+    ///
+    /// Called by a reader once it's done with a chunk, with the reader still where parsing stopped -
+    /// in strict mode, records how many bytes of the chunk were consumed
+    pub fn finish_chunk<T: io::Seek>(&self, chunk_name: &str, reader: &mut T) {
+        let Some(diagnostics) = &self.diagnostics else {
+            return;
+        };
+        let Some(chunk) = self.table_of_contents.get(chunk_name) else {
+            return;
+        };
+
+        let position = reader.stream_position().unwrap_or(chunk.offset);
+        let consumed = position.saturating_sub(chunk.offset);
+        if consumed != chunk.length {
+            warn!(
+                "chunk {} - consumed {} of {} bytes",
+                chunk_name, consumed, chunk.length
+            );
+        }
+
+        let mut diagnostics = diagnostics.borrow_mut();
+        diagnostics.located.insert(chunk_name.to_owned());
+        // Some chunks are read more than once - keep the furthest read
+        let entry = diagnostics
+            .consumed
+            .entry(chunk_name.to_owned())
+            .or_insert(0);
+        *entry = (*entry).max(consumed);
+    }
+
+    ///
+    /// report
+    ///
+    /// Every chunk in the file, and how far our parsers got with it - None unless in strict mode
+    pub fn report(&self) -> Option<ChunkReport> {
+        let diagnostics = self.diagnostics.as_ref()?.borrow();
+
+        let mut chunks = self
+            .table_of_contents
+            .iter()
+            .map(|(name, chunk)| {
+                let consumed_bytes = diagnostics.consumed.get(name).copied();
+                let status = match consumed_bytes {
+                    Some(_) => ChunkStatus::Parsed,
+                    None if diagnostics.located.contains(name) => ChunkStatus::Located,
+                    None => ChunkStatus::Unread,
+                };
+                ChunkRecord {
+                    name: name.clone(),
+                    declared_bytes: chunk.length,
+                    consumed_bytes,
+                    status,
+                }
+            })
+            .collect::<Vec<_>>();
+        chunks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let unknown_chunks = chunks
+            .iter()
+            .filter(|chunk| chunk.status != ChunkStatus::Parsed)
+            .map(|chunk| chunk.name.clone())
+            .collect();
+
+        Some(ChunkReport {
+            chunks,
+            unknown_chunks,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkStatus {
+    /// Read by a parser, which reported how much of it was consumed
+    Parsed,
+    /// Looked up, but not parsed - ie, skipped over, or only listed as unparsed
+    Located,
+    /// Never touched
+    Unread,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkRecord {
+    pub name: String,
+    pub declared_bytes: u64,
+    pub consumed_bytes: Option<u64>,
+    pub status: ChunkStatus,
+}
+
+impl ChunkRecord {
+    /// Whether a parsed chunk was read to exactly its declared length
+    pub fn is_fully_consumed(&self) -> bool {
+        self.consumed_bytes == Some(self.declared_bytes)
+    }
+}
+
+///
+/// ChunkReport
+///
+/// Parser coverage for a chunk file, from strict mode - every chunk in the table of contents with its
+/// declared size and how many bytes were consumed, plus the inventory of chunks nothing parsed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChunkReport {
+    pub chunks: Vec<ChunkRecord>,
+    pub unknown_chunks: Vec<String>,
+}
+
+impl ChunkReport {
+    /// Parsed chunks that were read short of, or past, their declared length
+    pub fn mismatched(&self) -> impl Iterator<Item = &ChunkRecord> {
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.status == ChunkStatus::Parsed && !chunk.is_fully_consumed())
+    }
+
+    /// Fraction of the file's chunk bytes that were parsed
+    pub fn coverage(&self) -> f32 {
+        let declared: u64 = self.chunks.iter().map(|chunk| chunk.declared_bytes).sum();
+        if declared == 0 {
+            return 1.0;
+        }
+
+        let parsed: u64 = self
+            .chunks
+            .iter()
+            .filter_map(|chunk| chunk.consumed_bytes.map(|c| c.min(chunk.declared_bytes)))
+            .sum();
+        parsed as f32 / declared as f32
+    }
+
+    /// No parsed chunk was misread
+    pub fn is_clean(&self) -> bool {
+        self.mismatched().next().is_none()
+    }
+}
+
+///
+/// read_table_of_contents_strict
+///
+/// Like `read_table_of_contents`, but tracks which chunks are read and how much of each is consumed,
+/// for a `ChunkReport`
+pub fn read_table_of_contents_strict<T: io::Read + io::Seek>(
+    reader: &mut T,
+) -> ChunkFileTableOfContents {
+    ChunkFileTableOfContents {
+        diagnostics: Some(RefCell::new(ChunkDiagnostics::default())),
+        ..read_table_of_contents(reader)
+    }
 }
 
 pub fn read_table_of_contents<T: io::Read + io::Seek>(reader: &mut T) -> ChunkFileTableOfContents {
-    let inv_offset = reader.read_u32::<byteorder::LittleEndian>().unwrap();
-    let zero = reader.read_u32::<byteorder::LittleEndian>().unwrap();
-    let one = reader.read_u32::<byteorder::LittleEndian>().unwrap();
+    try_read_table_of_contents(reader).unwrap()
+}
+
+///
+/// try_read_table_of_contents
+///
+/// Like `read_table_of_contents`, but returns an error for a truncated (or not a chunk) file,
+/// instead of panicking
+pub fn try_read_table_of_contents<T: io::Read + io::Seek>(
+    reader: &mut T,
+) -> io::Result<ChunkFileTableOfContents> {
+    let inv_offset = reader.read_u32::<byteorder::LittleEndian>()?;
+    let zero = reader.read_u32::<byteorder::LittleEndian>()?;
+    let one = reader.read_u32::<byteorder::LittleEndian>()?;
     let mut buf: [u8; 256] = [0; 256];
-    reader.read_exact(&mut buf).unwrap();
-    let dead_beef = reader.read_u32::<byteorder::LittleEndian>().unwrap();
+    reader.read_exact(&mut buf)?;
+    let dead_beef = reader.read_u32::<byteorder::LittleEndian>()?;
 
     info!(
         "reading chunk table of contents - inv_offset: {} zero: {}, one: {}, zeros: {}, dead_beef: {}, debug: {}",
@@ -61,28 +239,115 @@ pub fn read_table_of_contents<T: io::Read + io::Seek>(reader: &mut T) -> ChunkFi
         0x0EFBEADDE_u32
     );
 
-    reader.seek(SeekFrom::Start(inv_offset as u64)).unwrap();
+    reader.seek(SeekFrom::Start(inv_offset as u64))?;
 
-    let chunk_count = reader.read_u32::<byteorder::LittleEndian>().unwrap();
+    let chunk_count = reader.read_u32::<byteorder::LittleEndian>()?;
 
     let mut dictionary = HashMap::new();
     for _ in 0..chunk_count {
-        let chunk_name = read_string_with_size(reader, 12);
+        let mut name_bytes = [0; 12];
+        reader.read_exact(&mut name_bytes)?;
+        let name_end = name_bytes
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(name_bytes.len());
+        let chunk_name = String::from_utf8_lossy(&name_bytes[..name_end]).into_owned();
 
-        let offset = reader.read_u32::<byteorder::LittleEndian>().unwrap();
-        let length = reader.read_u32::<byteorder::LittleEndian>().unwrap();
+        let offset = reader.read_u32::<byteorder::LittleEndian>()?;
+        let length = reader.read_u32::<byteorder::LittleEndian>()?;
 
         dictionary.insert(
             chunk_name,
             Chunk {
                 // Always skip the header
-                offset: (offset + CHUNK_HEADER_SIZE) as u64,
+                offset: offset as u64 + CHUNK_HEADER_SIZE as u64,
                 length: length as u64,
             },
         );
     }
 
-    ChunkFileTableOfContents {
+    Ok(ChunkFileTableOfContents {
         table_of_contents: dictionary,
+        diagnostics: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_of_contents(chunks: &[(&str, u64, u64)]) -> ChunkFileTableOfContents {
+        ChunkFileTableOfContents {
+            table_of_contents: chunks
+                .iter()
+                .map(|(name, offset, length)| {
+                    (
+                        name.to_string(),
+                        Chunk {
+                            offset: *offset,
+                            length: *length,
+                        },
+                    )
+                })
+                .collect(),
+            diagnostics: Some(RefCell::new(ChunkDiagnostics::default())),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let toc = table_of_contents(&[("P$Full", 0, 8), ("P$Short", 8, 8), ("P$Skip", 16, 4)]);
+        let mut reader = io::Cursor::new(vec![0u8; 32]);
+
+        toc.get_chunk("P$Full".to_owned());
+        reader.seek(SeekFrom::Start(8)).unwrap();
+        toc.finish_chunk("P$Full", &mut reader);
+
+        toc.get_chunk("P$Short".to_owned());
+        reader.seek(SeekFrom::Start(12)).unwrap();
+        toc.finish_chunk("P$Short", &mut reader);
+
+        let report = toc.report().unwrap();
+        let statuses = report
+            .chunks
+            .iter()
+            .map(|chunk| (chunk.name.as_str(), chunk.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                ("P$Full", ChunkStatus::Parsed),
+                ("P$Short", ChunkStatus::Parsed),
+                ("P$Skip", ChunkStatus::Unread),
+            ]
+        );
+        assert_eq!(report.unknown_chunks, vec!["P$Skip".to_owned()]);
+        assert_eq!(
+            report
+                .mismatched()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["P$Short"]
+        );
+        assert!((report.coverage() - 0.6).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_no_report_unless_strict() {
+        let toc = ChunkFileTableOfContents {
+            table_of_contents: HashMap::new(),
+            diagnostics: None,
+        };
+        assert!(toc.report().is_none());
+    }
+
+    #[test]
+    fn test_truncated_table_of_contents_is_an_error() {
+        // A header pointing at a table of contents past the end of the file
+        let mut bytes = 4096u32.to_le_bytes().to_vec();
+        bytes.extend([0; 268]);
+
+        assert!(try_read_table_of_contents(&mut io::Cursor::new(bytes)).is_err());
+        assert!(try_read_table_of_contents(&mut io::Cursor::new(vec![0; 10])).is_err());
     }
 }
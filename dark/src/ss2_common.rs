@@ -1,4 +1,4 @@
-use byteorder::ReadBytesExt;
+use byteorder::{ReadBytesExt, WriteBytesExt};
 use cgmath::Decomposed;
 use cgmath::Deg;
 use cgmath::InnerSpace;
@@ -220,3 +220,39 @@ pub fn read_matrix<T: io::Read>(reader: &mut T) -> Decomposed<Vector3<f32>, Quat
         }
     }
 }
+
+// Writers - the inverse of the readers above, for data we serialize back out in the original format
+
+pub fn write_u32<T: io::Write>(writer: &mut T, value: u32) {
+    writer.write_u32::<byteorder::LittleEndian>(value).unwrap();
+}
+
+pub fn write_i32<T: io::Write>(writer: &mut T, value: i32) {
+    writer.write_i32::<byteorder::LittleEndian>(value).unwrap();
+}
+
+pub fn write_single<T: io::Write>(writer: &mut T, value: f32) {
+    writer.write_f32::<byteorder::LittleEndian>(value).unwrap();
+}
+
+pub fn write_bool<T: io::Write>(writer: &mut T, value: bool) {
+    write_u32(writer, value as u32);
+}
+
+pub fn write_vec3<T: io::Write>(writer: &mut T, value: Vector3<f32>) {
+    write_single(writer, -value.x);
+    write_single(writer, value.z);
+    write_single(writer, value.y);
+}
+
+pub fn write_string_with_size<T: io::Write>(writer: &mut T, value: &str, size: usize) {
+    let mut c_str = vec![0; size];
+    // A value that fills the field has no terminator, as in the game's own files
+    let len = value.len().min(size);
+    c_str[..len].copy_from_slice(&value.as_bytes()[..len]);
+    writer.write_all(&c_str).unwrap();
+}
+
+pub fn write_bytes<T: io::Write>(writer: &mut T, bytes: &[u8]) {
+    writer.write_all(bytes).unwrap();
+}
@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
     io::{self, SeekFrom},
-    rc::Rc,
+    sync::Arc,
 };
 
 use shipyard::{EntityId, World};
@@ -32,7 +32,7 @@ pub struct UnparsedLinkData {
 
 #[derive(Debug)]
 pub struct SystemShock2EntityInfo {
-    pub entity_to_properties: HashMap<i32, Vec<Rc<Box<dyn Property>>>>,
+    pub entity_to_properties: HashMap<i32, Vec<Arc<Box<dyn Property>>>>,
     pub template_to_links: HashMap<i32, TemplateLinks>,
     pub unparsed_properties: HashMap<String, Vec<UnparsedProperty>>,
     pub unparsed_links: HashMap<String, Vec<Link>>,
@@ -304,7 +304,7 @@ pub fn new<R: io::Read + io::Seek>(
             let entries = read_unparsed_property_chunk(toc, &chunk_name, reader);
             unparsed_properties.insert(chunk_name, entries);
         } else if chunk_name.starts_with("L$") && !known_link_chunks.contains(chunk_name.as_str()) {
-            let links_for_chunk = read_link(&chunk_name, reader, toc);
+            let links_for_chunk = read_link_entries(&chunk_name, reader, toc);
             unparsed_links.insert(chunk_name, links_for_chunk);
         } else if chunk_name.starts_with("LD$")
             && !known_link_data_chunks.contains(chunk_name.as_str())
@@ -338,6 +338,17 @@ pub fn read_link<T: io::Read + io::Seek>(
     link_chunk_name: &str,
     reader: &mut T,
     toc: &ChunkFileTableOfContents,
+) -> Vec<Link> {
+    let links = read_link_entries(link_chunk_name, reader, toc);
+    toc.finish_chunk(link_chunk_name, reader);
+    links
+}
+
+// Reads the links in a chunk, without marking it as parsed - unparsed links are only listed
+fn read_link_entries<T: io::Read + io::Seek>(
+    link_chunk_name: &str,
+    reader: &mut T,
+    toc: &ChunkFileTableOfContents,
 ) -> Vec<Link> {
     let mut ret = vec![];
 
@@ -430,6 +441,7 @@ pub fn read_link_data<T: io::Read + io::Seek>(
                 let bytes = read_bytes(reader, data_len as usize);
                 data.insert(id, bytes);
             }
+            toc.finish_chunk(link_data_chunk_name, reader);
             // panic!("link data size: {}", link_data_size);
         }
     }
@@ -474,7 +486,7 @@ fn read_all_properties<R: io::Read + io::Seek>(
     toc: &ChunkFileTableOfContents,
     properties: &Vec<Box<dyn PropertyDefinition<R>>>,
     ref_reader: &mut R,
-) -> HashMap<i32, Vec<Rc<Box<dyn Property>>>> {
+) -> HashMap<i32, Vec<Arc<Box<dyn Property>>>> {
     let mut ent_to_props = HashMap::new();
     for prop in properties {
         let name = prop.name();
@@ -507,10 +519,11 @@ fn read_all_properties<R: io::Read + io::Seek>(
                     ref_reader.stream_position().unwrap()
                 );
 
-                props.push(Rc::new(prop));
+                props.push(Arc::new(prop));
 
                 ref_reader.seek(SeekFrom::Start(expected_pos)).unwrap();
             }
+            toc.finish_chunk(&name, ref_reader);
         }
     }
     ent_to_props
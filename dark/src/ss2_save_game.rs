@@ -0,0 +1,314 @@
+// Original System Shock 2 savegames
+//
+// A save (ie, `save_0/current.sav`) is a tag file, like a .mis - the level's objects are stored in
+// the same property, link and OBJ_MAP chunks, alongside the quest database. We only pull out what
+// carries across to shock2quest: quest variables, and what the player was carrying.
+
+use std::{
+    collections::HashMap,
+    io::{self, Cursor, SeekFrom},
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+use tracing::{info, warn};
+
+use crate::{
+    Gamesys,
+    ss2_chunk_file_reader::{self, ChunkFileTableOfContents},
+    ss2_entity_info::{self, Link, calculate_hierarchy},
+};
+
+// Quest variable names are short (ie, "note_earth_1") - anything longer means we're misreading
+const MAX_QUEST_NAME_LENGTH: u32 = 256;
+
+// Size of a quest record, besides the name: length, value and type
+const QUEST_RECORD_SIZE: u64 = 12;
+
+// Size of a link record: id, source, destination (i32s) and flavor (u16)
+const LINK_RECORD_SIZE: usize = 14;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestVariable {
+    pub name: String,
+    pub value: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SavedGame {
+    /// Campaign-wide variables (QUEST_CMP), followed by the level's (QUEST_DB)
+    pub quest_variables: Vec<QuestVariable>,
+    /// Archetype names of the items the player was carrying
+    pub inventory: Vec<String>,
+}
+
+///
+/// read
+///
+/// Read a savegame - the gamesys is needed to follow object archetypes up to the player. Returns
+/// an error if the file is truncated, or isn't a savegame.
+pub fn read<T: io::Read + io::Seek>(reader: &mut T, gamesys: &Gamesys) -> io::Result<SavedGame> {
+    let toc = ss2_chunk_file_reader::try_read_table_of_contents(reader)?;
+    let file_length = reader.seek(SeekFrom::End(0))?;
+
+    let mut quest_variables = read_quest_chunk(&toc, "QUEST_CMP", reader, file_length)?;
+    quest_variables.extend(read_quest_chunk(&toc, "QUEST_DB", reader, file_length)?);
+
+    let inventory = read_inventory(&toc, reader, file_length, gamesys)?;
+
+    info!(
+        "read savegame: {} quest variables, {} inventory items",
+        quest_variables.len(),
+        inventory.len()
+    );
+
+    Ok(SavedGame {
+        quest_variables,
+        inventory,
+    })
+}
+
+///
+/// read_chunk
+///
+/// The contents of a chunk - None if the save doesn't have it, and an error if the table of
+/// contents points past the end of the file
+fn read_chunk<T: io::Read + io::Seek>(
+    toc: &ChunkFileTableOfContents,
+    chunk_name: &str,
+    reader: &mut T,
+    file_length: u64,
+) -> io::Result<Option<Vec<u8>>> {
+    let Some(chunk) = toc.get_chunk(chunk_name.to_owned()) else {
+        return Ok(None);
+    };
+    if chunk.offset.saturating_add(chunk.length) > file_length {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("chunk {} runs past the end of the file", chunk_name),
+        ));
+    }
+
+    reader.seek(SeekFrom::Start(chunk.offset))?;
+    let mut bytes = vec![0; chunk.length as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+fn read_quest_chunk<T: io::Read + io::Seek>(
+    toc: &ChunkFileTableOfContents,
+    chunk_name: &str,
+    reader: &mut T,
+    file_length: u64,
+) -> io::Result<Vec<QuestVariable>> {
+    match read_chunk(toc, chunk_name, reader, file_length)? {
+        Some(bytes) => {
+            let end = bytes.len() as u64;
+            read_quest_variables(&mut Cursor::new(bytes), end)
+        }
+        None => {
+            warn!("savegame has no {} chunk", chunk_name);
+            Ok(Vec::new())
+        }
+    }
+}
+
+///
+/// read_quest_variables
+///
+/// Read quest records up to `end` - each is a u32 name length, the name, the value (i32), and a
+/// u32 type we don't use
+fn read_quest_variables<T: io::Read + io::Seek>(
+    reader: &mut T,
+    end: u64,
+) -> io::Result<Vec<QuestVariable>> {
+    let mut variables = Vec::new();
+    while reader.stream_position()? + QUEST_RECORD_SIZE <= end {
+        let name_length = reader.read_u32::<LittleEndian>()?;
+        let remaining = end - reader.stream_position()?;
+        if name_length == 0
+            || name_length > MAX_QUEST_NAME_LENGTH
+            || name_length as u64 + QUEST_RECORD_SIZE - 4 > remaining
+        {
+            warn!(
+                "unexpected quest variable name length {} - skipping the rest of the chunk",
+                name_length
+            );
+            break;
+        }
+
+        let mut name_bytes = vec![0; name_length as usize];
+        reader.read_exact(&mut name_bytes)?;
+        let name = c_string(&name_bytes);
+
+        let value = reader.read_i32::<LittleEndian>()?;
+        let _type = reader.read_u32::<LittleEndian>()?;
+
+        variables.push(QuestVariable { name, value });
+    }
+    Ok(variables)
+}
+
+///
+/// read_inventory
+///
+/// The archetypes of the objects the player contains
+fn read_inventory<T: io::Read + io::Seek>(
+    toc: &ChunkFileTableOfContents,
+    reader: &mut T,
+    file_length: u64,
+    gamesys: &Gamesys,
+) -> io::Result<Vec<String>> {
+    let Some(obj_map_bytes) = read_chunk(toc, "OBJ_MAP", reader, file_length)? else {
+        warn!("savegame has no OBJ_MAP chunk - unable to read inventory");
+        return Ok(Vec::new());
+    };
+    let obj_map = read_obj_names(&obj_map_bytes)?;
+
+    // Concrete objects link to their archetypes in the save, and archetypes to their parents in
+    // the gamesys
+    let metaprops = read_links(toc, "L$MetaProp", reader, file_length)?;
+    let mut hierarchy = ss2_entity_info::get_hierarchy(gamesys.entity_info()).clone();
+    hierarchy.extend(calculate_hierarchy(&metaprops));
+
+    let contains = read_links(toc, "L$Contains", reader, file_length)?;
+
+    let is_player = |id: i32| {
+        id > 0
+            && std::iter::once(id)
+                .chain(ss2_entity_info::get_ancestors(&hierarchy, &id))
+                .filter_map(|id| obj_map.get(&id))
+                .any(|name| name.eq_ignore_ascii_case("player"))
+    };
+
+    let Some(player) = contains
+        .iter()
+        .map(|link| link.src)
+        .find(|src| is_player(*src))
+    else {
+        warn!("unable to find the player in the savegame");
+        return Ok(Vec::new());
+    };
+
+    Ok(contains
+        .iter()
+        .filter(|link| link.src == player)
+        .filter_map(|link| archetype_name(link.dest, &hierarchy, &obj_map))
+        .collect())
+}
+
+/// The OBJ_MAP chunk - object ids to names, each a u32-length prefixed string
+fn read_obj_names(bytes: &[u8]) -> io::Result<HashMap<i32, String>> {
+    let mut reader = Cursor::new(bytes);
+    let mut obj_map = HashMap::new();
+    while (reader.position() as usize) < bytes.len() {
+        let obj_id = reader.read_i32::<LittleEndian>()?;
+        let size = reader.read_u32::<LittleEndian>()? as usize;
+        let start = reader.position() as usize;
+        let Some(name_bytes) = bytes.get(start..start.saturating_add(size)) else {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "object name runs past the end of OBJ_MAP",
+            ));
+        };
+        reader.set_position((start + size) as u64);
+        obj_map.insert(obj_id, c_string(name_bytes));
+    }
+    Ok(obj_map)
+}
+
+/// The links in a link chunk - empty if the save doesn't have it
+fn read_links<T: io::Read + io::Seek>(
+    toc: &ChunkFileTableOfContents,
+    chunk_name: &str,
+    reader: &mut T,
+    file_length: u64,
+) -> io::Result<Vec<Link>> {
+    let Some(bytes) = read_chunk(toc, chunk_name, reader, file_length)? else {
+        return Ok(Vec::new());
+    };
+
+    bytes
+        .chunks_exact(LINK_RECORD_SIZE)
+        .map(|record| {
+            let mut record = Cursor::new(record);
+            Ok(Link {
+                id: record.read_i32::<LittleEndian>()?,
+                src: record.read_i32::<LittleEndian>()?,
+                dest: record.read_i32::<LittleEndian>()?,
+                flavor: record.read_u16::<LittleEndian>()?,
+                name: chunk_name.to_owned(),
+            })
+        })
+        .collect()
+}
+
+// A NUL-terminated string, which may fill its buffer
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// The most specific name for an object - its own, if it has one, otherwise its nearest archetype's
+fn archetype_name(
+    id: i32,
+    hierarchy: &HashMap<i32, Vec<i32>>,
+    obj_map: &HashMap<i32, String>,
+) -> Option<String> {
+    if let Some(name) = obj_map.get(&id) {
+        return Some(name.clone());
+    }
+
+    ss2_entity_info::get_ancestors(hierarchy, &id)
+        .iter()
+        .rev()
+        .find_map(|ancestor| obj_map.get(ancestor))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quest_record(name: &str, value: i32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend((name.len() as u32 + 1).to_le_bytes());
+        bytes.extend(name.as_bytes());
+        bytes.push(0);
+        bytes.extend(value.to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_read_quest_variables() {
+        let mut bytes = quest_record("note_earth_1", 1);
+        bytes.extend(quest_record("Hack_Med1", 2));
+        let end = bytes.len() as u64;
+
+        let variables = read_quest_variables(&mut Cursor::new(bytes), end).unwrap();
+        assert_eq!(
+            variables,
+            vec![
+                QuestVariable {
+                    name: "note_earth_1".to_owned(),
+                    value: 1
+                },
+                QuestVariable {
+                    name: "Hack_Med1".to_owned(),
+                    value: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_quest_variables_stops_on_bad_length() {
+        let mut bytes = quest_record("note_earth_1", 1);
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend([0; 8]);
+        let end = bytes.len() as u64;
+
+        let variables = read_quest_variables(&mut Cursor::new(bytes), end).unwrap();
+        assert_eq!(variables.len(), 1);
+    }
+}
@@ -210,7 +210,7 @@ impl Skeleton {
             Box::new(cube::create()),
         );
 
-        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon");
+        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
 
         for bone in &self.bones {
             let joint_idx = bone.joint_id as usize;
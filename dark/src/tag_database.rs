@@ -81,6 +81,18 @@ impl TagDatabase {
         TagDatabase { data, branches }
     }
 
+    /// Values stored at this node
+    pub fn data(&self) -> &[TagDatabaseData] {
+        &self.data
+    }
+
+    /// Child nodes, by the key that leads to them
+    pub fn branches(&self) -> impl Iterator<Item = (&TagDatabaseKey, &TagDatabase)> {
+        self.branches
+            .iter()
+            .map(|(key, branch)| (key, branch.as_ref()))
+    }
+
     /// Collect all tag keys used within this tag database (recursively)
     pub fn collect_all_keys(&self) -> Vec<TagDatabaseKey> {
         let mut keys = Vec::new();
@@ -0,0 +1,23 @@
+layout (location = 0) in vec3 inPos;
+layout (location = 1) in vec2 inTex;
+layout (location = 2) in vec3 inNormal;
+
+uniform mat4 world;
+uniform mat4 view;
+uniform mat4 projection;
+
+out vec2 texCoord;
+out vec3 worldPos;
+out vec3 worldNormal;
+
+void main() {
+    texCoord = inTex;
+    vec4 worldPosition = world * vec4(inPos, 1.0);
+    worldPos = worldPosition.xyz;
+
+    // Transform normal to world space
+    mat3 normalMatrix = transpose(inverse(mat3(world)));
+    worldNormal = normalize(normalMatrix * inNormal);
+
+    gl_Position = projection * view * worldPosition;
+}
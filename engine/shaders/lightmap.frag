@@ -0,0 +1,39 @@
+out vec4 fragColor;
+
+in vec2 texCoord;
+in highp vec2 lightMapTexCoord;
+in highp vec4 atlasCoord;
+in vec3 worldPos;
+in vec3 worldNormal;
+
+// Material properties
+uniform sampler2D texture1; // lightmap
+uniform sampler2D texture2; // diffuse texture
+
+#include "spotlights.glsl"
+
+void main() {
+    // Sample lightmap and diffuse texture with proper UV wrapping
+    float half_pixel = 0.5 / 4096.0;
+    float full_pixel = half_pixel * 2.0;
+    vec2 wrappedTexCoord = vec2(0.0, 0.0);
+    float width = atlasCoord.z - full_pixel;
+    float height = atlasCoord.w - full_pixel;
+
+    wrappedTexCoord.x = mod(lightMapTexCoord.x * width, width) + atlasCoord.x + half_pixel;
+    wrappedTexCoord.y = mod(lightMapTexCoord.y * height, height) + atlasCoord.y + half_pixel;
+
+    vec4 lightmapColor = texture(texture1, wrappedTexCoord);
+    vec4 diffuseColor = texture(texture2, texCoord);
+
+    // Base lighting from lightmap (baked static lighting)
+    vec3 finalColor = diffuseColor.rgb * lightmapColor.rgb;
+
+    // Add dynamic spotlight contributions on top of baked lighting
+    vec3 normal = normalize(worldNormal);
+    for (int i = 0; i < 6; i++) {
+        finalColor += calculateSpotlight(i, worldPos, normal, diffuseColor.rgb);
+    }
+
+    fragColor = vec4(finalColor, 1.0);
+}
@@ -0,0 +1,27 @@
+layout (location = 0) in vec3 inPos;
+layout (location = 1) in vec2 inTex;
+layout (location = 2) in vec2 inLightMapTex;
+layout (location = 3) in vec4 inAtlas;
+layout (location = 4) in vec3 inNormal;
+
+uniform mat4 world;
+uniform mat4 view;
+uniform mat4 projection;
+
+out vec2 texCoord;
+out highp vec2 lightMapTexCoord;
+out highp vec4 atlasCoord;
+out vec3 worldPos;
+out vec3 worldNormal;
+
+void main() {
+    texCoord = inTex;
+    lightMapTexCoord = inLightMapTex;
+    atlasCoord = inAtlas;
+
+    vec4 worldPosition = world * vec4(inPos, 1.0);
+    worldPos = worldPosition.xyz;
+    worldNormal = normalize(mat3(world) * inNormal);
+
+    gl_Position = projection * view * worldPosition;
+}
@@ -0,0 +1,31 @@
+out vec4 fragColor;
+
+in vec2 texCoord;
+in vec3 worldPos;
+in vec3 worldNormal;
+
+// Material properties
+uniform sampler2D texture1;
+uniform float emissivity;
+uniform float transparency;
+
+#include "spotlights.glsl"
+
+void main() {
+    vec4 texColor = texture(texture1, texCoord);
+    if (texColor.a < 0.1) discard;
+
+    // Base material color (ambient)
+    vec3 finalColor = texColor.rgb * 0.5;
+
+    // Add emissive contribution
+    finalColor += texColor.rgb * emissivity;
+
+    // Calculate contribution from all 6 spotlights
+    vec3 normal = normalize(worldNormal);
+    for (int i = 0; i < 6; i++) {
+        finalColor += calculateSpotlight(i, worldPos, normal, texColor.rgb);
+    }
+
+    fragColor = vec4(finalColor, texColor.a * (1.0 - transparency));
+}
@@ -0,0 +1,30 @@
+layout (location = 0) in vec3 inPos;
+layout (location = 1) in vec2 inTex;
+layout (location = 2) in ivec4 bone_ids;
+layout (location = 3) in vec4 bone_weights;
+layout (location = 4) in vec3 inNormal;
+
+uniform mat4 world;
+uniform mat4 view;
+uniform mat4 projection;
+
+#include "skinning.glsl"
+
+out vec2 texCoord;
+out vec3 worldPos;
+out vec3 worldNormal;
+
+void main() {
+    texCoord = inTex;
+
+    vec4 mod_position;
+    vec3 mod_normal;
+    skinVertex(inPos, inNormal, bone_ids, bone_weights, mod_position, mod_normal);
+
+    // Transform to world space
+    vec4 worldPosition = world * mod_position;
+    worldPos = worldPosition.xyz;
+    worldNormal = normalize(mat3(world) * mod_normal);
+
+    gl_Position = projection * view * worldPosition;
+}
@@ -0,0 +1,30 @@
+// Skeletal animation, shared by the skinned materials - blends up to 4 bones per vertex
+uniform mat4 bone_matrices[40];
+
+void skinVertex(vec3 position, vec3 normal, ivec4 boneIds, vec4 boneWeights,
+                out vec4 skinnedPosition, out vec3 skinnedNormal) {
+    vec4 blendedPos = vec4(0.0);
+    vec3 blendedNormal = vec3(0.0);
+
+    // Blend up to 4 bones based on weights
+    if (boneWeights.x > 0.0) {
+        blendedPos += boneWeights.x * (bone_matrices[boneIds.x] * vec4(position, 1.0));
+        blendedNormal += boneWeights.x * (mat3(bone_matrices[boneIds.x]) * normal);
+    }
+    if (boneWeights.y > 0.0) {
+        blendedPos += boneWeights.y * (bone_matrices[boneIds.y] * vec4(position, 1.0));
+        blendedNormal += boneWeights.y * (mat3(bone_matrices[boneIds.y]) * normal);
+    }
+    if (boneWeights.z > 0.0) {
+        blendedPos += boneWeights.z * (bone_matrices[boneIds.z] * vec4(position, 1.0));
+        blendedNormal += boneWeights.z * (mat3(bone_matrices[boneIds.z]) * normal);
+    }
+    if (boneWeights.w > 0.0) {
+        blendedPos += boneWeights.w * (bone_matrices[boneIds.w] * vec4(position, 1.0));
+        blendedNormal += boneWeights.w * (mat3(bone_matrices[boneIds.w]) * normal);
+    }
+
+    // Fallback to original position if no valid bones
+    skinnedPosition = (blendedPos.w > 0.0) ? blendedPos : vec4(position, 1.0);
+    skinnedNormal = (length(blendedNormal) > 0.0) ? normalize(blendedNormal) : normal;
+}
@@ -0,0 +1,49 @@
+// Up to 6 dynamic spotlights - set by the lit materials from the scene's LightArray
+uniform vec3 spotlightPos[6];
+uniform vec4 spotlightColorIntensity[6];  // RGB + intensity
+uniform vec3 spotlightDirection[6];
+uniform float spotlightInnerAngle[6];
+uniform float spotlightOuterAngle[6];
+uniform float spotlightRange[6];
+
+// Calculate spotlight contribution
+vec3 calculateSpotlight(int i, vec3 worldPos, vec3 normal, vec3 texColor) {
+    // Skip if light has zero intensity
+    if (spotlightColorIntensity[i].w <= 0.0) {
+        return vec3(0.0);
+    }
+
+    vec3 lightVec = spotlightPos[i] - worldPos;
+    float distance = length(lightVec);
+
+    // Range check
+    if (distance > spotlightRange[i]) {
+        return vec3(0.0);
+    }
+
+    vec3 lightDir = normalize(lightVec);
+
+    // Cone attenuation for spotlight
+    float cosOuterCone = cos(spotlightOuterAngle[i]);
+    float cosInnerCone = cos(spotlightInnerAngle[i]);
+    float spotFactor = dot(-lightDir, normalize(spotlightDirection[i]));
+
+    if (spotFactor < cosOuterCone) {
+        return vec3(0.0);
+    }
+
+    float coneAttenuation = 1.0;
+    if (spotFactor < cosInnerCone) {
+        coneAttenuation = (spotFactor - cosOuterCone) / (cosInnerCone - cosOuterCone);
+    }
+
+    // Distance attenuation
+    float distanceAttenuation = 1.0 / (1.0 + 0.1 * distance + 0.01 * distance * distance);
+
+    // Diffuse lighting
+    float lambertian = max(dot(normal, lightDir), 0.0);
+
+    // Combine all factors
+    return texColor * spotlightColorIntensity[i].rgb * spotlightColorIntensity[i].w
+           * lambertian * coneAttenuation * distanceAttenuation;
+}
@@ -1,12 +1,32 @@
+use once_cell::sync::Lazy;
 use std::{
     any::{Any, TypeId},
     collections::{HashMap, hash_map::DefaultHasher},
     hash::{Hash, Hasher},
     rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
 };
-use tracing::{self, debug, info};
+use tracing::{self, debug, info, warn};
 
-use super::{asset_importer::AssetImporter, asset_paths::AbstractAssetPath};
+use super::{
+    asset_importer::AssetImporter,
+    asset_paths::{AbstractAssetPath, ReadableAndSeekable},
+    missing_assets,
+};
+use crate::EngineError;
+
+// In strict mode, assets the game could carry on without are treated as fatal, so CI runs catch
+// missing content - set with `set_strict`, or the STRICT_ASSETS environment variable
+static STRICT: Lazy<AtomicBool> =
+    Lazy::new(|| AtomicBool::new(std::env::var_os("STRICT_ASSETS").is_some()));
+
+pub fn set_strict(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+pub fn is_strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
 
 type ImporterAssetMap = HashMap<TypeId, HashMap<u64, HashMap<String, Option<Rc<dyn Any>>>>>;
 
@@ -45,6 +65,56 @@ impl AssetCache {
         Some(asset.downcast::<TOutput>().unwrap())
     }
 
+    ///
+    /// is_cached
+    ///
+    /// Whether the asset has been requested already - including assets that turned out to be
+    /// missing
+    pub fn is_cached<TData: 'static, TOutput: 'static, TConfig: 'static + Hash + Default>(
+        &self,
+        importer: &AssetImporter<TData, TOutput, TConfig>,
+        asset_name: &str,
+    ) -> bool {
+        let config_hash = default_config_hash::<TConfig>();
+        self.importer_to_assets
+            .get(&importer.type_id())
+            .and_then(|config_map| config_map.get(&config_hash))
+            .map(|inner_map| inner_map.contains_key(&asset_name.to_ascii_lowercase()))
+            .unwrap_or(false)
+    }
+
+    ///
+    /// open_reader
+    ///
+    /// Open the file for an asset, without importing it. The reader is Send, so the raw asset can
+    /// be read off the main thread, and the processed result handed back with `insert`.
+    pub fn open_reader(&self, asset_name: &str) -> Option<Box<dyn ReadableAndSeekable>> {
+        self.path
+            .get_reader(self.base_path.clone(), asset_name.to_ascii_lowercase())
+            .map(|reader| reader.into_inner())
+    }
+
+    ///
+    /// insert
+    ///
+    /// Add an asset that was imported outside of the cache, with the importer's default config
+    pub fn insert<TData: 'static, TOutput: 'static, TConfig: 'static + Hash + Default>(
+        &mut self,
+        importer: &AssetImporter<TData, TOutput, TConfig>,
+        asset_name: &str,
+        asset: TOutput,
+    ) -> Rc<TOutput> {
+        let asset = Rc::new(asset);
+        let config_hash = default_config_hash::<TConfig>();
+        self.importer_to_assets
+            .entry(importer.type_id())
+            .or_default()
+            .entry(config_hash)
+            .or_default()
+            .insert(asset_name.to_ascii_lowercase(), Some(asset.clone()));
+        asset
+    }
+
     pub fn get_opt<TData: 'static, TOutput: 'static, TConfig: 'static + Hash + Default>(
         &mut self,
         importer: &AssetImporter<TData, TOutput, TConfig>,
@@ -68,9 +138,7 @@ impl AssetCache {
 
         let asset_name = asset_name.to_ascii_lowercase();
 
-        let mut hasher = DefaultHasher::new();
-        config.hash(&mut hasher);
-        let config_hash = hasher.finish();
+        let config_hash = config_hash(config);
 
         let try_preload = self.load_from_cache(importer, &asset_name, config_hash);
 
@@ -131,15 +199,108 @@ impl AssetCache {
         importer: &AssetImporter<TData, TOutput, TConfig>,
         asset_name: &str,
         config: &TConfig,
-    ) -> Rc<TOutput> {
-        self.get_ext_opt(importer, asset_name, config).unwrap()
+    ) -> Result<Rc<TOutput>, EngineError> {
+        self.get_ext_opt(importer, asset_name, config)
+            .ok_or_else(|| EngineError::AssetNotFound {
+                name: asset_name.to_owned(),
+            })
     }
 
     pub fn get<TData: 'static, TOutput: 'static, TConfig: 'static + Hash + Default>(
         &mut self,
         importer: &AssetImporter<TData, TOutput, TConfig>,
         asset_name: &str,
-    ) -> Rc<TOutput> {
-        self.get_opt(importer, asset_name).unwrap()
+    ) -> Result<Rc<TOutput>, EngineError> {
+        let config = TConfig::default();
+        self.get_ext(importer, asset_name, &config)
+    }
+
+    ///
+    /// get_or_warn
+    ///
+    /// Get an asset the game can carry on without - if it's missing, it's logged and added to the
+    /// missing asset report, and the caller falls back (usually to one of the `placeholders`).
+    /// Panics in strict mode.
+    pub fn get_or_warn<TData: 'static, TOutput: 'static, TConfig: 'static + Hash + Default>(
+        &mut self,
+        importer: &AssetImporter<TData, TOutput, TConfig>,
+        asset_name: &str,
+    ) -> Option<Rc<TOutput>> {
+        let config = TConfig::default();
+        self.get_ext_or_warn(importer, asset_name, &config)
+    }
+
+    pub fn get_ext_or_warn<TData: 'static, TOutput: 'static, TConfig: 'static + Hash + Default>(
+        &mut self,
+        importer: &AssetImporter<TData, TOutput, TConfig>,
+        asset_name: &str,
+        config: &TConfig,
+    ) -> Option<Rc<TOutput>> {
+        match self.get_ext(importer, asset_name, config) {
+            Ok(asset) => Some(asset),
+            Err(err) => {
+                if is_strict() {
+                    panic!("{} (strict asset mode)", err);
+                }
+                // Only warn the first time, but count each request in the report
+                if missing_assets::record(asset_name) {
+                    warn!("{}", err);
+                }
+                None
+            }
+        }
+    }
+}
+
+fn config_hash<TConfig: Hash>(config: &TConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn default_config_hash<TConfig: Hash + Default>() -> u64 {
+    config_hash(&TConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Cursor};
+
+    use super::*;
+    use crate::assets::{asset_paths::ReadableAndSeekable, text_importer::TEXT_IMPORTER};
+
+    struct SingleFile;
+
+    impl AbstractAssetPath for SingleFile {
+        fn exists(&self, _base_path: String, asset_name: String) -> bool {
+            asset_name == "present.txt"
+        }
+
+        fn get_reader(
+            &self,
+            base_path: String,
+            asset_name: String,
+        ) -> Option<RefCell<Box<dyn ReadableAndSeekable>>> {
+            if !self.exists(base_path, asset_name) {
+                return None;
+            }
+            let reader: Box<dyn ReadableAndSeekable> = Box::new(Cursor::new(b"hello".to_vec()));
+            Some(RefCell::new(reader))
+        }
+    }
+
+    #[test]
+    fn test_missing_asset_is_an_error() {
+        let mut asset_cache = AssetCache::new(String::new(), Box::new(SingleFile));
+        assert_eq!(
+            *asset_cache.get(&TEXT_IMPORTER, "present.txt").unwrap(),
+            "hello"
+        );
+        assert_eq!(
+            asset_cache.get(&TEXT_IMPORTER, "missing.txt"),
+            Err(EngineError::AssetNotFound {
+                name: "missing.txt".to_owned()
+            })
+        );
     }
 }
@@ -0,0 +1,56 @@
+use std::{cell::RefCell, sync::Arc};
+
+use tracing::trace;
+
+use super::asset_paths::{AbstractAssetPath, ReadableAndSeekable};
+use crate::file_system::FileSystem;
+
+///
+/// FileSystemAssetPath
+///
+/// A folder of loose assets read through a `FileSystem` - so the same asset layout can come from a
+/// plain directory, the Android asset manager, or a scoped-storage tree
+pub struct FileSystemAssetPath {
+    folder_name: String,
+    file_system: Arc<dyn FileSystem>,
+}
+
+impl AbstractAssetPath for FileSystemAssetPath {
+    fn exists(&self, _base_path: String, asset_name: String) -> bool {
+        let path = self.build_relative_path(asset_name);
+        let exists = self.file_system.file_exists(&path);
+        trace!("File system asset checking exists [{}]: {}", path, exists);
+        exists
+    }
+
+    fn get_reader(
+        &self,
+        _base_path: String,
+        asset_name: String,
+    ) -> Option<RefCell<Box<dyn ReadableAndSeekable>>> {
+        let path = self.build_relative_path(asset_name);
+        trace!("File system asset reading from path: {}", path);
+
+        self.file_system.open_reader(&path).map(RefCell::new)
+    }
+}
+
+impl FileSystemAssetPath {
+    pub fn new(
+        folder_name: String,
+        file_system: Arc<dyn FileSystem>,
+    ) -> Box<dyn AbstractAssetPath> {
+        Box::new(FileSystemAssetPath {
+            folder_name,
+            file_system,
+        })
+    }
+
+    fn build_relative_path(&self, asset_name: String) -> String {
+        if self.folder_name.is_empty() {
+            asset_name
+        } else {
+            format!("{}/{}", self.folder_name, asset_name)
+        }
+    }
+}
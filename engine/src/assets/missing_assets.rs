@@ -0,0 +1,116 @@
+///
+/// missing_assets.rs
+///
+/// The session's report of assets that failed to load, and had a placeholder substituted - so
+/// missing content shows up in the console (`missing_assets`) and the debug runtime
+/// (`/v1/assets/missing`), rather than as an invisible object somebody might notice.
+///
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+static REPORT: Lazy<Mutex<Vec<MissingAsset>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingAssetKind {
+    Model,
+    Texture,
+    Sound,
+    Other,
+}
+
+impl MissingAssetKind {
+    /// Guess the kind of asset from its file extension
+    pub fn from_name(name: &str) -> MissingAssetKind {
+        let extension = name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "bin" | "glb" => MissingAssetKind::Model,
+            "pcx" | "gif" | "png" | "dds" => MissingAssetKind::Texture,
+            "wav" => MissingAssetKind::Sound,
+            _ => MissingAssetKind::Other,
+        }
+    }
+}
+
+///
+/// MissingAsset
+///
+/// An asset that couldn't be found, and how many times it was asked for
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MissingAsset {
+    pub kind: MissingAssetKind,
+    pub name: String,
+    pub requests: u32,
+}
+
+///
+/// record
+///
+/// Add a failed load to the report - returns true the first time an asset is recorded
+pub fn record(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    let mut report = REPORT.lock().unwrap();
+    match report.iter_mut().find(|asset| asset.name == name) {
+        Some(asset) => {
+            asset.requests += 1;
+            false
+        }
+        None => {
+            report.push(MissingAsset {
+                kind: MissingAssetKind::from_name(&name),
+                name,
+                requests: 1,
+            });
+            true
+        }
+    }
+}
+
+/// The assets missing so far this session, in the order they were first asked for
+pub fn entries() -> Vec<MissingAsset> {
+    REPORT.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_name() {
+        assert_eq!(
+            MissingAssetKind::from_name("BRIDGE.BIN"),
+            MissingAssetKind::Model
+        );
+        assert_eq!(
+            MissingAssetKind::from_name("fam/tex.PCX"),
+            MissingAssetKind::Texture
+        );
+        assert_eq!(
+            MissingAssetKind::from_name("email.wav"),
+            MissingAssetKind::Sound
+        );
+        assert_eq!(
+            MissingAssetKind::from_name("motiondb"),
+            MissingAssetKind::Other
+        );
+    }
+
+    #[test]
+    fn test_repeat_requests_are_counted() {
+        // The report is global, so use a name no other test asks for
+        assert!(record("test_repeat_requests.wav"));
+        assert!(!record("TEST_REPEAT_REQUESTS.WAV"));
+
+        let entry = entries()
+            .into_iter()
+            .find(|asset| asset.name == "test_repeat_requests.wav")
+            .unwrap();
+        assert_eq!(entry.requests, 2);
+        assert_eq!(entry.kind, MissingAssetKind::Sound);
+    }
+}
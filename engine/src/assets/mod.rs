@@ -2,4 +2,7 @@ pub mod asset_cache;
 pub mod asset_importer;
 pub mod asset_paths;
 pub mod bundle_asset_path;
+pub mod file_system_asset_path;
+pub mod missing_assets;
+pub mod placeholders;
 pub mod text_importer;
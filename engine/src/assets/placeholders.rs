@@ -0,0 +1,102 @@
+///
+/// placeholders.rs
+///
+/// Stand-ins for assets that failed to load - loud on purpose, so missing content is noticed in
+/// a playtest: a magenta checker texture, a magenta cube for models, and a short beep for sounds.
+///
+use std::{f32::consts::PI, rc::Rc};
+
+use cgmath::{Matrix4, vec3};
+
+use crate::{
+    audio::AudioClip,
+    scene::{SceneObject, color_material, cube},
+    texture::{self, Texture},
+    texture_format::{PixelFormat, RawTextureData},
+};
+
+// Checker texture - size in pixels, and the size of each square
+const CHECKER_SIZE: u32 = 64;
+const CHECKER_SQUARE: u32 = 8;
+
+// Size of the placeholder cube, in world units
+pub const CUBE_SIZE: f32 = 1.0;
+
+// The placeholder beep
+const BEEP_SAMPLE_RATE: u32 = 22050;
+const BEEP_FREQUENCY: f32 = 880.0;
+const BEEP_DURATION: f32 = 0.15;
+const BEEP_VOLUME: f32 = 0.25;
+
+thread_local! {
+    static TEXTURE: Rc<Texture> = Rc::new(texture::init_from_memory(checker_texture_data()));
+    static SOUND: Rc<AudioClip> = Rc::new(AudioClip::from_raw(1, BEEP_SAMPLE_RATE, beep_samples()));
+}
+
+/// A magenta and black checker texture
+pub fn texture() -> Rc<Texture> {
+    TEXTURE.with(|texture| texture.clone())
+}
+
+/// A short, high beep
+pub fn sound() -> Rc<AudioClip> {
+    SOUND.with(|sound| sound.clone())
+}
+
+/// A magenta cube, centered on the origin
+pub fn cube() -> SceneObject {
+    let material = color_material::create(vec3(1.0, 0.0, 1.0));
+    let mut obj = SceneObject::new(material, Box::new(cube::create()));
+    obj.set_local_transform(Matrix4::from_scale(CUBE_SIZE));
+    obj
+}
+
+fn checker_texture_data() -> RawTextureData {
+    let mut bytes = Vec::with_capacity((CHECKER_SIZE * CHECKER_SIZE * 4) as usize);
+    for y in 0..CHECKER_SIZE {
+        for x in 0..CHECKER_SIZE {
+            let is_magenta = (x / CHECKER_SQUARE + y / CHECKER_SQUARE) % 2 == 0;
+            let pixel = if is_magenta {
+                [255, 0, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            };
+            bytes.extend_from_slice(&pixel);
+        }
+    }
+
+    RawTextureData {
+        bytes,
+        width: CHECKER_SIZE,
+        height: CHECKER_SIZE,
+        format: PixelFormat::RGBA,
+    }
+}
+
+fn beep_samples() -> Vec<i16> {
+    let sample_count = (BEEP_SAMPLE_RATE as f32 * BEEP_DURATION) as usize;
+    (0..sample_count)
+        .map(|i| {
+            let t = i as f32 / BEEP_SAMPLE_RATE as f32;
+            // Fade out, so the beep doesn't end with a click
+            let envelope = 1.0 - i as f32 / sample_count as f32;
+            let sample = (2.0 * PI * BEEP_FREQUENCY * t).sin() * envelope * BEEP_VOLUME;
+            (sample * i16::MAX as f32) as i16
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checker_texture_alternates() {
+        let data = checker_texture_data();
+        assert_eq!(data.bytes.len(), (CHECKER_SIZE * CHECKER_SIZE * 4) as usize);
+        assert_eq!(&data.bytes[0..4], &[255, 0, 255, 255]);
+
+        let next_square = (CHECKER_SQUARE * 4) as usize;
+        assert_eq!(&data.bytes[next_square..next_square + 4], &[0, 0, 0, 255]);
+    }
+}
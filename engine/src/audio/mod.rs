@@ -3,7 +3,7 @@ use std::hash::Hash;
 use std::io::Cursor;
 use std::rc::Rc;
 
-use cgmath::{Vector3, vec3};
+use cgmath::{Quaternion, Rotation, Vector3, vec3};
 use rodio::buffer::SamplesBuffer;
 use rodio::source::{Buffered, Source};
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, SpatialSink};
@@ -17,7 +17,10 @@ static NEXT_HANDLE_ID: AtomicU64 = AtomicU64::new(0);
 
 const SOUND_SCALE_FACTOR: f32 = 5.0;
 
-#[derive(Clone, Debug)]
+// Distance from the listener's head to each ear, in world units
+const EAR_OFFSET: f32 = 1.0;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AudioHandle {
     id: u64,
 }
@@ -119,6 +122,10 @@ where
 
     // Ambient, positional sounds
     ambient_sounds: HashMap<TAmbientKey, (SpatialSink, Rc<AudioClip>)>,
+
+    // Playback speed for world sounds - follows the game's time scale, so slow motion
+    // also lowers the pitch. Music and environmental loops keep playing at normal speed.
+    speed: f32,
 }
 
 impl<TAmbientKey, TCue> Default for AudioContext<TAmbientKey, TCue>
@@ -155,6 +162,26 @@ where
             last_right_ear_position: vec3(0.125, 0.0, 0.0),
 
             ambient_sounds: HashMap::new(),
+
+            speed: 1.0,
+        }
+    }
+
+    ///
+    /// set_speed
+    ///
+    /// Playback speed (and pitch) of world sounds - applied to playing sounds too
+    pub fn set_speed(&mut self, speed: f32) {
+        if speed == self.speed {
+            return;
+        }
+
+        self.speed = speed;
+        for sink in self.handle_to_sink.values() {
+            sink.inner().set_speed(speed);
+        }
+        for (sink, _) in self.ambient_sounds.values() {
+            sink.set_speed(speed);
         }
     }
 
@@ -183,9 +210,15 @@ where
         self.environmental_sink = Some((sink, clip.clone()));
     }
 
+    ///
+    /// update
+    ///
+    /// Move the listener to `position`, facing `orientation` - the ears are placed along the
+    /// orientation's right axis, so panning follows where the player is looking
     pub fn update(
         &mut self,
         position: Vector3<f32>,
+        orientation: Quaternion<f32>,
         current_ambient_sounds: Vec<(TAmbientKey, Vector3<f32>, Rc<AudioClip>)>,
     ) {
         audio_log!(DEBUG, "Audio system update started");
@@ -197,27 +230,14 @@ where
             current_ambient_sounds.len()
         );
 
-        let left_ear_position = [
-            (position.x - 1.0) / SOUND_SCALE_FACTOR,
-            position.y / SOUND_SCALE_FACTOR,
-            position.z / SOUND_SCALE_FACTOR,
-        ];
-        let right_ear_position = [
-            (position.x + 1.0) / SOUND_SCALE_FACTOR,
-            position.y / SOUND_SCALE_FACTOR,
-            position.z / SOUND_SCALE_FACTOR,
-        ];
-
-        self.last_left_ear_position = vec3(
-            left_ear_position[0],
-            left_ear_position[1],
-            left_ear_position[2],
-        );
-        self.last_right_ear_position = vec3(
-            right_ear_position[0],
-            right_ear_position[1],
-            right_ear_position[2],
-        );
+        let ear_offset = orientation.rotate_vector(vec3(EAR_OFFSET, 0.0, 0.0));
+        let left_ear = (position - ear_offset) / SOUND_SCALE_FACTOR;
+        let right_ear = (position + ear_offset) / SOUND_SCALE_FACTOR;
+        let left_ear_position = [left_ear.x, left_ear.y, left_ear.z];
+        let right_ear_position = [right_ear.x, right_ear.y, right_ear.z];
+
+        self.last_left_ear_position = left_ear;
+        self.last_right_ear_position = right_ear;
 
         self.handle_to_sink.retain(|_, sink| !sink.empty());
         // Update positional sounds
@@ -275,6 +295,7 @@ where
                     right_ear_position,
                 )
                 .unwrap();
+                sink.set_speed(self.speed);
 
                 self.ambient_sounds.insert(*key, (sink, clip.clone()));
             }
@@ -368,6 +389,21 @@ pub fn stop_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     }
 }
 
+///
+/// is_audio_playing
+///
+/// Whether a sound started with the handle is still playing
+pub fn is_audio_playing<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
+    context: &AudioContext<TAmbientKey, TCue>,
+    handle: &AudioHandle,
+) -> bool {
+    context
+        .handle_to_sink
+        .get(&handle.id)
+        .map(|sink| !sink.empty())
+        .unwrap_or(false)
+}
+
 /// Plays audio at the listener origin (non-spatial).
 pub fn play_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     context: &mut AudioContext<TAmbientKey, TCue>,
@@ -399,6 +435,40 @@ pub fn play_spatial_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
         .insert(id, SinkAdapter::positional(sink));
 }
 
+///
+/// set_audio_position
+///
+/// Move a sound started with `play_spatial_audio` - for sounds following a moving emitter.
+/// Returns false once the sound has finished (or was never spatial), so callers can stop tracking it.
+pub fn set_audio_position<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
+    context: &mut AudioContext<TAmbientKey, TCue>,
+    handle: &AudioHandle,
+    position: Vector3<f32>,
+) -> bool {
+    match context.handle_to_sink.get(&handle.id) {
+        Some(SinkAdapter::PositionalSink(sink)) if !sink.empty() => {
+            let scaled_position = position / SOUND_SCALE_FACTOR;
+            sink.set_emitter_position([scaled_position.x, scaled_position.y, scaled_position.z]);
+            true
+        }
+        _ => false,
+    }
+}
+
+///
+/// set_audio_volume
+///
+/// Change the volume of a playing sound - 1.0 is the clip's original volume
+pub fn set_audio_volume<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
+    context: &mut AudioContext<TAmbientKey, TCue>,
+    handle: &AudioHandle,
+    volume: f32,
+) {
+    if let Some(sink) = context.handle_to_sink.get(&handle.id) {
+        sink.inner().set_volume(volume);
+    }
+}
+
 pub fn play_audio_core<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     context: &mut AudioContext<TAmbientKey, TCue>,
     position: Vector3<f32>,
@@ -445,6 +515,7 @@ pub fn play_audio_core<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     );
     let sink = rodio::SpatialSink::try_new(&context.handle, positions.0, positions.1, positions.2)
         .unwrap();
+    sink.set_speed(context.speed);
     audio_clip.add_to_spatial_sink(&sink);
 
     //context.handle_to_sink.insert(handle.id, sink);
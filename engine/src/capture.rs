@@ -0,0 +1,103 @@
+use std::path::Path;
+
+///
+/// render_offscreen
+///
+/// Run `render` into a temporary framebuffer of the given size, and read the result back as
+/// tightly-packed RGB rows, top row first. Lets photos be captured at a higher resolution
+/// than the window / eye buffers. The previously bound framebuffer and viewport are restored.
+pub fn render_offscreen(width: u32, height: u32, render: impl FnOnce()) -> Vec<u8> {
+    let (width_i, height_i) = (width as i32, height as i32);
+    let mut rgba: Vec<u8> = vec![0; (width * height * 4) as usize];
+
+    // SAFETY: Only calling into OpenGL, and the read buffer is sized for the framebuffer
+    unsafe {
+        let mut previous_framebuffer = 0;
+        gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_framebuffer);
+        let mut previous_viewport: [i32; 4] = [0; 4];
+        gl::GetIntegerv(gl::VIEWPORT, previous_viewport.as_mut_ptr());
+
+        let mut framebuffer = 0;
+        let mut renderbuffers = [0; 2];
+        gl::GenFramebuffers(1, &mut framebuffer);
+        gl::GenRenderbuffers(2, renderbuffers.as_mut_ptr());
+
+        gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffers[0]);
+        gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width_i, height_i);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffers[1]);
+        gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, width_i, height_i);
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+        gl::FramebufferRenderbuffer(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::RENDERBUFFER,
+            renderbuffers[0],
+        );
+        gl::FramebufferRenderbuffer(
+            gl::FRAMEBUFFER,
+            gl::DEPTH_ATTACHMENT,
+            gl::RENDERBUFFER,
+            renderbuffers[1],
+        );
+
+        gl::Viewport(0, 0, width_i, height_i);
+        gl::Scissor(0, 0, width_i, height_i);
+        gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+        render();
+
+        // RGBA is the only format GLES guarantees for ReadPixels
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            width_i,
+            height_i,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            rgba.as_mut_ptr() as *mut std::ffi::c_void,
+        );
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, previous_framebuffer as u32);
+        gl::Viewport(
+            previous_viewport[0],
+            previous_viewport[1],
+            previous_viewport[2],
+            previous_viewport[3],
+        );
+        gl::Scissor(
+            previous_viewport[0],
+            previous_viewport[1],
+            previous_viewport[2],
+            previous_viewport[3],
+        );
+        gl::DeleteRenderbuffers(2, renderbuffers.as_ptr());
+        gl::DeleteFramebuffers(1, &framebuffer);
+    }
+
+    // OpenGL reads bottom row first
+    let row_size = (width * 4) as usize;
+    rgba.chunks_exact(row_size)
+        .rev()
+        .flat_map(|row| {
+            row.chunks_exact(4)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        })
+        .collect()
+}
+
+///
+/// save_png
+///
+/// Save tightly-packed RGB rows (top row first) as a PNG, creating the parent directory if needed
+pub fn save_png(path: &Path, width: u32, height: u32, rgb: Vec<u8>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+
+    let image = image::RgbImage::from_raw(width, height, rgb)
+        .ok_or_else(|| "pixel data does not match the image size".to_owned())?;
+    image.save(path).map_err(|err| err.to_string())
+}
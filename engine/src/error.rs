@@ -0,0 +1,27 @@
+use std::fmt;
+
+///
+/// EngineError
+///
+/// Errors from the engine that callers are expected to recover from - a missing asset, or a
+/// shader that doesn't compile
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// No asset path had a file with this name
+    AssetNotFound { name: String },
+    /// A shader failed to preprocess, compile or link - with the driver's log
+    Shader(String),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::AssetNotFound { name } => write!(f, "asset not found: {}", name),
+            EngineError::Shader(log) => write!(f, "shader error: {}", log),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+pub type EngineResult<T> = Result<T, EngineError>;
@@ -0,0 +1,259 @@
+// Scoped storage - reading a document tree the user granted access to through the Storage Access
+// Framework (ACTION_OPEN_DOCUMENT_TREE). On newer Android versions, apps can't read arbitrary
+// paths on /sdcard, so the game data has to come through a content:// tree URI instead.
+
+use crate::assets::asset_paths::ReadableAndSeekable;
+pub use crate::file_system::FileSystem;
+use jni::{
+    JNIEnv,
+    errors::Result as JniResult,
+    objects::{JObject, JValue},
+};
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    os::unix::io::FromRawFd,
+};
+use tracing::{debug, warn};
+
+pub struct ContentUriFileSystem {
+    ctx: ndk_context::AndroidContext,
+    /// The granted tree, ie content://com.android.externalstorage.documents/tree/primary%3Ashock2quest
+    tree_uri: String,
+}
+
+impl ContentUriFileSystem {
+    pub fn new(tree_uri: &str) -> ContentUriFileSystem {
+        ContentUriFileSystem {
+            ctx: ndk_context::android_context(),
+            tree_uri: tree_uri.to_owned(),
+        }
+    }
+
+    ///
+    /// from_persisted_permission
+    ///
+    /// The first tree the app holds a persisted read grant for - ie, the data folder the user
+    /// picked on a previous launch
+    pub fn from_persisted_permission() -> Option<ContentUriFileSystem> {
+        let file_system = ContentUriFileSystem::new("");
+        let tree_uri = file_system
+            .with_env(|env| {
+                let resolver = file_system.content_resolver(env)?;
+                let permissions = env
+                    .call_method(
+                        resolver,
+                        "getPersistedUriPermissions",
+                        "()Ljava/util/List;",
+                        &[],
+                    )?
+                    .l()?;
+                let count = env.call_method(permissions, "size", "()I", &[])?.i()?;
+                for i in 0..count {
+                    let permission = env
+                        .call_method(permissions, "get", "(I)Ljava/lang/Object;", &[i.into()])?
+                        .l()?;
+                    if !env
+                        .call_method(permission, "isReadPermission", "()Z", &[])?
+                        .z()?
+                    {
+                        continue;
+                    }
+                    let uri = env
+                        .call_method(permission, "getUri", "()Landroid/net/Uri;", &[])?
+                        .l()?;
+                    let uri = env
+                        .call_method(uri, "toString", "()Ljava/lang/String;", &[])?
+                        .l()?;
+                    return Ok(Some(env.get_string(uri.into())?.into()));
+                }
+                Ok(None)
+            })
+            .flatten()?;
+
+        Some(ContentUriFileSystem {
+            tree_uri,
+            ..file_system
+        })
+    }
+
+    fn with_env<T>(&self, f: impl FnOnce(&JNIEnv) -> JniResult<T>) -> Option<T> {
+        let vm = unsafe { jni::JavaVM::from_raw(self.ctx.vm().cast()) }.ok()?;
+        let env = vm.attach_current_thread().ok()?;
+        let result = f(&env);
+
+        // Missing documents surface as Java exceptions (FileNotFoundException) - clear them, so
+        // the next JNI call doesn't fail
+        if env.exception_check().unwrap_or(false) {
+            let _ = env.exception_clear();
+        }
+
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                debug!("ContentUriFileSystem: JNI call failed: {:?}", err);
+                None
+            }
+        }
+    }
+
+    fn open_fd(&self, path: &str) -> Option<File> {
+        self.with_env(|env| {
+            let uri = self.document_uri(env, path)?;
+            let resolver = self.content_resolver(env)?;
+            let descriptor = env
+                .call_method(
+                    resolver,
+                    "openFileDescriptor",
+                    "(Landroid/net/Uri;Ljava/lang/String;)Landroid/os/ParcelFileDescriptor;",
+                    &[uri.into(), env.new_string("r")?.into()],
+                )?
+                .l()?;
+            // Take ownership of the descriptor - it's closed when the File is dropped
+            let fd = env.call_method(descriptor, "detachFd", "()I", &[])?.i()?;
+            Ok(unsafe { File::from_raw_fd(fd) })
+        })
+    }
+
+    fn content_resolver<'a>(&self, env: &JNIEnv<'a>) -> JniResult<JObject<'a>> {
+        env.call_method(
+            self.ctx.context().cast(),
+            "getContentResolver",
+            "()Landroid/content/ContentResolver;",
+            &[],
+        )?
+        .l()
+    }
+
+    fn tree<'a>(&self, env: &JNIEnv<'a>) -> JniResult<(JObject<'a>, String)> {
+        let tree_uri = env
+            .call_static_method(
+                "android/net/Uri",
+                "parse",
+                "(Ljava/lang/String;)Landroid/net/Uri;",
+                &[env.new_string(&self.tree_uri)?.into()],
+            )?
+            .l()?;
+        let tree_id = env
+            .call_static_method(
+                "android/provider/DocumentsContract",
+                "getTreeDocumentId",
+                "(Landroid/net/Uri;)Ljava/lang/String;",
+                &[tree_uri.into()],
+            )?
+            .l()?;
+        let tree_id: String = env.get_string(tree_id.into())?.into();
+        Ok((tree_uri, tree_id))
+    }
+
+    fn document_uri<'a>(&self, env: &JNIEnv<'a>, path: &str) -> JniResult<JObject<'a>> {
+        let (tree_uri, tree_id) = self.tree(env)?;
+        env.call_static_method(
+            "android/provider/DocumentsContract",
+            "buildDocumentUriUsingTree",
+            "(Landroid/net/Uri;Ljava/lang/String;)Landroid/net/Uri;",
+            &[
+                tree_uri.into(),
+                env.new_string(document_id(&tree_id, path))?.into(),
+            ],
+        )?
+        .l()
+    }
+}
+
+/// Document IDs for the external storage provider are the tree's ID plus the relative path
+fn document_id(tree_id: &str, path: &str) -> String {
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        tree_id.to_owned()
+    } else {
+        format!("{}/{}", tree_id.trim_end_matches('/'), path)
+    }
+}
+
+impl FileSystem for ContentUriFileSystem {
+    fn open_dir(&self, path: &str) -> Vec<String> {
+        self.with_env(|env| {
+            let (tree_uri, tree_id) = self.tree(env)?;
+            let children_uri = env
+                .call_static_method(
+                    "android/provider/DocumentsContract",
+                    "buildChildDocumentsUriUsingTree",
+                    "(Landroid/net/Uri;Ljava/lang/String;)Landroid/net/Uri;",
+                    &[
+                        tree_uri.into(),
+                        env.new_string(document_id(&tree_id, path))?.into(),
+                    ],
+                )?
+                .l()?;
+
+            let projection = env.new_object_array(
+                1,
+                "java/lang/String",
+                env.new_string("_display_name")?,
+            )?;
+            let resolver = self.content_resolver(env)?;
+            let cursor = env
+                .call_method(
+                    resolver,
+                    "query",
+                    "(Landroid/net/Uri;[Ljava/lang/String;Ljava/lang/String;[Ljava/lang/String;Ljava/lang/String;)Landroid/database/Cursor;",
+                    &[
+                        children_uri.into(),
+                        JObject::from(projection).into(),
+                        JValue::Object(JObject::null()),
+                        JValue::Object(JObject::null()),
+                        JValue::Object(JObject::null()),
+                    ],
+                )?
+                .l()?;
+
+            let mut out = vec![];
+            if cursor.is_null() {
+                return Ok(out);
+            }
+
+            while env.call_method(cursor, "moveToNext", "()Z", &[])?.z()? {
+                let name = env
+                    .call_method(cursor, "getString", "(I)Ljava/lang/String;", &[0.into()])?
+                    .l()?;
+                out.push(env.get_string(name.into())?.into());
+                // Large folders can overflow the local reference table otherwise
+                env.delete_local_ref(name)?;
+            }
+            env.call_method(cursor, "close", "()V", &[])?;
+            Ok(out)
+        })
+        .unwrap_or_default()
+    }
+
+    fn open_file(&self, path: &str) -> Vec<u8> {
+        debug!("ContentUriFileSystem::open_file -> {}", path);
+        let mut file = self
+            .open_fd(path)
+            .unwrap_or_else(|| panic!("Could not open document: {}", path));
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).unwrap();
+        data
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        self.open_fd(path).is_some()
+    }
+
+    fn open_reader(&self, path: &str) -> Option<Box<dyn ReadableAndSeekable>> {
+        debug!("ContentUriFileSystem::open_reader -> {}", path);
+        match self.open_fd(path) {
+            Some(file) => Some(Box::new(BufReader::new(file))),
+            None => {
+                warn!("ContentUriFileSystem: unable to open {}", path);
+                None
+            }
+        }
+    }
+}
+
+// SAFETY: As with AndroidFileSystem, the raw pointers in AndroidContext stay valid for the
+// lifetime of the application - and every call attaches its thread to the VM before using them.
+unsafe impl Send for ContentUriFileSystem {}
+unsafe impl Sync for ContentUriFileSystem {}
@@ -1,5 +1,6 @@
+use crate::assets::asset_paths::ReadableAndSeekable;
 pub use crate::file_system::FileSystem;
-use std::path::Path;
+use std::{fs::File, io::BufReader, path::Path};
 use tracing::debug;
 
 pub struct DefaultFileSystem<'a> {
@@ -10,11 +11,14 @@ impl FileSystem for DefaultFileSystem<'_> {
     fn open_dir(&self, path: &str) -> Vec<String> {
         let full_path = self.root_path.join(path);
         debug!("DefaultFileSystem::open_dir -> {:?}", full_path);
-        let read_dir_result = std::fs::read_dir(full_path).unwrap();
+        // A missing directory (or a file) lists as empty, like the other file systems
+        let Ok(read_dir_result) = std::fs::read_dir(full_path) else {
+            return Vec::new();
+        };
 
         read_dir_result
+            .filter_map(|entry| entry.ok())
             .map(|entry| {
-                let entry = entry.unwrap();
                 let entry_path = entry.path();
                 let file_name = entry_path.file_name().unwrap();
                 let file_name_as_str = file_name.to_str().unwrap();
@@ -40,4 +44,12 @@ impl FileSystem for DefaultFileSystem<'_> {
         );
         exists
     }
+
+    fn open_reader(&self, path: &str) -> Option<Box<dyn ReadableAndSeekable>> {
+        let full_path = self.root_path.join(path);
+        debug!("DefaultFileSystem::open_reader -> {:?}", full_path);
+
+        let file = File::open(full_path).ok()?;
+        Some(Box::new(BufReader::new(file)))
+    }
 }
@@ -1,5 +1,19 @@
+use std::io::Cursor;
+
+use crate::assets::asset_paths::ReadableAndSeekable;
+
 pub trait FileSystem: Send + Sync {
     fn open_dir(&self, path: &str) -> Vec<String>;
     fn open_file(&self, path: &str) -> Vec<u8>;
     fn file_exists(&self, path: &str) -> bool;
+
+    /// Open a file for streaming reads - by default, by reading the whole file into memory.
+    /// File systems backed by real files override this, so large archives can be read in place.
+    fn open_reader(&self, path: &str) -> Option<Box<dyn ReadableAndSeekable>> {
+        if !self.file_exists(path) {
+            return None;
+        }
+
+        Some(Box::new(Cursor::new(self.open_file(path))))
+    }
 }
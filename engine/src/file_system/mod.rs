@@ -11,3 +11,8 @@ pub use default_file_system::DefaultFileSystem;
 pub mod android_file_system;
 #[cfg(target_os = "android")]
 pub use android_file_system::AndroidFileSystem;
+
+#[cfg(target_os = "android")]
+pub mod content_uri_file_system;
+#[cfg(target_os = "android")]
+pub use content_uri_file_system::ContentUriFileSystem;
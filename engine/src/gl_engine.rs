@@ -19,6 +19,7 @@ fn init(is_opengl_es: bool, storage: Arc<dyn crate::file_system::Storage>) -> Op
 
 use crate::engine::Engine;
 use crate::engine::EngineRenderContext;
+use crate::scene::render_order;
 use crate::scene::scene::Scene;
 
 impl Engine for OpenGLEngine {
@@ -31,6 +32,9 @@ impl Engine for OpenGLEngine {
         // let convertedVertex = shader::convert(vertexShaderSource, self.isES).unwrap();
         // let convertedFragment = shader::convert(fragmentShaderSource, self.isES).unwrap();
 
+        // Pick up edited shaders, when watching a shader directory
+        crate::shader_library::poll_for_changes();
+
         unsafe {
             gl::Enable(gl::DEPTH_TEST);
             gl::Enable(gl::BLEND);
@@ -112,15 +116,15 @@ impl Engine for OpenGLEngine {
             // floor.draw(&self, render_context, &view);
 
             // SINGLE-PASS LIGHTING: Opaque pass with all lighting calculated in shaders
-            scene
-                .iter()
-                .for_each(|s| s.draw_opaque(self, render_context, &view, scene.lights()));
+            for idx in render_order::opaque_order(scene) {
+                scene[idx].draw_opaque(self, render_context, &view, scene.lights());
+            }
 
-            // Transparent pass with all lighting calculated in shaders
+            // Transparent pass with all lighting calculated in shaders - back-to-front, see render_order
             gl::DepthMask(gl::FALSE);
-            scene
-                .iter()
-                .for_each(|s| s.draw_transparent(self, render_context, &view, scene.lights()));
+            for idx in render_order::transparent_order(scene, &view) {
+                scene[idx].draw_transparent(self, render_context, &view, scene.lights());
+            }
             gl::DepthMask(gl::TRUE);
 
             //cube.destroy();
@@ -0,0 +1,132 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+///
+/// GpuResourceKind
+///
+/// Categories of GPU memory that are tracked - each texture and mesh records its allocation
+/// when created and releases it when dropped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuResourceKind {
+    Texture,
+    VertexBuffer,
+    IndexBuffer,
+}
+
+impl GpuResourceKind {
+    pub const ALL: [GpuResourceKind; 3] = [
+        GpuResourceKind::Texture,
+        GpuResourceKind::VertexBuffer,
+        GpuResourceKind::IndexBuffer,
+    ];
+
+    fn index(&self) -> usize {
+        match self {
+            GpuResourceKind::Texture => 0,
+            GpuResourceKind::VertexBuffer => 1,
+            GpuResourceKind::IndexBuffer => 2,
+        }
+    }
+}
+
+static BYTES: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+static COUNTS: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+///
+/// GpuMemoryStats
+///
+/// Live allocations of one kind
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GpuMemoryStats {
+    pub kind: GpuResourceKind,
+    pub count: u64,
+    pub bytes: u64,
+}
+
+///
+/// GpuAllocation
+///
+/// An allocation counted in the totals until it's dropped - held by the resource that owns
+/// the GPU memory. A clone counts again, since cloned meshes delete their buffers on drop too.
+#[derive(Debug)]
+pub struct GpuAllocation {
+    kind: GpuResourceKind,
+    bytes: u64,
+}
+
+impl GpuAllocation {
+    pub fn new(kind: GpuResourceKind, bytes: u64) -> GpuAllocation {
+        BYTES[kind.index()].fetch_add(bytes, Ordering::Relaxed);
+        COUNTS[kind.index()].fetch_add(1, Ordering::Relaxed);
+        GpuAllocation { kind, bytes }
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl Clone for GpuAllocation {
+    fn clone(&self) -> Self {
+        GpuAllocation::new(self.kind, self.bytes)
+    }
+}
+
+impl Drop for GpuAllocation {
+    fn drop(&mut self) {
+        saturating_sub(&BYTES[self.kind.index()], self.bytes);
+        saturating_sub(&COUNTS[self.kind.index()], 1);
+    }
+}
+
+fn saturating_sub(counter: &AtomicU64, amount: u64) {
+    let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |value| {
+        Some(value.saturating_sub(amount))
+    });
+}
+
+///
+/// stats
+///
+/// Live GPU allocations, per kind
+pub fn stats() -> Vec<GpuMemoryStats> {
+    GpuResourceKind::ALL
+        .iter()
+        .map(|kind| GpuMemoryStats {
+            kind: *kind,
+            count: COUNTS[kind.index()].load(Ordering::Relaxed),
+            bytes: BYTES[kind.index()].load(Ordering::Relaxed),
+        })
+        .collect()
+}
+
+/// Bytes of GPU memory in use, across all kinds
+pub fn total_bytes() -> u64 {
+    BYTES
+        .iter()
+        .map(|bytes| bytes.load(Ordering::Relaxed))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocations_are_released_on_drop() {
+        // The counters are global, so compare against a baseline
+        let before = stats()[GpuResourceKind::IndexBuffer.index()].bytes;
+        let allocation = GpuAllocation::new(GpuResourceKind::IndexBuffer, 1024);
+        let clone = allocation.clone();
+        assert_eq!(
+            stats()[GpuResourceKind::IndexBuffer.index()].bytes,
+            before + 2048
+        );
+
+        drop(allocation);
+        drop(clone);
+        assert_eq!(stats()[GpuResourceKind::IndexBuffer.index()].bytes, before);
+    }
+}
@@ -1,23 +1,30 @@
 pub mod assets;
 pub mod audio;
+pub mod capture;
 mod engine;
+mod error;
 pub mod file_system;
 mod font;
 mod gl_engine;
+pub mod gpu_memory;
 pub mod importers;
 pub mod logging;
 pub mod macros;
 pub mod materials;
+pub mod profiler;
 pub mod scene;
 mod shader;
+pub mod shader_library;
 mod shader_program;
 pub mod texture;
 pub mod texture_atlas;
 pub mod texture_format;
 pub mod util;
+pub mod video;
 
 pub use crate::engine::Engine;
 pub use crate::engine::EngineRenderContext;
+pub use crate::error::{EngineError, EngineResult};
 pub use crate::font::{Font, FontCharacterInfo};
 
 pub fn opengl() -> Box<dyn Engine> {
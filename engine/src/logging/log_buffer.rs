@@ -0,0 +1,85 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tracing::Level;
+
+/// Number of scoped log entries kept in memory
+pub const LOG_BUFFER_CAPACITY: usize = 2048;
+
+static LOG_BUFFER: Lazy<Mutex<VecDeque<LogEntry>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY)));
+
+static CURRENT_FRAME: AtomicU64 = AtomicU64::new(0);
+
+///
+/// LogEntry
+///
+/// A single scoped log line, tagged with the frame it was logged on
+#[derive(Clone, Debug, Serialize)]
+pub struct LogEntry {
+    pub frame: u64,
+    pub level: String,
+    pub category: String,
+    pub entity_id: Option<u64>,
+    pub message: String,
+}
+
+///
+/// set_log_frame
+///
+/// Set the frame number attached to subsequent log entries. Called by the game once per update.
+pub fn set_log_frame(frame: u64) {
+    CURRENT_FRAME.store(frame, Ordering::Relaxed);
+}
+
+///
+/// record_log_entry
+///
+/// Push an entry into the log buffer, dropping the oldest entry when full.
+/// Called by the scoped logging macros - not usually called directly.
+pub fn record_log_entry(category: &str, level: Level, entity_id: Option<u64>, message: String) {
+    let entry = LogEntry {
+        frame: CURRENT_FRAME.load(Ordering::Relaxed),
+        level: level.to_string(),
+        category: category.to_owned(),
+        entity_id,
+        message,
+    };
+
+    // A poisoned lock just means a panic happened mid-push - the entries are still usable
+    let mut buffer = LOG_BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+    if buffer.len() >= LOG_BUFFER_CAPACITY {
+        buffer.pop_front();
+    }
+    buffer.push_back(entry);
+}
+
+///
+/// log_entries_since
+///
+/// All buffered entries logged on or after `since_frame`, oldest first
+pub fn log_entries_since(since_frame: u64) -> Vec<LogEntry> {
+    let buffer = LOG_BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+    buffer
+        .iter()
+        .filter(|entry| entry.frame >= since_frame)
+        .cloned()
+        .collect()
+}
+
+///
+/// recent_log_entries
+///
+/// The last `count` buffered entries, oldest first
+pub fn recent_log_entries(count: usize) -> Vec<LogEntry> {
+    let buffer = LOG_BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+    let skip = buffer.len().saturating_sub(count);
+    buffer.iter().skip(skip).cloned().collect()
+}
@@ -1,11 +1,33 @@
 // Profile macro is defined in macros.rs - this file contains additional logging utilities
 
 /// Convenience macro for scoped logging at different levels
+///
+/// Entries are also recorded in the in-memory log buffer (see `log_buffer`), optionally
+/// tagged with an entity id:
+/// ```ignore
+/// game_log!(WARN, entity: entity_id.inner(), "Unable to find animation");
+/// ```
 #[macro_export]
 macro_rules! scoped_log {
+    ($level:ident, $scope:expr, entity: $entity:expr, $($arg:tt)*) => {
+        let log_config = $crate::logging::get_log_config();
+        let should_log = log_config.should_log($scope, tracing::Level::$level);
+        // Warnings and errors always land in the log buffer, so crash reports have context
+        if should_log || tracing::Level::$level <= tracing::Level::WARN {
+            $crate::logging::record_log_entry($scope, tracing::Level::$level, Some($entity), format!($($arg)*));
+        }
+        if should_log {
+            tracing::event!(tracing::Level::$level, scope = $scope, entity = $entity, $($arg)*);
+        }
+    };
     ($level:ident, $scope:expr, $($arg:tt)*) => {
         let log_config = $crate::logging::get_log_config();
-        if log_config.should_log($scope, tracing::Level::$level) {
+        let should_log = log_config.should_log($scope, tracing::Level::$level);
+        // Warnings and errors always land in the log buffer, so crash reports have context
+        if should_log || tracing::Level::$level <= tracing::Level::WARN {
+            $crate::logging::record_log_entry($scope, tracing::Level::$level, None, format!($($arg)*));
+        }
+        if should_log {
             tracing::event!(tracing::Level::$level, scope = $scope, $($arg)*);
         }
     };
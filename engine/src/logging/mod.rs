@@ -1,7 +1,12 @@
 pub mod config;
+pub mod log_buffer;
 pub mod macros;
 
 pub use config::{LogConfig, init_logging};
+pub use log_buffer::{
+    LOG_BUFFER_CAPACITY, LogEntry, log_entries_since, recent_log_entries, record_log_entry,
+    set_log_frame,
+};
 pub use tracing::{Level, debug, error, info, trace, warn};
 
 use once_cell::sync::Lazy;
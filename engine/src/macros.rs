@@ -11,6 +11,9 @@ macro_rules! offset_of {
 
 /// Enhanced profile macro with scope and level awareness
 ///
+/// Timings are logged when the scope/level is enabled, and aggregated per frame
+/// by `engine::profiler` when the profiler is enabled.
+///
 /// Usage:
 /// ```
 /// profile!(scope: "physics", level: debug, "collision_detection", {
@@ -22,11 +25,15 @@ macro_rules! profile {
     // New scope-aware version
     (scope: $scope:expr, level: $level:ident, $description:expr, $block:expr) => {{
         let log_config = $crate::logging::get_log_config();
-        if log_config.should_log($scope, tracing::Level::$level) {
+        let should_log = log_config.should_log($scope, tracing::Level::$level);
+        if should_log || $crate::profiler::is_enabled() {
             let start = std::time::Instant::now();
             let result = $block;
             let duration = start.elapsed();
-            tracing::event!(tracing::Level::$level, scope = $scope, duration = ?duration, "{}", $description);
+            $crate::profiler::record($description, duration);
+            if should_log {
+                tracing::event!(tracing::Level::$level, scope = $scope, duration = ?duration, "{}", $description);
+            }
             result
         } else {
             $block
@@ -36,11 +43,15 @@ macro_rules! profile {
     // Backwards compatibility - old macro interface, defaults to "performance" scope and DEBUG level
     ($description:expr, $block:expr) => {{
         let log_config = $crate::logging::get_log_config();
-        if log_config.should_log("performance", tracing::Level::DEBUG) {
+        let should_log = log_config.should_log("performance", tracing::Level::DEBUG);
+        if should_log || $crate::profiler::is_enabled() {
             let start = std::time::Instant::now();
             let result = $block;
             let duration = start.elapsed();
-            tracing::event!(tracing::Level::DEBUG, scope = "performance", duration = ?duration, "{}", $description);
+            $crate::profiler::record($description, duration);
+            if should_log {
+                tracing::event!(tracing::Level::DEBUG, scope = "performance", duration = ?duration, "{}", $description);
+            }
             result
         } else {
             $block
@@ -0,0 +1,186 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Number of frames kept for rolling averages and percentiles
+pub const PROFILER_WINDOW: usize = 120;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static PROFILER: Lazy<Mutex<FrameProfiler>> = Lazy::new(|| Mutex::new(FrameProfiler::new()));
+
+///
+/// ProfileStats
+///
+/// Per-frame timing for a single `profile!` label, in milliseconds
+#[derive(Clone, Debug, Serialize)]
+pub struct ProfileStats {
+    pub label: String,
+    pub last_ms: f32,
+    pub average_ms: f32,
+    pub p95_ms: f32,
+    pub max_ms: f32,
+}
+
+struct FrameProfiler {
+    // Time spent per label in the frame in progress - labels can be hit multiple times per frame
+    current: HashMap<String, f32>,
+    history: HashMap<String, VecDeque<f32>>,
+}
+
+impl FrameProfiler {
+    fn new() -> FrameProfiler {
+        FrameProfiler {
+            current: HashMap::new(),
+            history: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, label: &str, duration: Duration) {
+        let ms = duration.as_secs_f32() * 1000.0;
+        match self.current.get_mut(label) {
+            Some(total) => *total += ms,
+            None => {
+                self.current.insert(label.to_owned(), ms);
+            }
+        }
+    }
+
+    fn end_frame(&mut self) {
+        // Labels that weren't hit this frame count as zero, so averages stay per-frame
+        for (label, samples) in self.history.iter_mut() {
+            let ms = self.current.remove(label).unwrap_or(0.0);
+            if samples.len() >= PROFILER_WINDOW {
+                samples.pop_front();
+            }
+            samples.push_back(ms);
+        }
+
+        for (label, ms) in self.current.drain() {
+            let mut samples = VecDeque::with_capacity(PROFILER_WINDOW);
+            samples.push_back(ms);
+            self.history.insert(label, samples);
+        }
+    }
+
+    fn stats(&self) -> Vec<ProfileStats> {
+        let mut stats = self
+            .history
+            .iter()
+            .map(|(label, samples)| {
+                let samples = samples.iter().copied().collect::<Vec<f32>>();
+                ProfileStats {
+                    label: label.to_owned(),
+                    last_ms: samples.last().copied().unwrap_or(0.0),
+                    average_ms: samples.iter().sum::<f32>() / samples.len().max(1) as f32,
+                    p95_ms: percentile(&samples, 0.95),
+                    max_ms: samples.iter().copied().fold(0.0, f32::max),
+                }
+            })
+            .collect::<Vec<ProfileStats>>();
+
+        stats.sort_by(|a, b| b.average_ms.total_cmp(&a.average_ms));
+        stats
+    }
+}
+
+fn percentile(samples: &[f32], percentile: f32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let index = ((sorted.len() - 1) as f32 * percentile).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+///
+/// set_enabled
+///
+/// Turn aggregation of `profile!` timings on or off. Off by default, so the macro
+/// costs nothing beyond a flag check unless someone is looking.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+///
+/// record
+///
+/// Add time spent under `label` to the current frame. Called by the `profile!` macro.
+pub fn record(label: &str, duration: Duration) {
+    if !is_enabled() {
+        return;
+    }
+
+    PROFILER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .record(label, duration);
+}
+
+///
+/// end_frame
+///
+/// Close out the current frame, pushing its totals into the rolling window.
+/// Called once per frame by the game.
+pub fn end_frame() {
+    if !is_enabled() {
+        return;
+    }
+
+    PROFILER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .end_frame();
+}
+
+///
+/// stats
+///
+/// Rolling per-label statistics, most expensive (on average) first
+pub fn stats() -> Vec<ProfileStats> {
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner()).stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let samples = (1..=100).map(|i| i as f32).collect::<Vec<f32>>();
+        assert_eq!(percentile(&samples, 0.95), 95.0);
+        assert_eq!(percentile(&samples, 0.0), 1.0);
+        assert_eq!(percentile(&[], 0.95), 0.0);
+    }
+
+    #[test]
+    fn test_frame_totals_accumulate() {
+        let mut profiler = FrameProfiler::new();
+        profiler.record("scripts", Duration::from_millis(2));
+        profiler.record("scripts", Duration::from_millis(3));
+        profiler.end_frame();
+
+        // A frame where the label isn't hit counts as zero
+        profiler.end_frame();
+
+        let stats = profiler.stats();
+        assert_eq!(stats.len(), 1);
+        assert!((stats[0].max_ms - 5.0).abs() < 0.01);
+        assert!((stats[0].last_ms - 0.0).abs() < 0.01);
+        assert!((stats[0].average_ms - 2.5).abs() < 0.01);
+    }
+}
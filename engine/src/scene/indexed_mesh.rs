@@ -6,6 +6,7 @@ use std::os::raw::c_void;
 
 use super::Vertex;
 use super::VertexAttributeType;
+use crate::gpu_memory::{GpuAllocation, GpuResourceKind};
 
 #[derive(Clone)]
 pub struct IndexedMesh {
@@ -13,12 +14,17 @@ pub struct IndexedMesh {
     pub vao: GLuint,
     pub ebo: GLuint,
     pub index_count: i32,
+
+    _vertex_memory: GpuAllocation,
+    _index_memory: GpuAllocation,
 }
 
 pub fn create<T: Vertex>(raw_vertices: Vec<T>, indices: Vec<u32>) -> IndexedMesh {
     let index_count = indices.len() as i32;
 
     let (mut vbo, mut vao, mut ebo) = (0, 0, 0);
+    let vertex_bytes = raw_vertices.len() * <T>::get_total_size() as usize;
+    let index_bytes = indices.len() * mem::size_of::<u32>();
     unsafe {
         gl::GenVertexArrays(1, &mut vao);
         gl::GenBuffers(1, &mut vbo);
@@ -97,6 +103,8 @@ pub fn create<T: Vertex>(raw_vertices: Vec<T>, indices: Vec<u32>) -> IndexedMesh
         vao,
         vbo,
         ebo,
+        _vertex_memory: GpuAllocation::new(GpuResourceKind::VertexBuffer, vertex_bytes as u64),
+        _index_memory: GpuAllocation::new(GpuResourceKind::IndexBuffer, index_bytes as u64),
     }
 }
 
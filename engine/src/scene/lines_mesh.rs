@@ -5,12 +5,16 @@ pub use crate::scene::VertexPosition;
 use std::mem::size_of;
 use std::os::raw::c_void;
 
+use crate::gpu_memory::{GpuAllocation, GpuResourceKind};
+
 pub struct LinesMesh {
     pub vbo: GLuint,
     pub vao: GLuint,
     pub ebo: GLuint,
 
     pub index_count: i32,
+
+    _vertex_memory: GpuAllocation,
 }
 
 pub fn create(raw_vertices: Vec<VertexPosition>) -> LinesMesh {
@@ -54,6 +58,10 @@ pub fn create(raw_vertices: Vec<VertexPosition>) -> LinesMesh {
         vao,
         vbo,
         ebo,
+        _vertex_memory: GpuAllocation::new(
+            GpuResourceKind::VertexBuffer,
+            (raw_vertices.len() * size_of::<VertexPosition>()) as u64,
+        ),
     }
 }
 
@@ -9,6 +9,10 @@ pub trait Material: Any {
     fn has_initialized(&self) -> bool;
     fn initialize(&mut self, is_opengl_es: bool);
 
+    /// Make the material glow at least this much (ie, for self-illuminated objects), until reset with None.
+    /// Materials are shared between objects, so scene objects set this right before they draw.
+    fn set_emissivity_override(&mut self, _emissivity: Option<f32>) {}
+
     /// Draw opaque material with single-pass lighting
     ///
     /// This method renders the material with all lighting calculations performed in a single pass.
@@ -7,6 +7,7 @@ use std::os::raw::c_void;
 
 use super::Vertex;
 use super::VertexAttributeType;
+use crate::gpu_memory::{GpuAllocation, GpuResourceKind};
 
 #[derive(Clone)]
 pub struct Mesh {
@@ -15,6 +16,9 @@ pub struct Mesh {
     pub ebo: GLuint,
 
     pub triangle_count: i32,
+
+    _vertex_memory: GpuAllocation,
+    _index_memory: GpuAllocation,
 }
 
 // use std::backtrace::Backtrace;
@@ -34,6 +38,8 @@ pub fn create<T: Vertex>(raw_vertices: Vec<T>) -> Mesh {
     let indices = [0, 1, 2];
 
     let (mut vbo, mut vao, mut ebo) = (0, 0, 0);
+    let vertex_bytes = raw_vertices.len() * <T>::get_total_size() as usize;
+    let index_bytes = indices.len() * mem::size_of::<GLfloat>();
     unsafe {
         gl::GenVertexArrays(1, &mut vao);
         gl::GenBuffers(1, &mut vbo);
@@ -114,6 +120,8 @@ pub fn create<T: Vertex>(raw_vertices: Vec<T>) -> Mesh {
         vao,
         vbo,
         ebo,
+        _vertex_memory: GpuAllocation::new(GpuResourceKind::VertexBuffer, vertex_bytes as u64),
+        _index_memory: GpuAllocation::new(GpuResourceKind::IndexBuffer, index_bytes as u64),
     }
 }
 
@@ -19,6 +19,11 @@ pub use billboard_material::*;
 pub mod scene_object;
 pub use scene_object::SceneObject;
 
+pub mod render_order;
+
+pub mod scene_graph;
+pub use scene_graph::SceneGraph;
+
 pub mod renderable;
 pub use renderable::{
     Renderable, TransformSceneObject, create_transform_group, flatten_renderables,
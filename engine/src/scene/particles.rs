@@ -1,6 +1,6 @@
 use std::{sync::Arc, time::Duration};
 
-use cgmath::{Matrix4, SquareMatrix, Vector3, vec3};
+use cgmath::{InnerSpace, Matrix4, SquareMatrix, Vector3, vec3};
 use rand::Rng;
 
 use crate::{
@@ -54,6 +54,58 @@ fn create_particle_texture() -> Arc<Texture> {
     Arc::new(texture)
 }
 
+///
+/// ParticlePool
+///
+/// Particle buffers handed back by systems that are gone, so new systems can reuse their
+/// allocations instead of growing fresh ones
+#[derive(Default)]
+pub struct ParticlePool {
+    free_buffers: Vec<Vec<Particle>>,
+}
+
+impl ParticlePool {
+    pub fn new() -> ParticlePool {
+        ParticlePool {
+            free_buffers: Vec::new(),
+        }
+    }
+
+    pub fn free_buffer_count(&self) -> usize {
+        self.free_buffers.len()
+    }
+
+    fn take(&mut self) -> Vec<Particle> {
+        self.free_buffers.pop().unwrap_or_default()
+    }
+
+    fn give(&mut self, mut buffer: Vec<Particle>) {
+        buffer.clear();
+        if buffer.capacity() > 0 {
+            self.free_buffers.push(buffer);
+        }
+    }
+}
+
+///
+/// allocate_particle_budgets
+///
+/// Split a cap on simultaneously simulated particles between systems, nearest first. Each request
+/// is (distance to the viewer, particles wanted); the result is the budget for each request, in order.
+pub fn allocate_particle_budgets(requests: &[(f32, usize)], max_particles: usize) -> Vec<usize> {
+    let mut by_distance = (0..requests.len()).collect::<Vec<_>>();
+    by_distance.sort_by(|a, b| requests[*a].0.total_cmp(&requests[*b].0));
+
+    let mut budgets = vec![0; requests.len()];
+    let mut remaining = max_particles;
+    for idx in by_distance {
+        let budget = requests[idx].1.min(remaining);
+        budgets[idx] = budget;
+        remaining -= budget;
+    }
+    budgets
+}
+
 pub struct ParticleSystem {
     particles: Vec<Particle>,
     acceleration: Vector3<f32>,
@@ -186,7 +238,64 @@ impl ParticleSystem {
         }
     }
 
+    /// Reuse a particle buffer from the pool, instead of allocating a new one
+    pub fn with_pool(self, pool: &mut ParticlePool) -> ParticleSystem {
+        ParticleSystem {
+            particles: pool.take(),
+            ..self
+        }
+    }
+
+    /// Hand the particle buffer back to the pool, when the system is going away
+    pub fn release(&mut self, pool: &mut ParticlePool) {
+        pool.give(std::mem::take(&mut self.particles));
+    }
+
+    pub fn max_particles(&self) -> usize {
+        self.max_particles
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Where the system is in the world
+    pub fn world_position(&self) -> Vector3<f32> {
+        self.root_transform.w.truncate()
+    }
+
+    /// Radius, around the world position, that the current particles fit within
+    pub fn bounding_radius(&self) -> f32 {
+        let max_size = self.particle_size.0.max(self.particle_size.1);
+        let local_radius = self
+            .particles
+            .iter()
+            .map(|p| p.position.magnitude())
+            .fold(max_size, |acc, dist| acc.max(dist + max_size));
+
+        let scale = self
+            .root_transform
+            .x
+            .truncate()
+            .magnitude()
+            .max(self.root_transform.y.truncate().magnitude())
+            .max(self.root_transform.z.truncate().magnitude());
+        local_radius * scale
+    }
+
     pub fn update(&mut self, dt: Duration, transform: Matrix4<f32>) {
+        self.update_with_budget(dt, transform, usize::MAX)
+    }
+
+    ///
+    /// update_with_budget
+    ///
+    /// Simulate at most `budget` particles - any over the budget are dropped, and no new ones
+    /// launch until there is room
+    pub fn update_with_budget(&mut self, dt: Duration, transform: Matrix4<f32>, budget: usize) {
+        let max_particles = self.max_particles.min(budget);
+        self.particles.truncate(max_particles);
+
         let delta_time = dt.as_secs_f32();
         self.particles.iter_mut().for_each(|p| {
             p.remaining_life_in_seconds -= delta_time;
@@ -199,7 +308,7 @@ impl ParticleSystem {
         self.launch_time_remaining -= delta_time;
 
         // Check if we should create a new particle
-        if self.particles.len() < self.max_particles && self.launch_time_remaining < 0.0 {
+        if self.particles.len() < max_particles && self.launch_time_remaining < 0.0 {
             self.launch_time_remaining = self.launch_time;
             self.particles.push(create_random_particle(self));
         }
@@ -243,3 +352,18 @@ struct Particle {
     velocity: Vector3<f32>,
     scale: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_particle_budgets() {
+        let requests = vec![(10.0, 50), (1.0, 60), (5.0, 40)];
+
+        // Nearest systems are filled first, the farthest gets what's left
+        assert_eq!(allocate_particle_budgets(&requests, 120), vec![20, 60, 40]);
+        assert_eq!(allocate_particle_budgets(&requests, 1000), vec![50, 60, 40]);
+        assert_eq!(allocate_particle_budgets(&requests, 0), vec![0, 0, 0]);
+    }
+}
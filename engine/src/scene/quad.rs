@@ -1,5 +1,5 @@
 extern crate gl;
-use cgmath::{vec2, vec3};
+use cgmath::{Vector2, vec2, vec3};
 use once_cell::sync::OnceCell;
 
 pub use crate::scene::Geometry;
@@ -14,49 +14,55 @@ pub fn create() -> Quad {
     Quad
 }
 
+///
+/// create_with_uvs
+///
+/// A quad mesh showing just part of a texture - ie, a region of a texture atlas
+pub fn create_with_uvs(uv_min: Vector2<f32>, uv_max: Vector2<f32>) -> Mesh {
+    // Normal pointing forward (positive Z direction)
+    let normal = vec3(0.0, 0.0, 1.0);
+
+    let vertices: [VertexPositionTextureNormal; 6] = [
+        // Tri 1
+        VertexPositionTextureNormal {
+            position: vec3(-0.5, -0.5, 0.0),
+            uv: vec2(uv_min.x, uv_min.y),
+            normal,
+        },
+        VertexPositionTextureNormal {
+            position: vec3(-0.5, 0.5, 0.0),
+            uv: vec2(uv_min.x, uv_max.y),
+            normal,
+        },
+        VertexPositionTextureNormal {
+            position: vec3(0.5, 0.5, 0.0),
+            uv: vec2(uv_max.x, uv_max.y),
+            normal,
+        },
+        // Tri2
+        VertexPositionTextureNormal {
+            position: vec3(0.5, -0.5, 0.0),
+            uv: vec2(uv_max.x, uv_min.y),
+            normal,
+        },
+        VertexPositionTextureNormal {
+            position: vec3(0.5, 0.5, 0.0),
+            uv: vec2(uv_max.x, uv_max.y),
+            normal,
+        },
+        VertexPositionTextureNormal {
+            position: vec3(-0.5, -0.5, 0.0),
+            uv: vec2(uv_min.x, uv_min.y),
+            normal,
+        },
+    ];
+
+    mesh::create(vertices.to_vec())
+}
+
 impl Geometry for Quad {
     fn draw(&self) {
-        let mesh = QUAD_GEOMETRY.get_or_init(|| {
-            // Normal pointing forward (positive Z direction)
-            let normal = vec3(0.0, 0.0, 1.0);
-
-            let vertices: [VertexPositionTextureNormal; 6] = [
-                // Tri 1
-                VertexPositionTextureNormal {
-                    position: vec3(-0.5, -0.5, 0.0),
-                    uv: vec2(0.0, 0.0),
-                    normal,
-                },
-                VertexPositionTextureNormal {
-                    position: vec3(-0.5, 0.5, 0.0),
-                    uv: vec2(0.0, 1.0),
-                    normal,
-                },
-                VertexPositionTextureNormal {
-                    position: vec3(0.5, 0.5, 0.0),
-                    uv: vec2(1.0, 1.0),
-                    normal,
-                },
-                // Tri2
-                VertexPositionTextureNormal {
-                    position: vec3(0.5, -0.5, 0.0),
-                    uv: vec2(1.0, 0.0),
-                    normal,
-                },
-                VertexPositionTextureNormal {
-                    position: vec3(0.5, 0.5, 0.0),
-                    uv: vec2(1.0, 1.0),
-                    normal,
-                },
-                VertexPositionTextureNormal {
-                    position: vec3(-0.5, -0.5, 0.0),
-                    uv: vec2(0.0, 0.0),
-                    normal,
-                },
-            ];
-
-            mesh::create(vertices.to_vec())
-        });
+        let mesh = QUAD_GEOMETRY.get_or_init(|| create_with_uvs(vec2(0.0, 0.0), vec2(1.0, 1.0)));
 
         mesh.draw();
     }
@@ -0,0 +1,88 @@
+///
+/// render_order.rs
+///
+/// Hints for the order scene objects are drawn in. In each pass, objects are drawn layer by layer
+/// (lowest first). In the transparent pass, world objects are also sorted back-to-front, so blended
+/// windows and particle clouds composite correctly regardless of the order they were added in.
+/// Objects in the other layers keep the order they were added in.
+///
+use std::cmp::Ordering;
+
+use cgmath::{Matrix4, Vector3};
+
+use super::SceneObject;
+
+/// Drawn before everything else, like skies
+pub const BACKGROUND: i32 = -100;
+/// The default - level geometry, models and effects
+pub const WORLD: i32 = 0;
+/// Drawn over the world, like the item outline
+pub const OVERLAY: i32 = 100;
+/// Screen-space HUD elements and text, drawn last
+pub const SCREEN_SPACE: i32 = 200;
+
+///
+/// opaque_order
+///
+/// Indices of the objects in the order to draw them in the opaque pass - by layer, otherwise as added
+pub fn opaque_order(objects: &[SceneObject]) -> Vec<usize> {
+    let mut order = (0..objects.len()).collect::<Vec<_>>();
+    order.sort_by_key(|idx| objects[*idx].render_order);
+    order
+}
+
+///
+/// transparent_order
+///
+/// Indices of the objects in the order to draw them in the transparent pass - by layer, and farthest
+/// from the camera first within the world layer
+pub fn transparent_order(objects: &[SceneObject], view: &Matrix4<f32>) -> Vec<usize> {
+    let keys = objects
+        .iter()
+        .map(|obj| (obj.render_order, obj.get_world_position()))
+        .collect::<Vec<_>>();
+    back_to_front(&keys, view)
+}
+
+fn back_to_front(keys: &[(i32, Vector3<f32>)], view: &Matrix4<f32>) -> Vec<usize> {
+    // The camera looks down -z in view space, so the farthest objects have the lowest z
+    let depths = keys
+        .iter()
+        .map(|(_, position)| (view * position.extend(1.0)).z)
+        .collect::<Vec<_>>();
+
+    let mut order = (0..keys.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        let (layer_a, layer_b) = (keys[*a].0, keys[*b].0);
+        layer_a.cmp(&layer_b).then_with(|| {
+            if layer_a == WORLD {
+                depths[*a].total_cmp(&depths[*b])
+            } else {
+                Ordering::Equal
+            }
+        })
+    });
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::vec3;
+
+    #[test]
+    fn test_back_to_front() {
+        let view = Matrix4::from_translation(vec3(0.0, 0.0, -1.0));
+        let keys = vec![
+            (SCREEN_SPACE, vec3(0.0, 0.0, 100.0)),
+            (WORLD, vec3(0.0, 0.0, -2.0)),
+            (SCREEN_SPACE, vec3(0.0, 0.0, -100.0)),
+            (WORLD, vec3(0.0, 0.0, -10.0)),
+            (OVERLAY, vec3(0.0, 0.0, 0.0)),
+            (WORLD, vec3(0.0, 0.0, -5.0)),
+        ];
+
+        // World objects farthest first, then the overlay, then screen-space objects as added
+        assert_eq!(back_to_front(&keys, &view), vec![3, 5, 1, 4, 0, 2]);
+    }
+}
@@ -0,0 +1,159 @@
+///
+/// scene_graph.rs
+///
+/// Parent-child transforms for things that follow something else - a weapon in a creature's hand,
+/// a panel on the player's arm. Roots are placed in the world; children are placed relative to their
+/// parent, and their world transforms are resolved through the chain of parents.
+///
+/// `TransformSceneObject` does the same for scene objects when rendering - this is for hierarchies
+/// that are maintained across frames, keyed by whatever identifies a node (ie, an entity id).
+///
+use std::{collections::HashMap, hash::Hash};
+
+use cgmath::Matrix4;
+
+#[derive(Clone, Copy, Debug)]
+enum Node<K> {
+    Root {
+        transform: Matrix4<f32>,
+    },
+    Child {
+        parent: K,
+        local_transform: Matrix4<f32>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct SceneGraph<K> {
+    nodes: HashMap<K, Node<K>>,
+}
+
+impl<K: Copy + Eq + Hash> SceneGraph<K> {
+    pub fn new() -> SceneGraph<K> {
+        SceneGraph {
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Place a node directly in the world
+    pub fn set_root(&mut self, key: K, transform: Matrix4<f32>) {
+        self.nodes.insert(key, Node::Root { transform });
+    }
+
+    /// Place a node relative to its parent
+    pub fn attach(&mut self, key: K, parent: K, local_transform: Matrix4<f32>) {
+        self.nodes.insert(
+            key,
+            Node::Child {
+                parent,
+                local_transform,
+            },
+        );
+    }
+
+    /// Remove a node - its children stay attached, but won't resolve until their parent is back
+    pub fn remove(&mut self, key: K) {
+        self.nodes.remove(&key);
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.nodes.contains_key(&key)
+    }
+
+    pub fn parent(&self, key: K) -> Option<K> {
+        match self.nodes.get(&key)? {
+            Node::Root { .. } => None,
+            Node::Child { parent, .. } => Some(*parent),
+        }
+    }
+
+    ///
+    /// world_transform
+    ///
+    /// The node's transform in the world - None if it, or any of its parents, isn't in the graph
+    /// (or the parents form a cycle)
+    pub fn world_transform(&self, key: K) -> Option<Matrix4<f32>> {
+        let mut transform = None::<Matrix4<f32>>;
+        let mut current = key;
+        // A chain longer than the graph must loop back on itself
+        for _ in 0..=self.nodes.len() {
+            match self.nodes.get(&current)? {
+                Node::Root {
+                    transform: root_transform,
+                } => {
+                    return Some(match transform {
+                        Some(local) => root_transform * local,
+                        None => *root_transform,
+                    });
+                }
+                Node::Child {
+                    parent,
+                    local_transform,
+                } => {
+                    transform = Some(match transform {
+                        Some(local) => local_transform * local,
+                        None => *local_transform,
+                    });
+                    current = *parent;
+                }
+            }
+        }
+        None
+    }
+
+    ///
+    /// child_world_transforms
+    ///
+    /// World transforms for every attached node whose chain of parents resolves
+    pub fn child_world_transforms(&self) -> Vec<(K, Matrix4<f32>)> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| matches!(node, Node::Child { .. }))
+            .filter_map(|(key, _)| self.world_transform(*key).map(|xform| (*key, xform)))
+            .collect()
+    }
+}
+
+impl<K: Copy + Eq + Hash> Default for SceneGraph<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{SquareMatrix, vec3};
+
+    #[test]
+    fn test_world_transforms() {
+        let mut graph = SceneGraph::new();
+        graph.set_root("creature", Matrix4::from_translation(vec3(10.0, 0.0, 0.0)));
+        graph.attach(
+            "hand",
+            "creature",
+            Matrix4::from_translation(vec3(0.0, 1.0, 0.0)),
+        );
+        graph.attach(
+            "weapon",
+            "hand",
+            Matrix4::from_translation(vec3(0.0, 0.0, 0.5)),
+        );
+
+        assert_eq!(
+            graph.world_transform("weapon"),
+            Some(Matrix4::from_translation(vec3(10.0, 1.0, 0.5)))
+        );
+        assert_eq!(graph.child_world_transforms().len(), 2);
+
+        // Orphans and cycles don't resolve
+        graph.attach("orphan", "missing", Matrix4::identity());
+        graph.attach("a", "b", Matrix4::identity());
+        graph.attach("b", "a", Matrix4::identity());
+        assert_eq!(graph.world_transform("orphan"), None);
+        assert_eq!(graph.world_transform("a"), None);
+
+        graph.remove("hand");
+        assert_eq!(graph.world_transform("weapon"), None);
+    }
+}
@@ -11,6 +11,7 @@ use crate::engine::EngineRenderContext;
 use crate::texture::TextureTrait;
 use cgmath::Matrix4;
 use cgmath::Vector2;
+use cgmath::Vector4;
 use cgmath::prelude::*;
 use cgmath::vec2;
 use cgmath::vec3;
@@ -27,6 +28,7 @@ use super::TextVertex;
 use super::basic_material;
 use super::mesh;
 use super::quad;
+use super::render_order;
 use super::skinned_material::SkinnedMaterial;
 use crate::materials;
 
@@ -38,6 +40,10 @@ pub struct SceneObject {
     pub local_transform: Matrix4<f32>, //hack...
     pub skinning_data: [Matrix4<f32>; 40],
     pub depth_write: bool,
+    /// Minimum emissivity for this object's material - see `Material::set_emissivity_override`
+    pub emissivity: Option<f32>,
+    /// Layer to draw in - see `render_order`
+    pub render_order: i32,
 }
 
 impl SceneObject {
@@ -56,21 +62,31 @@ impl SceneObject {
             * Matrix4::from_translation(vec3(0.5, 0.5, 0.0));
         let mut ret = Self::new(material, Box::new(mesh));
         ret.set_local_transform(xform);
+        ret.set_render_order(render_order::SCREEN_SPACE);
         ret
     }
     pub fn screen_space_quad(
         texture: Rc<dyn TextureTrait>,
         position: Vector2<f32>,
         size: Vector2<f32>,
+    ) -> SceneObject {
+        Self::screen_space_quad_with_color(texture, position, size, vec4(1.0, 1.0, 1.0, 1.0))
+    }
+    pub fn screen_space_quad_with_color(
+        texture: Rc<dyn TextureTrait>,
+        position: Vector2<f32>,
+        size: Vector2<f32>,
+        color: Vector4<f32>,
     ) -> SceneObject {
         let mesh = quad::create();
-        let material = materials::ScreenSpaceMaterial::create(texture, vec4(1.0, 1.0, 1.0, 1.0));
+        let material = materials::ScreenSpaceMaterial::create(texture, color);
 
         let xform = Matrix4::from_translation(vec3(position.x, position.y, 0.0))
             * Matrix4::from_nonuniform_scale(size.x, size.y, 1.0)
             * Matrix4::from_translation(vec3(0.5, 0.5, 0.0));
         let mut ret = Self::new(material, Box::new(mesh));
         ret.set_local_transform(xform);
+        ret.set_render_order(render_order::SCREEN_SPACE);
         ret
     }
     pub fn screen_space_text(
@@ -136,7 +152,9 @@ impl SceneObject {
             font.get_texture().clone(),
             vec4(1.0, 1.0, 1.0, transparency),
         );
-        Self::new(material, Box::new(mesh))
+        let mut ret = Self::new(material, Box::new(mesh));
+        ret.set_render_order(render_order::SCREEN_SPACE);
+        ret
     }
     pub fn world_space_text(str: &str, font: Rc<Box<dyn Font>>, transparency: f32) -> SceneObject {
         let mut x = 0.0;
@@ -208,6 +226,8 @@ impl SceneObject {
             local_transform: Matrix4::identity(),
             skinning_data: [Matrix4::identity(); 40],
             depth_write: true,
+            emissivity: None,
+            render_order: render_order::WORLD,
         }
     }
 
@@ -224,6 +244,10 @@ impl SceneObject {
                 .initialize(engine_context.is_opengl_es);
         }
 
+        self.material
+            .borrow_mut()
+            .set_emissivity_override(self.emissivity);
+
         let xform = self.transform * self.local_transform;
         if !self.depth_write {
             unsafe { gl::DepthMask(gl::FALSE) };
@@ -250,6 +274,10 @@ impl SceneObject {
         view: &Matrix4<f32>,
         lights: &crate::scene::light::LightArray,
     ) {
+        self.material
+            .borrow_mut()
+            .set_emissivity_override(self.emissivity);
+
         let xform = self.transform * self.local_transform;
         if self.material.borrow().draw_transparent(
             render_context,
@@ -295,6 +323,8 @@ impl SceneObject {
             local_transform: Matrix4::identity(),
             skinning_data: [Matrix4::identity(); 40],
             depth_write: true,
+            emissivity: None,
+            render_order: render_order::WORLD,
         }
     }
 
@@ -306,6 +336,8 @@ impl SceneObject {
             local_transform: self.local_transform,
             skinning_data: self.skinning_data,
             depth_write: self.depth_write,
+            emissivity: self.emissivity,
+            render_order: self.render_order,
         }
     }
 
@@ -313,6 +345,14 @@ impl SceneObject {
         self.depth_write = enabled;
     }
 
+    pub fn set_emissivity(&mut self, emissivity: Option<f32>) {
+        self.emissivity = emissivity;
+    }
+
+    pub fn set_render_order(&mut self, render_order: i32) {
+        self.render_order = render_order;
+    }
+
     pub fn set_skinned_transparency(&mut self, transparency: Option<f32>) {
         if let Some(material) = self
             .material
@@ -12,146 +12,9 @@ use c_string::*;
 use cgmath::Matrix4;
 use cgmath::prelude::*;
 
-use once_cell::sync::OnceCell;
+use crate::shader_library::ReloadableProgram;
 use std::any::Any;
 
-// Unified shader for single-pass lighting with up to 6 spotlights (skinned version)
-const UNIFIED_VERTEX_SHADER_SOURCE: &str = r#"
-        layout (location = 0) in vec3 inPos;
-        layout (location = 1) in vec2 inTex;
-        layout (location = 2) in ivec4 bone_ids;
-        layout (location = 3) in vec4 bone_weights;
-        layout (location = 4) in vec3 inNormal;
-
-        uniform mat4 world;
-        uniform mat4 view;
-        uniform mat4 projection;
-        uniform mat4 bone_matrices[40];
-
-        out vec2 texCoord;
-        out vec3 worldPos;
-        out vec3 worldNormal;
-
-        void main() {
-            texCoord = inTex;
-
-            // Apply weighted bone transformations to position and normal
-            vec4 skinnedPos = vec4(0.0);
-            vec3 skinnedNormal = vec3(0.0);
-
-            // Blend up to 4 bones based on weights
-            if (bone_weights.x > 0.0) {
-                skinnedPos += bone_weights.x * (bone_matrices[bone_ids.x] * vec4(inPos, 1.0));
-                skinnedNormal += bone_weights.x * (mat3(bone_matrices[bone_ids.x]) * inNormal);
-            }
-            if (bone_weights.y > 0.0) {
-                skinnedPos += bone_weights.y * (bone_matrices[bone_ids.y] * vec4(inPos, 1.0));
-                skinnedNormal += bone_weights.y * (mat3(bone_matrices[bone_ids.y]) * inNormal);
-            }
-            if (bone_weights.z > 0.0) {
-                skinnedPos += bone_weights.z * (bone_matrices[bone_ids.z] * vec4(inPos, 1.0));
-                skinnedNormal += bone_weights.z * (mat3(bone_matrices[bone_ids.z]) * inNormal);
-            }
-            if (bone_weights.w > 0.0) {
-                skinnedPos += bone_weights.w * (bone_matrices[bone_ids.w] * vec4(inPos, 1.0));
-                skinnedNormal += bone_weights.w * (mat3(bone_matrices[bone_ids.w]) * inNormal);
-            }
-
-            // Fallback to original position if no valid bones
-            vec4 mod_position = (skinnedPos.w > 0.0) ? skinnedPos : vec4(inPos, 1.0);
-            vec3 mod_normal = (length(skinnedNormal) > 0.0) ? normalize(skinnedNormal) : inNormal;
-
-            // Transform to world space
-            vec4 worldPosition = world * mod_position;
-            worldPos = worldPosition.xyz;
-            worldNormal = normalize(mat3(world) * mod_normal);
-
-            gl_Position = projection * view * worldPosition;
-        }
-"#;
-
-const UNIFIED_FRAGMENT_SHADER_SOURCE: &str = r#"
-        out vec4 fragColor;
-
-        in vec2 texCoord;
-        in vec3 worldPos;
-        in vec3 worldNormal;
-
-        // Material properties
-        uniform sampler2D texture1;
-        uniform float emissivity;
-        uniform float transparency;
-
-        // Spotlight array uniforms (up to 6 spotlights)
-        uniform vec3 spotlightPos[6];
-        uniform vec4 spotlightColorIntensity[6];  // RGB + intensity
-        uniform vec3 spotlightDirection[6];
-        uniform float spotlightInnerAngle[6];
-        uniform float spotlightOuterAngle[6];
-        uniform float spotlightRange[6];
-
-        // Calculate spotlight contribution
-        vec3 calculateSpotlight(int i, vec3 worldPos, vec3 normal, vec3 texColor) {
-            // Skip if light has zero intensity
-            if (spotlightColorIntensity[i].w <= 0.0) {
-                return vec3(0.0);
-            }
-
-            vec3 lightVec = spotlightPos[i] - worldPos;
-            float distance = length(lightVec);
-
-            // Range check
-            if (distance > spotlightRange[i]) {
-                return vec3(0.0);
-            }
-
-            vec3 lightDir = normalize(lightVec);
-
-            // Cone attenuation for spotlight
-            float cosOuterCone = cos(spotlightOuterAngle[i]);
-            float cosInnerCone = cos(spotlightInnerAngle[i]);
-            float spotFactor = dot(-lightDir, normalize(spotlightDirection[i]));
-
-            if (spotFactor < cosOuterCone) {
-                return vec3(0.0);
-            }
-
-            float coneAttenuation = 1.0;
-            if (spotFactor < cosInnerCone) {
-                coneAttenuation = (spotFactor - cosOuterCone) / (cosInnerCone - cosOuterCone);
-            }
-
-            // Distance attenuation
-            float distanceAttenuation = 1.0 / (1.0 + 0.1 * distance + 0.01 * distance * distance);
-
-            // Diffuse lighting
-            float lambertian = max(dot(normal, lightDir), 0.0);
-
-            // Combine all factors
-            return texColor * spotlightColorIntensity[i].rgb * spotlightColorIntensity[i].w
-                   * lambertian * coneAttenuation * distanceAttenuation;
-        }
-
-        void main() {
-            vec4 texColor = texture(texture1, texCoord);
-            if (texColor.a < 0.1) discard;
-
-            // Base material color (ambient)
-            vec3 finalColor = texColor.rgb * 0.5;
-
-            // Add emissive contribution
-            finalColor += texColor.rgb * emissivity;
-
-            // Calculate contribution from all 6 spotlights
-            vec3 normal = normalize(worldNormal);
-            for (int i = 0; i < 6; i++) {
-                finalColor += calculateSpotlight(i, worldPos, normal, texColor.rgb);
-            }
-
-            fragColor = vec4(finalColor, texColor.a * (1.0 - transparency));
-        }
-"#;
-
 struct UnifiedUniforms {
     // Basic transformation matrices
     world_loc: i32,
@@ -174,12 +37,92 @@ struct UnifiedUniforms {
     spotlight_range_loc: [i32; 6],
 }
 
-static UNIFIED_SHADER_PROGRAM: OnceCell<(ShaderProgram, UnifiedUniforms)> = OnceCell::new();
+// Unified shader for single-pass lighting with up to 6 spotlights (skinned version) - sources in engine/shaders
+static UNIFIED_SHADER_PROGRAM: ReloadableProgram<UnifiedUniforms> =
+    ReloadableProgram::new("skinned.vert", "skinned.frag", locate_uniforms);
+
+fn locate_uniforms(shader: &ShaderProgram) -> UnifiedUniforms {
+    unsafe {
+        // Get uniform locations for all shader variables
+        let mut bone_matrices_locs = [0i32; 40];
+        for i in 0..40 {
+            let name = format!("bone_matrices[{i}]");
+            let c_str = CString::new(name).unwrap();
+            bone_matrices_locs[i] = gl::GetUniformLocation(shader.gl_id, c_str.as_ptr());
+        }
+
+        let uniforms = UnifiedUniforms {
+            // Basic transformation matrices
+            world_loc: gl::GetUniformLocation(shader.gl_id, c_str!("world").as_ptr()),
+            view_loc: gl::GetUniformLocation(shader.gl_id, c_str!("view").as_ptr()),
+            projection_loc: gl::GetUniformLocation(shader.gl_id, c_str!("projection").as_ptr()),
+
+            // Material properties
+            emissivity_loc: gl::GetUniformLocation(shader.gl_id, c_str!("emissivity").as_ptr()),
+            transparency_loc: gl::GetUniformLocation(shader.gl_id, c_str!("transparency").as_ptr()),
+
+            // Bone matrices
+            bone_matrices_locs,
+
+            // Spotlight array uniforms (6 spotlights)
+            spotlight_pos_loc: [
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[0]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[1]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[2]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[3]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[4]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[5]").as_ptr()),
+            ],
+            spotlight_color_intensity_loc: [
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightColorIntensity[0]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightColorIntensity[1]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightColorIntensity[2]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightColorIntensity[3]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightColorIntensity[4]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightColorIntensity[5]").as_ptr()),
+            ],
+            spotlight_direction_loc: [
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightDirection[0]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightDirection[1]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightDirection[2]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightDirection[3]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightDirection[4]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightDirection[5]").as_ptr()),
+            ],
+            spotlight_inner_angle_loc: [
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightInnerAngle[0]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightInnerAngle[1]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightInnerAngle[2]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightInnerAngle[3]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightInnerAngle[4]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightInnerAngle[5]").as_ptr()),
+            ],
+            spotlight_outer_angle_loc: [
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightOuterAngle[0]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightOuterAngle[1]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightOuterAngle[2]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightOuterAngle[3]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightOuterAngle[4]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightOuterAngle[5]").as_ptr()),
+            ],
+            spotlight_range_loc: [
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[0]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[1]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[2]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[3]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[4]").as_ptr()),
+                gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[5]").as_ptr()),
+            ],
+        };
+        uniforms
+    }
+}
 
 pub struct SkinnedMaterial {
     has_initialized: bool,
     diffuse_texture: Rc<dyn TextureTrait>,
     emissivity: f32,
+    emissivity_override: Option<f32>,
     transparency: f32,
     base_transparency: f32,
 }
@@ -189,6 +132,13 @@ impl SkinnedMaterial {
         self.transparency > 0.01
     }
 
+    fn effective_emissivity(&self) -> f32 {
+        match self.emissivity_override {
+            Some(emissivity) => self.emissivity.max(emissivity),
+            None => self.emissivity,
+        }
+    }
+
     pub fn set_transparency_override(&mut self, transparency: f32) {
         self.transparency = transparency.clamp(0.0, 1.0);
     }
@@ -205,9 +155,8 @@ impl SkinnedMaterial {
         skinning_data: &[Matrix4<f32>],
         lights: &crate::scene::light::LightArray,
     ) {
-        let (shader_program, uniforms) = UNIFIED_SHADER_PROGRAM
-            .get()
-            .expect("unified shader not compiled");
+        let program = UNIFIED_SHADER_PROGRAM.get();
+        let (shader_program, uniforms) = &*program;
         self.diffuse_texture.bind0(render_context);
         unsafe {
             gl::UseProgram(shader_program.gl_id);
@@ -221,7 +170,7 @@ impl SkinnedMaterial {
 
             // Set material properties
             gl::Uniform1f(uniforms.transparency_loc, self.transparency);
-            gl::Uniform1f(uniforms.emissivity_loc, self.emissivity);
+            gl::Uniform1f(uniforms.emissivity_loc, self.effective_emissivity());
 
             // Set bone matrices for skeletal animation
             for i in 0..40 {
@@ -282,183 +231,16 @@ impl Material for SkinnedMaterial {
         self
     }
 
+    fn set_emissivity_override(&mut self, emissivity: Option<f32>) {
+        self.emissivity_override = emissivity;
+    }
+
     fn has_initialized(&self) -> bool {
         self.has_initialized
     }
 
     fn initialize(&mut self, is_opengl_es: bool) {
-        let _ = UNIFIED_SHADER_PROGRAM.get_or_init(|| {
-            // Build and compile unified shader program with 6-spotlight support for skinned meshes
-            let vertex_shader = crate::shader::build(
-                UNIFIED_VERTEX_SHADER_SOURCE,
-                crate::shader::ShaderType::Vertex,
-                is_opengl_es,
-            );
-
-            let fragment_shader = crate::shader::build(
-                UNIFIED_FRAGMENT_SHADER_SOURCE,
-                crate::shader::ShaderType::Fragment,
-                is_opengl_es,
-            );
-
-            unsafe {
-                let shader = crate::shader_program::link(&vertex_shader, &fragment_shader);
-
-                // Get uniform locations for all shader variables
-                let mut bone_matrices_locs = [0i32; 40];
-                for i in 0..40 {
-                    let name = format!("bone_matrices[{i}]");
-                    let c_str = CString::new(name).unwrap();
-                    bone_matrices_locs[i] = gl::GetUniformLocation(shader.gl_id, c_str.as_ptr());
-                }
-
-                let uniforms = UnifiedUniforms {
-                    // Basic transformation matrices
-                    world_loc: gl::GetUniformLocation(shader.gl_id, c_str!("world").as_ptr()),
-                    view_loc: gl::GetUniformLocation(shader.gl_id, c_str!("view").as_ptr()),
-                    projection_loc: gl::GetUniformLocation(
-                        shader.gl_id,
-                        c_str!("projection").as_ptr(),
-                    ),
-
-                    // Material properties
-                    emissivity_loc: gl::GetUniformLocation(
-                        shader.gl_id,
-                        c_str!("emissivity").as_ptr(),
-                    ),
-                    transparency_loc: gl::GetUniformLocation(
-                        shader.gl_id,
-                        c_str!("transparency").as_ptr(),
-                    ),
-
-                    // Bone matrices
-                    bone_matrices_locs,
-
-                    // Spotlight array uniforms (6 spotlights)
-                    spotlight_pos_loc: [
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[0]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[1]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[2]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[3]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[4]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightPos[5]").as_ptr()),
-                    ],
-                    spotlight_color_intensity_loc: [
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightColorIntensity[0]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightColorIntensity[1]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightColorIntensity[2]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightColorIntensity[3]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightColorIntensity[4]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightColorIntensity[5]").as_ptr(),
-                        ),
-                    ],
-                    spotlight_direction_loc: [
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightDirection[0]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightDirection[1]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightDirection[2]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightDirection[3]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightDirection[4]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightDirection[5]").as_ptr(),
-                        ),
-                    ],
-                    spotlight_inner_angle_loc: [
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightInnerAngle[0]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightInnerAngle[1]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightInnerAngle[2]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightInnerAngle[3]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightInnerAngle[4]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightInnerAngle[5]").as_ptr(),
-                        ),
-                    ],
-                    spotlight_outer_angle_loc: [
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightOuterAngle[0]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightOuterAngle[1]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightOuterAngle[2]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightOuterAngle[3]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightOuterAngle[4]").as_ptr(),
-                        ),
-                        gl::GetUniformLocation(
-                            shader.gl_id,
-                            c_str!("spotlightOuterAngle[5]").as_ptr(),
-                        ),
-                    ],
-                    spotlight_range_loc: [
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[0]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[1]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[2]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[3]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[4]").as_ptr()),
-                        gl::GetUniformLocation(shader.gl_id, c_str!("spotlightRange[5]").as_ptr()),
-                    ],
-                };
-                (shader, uniforms)
-            }
-        });
+        UNIFIED_SHADER_PROGRAM.initialize(is_opengl_es);
 
         self.has_initialized = true;
     }
@@ -518,6 +300,7 @@ impl SkinnedMaterial {
             diffuse_texture,
             has_initialized: false,
             emissivity,
+            emissivity_override: None,
             transparency,
             base_transparency: transparency,
         })
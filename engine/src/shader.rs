@@ -1,3 +1,4 @@
+use crate::EngineError;
 use crate::render_log;
 use gl::types;
 use gl::types::*;
@@ -21,15 +22,54 @@ pub enum ShaderType {
 }
 
 pub fn build(shader_contents: &str, shader_type: ShaderType, is_es: bool) -> Shader {
+    let (shader, maybe_error) = compile(shader_contents, shader_type, is_es);
+    if let Some(error) = maybe_error {
+        render_log!(ERROR, "{}", error);
+    }
+    shader
+}
+
+///
+/// try_build
+///
+/// Like `build`, but returns the error instead of logging it - for recompiling shaders at
+/// runtime, where a broken shader shouldn't replace a working one
+pub fn try_build(
+    shader_contents: &str,
+    shader_type: ShaderType,
+    is_es: bool,
+) -> Result<Shader, EngineError> {
+    match compile(shader_contents, shader_type, is_es) {
+        (shader, None) => Ok(shader),
+        (_, Some(error)) => Err(EngineError::Shader(error)),
+    }
+}
+
+fn compile(
+    shader_contents: &str,
+    shader_type: ShaderType,
+    is_es: bool,
+) -> (Shader, Option<String>) {
     let (gl_shader_type, gl_shader_description) = match shader_type {
         ShaderType::Fragment => (gl::FRAGMENT_SHADER, "FRAGMENT"),
         ShaderType::Vertex => (gl::VERTEX_SHADER, "VERTEX"),
     };
 
+    // Resolve any #include's - on failure, compile as-is so the error still shows up
+    let (preprocessed, mut maybe_error) = match crate::shader_library::preprocess(shader_contents) {
+        Ok(preprocessed) => (preprocessed, None),
+        Err(err) => (
+            shader_contents.to_owned(),
+            Some(format!(
+                "Shader preprocessing failed for {gl_shader_description}: {err}"
+            )),
+        ),
+    };
+
     let shader;
     unsafe {
         let mut success = 0;
-        let converted_fragment = convert(shader_contents, is_es).expect("Error compiling shader.");
+        let converted_fragment = convert(&preprocessed, is_es).expect("Error compiling shader.");
         shader = gl::CreateShader(gl_shader_type);
         let c_str_frag = CString::new(converted_fragment.as_bytes()).unwrap();
         let mut info_log = vec![0u8; 512 - 1]; // Initialize with zeros
@@ -44,16 +84,15 @@ pub fn build(shader_contents: &str, shader_type: ShaderType, is_es: bool) -> Sha
                 ptr::null_mut(),
                 info_log.as_mut_ptr() as *mut GLchar,
             );
-            render_log!(
-                ERROR,
+            maybe_error = maybe_error.or(Some(format!(
                 "Shader compilation failed for {}: {}",
                 gl_shader_description,
-                String::from_utf8_lossy(&info_log)
-            );
+                String::from_utf8_lossy(&info_log).trim_end_matches('\0')
+            )));
         }
     }
 
-    Shader { gl_id: shader }
+    (Shader { gl_id: shader }, maybe_error)
 }
 
 /**
@@ -0,0 +1,380 @@
+///
+/// shader_library.rs
+///
+/// GLSL sources for the lit materials, kept as files under `engine/shaders` - shared code
+/// (spotlights, skinning) lives in `.glsl` files pulled in with `#include "name.glsl"`, so the
+/// materials stay in sync.
+///
+/// The sources are built into the engine, but `watch` points the library at a directory of
+/// shaders on disk instead - those are used in place of the built-in copies, and edits to them
+/// recompile the affected programs at runtime.
+///
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use once_cell::sync::Lazy;
+use tracing::{info, warn};
+
+use crate::{
+    EngineError, EngineResult,
+    shader::{self, ShaderType},
+    shader_program::{self, ShaderProgram},
+};
+
+// How often the shader directory is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Extensions of the files in the shader directory
+const SHADER_EXTENSIONS: [&str; 3] = ["glsl", "vert", "frag"];
+
+const BUILTIN_SHADERS: [(&str, &str); 8] = [
+    ("basic.vert", include_str!("../shaders/basic.vert")),
+    ("basic.frag", include_str!("../shaders/basic.frag")),
+    ("lightmap.vert", include_str!("../shaders/lightmap.vert")),
+    ("lightmap.frag", include_str!("../shaders/lightmap.frag")),
+    ("skinned.vert", include_str!("../shaders/skinned.vert")),
+    ("skinned.frag", include_str!("../shaders/skinned.frag")),
+    ("skinning.glsl", include_str!("../shaders/skinning.glsl")),
+    (
+        "spotlights.glsl",
+        include_str!("../shaders/spotlights.glsl"),
+    ),
+];
+
+// Bumped whenever the sources change, so programs know to rebuild
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+static LIBRARY: Lazy<Mutex<ShaderLibrary>> = Lazy::new(|| Mutex::new(ShaderLibrary::new()));
+
+struct ShaderLibrary {
+    dir: Option<PathBuf>,
+    modified: HashMap<PathBuf, SystemTime>,
+    last_poll: Option<Instant>,
+}
+
+impl ShaderLibrary {
+    fn new() -> ShaderLibrary {
+        ShaderLibrary {
+            dir: None,
+            modified: HashMap::new(),
+            last_poll: None,
+        }
+    }
+}
+
+///
+/// watch
+///
+/// Load shaders from `dir` instead of the built-in copies, recompiling when they change
+pub fn watch(dir: &Path) {
+    let mut library = LIBRARY.lock().unwrap();
+    library.modified = scan_modified_times(dir);
+    library.dir = Some(dir.to_path_buf());
+    library.last_poll = Some(Instant::now());
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    info!(
+        "Watching {} shader files in {}",
+        library.modified.len(),
+        dir.display()
+    );
+}
+
+///
+/// poll_for_changes
+///
+/// Check the watched directory for edited shaders - called once a frame, but only looks at the
+/// files every `POLL_INTERVAL`
+pub fn poll_for_changes() {
+    let mut library = LIBRARY.lock().unwrap();
+    let Some(dir) = library.dir.clone() else {
+        return;
+    };
+
+    let now = Instant::now();
+    if let Some(last_poll) = library.last_poll {
+        if now.duration_since(last_poll) < POLL_INTERVAL {
+            return;
+        }
+    }
+    library.last_poll = Some(now);
+
+    let modified = scan_modified_times(&dir);
+    if modified != library.modified {
+        info!("Shaders changed in {}, recompiling", dir.display());
+        library.modified = modified;
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Changes each time the shader sources do
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+///
+/// source
+///
+/// The source of a shader file - from the watched directory, if there's a copy there
+pub fn source(name: &str) -> Option<String> {
+    let maybe_dir = LIBRARY.lock().unwrap().dir.clone();
+    if let Some(dir) = maybe_dir {
+        match std::fs::read_to_string(dir.join(name)) {
+            Ok(contents) => return Some(contents),
+            Err(err) => warn!(
+                "Unable to read shader {} from {}, using built-in copy: {}",
+                name,
+                dir.display(),
+                err
+            ),
+        }
+    }
+
+    BUILTIN_SHADERS
+        .iter()
+        .find(|(builtin_name, _)| *builtin_name == name)
+        .map(|(_, contents)| contents.to_string())
+}
+
+///
+/// preprocess
+///
+/// Resolve the `#include` directives in a shader, against the library's files
+pub fn preprocess(source: &str) -> Result<String, String> {
+    preprocess_with(source, &self::source)
+}
+
+///
+/// preprocess_with
+///
+/// Replace each `#include "name"` line with the contents of `name`, recursively. Each file is
+/// only included once, so shared files can include each other without duplicate definitions.
+pub fn preprocess_with(
+    source: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut included = HashSet::new();
+    let mut stack = Vec::new();
+    expand(source, lookup, &mut included, &mut stack)
+}
+
+fn expand(
+    source: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    included: &mut HashSet<String>,
+    stack: &mut Vec<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(source.len());
+    for line in source.lines() {
+        let Some(name) = parse_include(line)? else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+
+        if stack.iter().any(|parent| parent == name) {
+            return Err(format!("include cycle: {} -> {}", stack.join(" -> "), name));
+        }
+        if !included.insert(name.to_owned()) {
+            continue;
+        }
+
+        let contents = lookup(name).ok_or_else(|| format!("unknown include: {name}"))?;
+        stack.push(name.to_owned());
+        out.push_str(&expand(&contents, lookup, included, stack)?);
+        stack.pop();
+    }
+    Ok(out)
+}
+
+/// The file named by an `#include` line, or None for any other line
+fn parse_include(line: &str) -> Result<Option<&str>, String> {
+    let Some(rest) = line.trim().strip_prefix("#include") else {
+        return Ok(None);
+    };
+
+    rest.trim()
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .filter(|name| !name.is_empty())
+        .map(Some)
+        .ok_or_else(|| format!("malformed include: {}", line.trim()))
+}
+
+fn scan_modified_times(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        warn!("Unable to read shader directory: {}", dir.display());
+        return HashMap::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SHADER_EXTENSIONS.contains(&ext))
+        })
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+fn source_or_error(name: &str) -> EngineResult<String> {
+    source(name).ok_or_else(|| EngineError::AssetNotFound {
+        name: name.to_owned(),
+    })
+}
+
+struct CompiledProgram<U> {
+    generation: u64,
+    is_opengl_es: bool,
+    program: Arc<(ShaderProgram, U)>,
+}
+
+///
+/// ReloadableProgram
+///
+/// A shader program built from library files, rebuilt when they change. If a change doesn't
+/// compile, the last working program is kept, so a typo doesn't take down the renderer.
+pub struct ReloadableProgram<U> {
+    vertex_name: &'static str,
+    fragment_name: &'static str,
+    locate_uniforms: fn(&ShaderProgram) -> U,
+    compiled: Mutex<Option<CompiledProgram<U>>>,
+}
+
+impl<U> ReloadableProgram<U> {
+    pub const fn new(
+        vertex_name: &'static str,
+        fragment_name: &'static str,
+        locate_uniforms: fn(&ShaderProgram) -> U,
+    ) -> ReloadableProgram<U> {
+        ReloadableProgram {
+            vertex_name,
+            fragment_name,
+            locate_uniforms,
+            compiled: Mutex::new(None),
+        }
+    }
+
+    ///
+    /// initialize
+    ///
+    /// Build the program, if it hasn't been already
+    pub fn initialize(&self, is_opengl_es: bool) {
+        let mut compiled = self.compiled.lock().unwrap();
+        if compiled.is_some() {
+            return;
+        }
+
+        let vertex_source = source(self.vertex_name).unwrap_or_default();
+        let fragment_source = source(self.fragment_name).unwrap_or_default();
+        let vertex_shader = shader::build(&vertex_source, ShaderType::Vertex, is_opengl_es);
+        let fragment_shader = shader::build(&fragment_source, ShaderType::Fragment, is_opengl_es);
+        let program = shader_program::link(&vertex_shader, &fragment_shader);
+        let uniforms = (self.locate_uniforms)(&program);
+
+        *compiled = Some(CompiledProgram {
+            generation: generation(),
+            is_opengl_es,
+            program: Arc::new((program, uniforms)),
+        });
+    }
+
+    ///
+    /// get
+    ///
+    /// The program and its uniform locations - rebuilt first, if the sources have changed
+    pub fn get(&self) -> Arc<(ShaderProgram, U)> {
+        let mut guard = self.compiled.lock().unwrap();
+        let compiled = guard.as_mut().expect("shader program not initialized");
+
+        let current_generation = generation();
+        if compiled.generation != current_generation {
+            compiled.generation = current_generation;
+            match self.rebuild(compiled.is_opengl_es) {
+                Ok(program) => {
+                    info!(
+                        "Recompiled shader program {} + {}",
+                        self.vertex_name, self.fragment_name
+                    );
+                    compiled.program = Arc::new(program);
+                }
+                Err(err) => warn!(
+                    "Unable to recompile {} + {}, keeping previous program: {}",
+                    self.vertex_name, self.fragment_name, err
+                ),
+            }
+        }
+
+        compiled.program.clone()
+    }
+
+    fn rebuild(&self, is_opengl_es: bool) -> EngineResult<(ShaderProgram, U)> {
+        let vertex_source = source_or_error(self.vertex_name)?;
+        let fragment_source = source_or_error(self.fragment_name)?;
+
+        let vertex_shader = shader::try_build(&vertex_source, ShaderType::Vertex, is_opengl_es)?;
+        let fragment_shader =
+            shader::try_build(&fragment_source, ShaderType::Fragment, is_opengl_es)?;
+        let program = shader_program::try_link(&vertex_shader, &fragment_shader)?;
+        let uniforms = (self.locate_uniforms)(&program);
+        Ok((program, uniforms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "a.glsl" => Some("float a;\n#include \"common.glsl\"".to_owned()),
+            "b.glsl" => Some("#include \"common.glsl\"\nfloat b;".to_owned()),
+            "common.glsl" => Some("float common;".to_owned()),
+            "loop.glsl" => Some("#include \"loop2.glsl\"".to_owned()),
+            "loop2.glsl" => Some("#include \"loop.glsl\"".to_owned()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_includes_are_expanded_once() {
+        let source = "#include \"a.glsl\"\n  #include \"b.glsl\"\nvoid main() {}";
+        let out = preprocess_with(source, &lookup).unwrap();
+        assert_eq!(out, "float a;\nfloat common;\nfloat b;\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn test_include_errors() {
+        assert!(preprocess_with("#include \"missing.glsl\"", &lookup).is_err());
+        assert!(preprocess_with("#include missing.glsl", &lookup).is_err());
+        assert!(
+            preprocess_with("#include \"loop.glsl\"", &lookup)
+                .unwrap_err()
+                .contains("cycle")
+        );
+    }
+
+    #[test]
+    fn test_builtin_shaders_resolve() {
+        for (name, contents) in BUILTIN_SHADERS.iter() {
+            let out = preprocess_with(contents, &|name| {
+                BUILTIN_SHADERS
+                    .iter()
+                    .find(|(builtin_name, _)| *builtin_name == name)
+                    .map(|(_, contents)| contents.to_string())
+            });
+            assert!(out.is_ok(), "{name}: {:?}", out.err());
+        }
+    }
+}
@@ -2,6 +2,8 @@ use gl::types::*;
 use std::ptr;
 use tracing::warn;
 
+use crate::EngineError;
+
 pub struct ShaderProgram {
     pub gl_id: u32,
 }
@@ -16,8 +18,31 @@ impl Drop for ShaderProgram {
 use crate::shader::*;
 
 pub fn link(vertex_shader: &Shader, fragment_shader: &Shader) -> ShaderProgram {
+    let (shader_program, maybe_error) = link_program(vertex_shader, fragment_shader);
+    if let Some(error) = maybe_error {
+        warn!("ERROR::SHADER::PROGRAM::COMPILATION_FAILED\n{}", error);
+    }
+    shader_program
+}
+
+/// Like `link`, but returns the error instead of logging it
+pub fn try_link(
+    vertex_shader: &Shader,
+    fragment_shader: &Shader,
+) -> Result<ShaderProgram, EngineError> {
+    match link_program(vertex_shader, fragment_shader) {
+        (shader_program, None) => Ok(shader_program),
+        (_, Some(error)) => Err(EngineError::Shader(format!("link failed: {error}"))),
+    }
+}
+
+fn link_program(
+    vertex_shader: &Shader,
+    fragment_shader: &Shader,
+) -> (ShaderProgram, Option<String>) {
     let mut success = 0;
     let mut info_log = Vec::with_capacity(512);
+    let mut maybe_error = None;
     let shader_program;
     unsafe {
         info_log.set_len(512 - 1); // subtract 1 to skip the trailing null character
@@ -34,14 +59,14 @@ pub fn link(vertex_shader: &Shader, fragment_shader: &Shader) -> ShaderProgram {
                 ptr::null_mut(),
                 info_log.as_mut_ptr() as *mut GLchar,
             );
-            warn!(
-                "ERROR::SHADER::PROGRAM::COMPILATION_FAILED\n{}",
-                String::from_utf8_lossy(&info_log)
-            );
+            maybe_error = Some(String::from_utf8_lossy(&info_log).into_owned());
         }
     }
 
-    ShaderProgram {
-        gl_id: shader_program,
-    }
+    (
+        ShaderProgram {
+            gl_id: shader_program,
+        },
+        maybe_error,
+    )
 }
@@ -1,4 +1,5 @@
 use crate::EngineRenderContext;
+use crate::gpu_memory::{GpuAllocation, GpuResourceKind};
 use crate::texture_format;
 use crate::texture_format::RawTextureData;
 use crate::texture_format::TextureFormat;
@@ -12,6 +13,7 @@ pub struct Texture {
     gl_id: types::GLuint,
     width: u32,
     height: u32,
+    _memory: GpuAllocation,
 }
 
 // Will this cause problems for multi-threading??
@@ -170,6 +172,10 @@ pub fn init_from_memory2(raw_texture_data: RawTextureData, options: &TextureOpti
         gl_id: texture,
         width: raw_texture_data.width,
         height: raw_texture_data.height,
+        _memory: GpuAllocation::new(
+            GpuResourceKind::Texture,
+            raw_texture_data.bytes.len() as u64,
+        ),
     }
     // */
     // Texture { gl_id: 0 }
@@ -127,6 +127,46 @@ where
         let height = img.height();
 
         self.reserve_space(width, height);
+        self.copy_into_atlas(img)
+    }
+
+    ///
+    /// try_pack
+    ///
+    /// Like `pack`, but returns None (leaving the atlas untouched) if there isn't room for the image
+    pub fn try_pack(
+        &mut self,
+        img: &ImageBuffer<PixelFormat, std::vec::Vec<u8>>,
+    ) -> Option<TexturePackResult> {
+        let width = img.width();
+        let height = img.height();
+
+        if width >= self.pixel_width {
+            return None;
+        }
+
+        let starts_new_row = self.current_atlas_pixel_x + width >= self.pixel_width;
+        let y = if starts_new_row {
+            self.current_atlas_pixel_y + self.current_row_max_height
+        } else {
+            self.current_atlas_pixel_y
+        };
+        if y + height >= self.pixel_height {
+            return None;
+        }
+
+        if starts_new_row {
+            self.start_new_row(width, height);
+        }
+        Some(self.copy_into_atlas(img))
+    }
+
+    fn copy_into_atlas(
+        &mut self,
+        img: &ImageBuffer<PixelFormat, std::vec::Vec<u8>>,
+    ) -> TexturePackResult {
+        let width = img.width();
+        let height = img.height();
 
         let current_image = self.atlases.get_mut(0).unwrap();
         // Copy image into atlas at current position
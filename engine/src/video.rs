@@ -0,0 +1,601 @@
+// Pure-Rust .avi playback, for builds without ffmpeg (ie, Android / Quest).
+//
+// Reads the RIFF container directly, and decodes the codecs that don't need a native library:
+// uncompressed (8-bit paletted, 24-bit and 32-bit) and Motion JPEG video, with PCM audio.
+// The original cutscenes are Indeo-encoded - those need ffmpeg, or a mod pack with the
+// videos re-encoded to Motion JPEG.
+
+use std::{fmt, fs, path::Path, time::Duration};
+
+use image::ImageFormat;
+
+use crate::{
+    audio::AudioClip,
+    texture_format::{PixelFormat, RawTextureData},
+};
+
+#[derive(Debug)]
+pub enum VideoError {
+    Io(std::io::Error),
+    InvalidFile(String),
+    UnsupportedCodec(String),
+    DecodeFailed(String),
+}
+
+impl fmt::Display for VideoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoError::Io(err) => write!(f, "unable to read video: {}", err),
+            VideoError::InvalidFile(reason) => write!(f, "not a valid .avi file: {}", reason),
+            VideoError::UnsupportedCodec(codec) => write!(
+                f,
+                "video codec '{}' isn't supported without ffmpeg - re-encode it as Motion JPEG",
+                codec
+            ),
+            VideoError::DecodeFailed(reason) => write!(f, "unable to decode frame: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for VideoError {}
+
+impl From<std::io::Error> for VideoError {
+    fn from(err: std::io::Error) -> Self {
+        VideoError::Io(err)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VideoCodec {
+    Uncompressed,
+    MotionJpeg,
+}
+
+#[derive(Clone, Debug)]
+struct VideoFormat {
+    codec: VideoCodec,
+    width: u32,
+    height: i32,
+    bit_count: u16,
+    palette: Vec<[u8; 3]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AviAudio {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Vec<i16>,
+}
+
+///
+/// AviVideo
+///
+/// A parsed .avi file - the frames are kept encoded, and decoded one at a time during playback
+pub struct AviVideo {
+    bytes: Vec<u8>,
+    format: VideoFormat,
+    frame_duration: Duration,
+    // Byte range of each frame in `bytes` - an empty range repeats the previous frame
+    frames: Vec<(usize, usize)>,
+    audio: Option<AviAudio>,
+}
+
+impl AviVideo {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<AviVideo, VideoError> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"AVI " {
+            return Err(VideoError::InvalidFile(
+                "missing RIFF AVI header".to_owned(),
+            ));
+        }
+
+        let mut parser = AviParser::default();
+        parser.parse_chunks(&bytes, 12, bytes.len())?;
+
+        let video_stream = parser
+            .streams
+            .iter()
+            .position(|stream| stream.stream_type == *b"vids")
+            .ok_or_else(|| VideoError::InvalidFile("no video stream".to_owned()))?;
+        let video = &parser.streams[video_stream];
+        let format = parse_video_format(&video.format)?;
+        let frame_duration = if video.rate == 0 {
+            Duration::from_secs_f64(1.0 / 15.0)
+        } else {
+            Duration::from_secs_f64(video.scale as f64 / video.rate as f64)
+        };
+
+        let audio = parser
+            .streams
+            .iter()
+            .position(|stream| stream.stream_type == *b"auds")
+            .and_then(|audio_stream| {
+                parse_audio(
+                    &parser.streams[audio_stream].format,
+                    parser
+                        .chunks
+                        .iter()
+                        .filter(|chunk| chunk.stream == audio_stream && chunk.kind == *b"wb")
+                        .map(|chunk| &bytes[chunk.start..chunk.end]),
+                )
+            });
+
+        let frames = parser
+            .chunks
+            .iter()
+            .filter(|chunk| {
+                chunk.stream == video_stream && (chunk.kind == *b"db" || chunk.kind == *b"dc")
+            })
+            .map(|chunk| (chunk.start, chunk.end))
+            .collect::<Vec<_>>();
+
+        if frames.is_empty() {
+            return Err(VideoError::InvalidFile("no video frames".to_owned()));
+        }
+
+        Ok(AviVideo {
+            bytes,
+            format,
+            frame_duration,
+            frames,
+            audio,
+        })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.frame_duration * self.frames.len() as u32
+    }
+
+    pub fn audio(&self) -> Option<&AviAudio> {
+        self.audio.as_ref()
+    }
+
+    ///
+    /// decode_frame
+    ///
+    /// Decode the frame at `index` - None for a dropped frame, which repeats the one before it
+    pub fn decode_frame(&self, index: usize) -> Result<Option<RawTextureData>, VideoError> {
+        let (start, end) = self.frames[index];
+        if start == end {
+            return Ok(None);
+        }
+
+        let data = &self.bytes[start..end];
+        let frame = match self.format.codec {
+            VideoCodec::Uncompressed => decode_uncompressed(&self.format, data)?,
+            VideoCodec::MotionJpeg => {
+                let image = image::load_from_memory_with_format(data, ImageFormat::Jpeg)
+                    .map_err(|err| VideoError::DecodeFailed(err.to_string()))?
+                    .to_rgb8();
+                RawTextureData {
+                    width: image.width(),
+                    height: image.height(),
+                    bytes: image.into_raw(),
+                    format: PixelFormat::RGB,
+                }
+            }
+        };
+        Ok(Some(frame))
+    }
+}
+
+///
+/// AviPlayer
+///
+/// Plays back an AviVideo - same interface as the ffmpeg VideoPlayer
+pub struct AviPlayer {
+    video: AviVideo,
+    current_time: Duration,
+    current_frame_index: Option<usize>,
+    current_frame: RawTextureData,
+}
+
+impl AviPlayer {
+    pub fn from_filename(filename: &str) -> Result<AviPlayer, VideoError> {
+        let bytes = fs::read(Path::new(filename))?;
+        AviPlayer::new(AviVideo::from_bytes(bytes)?)
+    }
+
+    pub fn new(video: AviVideo) -> Result<AviPlayer, VideoError> {
+        let mut player = AviPlayer {
+            video,
+            current_time: Duration::ZERO,
+            current_frame_index: None,
+            current_frame: RawTextureData {
+                bytes: vec![0, 0, 0],
+                width: 1,
+                height: 1,
+                format: PixelFormat::RGB,
+            },
+        };
+        player.decode_current_frame()?;
+        Ok(player)
+    }
+
+    ///
+    /// audio_clip
+    ///
+    /// The soundtrack, if the video has a PCM audio stream
+    pub fn audio_clip(&self) -> Option<AudioClip> {
+        self.video.audio().map(|audio| {
+            AudioClip::from_raw(audio.channels, audio.sample_rate, audio.samples.clone())
+        })
+    }
+
+    pub fn advance_by_time(&mut self, time: Duration) {
+        self.current_time += time;
+        if let Err(err) = self.decode_current_frame() {
+            // Keep showing the last good frame
+            tracing::warn!("{}", err);
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_time >= self.video.duration()
+    }
+
+    pub fn get_current_frame(&self) -> RawTextureData {
+        self.current_frame.clone()
+    }
+
+    fn decode_current_frame(&mut self) -> Result<(), VideoError> {
+        let frame_index = ((self.current_time.as_secs_f64()
+            / self.video.frame_duration.as_secs_f64()) as usize)
+            .min(self.video.frame_count() - 1);
+
+        let first_frame_to_decode = match self.current_frame_index {
+            Some(current_frame_index) if current_frame_index == frame_index => return Ok(()),
+            Some(current_frame_index) if current_frame_index < frame_index => frame_index,
+            // Dropped frames repeat an earlier one - walk back to a frame that has data
+            _ => (0..=frame_index)
+                .rev()
+                .find(|index| {
+                    let (start, end) = self.video.frames[*index];
+                    start != end
+                })
+                .unwrap_or(frame_index),
+        };
+
+        for index in first_frame_to_decode..=frame_index {
+            if let Some(frame) = self.video.decode_frame(index)? {
+                self.current_frame = frame;
+            }
+        }
+        self.current_frame_index = Some(frame_index);
+        Ok(())
+    }
+}
+
+struct AviStream {
+    stream_type: [u8; 4],
+    scale: u32,
+    rate: u32,
+    format: Vec<u8>,
+}
+
+struct AviChunk {
+    stream: usize,
+    kind: [u8; 2],
+    start: usize,
+    end: usize,
+}
+
+#[derive(Default)]
+struct AviParser {
+    streams: Vec<AviStream>,
+    chunks: Vec<AviChunk>,
+}
+
+impl AviParser {
+    fn parse_chunks(&mut self, bytes: &[u8], start: usize, end: usize) -> Result<(), VideoError> {
+        let mut offset = start;
+        while offset + 8 <= end {
+            let id: [u8; 4] = bytes[offset..offset + 4].try_into().unwrap();
+            let size = read_u32(bytes, offset + 4) as usize;
+            let data_start = offset + 8;
+            let data_end = data_start + size;
+            if data_end > end {
+                // Truncated files still play up to the last complete chunk
+                break;
+            }
+
+            match &id {
+                b"LIST" if size >= 4 => self.parse_chunks(bytes, data_start + 4, data_end)?,
+                b"strh" if size >= 28 => self.streams.push(AviStream {
+                    stream_type: bytes[data_start..data_start + 4].try_into().unwrap(),
+                    scale: read_u32(bytes, data_start + 20),
+                    rate: read_u32(bytes, data_start + 24),
+                    format: Vec::new(),
+                }),
+                b"strf" => {
+                    if let Some(stream) = self.streams.last_mut() {
+                        stream.format = bytes[data_start..data_end].to_vec();
+                    }
+                }
+                _ => {
+                    // Stream data - two digit stream number, then the chunk type ('00dc', '01wb')
+                    let stream = std::str::from_utf8(&id[0..2])
+                        .ok()
+                        .and_then(|digits| digits.parse::<usize>().ok());
+                    if let Some(stream) = stream {
+                        self.chunks.push(AviChunk {
+                            stream,
+                            kind: [id[2], id[3]],
+                            start: data_start,
+                            end: data_end,
+                        });
+                    }
+                }
+            }
+
+            // Chunks are padded to an even size
+            offset = data_end + (size & 1);
+        }
+        Ok(())
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn parse_video_format(format: &[u8]) -> Result<VideoFormat, VideoError> {
+    // BITMAPINFOHEADER
+    if format.len() < 40 {
+        return Err(VideoError::InvalidFile("missing video format".to_owned()));
+    }
+
+    let header_size = read_u32(format, 0) as usize;
+    let width = read_u32(format, 4);
+    let height = read_u32(format, 8) as i32;
+    let bit_count = read_u16(format, 14);
+    let compression: [u8; 4] = format[16..20].try_into().unwrap();
+
+    let codec = match &compression {
+        [0, 0, 0, 0] | b"DIB " | b"RGB " => VideoCodec::Uncompressed,
+        b"MJPG" | b"mjpg" => VideoCodec::MotionJpeg,
+        _ => {
+            return Err(VideoError::UnsupportedCodec(
+                String::from_utf8_lossy(&compression).into_owned(),
+            ));
+        }
+    };
+
+    if codec == VideoCodec::Uncompressed && !matches!(bit_count, 8 | 24 | 32) {
+        return Err(VideoError::UnsupportedCodec(format!(
+            "uncompressed {}-bit",
+            bit_count
+        )));
+    }
+
+    // RGBQUAD palette entries (blue, green, red, reserved) follow the header
+    let palette = format
+        .get(header_size.min(format.len())..)
+        .unwrap_or_default()
+        .chunks_exact(4)
+        .map(|entry| [entry[2], entry[1], entry[0]])
+        .collect();
+
+    Ok(VideoFormat {
+        codec,
+        width,
+        height,
+        bit_count,
+        palette,
+    })
+}
+
+fn decode_uncompressed(format: &VideoFormat, data: &[u8]) -> Result<RawTextureData, VideoError> {
+    let width = format.width as usize;
+    let height = format.height.unsigned_abs() as usize;
+    let bytes_per_pixel = format.bit_count as usize / 8;
+    // Rows are padded to 4 bytes
+    let stride = (width * bytes_per_pixel).div_ceil(4) * 4;
+    if data.len() < stride * height {
+        return Err(VideoError::DecodeFailed(format!(
+            "expected {} bytes, got {}",
+            stride * height,
+            data.len()
+        )));
+    }
+
+    let mut bytes = Vec::with_capacity(width * height * 3);
+    for row in 0..height {
+        // Positive height is bottom-up
+        let source_row = if format.height > 0 {
+            height - 1 - row
+        } else {
+            row
+        };
+        let row_data = &data[source_row * stride..source_row * stride + width * bytes_per_pixel];
+        for pixel in row_data.chunks_exact(bytes_per_pixel) {
+            match bytes_per_pixel {
+                1 => bytes.extend(
+                    format
+                        .palette
+                        .get(pixel[0] as usize)
+                        .copied()
+                        .unwrap_or([0, 0, 0]),
+                ),
+                // BGR(A)
+                _ => bytes.extend([pixel[2], pixel[1], pixel[0]]),
+            }
+        }
+    }
+
+    Ok(RawTextureData {
+        bytes,
+        width: width as u32,
+        height: height as u32,
+        format: PixelFormat::RGB,
+    })
+}
+
+fn parse_audio<'a>(format: &[u8], chunks: impl Iterator<Item = &'a [u8]>) -> Option<AviAudio> {
+    // WAVEFORMATEX - only PCM is supported
+    if format.len() < 16 || read_u16(format, 0) != 1 {
+        tracing::warn!("Skipping non-PCM audio stream");
+        return None;
+    }
+
+    let channels = read_u16(format, 2);
+    let sample_rate = read_u32(format, 4);
+    let bits_per_sample = read_u16(format, 14);
+
+    let mut samples = Vec::new();
+    for chunk in chunks {
+        match bits_per_sample {
+            8 => samples.extend(chunk.iter().map(|sample| (*sample as i16 - 128) << 8)),
+            16 => samples.extend(
+                chunk
+                    .chunks_exact(2)
+                    .map(|sample| i16::from_le_bytes([sample[0], sample[1]])),
+            ),
+            _ => {
+                tracing::warn!("Skipping {}-bit audio stream", bits_per_sample);
+                return None;
+            }
+        }
+    }
+
+    Some(AviAudio {
+        channels,
+        sample_rate,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend((data.len() as u32).to_le_bytes());
+        bytes.extend(data);
+        if data.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    fn list(list_type: &[u8; 4], chunks: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = list_type.to_vec();
+        for c in chunks {
+            data.extend(c);
+        }
+        chunk(b"LIST", &data)
+    }
+
+    fn stream_header(stream_type: &[u8; 4], scale: u32, rate: u32) -> Vec<u8> {
+        let mut data = stream_type.to_vec();
+        data.extend([0; 16]);
+        data.extend(scale.to_le_bytes());
+        data.extend(rate.to_le_bytes());
+        data.extend([0; 28]);
+        chunk(b"strh", &data)
+    }
+
+    // 2x2, 24-bit, bottom-up, 10 fps, with 16-bit mono audio
+    fn test_avi(video_frames: &[&[u8]]) -> Vec<u8> {
+        let mut bitmap_info = Vec::new();
+        bitmap_info.extend(40u32.to_le_bytes());
+        bitmap_info.extend(2u32.to_le_bytes());
+        bitmap_info.extend(2i32.to_le_bytes());
+        bitmap_info.extend(1u16.to_le_bytes());
+        bitmap_info.extend(24u16.to_le_bytes());
+        bitmap_info.extend([0; 24]);
+
+        let mut wave_format = Vec::new();
+        wave_format.extend(1u16.to_le_bytes());
+        wave_format.extend(1u16.to_le_bytes());
+        wave_format.extend(22050u32.to_le_bytes());
+        wave_format.extend(44100u32.to_le_bytes());
+        wave_format.extend(2u16.to_le_bytes());
+        wave_format.extend(16u16.to_le_bytes());
+
+        let header = list(
+            b"hdrl",
+            &[
+                chunk(b"avih", &[0; 56]),
+                list(
+                    b"strl",
+                    &[stream_header(b"vids", 1, 10), chunk(b"strf", &bitmap_info)],
+                ),
+                list(
+                    b"strl",
+                    &[
+                        stream_header(b"auds", 1, 22050),
+                        chunk(b"strf", &wave_format),
+                    ],
+                ),
+            ],
+        );
+
+        let mut movi = vec![chunk(b"01wb", &[1, 0, 255, 255])];
+        movi.extend(video_frames.iter().map(|frame| chunk(b"00db", frame)));
+
+        let mut data = b"AVI ".to_vec();
+        data.extend(header);
+        data.extend(list(b"movi", &movi));
+        chunk(b"RIFF", &data)
+    }
+
+    // Rows padded to 8 bytes - bottom row (blue, green) first, then the top row (red, white)
+    const FRAME: &[u8] = &[255, 0, 0, 0, 255, 0, 0, 0, 0, 0, 255, 255, 255, 255, 0, 0];
+
+    #[test]
+    fn test_decodes_uncompressed_frames_and_pcm_audio() {
+        let video = AviVideo::from_bytes(test_avi(&[FRAME, &[]])).unwrap();
+        assert_eq!(video.frame_count(), 2);
+        assert_eq!(video.duration(), Duration::from_millis(200));
+        assert_eq!(
+            video.audio(),
+            Some(&AviAudio {
+                channels: 1,
+                sample_rate: 22050,
+                samples: vec![1, -1],
+            })
+        );
+
+        let frame = video.decode_frame(0).unwrap().unwrap();
+        assert_eq!((frame.width, frame.height), (2, 2));
+        assert_eq!(
+            frame.bytes,
+            vec![255, 0, 0, 255, 255, 255, 0, 0, 255, 0, 255, 0]
+        );
+        assert!(video.decode_frame(1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_player_holds_dropped_frames_and_finishes() {
+        let mut player =
+            AviPlayer::new(AviVideo::from_bytes(test_avi(&[FRAME, &[]])).unwrap()).unwrap();
+        let first_frame = player.get_current_frame().bytes;
+
+        player.advance_by_time(Duration::from_millis(150));
+        assert_eq!(player.get_current_frame().bytes, first_frame);
+        assert!(!player.is_finished());
+
+        player.advance_by_time(Duration::from_millis(100));
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_codecs() {
+        let mut avi = test_avi(&[FRAME]);
+        let compression = avi.windows(4).position(|w| w == b"strf").unwrap() + 8 + 16;
+        avi[compression..compression + 4].copy_from_slice(b"IV50");
+        assert!(matches!(
+            AviVideo::from_bytes(avi),
+            Err(VideoError::UnsupportedCodec(codec)) if codec == "IV50"
+        ));
+    }
+}
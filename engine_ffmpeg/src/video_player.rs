@@ -108,6 +108,10 @@ impl VideoPlayer {
         self.current_time += time;
     }
 
+    pub fn is_finished(&self) -> bool {
+        self.current_time >= self.duration
+    }
+
     pub fn get_current_frame(&self) -> RawTextureData {
         let ratio = self.current_time.as_secs_f64() / self.duration.as_secs_f64();
 
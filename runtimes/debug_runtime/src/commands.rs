@@ -3,8 +3,19 @@
 // This module defines the command interface between the HTTP server and game loop,
 // allowing remote control of the running game through a request/response pattern.
 
-use cgmath::Vector3;
+use cgmath::{Quaternion, Vector3, vec2, vec3};
+use engine::{
+    assets::missing_assets::MissingAsset, gpu_memory::GpuMemoryStats, logging::LogEntry,
+    profiler::ProfileStats,
+};
 use serde::{Deserialize, Serialize};
+use shock2vr::{
+    CollisionLayerDump, EntityEvent, ExperimentalFeatureInfo, NamedSpawnPoint, ResourceLeaks,
+    VisibilityEngineKind, VisibilityStats,
+    crash_report::CrashReport,
+    input_context::{Hand, InputContext},
+    statistics::Statistics,
+};
 use tokio::sync::oneshot;
 
 /// Commands that can be sent from HTTP handlers to the game loop
@@ -22,11 +33,11 @@ pub enum RuntimeCommand {
     /// Perform a physics raycast
     RayCast(RayCastRequest, oneshot::Sender<RayCastResult>),
 
-    /// Get current input state
+    /// Get the input fed to the game each frame
     GetInput(oneshot::Sender<InputState>),
 
-    /// Set input channel values
-    SetInput(InputPatch),
+    /// Change the input fed to the game - it's kept for every following frame, until changed again
+    SetInput(InputPatch, oneshot::Sender<InputState>),
 
     /// Move the player to a position
     MovePlayer(Vector3<f32>),
@@ -37,6 +48,27 @@ pub enum RuntimeCommand {
     /// Execute a game command (spawn, save, etc.)
     RunGameCommand(String, Vec<String>, oneshot::Sender<CommandResult>),
 
+    /// Run a developer console command line, ie `spawn pistol`
+    Console(String, oneshot::Sender<CommandResult>),
+
+    /// List the registered console commands
+    ListConsoleCommands(oneshot::Sender<Vec<ConsoleCommandInfo>>),
+
+    /// Get the game speed multiplier
+    GetTimeScale(oneshot::Sender<TimeScaleResult>),
+
+    /// Set the game speed multiplier (clamped to 0.1 - 8)
+    SetTimeScale(f32, oneshot::Sender<TimeScaleResult>),
+
+    /// Get the visibility engine, and its rendered vs culled stats from the last frame
+    GetVisibility(oneshot::Sender<VisibilityResult>),
+
+    /// Switch visibility engines
+    SetVisibilityEngine(VisibilityEngineKind, oneshot::Sender<VisibilityResult>),
+
+    /// Get the resources found belonging to deleted entities - None if the scene doesn't check
+    GetResourceLeaks(oneshot::Sender<Option<ResourceLeaks>>),
+
     /// Pathfinding test command (set_start, set_goal, reset)
     PathfindingTest(String, oneshot::Sender<CommandResult>),
 
@@ -44,9 +76,16 @@ pub enum RuntimeCommand {
     ListEntities {
         limit: Option<usize>,
         filter: Option<String>,
+        radius: Option<f32>,
         reply: oneshot::Sender<EntityListResult>,
     },
 
+    /// Get entity lifecycle events, from a sequence on
+    EntityEvents {
+        since: u64,
+        reply: oneshot::Sender<EntityEventsResult>,
+    },
+
     /// Get detailed information about an entity
     EntityDetail {
         id: i32,
@@ -65,6 +104,21 @@ pub enum RuntimeCommand {
         reply: oneshot::Sender<Option<PhysicsBodyDetailResult>>,
     },
 
+    /// Get crash status and the latest crash report, if any
+    GetCrash(oneshot::Sender<CrashStatus>),
+
+    /// Get the player's gameplay statistics
+    GetStats(oneshot::Sender<Statistics>),
+
+    /// Get the named spawn points in the current mission
+    GetSpawnPoints(oneshot::Sender<Vec<NamedSpawnPoint>>),
+
+    /// Get the known experimental features, and which are enabled
+    GetFeatures(oneshot::Sender<Vec<ExperimentalFeatureInfo>>),
+
+    /// Get the collision layer matrix - which collider profiles collide with each other
+    GetCollisionLayers(oneshot::Sender<CollisionLayerDump>),
+
     /// Shutdown the debug runtime gracefully
     Shutdown,
 }
@@ -92,6 +146,8 @@ pub struct StepResult {
 #[derive(Debug, Deserialize)]
 pub struct ScreenshotSpec {
     pub filename: Option<String>,
+    /// Width and height to render at - defaults to the window size
+    pub resolution: Option<[u32; 2]>,
 }
 
 /// Result of taking a screenshot
@@ -101,6 +157,21 @@ pub struct ScreenshotResult {
     pub full_path: String,
     pub resolution: [u32; 2],
     pub size_bytes: u64,
+    /// Why the screenshot wasn't taken, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ScreenshotResult {
+    pub fn failed(filename: &str, full_path: &str, error: &str) -> ScreenshotResult {
+        ScreenshotResult {
+            filename: filename.to_owned(),
+            full_path: full_path.to_owned(),
+            resolution: [0, 0],
+            size_bytes: 0,
+            error: Some(error.to_owned()),
+        }
+    }
 }
 
 /// Request for physics raycast
@@ -174,13 +245,101 @@ pub struct PhysicsBodyDetailResult {
     pub contact_count: usize,
 }
 
-/// Input channel modifications
+/// A single input channel to set, ie `{"channel": "right_hand.trigger_value", "value": 1.0}`
 #[derive(Debug, Deserialize)]
-pub struct InputPatch {
+pub struct InputChannel {
     pub channel: String,
     pub value: serde_json::Value,
 }
 
+impl InputChannel {
+    /// The patch setting just this channel - `right_hand.trigger_value` is `{"right_hand": {"trigger_value": ..}}`
+    pub fn into_patch(self) -> Result<InputPatch, String> {
+        let Some((device, field)) = self.channel.split_once('.') else {
+            return Err(format!(
+                "Expected a channel like 'right_hand.trigger_value', got '{}'",
+                self.channel
+            ));
+        };
+
+        let patch = serde_json::json!({ device: { field: self.value } });
+        serde_json::from_value(patch)
+            .map_err(|err| format!("Invalid input channel '{}': {}", self.channel, err))
+    }
+}
+
+/// Changes to the input fed to the game - anything left out keeps its current value
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputPatch {
+    pub head: Option<InputHeadPatch>,
+    pub left_hand: Option<InputHandPatch>,
+    pub right_hand: Option<InputHandPatch>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputHeadPatch {
+    pub rotation: Option<[f32; 4]>, // Quaternion [x, y, z, w]
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputHandPatch {
+    pub position: Option<[f32; 3]>,
+    pub rotation: Option<[f32; 4]>, // Quaternion [x, y, z, w]
+    pub thumbstick: Option<[f32; 2]>,
+    pub trigger_value: Option<f32>,
+    pub squeeze_value: Option<f32>,
+    pub a_value: Option<f32>,
+    pub b_value: Option<f32>,
+}
+
+impl InputPatch {
+    pub fn apply(&self, input_context: &mut InputContext) {
+        if let Some(rotation) = self.head.as_ref().and_then(|head| head.rotation) {
+            input_context.head.rotation = quaternion(rotation);
+        }
+        if let Some(hand) = &self.left_hand {
+            hand.apply(&mut input_context.left_hand);
+        }
+        if let Some(hand) = &self.right_hand {
+            hand.apply(&mut input_context.right_hand);
+        }
+    }
+}
+
+impl InputHandPatch {
+    fn apply(&self, hand: &mut Hand) {
+        if let Some([x, y, z]) = self.position {
+            hand.position = vec3(x, y, z);
+        }
+        if let Some(rotation) = self.rotation {
+            hand.rotation = quaternion(rotation);
+        }
+        if let Some([x, y]) = self.thumbstick {
+            hand.thumbstick = vec2(x, y);
+        }
+        if let Some(trigger_value) = self.trigger_value {
+            hand.trigger_value = trigger_value;
+        }
+        if let Some(squeeze_value) = self.squeeze_value {
+            hand.squeeze_value = squeeze_value;
+        }
+        if let Some(a_value) = self.a_value {
+            hand.a_value = a_value;
+        }
+        if let Some(b_value) = self.b_value {
+            hand.b_value = b_value;
+        }
+    }
+}
+
+// [x, y, z, w] to a quaternion
+fn quaternion([x, y, z, w]: [f32; 4]) -> Quaternion<f32> {
+    Quaternion::new(w, x, y, z)
+}
+
 /// Complete input state for reading/setting
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InputState {
@@ -202,6 +361,45 @@ pub struct InputHand {
     pub trigger_value: f32,
     pub squeeze_value: f32,
     pub a_value: f32,
+    #[serde(default)]
+    pub b_value: f32,
+}
+
+impl From<&InputContext> for InputState {
+    fn from(input_context: &InputContext) -> Self {
+        let head_rotation = input_context.head.rotation;
+        Self {
+            head: InputHead {
+                rotation: [
+                    head_rotation.v.x,
+                    head_rotation.v.y,
+                    head_rotation.v.z,
+                    head_rotation.s,
+                ],
+            },
+            left_hand: InputHand::from(&input_context.left_hand),
+            right_hand: InputHand::from(&input_context.right_hand),
+        }
+    }
+}
+
+impl From<&Hand> for InputHand {
+    fn from(hand: &Hand) -> Self {
+        Self {
+            position: [hand.position.x, hand.position.y, hand.position.z],
+            rotation: [
+                hand.rotation.v.x,
+                hand.rotation.v.y,
+                hand.rotation.v.z,
+                hand.rotation.s,
+            ],
+            thumbstick: [hand.thumbstick.x, hand.thumbstick.y],
+            trigger_value: hand.trigger_value,
+            squeeze_value: hand.squeeze_value,
+            a_value: hand.a_value,
+            b_value: hand.b_value,
+        }
+    }
 }
 
 impl Default for InputState {
@@ -231,6 +429,7 @@ impl Default for InputHand {
             trigger_value: 0.0,
             squeeze_value: 0.0,
             a_value: 0.0,
+            b_value: 0.0,
         }
     }
 }
@@ -243,6 +442,28 @@ pub struct CommandResult {
     pub data: Option<serde_json::Value>,
 }
 
+/// A registered console command
+#[derive(Debug, Serialize)]
+pub struct ConsoleCommandInfo {
+    pub name: String,
+    pub usage: String,
+    pub help: String,
+}
+
+/// Current game speed multiplier
+#[derive(Debug, Serialize)]
+pub struct TimeScaleResult {
+    pub time_scale: f32,
+}
+
+/// The selected visibility engine, and how the last frame's culling compared to the frustum - stats
+/// follow a switch once a frame has rendered with the new engine
+#[derive(Debug, Serialize)]
+pub struct VisibilityResult {
+    pub engine: VisibilityEngineKind,
+    pub stats: Option<VisibilityStats>,
+}
+
 /// List of entities
 #[derive(Debug, Serialize)]
 pub struct EntityListResult {
@@ -263,6 +484,14 @@ pub struct EntitySummary {
     pub link_count: usize,
 }
 
+/// Entities created, destroyed, re-modeled or given scripts - poll again with `next_since` for only
+/// the events after these
+#[derive(Debug, Serialize)]
+pub struct EntityEventsResult {
+    pub events: Vec<EntityEvent>,
+    pub next_since: u64,
+}
+
 /// Detailed information about an entity
 #[derive(Debug, Serialize)]
 pub struct EntityDetailResult {
@@ -300,6 +529,8 @@ pub struct FrameSnapshot {
     pub mission: String,
     pub player: PlayerInfo,
     pub entity_count: usize,
+    /// Hash of the simulation state (hex) - identical runs should match frame for frame
+    pub state_hash: Option<String>,
     pub debug_features: Vec<String>,
     pub inputs: InputSnapshot,
 }
@@ -389,3 +620,81 @@ impl FrameSnapshot {
         }
     }
 }
+
+/// Crash status for the /v1/crash endpoint
+#[derive(Debug, Serialize)]
+pub struct CrashStatus {
+    pub crashed: bool,
+    pub report: Option<CrashReport>,
+}
+
+impl CrashStatus {
+    pub fn from_report(report: Option<&CrashReport>) -> Self {
+        Self {
+            crashed: report.is_some(),
+            report: report.cloned(),
+        }
+    }
+}
+
+/// Result of a /v1/logs query
+#[derive(Debug, Serialize)]
+pub struct LogQueryResult {
+    /// Number of entries matching the query, before `limit` was applied
+    pub total_count: usize,
+    pub entries: Vec<LogEntry>,
+}
+
+/// Result of a /v1/profiler query
+#[derive(Debug, Serialize)]
+pub struct ProfilerSnapshot {
+    /// Number of frames the rolling statistics cover
+    pub window_frames: usize,
+    /// Per-label timings, most expensive (on average) first
+    pub systems: Vec<ProfileStats>,
+    /// GPU memory held by live textures and meshes, in bytes
+    pub gpu_memory_bytes: u64,
+    /// GPU memory per kind of resource
+    pub gpu_memory: Vec<GpuMemoryStats>,
+}
+
+/// Result of a /v1/assets/missing query
+#[derive(Debug, Serialize)]
+pub struct MissingAssetsSnapshot {
+    pub total: usize,
+    /// Missing assets, in the order they were first requested
+    pub assets: Vec<MissingAsset>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_patch_keeps_unset_values() {
+        let mut input_context = InputContext::default();
+
+        let patch: InputPatch = serde_json::from_str(
+            r#"{ "right_hand": { "thumbstick": [0.0, 1.0], "trigger_value": 1.0 } }"#,
+        )
+        .unwrap();
+        patch.apply(&mut input_context);
+
+        // A later patch, ie through the single channel endpoint, only changes its own channel
+        let channel = InputChannel {
+            channel: "right_hand.trigger_value".to_owned(),
+            value: serde_json::json!(0.5),
+        };
+        channel.into_patch().unwrap().apply(&mut input_context);
+
+        assert_eq!(input_context.right_hand.thumbstick, vec2(0.0, 1.0));
+        assert_eq!(input_context.right_hand.trigger_value, 0.5);
+        assert_eq!(input_context.left_hand.trigger_value, 0.0);
+
+        let unknown = InputChannel {
+            channel: "right_hand.trigger".to_owned(),
+            value: serde_json::json!(1.0),
+        };
+        assert!(unknown.into_patch().is_err());
+    }
+}
@@ -0,0 +1,70 @@
+// Determinism check: replay the same scripted inputs through two fresh games, and make sure
+// the world-state hash matches on every frame. Replay-based tests are only trustworthy if this holds.
+//
+// Needs the game data and a display (for the GL context), so it's ignored by default:
+//   cargo test -p debug_runtime -- --ignored determinism
+
+use std::time::Duration;
+
+use cgmath::vec2;
+use runtime_core::{DesktopWindow, WindowOptions};
+use shock2vr::{Game, GameOptions, input_context::InputContext, time::Time};
+
+const FRAMES: u32 = 180;
+const DELTA_TIME: f32 = 1.0 / 60.0;
+
+// Walk forward, then turn while walking, then stand still - enough to exercise movement,
+// collision and physics settling
+fn scripted_input(frame: u32) -> InputContext {
+    let mut input_context = InputContext::default();
+    if frame < 60 {
+        input_context.right_hand.thumbstick = vec2(0.0, 1.0);
+    } else if frame < 120 {
+        input_context.right_hand.thumbstick = vec2(0.0, 1.0);
+        input_context.left_hand.thumbstick = vec2(1.0, 0.0);
+    }
+    input_context
+}
+
+fn run_and_hash(engine: &Box<dyn engine::Engine>, mission: &str) -> Vec<u64> {
+    let options = GameOptions {
+        mission: mission.to_owned(),
+        profile: "determinism_test".to_owned(),
+        ..GameOptions::default()
+    };
+    let mut game = Game::init(options, engine.get_storage());
+
+    (0..FRAMES)
+        .map(|frame| {
+            let time = Time {
+                elapsed: Duration::from_secs_f32(DELTA_TIME),
+                total: Duration::from_secs_f32(DELTA_TIME * (frame + 1) as f32),
+            };
+            game.update(&time, &scripted_input(frame), vec![]);
+            game.state_hash()
+                .expect("mission scenes support state hashing")
+        })
+        .collect()
+}
+
+#[test]
+#[ignore = "needs the game data and a display"]
+fn test_identical_inputs_produce_identical_state() {
+    // Keep the window alive for the GL context
+    let _window = DesktopWindow::create(&WindowOptions::new("determinism test", 64, 64).hidden());
+
+    let engine = engine::opengl();
+    let first_run = run_and_hash(&engine, "earth.mis");
+    let second_run = run_and_hash(&engine, "earth.mis");
+
+    for (frame, (first, second)) in first_run.iter().zip(second_run.iter()).enumerate() {
+        assert_eq!(
+            first,
+            second,
+            "world state diverged on frame {} ({} vs {})",
+            frame,
+            shock2vr::state_hash::format_hash(*first),
+            shock2vr::state_hash::format_hash(*second)
+        );
+    }
+}
@@ -15,23 +15,29 @@ use cgmath::Vector3;
 use clap::Parser;
 use serde::Deserialize;
 use serde_json::{Value, json};
-use std::{collections::HashSet, net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, time::Duration};
 use tokio::{signal, sync::mpsc, sync::oneshot};
 use tracing::info;
 
 mod commands;
 use commands::*;
 
+#[cfg(test)]
+mod determinism_tests;
+
 // Game engine imports
 extern crate glfw;
-use self::glfw::{Context, WindowEvent};
-use cgmath::{Quaternion, vec2, vec3};
+use self::glfw::WindowEvent;
+use cgmath::{vec2, vec3};
 use dark::SCALE_FACTOR;
-use engine::{
-    EngineRenderContext, profile, scene::Scene, util::compute_view_matrix_from_render_context,
+use engine::profile;
+use runtime_core::{
+    DesktopWindow, FrameInput, FrameView, RenderedFrame, RuntimeHost, WindowOptions,
 };
 use shock2vr::{
-    Game, GameOptions, SpawnLocation, command::Command, input_context::InputContext, time::Time,
+    CollisionLayerDump, ExperimentalFeatureInfo, ExperimentalFeatures, Game, GameOptions,
+    NamedSpawnPoint, ResourceLeaks, SpawnLocation, VisibilityEngineKind,
+    input_context::InputContext, statistics::Statistics, time::Time,
 };
 
 // Property imports for state queries
@@ -42,6 +48,12 @@ use shipyard::{EntityId, Get, IntoIter, IntoWithId, View};
 const SCR_WIDTH: u32 = 800;
 const SCR_HEIGHT: u32 = 600;
 
+// Where screenshots with a relative filename are saved
+const SCREENSHOTS_DIR: &str = "/tmp/claude";
+
+// Largest screenshot side, in pixels - beyond this most GPUs can't allocate the framebuffer
+const MAX_SCREENSHOT_SIZE: u32 = 8192;
+
 #[derive(Parser)]
 #[command(name = "debug_runtime")]
 #[command(about = "HTTP-controlled game runtime for LLM testing and automation")]
@@ -66,7 +78,7 @@ struct Args {
     #[arg(long)]
     debug_draw: bool,
 
-    /// Show entity IDs
+    /// Start with object labels (entity names and ids) shown - see the `labels` console command
     #[arg(long)]
     debug_show_ids: bool,
 
@@ -78,38 +90,46 @@ struct Args {
     #[arg(long)]
     debug_pathfinding: bool,
 
+    /// Show the frame profiler overlay
+    #[arg(long)]
+    debug_profiler: bool,
+
     /// Save file to load
     #[arg(short, long)]
     save_file: Option<String>,
 
+    /// Continue from an original System Shock 2 save (quest state and inventory) in --mission
+    #[arg(long = "import-ss2-save")]
+    import_ss2_save: Option<String>,
+
+    /// Player profile to use - saves and settings are kept separate per profile
+    #[arg(long, default_value = "default")]
+    profile: String,
+
+    /// Language for UI and game strings (ie, english, german)
+    #[arg(long, default_value = "english")]
+    language: String,
+
+    /// How missions decide what to draw (portal, brute_force, distance_only)
+    #[arg(long = "visibility-engine", default_value = "portal")]
+    visibility_engine: String,
+
     /// Enable experimental features (comma-separated)
     #[arg(long)]
     experimental: Option<String>,
+
+    /// Run without showing or rendering the game - steps only simulate
+    #[arg(long)]
+    headless: bool,
+
+    /// List the known experimental features and exit
+    #[arg(long = "list-experimental")]
+    list_experimental: bool,
 }
 
 /// Parse mission string (supports mission:spawn_location format)
 fn parse_mission(mission: &str) -> (String, SpawnLocation) {
-    if !mission.contains(':') {
-        return (mission.to_owned(), SpawnLocation::MapDefault);
-    }
-    let parts: Vec<&str> = mission.split(':').collect();
-    if parts.len() > 2 {
-        panic!("Unable to parse mission argument: {}", mission);
-    }
-    let mission = parts[0];
-    let spawn_location = if parts[1].contains(',') {
-        let vec_parts: Vec<&str> = parts[1].split(',').collect();
-        if vec_parts.len() != 3 {
-            panic!("Unable to parse spawn location: {}", parts[1]);
-        }
-        let x = vec_parts[0].parse::<f32>().unwrap();
-        let y = vec_parts[1].parse::<f32>().unwrap();
-        let z = vec_parts[2].parse::<f32>().unwrap();
-        SpawnLocation::PositionRotation(vec3(x, y, z), Quaternion::new(1.0, 0.0, 0.0, 0.0))
-    } else {
-        SpawnLocation::MapDefault
-    };
-    (mission.to_owned(), spawn_location)
+    SpawnLocation::parse_mission_argument(mission).unwrap_or_else(|err| panic!("{}", err))
 }
 
 fn main() -> anyhow::Result<()> {
@@ -123,6 +143,11 @@ fn main() -> anyhow::Result<()> {
 
     let args = Args::parse();
 
+    if args.list_experimental {
+        print!("{}", shock2vr::experimental::list_text());
+        return Ok(());
+    }
+
     info!(
         "Starting debug runtime on port {} with mission: {}",
         args.port, args.mission
@@ -157,18 +182,39 @@ async fn start_http_server(
     let app = Router::new()
         .route("/v1/health", get(health_check))
         .route("/v1/info", get(get_info))
+        .route("/v1/crash", get(get_crash_status))
+        .route("/v1/logs", get(get_logs))
+        .route("/v1/profiler", get(get_profiler))
+        .route("/v1/assets/missing", get(get_missing_assets))
+        .route("/v1/stats", get(get_stats))
+        .route("/v1/spawn_points", get(get_spawn_points))
+        .route("/v1/features", get(get_features))
         .route("/v1/step", axum::routing::post(step_frame))
         .route("/v1/shutdown", axum::routing::post(shutdown_server))
         .route("/v1/entities", get(list_entities))
         .route("/v1/entities/:id", get(get_entity_detail))
+        .route("/v1/entity-events", get(get_entity_events))
         .route("/v1/player/position", get(get_player_position))
         .route("/v1/player/teleport", axum::routing::post(teleport_player))
         .route("/v1/physics/raycast", axum::routing::post(perform_raycast))
         .route("/v1/physics/bodies", get(list_physics_bodies))
+        .route("/v1/physics/layers", get(get_collision_layers))
         .route("/v1/physics/bodies/:id", get(get_physics_body_detail))
+        .route("/v1/input", get(get_input_state))
+        .route("/v1/input", axum::routing::post(set_input))
         .route("/v1/control/input", get(get_input_state))
         .route("/v1/control/input", axum::routing::post(set_input_channel))
         .route("/v1/control/command", axum::routing::post(run_game_command))
+        .route("/v1/console", get(list_console_commands))
+        .route("/v1/console", axum::routing::post(run_console))
+        .route("/v1/control/time_scale", get(get_time_scale))
+        .route(
+            "/v1/control/time_scale",
+            axum::routing::post(set_time_scale),
+        )
+        .route("/v1/visibility", get(get_visibility))
+        .route("/v1/resources/leaks", get(get_resource_leaks))
+        .route("/v1/visibility", axum::routing::post(set_visibility_engine))
         .route(
             "/v1/pathfinding-test",
             axum::routing::post(pathfinding_test),
@@ -184,16 +230,36 @@ async fn start_http_server(
     info!("Available endpoints:");
     info!("  GET  /v1/health           - Health check and server status");
     info!("  GET  /v1/info             - Get current game state snapshot");
+    info!("  GET  /v1/crash            - Get crash status and the latest crash report");
+    info!("  GET  /v1/logs             - Query buffered game logs (since_frame, category, level)");
+    info!(
+        "  GET  /v1/profiler         - Per-system frame timings (last, average, p95, max) and GPU memory"
+    );
+    info!(
+        "  GET  /v1/assets/missing   - Assets that failed to load and were replaced by placeholders"
+    );
+    info!(
+        "  GET  /v1/stats            - Gameplay statistics (kills, damage, accuracy) per mission"
+    );
+    info!("  GET  /v1/spawn_points     - Named spawn points usable as mission.mis:name");
+    info!("  GET  /v1/features         - Known experimental features and which are enabled");
     info!("  POST /v1/step             - Step the simulation forward");
     info!("  POST /v1/shutdown         - Shutdown the debug runtime gracefully");
     info!("  GET  /v1/entities         - List entities with optional limit and filter");
     info!("  GET  /v1/entities/{{id}}    - Get detailed entity information");
+    info!("  GET  /v1/entity-events    - Entity lifecycle events (?since=<sequence>)");
     info!("  GET  /v1/player/position  - Get current player position");
     info!("  POST /v1/player/teleport  - Teleport player to coordinates");
     info!("  POST /v1/physics/raycast  - Perform physics raycast for collision testing");
+    info!("  GET  /v1/physics/layers   - Dump the collision layer matrix");
+    info!("  GET  /v1/input            - Input fed to the game each frame (head, hands)");
+    info!("  POST /v1/input            - Patch the input - it persists across steps until changed");
     info!("  GET  /v1/control/input    - Retrieve controller/input state");
-    info!("  POST /v1/control/input    - Update controller/input channels");
+    info!("  POST /v1/control/input    - Update a single controller/input channel");
     info!("  POST /v1/control/command  - Execute gameplay commands (save, spawn, etc.)");
+    info!("  GET  /v1/visibility       - Visibility engine, with rendered vs culled stats");
+    info!("  POST /v1/visibility       - Switch visibility engines (portal, brute_force, ...)");
+    info!("  GET  /v1/resources/leaks  - Resources cleaned up after deleted entities");
     info!("  POST /v1/screenshot       - Capture the current framebuffer");
     info!("");
     info!("Test with: curl http://{}/v1/health", addr);
@@ -213,57 +279,28 @@ async fn start_http_server(
 /// Run the game loop (blocking)
 fn run_game_blocking(
     args: Args,
-    mut command_rx: mpsc::UnboundedReceiver<RuntimeCommand>,
+    command_rx: mpsc::UnboundedReceiver<RuntimeCommand>,
 ) -> anyhow::Result<()> {
     info!("Initializing game engine...");
 
-    // Initialize GLFW
-    info!("Step 1: Initializing GLFW...");
-    let mut glfw = glfw::init(glfw::fail_on_errors)?;
-    info!("GLFW initialized successfully");
-    glfw.window_hint(glfw::WindowHint::ContextVersion(4, 1));
-    glfw.window_hint(glfw::WindowHint::OpenGlProfile(
-        glfw::OpenGlProfileHint::Core,
-    ));
-    #[cfg(target_os = "macos")]
-    glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-    // Create window
-    info!("Step 2: Creating GLFW window...");
-    let (mut window, events) = glfw
-        .create_window(
-            SCR_WIDTH,
-            SCR_HEIGHT,
-            "Debug Runtime - Game View",
-            glfw::WindowMode::Windowed,
-        )
-        .expect("Failed to create GLFW window");
-    info!("GLFW window created successfully");
-
-    info!("Step 3: Setting up OpenGL context...");
-    window.make_current();
-    window.set_key_polling(true);
-    window.set_framebuffer_size_polling(true);
-
-    // Load OpenGL function pointers
-    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-    info!("OpenGL initialized successfully");
+    info!("Step 1: Creating GLFW window...");
+    // Headless still needs a (hidden) window, for the GL context
+    let window_options = WindowOptions::new("Debug Runtime - Game View", SCR_WIDTH, SCR_HEIGHT);
+    let window = if args.headless {
+        DesktopWindow::create(&window_options.hidden())
+    } else {
+        DesktopWindow::create(&window_options)
+    };
+    info!("GLFW window and OpenGL context created successfully");
 
     // Initialize the game engine
-    info!("Step 4: Initializing engine...");
+    info!("Step 2: Initializing engine...");
     let engine = engine::opengl();
     let bundle_storage = engine.get_storage();
     info!("Engine initialized successfully");
 
-    info!("Step 5: Setting up game options...");
-    let experimental_features: HashSet<String> = args
-        .experimental
-        .unwrap_or_default()
-        .split(',')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect();
+    info!("Step 3: Setting up game options...");
+    let experimental_features = ExperimentalFeatures::parse(args.experimental.as_deref());
 
     let (mission, spawn_location) = parse_mission(&args.mission);
     info!("Mission parsed: {} with spawn location", mission);
@@ -272,52 +309,189 @@ fn run_game_blocking(
         mission: mission.clone(),
         spawn_location,
         save_file: args.save_file,
+        import_save: args.import_ss2_save,
+        profile: args.profile,
+        language: args.language,
         debug_draw: args.debug_draw,
         debug_physics: args.debug_physics,
         debug_portals: args.debug_portals,
         debug_show_ids: args.debug_show_ids,
         debug_skeletons: args.debug_skeletons,
         debug_pathfinding: args.debug_pathfinding,
+        debug_profiler: args.debug_profiler,
+        visibility_engine: VisibilityEngineKind::parse(&args.visibility_engine),
         debug_ai: false,
         render_particles: true,
         experimental_features,
         ..GameOptions::default()
     };
 
-    info!("Step 6: Initializing game with mission: {}", mission);
+    info!("Step 4: Initializing game with mission: {}", mission);
+
+    // Always aggregate timings here, so /v1/profiler has data even without the overlay
+    engine::profiler::set_enabled(true);
 
     let mut game = Game::init(options, bundle_storage);
 
     info!("Game initialized successfully with mission: {}", mission);
 
-    let mut last_time = glfw.get_time() as f32;
-    let start_time = last_time;
-
-    // Debug runtime execution control
-    let mut is_paused = true; // Start paused by default
-    let mut step_requested = false;
-    let mut accumulated_time = 0.0f32;
-    let mut shutdown_requested = false;
-    let mut frame_counter = 0u64;
-    let mut frames_to_step = 0u32;
-    let mut target_step_time: Option<f32> = None;
+    let mut host = DebugHost::new(window, command_rx, args.headless);
 
     info!("Starting main game loop...");
     info!("Game is PAUSED by default - use /v1/step to advance frames");
 
-    // Main game loop
-    while !window.should_close() && !shutdown_requested {
-        // Calculate delta time
-        let time = glfw.get_time() as f32;
-        let delta_time = time - last_time;
-        last_time = time;
+    runtime_core::run(&mut host, &mut game, engine.as_ref());
+
+    if host.shutdown_requested {
+        info!("Game loop ended due to shutdown request");
+    } else {
+        info!("Game loop ended due to window close");
+    }
+    Ok(())
+}
+
+///
+/// DebugHost
+///
+/// Drives the game from HTTP commands - paused by default, advancing only when stepped
+struct DebugHost {
+    window: DesktopWindow,
+    command_rx: mpsc::UnboundedReceiver<RuntimeCommand>,
+
+    // Debug runtime execution control
+    is_paused: bool,
+    step_requested: bool,
+    accumulated_time: f32,
+    shutdown_requested: bool,
+    frame_counter: u64,
+    frames_to_step: u32,
+    target_step_time: Option<f32>,
+    crashed: bool,
+    // Skip render-prep and rendering - only the simulation is stepped
+    headless: bool,
+    // Screenshots requested this frame, captured once it's been rendered
+    pending_screenshots: Vec<(ScreenshotSpec, oneshot::Sender<ScreenshotResult>)>,
+    // Input fed to the game every frame - set over HTTP, and held until changed
+    input: InputContext,
+}
+
+impl DebugHost {
+    fn new(
+        window: DesktopWindow,
+        command_rx: mpsc::UnboundedReceiver<RuntimeCommand>,
+        headless: bool,
+    ) -> Self {
+        Self {
+            window,
+            command_rx,
+            is_paused: true, // Start paused by default
+            step_requested: false,
+            accumulated_time: 0.0,
+            shutdown_requested: false,
+            frame_counter: 0,
+            frames_to_step: 0,
+            target_step_time: None,
+            crashed: false,
+            headless,
+            pending_screenshots: Vec::new(),
+            input: InputContext::default(),
+        }
+    }
+
+    fn start_step(&mut self, step_spec: &StepSpec) {
+        match step_spec {
+            StepSpec::Frames { frames } => {
+                self.frames_to_step = *frames;
+                self.target_step_time = None;
+                self.step_requested = true;
+                self.is_paused = false;
+                tracing::info!("Starting step: {} frames", frames);
+            }
+            StepSpec::Duration { duration } => {
+                // Parse duration string using humantime
+                match duration.parse::<humantime::Duration>() {
+                    Ok(parsed_duration) => {
+                        let duration_secs = parsed_duration.as_secs_f32();
+                        self.target_step_time = Some(self.accumulated_time + duration_secs);
+                        self.frames_to_step = 0;
+                        self.step_requested = true;
+                        self.is_paused = false;
+                        tracing::info!("Starting step: {} ({:.3}s)", duration, duration_secs);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to parse duration '{}': {}", duration, e);
+                    }
+                }
+            }
+        }
+    }
+
+    // Advance the step after a frame, and pause again once it's done
+    fn advance_step(&mut self, elapsed: f32) {
+        // Increment frame counter and accumulated time
+        self.frame_counter += 1;
+        self.accumulated_time += elapsed;
+
+        // Check if we should continue stepping or pause
+        let should_continue = if let Some(target_time) = self.target_step_time {
+            // Time-based stepping
+            if self.accumulated_time >= target_time {
+                tracing::info!(
+                    "Time-based step completed: reached {:.3}s after {} frames",
+                    self.accumulated_time,
+                    self.frame_counter
+                );
+                false
+            } else {
+                true
+            }
+        } else if self.frames_to_step > 0 {
+            // Frame-based stepping
+            self.frames_to_step -= 1;
+            if self.frames_to_step == 0 {
+                tracing::info!(
+                    "Frame-based step completed: {} frames, total time: {:.3}s",
+                    self.frame_counter,
+                    self.accumulated_time
+                );
+                false
+            } else {
+                true
+            }
+        } else {
+            // Single frame step (legacy behavior)
+            tracing::info!(
+                "Stepped 1 frame, game paused again. Frame: {}, Total time: {:.3}s",
+                self.frame_counter,
+                self.accumulated_time
+            );
+            false
+        };
+
+        if !should_continue {
+            self.step_requested = false;
+            self.is_paused = true;
+            self.target_step_time = None;
+            self.frames_to_step = 0;
+        }
+    }
+}
+
+impl RuntimeHost for DebugHost {
+    fn now(&self) -> f32 {
+        self.window.now()
+    }
 
+    fn should_stop(&self) -> bool {
+        self.window.should_close() || self.shutdown_requested
+    }
+
+    fn begin_frame(&mut self, game: &mut Game, game_time: Time) -> FrameInput {
         // Process GLFW events
-        glfw.poll_events();
-        for (_, event) in glfw::flush_messages(&events) {
+        for (_, event) in glfw::flush_messages(&self.window.events) {
             match event {
                 WindowEvent::Key(glfw::Key::Escape, _, glfw::Action::Press, _) => {
-                    window.set_should_close(true);
+                    self.window.window.set_should_close(true);
                 }
                 WindowEvent::FramebufferSize(width, height) => unsafe {
                     gl::Viewport(0, 0, width, height);
@@ -326,223 +500,151 @@ fn run_game_blocking(
             }
         }
 
-        // Create minimal input context (no actual input for now)
-        let input_context = InputContext {
-            head: shock2vr::input_context::Head {
-                rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-            },
-            left_hand: shock2vr::input_context::Hand {
-                position: vec3(0.0, 0.0, 0.0),
-                rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-                thumbstick: vec2(0.0, 0.0),
-                trigger_value: 0.0,
-                squeeze_value: 0.0,
-                a_value: 0.0,
-            },
-            right_hand: shock2vr::input_context::Hand {
-                position: vec3(0.0, 0.0, 0.0),
-                rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-                thumbstick: vec2(0.0, 0.0),
-                trigger_value: 0.0,
-                squeeze_value: 0.0,
-                a_value: 0.0,
-            },
-        };
-
-        let game_time = Time {
-            elapsed: Duration::from_secs_f32(delta_time),
-            total: Duration::from_secs_f32(time - start_time),
-        };
-
         // Process commands from HTTP server
-        while let Ok(command) = command_rx.try_recv() {
+        while let Ok(command) = self.command_rx.try_recv() {
             match &command {
-                RuntimeCommand::Step(step_spec, _) => {
-                    match step_spec {
-                        StepSpec::Frames { frames } => {
-                            frames_to_step = *frames;
-                            target_step_time = None;
-                            step_requested = true;
-                            is_paused = false;
-                            tracing::info!("Starting step: {} frames", frames);
-                        }
-                        StepSpec::Duration { duration } => {
-                            // Parse duration string using humantime
-                            match duration.parse::<humantime::Duration>() {
-                                Ok(parsed_duration) => {
-                                    let duration_secs = parsed_duration.as_secs_f32();
-                                    target_step_time = Some(accumulated_time + duration_secs);
-                                    frames_to_step = 0;
-                                    step_requested = true;
-                                    is_paused = false;
-                                    tracing::info!(
-                                        "Starting step: {} ({:.3}s)",
-                                        duration,
-                                        duration_secs
-                                    );
-                                }
-                                Err(e) => {
-                                    tracing::error!(
-                                        "Failed to parse duration '{}': {}",
-                                        duration,
-                                        e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+                RuntimeCommand::Step(step_spec, _) => self.start_step(step_spec),
                 RuntimeCommand::Shutdown => {
-                    shutdown_requested = true;
+                    self.shutdown_requested = true;
                     tracing::info!("Shutdown requested via API");
                 }
                 _ => {}
             }
-            process_command(command, &mut game, &game_time, frame_counter);
-        }
-
-        // No commands for now
-        let commands: Vec<Box<dyn Command>> = vec![];
-
-        // Only update the game if not paused or if step was requested
-        let actual_game_time = if !is_paused || step_requested {
-            profile!(
-                "game.update",
-                game.update(&game_time, &input_context, commands)
-            );
-
-            if step_requested {
-                // Increment frame counter and accumulated time
-                frame_counter += 1;
-                accumulated_time += game_time.elapsed.as_secs_f32();
-
-                // Check if we should continue stepping or pause
-                let should_continue = if let Some(target_time) = target_step_time {
-                    // Time-based stepping
-                    if accumulated_time >= target_time {
-                        tracing::info!(
-                            "Time-based step completed: reached {:.3}s after {} frames",
-                            accumulated_time,
-                            frame_counter
-                        );
-                        false
-                    } else {
-                        true
+            // Input is the host's, not the game's - so it can be read and set even after a crash
+            let command = match command {
+                RuntimeCommand::GetInput(reply) => {
+                    if reply.send(InputState::from(&self.input)).is_err() {
+                        tracing::warn!("Failed to send input state - receiver dropped");
                     }
-                } else if frames_to_step > 0 {
-                    // Frame-based stepping
-                    frames_to_step -= 1;
-                    if frames_to_step == 0 {
-                        tracing::info!(
-                            "Frame-based step completed: {} frames, total time: {:.3}s",
-                            frame_counter,
-                            accumulated_time
-                        );
-                        false
-                    } else {
-                        true
+                    continue;
+                }
+                RuntimeCommand::SetInput(patch, reply) => {
+                    patch.apply(&mut self.input);
+                    if reply.send(InputState::from(&self.input)).is_err() {
+                        tracing::warn!("Failed to send input state - receiver dropped");
                     }
-                } else {
-                    // Single frame step (legacy behavior)
-                    tracing::info!(
-                        "Stepped 1 frame, game paused again. Frame: {}, Total time: {:.3}s",
-                        frame_counter,
-                        accumulated_time
-                    );
-                    false
-                };
-
-                if !should_continue {
-                    step_requested = false;
-                    is_paused = true;
-                    target_step_time = None;
-                    frames_to_step = 0;
+                    continue;
                 }
+                command => command,
+            };
+            if game.crash_report().is_some() {
+                // The world may be inconsistent after a crash, so only answer crash queries.
+                // Dropping the command drops its reply channel, which the handlers report as an error.
+                if let RuntimeCommand::GetCrash(reply) = command {
+                    let status = CrashStatus::from_report(game.crash_report());
+                    if reply.send(status).is_err() {
+                        tracing::warn!("Failed to send crash status - receiver dropped");
+                    }
+                }
+                continue;
+            }
+            if let RuntimeCommand::Screenshot(spec, reply) = command {
+                self.pending_screenshots.push((spec, reply));
+                continue;
             }
-            accumulated_time
+            process_command(command, game, &game_time, self.frame_counter);
+        }
+
+        // Only advance game time if not paused or if step was requested
+        let time = if !self.is_paused || self.step_requested {
+            game_time
         } else {
             // When paused, use zero delta time to prevent any updates
-            let zero_time = Time {
+            Time {
                 elapsed: Duration::from_secs_f32(0.0),
-                total: Duration::from_secs_f32(accumulated_time),
-            };
-            // Still call update with zero time to maintain state consistency
-            profile!(
-                "game.update",
-                game.update(&zero_time, &input_context, commands)
-            );
-            accumulated_time // Use accumulated time, not real time
+                total: Duration::from_secs_f32(self.accumulated_time),
+            }
         };
 
-        // Render the game
-        let ratio = SCR_WIDTH as f32 / SCR_HEIGHT as f32;
-        let projection_matrix: cgmath::Matrix4<f32> =
-            cgmath::perspective(cgmath::Deg(45.0), ratio, 0.1, 1000.0);
-
-        let screen_size = vec2(SCR_WIDTH as f32, SCR_HEIGHT as f32);
-
-        let (mut scene, pawn_offset, pawn_rotation) = profile!("game.render", game.render());
+        // The input set over HTTP persists across steps - commands go through process_command
+        FrameInput::new(time, self.input.clone())
+    }
 
-        // Create a simple render context for debug view
-        let render_context = EngineRenderContext {
-            time: actual_game_time, // Use accumulated game time, not real time
-            camera_offset: pawn_offset,
-            camera_rotation: pawn_rotation,
-            head_offset: vec3(0.0, 1.6 / SCALE_FACTOR, 0.0), // Default head height
-            head_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0), // Identity rotation
-            projection_matrix,
-            screen_size,
+    fn update(&mut self, game: &mut Game, input: FrameInput) {
+        // Still update with zero time when paused, to maintain state consistency
+        // A panic is turned into a crash report, surfaced through /v1/crash
+        let _ = if self.headless {
+            profile!(
+                "game.simulate",
+                game.try_simulate(&input.time, &input.input_context, input.commands)
+            )
+        } else {
+            profile!(
+                "game.update",
+                game.try_update(&input.time, &input.input_context, input.commands)
+            )
         };
+        self.crashed = game.crash_report().is_some();
 
-        let view = compute_view_matrix_from_render_context(&render_context);
-
-        // Render per eye to get the scene objects
-        let per_eye_scene = profile!(
-            "game.render_per_eye",
-            game.render_per_eye(view, projection_matrix, screen_size)
-        );
-
-        // Clear the screen to a visible color first
-        unsafe {
-            gl::ClearColor(0.1, 0.2, 0.3, 1.0); // Dark blue background
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        if self.step_requested {
+            self.advance_step(input.time.elapsed.as_secs_f32());
         }
+    }
 
-        // Combine scene objects
-        scene.extend(per_eye_scene);
+    fn view(&self, game: &Game) -> Option<FrameView> {
+        // After a crash, keep the window and HTTP server alive but stop driving the game
+        if self.headless || game.crash_report().is_some() {
+            return None;
+        }
 
-        // Create the final scene for rendering
-        let mut scene_for_render = Scene::from_objects(scene);
+        Some(FrameView {
+            time: self.accumulated_time, // Use accumulated game time, not real time
+            head_offset: vec3(0.0, 1.6 / SCALE_FACTOR, 0.0), // Default head height
+            head_rotation: self.input.head.rotation,
+            fov: cgmath::Deg(runtime_core::DEFAULT_FOV),
+            screen_size: vec2(SCR_WIDTH as f32, SCR_HEIGHT as f32),
+        })
+    }
 
-        // Add hand spotlights
-        let hand_spotlights = game.get_hand_spotlights();
-        for spotlight in hand_spotlights {
-            scene_for_render.lights_mut().add_spotlight(spotlight);
+    fn end_frame(&mut self, _game: &mut Game, engine: &dyn engine::Engine, frame: &RenderedFrame) {
+        for (spec, reply) in self.pending_screenshots.drain(..) {
+            let result = capture_screenshot(engine, frame, spec);
+            if reply.send(result).is_err() {
+                tracing::warn!("Failed to send screenshot result - receiver dropped");
+            }
         }
+    }
 
-        // Actually render the scene
-        profile!(
-            "engine.render",
-            engine.render(&render_context, &scene_for_render)
-        );
-
-        profile!("game.finish_render", {
-            game.finish_render(view, projection_matrix, screen_size)
-        });
+    fn present(&mut self) {
+        // Nothing was rendered this frame (headless, or crashed) - there's nothing to capture
+        for (spec, reply) in self.pending_screenshots.drain(..) {
+            let (filename, full_path) = screenshot_path(spec.filename);
+            let result = ScreenshotResult::failed(
+                &filename,
+                &full_path.to_string_lossy(),
+                "no frame was rendered",
+            );
+            if reply.send(result).is_err() {
+                tracing::warn!("Failed to send screenshot result - receiver dropped");
+            }
+        }
 
-        // Swap buffers
-        window.swap_buffers();
+        if self.crashed {
+            unsafe {
+                gl::ClearColor(0.3, 0.0, 0.0, 1.0);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            }
+        }
+        self.window.present();
     }
+}
 
-    if shutdown_requested {
-        info!("Game loop ended due to shutdown request");
-    } else {
-        info!("Game loop ended due to window close");
+/// Process a command from the HTTP server
+fn run_console_command(game: &mut Game, line: &str) -> CommandResult {
+    match game.run_console_command(line) {
+        Ok(message) => CommandResult {
+            success: true,
+            message,
+            data: None,
+        },
+        Err(message) => CommandResult {
+            success: false,
+            message,
+            data: None,
+        },
     }
-    Ok(())
 }
 
-/// Process a command from the HTTP server
 fn process_command(command: RuntimeCommand, game: &mut Game, time: &Time, frame_counter: u64) {
     match command {
         RuntimeCommand::GetInfo(reply) => {
@@ -575,47 +677,8 @@ fn process_command(command: RuntimeCommand, game: &mut Game, time: &Time, frame_
                 tracing::warn!("Failed to send step result - receiver dropped");
             }
         }
-        RuntimeCommand::Screenshot(spec, reply) => {
-            let filename = spec.filename.unwrap_or_else(|| {
-                format!(
-                    "screenshot_{}.png",
-                    chrono::Utc::now().format("%Y%m%d_%H%M%S")
-                )
-            });
-
-            // Create directory if it doesn't exist
-            let screenshots_dir = std::path::Path::new("/tmp/claude");
-            std::fs::create_dir_all(screenshots_dir).unwrap_or_else(|e| {
-                tracing::warn!("Failed to create screenshots directory: {}", e);
-            });
-
-            let full_path = screenshots_dir.join(&filename);
-
-            // Capture OpenGL framebuffer
-            let result = match capture_screenshot(&full_path, SCR_WIDTH, SCR_HEIGHT) {
-                Ok(size_bytes) => {
-                    tracing::info!("Screenshot saved to: {}", full_path.display());
-                    ScreenshotResult {
-                        filename: filename.clone(),
-                        full_path: full_path.to_string_lossy().to_string(),
-                        resolution: [SCR_WIDTH, SCR_HEIGHT],
-                        size_bytes,
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to capture screenshot: {}", e);
-                    ScreenshotResult {
-                        filename: filename.clone(),
-                        full_path: full_path.to_string_lossy().to_string(),
-                        resolution: [0, 0],
-                        size_bytes: 0,
-                    }
-                }
-            };
-
-            if let Err(_) = reply.send(result) {
-                tracing::warn!("Failed to send screenshot result - receiver dropped");
-            }
+        RuntimeCommand::Screenshot(..) => {
+            // Screenshots are deferred by DebugHost until the frame is rendered, this is just for completeness
         }
         RuntimeCommand::RayCast(request, reply) => {
             let result = if let Some(debug_scene) = game.debug_scene() {
@@ -705,15 +768,75 @@ fn process_command(command: RuntimeCommand, game: &mut Game, time: &Time, frame_
                 }
             }
         }
-        RuntimeCommand::RunGameCommand(_command, _args, reply) => {
-            // TODO: Implement game command execution
-            let result = CommandResult {
-                success: false,
-                message: "Game commands not yet implemented".to_string(),
-                data: None,
+        RuntimeCommand::RunGameCommand(command, args, reply) => {
+            // Game commands are console commands - `command` is the verb
+            let line = std::iter::once(command)
+                .chain(args)
+                .collect::<Vec<String>>()
+                .join(" ");
+            if let Err(_) = reply.send(run_console_command(game, &line)) {
+                tracing::warn!("Failed to send command result - receiver dropped");
+            }
+        }
+        RuntimeCommand::Console(line, reply) => {
+            if let Err(_) = reply.send(run_console_command(game, &line)) {
+                tracing::warn!("Failed to send console result - receiver dropped");
+            }
+        }
+        RuntimeCommand::ListConsoleCommands(reply) => {
+            let commands = game
+                .console_registry()
+                .commands()
+                .iter()
+                .map(|command| ConsoleCommandInfo {
+                    name: command.name.to_string(),
+                    usage: command.usage.to_string(),
+                    help: command.help.to_string(),
+                })
+                .collect();
+            if let Err(_) = reply.send(commands) {
+                tracing::warn!("Failed to send console commands - receiver dropped");
+            }
+        }
+        RuntimeCommand::GetTimeScale(reply) => {
+            let result = TimeScaleResult {
+                time_scale: game.time_scale(),
             };
             if let Err(_) = reply.send(result) {
-                tracing::warn!("Failed to send command result - receiver dropped");
+                tracing::warn!("Failed to send time scale - receiver dropped");
+            }
+        }
+        RuntimeCommand::SetTimeScale(time_scale, reply) => {
+            game.set_time_scale(time_scale);
+            let result = TimeScaleResult {
+                time_scale: game.time_scale(),
+            };
+            if let Err(_) = reply.send(result) {
+                tracing::warn!("Failed to send time scale - receiver dropped");
+            }
+        }
+        RuntimeCommand::GetResourceLeaks(reply) => {
+            if let Err(_) = reply.send(game.resource_leaks()) {
+                tracing::warn!("Failed to send resource leaks - receiver dropped");
+            }
+        }
+        RuntimeCommand::GetVisibility(reply) => {
+            let result = VisibilityResult {
+                engine: game.visibility_engine(),
+                stats: game.visibility_stats(),
+            };
+            if let Err(_) = reply.send(result) {
+                tracing::warn!("Failed to send visibility stats - receiver dropped");
+            }
+        }
+        RuntimeCommand::SetVisibilityEngine(kind, reply) => {
+            game.set_visibility_engine(kind);
+            let result = VisibilityResult {
+                engine: game.visibility_engine(),
+                stats: game.visibility_stats(),
+            };
+            if let Err(_) = reply.send(result) {
+                tracing::warn!("Failed to send visibility stats - receiver dropped");
             }
         }
         RuntimeCommand::PathfindingTest(action, reply) => {
@@ -735,13 +858,25 @@ fn process_command(command: RuntimeCommand, game: &mut Game, time: &Time, frame_
         RuntimeCommand::ListEntities {
             limit,
             filter,
+            radius,
             reply,
         } => {
             if let Some(debug_scene) = game.debug_scene() {
-                let entities = debug_scene.list_entities(limit, filter.as_deref());
+                // The list comes back sorted nearest first - so the radius cut has to happen
+                // before the limit, and the count is of everything in range
+                let mut entities: Vec<_> = debug_scene
+                    .list_entities(None, filter.as_deref())
+                    .into_iter()
+                    .filter(|e| radius.is_none_or(|radius| e.distance <= radius))
+                    .collect();
+                let total_count = entities.len();
+                if let Some(limit) = limit {
+                    entities.truncate(limit);
+                }
+
                 let player_pos = debug_scene.player_position();
                 let result = EntityListResult {
-                    total_count: entities.len(),
+                    total_count,
                     player_position: [player_pos.x, player_pos.y, player_pos.z],
                     entities: entities
                         .into_iter()
@@ -770,6 +905,19 @@ fn process_command(command: RuntimeCommand, game: &mut Game, time: &Time, frame_
                 }
             }
         }
+        RuntimeCommand::EntityEvents { since, reply } => {
+            let events = game
+                .debug_scene()
+                .map(|debug_scene| debug_scene.entity_events_since(since))
+                .unwrap_or_default();
+            let next_since = events
+                .last()
+                .map(|event| event.sequence + 1)
+                .unwrap_or(since);
+            if let Err(_) = reply.send(EntityEventsResult { events, next_since }) {
+                tracing::warn!("Failed to send entity events - receiver dropped");
+            }
+        }
         RuntimeCommand::EntityDetail { id, reply } => {
             let result = if let Some(debug_scene) = game.debug_scene() {
                 // Convert i32 id to EntityId
@@ -890,86 +1038,33 @@ fn process_command(command: RuntimeCommand, game: &mut Game, time: &Time, frame_
                 tracing::warn!("Failed to send physics body detail - receiver dropped");
             }
         }
-        RuntimeCommand::GetInput(reply) => {
-            // Get current input state from the debug scene
-            if let Some(debuggable) = game.debug_scene() {
-                let input_context = debuggable.get_input_state();
-                let input_state = commands::InputState {
-                    head: commands::InputHead {
-                        rotation: [
-                            input_context.head.rotation.v.x,
-                            input_context.head.rotation.v.y,
-                            input_context.head.rotation.v.z,
-                            input_context.head.rotation.s,
-                        ],
-                    },
-                    left_hand: commands::InputHand {
-                        position: [
-                            input_context.left_hand.position.x,
-                            input_context.left_hand.position.y,
-                            input_context.left_hand.position.z,
-                        ],
-                        rotation: [
-                            input_context.left_hand.rotation.v.x,
-                            input_context.left_hand.rotation.v.y,
-                            input_context.left_hand.rotation.v.z,
-                            input_context.left_hand.rotation.s,
-                        ],
-                        thumbstick: [
-                            input_context.left_hand.thumbstick.x,
-                            input_context.left_hand.thumbstick.y,
-                        ],
-                        trigger_value: input_context.left_hand.trigger_value,
-                        squeeze_value: input_context.left_hand.squeeze_value,
-                        a_value: input_context.left_hand.a_value,
-                    },
-                    right_hand: commands::InputHand {
-                        position: [
-                            input_context.right_hand.position.x,
-                            input_context.right_hand.position.y,
-                            input_context.right_hand.position.z,
-                        ],
-                        rotation: [
-                            input_context.right_hand.rotation.v.x,
-                            input_context.right_hand.rotation.v.y,
-                            input_context.right_hand.rotation.v.z,
-                            input_context.right_hand.rotation.s,
-                        ],
-                        thumbstick: [
-                            input_context.right_hand.thumbstick.x,
-                            input_context.right_hand.thumbstick.y,
-                        ],
-                        trigger_value: input_context.right_hand.trigger_value,
-                        squeeze_value: input_context.right_hand.squeeze_value,
-                        a_value: input_context.right_hand.a_value,
-                    },
-                };
-                if let Err(_) = reply.send(input_state) {
-                    tracing::warn!("Failed to send input state - receiver dropped");
-                }
-            } else {
-                tracing::warn!("Current scene is not a debuggable mission scene");
-                if let Err(_) = reply.send(commands::InputState::default()) {
-                    tracing::warn!("Failed to send default input state - receiver dropped");
-                }
+        RuntimeCommand::GetInput(..) | RuntimeCommand::SetInput(..) => {
+            // Input is held by DebugHost and answered there, this is just for completeness
+        }
+        RuntimeCommand::GetCrash(reply) => {
+            let status = CrashStatus::from_report(game.crash_report());
+            if let Err(_) = reply.send(status) {
+                tracing::warn!("Failed to send crash status - receiver dropped");
             }
         }
-        RuntimeCommand::SetInput(patch) => {
-            // Set input channel value on the debug scene
-            if let Some(debuggable) = game.debug_scene_mut() {
-                let success = debuggable.set_input(&patch.channel, patch.value);
-                if success {
-                    tracing::info!(
-                        "Successfully set input channel '{}' via remote control",
-                        patch.channel
-                    );
-                } else {
-                    tracing::warn!("Failed to set input channel '{}'", patch.channel);
-                }
-            } else {
-                tracing::warn!(
-                    "Current scene is not a debuggable mission scene - cannot set input"
-                );
+        RuntimeCommand::GetStats(reply) => {
+            if let Err(_) = reply.send(game.statistics()) {
+                tracing::warn!("Failed to send statistics - receiver dropped");
+            }
+        }
+        RuntimeCommand::GetSpawnPoints(reply) => {
+            if let Err(_) = reply.send(game.spawn_points()) {
+                tracing::warn!("Failed to send spawn points - receiver dropped");
+            }
+        }
+        RuntimeCommand::GetFeatures(reply) => {
+            if let Err(_) = reply.send(game.experimental_features()) {
+                tracing::warn!("Failed to send experimental features - receiver dropped");
+            }
+        }
+        RuntimeCommand::GetCollisionLayers(reply) => {
+            if let Err(_) = reply.send(game.collision_layers()) {
+                tracing::warn!("Failed to send collision layers - receiver dropped");
             }
         }
         RuntimeCommand::Shutdown => {
@@ -1054,6 +1149,7 @@ fn capture_frame_snapshot(game: &Game, time: &Time, frame_counter: u64) -> Frame
             camera_rotation: [1.0, 0.0, 0.0, 0.0], // TODO: Get camera rotation
         },
         entity_count,
+        state_hash: game.state_hash().map(shock2vr::state_hash::format_hash),
         debug_features: vec![], // TODO: List active debug features
         inputs: InputSnapshot {
             head_rotation: [1.0, 0.0, 0.0, 0.0],
@@ -1111,6 +1207,176 @@ async fn get_info(
     }
 }
 
+/// Get crash status - whether the game has crashed, and the crash report if so
+async fn get_crash_status(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<CrashStatus>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx.send(RuntimeCommand::GetCrash(reply_tx)).is_err() {
+        tracing::error!("Failed to send GetCrash command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(status) => Ok(Json(status)),
+        Err(_) => {
+            tracing::error!("Failed to receive crash status - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn get_stats(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<Statistics>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx.send(RuntimeCommand::GetStats(reply_tx)).is_err() {
+        tracing::error!("Failed to send GetStats command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(statistics) => Ok(Json(statistics)),
+        Err(_) => {
+            tracing::error!("Failed to receive statistics - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn get_spawn_points(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<Vec<NamedSpawnPoint>>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::GetSpawnPoints(reply_tx))
+        .is_err()
+    {
+        tracing::error!("Failed to send GetSpawnPoints command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(spawn_points) => Ok(Json(spawn_points)),
+        Err(_) => {
+            tracing::error!("Failed to receive spawn points - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn get_features(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<Vec<ExperimentalFeatureInfo>>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::GetFeatures(reply_tx))
+        .is_err()
+    {
+        tracing::error!("Failed to send GetFeatures command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(features) => Ok(Json(features)),
+        Err(_) => {
+            tracing::error!("Failed to receive experimental features - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn get_collision_layers(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<CollisionLayerDump>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::GetCollisionLayers(reply_tx))
+        .is_err()
+    {
+        tracing::error!("Failed to send GetCollisionLayers command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(layers) => Ok(Json(layers)),
+        Err(_) => {
+            tracing::error!("Failed to receive collision layers - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Query parameters for log queries
+#[derive(Deserialize)]
+struct LogQueryParams {
+    since_frame: Option<u64>,
+    category: Option<String>,
+    level: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Query the in-memory game log buffer
+///
+/// The buffer lives in the engine rather than the game loop, so this is answered directly
+/// (and keeps working after a crash).
+async fn get_logs(Query(params): Query<LogQueryParams>) -> Json<LogQueryResult> {
+    let mut entries: Vec<_> = engine::logging::log_entries_since(params.since_frame.unwrap_or(0))
+        .into_iter()
+        .filter(|entry| {
+            params
+                .category
+                .as_ref()
+                .is_none_or(|category| entry.category.eq_ignore_ascii_case(category))
+        })
+        .filter(|entry| {
+            params
+                .level
+                .as_ref()
+                .is_none_or(|level| entry.level.eq_ignore_ascii_case(level))
+        })
+        .collect();
+
+    let total_count = entries.len();
+    if let Some(limit) = params.limit {
+        // Keep the most recent entries
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+
+    Json(LogQueryResult {
+        total_count,
+        entries,
+    })
+}
+
+/// Get rolling per-system frame timings
+///
+/// Like the log buffer, the profiler lives in the engine, so this is answered directly.
+async fn get_profiler() -> Json<ProfilerSnapshot> {
+    Json(ProfilerSnapshot {
+        window_frames: engine::profiler::PROFILER_WINDOW,
+        systems: engine::profiler::stats(),
+        gpu_memory_bytes: engine::gpu_memory::total_bytes(),
+        gpu_memory: engine::gpu_memory::stats(),
+    })
+}
+
+/// Get the assets that failed to load this session
+///
+/// The report is kept by the engine's asset cache, so this is answered directly too.
+async fn get_missing_assets() -> Json<MissingAssetsSnapshot> {
+    let assets = engine::assets::missing_assets::entries();
+    Json(MissingAssetsSnapshot {
+        total: assets.len(),
+        assets,
+    })
+}
+
 /// Step the simulation forward by one frame or time duration
 async fn step_frame(
     State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
@@ -1172,6 +1438,8 @@ async fn shutdown_server(
 struct EntityQueryParams {
     limit: Option<usize>,
     filter: Option<String>,
+    /// Only entities within this distance of the player
+    radius: Option<f32>,
 }
 
 /// List entities with optional filtering and limiting
@@ -1185,6 +1453,7 @@ async fn list_entities(
     if let Err(_) = command_tx.send(RuntimeCommand::ListEntities {
         limit: params.limit,
         filter: params.filter,
+        radius: params.radius,
         reply: reply_tx,
     }) {
         tracing::error!("Failed to send ListEntities command - game loop receiver dropped");
@@ -1209,6 +1478,34 @@ async fn list_entities(
     }
 }
 
+#[derive(Deserialize)]
+struct EntityEventsParams {
+    since: Option<u64>,
+}
+
+/// Get entity lifecycle events - all the retained ones, or those from `since` on
+async fn get_entity_events(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+    Query(params): Query<EntityEventsParams>,
+) -> Result<Json<EntityEventsResult>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::EntityEvents {
+            since: params.since.unwrap_or(0),
+            reply: reply_tx,
+        })
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
 /// Get detailed information about a specific entity
 async fn get_entity_detail(
     State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
@@ -1452,6 +1749,7 @@ async fn get_physics_body_detail(
 #[derive(serde::Deserialize)]
 struct ScreenshotRequest {
     filename: Option<String>,
+    resolution: Option<[u32; 2]>,
 }
 
 /// HTTP handler for taking screenshots
@@ -1463,17 +1761,17 @@ async fn take_screenshot(
 
     let spec = ScreenshotSpec {
         filename: request.filename,
+        resolution: request.resolution,
     };
 
     // Send screenshot command to game loop
     if let Err(_) = command_tx.send(RuntimeCommand::Screenshot(spec, reply_tx)) {
         tracing::error!("Failed to send Screenshot command - game loop receiver dropped");
-        return Json(ScreenshotResult {
-            filename: "error.png".to_string(),
-            full_path: "/tmp/error.png".to_string(),
-            resolution: [0, 0],
-            size_bytes: 0,
-        });
+        return Json(ScreenshotResult::failed(
+            "error.png",
+            "/tmp/error.png",
+            "game loop receiver dropped",
+        ));
     }
 
     // Wait for response
@@ -1481,94 +1779,94 @@ async fn take_screenshot(
         Ok(result) => Json(result),
         Err(_) => {
             tracing::error!("Failed to receive screenshot result - sender dropped");
-            Json(ScreenshotResult {
-                filename: "error.png".to_string(),
-                full_path: "/tmp/error.png".to_string(),
-                resolution: [0, 0],
-                size_bytes: 0,
-            })
+            Json(ScreenshotResult::failed(
+                "error.png",
+                "/tmp/error.png",
+                "game loop dropped the request",
+            ))
         }
     }
 }
 
-/// Capture the current OpenGL framebuffer and save it as a PNG
+///
+/// screenshot_path
+///
+/// Where a screenshot is saved - relative filenames go in the screenshots directory
+fn screenshot_path(filename: Option<String>) -> (String, std::path::PathBuf) {
+    let filename = filename.unwrap_or_else(|| {
+        format!(
+            "screenshot_{}.png",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        )
+    });
+
+    let mut full_path = std::path::Path::new(SCREENSHOTS_DIR).join(&filename);
+    if full_path.extension().is_none() {
+        full_path.set_extension("png");
+    }
+    (filename, full_path)
+}
+
+///
+/// capture_screenshot
+///
+/// Re-render the frame offscreen at the requested resolution (the window size by default), and
+/// save it as a PNG
 fn capture_screenshot(
-    path: &std::path::Path,
-    width: u32,
-    height: u32,
-) -> Result<u64, Box<dyn std::error::Error>> {
-    unsafe {
-        // Query the current viewport to see what size it actually is
-        let mut viewport: [i32; 4] = [0; 4];
-        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
-        tracing::info!(
-            "Current viewport: x={}, y={}, width={}, height={}",
-            viewport[0],
-            viewport[1],
-            viewport[2],
-            viewport[3]
+    engine: &dyn engine::Engine,
+    frame: &RenderedFrame,
+    spec: ScreenshotSpec,
+) -> ScreenshotResult {
+    let (filename, full_path) = screenshot_path(spec.filename);
+    let full_path_str = full_path.to_string_lossy().to_string();
+
+    let [width, height] = spec.resolution.unwrap_or([SCR_WIDTH, SCR_HEIGHT]);
+    if width == 0 || height == 0 || width > MAX_SCREENSHOT_SIZE || height > MAX_SCREENSHOT_SIZE {
+        let error = format!(
+            "Resolution {}x{} is out of range (1 - {})",
+            width, height, MAX_SCREENSHOT_SIZE
         );
+        tracing::error!("Failed to capture screenshot: {}", error);
+        return ScreenshotResult::failed(&filename, &full_path_str, &error);
+    }
 
-        // Use actual viewport size for screenshot, falling back to requested size if needed
-        let actual_width = if viewport[2] > 0 {
-            viewport[2] as u32
-        } else {
-            tracing::warn!(
-                "Viewport width unavailable, using requested width {}",
-                width
-            );
-            width
-        };
-        let actual_height = if viewport[3] > 0 {
-            viewport[3] as u32
-        } else {
-            tracing::warn!(
-                "Viewport height unavailable, using requested height {}",
-                height
-            );
-            height
-        };
+    // Keep the vertical field of view, and widen / narrow the horizontal one to the requested aspect
+    let mut projection_matrix = frame.render_context.projection_matrix;
+    projection_matrix.x.x = projection_matrix.y.y * height as f32 / width as f32;
+    let capture_context = engine::EngineRenderContext {
+        screen_size: vec2(width as f32, height as f32),
+        projection_matrix,
+        ..frame.render_context
+    };
 
-        // Read pixels from the framebuffer using actual viewport size
-        let mut pixels: Vec<u8> = vec![0; (actual_width * actual_height * 3) as usize];
-        gl::ReadPixels(
-            0,
-            0,
-            actual_width as i32,
-            actual_height as i32,
-            gl::RGB,
-            gl::UNSIGNED_BYTE,
-            pixels.as_mut_ptr() as *mut gl::types::GLvoid,
-        );
+    let pixels = engine::capture::render_offscreen(width, height, || {
+        engine.render(&capture_context, &frame.scene)
+    });
 
-        // Check for OpenGL errors
-        let error = gl::GetError();
-        if error != gl::NO_ERROR {
-            return Err(format!("OpenGL error during ReadPixels: {}", error).into());
+    let saved = engine::capture::save_png(&full_path, width, height, pixels).and_then(|()| {
+        std::fs::metadata(&full_path)
+            .map(|metadata| metadata.len())
+            .map_err(|err| err.to_string())
+    });
+    match saved {
+        Ok(size_bytes) => {
+            tracing::info!("Screenshot saved to: {}", full_path.display());
+            ScreenshotResult {
+                filename,
+                full_path: full_path_str,
+                resolution: [width, height],
+                size_bytes,
+                error: None,
+            }
         }
-
-        // Flip the image vertically (OpenGL origin is bottom-left, PNG is top-left)
-        let mut flipped_pixels: Vec<u8> = vec![0; pixels.len()];
-        for y in 0..actual_height {
-            let src_row = y as usize * actual_width as usize * 3;
-            let dst_row = (actual_height - 1 - y) as usize * actual_width as usize * 3;
-            flipped_pixels[dst_row..dst_row + actual_width as usize * 3]
-                .copy_from_slice(&pixels[src_row..src_row + actual_width as usize * 3]);
+        Err(err) => {
+            tracing::error!("Failed to capture screenshot: {}", err);
+            ScreenshotResult::failed(&filename, &full_path_str, &err)
         }
-
-        // Create image and save as PNG using actual dimensions
-        let img = image::RgbImage::from_vec(actual_width, actual_height, flipped_pixels)
-            .ok_or("Failed to create image from pixel data")?;
-
-        img.save(path)?;
-
-        // Calculate file size
-        let metadata = std::fs::metadata(path)?;
-        Ok(metadata.len())
     }
 }
 
-/// HTTP endpoint handler: Get current input state
+/// HTTP endpoint handler: Get the input fed to the game each frame
 async fn get_input_state(
     State(command_tx): State<tokio::sync::mpsc::UnboundedSender<commands::RuntimeCommand>>,
 ) -> Result<Json<commands::InputState>, StatusCode> {
@@ -1587,24 +1885,56 @@ async fn get_input_state(
     }
 }
 
-/// HTTP endpoint handler: Set input channel value
+/// HTTP endpoint handler: Patch the input, returning the input every following step will see
+async fn set_input(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+    Json(patch): Json<InputPatch>,
+) -> Result<Json<InputState>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::SetInput(patch, reply_tx))
+        .is_err()
+    {
+        tracing::error!("Failed to send SetInput command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(input_state) => Ok(Json(input_state)),
+        Err(_) => {
+            tracing::error!("Failed to receive input state - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// HTTP endpoint handler: Set a single input channel, ie `right_hand.trigger_value`
 async fn set_input_channel(
     State(command_tx): State<tokio::sync::mpsc::UnboundedSender<commands::RuntimeCommand>>,
-    Json(patch): Json<commands::InputPatch>,
+    Json(channel): Json<commands::InputChannel>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
+    let patch = channel.into_patch().map_err(|err| {
+        tracing::warn!("{}", err);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let (reply_tx, reply_rx) = oneshot::channel();
     if command_tx
-        .send(commands::RuntimeCommand::SetInput(patch))
+        .send(commands::RuntimeCommand::SetInput(patch, reply_tx))
         .is_err()
     {
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
 
-    // SetInput doesn't return a value, so just return success
-    let response = serde_json::json!({
-        "success": true,
-        "message": "Input channel updated"
-    });
-    Ok(Json(response))
+    match reply_rx.await {
+        Ok(input_state) => Ok(Json(serde_json::json!({
+            "success": true,
+            "message": "Input channel updated",
+            "input": input_state,
+        }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
 }
 
 /// HTTP endpoint handler: Execute a gameplay command via the runtime
@@ -1635,6 +1965,162 @@ async fn run_game_command(
     }
 }
 
+#[derive(Deserialize)]
+struct ConsoleRequest {
+    command: String,
+}
+
+/// HTTP endpoint handler: Run a developer console command line
+async fn run_console(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+    Json(request): Json<ConsoleRequest>,
+) -> Result<Json<CommandResult>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::Console(request.command, reply_tx))
+        .is_err()
+    {
+        tracing::error!("Failed to send Console command - game loop receiver dropped");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match reply_rx.await {
+        Ok(result) => Ok(Json(result)),
+        Err(_) => {
+            tracing::error!("Failed to receive Console result - sender dropped");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// HTTP endpoint handler: List the registered console commands
+async fn list_console_commands(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<Vec<ConsoleCommandInfo>>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::ListConsoleCommands(reply_tx))
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[derive(Deserialize)]
+struct TimeScaleRequest {
+    time_scale: f32,
+}
+
+/// HTTP endpoint handler: Get the game speed multiplier
+async fn get_time_scale(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<TimeScaleResult>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::GetTimeScale(reply_tx))
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// HTTP endpoint handler: Set the game speed multiplier - slow motion below 1, fast-forward above
+async fn set_time_scale(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+    Json(request): Json<TimeScaleRequest>,
+) -> Result<Json<TimeScaleResult>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::SetTimeScale(request.time_scale, reply_tx))
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// HTTP endpoint handler: Get the resources found belonging to deleted entities, and cleaned up
+async fn get_resource_leaks(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<Option<ResourceLeaks>>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::GetResourceLeaks(reply_tx))
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[derive(Deserialize)]
+struct VisibilityEngineRequest {
+    engine: String,
+}
+
+/// HTTP endpoint handler: Get the visibility engine, and how its culling compared to the frustum
+async fn get_visibility(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+) -> Result<Json<VisibilityResult>, StatusCode> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::GetVisibility(reply_tx))
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// HTTP endpoint handler: Switch visibility engines - unknown engine names are a bad request
+async fn set_visibility_engine(
+    State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
+    Json(request): Json<VisibilityEngineRequest>,
+) -> Result<Json<VisibilityResult>, StatusCode> {
+    let kind = VisibilityEngineKind::from_name(&request.engine).ok_or(StatusCode::BAD_REQUEST)?;
+    let (reply_tx, reply_rx) = oneshot::channel();
+
+    if command_tx
+        .send(RuntimeCommand::SetVisibilityEngine(kind, reply_tx))
+        .is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    reply_rx
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
 /// HTTP endpoint handler: Execute a pathfinding test command
 async fn pathfinding_test(
     State(command_tx): State<mpsc::UnboundedSender<RuntimeCommand>>,
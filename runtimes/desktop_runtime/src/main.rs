@@ -1,5 +1,5 @@
 extern crate glfw;
-use self::glfw::{Action, Context, Key};
+use self::glfw::{Action, Key};
 use cgmath::Decomposed;
 use cgmath::point3;
 
@@ -9,19 +9,35 @@ use dark::SCALE_FACTOR;
 use engine::profile;
 use engine::scene::Scene;
 
-use engine::util::compute_view_matrix_from_render_context;
 use glfw::GlfwReceiver;
 use glfw::Modifiers;
+use shock2vr::command::EntityInspectorCommand;
+use shock2vr::command::ExamineCommand;
 use shock2vr::command::LoadCommand;
 use shock2vr::command::MoveInventoryCommand;
+use shock2vr::command::NoclipCommand;
 use shock2vr::command::PathfindingTestCommand;
 
 use shock2vr::command::SaveCommand;
 use shock2vr::command::SpawnItemCommand;
 
+use runtime_core::{
+    DesktopWindow, FlatCamera, FlatCameraSettings, FrameInput, FrameView, RenderedFrame,
+    RuntimeHost, WindowOptions,
+};
 use shock2vr::GameOptions;
+use shock2vr::InspectorAction;
+use shock2vr::ReticleOptions;
 use shock2vr::SpawnLocation;
-use tracing::trace;
+use shock2vr::VisibilityEngineKind;
+use shock2vr::accessibility::{AccessibilitySettings, ButtonMode, ColorPalette};
+use shock2vr::attract_mode::AttractMode;
+use shock2vr::benchmark::{Benchmark, CameraPath};
+use shock2vr::console::ConsoleInput;
+use shock2vr::experimental::ExperimentalFeatures;
+use shock2vr::photo_mode::PhotoModeAction;
+use shock2vr::replay::InputRecording;
+use tracing::{info, trace, warn};
 
 extern crate gl;
 
@@ -33,8 +49,6 @@ use shock2vr::command::Command;
 use glfw::MouseButton;
 use shock2vr::input_context::InputContext;
 use shock2vr::time::Time;
-use std::collections::HashSet;
-use std::time::Duration;
 
 // settings
 const SCR_WIDTH: u32 = 800;
@@ -118,13 +132,129 @@ struct Args {
     #[arg(long = "debug-pathfinding")]
     debug_pathfinding: bool,
 
+    #[arg(long = "debug-profiler")]
+    debug_profiler: bool,
+
     #[arg(short, long, default_value = None)]
     save_file: Option<String>,
+
+    /// Continue from an original System Shock 2 save (quest state and inventory) in --mission
+    #[arg(long = "import-ss2-save")]
+    import_ss2_save: Option<String>,
+
+    #[arg(long, default_value = "default")]
+    profile: String,
+
+    /// Mods to load, comma-separated in place of the profile's enabled mods ("" for none)
+    #[arg(long)]
+    mods: Option<String>,
+
+    /// List the installed mods, with the load order for the profile (or --mods), and exit
+    #[arg(long = "list-mods")]
+    list_mods: bool,
+
+    /// Scale for subtitle / HUD text
+    #[arg(long = "text-scale", default_value_t = 1.0)]
+    text_scale: f32,
+
+    /// Outline palette: standard, deuteranopia, protanopia, tritanopia, high_contrast
+    #[arg(long = "color-palette", default_value = "standard")]
+    color_palette: String,
+
+    /// hold or toggle
+    #[arg(long = "grab-mode", default_value = "hold")]
+    grab_mode: String,
+
+    /// hold or toggle
+    #[arg(long = "crouch-mode", default_value = "hold")]
+    crouch_mode: String,
+
+    #[arg(long = "reduced-flashing")]
+    reduced_flashing: bool,
+
+    /// Game speed, from 0.1 (slow motion) to 8 (fast-forward)
+    #[arg(long = "time-scale", default_value_t = 1.0)]
+    time_scale: f32,
+
+    /// Turn off hand colliders - interact only by pointing
+    #[arg(long = "no-physical-hands")]
+    no_physical_hands: bool,
+
+    /// Hide subtitles for announcements
+    #[arg(long = "no-subtitles")]
+    no_subtitles: bool,
+
+    /// Hide the predicted arc when holding a primed grenade
+    #[arg(long = "no-throw-arc")]
+    no_throw_arc: bool,
+
+    /// Language for UI and game strings (ie, english, german)
+    #[arg(long, default_value = "english")]
+    language: String,
+
+    /// How missions decide what to draw (portal, brute_force, distance_only)
+    #[arg(long = "visibility-engine", default_value = "portal")]
+    visibility_engine: String,
+
+    /// Vertical field of view, in degrees (30 to 110)
+    #[arg(long, default_value_t = runtime_core::DEFAULT_FOV)]
+    fov: f32,
+
+    /// Head bob strength while walking, from 0 (none) to 1
+    #[arg(long = "head-bob", default_value_t = 0.0)]
+    head_bob: f32,
+
+    /// Seconds for the view to catch up with the mouse - 0 follows it exactly
+    #[arg(long = "camera-smoothing", default_value_t = 0.0)]
+    camera_smoothing: f32,
+
+    /// Don't flash a marker on the crosshair when damaging something
+    #[arg(long = "no-hit-markers")]
+    no_hit_markers: bool,
+
+    /// Load the lit materials' shaders from this folder (ie, engine/shaders), recompiling them
+    /// when they're edited
+    #[arg(long = "shader-dir")]
+    shader_dir: Option<String>,
+
+    /// Photo mode captures are rendered at this multiple of the window size
+    #[arg(long = "photo-scale", default_value_t = 2)]
+    photo_scale: u32,
+
+    /// Fly the camera along a fixed path (with AI running), write timings to --benchmark-output, and exit
+    #[arg(long)]
+    benchmark: bool,
+
+    #[arg(long = "benchmark-duration", default_value_t = 30.0)]
+    benchmark_duration: f32,
+
+    /// JSON camera path (keyframes relative to the spawn point) - defaults to a turn around the spawn
+    #[arg(long = "benchmark-path")]
+    benchmark_path: Option<String>,
+
+    #[arg(long = "benchmark-output", default_value = "benchmark.json")]
+    benchmark_output: String,
+
+    /// Record the session's input to a file on exit, for playback in attract mode
+    #[arg(long = "record-input")]
+    record_input: Option<String>,
+
+    /// Folder of input recordings to play back after --attract-idle seconds without input
+    #[arg(long)]
+    attract: Option<String>,
+
+    #[arg(long = "attract-idle", default_value_t = shock2vr::attract_mode::DEFAULT_IDLE_SECONDS)]
+    attract_idle: f32,
     // Number of times to greet
     // #[arg(short, long, default_value_t = 1)]
     // count: u8,
+    /// Enable experimental features (comma-separated, or repeated)
     #[arg(short, long, default_value = None)]
     experimental: Option<Vec<String>>,
+
+    /// List the known experimental features and exit
+    #[arg(long = "list-experimental")]
+    list_experimental: bool,
 }
 struct MouseUpdateResult {
     delta_x: f32,
@@ -192,57 +322,84 @@ pub fn main() {
     //tracing_subscriber::fmt::init();
     let args = Args::parse();
     //panic!("args: {:?}", args);
-    let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
-    // TODO: Figure out ANGLE
-    // glfw.window_hint(glfw::WindowHint::ClientApi(glfw::OpenGlEs));
-    glfw.window_hint(glfw::WindowHint::ContextVersion(4, 1));
-    glfw.window_hint(glfw::WindowHint::OpenGlProfile(
-        glfw::OpenGlProfileHint::Core,
-    ));
-    #[cfg(target_os = "macos")]
-    glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-    // glfw window creation
-    // --------------------
-    let (mut window, events) = glfw
-        .create_window(
-            SCR_WIDTH,
-            SCR_HEIGHT,
-            "Shock Engine - Game Mode",
-            glfw::WindowMode::Windowed,
-        )
-        .expect("Failed to create GLFW window");
-
-    window.make_current();
-    window.set_key_polling(true);
-    window.set_cursor_pos_polling(true);
-    window.set_framebuffer_size_polling(true);
-    window.set_cursor_mode(glfw::CursorMode::Disabled);
-
-    // gl: load all OpenGL function pointers
-    // ---------------------------------------
-    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-    println!(
-        "es2 extension supported: {}",
-        glfw.extension_supported("GL_ARB_ES2_compatibility")
-    );
-    println!(
-        "es3 extension supported: {}",
-        glfw.extension_supported("GL_ARB_ES3_compatibility")
+    if args.list_experimental {
+        print!("{}", shock2vr::experimental::list_text());
+        return;
+    }
+    let mods = args.mods.as_deref().map(shock2vr::mods::parse_mod_list);
+    if args.list_mods {
+        let enabled = mods.clone().unwrap_or_else(|| {
+            shock2vr::profiles::PlayerProfile::new(&args.profile)
+                .load_settings()
+                .enabled_mods
+        });
+        let manager =
+            shock2vr::mods::ModManager::scan(shock2vr::paths::data_file_system().as_ref());
+        for line in manager.describe(&enabled) {
+            println!("{}", line);
+        }
+        return;
+    }
+    let mut window = DesktopWindow::create(
+        &WindowOptions::new("Shock Engine - Game Mode", SCR_WIDTH, SCR_HEIGHT)
+            .with_captured_cursor(),
     );
 
     let engine = engine::opengl();
+    if let Some(shader_dir) = &args.shader_dir {
+        engine::shader_library::watch(std::path::Path::new(shader_dir));
+    }
     let bundle_storage = engine.get_storage();
-    let experimental_features: HashSet<String> =
-        args.experimental.unwrap_or(vec![]).into_iter().collect();
+    let experimental_features = ExperimentalFeatures::parse(args.experimental.unwrap_or_default());
 
     let (mission, spawn_location) = parse_mission(&args.mission);
+    let recording = args
+        .record_input
+        .map(|path| (InputRecording::new(&args.mission), path));
+    let attract_mode = args.attract.map(|dir| {
+        let recordings = InputRecording::load_dir(std::path::Path::new(&dir));
+        info!("Attract mode: {} recordings from {}", recordings.len(), dir);
+        AttractMode::new(&args.mission, recordings, args.attract_idle)
+    });
+
+    let benchmark = if args.benchmark {
+        let path = match &args.benchmark_path {
+            Some(path_file) => {
+                let json = std::fs::read_to_string(path_file)
+                    .unwrap_or_else(|err| panic!("Unable to read {}: {}", path_file, err));
+                CameraPath::from_json(&json)
+                    .unwrap_or_else(|err| panic!("Unable to parse {}: {}", path_file, err))
+            }
+            None => CameraPath::default_path(),
+        };
+        // Uncapped, so the results reflect the frame cost rather than the refresh rate
+        window.glfw.set_swap_interval(glfw::SwapInterval::None);
+        engine::profiler::set_enabled(true);
+        Some(Benchmark::new(&mission, args.benchmark_duration, path))
+    } else {
+        None
+    };
+
+    let accessibility = AccessibilitySettings {
+        text_scale: args.text_scale,
+        color_palette: ColorPalette::from_name(&args.color_palette).unwrap_or_default(),
+        grab_mode: ButtonMode::from_name(&args.grab_mode).unwrap_or_default(),
+        crouch_mode: ButtonMode::from_name(&args.crouch_mode).unwrap_or_default(),
+        reduced_flashing: args.reduced_flashing,
+        time_scale: args.time_scale,
+        physical_hands: !args.no_physical_hands,
+        subtitles: !args.no_subtitles,
+        throw_arc: !args.no_throw_arc,
+    };
 
     let options = GameOptions {
         mission,
         spawn_location,
         save_file: args.save_file,
+        import_save: args.import_ss2_save,
+        profile: args.profile,
+        mods,
+        language: args.language,
         debug_draw: args.debug_draw,
         debug_physics: args.debug_physics,
         debug_portals: args.debug_portals,
@@ -250,11 +407,19 @@ pub fn main() {
         debug_skeletons: args.debug_skeletons,
         debug_ai: args.debug_ai,
         debug_pathfinding: args.debug_pathfinding,
+        debug_profiler: args.debug_profiler,
+        visibility_engine: VisibilityEngineKind::parse(&args.visibility_engine),
         render_particles: true,
+        reticle: ReticleOptions {
+            enabled: true,
+            hit_markers: !args.no_hit_markers,
+        },
         experimental_features,
+        accessibility,
         ..GameOptions::default()
     };
     let mut game = shock2vr::Game::init(options, bundle_storage);
+    let benchmark_origin = game.player_position().unwrap_or(vec3(0.0, 0.0, 0.0));
     // FOR SCREENSHOT
     // let mut camera_context = CameraContext {
     //     camera_offset: cgmath::Vector3::new(1.25, -14.0, -24.0),
@@ -263,138 +428,259 @@ pub fn main() {
     //     mouse_position: None,
     // };
 
-    let mut camera_context = CameraContext::new();
-
-    let mut hand_context = HandContext::new();
-
-    let mut last_time = glfw.get_time() as f32;
-    let start_time = last_time;
-
-    let mut _frame = 0;
-    let mut last_input_state = InputState::new();
-
     let _mode = Mode::Gameplay;
-    // render loop
-    // -----------
-    while !window.should_close() {
-        // events
-        // -----
-        let time = glfw.get_time() as f32;
-        let delta_time = time - last_time;
-        last_time = time;
-
-        let (input_context, new_input_state, commands, _effects) = process_events(
-            &mut window,
-            &mut camera_context,
-            &mut hand_context,
-            &last_input_state,
-            &events,
-            delta_time,
-        );
-        last_input_state = new_input_state;
-        let ratio = SCR_WIDTH as f32 / SCR_HEIGHT as f32;
-        let projection_matrix: cgmath::Matrix4<f32> =
-            cgmath::perspective(cgmath::Deg(45.0), ratio, 0.1, 1000.0);
-
-        let time = Time {
-            elapsed: Duration::from_secs_f32(delta_time),
-            total: Duration::from_secs_f32(time - start_time),
-        };
 
-        profile!("game.update", game.update(&time, &input_context, commands));
+    let mut host = DesktopHost {
+        window,
+        camera_context: CameraContext::new(),
+        camera: FlatCamera::new(FlatCameraSettings {
+            fov: args.fov,
+            head_bob: args.head_bob,
+            smoothing: args.camera_smoothing,
+        }),
+        hand_context: HandContext::new(),
+        last_input_state: InputState::new(),
+        benchmark,
+        benchmark_origin,
+        benchmark_output: args.benchmark_output,
+        photo_scale: args.photo_scale,
+        recording,
+        attract_mode,
+        replay_head_rotation: None,
+    };
 
-        let screen_size = vec2(SCR_WIDTH as f32, SCR_HEIGHT as f32);
+    runtime_core::run(&mut host, &mut game, engine.as_ref());
 
-        let (mut scene, pawn_offset, pawn_rotation) = profile!("game.render", game.render());
+    if let Some((recording, path)) = &host.recording {
+        match recording.save(std::path::Path::new(path)) {
+            Ok(()) => info!(
+                "Wrote input recording ({:.1}s) to {}",
+                recording.duration(),
+                path
+            ),
+            Err(err) => warn!("{}", err),
+        }
+    }
+}
 
-        let head_height = if last_input_state.is_crouching {
-            1.5
-        } else {
-            4.0
-        };
-        let render_context = engine::EngineRenderContext {
-            time: glfw.get_time() as f32,
-            camera_offset: pawn_offset,
-            camera_rotation: pawn_rotation,
+///
+/// DesktopHost
+///
+/// Keyboard and mouse input, a GLFW window, and the benchmark / photo capture hooks
+struct DesktopHost {
+    window: DesktopWindow,
+    camera_context: CameraContext,
+    // The view - smoothed mouse look, head bob and field of view
+    camera: FlatCamera,
+    hand_context: HandContext,
+    last_input_state: InputState,
+    benchmark: Option<Benchmark>,
+    benchmark_origin: Vector3<f32>,
+    benchmark_output: String,
+    photo_scale: u32,
+    recording: Option<(InputRecording, String)>,
+    attract_mode: Option<AttractMode>,
+    // While attract mode plays a recording, the view follows the recorded head
+    replay_head_rotation: Option<Quaternion<f32>>,
+}
 
-            head_offset: vec3(0.0, head_height / SCALE_FACTOR, 0.0),
-            head_rotation: camera_rotation(&camera_context),
+impl RuntimeHost for DesktopHost {
+    fn now(&self) -> f32 {
+        self.window.now()
+    }
 
-            projection_matrix,
-            screen_size,
-        };
+    fn should_stop(&self) -> bool {
+        self.window.should_close()
+    }
 
-        let view = compute_view_matrix_from_render_context(&render_context);
-        let per_eye_scene = profile!(
-            "game.render_per_eye",
-            game.render_per_eye(view, projection_matrix, screen_size)
+    fn begin_frame(&mut self, game: &mut shock2vr::Game, time: Time) -> FrameInput {
+        let (
+            input_context,
+            new_input_state,
+            commands,
+            _effects,
+            photo_mode_actions,
+            console_inputs,
+        ) = process_events(
+            &mut self.window.window,
+            &mut self.camera_context,
+            &mut self.hand_context,
+            &self.last_input_state,
+            &self.window.events,
+            time.elapsed.as_secs_f32(),
+            game.photo_mode().is_some(),
+            game.console().is_open(),
         );
+        self.last_input_state = new_input_state;
+        for action in photo_mode_actions {
+            game.photo_mode_action(action);
+        }
+        for input in console_inputs {
+            game.console_input(input);
+        }
 
-        game.finish_render(view, projection_matrix, screen_size);
-
-        _frame += 1;
+        // The benchmark runs hands-off, so every run sees the same inputs
+        match &self.benchmark {
+            Some(benchmark) => {
+                game.set_camera_override(Some(benchmark.camera(self.benchmark_origin)));
+                FrameInput::new(time, InputContext::default())
+            }
+            None => {
+                if let Some((recording, _)) = &mut self.recording {
+                    recording.record(&time, &input_context);
+                }
+
+                let replay = self.attract_mode.as_mut().and_then(|attract_mode| {
+                    attract_mode.update(game, &time, &input_context, !commands.is_empty())
+                });
+                match replay {
+                    Some((replay_time, replay_input)) => {
+                        self.replay_head_rotation = Some(replay_input.head.rotation);
+                        FrameInput::new(replay_time, replay_input)
+                    }
+                    None => {
+                        self.replay_head_rotation = None;
+                        FrameInput {
+                            time,
+                            input_context,
+                            commands,
+                        }
+                    }
+                }
+            }
+        }
+    }
 
-        scene.extend(per_eye_scene);
+    fn update(&mut self, game: &mut shock2vr::Game, input: FrameInput) {
+        // A panic is written out as a crash report, and the game stops updating
+        let _ = profile!(
+            "game.update",
+            game.try_update(&input.time, &input.input_context, input.commands)
+        );
 
-        let mut scene_for_render = Scene::from_objects(scene);
+        let target_rotation = self
+            .replay_head_rotation
+            .unwrap_or_else(|| camera_rotation(&self.camera_context));
+        self.camera.update(
+            input.time.elapsed.as_secs_f32(),
+            target_rotation,
+            game.player_position(),
+        );
 
-        // Add hand spotlights for enhanced lighting testing (experimental feature)
-        let hand_spotlights = game.get_hand_spotlights();
-        for spotlight in hand_spotlights {
-            scene_for_render.lights_mut().add_spotlight(spotlight);
+        if let Some(benchmark) = &mut self.benchmark {
+            benchmark.record_frame(input.time.elapsed.as_secs_f32());
+            if benchmark.is_finished() {
+                write_benchmark_results(benchmark, &self.benchmark_output);
+                self.window.window.set_should_close(true);
+            }
         }
+    }
 
-        profile!(
-            "engine.render",
-            engine.render(&render_context, &scene_for_render)
-        );
+    fn view(&self, game: &shock2vr::Game) -> Option<FrameView> {
+        let fov = game
+            .photo_mode()
+            .map(|photo_mode| photo_mode.fov)
+            .unwrap_or(self.camera.settings().fov);
+
+        // Crouching is handled by the game (so it follows the crouch accessibility mode)
+        // In photo mode, the game's camera is the whole view - there's no head on top of it
+        let (head_offset, head_rotation) = match (game.photo_mode(), &self.benchmark) {
+            (Some(_), _) | (_, Some(_)) => (vec3(0.0, 0.0, 0.0), Quaternion::one()),
+            (None, None) => (
+                vec3(0.0, 4.0 / SCALE_FACTOR, 0.0) + self.camera.head_bob_offset(),
+                self.camera.rotation().unwrap_or_else(|| {
+                    self.replay_head_rotation
+                        .unwrap_or_else(|| camera_rotation(&self.camera_context))
+                }),
+            ),
+        };
 
-        // glfw: swap buffers and poll IO events (keys pressed/released, mouse moved etc.)
-        // -------------------------------------------------------------------------------
-        window.swap_buffers();
-        glfw.poll_events();
+        Some(FrameView {
+            time: self.window.now(),
+            head_offset,
+            head_rotation,
+            fov: cgmath::Deg(fov),
+            screen_size: vec2(SCR_WIDTH as f32, SCR_HEIGHT as f32),
+        })
     }
-}
 
-fn parse_mission(mission: &str) -> (String, SpawnLocation) {
-    if !mission.contains(':') {
-        return (mission.to_owned(), SpawnLocation::MapDefault);
+    fn end_frame(
+        &mut self,
+        game: &mut shock2vr::Game,
+        engine: &dyn engine::Engine,
+        frame: &RenderedFrame,
+    ) {
+        if let Some(path) = game.take_photo_capture_request() {
+            capture_photo(
+                game,
+                engine,
+                &frame.render_context,
+                &frame.scene,
+                self.photo_scale,
+                &path,
+            );
+        }
     }
 
-    let parts: Vec<&str> = mission.split(':').collect();
-
-    if parts.len() > 2 {
-        panic!("Unable to parse mission argument: {}", mission);
+    fn present(&mut self) {
+        self.window.present();
     }
+}
 
-    let mission = parts[0];
+fn write_benchmark_results(benchmark: &Benchmark, output: &str) {
+    let results = benchmark.results();
+    info!(
+        "Benchmark finished: {:.1} avg fps, {:.1} 1% low, {:.1} min over {} frames",
+        results.average_fps, results.one_percent_low_fps, results.min_fps, results.frames
+    );
 
-    let spawn_location = if parts[1].contains(",") {
-        let vec_parts: Vec<&str> = parts[1].split(',').collect();
-        if vec_parts.len() != 3 {
-            panic!("Unable to parse position: {}", parts[1]);
-        }
+    match results.save(std::path::Path::new(output)) {
+        Ok(()) => info!("Wrote benchmark results to {}", output),
+        Err(err) => warn!("Unable to write benchmark results to {}: {}", output, err),
+    }
+}
 
-        let x = vec_parts[0].parse::<f32>().unwrap();
-        let y = vec_parts[1].parse::<f32>().unwrap();
-        let z = vec_parts[2].parse::<f32>().unwrap();
-        SpawnLocation::PositionRotation(vec3(x, y, z), Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0))
-    } else {
-        match parts[1].parse::<i32>() {
-            Ok(num) => SpawnLocation::Marker(num),
-            Err(_) => SpawnLocation::MapDefault,
-        }
+///
+/// capture_photo
+///
+/// Re-render the current frame offscreen at `scale` times the window size, and save it as a PNG
+fn capture_photo(
+    game: &shock2vr::Game,
+    engine: &dyn engine::Engine,
+    render_context: &engine::EngineRenderContext,
+    scene: &Scene,
+    scale: u32,
+    path: &std::path::Path,
+) {
+    let scale = scale.max(1);
+    let (width, height) = (SCR_WIDTH * scale, SCR_HEIGHT * scale);
+    let capture_context = engine::EngineRenderContext {
+        screen_size: vec2(width as f32, height as f32),
+        ..*render_context
     };
 
-    return (mission.to_owned(), spawn_location);
+    let mut pixels =
+        engine::capture::render_offscreen(width, height, || engine.render(&capture_context, scene));
+    if let Some(photo_mode) = game.photo_mode() {
+        photo_mode.filter.apply(&mut pixels);
+    }
+
+    match engine::capture::save_png(path, width, height, pixels) {
+        Ok(()) => info!("Saved photo to {:?}", path),
+        Err(err) => warn!("Unable to save photo to {:?}: {}", path, err),
+    }
+}
+
+fn parse_mission(mission: &str) -> (String, SpawnLocation) {
+    SpawnLocation::parse_mission_argument(mission).unwrap_or_else(|err| panic!("{}", err))
 }
 
 struct InputState {
     quick_load_pressed: bool,
     quick_save_pressed: bool,
     space_pressed: bool,
-    is_crouching: bool,
     pathfinding_test_pressed: bool,
+    inspector_select_pressed: bool,
 }
 impl InputState {
     pub fn new() -> Self {
@@ -402,8 +688,8 @@ impl InputState {
             quick_load_pressed: false,
             quick_save_pressed: false,
             space_pressed: false,
-            is_crouching: false,
             pathfinding_test_pressed: false,
+            inspector_select_pressed: false,
         }
     }
 }
@@ -417,7 +703,16 @@ fn process_events(
     last_input_state: &InputState,
     events: &GlfwReceiver<(f64, glfw::WindowEvent)>,
     delta_time: f32,
-) -> (InputContext, InputState, Vec<Box<dyn Command>>, Vec<Effect>) {
+    is_photo_mode: bool,
+    is_console_open: bool,
+) -> (
+    InputContext,
+    InputState,
+    Vec<Box<dyn Command>>,
+    Vec<Effect>,
+    Vec<PhotoModeAction>,
+    Vec<ConsoleInput>,
+) {
     let _speed = 20.0;
     let head_rot_speed = 10.0;
 
@@ -425,6 +720,8 @@ fn process_events(
 
     let _movement = cgmath::Vector3::new(0.0, 0.0, 0.0);
     let mut commands: Vec<Box<dyn Command>> = vec![];
+    let mut photo_mode_actions = vec![];
+    let mut console_inputs = vec![];
     //let mut forward = cgmath::Vector3::new(0.0, );
 
     trace!("delta time: {delta_time}");
@@ -440,6 +737,26 @@ fn process_events(
     // }
 
     for (_, event) in glfw::flush_messages(events) {
+        if let glfw::WindowEvent::Key(Key::GraveAccent, _, Action::Press, _) = event {
+            console_inputs.push(ConsoleInput::Toggle);
+            continue;
+        }
+
+        // While the console is open, it gets all the keyboard input
+        if is_console_open {
+            match event {
+                glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
+                    console_inputs.push(ConsoleInput::Toggle);
+                    continue;
+                }
+                glfw::WindowEvent::Char(_) | glfw::WindowEvent::Key(..) => {
+                    console_inputs.extend(console_input(&event));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
         match event {
             glfw::WindowEvent::FramebufferSize(width, height) => {
                 // make sure the viewport matches the new window dimensions; note that width and
@@ -460,6 +777,48 @@ fn process_events(
             glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
                 window.set_should_close(true)
             }
+            glfw::WindowEvent::Key(Key::F5, _, Action::Press, _) => {
+                photo_mode_actions.push(PhotoModeAction::Toggle)
+            }
+            glfw::WindowEvent::Key(Key::F6, _, Action::Press, _) => {
+                photo_mode_actions.push(PhotoModeAction::CycleFilter)
+            }
+            glfw::WindowEvent::Key(Key::F7, _, Action::Press, _) => {
+                photo_mode_actions.push(PhotoModeAction::ToggleHud)
+            }
+            glfw::WindowEvent::Key(Key::F8, _, Action::Press, _) => {
+                photo_mode_actions.push(PhotoModeAction::ToggleHands)
+            }
+            glfw::WindowEvent::Key(Key::F12, _, Action::Press, _) => {
+                photo_mode_actions.push(PhotoModeAction::Capture)
+            }
+            glfw::WindowEvent::Key(Key::F3, _, Action::Press, _) => commands.push(Box::new(
+                EntityInspectorCommand::new(InspectorAction::Toggle),
+            )),
+            glfw::WindowEvent::Key(Key::F4, _, Action::Press, _) => {
+                commands.push(Box::new(NoclipCommand::new()))
+            }
+            glfw::WindowEvent::Key(Key::X, _, Action::Press, _) => {
+                commands.push(Box::new(ExamineCommand::new()))
+            }
+            glfw::WindowEvent::Key(Key::LeftBracket, _, Action::Press | Action::Repeat, _) => {
+                commands.push(Box::new(EntityInspectorCommand::new(
+                    InspectorAction::PreviousField,
+                )))
+            }
+            glfw::WindowEvent::Key(Key::RightBracket, _, Action::Press | Action::Repeat, _) => {
+                commands.push(Box::new(EntityInspectorCommand::new(
+                    InspectorAction::NextField,
+                )))
+            }
+            glfw::WindowEvent::Key(Key::Minus, _, Action::Press | Action::Repeat, _) => commands
+                .push(Box::new(EntityInspectorCommand::new(
+                    InspectorAction::Decrease,
+                ))),
+            glfw::WindowEvent::Key(Key::Equal, _, Action::Press | Action::Repeat, _) => commands
+                .push(Box::new(EntityInspectorCommand::new(
+                    InspectorAction::Increase,
+                ))),
             glfw::WindowEvent::CursorPos(x, y) => {
                 let mouse_update = camera_update_mouse(camera_context, x as f32, y as f32);
                 rot_yaw = 1.0 * mouse_update.delta_x;
@@ -580,9 +939,35 @@ fn process_events(
     input_context.left_hand.trigger_value = f32_from_bool(hand_context.left_trigger_pressed);
     input_context.left_hand.squeeze_value = f32_from_bool(hand_context.left_squeeze_pressed);
     input_context.left_hand.a_value = f32_from_bool(hand_context.left_a_pressed);
+    input_context.left_hand.b_value =
+        f32_from_bool(window.get_key(Key::LeftControl) == Action::Press);
+    // Photo mode: hold R to switch the arrow keys to roll / zoom, Space to rise
+    if is_photo_mode {
+        if window.get_key(Key::R) == Action::Press {
+            input_context.left_hand.trigger_value = 1.0;
+        }
+        if window.get_key(Key::Space) == Action::Press {
+            input_context.right_hand.a_value = 1.0;
+        }
+    }
     // input_context.left_hand.trigger_value = trigger_value;
     // input_context.left_hand.squeeze_value = squeeze_value;
 
+    // Typing into the console shouldn't move the player or fire key bindings
+    if is_console_open {
+        input_context.right_hand.thumbstick = vec2(0.0, 0.0);
+        input_context.left_hand.thumbstick = vec2(0.0, 0.0);
+        input_context.left_hand.b_value = 0.0;
+        return (
+            input_context,
+            InputState::new(),
+            commands,
+            effects,
+            photo_mode_actions,
+            console_inputs,
+        );
+    }
+
     let mut input_state = InputState::new();
     if window.get_key(Key::Space) == Action::Press {
         input_state.space_pressed = true;
@@ -607,13 +992,24 @@ fn process_events(
         }
     }
 
-    input_state.is_crouching = window.get_key(Key::LeftControl) == Action::Press;
-
     if window.get_key(Key::I) == Action::Press {
         //commands.push(Box::new(SavePositionCommand::new()));
         commands.push(Box::new(MoveInventoryCommand::new(head_rotation)))
     }
 
+    // Entity inspector - a left click (without E / Q held) inspects what the right hand points at
+    if window.get_mouse_button(MouseButton::Button1) == Action::Press
+        && window.get_key(Key::E) != Action::Press
+        && window.get_key(Key::Q) != Action::Press
+    {
+        input_state.inspector_select_pressed = true;
+        if !last_input_state.inspector_select_pressed {
+            commands.push(Box::new(EntityInspectorCommand::new(
+                InspectorAction::Select,
+            )));
+        }
+    }
+
     // Pathfinding test system - P key cycles through test states
     if window.get_key(Key::P) == Action::Press {
         input_state.pathfinding_test_pressed = true;
@@ -621,5 +1017,27 @@ fn process_events(
             commands.push(Box::new(PathfindingTestCommand::new()));
         }
     }
-    (input_context, input_state, commands, effects)
+    (
+        input_context,
+        input_state,
+        commands,
+        effects,
+        photo_mode_actions,
+        console_inputs,
+    )
+}
+
+fn console_input(event: &glfw::WindowEvent) -> Option<ConsoleInput> {
+    match event {
+        glfw::WindowEvent::Char(c) => Some(ConsoleInput::Char(*c)),
+        glfw::WindowEvent::Key(key, _, Action::Press | Action::Repeat, _) => match key {
+            Key::Backspace => Some(ConsoleInput::Backspace),
+            Key::Enter | Key::KpEnter => Some(ConsoleInput::Submit),
+            Key::Tab => Some(ConsoleInput::Complete),
+            Key::Up => Some(ConsoleInput::HistoryPrevious),
+            Key::Down => Some(ConsoleInput::HistoryNext),
+            _ => None,
+        },
+        _ => None,
+    }
 }
@@ -3,15 +3,18 @@ extern crate khronos_egl as egl;
 
 use cgmath::Quaternion;
 use cgmath::vec2;
+use engine::file_system::Storage;
 use engine::profile;
 use engine::scene::Scene;
 use engine::scene::SceneObject;
 use openxr as xr;
 use shock2vr::Game;
 use shock2vr::GameOptions;
+use shock2vr::experimental::ExperimentalFeatures;
 use shock2vr::input_context::InputContext;
 use shock2vr::paths;
-use std::collections::HashSet;
+use shock2vr::photo_mode::PhotoModeAction;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use std::cell::RefCell;
@@ -29,6 +32,42 @@ use tokio::runtime::Runtime;
 #[cfg_attr(target_os = "android", link(name = "c++_shared"))]
 unsafe extern "C" {}
 
+///
+/// select_data_file_system
+///
+/// Pick where the game data is read from: /sdcard/shock2quest when it's readable, otherwise a
+/// scoped-storage tree the user granted access to, otherwise data packaged into the APK's assets
+fn select_data_file_system(bundle_storage: &Arc<dyn Storage>) {
+    if paths::data_root().join("shock2.gam").exists() {
+        println!("Reading game data from {}", paths::data_root().display());
+        return;
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        use engine::file_system::{ContentUriFileSystem, FileSystem};
+
+        if let Some(tree) = ContentUriFileSystem::from_persisted_permission() {
+            if tree.file_exists("shock2.gam") {
+                println!("Reading game data from a scoped storage tree");
+                paths::set_data_file_system(Arc::new(tree));
+                return;
+            }
+        }
+    }
+
+    if bundle_storage.bundle_filesystem().file_exists("shock2.gam") {
+        println!("Reading game data from the APK assets");
+        paths::set_data_file_system(bundle_storage.bundle_filesystem_arc());
+        return;
+    }
+
+    println!(
+        "No game data found - copy the SS2 data folder to {}",
+        paths::data_root().display()
+    );
+}
+
 #[cfg_attr(target_os = "android", ndk_glue::main)]
 fn main() {
     #[cfg(feature = "linked")]
@@ -324,6 +363,19 @@ fn main() {
         .create_action::<f32>("right_squeeze", "Right Hand Squeeze", &[])
         .unwrap();
 
+    let left_y_button = action_set
+        .create_action::<bool>("left_y_button", "Left Hand Y Button", &[])
+        .unwrap();
+
+    // Held to examine the item in hand
+    let right_b_button = action_set
+        .create_action::<bool>("right_b_button", "Right Hand B Button", &[])
+        .unwrap();
+
+    let left_menu_button = action_set
+        .create_action::<bool>("left_menu_button", "Left Hand Menu Button", &[])
+        .unwrap();
+
     let left_thumbstick_action = action_set
         .create_action::<xr::Vector2f>("left_hand_thumbstick", "Left Hand Thumbstick", &[])
         .unwrap();
@@ -389,6 +441,24 @@ fn main() {
                         .string_to_path("/user/hand/right/input/squeeze/value")
                         .unwrap(),
                 ),
+                xr::Binding::new(
+                    &left_y_button,
+                    xr_instance
+                        .string_to_path("/user/hand/left/input/y/click")
+                        .unwrap(),
+                ),
+                xr::Binding::new(
+                    &right_b_button,
+                    xr_instance
+                        .string_to_path("/user/hand/right/input/b/click")
+                        .unwrap(),
+                ),
+                xr::Binding::new(
+                    &left_menu_button,
+                    xr_instance
+                        .string_to_path("/user/hand/left/input/menu/click")
+                        .unwrap(),
+                ),
                 xr::Binding::new(
                     &left_thumbstick_action,
                     xr_instance
@@ -427,8 +497,9 @@ fn main() {
     let now = Instant::now();
     let engine = engine::android();
     let bundle_storage = engine.get_storage();
-    let mut experimental_features = HashSet::new();
-    // experimental_features.insert("gui".to_owned());
+    select_data_file_system(&bundle_storage);
+    let mut experimental_features = ExperimentalFeatures::default();
+    // experimental_features.insert(ExperimentalFeature::Gui);
     let options: GameOptions = GameOptions {
         render_particles: false,
         mission: "debug_gloves".to_string(),
@@ -442,6 +513,7 @@ fn main() {
 
     let render_time = Instant::now();
     let mut last_update_time = render_time;
+    let mut last_menu_pressed = false;
     'main_loop: loop {
         frame = frame + 1;
 
@@ -549,6 +621,26 @@ fn main() {
             .unwrap()
             .current_state;
 
+        let left_y_pressed = left_y_button
+            .state(&session, xr::Path::NULL)
+            .unwrap()
+            .current_state;
+
+        let right_b_pressed = right_b_button
+            .state(&session, xr::Path::NULL)
+            .unwrap()
+            .current_state;
+
+        // The menu button toggles photo mode
+        let menu_pressed = left_menu_button
+            .state(&session, xr::Path::NULL)
+            .unwrap()
+            .current_state;
+        if menu_pressed && !last_menu_pressed {
+            game.photo_mode_action(PhotoModeAction::Toggle);
+        }
+        last_menu_pressed = menu_pressed;
+
         let _speed = 50.0;
 
         // let forward_xr = right_aim_location.pose.orientation;
@@ -594,9 +686,12 @@ fn main() {
         input_context.left_hand.position = left_hand_position;
         input_context.left_hand.trigger_value = left_trigger_value;
         input_context.left_hand.squeeze_value = left_squeeze_value;
+        input_context.left_hand.b_value = if left_y_pressed { 1.0 } else { 0.0 };
+        input_context.right_hand.b_value = if right_b_pressed { 1.0 } else { 0.0 };
         input_context.left_hand.thumbstick =
             vec2(-left_thumbstick_value.x, left_thumbstick_value.y);
-        game.update(&time_context, &input_context, vec![]);
+        // A panic is written out as a crash report - the game stops updating, and rendering
+        let _ = game.try_update(&time_context, &input_context, vec![]);
 
         // Must be called before any rendering is done!
         frame_stream.begin().unwrap();
@@ -760,7 +855,16 @@ fn main() {
             )
             .unwrap();
 
-        let (scene, camera_pos, camera_rot) = game.render();
+        let Ok((scene, camera_pos, camera_rot)) = game.try_render() else {
+            frame_stream
+                .end(
+                    xr_frame_state.predicted_display_time,
+                    environment_blend_mode,
+                    &[],
+                )
+                .unwrap();
+            continue;
+        };
 
         // Render to each eye
         let time = now.elapsed().as_secs_f32();
@@ -789,6 +893,12 @@ fn main() {
             true,
         );
 
+        if let Some(path) = game.take_photo_capture_request() {
+            capture_photo(
+                &mut game, &engine, camera_pos, camera_rot, time, &scene, &path,
+            );
+        }
+
         let swap1 = &swapchain[0].handle.borrow();
         let rect = xr::Rect2Di {
             offset: xr::Offset2Di { x: 0, y: 0 },
@@ -997,6 +1107,59 @@ fn render_swapchain(
     xr_swapchain.release_image().unwrap();
 }
 
+// Photos from the drone camera are captured at a fixed 16:9 resolution, independent of the eye buffers
+const PHOTO_WIDTH: u32 = 2560;
+const PHOTO_HEIGHT: u32 = 1440;
+
+fn capture_photo(
+    game: &mut Game,
+    engine: &Box<dyn engine::Engine>,
+    camera_pos: Vector3<f32>,
+    camera_rot: Quaternion<f32>,
+    time: f32,
+    scene: &Vec<SceneObject>,
+    path: &std::path::Path,
+) {
+    let Some(fov) = game.photo_mode().map(|photo_mode| photo_mode.fov) else {
+        return;
+    };
+
+    let projection_matrix = cgmath::perspective(
+        cgmath::Deg(fov),
+        PHOTO_WIDTH as f32 / PHOTO_HEIGHT as f32,
+        0.1,
+        1000.0,
+    );
+    let screen_size = vec2(PHOTO_WIDTH as f32, PHOTO_HEIGHT as f32);
+    // Captured from the drone itself, ignoring where the headset is looking
+    let render_context = engine::EngineRenderContext {
+        time,
+        camera_offset: camera_pos,
+        camera_rotation: camera_rot,
+        head_offset: vec3(0.0, 0.0, 0.0),
+        head_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+        projection_matrix,
+        screen_size,
+    };
+
+    let view_matrix = engine::util::compute_view_matrix_from_render_context(&render_context);
+    let mut all_scene_objs = game.render_per_eye(view_matrix, projection_matrix, screen_size);
+    all_scene_objs.extend(scene.iter().cloned());
+    let scene_for_render = Scene::from_objects(all_scene_objs);
+
+    let mut pixels = engine::capture::render_offscreen(PHOTO_WIDTH, PHOTO_HEIGHT, || {
+        engine.render(&render_context, &scene_for_render)
+    });
+    if let Some(photo_mode) = game.photo_mode() {
+        photo_mode.filter.apply(&mut pixels);
+    }
+
+    match engine::capture::save_png(path, PHOTO_WIDTH, PHOTO_HEIGHT, pixels) {
+        Ok(()) => tracing::info!("Saved photo to {:?}", path),
+        Err(err) => tracing::warn!("Unable to save photo to {:?}: {}", path, err),
+    }
+}
+
 const VIEW_TYPE: xr::ViewConfigurationType = xr::ViewConfigurationType::PRIMARY_STEREO;
 pub const VIEW_COUNT: u32 = 2;
 
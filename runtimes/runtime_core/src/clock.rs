@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use shock2vr::time::Time;
+
+///
+/// FrameClock
+///
+/// Turns a platform's clock readings (in seconds) into per-frame game time
+pub struct FrameClock {
+    start_time: f32,
+    last_time: f32,
+}
+
+impl FrameClock {
+    pub fn new(now: f32) -> FrameClock {
+        FrameClock {
+            start_time: now,
+            last_time: now,
+        }
+    }
+
+    pub fn tick(&mut self, now: f32) -> Time {
+        // Clocks can go backwards across a suspend / resume - treat it as no time passing
+        let delta_time = (now - self.last_time).max(0.0);
+        self.last_time = now;
+
+        Time {
+            elapsed: Duration::from_secs_f32(delta_time),
+            total: Duration::from_secs_f32((now - self.start_time).max(0.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick() {
+        let mut clock = FrameClock::new(10.0);
+        let time = clock.tick(10.5);
+        assert_eq!(time.elapsed, Duration::from_secs_f32(0.5));
+        assert_eq!(time.total, Duration::from_secs_f32(0.5));
+
+        let time = clock.tick(11.0);
+        assert_eq!(time.elapsed, Duration::from_secs_f32(0.5));
+        assert_eq!(time.total, Duration::from_secs_f32(1.0));
+    }
+
+    #[test]
+    fn test_tick_backwards() {
+        let mut clock = FrameClock::new(10.0);
+        let time = clock.tick(9.0);
+        assert_eq!(time.elapsed, Duration::ZERO);
+        assert_eq!(time.total, Duration::ZERO);
+    }
+}
@@ -0,0 +1,219 @@
+use std::f32::consts::PI;
+
+use cgmath::{Deg, InnerSpace, Quaternion, Vector3, vec3};
+
+// The default vertical field of view, in degrees
+pub const DEFAULT_FOV: f32 = shock2vr::photo_mode::DEFAULT_FOV;
+
+// Range the field of view is clamped to, in degrees
+pub const MIN_FOV: f32 = 30.0;
+pub const MAX_FOV: f32 = 110.0;
+
+// Head bob at full strength - how far the head rises and sways, in meters
+const HEAD_BOB_HEIGHT: f32 = 0.04;
+const HEAD_BOB_SWAY: f32 = 0.02;
+
+// Steps per second while walking
+const HEAD_BOB_STEP_RATE: f32 = 1.8;
+
+// Slower than this (meters per second), the player isn't walking - faster, they were teleported
+const HEAD_BOB_MIN_SPEED: f32 = 0.5;
+const HEAD_BOB_MAX_SPEED: f32 = 20.0;
+
+// How quickly the bob fades in and out when starting and stopping, per second
+const HEAD_BOB_FADE_RATE: f32 = 4.0;
+
+///
+/// FlatCameraSettings
+///
+/// How the flat-screen runtimes view the game - there's no headset to take these from
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlatCameraSettings {
+    /// Vertical field of view, in degrees
+    pub fov: f32,
+    /// Head bob strength while walking, from 0.0 (none) to 1.0
+    pub head_bob: f32,
+    /// Seconds for the view to catch up with the mouse - 0.0 follows it exactly
+    pub smoothing: f32,
+}
+
+impl FlatCameraSettings {
+    /// Clamp the settings to the ranges that make sense
+    pub fn clamped(self) -> FlatCameraSettings {
+        FlatCameraSettings {
+            fov: self.fov.clamp(MIN_FOV, MAX_FOV),
+            head_bob: self.head_bob.clamp(0.0, 1.0),
+            smoothing: self.smoothing.max(0.0),
+        }
+    }
+}
+
+impl Default for FlatCameraSettings {
+    fn default() -> Self {
+        FlatCameraSettings {
+            fov: DEFAULT_FOV,
+            head_bob: 0.0,
+            smoothing: 0.0,
+        }
+    }
+}
+
+///
+/// FlatCamera
+///
+/// The view for a flat-screen runtime - the mouse look rotation, smoothed, plus head bob from the
+/// player's movement. Updated once a frame, and turned into the `FrameView` the render context is
+/// built from.
+pub struct FlatCamera {
+    settings: FlatCameraSettings,
+    rotation: Option<Quaternion<f32>>,
+    last_position: Option<Vector3<f32>>,
+    bob_phase: f32,
+    // How much of the bob is applied, fading in and out as the player starts and stops
+    bob_weight: f32,
+}
+
+impl FlatCamera {
+    pub fn new(settings: FlatCameraSettings) -> FlatCamera {
+        FlatCamera {
+            settings: settings.clamped(),
+            rotation: None,
+            last_position: None,
+            bob_phase: 0.0,
+            bob_weight: 0.0,
+        }
+    }
+
+    pub fn settings(&self) -> &FlatCameraSettings {
+        &self.settings
+    }
+
+    pub fn fov(&self) -> Deg<f32> {
+        Deg(self.settings.fov)
+    }
+
+    ///
+    /// update
+    ///
+    /// Follow the mouse look rotation, and the player's position (None outside of gameplay)
+    pub fn update(
+        &mut self,
+        delta_time: f32,
+        target_rotation: Quaternion<f32>,
+        player_position: Option<Vector3<f32>>,
+    ) {
+        self.rotation = Some(match self.rotation {
+            Some(rotation) if self.settings.smoothing > 0.0 => {
+                let amount = 1.0 - (-delta_time / self.settings.smoothing).exp();
+                rotation.slerp(target_rotation, amount)
+            }
+            _ => target_rotation,
+        });
+
+        let speed = match (self.last_position, player_position) {
+            (Some(last_position), Some(position)) if delta_time > 0.0 => {
+                let movement = position - last_position;
+                vec3(movement.x, 0.0, movement.z).magnitude() / delta_time
+            }
+            _ => 0.0,
+        };
+        self.last_position = player_position;
+
+        let is_walking = (HEAD_BOB_MIN_SPEED..HEAD_BOB_MAX_SPEED).contains(&speed);
+        let target_weight = if is_walking { 1.0 } else { 0.0 };
+        let max_change = HEAD_BOB_FADE_RATE * delta_time;
+        self.bob_weight += (target_weight - self.bob_weight).clamp(-max_change, max_change);
+        if self.bob_weight > 0.0 {
+            self.bob_phase = (self.bob_phase + delta_time * HEAD_BOB_STEP_RATE).fract();
+        } else {
+            self.bob_phase = 0.0;
+        }
+    }
+
+    /// The view rotation - the smoothed target rotation, or None before the first update
+    pub fn rotation(&self) -> Option<Quaternion<f32>> {
+        self.rotation
+    }
+
+    /// Offset for the head, bobbing up on each step and swaying side to side every other step
+    pub fn head_bob_offset(&self) -> Vector3<f32> {
+        let strength = self.settings.head_bob * self.bob_weight;
+        if strength <= 0.0 {
+            return vec3(0.0, 0.0, 0.0);
+        }
+
+        let angle = self.bob_phase * 2.0 * PI;
+        let right = self
+            .rotation
+            .map(|rotation| rotation * vec3(1.0, 0.0, 0.0))
+            .unwrap_or(vec3(1.0, 0.0, 0.0));
+        let up = vec3(0.0, (angle * 2.0).sin().abs() * HEAD_BOB_HEIGHT, 0.0);
+        let sway = right * angle.sin() * HEAD_BOB_SWAY;
+        (up + sway) * strength
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{Rad, Rotation3};
+
+    #[test]
+    fn test_settings_are_clamped() {
+        let camera = FlatCamera::new(FlatCameraSettings {
+            fov: 170.0,
+            head_bob: 2.0,
+            smoothing: -1.0,
+        });
+        assert_eq!(camera.settings().fov, MAX_FOV);
+        assert_eq!(camera.settings().head_bob, 1.0);
+        assert_eq!(camera.settings().smoothing, 0.0);
+    }
+
+    #[test]
+    fn test_smoothing_eases_towards_target() {
+        let start = Quaternion::from_angle_y(Rad(0.0));
+        let target = Quaternion::from_angle_y(Rad(1.0));
+
+        let mut camera = FlatCamera::new(FlatCameraSettings::default());
+        camera.update(0.1, start, None);
+        camera.update(0.1, target, None);
+        assert_eq!(camera.rotation(), Some(target));
+
+        let mut camera = FlatCamera::new(FlatCameraSettings {
+            smoothing: 0.2,
+            ..FlatCameraSettings::default()
+        });
+        camera.update(0.1, start, None);
+        camera.update(0.1, target, None);
+        let rotation = camera.rotation().unwrap();
+        assert!(rotation.dot(target) < 0.9999);
+        assert!(rotation.dot(target) > start.dot(target));
+    }
+
+    #[test]
+    fn test_head_bob_only_while_walking() {
+        let rotation = Quaternion::from_angle_y(Rad(0.0));
+        let mut camera = FlatCamera::new(FlatCameraSettings {
+            head_bob: 1.0,
+            ..FlatCameraSettings::default()
+        });
+
+        camera.update(0.1, rotation, Some(vec3(0.0, 0.0, 0.0)));
+        camera.update(0.1, rotation, Some(vec3(0.0, 0.0, 0.0)));
+        assert_eq!(camera.head_bob_offset(), vec3(0.0, 0.0, 0.0));
+
+        // Walking at 2 m/s
+        for step in 1..10 {
+            camera.update(0.1, rotation, Some(vec3(0.2 * step as f32, 0.0, 0.0)));
+        }
+        assert!(camera.head_bob_offset().magnitude() > 0.0);
+
+        // Teleported - not walking
+        camera.update(0.1, rotation, Some(vec3(100.0, 0.0, 0.0)));
+        for _ in 0..10 {
+            camera.update(0.1, rotation, Some(vec3(100.0, 0.0, 0.0)));
+        }
+        assert_eq!(camera.head_bob_offset(), vec3(0.0, 0.0, 0.0));
+    }
+}
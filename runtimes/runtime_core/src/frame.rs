@@ -0,0 +1,79 @@
+use cgmath::{Deg, Quaternion, Vector2, Vector3};
+use engine::{
+    Engine, EngineRenderContext, profile, scene::Scene,
+    util::compute_view_matrix_from_render_context,
+};
+use shock2vr::Game;
+
+const NEAR_PLANE: f32 = 0.1;
+const FAR_PLANE: f32 = 1000.0;
+
+///
+/// FrameView
+///
+/// How the host wants this frame viewed - the head pose on top of the player's pawn, and the screen
+pub struct FrameView {
+    /// Time passed to the shaders
+    pub time: f32,
+    pub head_offset: Vector3<f32>,
+    pub head_rotation: Quaternion<f32>,
+    pub fov: Deg<f32>,
+    pub screen_size: Vector2<f32>,
+}
+
+pub struct RenderedFrame {
+    pub render_context: EngineRenderContext,
+    pub scene: Scene,
+}
+
+///
+/// render_frame
+///
+/// Render the game from the given view - returns None if the game has crashed, and can't be rendered
+pub fn render_frame(
+    game: &mut Game,
+    engine: &dyn Engine,
+    view: &FrameView,
+) -> Option<RenderedFrame> {
+    let (mut scene, pawn_offset, pawn_rotation) =
+        profile!("game.render", game.try_render()).ok()?;
+
+    let ratio = view.screen_size.x / view.screen_size.y;
+    let projection_matrix = cgmath::perspective(view.fov, ratio, NEAR_PLANE, FAR_PLANE);
+
+    let render_context = EngineRenderContext {
+        time: view.time,
+        camera_offset: pawn_offset,
+        camera_rotation: pawn_rotation,
+        head_offset: view.head_offset,
+        head_rotation: view.head_rotation,
+        projection_matrix,
+        screen_size: view.screen_size,
+    };
+
+    let view_matrix = compute_view_matrix_from_render_context(&render_context);
+    let per_eye_scene = profile!(
+        "game.render_per_eye",
+        game.render_per_eye(view_matrix, projection_matrix, view.screen_size)
+    );
+    scene.extend(per_eye_scene);
+
+    let mut scene = Scene::from_objects(scene);
+
+    // Hand spotlights (enhanced_lighting experimental feature)
+    for spotlight in game.get_hand_spotlights() {
+        scene.lights_mut().add_spotlight(spotlight);
+    }
+
+    profile!("engine.render", engine.render(&render_context, &scene));
+
+    profile!(
+        "game.finish_render",
+        game.finish_render(view_matrix, projection_matrix, view.screen_size)
+    );
+
+    Some(RenderedFrame {
+        render_context,
+        scene,
+    })
+}
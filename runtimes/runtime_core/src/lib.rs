@@ -0,0 +1,94 @@
+// Runtime Core - the frame loop shared by the runtimes
+//
+// Every runtime drives the game the same way: tick the clock, gather input, update, render from
+// the player's view, and present. A runtime implements `RuntimeHost` for the platform-specific
+// parts (windowing, input, what to do with a rendered frame) and hands it to `run`.
+
+mod clock;
+mod flat_camera;
+mod frame;
+pub mod window;
+
+pub use clock::FrameClock;
+pub use flat_camera::{DEFAULT_FOV, FlatCamera, FlatCameraSettings, MAX_FOV, MIN_FOV};
+pub use frame::{FrameView, RenderedFrame, render_frame};
+pub use window::{DesktopWindow, WindowOptions};
+
+use engine::{Engine, profile};
+use shock2vr::{Game, command::Command, input_context::InputContext, time::Time};
+
+///
+/// FrameInput
+///
+/// Everything the game is updated with for a frame
+pub struct FrameInput {
+    /// Game time for this update - usually the frame clock's, but hosts can pause or step it
+    pub time: Time,
+    pub input_context: InputContext,
+    pub commands: Vec<Box<dyn Command>>,
+}
+
+impl FrameInput {
+    pub fn new(time: Time, input_context: InputContext) -> FrameInput {
+        FrameInput {
+            time,
+            input_context,
+            commands: Vec::new(),
+        }
+    }
+}
+
+///
+/// RuntimeHost
+///
+/// The platform-specific half of a runtime
+pub trait RuntimeHost {
+    /// The platform clock, in seconds
+    fn now(&self) -> f32;
+
+    /// Whether the loop should stop - ie, the window was closed
+    fn should_stop(&self) -> bool;
+
+    /// Handle platform events, and build this frame's input from the frame clock's time
+    fn begin_frame(&mut self, game: &mut Game, time: Time) -> FrameInput;
+
+    /// Update the game - a panic is written out as a crash report, and the game stops updating.
+    /// Hosts can override this to surface the crash, or record frame timings
+    fn update(&mut self, game: &mut Game, input: FrameInput) {
+        let _ = profile!(
+            "game.update",
+            game.try_update(&input.time, &input.input_context, input.commands)
+        );
+    }
+
+    /// The view to render this frame from, or None to skip rendering
+    fn view(&self, game: &Game) -> Option<FrameView>;
+
+    /// Called after a frame is rendered - for captures that re-render the same scene
+    fn end_frame(&mut self, _game: &mut Game, _engine: &dyn Engine, _frame: &RenderedFrame) {}
+
+    /// Show the frame
+    fn present(&mut self);
+}
+
+///
+/// run
+///
+/// Drive the game until the host stops
+pub fn run(host: &mut impl RuntimeHost, game: &mut Game, engine: &dyn Engine) {
+    let mut clock = FrameClock::new(host.now());
+
+    while !host.should_stop() {
+        let time = clock.tick(host.now());
+        let input = host.begin_frame(game, time);
+        host.update(game, input);
+
+        if let Some(view) = host.view(game) {
+            if let Some(frame) = render_frame(game, engine, &view) {
+                host.end_frame(game, engine, &frame);
+            }
+        }
+
+        host.present();
+    }
+}
@@ -0,0 +1,116 @@
+// Desktop window setup - a GLFW window with an OpenGL 4.1 core context, shared by the desktop
+// runtime, the debug runtime and the tools that render with the engine
+
+use glfw::{Context, GlfwReceiver, PWindow, WindowEvent};
+use tracing::info;
+
+pub struct WindowOptions {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    /// Hidden windows still get a GL context - for tests that need one, but nothing on screen
+    pub visible: bool,
+    /// Hide and lock the cursor, for mouse-look
+    pub capture_cursor: bool,
+}
+
+impl WindowOptions {
+    pub fn new(title: &str, width: u32, height: u32) -> WindowOptions {
+        WindowOptions {
+            title: title.to_owned(),
+            width,
+            height,
+            visible: true,
+            capture_cursor: false,
+        }
+    }
+
+    pub fn hidden(self) -> WindowOptions {
+        WindowOptions {
+            visible: false,
+            ..self
+        }
+    }
+
+    pub fn with_captured_cursor(self) -> WindowOptions {
+        WindowOptions {
+            capture_cursor: true,
+            ..self
+        }
+    }
+}
+
+pub struct DesktopWindow {
+    pub glfw: glfw::Glfw,
+    pub window: PWindow,
+    pub events: GlfwReceiver<(f64, WindowEvent)>,
+}
+
+impl DesktopWindow {
+    ///
+    /// create
+    ///
+    /// Create the window, make its GL context current, and load the GL function pointers
+    pub fn create(options: &WindowOptions) -> DesktopWindow {
+        let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
+        // TODO: Figure out ANGLE
+        // glfw.window_hint(glfw::WindowHint::ClientApi(glfw::OpenGlEs));
+        glfw.window_hint(glfw::WindowHint::ContextVersion(4, 1));
+        glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+            glfw::OpenGlProfileHint::Core,
+        ));
+        #[cfg(target_os = "macos")]
+        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+        if !options.visible {
+            glfw.window_hint(glfw::WindowHint::Visible(false));
+        }
+
+        let (mut window, events) = glfw
+            .create_window(
+                options.width,
+                options.height,
+                &options.title,
+                glfw::WindowMode::Windowed,
+            )
+            .expect("Failed to create GLFW window");
+
+        window.make_current();
+        window.set_key_polling(true);
+        window.set_char_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_scroll_polling(true);
+        window.set_framebuffer_size_polling(true);
+        if options.capture_cursor {
+            window.set_cursor_mode(glfw::CursorMode::Disabled);
+        }
+
+        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+
+        info!(
+            "es2 extension supported: {}, es3 extension supported: {}",
+            glfw.extension_supported("GL_ARB_ES2_compatibility"),
+            glfw.extension_supported("GL_ARB_ES3_compatibility")
+        );
+
+        DesktopWindow {
+            glfw,
+            window,
+            events,
+        }
+    }
+
+    /// Seconds since GLFW was initialized
+    pub fn now(&self) -> f32 {
+        self.glfw.get_time() as f32
+    }
+
+    pub fn should_close(&self) -> bool {
+        self.window.should_close()
+    }
+
+    /// Swap buffers, and poll for the next frame's events
+    pub fn present(&mut self) {
+        self.window.swap_buffers();
+        self.glfw.poll_events();
+    }
+}
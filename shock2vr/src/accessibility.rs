@@ -0,0 +1,234 @@
+use cgmath::{Vector4, vec4};
+use serde::{Deserialize, Serialize};
+
+// Keep scaled text within a range the HUD layout can still cope with
+const MIN_TEXT_SCALE: f32 = 0.5;
+const MAX_TEXT_SCALE: f32 = 3.0;
+
+// Below 0.1x the game looks frozen, and above 8x the simulation falls apart
+pub const MIN_TIME_SCALE: f32 = 0.1;
+pub const MAX_TIME_SCALE: f32 = 8.0;
+
+///
+/// ColorPalette
+///
+/// Color scheme for outlines and highlights. The colorblind-safe palettes avoid
+/// relying on the hue pairs that are hard to tell apart for each type of color blindness.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorPalette {
+    #[default]
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl ColorPalette {
+    pub fn from_name(name: &str) -> Option<ColorPalette> {
+        match name.to_ascii_lowercase().as_str() {
+            "standard" => Some(ColorPalette::Standard),
+            "deuteranopia" => Some(ColorPalette::Deuteranopia),
+            "protanopia" => Some(ColorPalette::Protanopia),
+            "tritanopia" => Some(ColorPalette::Tritanopia),
+            "high_contrast" | "highcontrast" => Some(ColorPalette::HighContrast),
+            _ => None,
+        }
+    }
+
+    ///
+    /// outline_color
+    ///
+    /// Tint for the brackets drawn around the hovered item
+    pub fn outline_color(&self) -> Vector4<f32> {
+        match self {
+            // Untinted - the bracket textures keep their original colors
+            ColorPalette::Standard => vec4(1.0, 1.0, 1.0, 1.0),
+            // Blue / orange are distinguishable with red-green color blindness
+            ColorPalette::Deuteranopia => vec4(0.0, 0.45, 0.7, 1.0),
+            ColorPalette::Protanopia => vec4(0.9, 0.6, 0.0, 1.0),
+            // Red / cyan for blue-yellow color blindness
+            ColorPalette::Tritanopia => vec4(0.85, 0.15, 0.3, 1.0),
+            ColorPalette::HighContrast => vec4(1.0, 1.0, 0.0, 1.0),
+        }
+    }
+
+    ///
+    /// highlight_color
+    ///
+    /// Color for warnings and alerts (alarms, hazards) that must stand out from the outline color
+    pub fn highlight_color(&self) -> Vector4<f32> {
+        match self {
+            ColorPalette::Standard => vec4(1.0, 0.0, 0.0, 1.0),
+            ColorPalette::Deuteranopia => vec4(0.9, 0.6, 0.0, 1.0),
+            ColorPalette::Protanopia => vec4(0.0, 0.45, 0.7, 1.0),
+            ColorPalette::Tritanopia => vec4(0.0, 0.6, 0.5, 1.0),
+            ColorPalette::HighContrast => vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+}
+
+///
+/// ButtonMode
+///
+/// Whether an action is active only while its button is held, or flips on each press
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ButtonMode {
+    #[default]
+    Hold,
+    Toggle,
+}
+
+impl ButtonMode {
+    pub fn from_name(name: &str) -> Option<ButtonMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "hold" => Some(ButtonMode::Hold),
+            "toggle" => Some(ButtonMode::Toggle),
+            _ => None,
+        }
+    }
+}
+
+///
+/// AccessibilitySettings
+///
+/// Player-facing accessibility options - persisted per profile
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Multiplier for subtitle and HUD text
+    pub text_scale: f32,
+    pub color_palette: ColorPalette,
+    pub grab_mode: ButtonMode,
+    pub crouch_mode: ButtonMode,
+    /// Replace flashing / pulsing effects (alarms, damage flash, low health pulse) with steady ones
+    pub reduced_flashing: bool,
+    /// Game speed multiplier - below 1 for slow motion, above 1 to fast-forward
+    pub time_scale: f32,
+    /// Let the hands touch the world - press buttons and push props by reaching out, alongside pointing
+    pub physical_hands: bool,
+    /// Show subtitles for announcements
+    pub subtitles: bool,
+    /// Preview where a primed grenade will land
+    pub throw_arc: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        AccessibilitySettings {
+            text_scale: 1.0,
+            color_palette: ColorPalette::Standard,
+            grab_mode: ButtonMode::Hold,
+            crouch_mode: ButtonMode::Hold,
+            reduced_flashing: false,
+            time_scale: 1.0,
+            physical_hands: true,
+            subtitles: true,
+            throw_arc: true,
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    pub fn scaled_font_size(&self, font_size: f32) -> f32 {
+        font_size * self.text_scale.clamp(MIN_TEXT_SCALE, MAX_TEXT_SCALE)
+    }
+
+    pub fn clamped_time_scale(&self) -> f32 {
+        self.time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE)
+    }
+}
+
+///
+/// ButtonLatch
+///
+/// Turns a raw button into an 'is active' state, following the button mode -
+/// in toggle mode, each press flips the state, instead of it following the button.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ButtonLatch {
+    was_pressed: bool,
+    active: bool,
+}
+
+impl ButtonLatch {
+    pub fn new() -> ButtonLatch {
+        ButtonLatch {
+            was_pressed: false,
+            active: false,
+        }
+    }
+
+    pub fn update(&mut self, mode: ButtonMode, is_pressed: bool) -> bool {
+        let just_pressed = is_pressed && !self.was_pressed;
+        self.was_pressed = is_pressed;
+
+        self.active = match mode {
+            ButtonMode::Hold => is_pressed,
+            ButtonMode::Toggle => {
+                if just_pressed {
+                    !self.active
+                } else {
+                    self.active
+                }
+            }
+        };
+        self.active
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    ///
+    /// release
+    ///
+    /// Turn the latch off without waiting for another press - ie, when a toggled grab didn't catch anything
+    pub fn release(&mut self) {
+        self.active = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hold_follows_button() {
+        let mut latch = ButtonLatch::new();
+        assert!(latch.update(ButtonMode::Hold, true));
+        assert!(latch.update(ButtonMode::Hold, true));
+        assert!(!latch.update(ButtonMode::Hold, false));
+    }
+
+    #[test]
+    fn test_toggle_flips_on_press() {
+        let mut latch = ButtonLatch::new();
+        assert!(latch.update(ButtonMode::Toggle, true));
+        assert!(latch.update(ButtonMode::Toggle, false));
+        assert!(!latch.update(ButtonMode::Toggle, true));
+        assert!(!latch.update(ButtonMode::Toggle, false));
+
+        latch.update(ButtonMode::Toggle, true);
+        latch.release();
+        assert!(!latch.update(ButtonMode::Toggle, true));
+    }
+
+    #[test]
+    fn test_text_scale_is_clamped() {
+        let settings = AccessibilitySettings {
+            text_scale: 10.0,
+            ..AccessibilitySettings::default()
+        };
+        assert_eq!(settings.scaled_font_size(10.0), 10.0 * MAX_TEXT_SCALE);
+    }
+
+    #[test]
+    fn test_time_scale_is_clamped() {
+        let mut settings = AccessibilitySettings::default();
+        assert_eq!(settings.clamped_time_scale(), 1.0);
+        settings.time_scale = 0.0;
+        assert_eq!(settings.clamped_time_scale(), MIN_TIME_SCALE);
+        settings.time_scale = 100.0;
+        assert_eq!(settings.clamped_time_scale(), MAX_TIME_SCALE);
+    }
+}
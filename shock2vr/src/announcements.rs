@@ -0,0 +1,288 @@
+///
+/// announcements.rs
+///
+/// The Von Braun's PA system - ambient speeches and announcements, triggered by time in the mission
+/// or by quest bits, played through a global audio channel with an optional subtitle.
+///
+/// Announcements are data-driven, from `announcements.json` in the bundle, so level designers can
+/// add to them without touching code:
+///
+/// ```json
+/// [
+///   {
+///     "id": "medsci_welcome",
+///     "missions": ["medsci1"],
+///     "sound": "xerxes_welcome",
+///     "subtitle": "announcement-medsci-welcome",
+///     "trigger": { "timer": { "after_seconds": 30.0 } }
+///   },
+///   {
+///     "id": "lockdown",
+///     "sound": "xerxes_lockdown",
+///     "trigger": { "quest_bit": { "name": "note_lockdown", "value": 2 } }
+///   }
+/// ]
+/// ```
+///
+use std::io::Read;
+
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::{quest_info::QuestInfo, scripts::Effect};
+
+const ANNOUNCEMENTS_FILE: &str = "announcements.json";
+
+///
+/// AnnouncementTrigger
+///
+/// When an announcement plays - `after_seconds` into the mission (and every `repeat_seconds` after that,
+/// if set), or once a quest bit reaches `value`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementTrigger {
+    Timer {
+        after_seconds: f32,
+        #[serde(default)]
+        repeat_seconds: Option<f32>,
+    },
+    QuestBit {
+        name: String,
+        value: u32,
+    },
+}
+
+///
+/// Announcement
+///
+/// A single announcement. `missions` limits it to particular levels (ie, `medsci1`) - empty plays everywhere.
+/// `sound` is a sound schema or sample name, and `subtitle` an id in `ui.ftl`.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct Announcement {
+    pub id: String,
+    #[serde(default)]
+    pub missions: Vec<String>,
+    pub sound: String,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+    pub trigger: AnnouncementTrigger,
+}
+
+impl Announcement {
+    fn applies_to_mission(&self, mission: &str) -> bool {
+        let mission = normalize_mission_name(mission);
+        self.missions.is_empty()
+            || self
+                .missions
+                .iter()
+                .any(|m| normalize_mission_name(m) == mission)
+    }
+
+    fn is_repeating(&self) -> bool {
+        matches!(
+            self.trigger,
+            AnnouncementTrigger::Timer {
+                repeat_seconds: Some(_),
+                ..
+            }
+        )
+    }
+
+    fn to_effect(&self) -> Effect {
+        Effect::PlayAnnouncement {
+            sound: self.sound.clone(),
+            subtitle: self.subtitle.clone(),
+        }
+    }
+}
+
+fn normalize_mission_name(mission: &str) -> String {
+    let lower = mission.to_ascii_lowercase();
+    lower.strip_suffix(".mis").unwrap_or(&lower).to_owned()
+}
+
+pub fn parse_announcements(json: &str) -> Result<Vec<Announcement>, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+fn import_announcements(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> Vec<Announcement> {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read announcements {}: {}", name, err);
+        return vec![];
+    }
+
+    parse_announcements(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse announcements {}: {}", name, err);
+        vec![]
+    })
+}
+
+fn process_announcements(
+    content: Vec<Announcement>,
+    _asset_cache: &mut AssetCache,
+    _config: &(),
+) -> Vec<Announcement> {
+    content
+}
+
+static ANNOUNCEMENTS_IMPORTER: Lazy<AssetImporter<Vec<Announcement>, Vec<Announcement>, ()>> =
+    Lazy::new(|| AssetImporter::define(import_announcements, process_announcements));
+
+struct ScheduledAnnouncement {
+    announcement: Announcement,
+    // Mission time the announcement is next due, for timers
+    next_time: Option<f32>,
+}
+
+///
+/// AnnouncementSystem
+///
+/// Plays the announcements for the current mission as their triggers fire. One-shot announcements
+/// are remembered in the QuestInfo, so they don't replay after loading a save.
+pub struct AnnouncementSystem {
+    scheduled: Vec<ScheduledAnnouncement>,
+    elapsed: f32,
+}
+
+impl AnnouncementSystem {
+    pub fn load(asset_cache: &mut AssetCache, mission: &str) -> AnnouncementSystem {
+        let announcements = asset_cache
+            .get_opt(&ANNOUNCEMENTS_IMPORTER, ANNOUNCEMENTS_FILE)
+            .map(|announcements| announcements.as_ref().clone())
+            .unwrap_or_default();
+        AnnouncementSystem::new(announcements, mission)
+    }
+
+    pub fn new(announcements: Vec<Announcement>, mission: &str) -> AnnouncementSystem {
+        let scheduled = announcements
+            .into_iter()
+            .filter(|announcement| announcement.applies_to_mission(mission))
+            .map(|announcement| {
+                let next_time = match announcement.trigger {
+                    AnnouncementTrigger::Timer { after_seconds, .. } => Some(after_seconds),
+                    AnnouncementTrigger::QuestBit { .. } => None,
+                };
+                ScheduledAnnouncement {
+                    announcement,
+                    next_time,
+                }
+            })
+            .collect();
+
+        AnnouncementSystem {
+            scheduled,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32, quest_info: &mut QuestInfo) -> Vec<Effect> {
+        self.elapsed += delta_time;
+
+        let mut effects = Vec::new();
+        for scheduled in &mut self.scheduled {
+            let announcement = &scheduled.announcement;
+            let is_repeating = announcement.is_repeating();
+            if !is_repeating && quest_info.has_played_announcement(&announcement.id) {
+                continue;
+            }
+
+            let should_play = match &announcement.trigger {
+                AnnouncementTrigger::Timer { repeat_seconds, .. } => match scheduled.next_time {
+                    Some(next_time) if self.elapsed >= next_time => {
+                        scheduled.next_time = repeat_seconds.map(|repeat| next_time + repeat);
+                        true
+                    }
+                    _ => false,
+                },
+                AnnouncementTrigger::QuestBit { name, value } => {
+                    quest_info.read_quest_bit_value(name).bits() == *value
+                }
+            };
+
+            if should_play {
+                if !is_repeating {
+                    quest_info.mark_announcement_as_played(&announcement.id);
+                }
+                effects.push(announcement.to_effect());
+            }
+        }
+
+        effects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::QuestBitValue;
+
+    use super::*;
+
+    const ANNOUNCEMENTS: &str = r#"[
+        { "id": "welcome", "missions": ["medsci1"], "sound": "welcome", "subtitle": "welcome-text",
+          "trigger": { "timer": { "after_seconds": 1.0 } } },
+        { "id": "reminder", "sound": "reminder",
+          "trigger": { "timer": { "after_seconds": 2.0, "repeat_seconds": 2.0 } } },
+        { "id": "lockdown", "missions": ["eng1.mis"], "sound": "lockdown",
+          "trigger": { "quest_bit": { "name": "note_lockdown", "value": 2 } } }
+    ]"#;
+
+    fn sounds(effects: &[Effect]) -> Vec<String> {
+        effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::PlayAnnouncement { sound, .. } => Some(sound.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_timers_fire_once_or_repeat() {
+        let announcements = parse_announcements(ANNOUNCEMENTS).unwrap();
+        let mut system = AnnouncementSystem::new(announcements, "MEDSCI1.MIS");
+        let mut quest_info = QuestInfo::new();
+
+        assert!(system.update(0.5, &mut quest_info).is_empty());
+        assert_eq!(
+            sounds(&system.update(0.5, &mut quest_info)),
+            vec!["welcome"]
+        );
+        assert_eq!(
+            sounds(&system.update(1.0, &mut quest_info)),
+            vec!["reminder"]
+        );
+        assert!(system.update(1.0, &mut quest_info).is_empty());
+        assert_eq!(
+            sounds(&system.update(1.0, &mut quest_info)),
+            vec!["reminder"]
+        );
+        assert!(quest_info.has_played_announcement("welcome"));
+    }
+
+    #[test]
+    fn test_quest_bit_fires_once_and_persists() {
+        let announcements = parse_announcements(ANNOUNCEMENTS).unwrap();
+        let mut quest_info = QuestInfo::new();
+        let mut system = AnnouncementSystem::new(announcements.clone(), "eng1");
+
+        assert!(sounds(&system.update(0.1, &mut quest_info)).is_empty());
+        quest_info.set_quest_bit_value("note_lockdown", QuestBitValue::COMPLETE);
+        assert_eq!(
+            sounds(&system.update(0.1, &mut quest_info)),
+            vec!["lockdown"]
+        );
+        assert!(sounds(&system.update(0.1, &mut quest_info)).is_empty());
+
+        // Reloading the mission with the same quest info shouldn't replay it
+        let mut reloaded = AnnouncementSystem::new(announcements, "eng1");
+        assert!(sounds(&reloaded.update(0.1, &mut quest_info)).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+///
+/// attract_mode.rs
+///
+/// After the player has been idle in the starting mission for a while, play back recorded
+/// sessions (see `replay.rs`) with the hands and HUD hidden, cycling through them until the
+/// player picks the controls back up - then return to the starting mission.
+///
+/// Left running, it also works as a soak test: every recording restarts a mission from scratch.
+///
+use cgmath::{InnerSpace, Quaternion};
+use tracing::{info, warn};
+
+use crate::{
+    Game, SpawnLocation,
+    input_context::{Hand, InputContext},
+    replay::{InputRecording, ReplayPlayer},
+    time::Time,
+};
+
+pub const DEFAULT_IDLE_SECONDS: f32 = 30.0;
+
+const STICK_THRESHOLD: f32 = 0.1;
+const BUTTON_THRESHOLD: f32 = 0.5;
+// Head rotations closer than this (as a quaternion dot product) count as holding still
+const HEAD_STILL_THRESHOLD: f32 = 0.9999;
+
+pub struct AttractMode {
+    /// Mission argument to return to when the player takes over
+    start_mission: String,
+    recordings: Vec<InputRecording>,
+    idle_timeout: f32,
+    idle_time: f32,
+    next_recording: usize,
+    playback: Option<ReplayPlayer>,
+    completed_recordings: u32,
+    last_head_rotation: Option<Quaternion<f32>>,
+}
+
+impl AttractMode {
+    pub fn new(
+        start_mission: &str,
+        recordings: Vec<InputRecording>,
+        idle_timeout: f32,
+    ) -> AttractMode {
+        if recordings.is_empty() {
+            warn!("Attract mode has no recordings to play");
+        }
+
+        AttractMode {
+            start_mission: start_mission.to_owned(),
+            recordings,
+            idle_timeout,
+            idle_time: 0.0,
+            next_recording: 0,
+            playback: None,
+            completed_recordings: 0,
+            last_head_rotation: None,
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    ///
+    /// update
+    ///
+    /// Call every frame with the player's input, before updating the game. Returns the time and
+    /// input to update the game with instead, while a recording is playing.
+    pub fn update(
+        &mut self,
+        game: &mut Game,
+        time: &Time,
+        input: &InputContext,
+        has_commands: bool,
+    ) -> Option<(Time, InputContext)> {
+        let active = has_commands || self.has_activity(input);
+
+        if self.playback.is_some() {
+            if active {
+                self.stop(game);
+                return None;
+            }
+            return self.next_frame(game);
+        }
+
+        if active {
+            self.idle_time = 0.0;
+            return None;
+        }
+
+        self.idle_time += time.elapsed.as_secs_f32();
+        if self.idle_time >= self.idle_timeout && !self.recordings.is_empty() {
+            self.start_next(game);
+            return self.next_frame(game);
+        }
+
+        None
+    }
+
+    fn next_frame(&mut self, game: &mut Game) -> Option<(Time, InputContext)> {
+        if let Some(frame) = self
+            .playback
+            .as_mut()
+            .and_then(|player| player.next_frame())
+        {
+            return Some(frame);
+        }
+
+        // Finished - move on to the next recording
+        self.completed_recordings += 1;
+        info!(
+            "Attract mode: finished recording ({} played so far)",
+            self.completed_recordings
+        );
+        self.start_next(game);
+        self.playback
+            .as_mut()
+            .and_then(|player| player.next_frame())
+    }
+
+    fn start_next(&mut self, game: &mut Game) {
+        let recording = self.recordings[self.next_recording].clone();
+        self.next_recording = (self.next_recording + 1) % self.recordings.len();
+
+        info!(
+            "Attract mode: playing {} ({:.1}s)",
+            recording.mission,
+            recording.duration()
+        );
+        restart(game, &recording.mission);
+        game.set_hud_hidden(true);
+        self.playback = Some(ReplayPlayer::new(recording));
+    }
+
+    fn stop(&mut self, game: &mut Game) {
+        info!(
+            "Attract mode: player took over, returning to {}",
+            self.start_mission
+        );
+        self.playback = None;
+        self.idle_time = 0.0;
+        game.set_hud_hidden(false);
+        restart(game, &self.start_mission);
+    }
+
+    fn has_activity(&mut self, input: &InputContext) -> bool {
+        let head_rotation = input.head.rotation;
+        let head_moved = self
+            .last_head_rotation
+            .is_some_and(|last| last.dot(head_rotation).abs() < HEAD_STILL_THRESHOLD);
+        self.last_head_rotation = Some(head_rotation);
+
+        head_moved || hand_active(&input.left_hand) || hand_active(&input.right_hand)
+    }
+}
+
+fn hand_active(hand: &Hand) -> bool {
+    hand.thumbstick.magnitude() > STICK_THRESHOLD
+        || hand.trigger_value > BUTTON_THRESHOLD
+        || hand.squeeze_value > BUTTON_THRESHOLD
+        || hand.a_value > BUTTON_THRESHOLD
+        || hand.b_value > BUTTON_THRESHOLD
+}
+
+fn restart(game: &mut Game, mission_argument: &str) {
+    match SpawnLocation::parse_mission_argument(mission_argument) {
+        Ok((mission, spawn_location)) => game.restart(&mission, spawn_location),
+        Err(err) => warn!("Attract mode: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::vec2;
+
+    use super::*;
+
+    #[test]
+    fn test_hand_activity() {
+        let mut hand = Hand::default();
+        assert!(!hand_active(&hand));
+
+        hand.thumbstick = vec2(0.05, 0.0);
+        assert!(!hand_active(&hand), "stick drift isn't activity");
+
+        hand.trigger_value = 1.0;
+        assert!(hand_active(&hand));
+    }
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, path::Path};
+
+use cgmath::{Deg, Quaternion, Rotation3, Vector3, vec3};
+use serde::{Deserialize, Serialize};
+
+// Frames before this point are dropped from the results - they're dominated by loading hitches
+const WARMUP_SECONDS: f32 = 2.0;
+
+const HEAD_HEIGHT: f32 = 4.0 / dark::SCALE_FACTOR;
+
+///
+/// CameraKeyframe
+///
+/// A point on a benchmark camera path. `time` is a fraction (0 - 1) of the benchmark duration,
+/// and `position` is relative to the player's spawn, so the same path works for any run length.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    pub time: f32,
+    pub position: Vector3<f32>,
+    /// Degrees
+    pub yaw: f32,
+    #[serde(default)]
+    pub pitch: f32,
+}
+
+///
+/// CameraPath
+///
+/// Camera flythrough for a benchmark - linearly interpolated between keyframes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraPath {
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraPath {
+    ///
+    /// default_path
+    ///
+    /// A full turn at head height, drifting out and back - sees the whole area around the spawn,
+    /// which is the part of a mission we know is reachable
+    pub fn default_path() -> CameraPath {
+        let distance = 8.0 / dark::SCALE_FACTOR;
+        let keyframes = (0..=8)
+            .map(|idx| {
+                let time = idx as f32 / 8.0;
+                let drift = if idx % 2 == 0 { 0.0 } else { distance };
+                let yaw = time * 360.0;
+                let forward = Quaternion::from_angle_y(Deg(yaw)) * vec3(0.0, 0.0, -1.0);
+                CameraKeyframe {
+                    time,
+                    position: vec3(0.0, HEAD_HEIGHT, 0.0) + forward * drift,
+                    yaw,
+                    pitch: 0.0,
+                }
+            })
+            .collect();
+        CameraPath { keyframes }
+    }
+
+    pub fn from_json(json: &str) -> Result<CameraPath, serde_json::Error> {
+        let mut path: CameraPath = serde_json::from_str(json)?;
+        path.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(path)
+    }
+
+    ///
+    /// sample
+    ///
+    /// Camera position (relative to the spawn) and rotation at `time` (0 - 1)
+    pub fn sample(&self, time: f32) -> (Vector3<f32>, Quaternion<f32>) {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return (
+                vec3(0.0, HEAD_HEIGHT, 0.0),
+                Quaternion::from_angle_y(Deg(0.0)),
+            );
+        };
+
+        let (from, to) = if time <= first.time {
+            (first, first)
+        } else if time >= last.time {
+            (last, last)
+        } else {
+            let next_idx = self
+                .keyframes
+                .iter()
+                .position(|keyframe| keyframe.time > time)
+                .unwrap_or(self.keyframes.len() - 1);
+            (&self.keyframes[next_idx - 1], &self.keyframes[next_idx])
+        };
+
+        let span = to.time - from.time;
+        let alpha = if span > 0.0 {
+            (time - from.time) / span
+        } else {
+            0.0
+        };
+        let lerp = |a: f32, b: f32| a + (b - a) * alpha;
+
+        let position = from.position + (to.position - from.position) * alpha;
+        let rotation = Quaternion::from_angle_y(Deg(lerp(from.yaw, to.yaw)))
+            * Quaternion::from_angle_x(Deg(lerp(from.pitch, to.pitch)));
+        (position, rotation)
+    }
+}
+
+///
+/// SystemTiming
+///
+/// Average / worst time spent under a `profile!` label, across the benchmark
+#[derive(Clone, Debug, Serialize)]
+pub struct SystemTiming {
+    pub label: String,
+    pub average_ms: f32,
+    pub max_ms: f32,
+}
+
+///
+/// BenchmarkResults
+///
+/// What gets written out at the end of a benchmark run
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkResults {
+    pub mission: String,
+    pub duration_secs: f32,
+    pub frames: usize,
+    pub average_fps: f32,
+    pub min_fps: f32,
+    /// Average fps over the slowest 1% of frames
+    pub one_percent_low_fps: f32,
+    pub average_frame_ms: f32,
+    pub max_frame_ms: f32,
+    pub systems: Vec<SystemTiming>,
+}
+
+impl BenchmarkResults {
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+}
+
+///
+/// Benchmark
+///
+/// Drives the camera along a path for a fixed time, while the simulation (including AI) runs
+/// as normal, and collects frame times and per-system timings from `engine::profiler`.
+pub struct Benchmark {
+    mission: String,
+    duration: f32,
+    path: CameraPath,
+    elapsed: f32,
+    frame_times_ms: Vec<f32>,
+    // label -> (total, max) in milliseconds
+    system_times_ms: HashMap<String, (f32, f32)>,
+}
+
+impl Benchmark {
+    pub fn new(mission: &str, duration: f32, path: CameraPath) -> Benchmark {
+        Benchmark {
+            mission: mission.to_owned(),
+            duration,
+            path,
+            elapsed: 0.0,
+            frame_times_ms: Vec::new(),
+            system_times_ms: HashMap::new(),
+        }
+    }
+
+    ///
+    /// camera
+    ///
+    /// Where the camera should be this frame, given the player's spawn position
+    pub fn camera(&self, origin: Vector3<f32>) -> (Vector3<f32>, Quaternion<f32>) {
+        let time = if self.duration > 0.0 {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let (offset, rotation) = self.path.sample(time);
+        (origin + offset, rotation)
+    }
+
+    ///
+    /// record_frame
+    ///
+    /// Add a finished frame - call once per frame, after `Game::update`, so the profiler
+    /// has closed out the previous frame's timings
+    pub fn record_frame(&mut self, delta_time: f32) {
+        self.elapsed += delta_time;
+        if self.elapsed < WARMUP_SECONDS.min(self.duration * 0.5) {
+            return;
+        }
+
+        self.frame_times_ms.push(delta_time * 1000.0);
+        for stats in engine::profiler::stats() {
+            let (total, max) = self
+                .system_times_ms
+                .entry(stats.label)
+                .or_insert((0.0, 0.0));
+            *total += stats.last_ms;
+            *max = max.max(stats.last_ms);
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    pub fn results(&self) -> BenchmarkResults {
+        let frames = self.frame_times_ms.len();
+        let frame_count = frames.max(1) as f32;
+        let total_ms: f32 = self.frame_times_ms.iter().sum();
+        let average_frame_ms = total_ms / frame_count;
+        let max_frame_ms = self.frame_times_ms.iter().copied().fold(0.0, f32::max);
+
+        let mut slowest = self.frame_times_ms.clone();
+        slowest.sort_by(|a, b| b.total_cmp(a));
+        let one_percent = slowest.len().div_ceil(100).max(1);
+        let one_percent_low_ms = slowest.iter().take(one_percent).sum::<f32>() / one_percent as f32;
+
+        let mut systems = self
+            .system_times_ms
+            .iter()
+            .map(|(label, (total, max))| SystemTiming {
+                label: label.to_owned(),
+                average_ms: total / frame_count,
+                max_ms: *max,
+            })
+            .collect::<Vec<SystemTiming>>();
+        systems.sort_by(|a, b| b.average_ms.total_cmp(&a.average_ms));
+
+        BenchmarkResults {
+            mission: self.mission.clone(),
+            duration_secs: self.duration,
+            frames,
+            average_fps: fps(average_frame_ms),
+            min_fps: fps(max_frame_ms),
+            one_percent_low_fps: fps(one_percent_low_ms),
+            average_frame_ms,
+            max_frame_ms,
+            systems,
+        }
+    }
+}
+
+fn fps(frame_ms: f32) -> f32 {
+    if frame_ms > 0.0 {
+        1000.0 / frame_ms
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results() {
+        let mut benchmark = Benchmark::new("earth.mis", 10.0, CameraPath::default_path());
+        // Skip past the warmup
+        benchmark.record_frame(WARMUP_SECONDS);
+        benchmark.frame_times_ms.clear();
+
+        for _ in 0..99 {
+            benchmark.record_frame(0.010);
+        }
+        benchmark.record_frame(0.050);
+
+        let results = benchmark.results();
+        assert_eq!(results.frames, 100);
+        assert!((results.min_fps - 20.0).abs() < 0.01);
+        assert!((results.one_percent_low_fps - 20.0).abs() < 0.01);
+        assert!((results.average_fps - 1000.0 / 10.4).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_path_sampling() {
+        let path = CameraPath::from_json(
+            r#"{ "keyframes": [
+                { "time": 1.0, "position": { "x": 10.0, "y": 0.0, "z": 0.0 }, "yaw": 90.0 },
+                { "time": 0.0, "position": { "x": 0.0, "y": 0.0, "z": 0.0 }, "yaw": 0.0 }
+            ] }"#,
+        )
+        .unwrap();
+
+        let (position, _) = path.sample(0.5);
+        assert!((position.x - 5.0).abs() < 0.001);
+
+        let (position, _) = path.sample(2.0);
+        assert!((position.x - 10.0).abs() < 0.001);
+    }
+}
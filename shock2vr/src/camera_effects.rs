@@ -0,0 +1,323 @@
+use std::sync::Arc;
+
+use cgmath::{Matrix4, SquareMatrix, Vector3, vec3, vec4};
+use engine::{
+    scene::{SceneObject, basic_material, quad, render_order},
+    texture::{Texture, TextureTrait},
+    texture_format::{PixelFormat, RawTextureData},
+};
+use once_cell::sync::OnceCell;
+use rand::{Rng, thread_rng};
+use shipyard::Unique;
+
+use crate::player_status::LOW_HEALTH_THRESHOLD;
+
+// Maximum positional offset applied to the camera by shake, in meters.
+// Kept small on purpose - large camera motion that the player's head didn't cause is a fast track to motion sickness in VR.
+const MAX_SHAKE_OFFSET: f32 = 0.03;
+
+// Distance (in meters) at which a shake is felt at half intensity
+const SHAKE_FALLOFF_DISTANCE: f32 = 5.0;
+
+// How long a damage direction indicator stays visible, in seconds
+const DAMAGE_INDICATOR_DURATION: f32 = 1.5;
+
+// How long the red flash lasts after taking damage, in seconds
+const DAMAGE_FLASH_DURATION: f32 = 0.4;
+
+// Overlays are drawn just in front of the eye
+const OVERLAY_DISTANCE: f32 = 0.15;
+
+struct CameraShake {
+    origin: Vector3<f32>,
+    intensity: f32,
+    duration: f32,
+    remaining: f32,
+}
+
+struct DamageIndicator {
+    source: Vector3<f32>,
+    remaining: f32,
+}
+
+///
+/// CameraEffects
+///
+/// Screen feedback driven by script effects - camera shake, damage direction
+/// indicators and the low health pulse, in time with the player's heartbeat
+/// (see `PlayerStatus`). Updated by the mission each frame,
+/// applied to the camera in `Game::render` and drawn per-eye as overlays.
+#[derive(Unique)]
+pub struct CameraEffects {
+    shakes: Vec<CameraShake>,
+    damage_indicators: Vec<DamageIndicator>,
+    damage_flash: f32,
+    health_fraction: f32,
+    heartbeat_intensity: f32,
+    current_offset: Vector3<f32>,
+    // Accessibility: no damage flash, and a steady low health vignette instead of a pulse
+    reduced_flashing: bool,
+}
+
+impl CameraEffects {
+    pub fn new() -> CameraEffects {
+        CameraEffects {
+            shakes: Vec::new(),
+            damage_indicators: Vec::new(),
+            damage_flash: 0.0,
+            health_fraction: 1.0,
+            heartbeat_intensity: 0.0,
+            current_offset: vec3(0.0, 0.0, 0.0),
+            reduced_flashing: false,
+        }
+    }
+
+    ///
+    /// add_shake
+    ///
+    /// Start a positional shake originating at `origin`. Intensity is roughly
+    /// 'meters of offset at the origin', before the VR comfort cap is applied.
+    pub fn add_shake(&mut self, origin: Vector3<f32>, intensity: f32, duration: f32) {
+        if duration <= 0.0 || intensity <= 0.0 {
+            return;
+        }
+
+        self.shakes.push(CameraShake {
+            origin,
+            intensity,
+            duration,
+            remaining: duration,
+        });
+    }
+
+    ///
+    /// add_damage
+    ///
+    /// Flash the screen, and if a source is provided, show which direction the damage came from
+    pub fn add_damage(&mut self, source: Option<Vector3<f32>>) {
+        self.damage_flash = DAMAGE_FLASH_DURATION;
+
+        if let Some(source) = source {
+            self.damage_indicators.push(DamageIndicator {
+                source,
+                remaining: DAMAGE_INDICATOR_DURATION,
+            });
+        }
+    }
+
+    pub fn set_reduced_flashing(&mut self, reduced_flashing: bool) {
+        self.reduced_flashing = reduced_flashing;
+    }
+
+    pub fn set_heartbeat_intensity(&mut self, heartbeat_intensity: f32) {
+        self.heartbeat_intensity = heartbeat_intensity;
+    }
+
+    pub fn update(&mut self, delta_time: f32, player_position: Vector3<f32>, health_fraction: f32) {
+        self.health_fraction = health_fraction.clamp(0.0, 1.0);
+        self.damage_flash = (self.damage_flash - delta_time).max(0.0);
+
+        for indicator in self.damage_indicators.iter_mut() {
+            indicator.remaining -= delta_time;
+        }
+        self.damage_indicators.retain(|i| i.remaining > 0.0);
+
+        for shake in self.shakes.iter_mut() {
+            shake.remaining -= delta_time;
+        }
+        self.shakes.retain(|s| s.remaining > 0.0);
+
+        let amplitude = self.shake_amplitude(player_position);
+        self.current_offset = if amplitude > 0.0 {
+            let mut rng = thread_rng();
+            vec3(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            ) * amplitude
+        } else {
+            vec3(0.0, 0.0, 0.0)
+        };
+    }
+
+    /// Total shake amplitude at the given position, after distance falloff and the comfort cap
+    fn shake_amplitude(&self, position: Vector3<f32>) -> f32 {
+        let amplitude: f32 = self
+            .shakes
+            .iter()
+            .map(|shake| {
+                let distance = cgmath::InnerSpace::magnitude(shake.origin - position);
+                let falloff = 1.0 / (1.0 + (distance / SHAKE_FALLOFF_DISTANCE).powi(2));
+                // Decay linearly over the lifetime of the shake
+                let decay = shake.remaining / shake.duration;
+                shake.intensity * falloff * decay
+            })
+            .sum();
+
+        amplitude.min(MAX_SHAKE_OFFSET)
+    }
+
+    /// Offset to apply to the camera position this frame
+    pub fn camera_offset(&self) -> Vector3<f32> {
+        self.current_offset
+    }
+
+    ///
+    /// render_overlays
+    ///
+    /// Create the per-eye overlay objects (damage flash, low health pulse, damage indicators)
+    pub fn render_overlays(&self, view: Matrix4<f32>) -> Vec<SceneObject> {
+        let mut objects = Vec::new();
+        let Some(eye_to_world) = view.invert() else {
+            return objects;
+        };
+
+        // Full-view vignette, combining damage flash and the low health pulse
+        let flash_alpha = if self.reduced_flashing {
+            0.0
+        } else {
+            (self.damage_flash / DAMAGE_FLASH_DURATION) * 0.6
+        };
+        let low_health_alpha = if self.reduced_flashing {
+            let severity = (1.0 - self.health_fraction / LOW_HEALTH_THRESHOLD).max(0.0);
+            severity * 0.25
+        } else {
+            self.heartbeat_intensity * 0.5
+        };
+
+        let vignette_alpha = flash_alpha.max(low_health_alpha).min(1.0);
+        if vignette_alpha > 0.01 {
+            let transform = eye_to_world
+                * Matrix4::from_translation(vec3(0.0, 0.0, -OVERLAY_DISTANCE))
+                * Matrix4::from_scale(OVERLAY_DISTANCE * 3.0);
+            objects.push(create_overlay(
+                vignette_texture(),
+                transform,
+                vignette_alpha,
+            ));
+        }
+
+        // Damage direction indicators, placed on a ring around the center of view
+        for indicator in &self.damage_indicators {
+            let source_in_view = view
+                * vec4(
+                    indicator.source.x,
+                    indicator.source.y,
+                    indicator.source.z,
+                    1.0,
+                );
+            // Ahead is up, behind is down
+            let angle = source_in_view.x.atan2(-source_in_view.z);
+            let ring_radius = OVERLAY_DISTANCE * 0.35;
+            let position = vec3(
+                angle.sin() * ring_radius,
+                angle.cos() * ring_radius,
+                -OVERLAY_DISTANCE,
+            );
+
+            let alpha = (indicator.remaining / DAMAGE_INDICATOR_DURATION).min(1.0);
+            let transform = eye_to_world
+                * Matrix4::from_translation(position)
+                * Matrix4::from_scale(OVERLAY_DISTANCE * 0.1);
+            objects.push(create_overlay(indicator_texture(), transform, alpha));
+        }
+
+        objects
+    }
+}
+
+fn create_overlay(texture: Arc<Texture>, transform: Matrix4<f32>, alpha: f32) -> SceneObject {
+    let texture: Arc<dyn TextureTrait> = texture;
+    // Keep a small amount of transparency so the overlay always goes through the blended pass
+    let material = basic_material::create(texture, 1.0, (1.0 - alpha).max(0.02));
+    let mut obj = SceneObject::new(material, Box::new(quad::create()));
+    obj.set_transform(transform);
+    obj.set_depth_write(false);
+    // After the world's transparent objects, so they don't blend over it
+    obj.set_render_order(render_order::OVERLAY);
+    obj
+}
+
+static VIGNETTE_TEXTURE: OnceCell<Arc<Texture>> = OnceCell::new();
+static INDICATOR_TEXTURE: OnceCell<Arc<Texture>> = OnceCell::new();
+
+fn vignette_texture() -> Arc<Texture> {
+    VIGNETTE_TEXTURE
+        .get_or_init(|| {
+            // Clear in the center, red towards the edges
+            Arc::new(engine::texture::init_from_memory(create_radial_texture(
+                |distance| (distance - 0.4).clamp(0.0, 0.6) / 0.6,
+            )))
+        })
+        .clone()
+}
+
+fn indicator_texture() -> Arc<Texture> {
+    INDICATOR_TEXTURE
+        .get_or_init(|| {
+            // Soft red dot
+            Arc::new(engine::texture::init_from_memory(create_radial_texture(
+                |distance| 1.0 - distance.min(1.0),
+            )))
+        })
+        .clone()
+}
+
+/// Creates a red texture, with alpha determined by the normalized distance from the center
+fn create_radial_texture(alpha_for_distance: impl Fn(f32) -> f32) -> RawTextureData {
+    let size = 64u32;
+    let center = size as f32 / 2.0;
+    let mut bytes = vec![0; (size * size * 4) as usize];
+
+    for y in 0..size {
+        for x in 0..size {
+            let dx = (x as f32 - center) / center;
+            let dy = (y as f32 - center) / center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let alpha = alpha_for_distance(distance).clamp(0.0, 1.0);
+
+            let index = ((y * size + x) * 4) as usize;
+            bytes[index] = 200;
+            bytes[index + 1] = 0;
+            bytes[index + 2] = 0;
+            bytes[index + 3] = (alpha * 255.0) as u8;
+        }
+    }
+
+    RawTextureData {
+        bytes,
+        width: size,
+        height: size,
+        format: PixelFormat::RGBA,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shake_is_capped_for_comfort() {
+        let mut effects = CameraEffects::new();
+        effects.add_shake(vec3(0.0, 0.0, 0.0), 10.0, 1.0);
+        effects.update(0.0, vec3(0.0, 0.0, 0.0), 1.0);
+
+        assert_eq!(
+            effects.shake_amplitude(vec3(0.0, 0.0, 0.0)),
+            MAX_SHAKE_OFFSET
+        );
+        let offset = effects.camera_offset();
+        assert!(offset.x.abs() <= MAX_SHAKE_OFFSET);
+        assert!(offset.y.abs() <= MAX_SHAKE_OFFSET);
+        assert!(offset.z.abs() <= MAX_SHAKE_OFFSET);
+    }
+
+    #[test]
+    fn test_shake_expires() {
+        let mut effects = CameraEffects::new();
+        effects.add_shake(vec3(0.0, 0.0, 0.0), 0.01, 0.5);
+        effects.update(1.0, vec3(0.0, 0.0, 0.0), 1.0);
+
+        assert_eq!(effects.camera_offset(), vec3(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,778 @@
+use cgmath::{Vector3, vec3};
+use dark::{
+    SCALE_FACTOR,
+    properties::{PropAI, PropPosition},
+};
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, Get, IntoIter, IntoWithId, UniqueView, View, World};
+
+use crate::{
+    PlayerInfo,
+    accessibility::{MAX_TIME_SCALE, MIN_TIME_SCALE},
+    console::{ConsoleCommand, ConsoleRegistry},
+    factions::Faction,
+    mission::VisibilityEngineKind,
+    player_status::StatusEffectKind,
+    scripts::{Effect, GlobalEffect},
+    security::DEFAULT_ALARM_DURATION,
+    util::{debug_entity, vec3_to_point3},
+};
+
+use super::{Command, NoclipCommand};
+
+// How far in front of the player spawned entities appear
+const SPAWN_DISTANCE: f32 = 4.0 / SCALE_FACTOR;
+const SPAWN_HEIGHT: f32 = 2.0 / SCALE_FACTOR;
+
+// How long status effects from the console last, if no duration is given, in seconds
+const DEFAULT_STATUS_EFFECT_DURATION: f32 = 10.0;
+
+///
+/// register_console_commands
+///
+/// Console verbs for spawning, moving the player around and clearing out enemies
+pub fn register_console_commands(registry: &mut ConsoleRegistry) {
+    registry.register(ConsoleCommand {
+        name: "spawn",
+        usage: "spawn <template name>",
+        help: "create an entity in front of the player",
+        parse: |args| match args {
+            [template_name] => Ok(Box::new(SpawnTemplateCommand::new(template_name))),
+            _ => Err("Expected a template name".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "give",
+        usage: "give <template name>",
+        help: "create an item in the player's inventory",
+        parse: |args| match args {
+            [template_name] => Ok(Box::new(GiveItemCommand::new(template_name))),
+            _ => Err("Expected a template name".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "tp",
+        usage: "tp <x> <y> <z> | tp <entity id or name>",
+        help: "teleport the player",
+        parse: |args| match args {
+            [x, y, z] => {
+                let parse = |value: &str| {
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| format!("'{}' is not a number", value))
+                };
+                Ok(Box::new(TeleportCommand::new(TeleportTarget::Position(
+                    vec3(parse(x)?, parse(y)?, parse(z)?),
+                ))))
+            }
+            [entity] => Ok(Box::new(TeleportCommand::new(TeleportTarget::Entity(
+                entity.to_string(),
+            )))),
+            _ => Err("Expected a position or an entity".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "killall",
+        usage: "killall [name filter]",
+        help: "slay every AI (whose name contains the filter)",
+        parse: |args| match args {
+            [] => Ok(Box::new(KillAllCommand::new(None))),
+            [filter] => Ok(Box::new(KillAllCommand::new(Some(filter)))),
+            _ => Err("Expected at most one filter".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "ai_debug",
+        usage: "ai_debug <on|off|entity id or name>",
+        help: "draw AI behavior, targets, waypoints and senses (for all AIs, or matching ones)",
+        parse: |args| match args {
+            ["on"] | ["all"] => Ok(Box::new(AiDebugCommand::new(AiDebugFilter::All))),
+            ["off"] => Ok(Box::new(AiDebugCommand::new(AiDebugFilter::Off))),
+            [filter] => Ok(Box::new(AiDebugCommand::new(AiDebugFilter::Matching(
+                filter.to_ascii_lowercase(),
+            )))),
+            _ => Err("Expected on, off or an entity".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "timescale",
+        usage: "timescale <0.1 - 8>",
+        help: "slow down or fast-forward the game (1 is normal speed)",
+        parse: |args| match args {
+            [scale] => match scale.parse::<f32>() {
+                Ok(scale) if (MIN_TIME_SCALE..=MAX_TIME_SCALE).contains(&scale) => {
+                    Ok(Box::new(TimeScaleCommand::new(scale)))
+                }
+                _ => Err(format!(
+                    "Expected a number from {} to {}",
+                    MIN_TIME_SCALE, MAX_TIME_SCALE
+                )),
+            },
+            _ => Err("Expected a time scale".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "visibility",
+        usage: "visibility <portal|brute_force|distance_only>",
+        help: "switch how missions decide what to draw (portal is the default)",
+        parse: |args| match args {
+            [name] => VisibilityEngineKind::from_name(name)
+                .map(|kind| Box::new(VisibilityEngineCommand::new(kind)) as Box<dyn Command>)
+                .ok_or_else(|| format!("Unknown visibility engine '{}'", name)),
+            _ => Err("Expected a visibility engine".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "faction",
+        usage: "faction <entity id or name> <player|hybrid|robot|security>",
+        help: "switch the side an entity is on",
+        parse: |args| match args {
+            [entity, name] => Faction::from_name(name)
+                .map(|faction| Box::new(FactionCommand::new(entity, faction)) as Box<dyn Command>)
+                .ok_or_else(|| format!("Unknown faction '{}'", name)),
+            _ => Err("Expected an entity and a faction".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "alarm",
+        usage: "alarm <on|off>",
+        help: "set off or reset the security alarm",
+        parse: |args| match args {
+            ["on"] => Ok(Box::new(AlarmCommand::new(true))),
+            ["off"] => Ok(Box::new(AlarmCommand::new(false))),
+            _ => Err("Expected on or off".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "cutscene",
+        usage: "cutscene <video>",
+        help: "pause the mission and play a cutscene (ie, cs1.avi)",
+        parse: |args| match args {
+            [video_name] => Ok(Box::new(CutsceneCommand::new(video_name))),
+            _ => Err("Expected a video name".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "sequence",
+        usage: "sequence <id>",
+        help: "play a scripted sequence from sequences.json",
+        parse: |args| match args {
+            [id] => Ok(Box::new(SequenceCommand::new(id))),
+            _ => Err("Expected a sequence id".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "sound",
+        usage: "sound <schema> [x y z | entity id or name]",
+        help: "play a sound schema (or sample) in front of the player, or at a position",
+        parse: |args| match args {
+            [name] => Ok(Box::new(PlaySoundCommand::new(name, None))),
+            [name, x, y, z] => {
+                let parse = |value: &str| {
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| format!("'{}' is not a number", value))
+                };
+                Ok(Box::new(PlaySoundCommand::new(
+                    name,
+                    Some(TeleportTarget::Position(vec3(
+                        parse(x)?,
+                        parse(y)?,
+                        parse(z)?,
+                    ))),
+                )))
+            }
+            [name, entity] => Ok(Box::new(PlaySoundCommand::new(
+                name,
+                Some(TeleportTarget::Entity(entity.to_string())),
+            ))),
+            _ => Err("Expected a schema name, and optionally a position or an entity".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "status",
+        usage: "status <radiation|toxin|emp> [seconds]",
+        help: "give the player a status effect",
+        parse: |args| {
+            let (name, duration) = match args {
+                [name] => (*name, DEFAULT_STATUS_EFFECT_DURATION),
+                [name, seconds] => (
+                    *name,
+                    seconds
+                        .parse::<f32>()
+                        .map_err(|_| format!("'{}' is not a number", seconds))?,
+                ),
+                _ => return Err("Expected a status effect".to_owned()),
+            };
+            let kind = StatusEffectKind::from_name(name)
+                .ok_or_else(|| format!("Unknown status effect '{}'", name))?;
+            Ok(Box::new(StatusEffectCommand::new(kind, duration)))
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "mods",
+        usage: "mods [enable|disable <mod id>]",
+        help: "list the installed mods, or enable / disable one for the next start",
+        parse: |args| match args {
+            [] => Ok(Box::new(ModsCommand::new(None))),
+            ["enable", id] => Ok(Box::new(ModsCommand::new(Some((*id, true))))),
+            ["disable", id] => Ok(Box::new(ModsCommand::new(Some((*id, false))))),
+            _ => Err("Expected nothing, or enable / disable and a mod id".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "hints",
+        usage: "hints reset",
+        help: "show the tutorial hints again, for the active profile",
+        parse: |args| match args {
+            ["reset"] => Ok(Box::new(ResetHintsCommand)),
+            _ => Err("Expected reset".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "missing_assets",
+        usage: "missing_assets",
+        help: "list the assets that failed to load this session, and had a placeholder substituted",
+        parse: |args| match args {
+            [] => Ok(Box::new(MissingAssetsCommand)),
+            _ => Err("Expected no arguments".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "noclip",
+        usage: "noclip",
+        help: "fly through walls (debug builds, or --experimental noclip)",
+        parse: |args| match args {
+            [] => Ok(Box::new(NoclipCommand::new())),
+            _ => Err("Expected no arguments".to_owned()),
+        },
+    });
+}
+
+// SpawnTemplateCommand
+#[derive(Debug)]
+pub struct SpawnTemplateCommand {
+    template_name: String,
+}
+
+impl SpawnTemplateCommand {
+    pub fn new(template_name: &str) -> SpawnTemplateCommand {
+        SpawnTemplateCommand {
+            template_name: template_name.to_owned(),
+        }
+    }
+}
+
+impl Command for SpawnTemplateCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+        let forward = player.rotation * vec3(0.0, SPAWN_HEIGHT, -SPAWN_DISTANCE);
+
+        Effect::CreateEntityByTemplateName {
+            template_name: self.template_name.clone(),
+            position: vec3_to_point3(player.pos + forward),
+            orientation: player.rotation,
+        }
+    }
+}
+
+// GiveItemCommand
+#[derive(Debug)]
+pub struct GiveItemCommand {
+    template_name: String,
+}
+
+impl GiveItemCommand {
+    pub fn new(template_name: &str) -> GiveItemCommand {
+        GiveItemCommand {
+            template_name: template_name.to_owned(),
+        }
+    }
+}
+
+impl Command for GiveItemCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GiveItem {
+            template_name: self.template_name.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TeleportTarget {
+    Position(Vector3<f32>),
+    /// Entity id, or (part of) its name
+    Entity(String),
+}
+
+// TeleportCommand
+#[derive(Debug)]
+pub struct TeleportCommand {
+    target: TeleportTarget,
+}
+
+impl TeleportCommand {
+    pub fn new(target: TeleportTarget) -> TeleportCommand {
+        TeleportCommand { target }
+    }
+}
+
+impl Command for TeleportCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let position = match &self.target {
+            TeleportTarget::Position(position) => Some(*position),
+            TeleportTarget::Entity(entity) => find_entity_position(world, entity),
+        };
+
+        match position {
+            Some(position) => Effect::SetPlayerPosition {
+                position,
+                is_teleport: true,
+            },
+            None => {
+                tracing::warn!("tp: no entity matching '{:?}'", self.target);
+                Effect::NoEffect
+            }
+        }
+    }
+}
+
+// PlaySoundCommand
+#[derive(Debug)]
+pub struct PlaySoundCommand {
+    name: String,
+    target: Option<TeleportTarget>,
+}
+
+impl PlaySoundCommand {
+    pub fn new(name: &str, target: Option<TeleportTarget>) -> PlaySoundCommand {
+        PlaySoundCommand {
+            name: name.to_owned(),
+            target,
+        }
+    }
+}
+
+impl Command for PlaySoundCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let position = match &self.target {
+            Some(TeleportTarget::Position(position)) => Some(*position),
+            Some(TeleportTarget::Entity(entity)) => find_entity_position(world, entity),
+            None => {
+                let player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+                Some(player.pos + player.rotation * vec3(0.0, SPAWN_HEIGHT, -SPAWN_DISTANCE))
+            }
+        };
+
+        match position {
+            Some(position) => Effect::PlaySoundAt {
+                handle: AudioHandle::new(),
+                name: self.name.clone(),
+                position,
+            },
+            None => {
+                tracing::warn!("sound: no entity matching '{:?}'", self.target);
+                Effect::NoEffect
+            }
+        }
+    }
+}
+
+fn find_entity_position(world: &World, entity: &str) -> Option<Vector3<f32>> {
+    let entity_id = find_entity(world, entity)?;
+    let v_position = world.borrow::<View<PropPosition>>().unwrap();
+    v_position
+        .get(entity_id)
+        .ok()
+        .map(|position| position.position)
+}
+
+// Entity with the given id, or the first one whose name contains the text
+fn find_entity(world: &World, entity: &str) -> Option<EntityId> {
+    let v_position = world.borrow::<View<PropPosition>>().unwrap();
+
+    if let Ok(index) = entity.parse::<u64>() {
+        return v_position
+            .iter()
+            .with_id()
+            .find(|(id, _)| id.index() == index)
+            .map(|(id, _)| id);
+    }
+
+    let entity = entity.to_ascii_lowercase();
+    v_position
+        .iter()
+        .with_id()
+        .find(|(id, _)| {
+            debug_entity(world, *id)
+                .to_ascii_lowercase()
+                .contains(&entity)
+        })
+        .map(|(id, _)| id)
+}
+
+// TimeScaleCommand
+#[derive(Debug)]
+pub struct TimeScaleCommand {
+    scale: f32,
+}
+
+impl TimeScaleCommand {
+    pub fn new(scale: f32) -> TimeScaleCommand {
+        TimeScaleCommand { scale }
+    }
+}
+
+impl Command for TimeScaleCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::SetTimeScale { scale: self.scale })
+    }
+}
+
+// VisibilityEngineCommand
+#[derive(Debug)]
+pub struct VisibilityEngineCommand {
+    kind: VisibilityEngineKind,
+}
+
+impl VisibilityEngineCommand {
+    pub fn new(kind: VisibilityEngineKind) -> VisibilityEngineCommand {
+        VisibilityEngineCommand { kind }
+    }
+}
+
+impl Command for VisibilityEngineCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::SetVisibilityEngine { kind: self.kind })
+    }
+}
+
+// ModsCommand
+#[derive(Debug)]
+pub struct ModsCommand {
+    change: Option<(String, bool)>,
+}
+
+impl ModsCommand {
+    pub fn new(change: Option<(&str, bool)>) -> ModsCommand {
+        ModsCommand {
+            change: change.map(|(id, enabled)| (id.to_owned(), enabled)),
+        }
+    }
+}
+
+impl Command for ModsCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        match &self.change {
+            None => Effect::GlobalEffect(GlobalEffect::ListMods),
+            Some((id, enabled)) => Effect::GlobalEffect(GlobalEffect::SetModEnabled {
+                id: id.clone(),
+                enabled: *enabled,
+            }),
+        }
+    }
+}
+
+// MissingAssetsCommand
+#[derive(Debug)]
+pub struct MissingAssetsCommand;
+
+impl Command for MissingAssetsCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::ListMissingAssets)
+    }
+}
+
+// ResetHintsCommand
+#[derive(Debug)]
+pub struct ResetHintsCommand;
+
+impl Command for ResetHintsCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::ResetHints)
+    }
+}
+
+// StatusEffectCommand
+#[derive(Debug)]
+pub struct StatusEffectCommand {
+    kind: StatusEffectKind,
+    duration: f32,
+}
+
+impl StatusEffectCommand {
+    pub fn new(kind: StatusEffectKind, duration: f32) -> StatusEffectCommand {
+        StatusEffectCommand { kind, duration }
+    }
+}
+
+impl Command for StatusEffectCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::ApplyStatusEffect {
+            kind: self.kind,
+            duration: self.duration,
+        }
+    }
+}
+
+// FactionCommand
+#[derive(Debug)]
+pub struct FactionCommand {
+    entity: String,
+    faction: Faction,
+}
+
+impl FactionCommand {
+    pub fn new(entity: &str, faction: Faction) -> FactionCommand {
+        FactionCommand {
+            entity: entity.to_owned(),
+            faction,
+        }
+    }
+}
+
+impl Command for FactionCommand {
+    fn execute(&self, world: &World) -> Effect {
+        match find_entity(world, &self.entity) {
+            Some(entity_id) => Effect::SetFaction {
+                entity_id,
+                faction: self.faction,
+            },
+            None => {
+                tracing::warn!("faction: no entity matching '{}'", self.entity);
+                Effect::NoEffect
+            }
+        }
+    }
+}
+
+// AlarmCommand
+#[derive(Debug)]
+pub struct AlarmCommand {
+    active: bool,
+}
+
+impl AlarmCommand {
+    pub fn new(active: bool) -> AlarmCommand {
+        AlarmCommand { active }
+    }
+}
+
+impl Command for AlarmCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        if self.active {
+            Effect::TriggerAlarm {
+                duration: DEFAULT_ALARM_DURATION,
+            }
+        } else {
+            Effect::ResetAlarm
+        }
+    }
+}
+
+// CutsceneCommand
+#[derive(Debug)]
+pub struct CutsceneCommand {
+    video_name: String,
+}
+
+impl CutsceneCommand {
+    pub fn new(video_name: &str) -> CutsceneCommand {
+        CutsceneCommand {
+            video_name: video_name.to_owned(),
+        }
+    }
+}
+
+impl Command for CutsceneCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+        Effect::PlayCutscene {
+            video_name: self.video_name.clone(),
+            from: player_entity_id,
+        }
+    }
+}
+
+// SequenceCommand
+#[derive(Debug)]
+pub struct SequenceCommand {
+    id: String,
+}
+
+impl SequenceCommand {
+    pub fn new(id: &str) -> SequenceCommand {
+        SequenceCommand { id: id.to_owned() }
+    }
+}
+
+impl Command for SequenceCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+        Effect::PlaySequence {
+            sequence: self.id.clone(),
+            from: player_entity_id,
+        }
+    }
+}
+
+// KillAllCommand
+#[derive(Debug)]
+pub struct KillAllCommand {
+    filter: Option<String>,
+}
+
+impl KillAllCommand {
+    pub fn new(filter: Option<&str>) -> KillAllCommand {
+        KillAllCommand {
+            filter: filter.map(|filter| filter.to_ascii_lowercase()),
+        }
+    }
+}
+
+impl Command for KillAllCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let entities = find_ai_entities(world, self.filter.as_deref());
+
+        Effect::Multiple(
+            entities
+                .into_iter()
+                .map(|entity_id| Effect::SlayEntity { entity_id })
+                .collect(),
+        )
+    }
+}
+
+fn find_ai_entities(world: &World, filter: Option<&str>) -> Vec<EntityId> {
+    let v_ai = world.borrow::<View<PropAI>>().unwrap();
+    v_ai.iter()
+        .with_id()
+        .map(|(id, _)| id)
+        .filter(|id| match filter {
+            None => true,
+            Some(filter) => {
+                filter.parse::<u64>().ok() == Some(id.index())
+                    || debug_entity(world, *id)
+                        .to_ascii_lowercase()
+                        .contains(filter)
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum AiDebugFilter {
+    Off,
+    All,
+    /// Entity id, or (part of) its name - lowercased
+    Matching(String),
+}
+
+// AiDebugCommand
+#[derive(Debug)]
+pub struct AiDebugCommand {
+    filter: AiDebugFilter,
+}
+
+impl AiDebugCommand {
+    pub fn new(filter: AiDebugFilter) -> AiDebugCommand {
+        AiDebugCommand { filter }
+    }
+}
+
+impl Command for AiDebugCommand {
+    fn execute(&self, world: &World) -> Effect {
+        match &self.filter {
+            AiDebugFilter::Off => Effect::SetAiDebug {
+                enabled: false,
+                entities: None,
+            },
+            AiDebugFilter::All => Effect::SetAiDebug {
+                enabled: true,
+                entities: None,
+            },
+            AiDebugFilter::Matching(filter) => {
+                let entities = find_ai_entities(world, Some(filter));
+                if entities.is_empty() {
+                    tracing::warn!("ai_debug: no AI matching '{}'", filter);
+                    return Effect::NoEffect;
+                }
+                Effect::SetAiDebug {
+                    enabled: true,
+                    entities: Some(entities.into_iter().collect()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tp_arguments() {
+        let mut registry = ConsoleRegistry::new();
+        register_console_commands(&mut registry);
+
+        assert!(registry.parse_line("tp 1 2 3").is_ok());
+        assert!(registry.parse_line("tp elevator").is_ok());
+        assert!(registry.parse_line("tp 1 two 3").is_err());
+        assert!(registry.parse_line("tp 1 2").is_err());
+        assert!(registry.parse_line("spawn").is_err());
+    }
+
+    #[test]
+    fn test_ai_debug_arguments() {
+        let mut registry = ConsoleRegistry::new();
+        register_console_commands(&mut registry);
+
+        assert!(registry.parse_line("ai_debug on").is_ok());
+        assert!(registry.parse_line("ai_debug off").is_ok());
+        assert!(registry.parse_line("ai_debug midwife").is_ok());
+        assert!(registry.parse_line("ai_debug").is_err());
+    }
+
+    #[test]
+    fn test_timescale_arguments() {
+        let mut registry = ConsoleRegistry::new();
+        register_console_commands(&mut registry);
+
+        assert!(registry.parse_line("timescale 0.25").is_ok());
+        assert!(registry.parse_line("timescale 8").is_ok());
+        assert!(registry.parse_line("timescale 20").is_err());
+        assert!(registry.parse_line("timescale fast").is_err());
+    }
+
+    #[test]
+    fn test_status_arguments() {
+        let mut registry = ConsoleRegistry::new();
+        register_console_commands(&mut registry);
+
+        assert!(registry.parse_line("status emp").is_ok());
+        assert!(registry.parse_line("status radiation 30").is_ok());
+        assert!(registry.parse_line("status radiation long").is_err());
+        assert!(registry.parse_line("status sunburn").is_err());
+    }
+
+    #[test]
+    fn test_mods_arguments() {
+        let mut registry = ConsoleRegistry::new();
+        register_console_commands(&mut registry);
+
+        assert!(registry.parse_line("mods").is_ok());
+        assert!(registry.parse_line("mods enable hd_textures").is_ok());
+        assert!(registry.parse_line("mods disable hd_textures").is_ok());
+        assert!(registry.parse_line("mods enable").is_err());
+        assert!(registry.parse_line("mods remove hd_textures").is_err());
+    }
+
+    #[test]
+    fn test_missing_assets_takes_no_arguments() {
+        let mut registry = ConsoleRegistry::new();
+        register_console_commands(&mut registry);
+
+        assert!(registry.parse_line("missing_assets").is_ok());
+        assert!(registry.parse_line("missing_assets models").is_err());
+    }
+}
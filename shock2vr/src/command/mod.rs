@@ -1,13 +1,15 @@
+mod console_commands;
 mod move_inventory_command;
 mod spawn_item_command;
 
+pub use console_commands::*;
 pub use move_inventory_command::*;
 use shipyard::World;
 pub use spawn_item_command::*;
 
 use std::fmt;
 
-use crate::scripts::Effect;
+use crate::{mission::entity_inspector::InspectorAction, scripts::Effect};
 
 pub trait Command: fmt::Debug {
     fn execute(&self, world: &World) -> Effect;
@@ -49,6 +51,28 @@ impl Command for LoadCommand {
     }
 }
 
+// SwitchProfileCommand
+#[derive(Debug)]
+pub struct SwitchProfileCommand {
+    name: String,
+}
+
+impl SwitchProfileCommand {
+    pub fn new(name: &str) -> SwitchProfileCommand {
+        SwitchProfileCommand {
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl Command for SwitchProfileCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(crate::scripts::GlobalEffect::SwitchProfile {
+            name: self.name.clone(),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct TransitionLevelCommand {}
 
@@ -83,3 +107,53 @@ impl Command for PathfindingTestCommand {
         Effect::PathfindingTest
     }
 }
+
+// EntityInspectorCommand
+#[derive(Debug)]
+pub struct EntityInspectorCommand {
+    action: InspectorAction,
+}
+
+impl EntityInspectorCommand {
+    pub fn new(action: InspectorAction) -> EntityInspectorCommand {
+        EntityInspectorCommand { action }
+    }
+}
+
+impl Command for EntityInspectorCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::EntityInspector(self.action)
+    }
+}
+
+// ExamineCommand
+#[derive(Debug)]
+pub struct ExamineCommand {}
+
+impl ExamineCommand {
+    pub fn new() -> ExamineCommand {
+        ExamineCommand {}
+    }
+}
+
+impl Command for ExamineCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::ToggleExamine
+    }
+}
+
+// NoclipCommand
+#[derive(Debug)]
+pub struct NoclipCommand {}
+
+impl NoclipCommand {
+    pub fn new() -> NoclipCommand {
+        NoclipCommand {}
+    }
+}
+
+impl Command for NoclipCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::ToggleNoclip
+    }
+}
@@ -0,0 +1,342 @@
+// Conditions - small boolean expressions over quest bits and global variables, so level logic
+// like "open once the cyborg is found and the power is back" doesn't need a new Rust script:
+//
+//   qb('found_cyborg') == 1 && var('power_on')
+//
+// Supported: `||`, `&&`, `!`, `==`, `!=`, `<`, `<=`, `>`, `>=`, parentheses, integers,
+// quoted strings, `true` / `false`, `qb('name')` (the quest bit's value) and `var('name')`
+// (a global variable - unset variables are `0`). Values are truthy if they're `true`, a
+// non-zero number or a non-empty string.
+
+use std::cmp::Ordering;
+
+use crate::quest_info::{GlobalValue, QuestInfo};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Literal(GlobalValue),
+    QuestBit(String),
+    Variable(String),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Compare(Box<Condition>, CompareOp, Box<Condition>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl Condition {
+    ///
+    /// parse
+    ///
+    /// Parse an expression, ie `qb('found_cyborg') == 1 && var('power_on')`
+    pub fn parse(source: &str) -> Result<Condition, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let condition = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(condition),
+            Some(token) => Err(format!("Unexpected {:?} in '{}'", token, source)),
+        }
+    }
+
+    pub fn evaluate(&self, quest_info: &QuestInfo) -> GlobalValue {
+        match self {
+            Condition::Literal(value) => value.clone(),
+            Condition::QuestBit(name) => {
+                GlobalValue::Int(quest_info.read_quest_bit_value(name).bits() as i64)
+            }
+            Condition::Variable(name) => quest_info.read_variable(name),
+            Condition::Not(inner) => GlobalValue::Bool(!inner.is_true(quest_info)),
+            Condition::And(lhs, rhs) => {
+                GlobalValue::Bool(lhs.is_true(quest_info) && rhs.is_true(quest_info))
+            }
+            Condition::Or(lhs, rhs) => {
+                GlobalValue::Bool(lhs.is_true(quest_info) || rhs.is_true(quest_info))
+            }
+            Condition::Compare(lhs, op, rhs) => {
+                let ordering = compare(&lhs.evaluate(quest_info), &rhs.evaluate(quest_info));
+                GlobalValue::Bool(match (op, ordering) {
+                    (CompareOp::Equal, ordering) => ordering == Some(Ordering::Equal),
+                    (CompareOp::NotEqual, ordering) => ordering != Some(Ordering::Equal),
+                    (_, None) => false,
+                    (CompareOp::Less, Some(ordering)) => ordering.is_lt(),
+                    (CompareOp::LessEqual, Some(ordering)) => ordering.is_le(),
+                    (CompareOp::Greater, Some(ordering)) => ordering.is_gt(),
+                    (CompareOp::GreaterEqual, Some(ordering)) => ordering.is_ge(),
+                })
+            }
+        }
+    }
+
+    pub fn is_true(&self, quest_info: &QuestInfo) -> bool {
+        self.evaluate(quest_info).is_true()
+    }
+}
+
+// Strings only compare with strings - numbers and bools compare as numbers
+fn compare(lhs: &GlobalValue, rhs: &GlobalValue) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (GlobalValue::Str(a), GlobalValue::Str(b)) => Some(a.cmp(b)),
+        _ => Some(lhs.as_int()?.cmp(&rhs.as_int()?)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Str(String),
+    Ident(String),
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Compare(CompareOp),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Compare(CompareOp::Equal));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Compare(CompareOp::NotEqual));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                tokens.push(Token::Compare(match (c, or_equal) {
+                    ('<', false) => CompareOp::Less,
+                    ('<', true) => CompareOp::LessEqual,
+                    (_, false) => CompareOp::Greater,
+                    (_, true) => CompareOp::GreaterEqual,
+                }));
+                i += if or_equal { 2 } else { 1 };
+            }
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|ch| *ch == c)
+                    .ok_or_else(|| format!("Unterminated string in '{}'", source))?;
+                tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .take_while(|ch| ch.is_ascii_digit())
+                    .count();
+                let text = chars[i..i + 1 + len].iter().collect::<String>();
+                let value = text
+                    .parse::<i64>()
+                    .map_err(|_| format!("'{}' is not a number", text))?;
+                tokens.push(Token::Int(value));
+                i += len + 1;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_ascii_alphanumeric() || **ch == '_')
+                    .count();
+                tokens.push(Token::Ident(chars[i..i + len].iter().collect()));
+                i += len;
+            }
+            c => return Err(format!("Unexpected '{}' in '{}'", c, source)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(format!("Expected {:?}, found {:?}", expected, other)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            lhs = Condition::Or(Box::new(lhs), Box::new(self.parse_and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut lhs = self.parse_compare()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            lhs = Condition::And(Box::new(lhs), Box::new(self.parse_compare()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_compare(&mut self) -> Result<Condition, String> {
+        let lhs = self.parse_unary()?;
+        if let Some(Token::Compare(op)) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            return Ok(Condition::Compare(Box::new(lhs), op, Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Condition::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Condition, String> {
+        match self.next() {
+            Some(Token::Int(value)) => Ok(Condition::Literal(GlobalValue::Int(value))),
+            Some(Token::Str(value)) => Ok(Condition::Literal(GlobalValue::Str(value))),
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(ident)) => match ident.to_ascii_lowercase().as_str() {
+                "true" => Ok(Condition::Literal(GlobalValue::Bool(true))),
+                "false" => Ok(Condition::Literal(GlobalValue::Bool(false))),
+                function @ ("qb" | "var") => {
+                    self.expect(Token::LParen)?;
+                    let name = match self.next() {
+                        Some(Token::Str(name)) => name,
+                        other => {
+                            return Err(format!(
+                                "{}() expects a quoted name, found {:?}",
+                                function, other
+                            ));
+                        }
+                    };
+                    self.expect(Token::RParen)?;
+                    Ok(if function == "qb" {
+                        Condition::QuestBit(name)
+                    } else {
+                        Condition::Variable(name)
+                    })
+                }
+                _ => Err(format!("Unknown identifier '{}'", ident)),
+            },
+            other => Err(format!("Expected a value, found {:?}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::QuestBitValue;
+
+    use super::*;
+
+    fn eval(source: &str, quest_info: &QuestInfo) -> bool {
+        Condition::parse(source).unwrap().is_true(quest_info)
+    }
+
+    #[test]
+    fn test_quest_bits_and_variables() {
+        let mut quest_info = QuestInfo::new();
+        let source = "qb('found_cyborg')==1 && var('power_on')";
+        assert!(!eval(source, &quest_info));
+
+        quest_info.set_quest_bit_value("Found_Cyborg", QuestBitValue::INCOMPLETE);
+        assert!(!eval(source, &quest_info));
+
+        quest_info.set_variable("power_on", GlobalValue::Bool(true));
+        assert!(eval(source, &quest_info));
+    }
+
+    #[test]
+    fn test_precedence_and_comparisons() {
+        let mut quest_info = QuestInfo::new();
+        quest_info.set_variable("count", GlobalValue::Int(3));
+        quest_info.set_variable("deck", GlobalValue::Str("medsci".to_owned()));
+
+        assert!(eval("true || false && false", &quest_info));
+        assert!(!eval("(true || false) && false", &quest_info));
+        assert!(eval("!var('unset') && var('count') >= 3", &quest_info));
+        assert!(eval("var('count') > -1 && var('count') != 4", &quest_info));
+        assert!(eval("var('deck') == \"medsci\"", &quest_info));
+        // Strings and numbers are never equal
+        assert!(!eval("var('deck') == 0", &quest_info));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Condition::parse("qb(found)").is_err());
+        assert!(Condition::parse("var('a') &&").is_err());
+        assert!(Condition::parse("(true").is_err());
+        assert!(Condition::parse("'unterminated").is_err());
+        assert!(Condition::parse("true true").is_err());
+    }
+
+    #[test]
+    fn test_global_value_parse() {
+        assert_eq!(GlobalValue::parse("TRUE"), GlobalValue::Bool(true));
+        assert_eq!(GlobalValue::parse("-12"), GlobalValue::Int(-12));
+        assert_eq!(
+            GlobalValue::parse("'medsci'"),
+            GlobalValue::Str("medsci".to_owned())
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+
+use dark::importers::FONT_IMPORTER;
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+
+use crate::{accessibility::AccessibilitySettings, command::Command};
+
+// Lines of output kept / shown - older lines scroll off
+const MAX_OUTPUT_LINES: usize = 12;
+const MAX_HISTORY: usize = 50;
+
+const FONT_SIZE: f32 = 10.0;
+const CONSOLE_X: f32 = 8.0;
+const CONSOLE_Y: f32 = 8.0;
+
+pub type ConsoleParser = fn(&[&str]) -> Result<Box<dyn Command>, String>;
+
+///
+/// ConsoleCommand
+///
+/// A console verb - `parse` turns the arguments into a regular `Command`, so console commands
+/// run through the same path as the runtimes' key bindings
+pub struct ConsoleCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+    pub parse: ConsoleParser,
+}
+
+///
+/// ConsoleRegistry
+///
+/// Console verbs, registered by the subsystems that own them
+pub struct ConsoleRegistry {
+    commands: Vec<ConsoleCommand>,
+}
+
+impl ConsoleRegistry {
+    pub fn new() -> ConsoleRegistry {
+        ConsoleRegistry { commands: vec![] }
+    }
+
+    pub fn register(&mut self, command: ConsoleCommand) {
+        self.commands
+            .retain(|existing| existing.name != command.name);
+        self.commands.push(command);
+        self.commands.sort_by_key(|command| command.name);
+    }
+
+    pub fn commands(&self) -> &[ConsoleCommand] {
+        &self.commands
+    }
+
+    pub fn find(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.commands
+            .iter()
+            .find(|command| command.name.eq_ignore_ascii_case(name))
+    }
+
+    ///
+    /// complete
+    ///
+    /// Names of the commands starting with `prefix`
+    pub fn complete(&self, prefix: &str) -> Vec<&'static str> {
+        let prefix = prefix.to_ascii_lowercase();
+        self.commands
+            .iter()
+            .map(|command| command.name)
+            .filter(|name| name.starts_with(&prefix))
+            .collect()
+    }
+
+    ///
+    /// parse_line
+    ///
+    /// Parse a full command line, ie `spawn pistol` - the first word picks the command
+    pub fn parse_line(&self, line: &str) -> Result<Box<dyn Command>, String> {
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let Some((name, args)) = words.split_first() else {
+            return Err("Empty command".to_owned());
+        };
+
+        let command = self
+            .find(name)
+            .ok_or_else(|| format!("Unknown command '{}' - try 'help'", name))?;
+        (command.parse)(args).map_err(|err| format!("{} (usage: {})", err, command.usage))
+    }
+
+    pub fn help(&self) -> Vec<String> {
+        self.commands
+            .iter()
+            .map(|command| format!("{} - {}", command.usage, command.help))
+            .collect()
+    }
+}
+
+impl Default for ConsoleRegistry {
+    fn default() -> Self {
+        let mut registry = ConsoleRegistry::new();
+        crate::command::register_console_commands(&mut registry);
+        crate::object_labels::register_console_commands(&mut registry);
+        crate::quest_info::register_console_commands(&mut registry);
+        registry
+    }
+}
+
+///
+/// ConsoleInput
+///
+/// Key presses the console cares about - runtimes translate their own input into these
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleInput {
+    Toggle,
+    Char(char),
+    Backspace,
+    Submit,
+    Complete,
+    HistoryPrevious,
+    HistoryNext,
+}
+
+///
+/// Console
+///
+/// Drop-down developer console - the line being edited, history, and recent output
+pub struct Console {
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    // Position while browsing history - None when editing a fresh line
+    history_cursor: Option<usize>,
+    output: VecDeque<String>,
+}
+
+impl Console {
+    pub fn new() -> Console {
+        Console {
+            open: false,
+            input: String::new(),
+            history: vec![],
+            history_cursor: None,
+            output: VecDeque::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn output(&self) -> impl Iterator<Item = &String> {
+        self.output.iter()
+    }
+
+    pub fn print(&mut self, line: &str) {
+        for line in line.lines() {
+            if self.output.len() >= MAX_OUTPUT_LINES {
+                self.output.pop_front();
+            }
+            self.output.push_back(line.to_owned());
+        }
+    }
+
+    ///
+    /// handle_input
+    ///
+    /// Apply a key press - returns the line when one is submitted
+    pub fn handle_input(
+        &mut self,
+        input: ConsoleInput,
+        registry: &ConsoleRegistry,
+    ) -> Option<String> {
+        if input == ConsoleInput::Toggle {
+            self.open = !self.open;
+            return None;
+        }
+
+        if !self.open {
+            return None;
+        }
+
+        match input {
+            ConsoleInput::Toggle => (),
+            // The toggle key itself shouldn't end up in the line
+            ConsoleInput::Char('`') | ConsoleInput::Char('~') => (),
+            ConsoleInput::Char(c) => self.input.push(c),
+            ConsoleInput::Backspace => {
+                self.input.pop();
+            }
+            ConsoleInput::Complete => self.complete(registry),
+            ConsoleInput::HistoryPrevious => {
+                if self.history.is_empty() {
+                    return None;
+                }
+                let idx = match self.history_cursor {
+                    None => self.history.len() - 1,
+                    Some(idx) => idx.saturating_sub(1),
+                };
+                self.history_cursor = Some(idx);
+                self.input = self.history[idx].clone();
+            }
+            ConsoleInput::HistoryNext => match self.history_cursor {
+                Some(idx) if idx + 1 < self.history.len() => {
+                    self.history_cursor = Some(idx + 1);
+                    self.input = self.history[idx + 1].clone();
+                }
+                _ => {
+                    self.history_cursor = None;
+                    self.input.clear();
+                }
+            },
+            ConsoleInput::Submit => {
+                let line = std::mem::take(&mut self.input).trim().to_owned();
+                self.history_cursor = None;
+                if line.is_empty() {
+                    return None;
+                }
+                if self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                    if self.history.len() > MAX_HISTORY {
+                        self.history.remove(0);
+                    }
+                }
+                return Some(line);
+            }
+        }
+
+        None
+    }
+
+    // Tab completion for the command name: a single match is filled in, several are listed
+    // (and filled in as far as they agree)
+    fn complete(&mut self, registry: &ConsoleRegistry) {
+        if self.input.contains(char::is_whitespace) {
+            return;
+        }
+
+        let candidates = registry.complete(&self.input);
+        match candidates.as_slice() {
+            [] => (),
+            [single] => self.input = format!("{} ", single),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.len(), |len, candidate| {
+                    first
+                        .chars()
+                        .zip(candidate.chars())
+                        .take(len)
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                });
+                self.input = first[..common].to_owned();
+                self.print(&candidates.join("  "));
+            }
+        }
+    }
+
+    pub fn render(
+        &self,
+        asset_cache: &mut AssetCache,
+        accessibility: &AccessibilitySettings,
+    ) -> Vec<SceneObject> {
+        if !self.open {
+            return vec![];
+        }
+
+        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+        let font_size = accessibility.scaled_font_size(FONT_SIZE);
+        let prompt = format!("> {}_", self.input);
+        self.output
+            .iter()
+            .chain(std::iter::once(&prompt))
+            .enumerate()
+            .map(|(idx, line)| {
+                SceneObject::screen_space_text(
+                    line,
+                    font.clone(),
+                    font_size,
+                    0.9,
+                    CONSOLE_X,
+                    CONSOLE_Y + idx as f32 * font_size * 1.2,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Console::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::SaveCommand;
+
+    fn registry() -> ConsoleRegistry {
+        let mut registry = ConsoleRegistry::new();
+        for name in ["spawn", "save", "sethp"] {
+            registry.register(ConsoleCommand {
+                name,
+                usage: name,
+                help: "",
+                parse: |args| {
+                    if args.len() > 1 {
+                        Err("Too many arguments".to_owned())
+                    } else {
+                        Ok(Box::new(SaveCommand::new()))
+                    }
+                },
+            });
+        }
+        registry
+    }
+
+    fn type_line(console: &mut Console, registry: &ConsoleRegistry, line: &str) {
+        for c in line.chars() {
+            console.handle_input(ConsoleInput::Char(c), registry);
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let registry = registry();
+        assert!(registry.parse_line("spawn pistol").is_ok());
+        assert!(registry.parse_line("SPAWN").is_ok());
+        assert!(registry.parse_line("spawn a b").is_err());
+        assert!(registry.parse_line("fly").is_err());
+        assert!(registry.parse_line("   ").is_err());
+    }
+
+    #[test]
+    fn test_completion() {
+        let registry = registry();
+        let mut console = Console::new();
+        console.handle_input(ConsoleInput::Toggle, &registry);
+
+        type_line(&mut console, &registry, "sp");
+        console.handle_input(ConsoleInput::Complete, &registry);
+        assert_eq!(console.input(), "spawn ");
+
+        console.handle_input(ConsoleInput::HistoryNext, &registry);
+        type_line(&mut console, &registry, "s");
+        console.handle_input(ConsoleInput::Complete, &registry);
+        // Ambiguous - completes as far as the candidates agree
+        assert_eq!(console.input(), "s");
+        type_line(&mut console, &registry, "e");
+        console.handle_input(ConsoleInput::Complete, &registry);
+        assert_eq!(console.input(), "sethp ");
+    }
+
+    #[test]
+    fn test_history() {
+        let registry = registry();
+        let mut console = Console::new();
+        console.handle_input(ConsoleInput::Toggle, &registry);
+
+        type_line(&mut console, &registry, "save");
+        assert_eq!(
+            console.handle_input(ConsoleInput::Submit, &registry),
+            Some("save".to_owned())
+        );
+        type_line(&mut console, &registry, "spawn pistol");
+        console.handle_input(ConsoleInput::Submit, &registry);
+
+        console.handle_input(ConsoleInput::HistoryPrevious, &registry);
+        assert_eq!(console.input(), "spawn pistol");
+        console.handle_input(ConsoleInput::HistoryPrevious, &registry);
+        assert_eq!(console.input(), "save");
+        console.handle_input(ConsoleInput::HistoryNext, &registry);
+        console.handle_input(ConsoleInput::HistoryNext, &registry);
+        assert_eq!(console.input(), "");
+    }
+
+    #[test]
+    fn test_closed_console_ignores_input() {
+        let registry = registry();
+        let mut console = Console::new();
+        type_line(&mut console, &registry, "save");
+        assert_eq!(console.handle_input(ConsoleInput::Submit, &registry), None);
+    }
+}
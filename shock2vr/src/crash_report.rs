@@ -0,0 +1,217 @@
+use std::{
+    backtrace::Backtrace,
+    cell::RefCell,
+    fs::{self, File},
+    io::Write,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::Once,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use engine::logging::LogEntry;
+use serde::Serialize;
+use tracing::error;
+
+use crate::paths;
+
+// Number of log entries included in a report
+const REPORTED_LOG_ENTRIES: usize = 200;
+
+// Cap on how many effects are included in a report - a runaway script can emit thousands
+pub const MAX_REPORTED_EFFECTS: usize = 100;
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    // Message and backtrace of the most recent panic on this thread, filled in by the panic hook.
+    // The backtrace has to be captured in the hook - by the time catch_unwind returns, the stack is gone.
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+///
+/// CrashReport
+///
+/// Everything we know about a panic caught at the game boundary. Written to disk
+/// as JSON by `report_crash`, and kept around so runtimes can surface it (ie, the debug runtime's `/v1/crash`).
+#[derive(Clone, Debug, Serialize)]
+pub struct CrashReport {
+    /// Which part of the frame panicked - "update", "render", etc.
+    pub phase: String,
+    pub message: String,
+    pub backtrace: String,
+    pub mission: String,
+    pub frame: u64,
+    pub timestamp_secs: u64,
+    pub recent_logs: Vec<LogEntry>,
+    pub active_effects: Vec<String>,
+    /// Where the report was written, if writing succeeded
+    pub report_path: Option<String>,
+}
+
+///
+/// CrashContext
+///
+/// Game state captured alongside a crash report
+pub struct CrashContext {
+    pub phase: &'static str,
+    pub mission: String,
+    pub frame: u64,
+    pub active_effects: Vec<String>,
+}
+
+///
+/// install_panic_hook
+///
+/// Chain a hook in front of the existing panic hook to record the message and backtrace.
+/// Safe to call multiple times - the hook is only installed once.
+pub fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+                s.to_string()
+            } else if let Some(s) = info.payload().downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "<non-string panic payload>".to_owned()
+            };
+
+            let message = match info.location() {
+                Some(location) => format!("{message} ({location})"),
+                None => message,
+            };
+
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_PANIC.with(|last_panic| {
+                *last_panic.borrow_mut() = Some((message, backtrace));
+            });
+
+            previous_hook(info);
+        }));
+    });
+}
+
+///
+/// CaughtPanic
+///
+/// The message and backtrace of a panic caught by `catch_panic`
+#[derive(Clone, Debug)]
+pub struct CaughtPanic {
+    pub message: String,
+    pub backtrace: String,
+}
+
+///
+/// catch_panic
+///
+/// Run `f`, catching any panic along with the message and backtrace recorded by the panic hook
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, CaughtPanic> {
+    install_panic_hook();
+
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|_| {
+        let (message, backtrace) = LAST_PANIC
+            .with(|last_panic| last_panic.borrow_mut().take())
+            .unwrap_or_else(|| ("<unknown panic>".to_owned(), String::new()));
+        CaughtPanic { message, backtrace }
+    })
+}
+
+///
+/// report_crash
+///
+/// Build a crash report for a caught panic and write it to the crash report directory
+pub fn report_crash(caught: CaughtPanic, context: CrashContext) -> CrashReport {
+    let mut report = build_report(context, caught.message, caught.backtrace);
+    match write_report(&report) {
+        Ok(path) => {
+            error!(
+                "Game crashed during {}: {} - report written to {}",
+                report.phase,
+                report.message,
+                path.display()
+            );
+            report.report_path = Some(path.to_string_lossy().into_owned());
+        }
+        Err(err) => {
+            error!(
+                "Game crashed during {}: {} - unable to write report: {}",
+                report.phase, report.message, err
+            );
+        }
+    }
+    report
+}
+
+fn build_report(context: CrashContext, message: String, backtrace: String) -> CrashReport {
+    let timestamp_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut active_effects = context.active_effects;
+    active_effects.truncate(MAX_REPORTED_EFFECTS);
+
+    CrashReport {
+        phase: context.phase.to_owned(),
+        message,
+        backtrace,
+        mission: context.mission,
+        frame: context.frame,
+        timestamp_secs,
+        recent_logs: engine::logging::recent_log_entries(REPORTED_LOG_ENTRIES),
+        active_effects,
+        report_path: None,
+    }
+}
+
+pub fn crash_report_dir() -> PathBuf {
+    paths::data_root().join("crashes")
+}
+
+fn write_report(report: &CrashReport) -> std::io::Result<PathBuf> {
+    let dir = crash_report_dir();
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!(
+        "crash_{}_frame{}.json",
+        report.timestamp_secs, report.frame
+    ));
+    let json = serde_json::to_string_pretty(report)?;
+    let mut file = File::create(&path)?;
+    file.write_all(json.as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_panic_passes_through() {
+        let result = catch_panic(|| 42);
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn test_panic_is_caught_with_message() {
+        let result = catch_panic(|| -> i32 { panic!("boom") });
+        let caught = result.unwrap_err();
+        assert!(caught.message.contains("boom"));
+    }
+
+    #[test]
+    fn test_build_report_truncates_effects() {
+        let context = CrashContext {
+            phase: "update",
+            mission: "earth.mis".to_owned(),
+            frame: 7,
+            active_effects: (0..500).map(|i| format!("Effect{i}")).collect(),
+        };
+        let report = build_report(context, "boom".to_owned(), String::new());
+
+        assert_eq!(report.frame, 7);
+        assert_eq!(report.mission, "earth.mis");
+        assert_eq!(report.active_effects.len(), MAX_REPORTED_EFFECTS);
+    }
+}
@@ -47,8 +47,22 @@ impl CreatureDefinition {
     pub fn get_hitbox_type(&self, joint_id: u32) -> Option<HitBoxType> {
         self.hit_boxes.get(&joint_id).cloned()
     }
+
+    ///
+    /// weapon_joint
+    ///
+    /// Joint a held weapon model is attached to - only humanoids hold separate weapon models
+    pub fn weapon_joint(&self) -> Option<u32> {
+        match self.actor_type {
+            ActorType::Human => Some(HUMAN_RIGHT_WEAPON_JOINT),
+            _ => None,
+        }
+    }
 }
 
+// RWeap, in the humanoid model's joint indices (see HUMANOID_HIT_BOXES)
+const HUMAN_RIGHT_WEAPON_JOINT: u32 = 15;
+
 pub const HUMAN_HEIGHT: f32 = 6.5 / SCALE_FACTOR;
 pub const HUMAN_WIDTH: f32 = 3.5 / SCALE_FACTOR;
 pub const HUMAN_PHYS_OFFSET: f32 = 1.0 / SCALE_FACTOR;
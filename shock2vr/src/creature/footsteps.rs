@@ -0,0 +1,41 @@
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, Get, View, World};
+
+use crate::{
+    mission::SurfaceMaterials,
+    physics::PhysicsWorld,
+    runtime_props::RuntimePropTransform,
+    scripts::{Effect, script_util::get_environmental_sound_query},
+    util::{get_position_from_matrix, point3_to_vec3},
+};
+
+///
+/// footstep_effect
+///
+/// The environmental sound for `entity_id` taking a step - the schema is picked from the entity's
+/// class tags along with the material of the surface it is standing on
+pub fn footstep_effect(
+    world: &World,
+    physics: &PhysicsWorld,
+    surface_materials: &SurfaceMaterials,
+    entity_id: EntityId,
+) -> Option<Effect> {
+    let position = {
+        let v_transform = world.borrow::<View<RuntimePropTransform>>().unwrap();
+        let transform = v_transform.get(entity_id).ok()?;
+        point3_to_vec3(get_position_from_matrix(&transform.0))
+    };
+
+    let additional_tags = surface_materials
+        .material_below(physics, position)
+        .map(|material| vec![("material", material)])
+        .unwrap_or_default();
+
+    let query = get_environmental_sound_query(world, entity_id, "footstep", additional_tags)?;
+    Some(Effect::PlayEnvironmentalSound {
+        audio_handle: AudioHandle::new(),
+        query,
+        position,
+        entity_id: Some(entity_id),
+    })
+}
@@ -0,0 +1,128 @@
+use std::collections::{HashMap, HashSet};
+
+use cgmath::{Matrix4, SquareMatrix};
+use dark::{
+    motion::JointId,
+    properties::{Link, PropPlayerGun, PropTemplateId},
+};
+use shipyard::{EntityId, Get, IntoIter, IntoWithId, View, World};
+
+use super::get_entity_creature;
+use crate::{
+    runtime_props::{RuntimePropAttachment, RuntimePropJointTransforms},
+    scripts::script_util::{get_all_links_with_data, get_first_link_with_template_and_data},
+};
+
+///
+/// HeldWeapon
+///
+/// A weapon model attached to a creature's weapon joint
+#[derive(Clone, Copy, Debug)]
+pub struct HeldWeapon {
+    /// The visual-only entity following the joint
+    pub entity_id: EntityId,
+    /// Template to spawn as a pickup when the creature dies
+    pub template_id: i32,
+    pub joint: JointId,
+}
+
+impl HeldWeapon {
+    /// Attaches the weapon entity to the creature's weapon joint (see systems::update_attachments)
+    pub fn attachment(&self, creature_entity_id: EntityId) -> RuntimePropAttachment {
+        RuntimePropAttachment {
+            parent: creature_entity_id,
+            joint: Some(self.joint),
+            local_transform: Matrix4::identity(),
+        }
+    }
+}
+
+///
+/// HeldWeaponManager
+///
+/// Attaches the weapons AIs are holding (via AIRangedWeapon or Contains links) to their hand joint,
+/// so they aren't firing from nowhere, and hands the weapon back on death so it can be dropped.
+pub struct HeldWeaponManager {
+    held_weapons: HashMap<EntityId, HeldWeapon>,
+    // Creatures we've already looked for a weapon on - the link lookup only needs to happen once
+    checked: HashSet<EntityId>,
+}
+
+impl HeldWeaponManager {
+    pub fn new() -> HeldWeaponManager {
+        HeldWeaponManager {
+            held_weapons: HashMap::new(),
+            checked: HashSet::new(),
+        }
+    }
+
+    ///
+    /// creatures_needing_weapons
+    ///
+    /// Animated creatures that hold a weapon which hasn't been attached yet,
+    /// along with the weapon template and the joint to attach it to
+    pub fn creatures_needing_weapons(&mut self, world: &World) -> Vec<(EntityId, i32, JointId)> {
+        let v_joint_transforms = world.borrow::<View<RuntimePropJointTransforms>>().unwrap();
+
+        let mut ret = Vec::new();
+        for (entity_id, _) in v_joint_transforms.iter().with_id() {
+            if !self.checked.insert(entity_id) {
+                continue;
+            }
+
+            let maybe_joint =
+                get_entity_creature(world, entity_id).and_then(|creature| creature.weapon_joint());
+            let maybe_template = find_held_weapon_template(world, entity_id);
+
+            if let (Some(joint), Some(template_id)) = (maybe_joint, maybe_template) {
+                ret.push((entity_id, template_id, joint));
+            }
+        }
+        ret
+    }
+
+    pub fn attach(&mut self, creature_entity_id: EntityId, held_weapon: HeldWeapon) {
+        self.held_weapons.insert(creature_entity_id, held_weapon);
+    }
+
+    ///
+    /// detach
+    ///
+    /// Stop tracking the weapon held by `creature_entity_id`, returning it so the caller can
+    /// remove the visual entity (and drop a pickup, if the creature died)
+    pub fn detach(&mut self, creature_entity_id: EntityId) -> Option<HeldWeapon> {
+        self.checked.remove(&creature_entity_id);
+        self.held_weapons.remove(&creature_entity_id)
+    }
+}
+
+///
+/// find_held_weapon_template
+///
+/// The template of the weapon a creature is holding - either the target of an AIRangedWeapon link,
+/// or a contained entity that is a gun
+fn find_held_weapon_template(world: &World, entity_id: EntityId) -> Option<i32> {
+    let ranged_weapon = get_first_link_with_template_and_data(world, entity_id, |link| {
+        if matches!(link, Link::AIRangedWeapon) {
+            Some(())
+        } else {
+            None
+        }
+    });
+
+    if let Some((template_id, _)) = ranged_weapon {
+        return Some(template_id);
+    }
+
+    let v_player_gun = world.borrow::<View<PropPlayerGun>>().unwrap();
+    let v_template_id = world.borrow::<View<PropTemplateId>>().unwrap();
+    get_all_links_with_data(world, entity_id, |link| match link {
+        Link::Contains(_) => Some(()),
+        _ => None,
+    })
+    .into_iter()
+    .map(|(contained_entity_id, _)| contained_entity_id)
+    .find(|contained_entity_id| v_player_gun.contains(*contained_entity_id))
+    .and_then(|contained_entity_id| v_template_id.get(contained_entity_id).ok())
+    .map(|template_id| template_id.template_id)
+}
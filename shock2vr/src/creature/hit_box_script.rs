@@ -39,10 +39,13 @@ impl Script for HitBoxScript {
         msg: &MessagePayload,
     ) -> Effect {
         match msg {
-            MessagePayload::Damage { amount } => Effect::Send {
+            MessagePayload::Damage { amount, source } => Effect::Send {
                 msg: Message {
                     to: self.parent_entity_id,
-                    payload: MessagePayload::Damage { amount: *amount },
+                    payload: MessagePayload::Damage {
+                        amount: *amount,
+                        source: *source,
+                    },
                 },
             },
             _ => Effect::NoEffect,
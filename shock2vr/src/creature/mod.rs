@@ -1,8 +1,14 @@
 mod creature_definitions;
+mod footsteps;
+mod held_weapons;
 mod hit_box_script;
 mod hit_boxes;
 mod rag_doll;
+mod root_motion;
 
 pub use creature_definitions::*;
+pub use footsteps::*;
+pub use held_weapons::*;
 pub use hit_boxes::*;
 pub use rag_doll::*;
+pub use root_motion::*;
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use cgmath::{Deg, InnerSpace, Vector3, vec3};
+use dark::motion::{AnimationClip, RootMotionMode};
+
+///
+/// RootMotionConfig
+///
+/// Controls how creature animations drive physics - see `MissionCore::update_animations`
+#[derive(Clone, Debug)]
+pub struct RootMotionConfig {
+    /// Zero out horizontal velocity when the root motion is slower than `foot_lock_speed`,
+    /// so standing animations with a little drift don't skate
+    pub foot_lock: bool,
+    pub foot_lock_speed: f32,
+
+    /// Scale applied to a clip's end rotation when turning the creature
+    pub turn_assist: f32,
+    /// Turn gradually over the course of the clip, instead of snapping when it completes
+    pub spread_turn_over_clip: bool,
+
+    /// Per-clip root motion mode, keyed by (lowercase) clip name - overrides the mode picked at import
+    pub clip_overrides: HashMap<String, RootMotionMode>,
+}
+
+impl Default for RootMotionConfig {
+    fn default() -> RootMotionConfig {
+        RootMotionConfig {
+            foot_lock: true,
+            foot_lock_speed: 0.05,
+            turn_assist: 0.5,
+            spread_turn_over_clip: false,
+            clip_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl RootMotionConfig {
+    pub fn mode_for_clip(&self, clip: &AnimationClip) -> RootMotionMode {
+        clip.name
+            .as_ref()
+            .and_then(|name| self.clip_overrides.get(&name.to_ascii_lowercase()))
+            .copied()
+            .unwrap_or(clip.root_motion)
+    }
+
+    ///
+    /// root_velocity
+    ///
+    /// Horizontal velocity (in the clip's space) for `clip` at `frame`, with foot-lock applied
+    pub fn root_velocity(&self, clip: &AnimationClip, frame: u32) -> Vector3<f32> {
+        let velocity = clip.root_velocity(self.mode_for_clip(clip), frame);
+        self.apply_foot_lock(velocity)
+    }
+
+    pub fn apply_foot_lock(&self, velocity: Vector3<f32>) -> Vector3<f32> {
+        if self.foot_lock && velocity.magnitude() < self.foot_lock_speed {
+            vec3(0.0, 0.0, 0.0)
+        } else {
+            velocity
+        }
+    }
+
+    ///
+    /// turn_for_frame
+    ///
+    /// Rotation to apply this frame when turning is spread over the clip
+    pub fn turn_for_frame(&self, clip: &AnimationClip, delta_time: f32) -> Deg<f32> {
+        let duration = clip.duration.as_secs_f32();
+        if duration <= 0.0 {
+            return Deg(0.0);
+        }
+
+        clip.end_rotation * self.turn_assist * (delta_time / duration).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foot_lock_zeroes_slow_drift() {
+        let config = RootMotionConfig::default();
+        assert_eq!(
+            config.apply_foot_lock(vec3(0.01, 0.0, 0.01)),
+            vec3(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            config.apply_foot_lock(vec3(1.0, 0.0, 0.0)),
+            vec3(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_foot_lock_disabled() {
+        let config = RootMotionConfig {
+            foot_lock: false,
+            ..RootMotionConfig::default()
+        };
+        assert_eq!(
+            config.apply_foot_lock(vec3(0.01, 0.0, 0.01)),
+            vec3(0.01, 0.0, 0.01)
+        );
+    }
+}
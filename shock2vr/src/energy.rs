@@ -0,0 +1,122 @@
+///
+/// energy.rs
+///
+/// The player's energy - a single pool that powers energy weapons (each shot costs a charge) and
+/// powered equipment (drained continuously while equipped, see `equipment`). Topped up by
+/// batteries, and refilled at energy stations.
+///
+/// Carried across levels and saves with the player's inventory.
+///
+use shipyard::{Unique, UniqueView, World};
+
+// Energy in a full pool
+pub const MAX_ENERGY: f32 = 100.0;
+
+// Energy used by each shot of an energy weapon
+pub const ENERGY_PER_SHOT: f32 = 4.0;
+
+// Energy restored by a portable battery
+pub const BATTERY_ENERGY: f32 = 50.0;
+
+#[derive(Unique, Clone, Debug, PartialEq)]
+pub struct PlayerEnergy {
+    energy: f32,
+}
+
+impl PlayerEnergy {
+    pub fn new() -> PlayerEnergy {
+        PlayerEnergy { energy: MAX_ENERGY }
+    }
+
+    pub fn with_energy(energy: f32) -> PlayerEnergy {
+        PlayerEnergy {
+            energy: energy.clamp(0.0, MAX_ENERGY),
+        }
+    }
+
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.energy / MAX_ENERGY
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.energy <= 0.0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.energy >= MAX_ENERGY
+    }
+
+    /// Use `amount` of energy, if there's that much left - otherwise, nothing is used
+    pub fn try_use(&mut self, amount: f32) -> bool {
+        if self.energy < amount {
+            return false;
+        }
+        self.energy -= amount;
+        true
+    }
+
+    /// Add (or, if negative, drain) energy, within the pool's limits
+    pub fn adjust(&mut self, amount: f32) {
+        self.energy = (self.energy + amount).clamp(0.0, MAX_ENERGY);
+    }
+
+    pub fn recharge(&mut self) {
+        self.energy = MAX_ENERGY;
+    }
+
+    ///
+    /// update
+    ///
+    /// Drain `drain_per_second` for powered equipment - returns true if the pool ran dry this frame
+    pub fn update(&mut self, drain_per_second: f32, delta_time: f32) -> bool {
+        if drain_per_second <= 0.0 || self.is_empty() {
+            return false;
+        }
+        self.adjust(-drain_per_second * delta_time);
+        self.is_empty()
+    }
+}
+
+impl Default for PlayerEnergy {
+    fn default() -> Self {
+        PlayerEnergy::new()
+    }
+}
+
+/// Fraction of the player's energy left, for the HUD - None outside of a mission
+pub fn get_energy_fraction(world: &World) -> Option<f32> {
+    world
+        .borrow::<UniqueView<PlayerEnergy>>()
+        .map(|energy| energy.fraction())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shots_need_enough_energy() {
+        let mut energy = PlayerEnergy::with_energy(ENERGY_PER_SHOT * 1.5);
+        assert!(energy.try_use(ENERGY_PER_SHOT));
+        assert!(!energy.try_use(ENERGY_PER_SHOT));
+        assert_eq!(energy.energy(), ENERGY_PER_SHOT * 0.5);
+
+        energy.adjust(BATTERY_ENERGY * 10.0);
+        assert!(energy.is_full());
+    }
+
+    #[test]
+    fn test_equipment_drains_pool() {
+        let mut energy = PlayerEnergy::with_energy(1.0);
+        assert!(!energy.update(0.5, 1.0));
+        assert!(energy.update(0.5, 1.0));
+        assert!(energy.is_empty());
+        // Only reported once
+        assert!(!energy.update(0.5, 1.0));
+    }
+}
@@ -0,0 +1,128 @@
+// Entity tags - gameplay categories ("is this a weapon / corpse / hackable?") worked out once, when an
+// entity is created, from the archetypes it inherits from. Systems and scripts check the
+// `RuntimePropEntityTags` component (see `has_tag`) instead of walking the template hierarchy each time.
+
+use std::collections::HashMap;
+
+use bitflags::bitflags;
+use dark::{properties::PropScripts, ss2_entity_info};
+use shipyard::{EntityId, Get, View, World};
+
+use crate::runtime_props::RuntimePropEntityTags;
+
+bitflags! {
+    pub struct EntityTags: u32 {
+        const WEAPON = 1 << 0;
+        const AMMO = 1 << 1;
+        const CREATURE = 1 << 2;
+        const CORPSE = 1 << 3;
+        const HACKABLE = 1 << 4;
+        const CONTAINER = 1 << 5;
+        const DOOR = 1 << 6;
+        const EXPLOSIVE = 1 << 7;
+        const ROBOT = 1 << 8;
+        const ENERGY_WEAPON = 1 << 9;
+    }
+}
+
+// Archetype names (lowercase) that give a tag to everything inheriting from them
+const ARCHETYPE_TAGS: &[(&str, EntityTags)] = &[
+    ("weapon", EntityTags::WEAPON),
+    ("ammo", EntityTags::AMMO),
+    ("creature", EntityTags::CREATURE),
+    ("monsters", EntityTags::CREATURE),
+    ("corpse", EntityTags::CORPSE),
+    ("corpses", EntityTags::CORPSE),
+    ("containers", EntityTags::CONTAINER),
+    ("door", EntityTags::DOOR),
+    ("doors", EntityTags::DOOR),
+    ("explosives", EntityTags::EXPLOSIVE),
+    ("grenades", EntityTags::EXPLOSIVE),
+    ("robot", EntityTags::ROBOT),
+    ("robots", EntityTags::ROBOT),
+];
+
+// Scripts (lowercase) that give a tag - scripts are inherited too, so these are checked on the final props
+const SCRIPT_TAGS: &[(&str, EntityTags)] = &[
+    ("hackablecrate", EntityTags::HACKABLE),
+    ("keypad", EntityTags::HACKABLE),
+    ("energyweapon", EntityTags::ENERGY_WEAPON),
+];
+
+///
+/// tags_for_template
+///
+/// Tags from the names of the template and all of its ancestors
+pub fn tags_for_template(
+    template_id: i32,
+    hierarchy: &HashMap<i32, Vec<i32>>,
+    obj_map: &HashMap<i32, String>,
+) -> EntityTags {
+    let mut ancestors = ss2_entity_info::get_ancestors(hierarchy, &template_id);
+    ancestors.push(template_id);
+
+    ancestors
+        .iter()
+        .filter_map(|id| obj_map.get(id))
+        .fold(EntityTags::empty(), |tags, name| tags | tags_for_name(name))
+}
+
+fn tags_for_name(name: &str) -> EntityTags {
+    let name = name.to_ascii_lowercase();
+    ARCHETYPE_TAGS
+        .iter()
+        .filter(|(archetype, _)| *archetype == name)
+        .fold(EntityTags::empty(), |tags, (_, tag)| tags | *tag)
+}
+
+pub fn tags_for_scripts(scripts: &PropScripts) -> EntityTags {
+    scripts
+        .scripts
+        .iter()
+        .map(|script| script.to_ascii_lowercase())
+        .fold(EntityTags::empty(), |tags, script| {
+            SCRIPT_TAGS
+                .iter()
+                .filter(|(name, _)| *name == script)
+                .fold(tags, |tags, (_, tag)| tags | *tag)
+        })
+}
+
+///
+/// has_tag
+///
+/// Whether the entity has all of the given tags
+pub fn has_tag(world: &World, entity_id: EntityId, tag: EntityTags) -> bool {
+    world
+        .borrow::<View<RuntimePropEntityTags>>()
+        .ok()
+        .and_then(|v_tags| v_tags.get(entity_id).ok().map(|tags| tags.0.contains(tag)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_are_inherited_from_archetypes() {
+        // -1 "Weapon" <- -2 "Pistol" <- 10 (no name)
+        let hierarchy = HashMap::from([(-2, vec![-1]), (10, vec![-2])]);
+        let obj_map = HashMap::from([(-1, "Weapon".to_owned()), (-2, "Pistol".to_owned())]);
+
+        assert_eq!(
+            tags_for_template(10, &hierarchy, &obj_map),
+            EntityTags::WEAPON
+        );
+        assert!(tags_for_template(-3, &hierarchy, &obj_map).is_empty());
+    }
+
+    #[test]
+    fn test_tags_from_scripts() {
+        let scripts = PropScripts {
+            scripts: vec!["HackableCrate".to_owned(), "Other".to_owned()],
+            inherits: true,
+        };
+        assert_eq!(tags_for_scripts(&scripts), EntityTags::HACKABLE);
+    }
+}
@@ -0,0 +1,429 @@
+///
+/// equipment.rs
+///
+/// What the player is wearing - a suit of armor and two implants, equipped by frobbing them in
+/// the inventory. Each piece has passive modifiers: armor soaks up a share of incoming damage,
+/// implants and suits can resist hazards (see `hazards`) or raise the player's maximum hit
+/// points, and powered pieces drain the player's energy (see `energy`) while equipped - once
+/// it's empty, they stop working until the player recharges.
+///
+/// Modifiers come from a built-in table, by template name, and can be set on any item through
+/// its design note, ie `damage=0.2; electric=0.5; hit_points=10; drain=0.5`.
+///
+use dark::properties::{PropDesignNote, PropHitPoints, PropMaxHitPoints, PropTemplateId};
+use serde::{Deserialize, Serialize};
+use shipyard::{EntityId, Get, Unique, UniqueView, UniqueViewMut, View, ViewMut, World};
+use tracing::warn;
+
+use crate::{
+    hazards::{HazardKind, HazardResistances},
+    mission::{GlobalEntityMetadata, PlayerInfo},
+};
+
+// Armor that isn't in the table or its design note still soaks up some damage
+const DEFAULT_ARMOR_SETTINGS: &str = "damage=0.15";
+
+// Modifiers for the original game's armor and implants, by (lowercase) template name
+const EQUIPMENT_SETTINGS: [(&str, &str); 8] = [
+    ("light armor", "damage=0.1"),
+    ("medium armor", "damage=0.2"),
+    ("heavy armor", "damage=0.3"),
+    ("powered armor", "damage=0.4; electric=0.5; drain=0.5"),
+    ("worm skin", "damage=0.2; toxin=0.75"),
+    ("endurboost", "hit_points=15"),
+    ("wormblood", "toxin=0.5; radiation=0.5"),
+    ("wormheart", "hit_points=10; drain=0.25"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquipmentKind {
+    Armor,
+    Implant,
+}
+
+impl EquipmentKind {
+    /// The slots this kind of equipment can go in
+    pub fn slots(&self) -> &'static [EquipSlot] {
+        match self {
+            EquipmentKind::Armor => &[EquipSlot::Armor],
+            EquipmentKind::Implant => &[EquipSlot::Implant1, EquipSlot::Implant2],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Armor,
+    Implant1,
+    Implant2,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Armor, EquipSlot::Implant1, EquipSlot::Implant2];
+
+    fn index(&self) -> usize {
+        match self {
+            EquipSlot::Armor => 0,
+            EquipSlot::Implant1 => 1,
+            EquipSlot::Implant2 => 2,
+        }
+    }
+
+    pub fn kind(&self) -> EquipmentKind {
+        match self {
+            EquipSlot::Armor => EquipmentKind::Armor,
+            EquipSlot::Implant1 | EquipSlot::Implant2 => EquipmentKind::Implant,
+        }
+    }
+}
+
+///
+/// EquipmentModifiers
+///
+/// The passive effects of a piece of equipment, while it's equipped (and charged)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EquipmentModifiers {
+    /// Share of incoming damage absorbed, from 0.0 to 1.0
+    pub damage_resistance: f32,
+    pub hazard_resistances: HazardResistances,
+    /// Added to the player's maximum hit points
+    pub max_hit_points: i32,
+    /// Energy used per second - zero for unpowered equipment
+    pub power_drain: f32,
+}
+
+impl EquipmentModifiers {
+    ///
+    /// parse
+    ///
+    /// Read modifiers from `name=value` settings, separated by `;` or commas - `damage`,
+    /// `hit_points`, `drain`, or a hazard kind (`electric`, `steam`, `radiation`, `toxin`)
+    pub fn parse(settings: &str) -> EquipmentModifiers {
+        let mut modifiers = EquipmentModifiers::default();
+        for (name, value) in settings
+            .split([';', ','])
+            .filter_map(|setting| setting.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim()))
+        {
+            let Ok(number) = value.parse::<f32>() else {
+                warn!(
+                    "Equipment setting {} should be a number, found '{}'",
+                    name, value
+                );
+                continue;
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "damage" => modifiers.damage_resistance = number.clamp(0.0, 1.0),
+                "hit_points" => modifiers.max_hit_points = number as i32,
+                "drain" => modifiers.power_drain = number.max(0.0),
+                hazard => match HazardKind::from_name(hazard) {
+                    Some(kind) => modifiers.hazard_resistances.set(kind, number),
+                    None => warn!("Unknown equipment setting: {}", name),
+                },
+            }
+        }
+        modifiers
+    }
+}
+
+///
+/// modifiers_for
+///
+/// The modifiers for an item - from its design note if it has settings there, then the built-in
+/// table by template name, then the defaults for its kind
+pub fn modifiers_for(
+    world: &World,
+    entity_id: EntityId,
+    kind: EquipmentKind,
+) -> EquipmentModifiers {
+    let v_design_note = world.borrow::<View<PropDesignNote>>().unwrap();
+    if let Ok(design_note) = v_design_note.get(entity_id) {
+        if design_note.0.contains('=') {
+            return EquipmentModifiers::parse(&design_note.0);
+        }
+    }
+
+    let maybe_settings = template_name(world, entity_id).and_then(|name| {
+        EQUIPMENT_SETTINGS
+            .iter()
+            .find(|(template, _)| *template == name)
+            .map(|(_, settings)| *settings)
+    });
+
+    match (maybe_settings, kind) {
+        (Some(settings), _) => EquipmentModifiers::parse(settings),
+        (None, EquipmentKind::Armor) => EquipmentModifiers::parse(DEFAULT_ARMOR_SETTINGS),
+        (None, EquipmentKind::Implant) => EquipmentModifiers::default(),
+    }
+}
+
+fn template_name(world: &World, entity_id: EntityId) -> Option<String> {
+    let template_id = world
+        .borrow::<View<PropTemplateId>>()
+        .unwrap()
+        .get(entity_id)
+        .ok()?
+        .template_id;
+    let entity_metadata = world.borrow::<UniqueView<GlobalEntityMetadata>>().ok()?;
+    entity_metadata
+        .0
+        .iter()
+        .find(|(_, metadata)| metadata.template_id == template_id)
+        .map(|(name, _)| name.clone())
+}
+
+#[derive(Clone, Debug)]
+pub struct EquippedItem {
+    pub entity_id: EntityId,
+    pub modifiers: EquipmentModifiers,
+}
+
+///
+/// PlayerEquipment
+///
+/// The player's equip slots
+#[derive(Unique, Clone, Debug)]
+pub struct PlayerEquipment {
+    slots: [Option<EquippedItem>; 3],
+    // Whether there's energy for powered pieces
+    is_powered: bool,
+    // The maximum hit points bonus currently added to the player (see `apply_modifiers`)
+    applied_max_hit_points: i32,
+}
+
+impl PlayerEquipment {
+    pub fn new() -> PlayerEquipment {
+        PlayerEquipment {
+            slots: [None, None, None],
+            is_powered: true,
+            applied_max_hit_points: 0,
+        }
+    }
+
+    pub fn get(&self, slot: EquipSlot) -> Option<&EquippedItem> {
+        self.slots[slot.index()].as_ref()
+    }
+
+    pub fn slot_of(&self, entity_id: EntityId) -> Option<EquipSlot> {
+        EquipSlot::ALL.into_iter().find(|slot| {
+            self.get(*slot)
+                .is_some_and(|item| item.entity_id == entity_id)
+        })
+    }
+
+    pub fn equipped(&self) -> impl Iterator<Item = (EquipSlot, &EquippedItem)> {
+        EquipSlot::ALL
+            .into_iter()
+            .filter_map(|slot| self.get(slot).map(|item| (slot, item)))
+    }
+
+    ///
+    /// equip
+    ///
+    /// Put an item in the first free slot for its kind, or swap out the first slot if they're
+    /// all taken - returns the slot, and the item it replaced
+    pub fn equip(
+        &mut self,
+        entity_id: EntityId,
+        kind: EquipmentKind,
+        modifiers: EquipmentModifiers,
+    ) -> (EquipSlot, Option<EntityId>) {
+        let slots = kind.slots();
+        let slot = slots
+            .iter()
+            .find(|slot| self.get(**slot).is_none())
+            .copied()
+            .unwrap_or(slots[0]);
+        self.equip_in(slot, entity_id, modifiers)
+    }
+
+    pub fn equip_in(
+        &mut self,
+        slot: EquipSlot,
+        entity_id: EntityId,
+        modifiers: EquipmentModifiers,
+    ) -> (EquipSlot, Option<EntityId>) {
+        self.unequip(entity_id);
+        let replaced = self.slots[slot.index()].replace(EquippedItem {
+            entity_id,
+            modifiers,
+        });
+        (slot, replaced.map(|item| item.entity_id))
+    }
+
+    pub fn unequip(&mut self, entity_id: EntityId) -> Option<EquipSlot> {
+        let slot = self.slot_of(entity_id)?;
+        self.slots[slot.index()] = None;
+        Some(slot)
+    }
+
+    /// Energy used per second by the equipped powered pieces
+    pub fn power_drain(&self) -> f32 {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|item| item.modifiers.power_drain)
+            .sum()
+    }
+
+    ///
+    /// set_powered
+    ///
+    /// Whether the player has energy for powered pieces - returns true if that changed, so the
+    /// modifiers need re-applying
+    pub fn set_powered(&mut self, is_powered: bool) -> bool {
+        let changed = self.is_powered != is_powered;
+        self.is_powered = is_powered;
+        changed
+    }
+
+    fn is_active(&self, item: &EquippedItem) -> bool {
+        item.modifiers.power_drain <= 0.0 || self.is_powered
+    }
+
+    fn active_modifiers(&self) -> impl Iterator<Item = &EquipmentModifiers> {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|item| self.is_active(item))
+            .map(|item| &item.modifiers)
+    }
+
+    /// Share of incoming damage absorbed - stacking pieces absorb from what's left
+    pub fn damage_resistance(&self) -> f32 {
+        1.0 - self
+            .active_modifiers()
+            .map(|modifiers| 1.0 - modifiers.damage_resistance)
+            .product::<f32>()
+    }
+
+    pub fn max_hit_points_bonus(&self) -> i32 {
+        self.active_modifiers()
+            .map(|modifiers| modifiers.max_hit_points)
+            .sum()
+    }
+
+    pub fn hazard_resistances(&self) -> HazardResistances {
+        let mut resistances = HazardResistances::new();
+        for kind in HazardKind::ALL {
+            let resistance = 1.0
+                - self
+                    .active_modifiers()
+                    .map(|modifiers| 1.0 - modifiers.hazard_resistances.get(kind))
+                    .product::<f32>();
+            resistances.set(kind, resistance);
+        }
+        resistances
+    }
+
+    ///
+    /// absorb_damage
+    ///
+    /// Split incoming damage (a positive number of hit points) into what gets through and what
+    /// the armor soaks up
+    pub fn absorb_damage(&self, damage: i32) -> (i32, i32) {
+        let absorbed = (damage as f32 * self.damage_resistance()).round() as i32;
+        (damage - absorbed, absorbed)
+    }
+}
+
+///
+/// apply_modifiers
+///
+/// Bring the player in line with their equipment - their hazard resistances, and maximum hit
+/// points. Called whenever the equipment (or a powered item's charge) changes.
+pub fn apply_modifiers(world: &World) {
+    let mut equipment = world.borrow::<UniqueViewMut<PlayerEquipment>>().unwrap();
+    *world.borrow::<UniqueViewMut<HazardResistances>>().unwrap() = equipment.hazard_resistances();
+
+    let bonus = equipment.max_hit_points_bonus();
+    let change = bonus - equipment.applied_max_hit_points;
+    if change == 0 {
+        return;
+    }
+    equipment.applied_max_hit_points = bonus;
+
+    let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+    let mut v_max_hit_points = world.borrow::<ViewMut<PropMaxHitPoints>>().unwrap();
+    let Ok(max_hit_points) = (&mut v_max_hit_points).get(player_entity_id) else {
+        return;
+    };
+    max_hit_points.hit_points += change;
+
+    let mut v_hit_points = world.borrow::<ViewMut<PropHitPoints>>().unwrap();
+    if let Ok(hit_points) = (&mut v_hit_points).get(player_entity_id) {
+        hit_points.hit_points = hit_points.hit_points.min(max_hit_points.hit_points);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities(count: usize) -> Vec<EntityId> {
+        let mut world = World::new();
+        (0..count).map(|_| world.add_entity(())).collect()
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let modifiers =
+            EquipmentModifiers::parse("damage=0.4; Electric=0.5, hit_points=10; drain=x");
+        assert_eq!(modifiers.damage_resistance, 0.4);
+        assert_eq!(modifiers.hazard_resistances.get(HazardKind::Electric), 0.5);
+        assert_eq!(modifiers.max_hit_points, 10);
+        assert_eq!(modifiers.power_drain, 0.0);
+    }
+
+    #[test]
+    fn test_equip_slots() {
+        let ids = entities(3);
+        let mut equipment = PlayerEquipment::new();
+        let implant = EquipmentModifiers::parse("hit_points=5");
+
+        assert_eq!(
+            equipment.equip(ids[0], EquipmentKind::Implant, implant.clone()),
+            (EquipSlot::Implant1, None)
+        );
+        assert_eq!(
+            equipment.equip(ids[1], EquipmentKind::Implant, implant.clone()),
+            (EquipSlot::Implant2, None)
+        );
+        assert_eq!(
+            equipment.equip(ids[2], EquipmentKind::Implant, implant.clone()),
+            (EquipSlot::Implant1, Some(ids[0]))
+        );
+        assert_eq!(equipment.max_hit_points_bonus(), 10);
+
+        assert_eq!(equipment.unequip(ids[1]), Some(EquipSlot::Implant2));
+        assert_eq!(equipment.slot_of(ids[2]), Some(EquipSlot::Implant1));
+        assert_eq!(equipment.max_hit_points_bonus(), 5);
+    }
+
+    #[test]
+    fn test_powered_armor_runs_out() {
+        let ids = entities(2);
+        let mut equipment = PlayerEquipment::new();
+        equipment.equip(
+            ids[0],
+            EquipmentKind::Armor,
+            EquipmentModifiers::parse("damage=0.5; drain=50"),
+        );
+        equipment.equip(
+            ids[1],
+            EquipmentKind::Implant,
+            EquipmentModifiers::parse("damage=0.5"),
+        );
+        assert_eq!(equipment.absorb_damage(8), (2, 6));
+
+        assert_eq!(equipment.power_drain(), 50.0);
+
+        assert!(!equipment.set_powered(true));
+        assert!(equipment.set_powered(false));
+        assert_eq!(equipment.absorb_damage(8), (4, 4));
+
+        assert!(equipment.set_powered(true));
+        assert_eq!(equipment.absorb_damage(8), (2, 6));
+    }
+}
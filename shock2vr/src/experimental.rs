@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+///
+/// Stability
+///
+/// How far along an experimental feature is - whether it's expected to work, or just to run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stability {
+    /// Work in progress - likely broken in places
+    Unstable,
+    /// Mostly working, and on its way to being on by default
+    Preview,
+    /// A developer tool, rather than a gameplay feature
+    Debug,
+}
+
+///
+/// ExperimentalFeature
+///
+/// The features that can be turned on with `--experimental <name>` (or `experimental_features`
+/// in a profile's settings.json)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentalFeature {
+    Teleport,
+    Gui,
+    Noclip,
+    EnhancedLighting,
+}
+
+impl ExperimentalFeature {
+    pub const ALL: [ExperimentalFeature; 4] = [
+        ExperimentalFeature::Teleport,
+        ExperimentalFeature::Gui,
+        ExperimentalFeature::Noclip,
+        ExperimentalFeature::EnhancedLighting,
+    ];
+
+    /// The name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExperimentalFeature::Teleport => "teleport",
+            ExperimentalFeature::Gui => "gui",
+            ExperimentalFeature::Noclip => "noclip",
+            ExperimentalFeature::EnhancedLighting => "enhanced_lighting",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ExperimentalFeature::Teleport => "Point-and-teleport VR locomotion",
+            ExperimentalFeature::Gui => "Script-driven in-world GUI panels",
+            ExperimentalFeature::Noclip => "Allow noclip in release builds",
+            ExperimentalFeature::EnhancedLighting => "Spotlights on the hands",
+        }
+    }
+
+    pub fn stability(&self) -> Stability {
+        match self {
+            ExperimentalFeature::Teleport => Stability::Preview,
+            ExperimentalFeature::Gui => Stability::Unstable,
+            ExperimentalFeature::Noclip => Stability::Debug,
+            ExperimentalFeature::EnhancedLighting => Stability::Unstable,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ExperimentalFeature> {
+        let name = name.trim().replace('-', "_").to_ascii_lowercase();
+        Self::ALL.into_iter().find(|feature| feature.name() == name)
+    }
+}
+
+///
+/// ExperimentalFeatureInfo
+///
+/// A feature's description, for listing with `--list-experimental` or `/v1/features`
+#[derive(Clone, Debug, Serialize)]
+pub struct ExperimentalFeatureInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub stability: Stability,
+    pub enabled: bool,
+}
+
+///
+/// ExperimentalFeatures
+///
+/// The set of experimental features that are turned on
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct ExperimentalFeatures(HashSet<ExperimentalFeature>);
+
+impl ExperimentalFeatures {
+    ///
+    /// parse
+    ///
+    /// Parse feature names - each entry can also be a comma-separated list. Unknown names are
+    /// warned about and left out, so a typo doesn't silently do nothing.
+    pub fn parse<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> ExperimentalFeatures {
+        let mut features = HashSet::new();
+        for entry in names {
+            for name in entry
+                .as_ref()
+                .split(',')
+                .filter(|name| !name.trim().is_empty())
+            {
+                match ExperimentalFeature::from_name(name) {
+                    Some(feature) => {
+                        features.insert(feature);
+                    }
+                    None => warn!(
+                        "Unknown experimental feature '{}' - known features: {}",
+                        name.trim(),
+                        known_feature_names()
+                    ),
+                }
+            }
+        }
+        ExperimentalFeatures(features)
+    }
+
+    pub fn contains(&self, feature: ExperimentalFeature) -> bool {
+        self.0.contains(&feature)
+    }
+
+    pub fn insert(&mut self, feature: ExperimentalFeature) {
+        self.0.insert(feature);
+    }
+
+    pub fn extend(&mut self, other: ExperimentalFeatures) {
+        self.0.extend(other.0);
+    }
+
+    ///
+    /// describe
+    ///
+    /// Every known feature, and whether it's turned on
+    pub fn describe(&self) -> Vec<ExperimentalFeatureInfo> {
+        ExperimentalFeature::ALL
+            .into_iter()
+            .map(|feature| ExperimentalFeatureInfo {
+                name: feature.name(),
+                description: feature.description(),
+                stability: feature.stability(),
+                enabled: self.contains(feature),
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<String>> for ExperimentalFeatures {
+    fn from(names: Vec<String>) -> Self {
+        ExperimentalFeatures::parse(names)
+    }
+}
+
+impl From<ExperimentalFeatures> for Vec<String> {
+    fn from(features: ExperimentalFeatures) -> Self {
+        let mut names: Vec<String> = features
+            .0
+            .into_iter()
+            .map(|feature| feature.name().to_owned())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+///
+/// list_text
+///
+/// A table of the known features, for printing with `--list-experimental`
+pub fn list_text() -> String {
+    let mut text = String::from("Experimental features (enable with --experimental <name>,...):\n");
+    for feature in ExperimentalFeature::ALL {
+        text.push_str(&format!(
+            "  {:<20} {:<10} {}\n",
+            feature.name(),
+            format!("{:?}", feature.stability()).to_ascii_lowercase(),
+            feature.description()
+        ));
+    }
+    text
+}
+
+fn known_feature_names() -> String {
+    ExperimentalFeature::ALL
+        .iter()
+        .map(|feature| feature.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for feature in ExperimentalFeature::ALL {
+            assert_eq!(
+                ExperimentalFeature::from_name(feature.name()),
+                Some(feature)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_comma_separated() {
+        let features = ExperimentalFeatures::parse(["teleport,gui", "Enhanced-Lighting"]);
+        assert!(features.contains(ExperimentalFeature::Teleport));
+        assert!(features.contains(ExperimentalFeature::Gui));
+        assert!(features.contains(ExperimentalFeature::EnhancedLighting));
+        assert!(!features.contains(ExperimentalFeature::Noclip));
+    }
+
+    #[test]
+    fn test_parse_skips_unknown() {
+        let features = ExperimentalFeatures::parse(["telport", "noclip"]);
+        assert_eq!(
+            features,
+            ExperimentalFeatures::parse(["noclip"]),
+            "unknown names are left out"
+        );
+    }
+
+    #[test]
+    fn test_serializes_as_names() {
+        let features = ExperimentalFeatures::parse(["noclip", "teleport"]);
+        let json = serde_json::to_string(&features).unwrap();
+        assert_eq!(json, r#"["noclip","teleport"]"#);
+        let restored: ExperimentalFeatures = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, features);
+    }
+}
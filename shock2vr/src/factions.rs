@@ -0,0 +1,231 @@
+///
+/// factions.rs
+///
+/// Friend-or-foe - every creature, robot and security device belongs to a faction, and an
+/// attitude matrix says how each faction feels about the others. AI only notices the player if
+/// its faction is hostile to them, and turrets shoot whatever is hostile to theirs.
+///
+/// An entity's faction comes from what it is (see `default_faction`), unless it's been switched
+/// at runtime with `Effect::SetFaction` - ie, hacking a turret brings it over to the player's
+/// side. Like the rest of the runtime props, a switched faction isn't saved.
+///
+use dark::properties::PropAI;
+use shipyard::{EntityId, Get, Unique, UniqueView, View, World};
+
+use crate::{
+    entity_tags::{self, EntityTags},
+    mission::PlayerInfo,
+    runtime_props::RuntimePropFaction,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Faction {
+    Player,
+    Hybrid,
+    Robot,
+    /// Cameras, turrets and the rest of the ship's security network
+    Security,
+}
+
+impl Faction {
+    pub const ALL: [Faction; 4] = [
+        Faction::Player,
+        Faction::Hybrid,
+        Faction::Robot,
+        Faction::Security,
+    ];
+
+    /// Parse a faction name, ie 'robot'
+    pub fn from_name(name: &str) -> Option<Faction> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "player" => Some(Faction::Player),
+            "hybrid" | "hybrids" | "many" => Some(Faction::Hybrid),
+            "robot" | "robots" => Some(Faction::Robot),
+            "security" => Some(Faction::Security),
+            _ => None,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Faction::Player => 0,
+            Faction::Hybrid => 1,
+            Faction::Robot => 2,
+            Faction::Security => 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attitude {
+    Hostile,
+    Neutral,
+    Friendly,
+}
+
+///
+/// FactionRelations
+///
+/// How each faction feels about the others - `attitude(from, to)` is how `from` treats `to`,
+/// which doesn't have to be returned
+#[derive(Unique, Clone, Debug, PartialEq)]
+pub struct FactionRelations {
+    attitudes: [[Attitude; 4]; 4],
+}
+
+impl FactionRelations {
+    ///
+    /// new
+    ///
+    /// Everything on the ship is out to get the player, but leaves each other be. The player's
+    /// side (hacked devices) goes after hybrids and robots, and ignores the rest of the
+    /// security network.
+    pub fn new() -> FactionRelations {
+        let mut relations = FactionRelations {
+            attitudes: [[Attitude::Neutral; 4]; 4],
+        };
+        for faction in Faction::ALL {
+            relations.set_attitude(faction, faction, Attitude::Friendly);
+        }
+        for faction in [Faction::Hybrid, Faction::Robot, Faction::Security] {
+            relations.set_attitude(faction, Faction::Player, Attitude::Hostile);
+        }
+        relations.set_attitude(Faction::Player, Faction::Hybrid, Attitude::Hostile);
+        relations.set_attitude(Faction::Player, Faction::Robot, Attitude::Hostile);
+        relations
+    }
+
+    pub fn attitude(&self, from: Faction, to: Faction) -> Attitude {
+        self.attitudes[from.index()][to.index()]
+    }
+
+    pub fn set_attitude(&mut self, from: Faction, to: Faction, attitude: Attitude) {
+        self.attitudes[from.index()][to.index()] = attitude;
+    }
+}
+
+impl Default for FactionRelations {
+    fn default() -> Self {
+        FactionRelations::new()
+    }
+}
+
+///
+/// faction_of
+///
+/// The entity's faction - None for things that don't take sides (items, doors, ...)
+pub fn faction_of(world: &World, entity_id: EntityId) -> Option<Faction> {
+    let switched = world
+        .borrow::<View<RuntimePropFaction>>()
+        .ok()
+        .and_then(|v_faction| v_faction.get(entity_id).ok().map(|faction| faction.0));
+    switched.or_else(|| default_faction(world, entity_id))
+}
+
+///
+/// default_faction
+///
+/// The faction an entity starts in - the player, security devices (by their AI), robots, or
+/// any other creature, which are the Many's
+fn default_faction(world: &World, entity_id: EntityId) -> Option<Faction> {
+    let is_player = world
+        .borrow::<UniqueView<PlayerInfo>>()
+        .is_ok_and(|player| player.entity_id == entity_id);
+    if is_player {
+        return Some(Faction::Player);
+    }
+
+    let is_security = world.borrow::<View<PropAI>>().is_ok_and(|v_ai| {
+        v_ai.get(entity_id).is_ok_and(|ai| {
+            ai.0.eq_ignore_ascii_case("turret") || ai.0.eq_ignore_ascii_case("camera")
+        })
+    });
+    if is_security {
+        Some(Faction::Security)
+    } else if entity_tags::has_tag(world, entity_id, EntityTags::ROBOT) {
+        Some(Faction::Robot)
+    } else if entity_tags::has_tag(world, entity_id, EntityTags::CREATURE) {
+        Some(Faction::Hybrid)
+    } else {
+        None
+    }
+}
+
+///
+/// attitude_between
+///
+/// How `from` treats `to` - neutral if either doesn't take sides
+pub fn attitude_between(world: &World, from: EntityId, to: EntityId) -> Attitude {
+    match (faction_of(world, from), faction_of(world, to)) {
+        (Some(from), Some(to)) => world
+            .borrow::<UniqueView<FactionRelations>>()
+            .map(|relations| relations.attitude(from, to))
+            .unwrap_or_else(|_| FactionRelations::new().attitude(from, to)),
+        _ => Attitude::Neutral,
+    }
+}
+
+pub fn is_hostile(world: &World, from: EntityId, to: EntityId) -> bool {
+    attitude_between(world, from, to) == Attitude::Hostile
+}
+
+/// Whether the entity is out to get the player
+pub fn is_hostile_to_player(world: &World, entity_id: EntityId) -> bool {
+    let Ok(player_id) = world
+        .borrow::<UniqueView<PlayerInfo>>()
+        .map(|player| player.entity_id)
+    else {
+        return false;
+    };
+    is_hostile(world, entity_id, player_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime_props::RuntimePropEntityTags;
+
+    use super::*;
+
+    #[test]
+    fn test_default_relations() {
+        let relations = FactionRelations::new();
+        assert_eq!(
+            relations.attitude(Faction::Hybrid, Faction::Player),
+            Attitude::Hostile
+        );
+        assert_eq!(
+            relations.attitude(Faction::Hybrid, Faction::Robot),
+            Attitude::Neutral
+        );
+        assert_eq!(
+            relations.attitude(Faction::Security, Faction::Security),
+            Attitude::Friendly
+        );
+
+        // A hacked turret goes after the Many, but leaves the security network alone
+        assert_eq!(
+            relations.attitude(Faction::Player, Faction::Hybrid),
+            Attitude::Hostile
+        );
+        assert_eq!(
+            relations.attitude(Faction::Player, Faction::Security),
+            Attitude::Neutral
+        );
+    }
+
+    #[test]
+    fn test_switched_faction_replaces_default() {
+        let mut world = World::new();
+        let turret = world.add_entity(PropAI("Turret".to_owned()));
+        let robot = world.add_entity(RuntimePropEntityTags(
+            EntityTags::CREATURE | EntityTags::ROBOT,
+        ));
+        let crate_id = world.add_entity(());
+        assert_eq!(faction_of(&world, turret), Some(Faction::Security));
+        assert_eq!(faction_of(&world, robot), Some(Faction::Robot));
+        assert_eq!(faction_of(&world, crate_id), None);
+
+        world.add_component(turret, RuntimePropFaction(Faction::Player));
+        assert_eq!(faction_of(&world, turret), Some(Faction::Player));
+    }
+}
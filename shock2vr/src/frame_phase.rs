@@ -0,0 +1,41 @@
+///
+/// frame_phase.rs
+///
+/// `Game::update` runs in three phases, each handing its results to the next:
+///
+/// - `Game::process_input` - pauses (photo mode, cutscenes), time scaling, and turning commands
+///   into effects. Produces a `SimulationInput`, or None if nothing is simulated this frame.
+/// - `Game::simulate` - the scene update, and handling the effects it produced. Produces a
+///   `SimulationOutput`.
+/// - `Game::prepare_render` - music, ambient sounds and the audio listener, from where the
+///   simulation left the player.
+///
+/// Runtimes can run them separately - ie, the VR runtime can simulate while waiting on the
+/// compositor, and a headless runtime can skip render-prep altogether.
+///
+use cgmath::Quaternion;
+
+use crate::{input_context::InputContext, scripts::Effect, time::Time};
+
+///
+/// SimulationInput
+///
+/// Handed from input processing to simulation
+pub struct SimulationInput {
+    /// Game time for this frame - already scaled by the time scale
+    pub time: Time,
+    pub input_context: InputContext,
+    /// Effects from this frame's commands
+    pub command_effects: Vec<Effect>,
+}
+
+///
+/// SimulationOutput
+///
+/// Handed from simulation to render-prep. The ambient audio state and the player are read from
+/// the scene in render-prep, after the frame's effects - so a level transition is heard from
+/// the new level, with its music and emitters.
+pub struct SimulationOutput {
+    /// The headset's rotation this frame - the player's turn is added in render-prep
+    pub head_rotation: Quaternion<f32>,
+}
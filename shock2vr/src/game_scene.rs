@@ -1,17 +1,24 @@
+use cgmath::InnerSpace;
 use cgmath::{Matrix4, Point3, Quaternion, Vector2, Vector3};
 use engine::{
     assets::asset_cache::AssetCache,
-    audio::AudioContext,
+    audio::{self, AudioClip, AudioContext, AudioHandle},
     scene::{SceneObject, light::SpotLight},
 };
 use serde::Serialize;
 use shipyard::{EntityId, World};
-use std::any::Any;
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+use tracing::warn;
 
 use crate::{
-    GameOptions,
+    GameOptions, PhysicsConfig,
     input_context::InputContext,
-    mission::GlobalContext,
+    mission::{EntityEvent, GlobalContext, ResourceLeaks, VisibilityEngineKind, VisibilityStats},
+    physics::InternalCollisionGroups,
     quest_info::QuestInfo,
     scripts::{Effect, GlobalEffect},
     time::Time,
@@ -22,7 +29,166 @@ pub struct AmbientAudioState {
     pub player_position: Vector3<f32>,
     pub music_cue: Option<String>,
     pub environmental_cue: Option<String>,
-    pub ambient_emitters: Vec<(EntityId, Vector3<f32>, String)>,
+    /// Positional ambient sounds near the player - everything within `AMBIENT_EXIT_RADIUS_SCALE`
+    /// times its radius, for `AmbientAudioSystem` to pick from
+    pub ambient_emitters: Vec<AmbientEmitter>,
+}
+
+///
+/// AmbientEmitter
+///
+/// A positional ambient sound (`P$AmbientHa`) - `radius` is how far away it can be heard,
+/// and `volume` is in millibels, like the original (0 is full volume, -10000 silent)
+#[derive(Clone, Debug)]
+pub struct AmbientEmitter {
+    pub entity_id: EntityId,
+    pub position: Vector3<f32>,
+    pub schema: String,
+    pub radius: f32,
+    pub volume: i32,
+}
+
+// Most ambient emitters playing at once - the closest win
+pub const MAX_AMBIENT_EMITTERS: usize = 8;
+
+// Hysteresis, so emitters near a boundary don't flicker on and off as the player moves:
+// an emitter starts inside its radius, but only stops once past this multiple of it
+pub const AMBIENT_EXIT_RADIUS_SCALE: f32 = 1.15;
+
+// ...and a playing emitter only loses its slot to one that's this much closer
+const AMBIENT_SWAP_DISTANCE_SCALE: f32 = 0.8;
+
+const AMBIENT_BASE_VOLUME: f32 = 0.5;
+
+struct ActiveAmbientEmitter {
+    handle: AudioHandle,
+    schema: String,
+}
+
+///
+/// AmbientAudioSystem
+///
+/// Owns the ambient emitter sounds across frames - starts the closest `MAX_AMBIENT_EMITTERS`,
+/// loops them, attenuates them with distance, and stops them once out of range
+#[derive(Default)]
+pub struct AmbientAudioSystem {
+    active: HashMap<EntityId, ActiveAmbientEmitter>,
+}
+
+impl AmbientAudioSystem {
+    pub fn new() -> AmbientAudioSystem {
+        AmbientAudioSystem {
+            active: HashMap::new(),
+        }
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    ///
+    /// update
+    ///
+    /// `resolve_clip` turns an emitter's schema into a clip - it's called each time a sound (re)starts,
+    /// so looping schemas with several samples vary between loops
+    pub fn update(
+        &mut self,
+        listener_position: Vector3<f32>,
+        emitters: &[AmbientEmitter],
+        audio_context: &mut AudioContext<EntityId, String>,
+        mut resolve_clip: impl FnMut(&str) -> Option<Rc<AudioClip>>,
+    ) {
+        let active_ids: HashSet<EntityId> = self.active.keys().copied().collect();
+        let selected = select_emitters(listener_position, emitters, &active_ids);
+        let selected_ids: HashSet<EntityId> = selected.iter().map(|(e, _)| e.entity_id).collect();
+
+        // Stop anything that's out of range, or lost its slot
+        self.active.retain(|entity_id, active| {
+            let keep = selected_ids.contains(entity_id);
+            if !keep {
+                audio::stop_audio(audio_context, active.handle.clone());
+            }
+            keep
+        });
+
+        for (emitter, volume) in selected {
+            // The same entity id might be reused for a different sound after a level change
+            if let Some(active) = self.active.get(&emitter.entity_id) {
+                if active.schema != emitter.schema {
+                    audio::stop_audio(audio_context, active.handle.clone());
+                    self.active.remove(&emitter.entity_id);
+                }
+            }
+
+            let active =
+                self.active
+                    .entry(emitter.entity_id)
+                    .or_insert_with(|| ActiveAmbientEmitter {
+                        handle: AudioHandle::new(),
+                        schema: emitter.schema.clone(),
+                    });
+
+            // Finished (or never started) - loop it
+            if !audio::set_audio_position(audio_context, &active.handle, emitter.position) {
+                if let Some(clip) = resolve_clip(&emitter.schema) {
+                    audio::play_spatial_audio(
+                        audio_context,
+                        emitter.position,
+                        active.handle.clone(),
+                        None,
+                        clip,
+                    );
+                }
+            }
+            audio::set_audio_volume(audio_context, &active.handle, volume);
+        }
+    }
+}
+
+// Closest emitters in range, with the volume each should play at. Playing emitters get
+// the wider exit radius, and a head start against new ones - see the hysteresis constants.
+fn select_emitters<'a>(
+    listener_position: Vector3<f32>,
+    emitters: &'a [AmbientEmitter],
+    active: &HashSet<EntityId>,
+) -> Vec<(&'a AmbientEmitter, f32)> {
+    let mut candidates: Vec<(f32, &AmbientEmitter, f32)> = emitters
+        .iter()
+        .filter(|emitter| emitter.radius > 0.0)
+        .filter_map(|emitter| {
+            let distance = (emitter.position - listener_position).magnitude();
+            let normalized_distance = distance / emitter.radius;
+            let is_active = active.contains(&emitter.entity_id);
+            let max_distance = if is_active {
+                AMBIENT_EXIT_RADIUS_SCALE
+            } else {
+                1.0
+            };
+            if normalized_distance >= max_distance {
+                return None;
+            }
+
+            let priority = if is_active {
+                distance * AMBIENT_SWAP_DISTANCE_SCALE
+            } else {
+                distance
+            };
+            Some((priority, emitter, attenuate(emitter, normalized_distance)))
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+    candidates
+        .into_iter()
+        .take(MAX_AMBIENT_EMITTERS)
+        .map(|(_, emitter, volume)| (emitter, volume))
+        .collect()
+}
+
+fn attenuate(emitter: &AmbientEmitter, normalized_distance: f32) -> f32 {
+    let falloff = (1.0 - normalized_distance.min(1.0)).powi(2);
+    let gain = 10.0_f32.powf(emitter.volume.min(0) as f32 / 2000.0);
+    AMBIENT_BASE_VOLUME * gain * falloff
 }
 
 /// Abstract game scene that can be rendered and updated
@@ -84,6 +250,22 @@ pub trait GameScene {
         Vec::new()
     }
 
+    /// Apply a new physics config, for scenes that have a physics world
+    fn set_physics_config(&mut self, _config: &PhysicsConfig) {}
+
+    /// Switch visibility engines, for scenes that cull against a world rep
+    fn set_visibility_engine(&mut self, _kind: VisibilityEngineKind) {}
+
+    /// Rendered vs culled entities from the last frame, for scenes that cull
+    fn visibility_stats(&self) -> Option<VisibilityStats> {
+        None
+    }
+
+    /// Resources found belonging to deleted entities, for scenes that check
+    fn resource_leaks(&self) -> Option<ResourceLeaks> {
+        None
+    }
+
     /// Get lighting information for VR enhancement
     fn get_hand_spotlights(&self, options: &GameOptions) -> Vec<SpotLight>;
 
@@ -206,6 +388,34 @@ impl RaycastMask {
             ],
         }
     }
+
+    ///
+    /// collision_groups
+    ///
+    /// The physics groups the mask's names stand for - "level" is accepted as another name for
+    /// "world", and "all" for everything. Unknown names are skipped, with a warning.
+    pub fn collision_groups(&self) -> InternalCollisionGroups {
+        self.groups
+            .iter()
+            .fold(InternalCollisionGroups::empty(), |groups, name| {
+                let group = match name.to_ascii_lowercase().as_str() {
+                    "all" => InternalCollisionGroups::ALL,
+                    "world" | "level" => InternalCollisionGroups::WORLD,
+                    "entity" => InternalCollisionGroups::ENTITY,
+                    "selectable" => InternalCollisionGroups::SELECTABLE,
+                    "player" => InternalCollisionGroups::PLAYER,
+                    "ui" => InternalCollisionGroups::UI,
+                    "hitbox" => InternalCollisionGroups::HITBOX,
+                    "raycast" => InternalCollisionGroups::RAYCAST,
+                    "hand" => InternalCollisionGroups::HAND,
+                    _ => {
+                        warn!("Unknown raycast collision group: {}", name);
+                        InternalCollisionGroups::empty()
+                    }
+                };
+                groups | group
+            })
+    }
 }
 
 /// Summary information about a physics body for debug listing
@@ -344,32 +554,111 @@ pub trait DebuggableScene {
     /// Detailed physics body information, or None if body doesn't exist
     fn physics_body_detail(&self, body_id: u32) -> Option<DebugPhysicsBodyDetail>;
 
-    /// Get current input context state
+    /// Hash of the simulation state - serializable components plus physics bodies
     ///
-    /// Returns the current input state including head rotation, hand positions,
-    /// and controller values. Used for debugging input issues and understanding
-    /// current control state.
+    /// Two runs that start from the same mission and see the same inputs should
+    /// produce the same hash every frame. Used to check the simulation is deterministic,
+    /// which replay-based tests rely on.
     ///
     /// # Returns
-    /// Current input context state as a serializable structure
-    fn get_input_state(&self) -> crate::input_context::InputContext;
+    /// FNV-1a hash of the world state (see `state_hash::format_hash` for display)
+    fn state_hash(&self) -> u64;
 
-    /// Set or modify input context values
+    /// Get recent entity lifecycle events
     ///
-    /// Allows remote control of input channels for testing and debugging.
-    /// Can be used to simulate controller input, head rotation, and hand positions
-    /// without requiring physical VR hardware.
+    /// Returns the entities created, destroyed, re-modeled or given scripts
+    /// since the mission loaded, as far back as the event history goes. Observers
+    /// poll with one past the last sequence they saw, to get only new events.
     ///
     /// # Arguments
-    /// * `channel` - Input channel to modify (e.g., "head.rotation", "left_hand.trigger_value")
-    /// * `value` - New value for the channel (format depends on channel type)
+    /// * `sequence` - Oldest event sequence to return
     ///
     /// # Returns
-    /// true if the channel was successfully modified, false if channel doesn't exist
-    ///
-    /// # Examples
-    /// - `set_input("head.rotation", [0.0, 0.707, 0.0, 0.707])` - Set head rotation
-    /// - `set_input("right_hand.trigger_value", 1.0)` - Full trigger press
-    /// - `set_input("left_hand.thumbstick", [0.5, -0.8])` - Thumbstick input
-    fn set_input(&mut self, channel: &str, value: serde_json::Value) -> bool;
+    /// Events in the order they happened
+    fn entity_events_since(&self, sequence: u64) -> Vec<EntityEvent>;
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::vec3;
+
+    use super::*;
+
+    fn emitter(entity_id: EntityId, x: f32, radius: f32) -> AmbientEmitter {
+        AmbientEmitter {
+            entity_id,
+            position: vec3(x, 0.0, 0.0),
+            schema: format!("amb{entity_id:?}"),
+            radius,
+            volume: 0,
+        }
+    }
+
+    fn selected_ids(selected: &[(&AmbientEmitter, f32)]) -> Vec<EntityId> {
+        selected.iter().map(|(e, _)| e.entity_id).collect()
+    }
+
+    #[test]
+    fn test_raycast_mask_accepts_level_for_world() {
+        let mask = RaycastMask::new(vec!["entity".to_string(), "level".to_string()]);
+        assert_eq!(
+            mask.collision_groups(),
+            InternalCollisionGroups::ENTITY | InternalCollisionGroups::WORLD
+        );
+
+        let mask = RaycastMask::new(vec!["hitbox".to_string(), "bogus".to_string()]);
+        assert_eq!(mask.collision_groups(), InternalCollisionGroups::HITBOX);
+    }
+
+    #[test]
+    fn test_emitters_start_inside_radius_and_stop_past_exit_radius() {
+        let mut world = World::new();
+        let entity_id = world.add_entity(());
+        let emitters = vec![emitter(entity_id, 10.5, 10.0)];
+        let origin = vec3(0.0, 0.0, 0.0);
+
+        // Just outside the radius - doesn't start...
+        assert!(select_emitters(origin, &emitters, &HashSet::new()).is_empty());
+
+        // ...but keeps playing if it already was
+        let active = HashSet::from([emitters[0].entity_id]);
+        assert_eq!(select_emitters(origin, &emitters, &active).len(), 1);
+
+        let far = vec![emitter(entity_id, 12.0, 10.0)];
+        assert!(select_emitters(origin, &far, &active).is_empty());
+    }
+
+    #[test]
+    fn test_closest_emitters_win_with_hysteresis() {
+        let mut world = World::new();
+        let mut emitters: Vec<AmbientEmitter> = (0..MAX_AMBIENT_EMITTERS)
+            .map(|_| emitter(world.add_entity(()), 5.0, 100.0))
+            .collect();
+        let origin = vec3(0.0, 0.0, 0.0);
+        let active: HashSet<EntityId> = emitters.iter().map(|e| e.entity_id).collect();
+
+        // Slightly closer isn't enough to take a slot from a playing emitter
+        let newcomer = world.add_entity(());
+        emitters.push(emitter(newcomer, 4.5, 100.0));
+        let selected = select_emitters(origin, &emitters, &active);
+        assert!(!selected_ids(&selected).contains(&newcomer));
+
+        // Much closer is
+        emitters[MAX_AMBIENT_EMITTERS] = emitter(newcomer, 1.0, 100.0);
+        let selected = select_emitters(origin, &emitters, &active);
+        assert_eq!(selected.len(), MAX_AMBIENT_EMITTERS);
+        assert!(selected_ids(&selected).contains(&newcomer));
+    }
+
+    #[test]
+    fn test_volume_falls_off_with_distance() {
+        let entity_id = World::new().add_entity(());
+        let loud = emitter(entity_id, 0.0, 10.0);
+        assert!(attenuate(&loud, 0.1) > attenuate(&loud, 0.9));
+        assert_eq!(attenuate(&loud, 1.0), 0.0);
+
+        let mut quiet = loud.clone();
+        quiet.volume = -2000;
+        assert!((attenuate(&quiet, 0.0) - AMBIENT_BASE_VOLUME * 0.1).abs() < 0.0001);
+    }
 }
@@ -1,11 +1,20 @@
 use std::rc::Rc;
 
-use cgmath::{Deg, Matrix4, Point2, Vector2, vec2, vec3};
+use cgmath::{Deg, Matrix4, Point2, Vector2, Vector3, vec2, vec3};
 use dark::importers::{FONT_IMPORTER, TEXTURE_IMPORTER};
-use engine::{assets::asset_cache::AssetCache, scene::SceneObject, texture::TextureTrait};
+use engine::{
+    assets::{asset_cache::AssetCache, placeholders},
+    scene::{Geometry, Mesh, SceneObject, VertexPositionTextureNormal, mesh},
+    texture::TextureTrait,
+};
 use shipyard::EntityId;
 
-use crate::{inventory::Inventory, vr_config::Handedness};
+use crate::{
+    gui::GuiTheme,
+    hud::{InterfaceArt, load_interface_art},
+    inventory::Inventory,
+    vr_config::Handedness,
+};
 
 #[derive(Clone, Debug)]
 pub enum ButtonHoverBehavior {
@@ -43,6 +52,19 @@ where
     Inventory {
         inventory: Inventory,
     },
+    /// One of the theme's nine-slice frames, by name
+    Frame {
+        position: Vector2<f32>,
+        size: Vector2<f32>,
+        frame: String,
+        alpha: f32,
+    },
+    /// A block of one of the theme's colors, by name
+    Fill {
+        position: Vector2<f32>,
+        size: Vector2<f32>,
+        color: String,
+    },
 }
 
 impl<TEvent> GuiComponent<TEvent>
@@ -93,6 +115,19 @@ where
                 position: new_position,
                 alpha,
             },
+            Self::Frame {
+                size, frame, alpha, ..
+            } => Self::Frame {
+                position: new_position,
+                size,
+                frame,
+                alpha,
+            },
+            Self::Fill { size, color, .. } => Self::Fill {
+                position: new_position,
+                size,
+                color,
+            },
         }
     }
 
@@ -140,6 +175,24 @@ where
                 text,
                 alpha,
             },
+            Self::Frame {
+                position,
+                frame,
+                alpha,
+                ..
+            } => Self::Frame {
+                position,
+                size: new_size,
+                frame,
+                alpha,
+            },
+            Self::Fill {
+                position, color, ..
+            } => Self::Fill {
+                position,
+                size: new_size,
+                color,
+            },
         }
     }
 
@@ -187,6 +240,7 @@ where
                 alpha,
             },
             Self::Inventory { inventory } => Self::Inventory { inventory },
+            Self::Frame { .. } | Self::Fill { .. } => self,
         }
     }
 
@@ -235,6 +289,18 @@ where
                 text,
                 alpha,
             },
+            Self::Frame {
+                position,
+                size,
+                frame,
+                ..
+            } => Self::Frame {
+                position,
+                size,
+                frame,
+                alpha,
+            },
+            Self::Fill { .. } => self,
         }
     }
 
@@ -284,6 +350,7 @@ where
                 text,
                 alpha,
             },
+            Self::Frame { .. } | Self::Fill { .. } => self,
         }
     }
 
@@ -318,7 +385,7 @@ where
                 on_grab,
                 hover,
             },
-            Self::Text { .. } => self,
+            Self::Text { .. } | Self::Frame { .. } | Self::Fill { .. } => self,
         }
     }
 }
@@ -366,6 +433,26 @@ pub fn text<TMsg: Clone>(text: &str) -> GuiComponent<TMsg> {
     }
 }
 
+// Frames and fills are for screens built on the theme, rather than the original art
+#[allow(dead_code)]
+pub fn frame<TMsg: Clone>(frame: &str) -> GuiComponent<TMsg> {
+    GuiComponent::Frame {
+        position: vec2(0.0, 0.0),
+        size: vec2(30.0, 30.0),
+        frame: frame.to_owned(),
+        alpha: 1.0,
+    }
+}
+
+#[allow(dead_code)]
+pub fn fill<TMsg: Clone>(color: &str) -> GuiComponent<TMsg> {
+    GuiComponent::Fill {
+        position: vec2(0.0, 0.0),
+        size: vec2(30.0, 30.0),
+        color: color.to_owned(),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum GuiComponentRenderInfo {
     Image {
@@ -381,6 +468,21 @@ pub enum GuiComponentRenderInfo {
         text: String,
         alpha: f32,
     },
+    Frame {
+        position: Vector2<f32>,
+        size: Vector2<f32>,
+        texture: String,
+        // Fraction of the texture, on each side, that's the frame's edge
+        slice: f32,
+        // How thick the edge is drawn
+        border: Vector2<f32>,
+        alpha: f32,
+    },
+    Fill {
+        position: Vector2<f32>,
+        size: Vector2<f32>,
+        color: Vector3<f32>,
+    },
 }
 
 impl GuiComponentRenderInfo {
@@ -388,6 +490,8 @@ impl GuiComponentRenderInfo {
         match self {
             Self::Image { position, .. } => *position,
             Self::Text { position, .. } => *position,
+            Self::Frame { position, .. } => *position,
+            Self::Fill { position, .. } => *position,
         }
     }
 
@@ -395,6 +499,8 @@ impl GuiComponentRenderInfo {
         match self {
             Self::Image { size, .. } => *size,
             Self::Text { size, .. } => *size,
+            Self::Frame { size, .. } => *size,
+            Self::Fill { size, .. } => *size,
         }
     }
 
@@ -406,11 +512,19 @@ impl GuiComponentRenderInfo {
                 texture,
                 alpha,
             } => {
-                let texture: Rc<dyn TextureTrait> =
-                    asset_cache.get(&TEXTURE_IMPORTER, texture).clone();
+                // Interface art comes from the atlas where it's packed there
+                let (texture, geometry): (Rc<dyn TextureTrait>, Box<dyn Geometry>) =
+                    match load_interface_art(asset_cache, texture) {
+                        Some(art) => (art.texture.clone(), art.geometry()),
+                        None => (
+                            asset_cache
+                                .get_or_warn(&TEXTURE_IMPORTER, texture)
+                                .unwrap_or_else(placeholders::texture),
+                            Box::new(engine::scene::quad::create()),
+                        ),
+                    };
                 let comp_mat = engine::scene::basic_material::create(texture, 1.0, 1.0 - alpha);
-                let mut comp_obj =
-                    SceneObject::new(comp_mat, Box::new(engine::scene::quad::create()));
+                let mut comp_obj = SceneObject::new(comp_mat, geometry);
                 comp_obj.set_local_transform(
                     Matrix4::from_angle_z(Deg(180.0))
                         * Matrix4::from_translation(vec3(
@@ -429,7 +543,7 @@ impl GuiComponentRenderInfo {
                 font,
                 alpha,
             } => {
-                let font = asset_cache.get(&FONT_IMPORTER, font).clone();
+                let font = asset_cache.get(&FONT_IMPORTER, font).unwrap();
 
                 let mut text =
                     SceneObject::world_space_text(text, font, (1.0 - alpha).max(0.0).min(1.0));
@@ -439,12 +553,120 @@ impl GuiComponentRenderInfo {
                 );
                 text
             }
+            Self::Frame {
+                position,
+                size,
+                texture,
+                slice,
+                border,
+                alpha,
+            } => {
+                let (texture, uv_min, uv_max): (Rc<dyn TextureTrait>, _, _) =
+                    match load_interface_art(asset_cache, texture) {
+                        Some(InterfaceArt {
+                            texture,
+                            region: Some(region),
+                        }) => {
+                            let (min_x, min_y) = region.uv_min();
+                            let (max_x, max_y) = region.uv_max();
+                            (texture, vec2(min_x, min_y), vec2(max_x, max_y))
+                        }
+                        Some(art) => (art.texture, vec2(0.0, 0.0), vec2(1.0, 1.0)),
+                        None => (
+                            asset_cache
+                                .get_or_warn(&TEXTURE_IMPORTER, texture)
+                                .unwrap_or_else(placeholders::texture),
+                            vec2(0.0, 0.0),
+                            vec2(1.0, 1.0),
+                        ),
+                    };
+                let mesh = nine_slice_mesh(*position, *size, *border, uv_min, uv_max, *slice);
+                let comp_mat = engine::scene::basic_material::create(texture, 1.0, 1.0 - alpha);
+                let mut comp_obj = SceneObject::new(comp_mat, Box::new(mesh));
+                comp_obj.set_local_transform(Matrix4::from_angle_z(Deg(180.0)));
+                comp_obj
+            }
+            Self::Fill {
+                position,
+                size,
+                color,
+            } => {
+                let comp_mat = engine::scene::color_material::create(*color);
+                let mut comp_obj =
+                    SceneObject::new(comp_mat, Box::new(engine::scene::quad::create()));
+                comp_obj.set_local_transform(
+                    Matrix4::from_angle_z(Deg(180.0))
+                        * Matrix4::from_translation(vec3(
+                            position.x - 0.5 + size.x / 2.0,
+                            position.y - 0.5 + size.y / 2.0,
+                            0.0,
+                        ))
+                        * Matrix4::from_nonuniform_scale(size.x, size.y, 1.0),
+                );
+                comp_obj
+            }
         };
 
         scene_object
     }
 }
 
+///
+/// nine_slice_mesh
+///
+/// Nine quads covering the same area as an image would - the corners keep the border's size,
+/// the edges stretch along their length, and the middle stretches both ways
+fn nine_slice_mesh(
+    position: Vector2<f32>,
+    size: Vector2<f32>,
+    border: Vector2<f32>,
+    uv_min: Vector2<f32>,
+    uv_max: Vector2<f32>,
+    slice: f32,
+) -> Mesh {
+    let border = vec2(border.x.min(size.x / 2.0), border.y.min(size.y / 2.0));
+    let (x0, y0) = (position.x - 0.5, position.y - 0.5);
+    let (x1, y1) = (x0 + size.x, y0 + size.y);
+    let xs = [x0, x0 + border.x, x1 - border.x, x1];
+    let ys = [y0, y0 + border.y, y1 - border.y, y1];
+
+    let uv_slice = (uv_max - uv_min) * slice.clamp(0.0, 0.5);
+    let us = [
+        uv_min.x,
+        uv_min.x + uv_slice.x,
+        uv_max.x - uv_slice.x,
+        uv_max.x,
+    ];
+    let vs = [
+        uv_min.y,
+        uv_min.y + uv_slice.y,
+        uv_max.y - uv_slice.y,
+        uv_max.y,
+    ];
+
+    let normal = vec3(0.0, 0.0, 1.0);
+    let vertex = |column: usize, row: usize| VertexPositionTextureNormal {
+        position: vec3(xs[column], ys[row], 0.0),
+        uv: vec2(us[column], vs[row]),
+        normal,
+    };
+
+    let mut vertices = Vec::with_capacity(9 * 6);
+    for row in 0..3 {
+        for column in 0..3 {
+            vertices.extend([
+                vertex(column, row),
+                vertex(column, row + 1),
+                vertex(column + 1, row + 1),
+                vertex(column + 1, row),
+                vertex(column + 1, row + 1),
+                vertex(column, row),
+            ]);
+        }
+    }
+    mesh::create(vertices)
+}
+
 #[derive(Clone, Debug)]
 pub struct GuiInputInfo {
     #[allow(dead_code)]
@@ -470,6 +692,7 @@ where
         &self,
         screen_size: Vector2<f32>,
         screen_space_cursor: Point2<f32>,
+        theme: &GuiTheme,
     ) -> GuiComponentRenderInfo {
         match self {
             GuiComponent::Inventory { inventory: _ } => todo!("not implemented"),
@@ -482,7 +705,7 @@ where
             } => GuiComponentRenderInfo::Text {
                 position: vec2(position.x / screen_size.x, (-position.y) / screen_size.y),
                 size: vec2(size.x / screen_size.x, size.y / screen_size.y),
-                font: font.clone(),
+                font: theme.font(font).to_owned(),
                 text: text.clone(),
                 alpha: *alpha,
             },
@@ -494,9 +717,47 @@ where
             } => GuiComponentRenderInfo::Image {
                 position: vec2(position.x / screen_size.x, position.y / screen_size.y),
                 size: vec2(size.x / screen_size.x, size.y / screen_size.y),
-                texture: texture.clone(),
+                texture: theme.texture(texture).to_owned(),
                 alpha: *alpha,
             },
+            GuiComponent::Frame {
+                position,
+                size,
+                frame,
+                alpha,
+            } => {
+                let position = vec2(position.x / screen_size.x, position.y / screen_size.y);
+                let size = vec2(size.x / screen_size.x, size.y / screen_size.y);
+                match theme.frame(frame) {
+                    Some(nine_slice) => GuiComponentRenderInfo::Frame {
+                        position,
+                        size,
+                        texture: theme.texture(&nine_slice.texture).to_owned(),
+                        slice: nine_slice.slice,
+                        border: vec2(
+                            nine_slice.border / screen_size.x,
+                            nine_slice.border / screen_size.y,
+                        ),
+                        alpha: *alpha,
+                    },
+                    // Not one of the theme's frames - stretch it as a plain image instead
+                    None => GuiComponentRenderInfo::Image {
+                        position,
+                        size,
+                        texture: theme.texture(frame).to_owned(),
+                        alpha: *alpha,
+                    },
+                }
+            }
+            GuiComponent::Fill {
+                position,
+                size,
+                color,
+            } => GuiComponentRenderInfo::Fill {
+                position: vec2(position.x / screen_size.x, position.y / screen_size.y),
+                size: vec2(size.x / screen_size.x, size.y / screen_size.y),
+                color: theme.color(color),
+            },
             GuiComponent::Button {
                 position,
                 size,
@@ -511,18 +772,18 @@ where
                 let size = vec2(size.x / screen_size.x, size.y / screen_size.y);
 
                 let texture = if !is_hovered {
-                    texture.clone()
+                    texture
                 } else {
                     match hover {
-                        ButtonHoverBehavior::None => texture.clone(),
-                        ButtonHoverBehavior::Texture(hover_texture) => hover_texture.clone(),
+                        ButtonHoverBehavior::None => texture,
+                        ButtonHoverBehavior::Texture(hover_texture) => hover_texture,
                     }
                 };
 
                 GuiComponentRenderInfo::Image {
                     position,
                     size,
-                    texture,
+                    texture: theme.texture(texture).to_owned(),
                     alpha: *alpha,
                 }
             }
@@ -538,6 +799,7 @@ where
             GuiComponent::Inventory { inventory: _ } => todo!("not implemented"),
             GuiComponent::Text { .. } => None,
             GuiComponent::Image { .. } => None,
+            GuiComponent::Frame { .. } | GuiComponent::Fill { .. } => None,
             GuiComponent::Button {
                 on_click,
                 on_grab,
@@ -16,7 +16,6 @@ use crate::{
 use crate::gui::*;
 
 pub struct GuiInstanceInfo {
-    #[allow(dead_code)]
     pub parent_entity: EntityId,
     pub proxy_entity: EntityId,
     #[allow(dead_code)]
@@ -105,6 +104,38 @@ impl GuiManager {
 
     pub fn update(&mut self) {}
 
+    ///
+    /// remove_entity
+    ///
+    /// Remove the GUIs attached to a destroyed entity, along with their proxy entities
+    pub fn remove_entity(
+        &mut self,
+        entity_id: EntityId,
+        world: &mut World,
+        physics: &mut PhysicsWorld,
+        scripts: &mut ScriptWorld,
+        id_to_physics: &mut HashMap<EntityId, RigidBodyHandle>,
+    ) {
+        self.entity_id_to_proxy_entity_id.remove(&entity_id);
+
+        let mut proxy_entities = Vec::new();
+        self.handle_to_instance.retain(|_, instance| {
+            if instance.parent_entity == entity_id {
+                proxy_entities.push(instance.proxy_entity);
+                false
+            } else {
+                true
+            }
+        });
+
+        for proxy_entity in proxy_entities {
+            physics.remove(proxy_entity);
+            id_to_physics.remove(&proxy_entity);
+            scripts.remove_entity(proxy_entity);
+            world.delete_entity(proxy_entity);
+        }
+    }
+
     pub fn render(&mut self, asset_cache: &mut AssetCache, world: &World) -> Vec<SceneObject> {
         let mut ret = Vec::new();
         let v_transform = world.borrow::<View<RuntimePropTransform>>().unwrap();
@@ -2,13 +2,13 @@ use cgmath::{Point2, point2, vec2};
 use shipyard::{EntityId, World};
 
 use crate::{
-    gui::{Gui, GuiComponent, GuiHandle, GuiInputInfo},
+    gui::{Gui, GuiComponent, GuiComponentRenderInfo, GuiHandle, GuiInputInfo},
     physics::PhysicsWorld,
     scripts::{Effect, MessagePayload, Script},
     time::Time,
 };
 
-use super::{GUI_PIXEL_TO_WORLD_SIZE, GuiCursor};
+use super::{GUI_PIXEL_TO_WORLD_SIZE, GuiCursor, with_theme};
 
 pub struct GuiScript<TState, TMsg>
 where
@@ -70,10 +70,12 @@ where
             size,
             texture: "cursor.pcx".to_owned(),
         });
-        let render_components = components
-            .into_iter()
-            .map(|c| c.to_render_info(config.screen_size_in_pixels, self.cursor))
-            .collect();
+        let render_components: Vec<GuiComponentRenderInfo> = with_theme(world, |theme| {
+            components
+                .into_iter()
+                .map(|c| c.to_render_info(config.screen_size_in_pixels, self.cursor, theme))
+                .collect()
+        });
 
         Effect::SetUI {
             parent_entity: entity_id,
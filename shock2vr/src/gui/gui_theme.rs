@@ -0,0 +1,174 @@
+///
+/// gui_theme.rs
+///
+/// The look shared by the in-world GUIs (keypads, replicators, containers...), so they stay
+/// consistent and can be reskinned together. A theme can:
+/// - replace the original interface art and fonts, ie `"key0.pcx": "hd/button.png"`
+/// - name colors, for `fill` components
+/// - name nine-slice frames, for `frame` components - the corners keep their size, and the
+///   edges and middle stretch to fit
+///
+/// It's loaded from `gui_theme.json` in the bundle, so a mod can override it - anything the
+/// file leaves out keeps the default skin, which is the original game's look:
+///
+/// ```json
+/// {
+///   "fonts": { "mainfont.fon": "textfont.fon" },
+///   "textures": { "cursor.pcx": "hd/cursor.png" },
+///   "colors": { "panel": [0.03, 0.08, 0.07] },
+///   "frames": { "panel": { "texture": "invback.pcx", "slice": 0.08, "border": 12.0 } }
+/// }
+/// ```
+///
+use std::{collections::HashMap, io::Read};
+
+use cgmath::{Vector3, vec3};
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use shipyard::{Unique, UniqueView, World};
+use tracing::warn;
+
+const THEME_FILE: &str = "gui_theme.json";
+
+///
+/// NineSlice
+///
+/// A frame cut from a texture - `slice` is how much of the texture (0 - 0.5, on each side) is
+/// the frame's edge, and `border` is how thick the edge is drawn, in GUI pixels
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct NineSlice {
+    pub texture: String,
+    pub slice: f32,
+    pub border: f32,
+}
+
+///
+/// GuiTheme
+///
+/// Replacement fonts and textures, and the named colors and frames GUIs can ask for
+#[derive(Unique, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct GuiTheme {
+    pub fonts: HashMap<String, String>,
+    pub textures: HashMap<String, String>,
+    pub colors: HashMap<String, [f32; 3]>,
+    pub frames: HashMap<String, NineSlice>,
+}
+
+impl Default for GuiTheme {
+    ///
+    /// default
+    ///
+    /// The original game's skin - its own art and fonts, the dark teal panels and green
+    /// highlights of the ship's terminals, and the inventory's frame
+    fn default() -> Self {
+        GuiTheme {
+            fonts: HashMap::new(),
+            textures: HashMap::new(),
+            colors: HashMap::from([
+                ("panel".to_owned(), [0.03, 0.08, 0.07]),
+                ("highlight".to_owned(), [0.25, 0.85, 0.45]),
+                ("warning".to_owned(), [0.85, 0.2, 0.1]),
+            ]),
+            frames: HashMap::from([(
+                "panel".to_owned(),
+                NineSlice {
+                    texture: "invback.pcx".to_owned(),
+                    slice: 0.08,
+                    border: 12.0,
+                },
+            )]),
+        }
+    }
+}
+
+impl GuiTheme {
+    pub fn load(asset_cache: &mut AssetCache) -> GuiTheme {
+        asset_cache
+            .get_opt(&THEME_IMPORTER, THEME_FILE)
+            .map(|theme| theme.as_ref().clone())
+            .unwrap_or_default()
+    }
+
+    pub fn font<'a>(&'a self, font: &'a str) -> &'a str {
+        self.fonts.get(font).map(String::as_str).unwrap_or(font)
+    }
+
+    pub fn texture<'a>(&'a self, texture: &'a str) -> &'a str {
+        self.textures
+            .get(texture)
+            .map(String::as_str)
+            .unwrap_or(texture)
+    }
+
+    /// A named color - white if the theme doesn't have it
+    pub fn color(&self, name: &str) -> Vector3<f32> {
+        self.colors
+            .get(name)
+            .map(|[r, g, b]| vec3(*r, *g, *b))
+            .unwrap_or(vec3(1.0, 1.0, 1.0))
+    }
+
+    pub fn frame(&self, name: &str) -> Option<&NineSlice> {
+        self.frames.get(name)
+    }
+}
+
+///
+/// with_theme
+///
+/// Run `f` with the mission's theme - or the default skin, outside of a mission
+pub fn with_theme<R>(world: &World, f: impl FnOnce(&GuiTheme) -> R) -> R {
+    match world.borrow::<UniqueView<GuiTheme>>() {
+        Ok(theme) => f(&theme),
+        Err(_) => f(&GuiTheme::default()),
+    }
+}
+
+pub fn parse_theme(json: &str) -> Result<GuiTheme, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+fn import_theme(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> GuiTheme {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read GUI theme {}: {}", name, err);
+        return GuiTheme::default();
+    }
+
+    parse_theme(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse GUI theme {}: {}", name, err);
+        GuiTheme::default()
+    })
+}
+
+fn process_theme(content: GuiTheme, _asset_cache: &mut AssetCache, _config: &()) -> GuiTheme {
+    content
+}
+
+static THEME_IMPORTER: Lazy<AssetImporter<GuiTheme, GuiTheme, ()>> =
+    Lazy::new(|| AssetImporter::define(import_theme, process_theme));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_theme_keeps_default_skin() {
+        let theme = parse_theme(r#"{ "textures": { "key0.pcx": "hd/button.png" } }"#).unwrap();
+        assert_eq!(theme.texture("key0.pcx"), "hd/button.png");
+        assert_eq!(theme.texture("cursor.pcx"), "cursor.pcx");
+        assert_eq!(theme.font("mainfont.fon"), "mainfont.fon");
+
+        // Left out, so the colors and frames are the original game's
+        assert_eq!(theme.colors, GuiTheme::default().colors);
+        assert_eq!(theme.frame("panel"), GuiTheme::default().frame("panel"));
+        assert_eq!(theme.color("missing"), vec3(1.0, 1.0, 1.0));
+    }
+}
@@ -6,10 +6,12 @@ use shipyard::{EntityId, World};
 mod gui_component;
 mod gui_manager;
 mod gui_script;
+mod gui_theme;
 mod proxy_gui_script;
 pub use gui_component::*;
 pub use gui_manager::*;
 pub use gui_script::*;
+pub use gui_theme::*;
 pub use proxy_gui_script::*;
 
 static NEXT_HANDLE_ID: AtomicU64 = AtomicU64::new(0);
@@ -0,0 +1,218 @@
+///
+/// hazards.rs
+///
+/// Environmental hazards - electrified water, steam venting from broken pipes, radiation and
+/// toxic areas. Each hurts whoever's inside every so often, and may leave the player with a
+/// status effect (see `player_status`). The player can shrug off some of it, through
+/// `HazardResistances`.
+///
+/// Hazards are level entities with the `HazardVolume` script, configured from their design note
+/// (see `HazardConfig::parse`), and rooms with the original `RadRoom` script.
+///
+use shipyard::Unique;
+use tracing::warn;
+
+use crate::player_status::StatusEffectKind;
+
+// Seconds between damage ticks, when the design note doesn't say
+const DEFAULT_INTERVAL: f32 = 1.0;
+
+// Hazards can't tick faster than this, so a typo doesn't shred the player
+const MIN_INTERVAL: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HazardKind {
+    Electric,
+    Steam,
+    Radiation,
+    Toxin,
+}
+
+impl HazardKind {
+    pub const ALL: [HazardKind; 4] = [
+        HazardKind::Electric,
+        HazardKind::Steam,
+        HazardKind::Radiation,
+        HazardKind::Toxin,
+    ];
+
+    /// Parse the name used in design notes, ie 'steam'
+    pub fn from_name(name: &str) -> Option<HazardKind> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "electric" | "electricity" => Some(HazardKind::Electric),
+            "steam" => Some(HazardKind::Steam),
+            "radiation" => Some(HazardKind::Radiation),
+            "toxin" | "toxic" => Some(HazardKind::Toxin),
+            _ => None,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            HazardKind::Electric => 0,
+            HazardKind::Steam => 1,
+            HazardKind::Radiation => 2,
+            HazardKind::Toxin => 3,
+        }
+    }
+
+    /// Hit points taken each tick, when the design note doesn't say
+    fn default_damage(&self) -> f32 {
+        match self {
+            HazardKind::Electric => 4.0,
+            HazardKind::Steam => 3.0,
+            HazardKind::Radiation => 1.0,
+            HazardKind::Toxin => 1.0,
+        }
+    }
+
+    ///
+    /// status_effect
+    ///
+    /// The status effect each tick leaves the player with, and for how long - electricity
+    /// scrambles the HUD, and radiation and toxins keep hurting for a while after leaving
+    pub fn status_effect(&self) -> Option<(StatusEffectKind, f32)> {
+        match self {
+            HazardKind::Electric => Some((StatusEffectKind::HudDisrupted, 2.0)),
+            HazardKind::Steam => None,
+            HazardKind::Radiation => Some((StatusEffectKind::Radiation, 5.0)),
+            HazardKind::Toxin => Some((StatusEffectKind::Toxin, 5.0)),
+        }
+    }
+}
+
+///
+/// HazardConfig
+///
+/// How a hazard volume behaves
+#[derive(Clone, Debug, PartialEq)]
+pub struct HazardConfig {
+    pub kind: HazardKind,
+    /// Hit points taken each tick
+    pub damage: f32,
+    /// Seconds between ticks
+    pub interval: f32,
+    /// For hazards without a volume of their own (ie, a steam emitter) - anything within this
+    /// distance of the entity is inside
+    pub radius: Option<f32>,
+    /// Whether the hazard starts switched on - TurnOn / TurnOff messages switch it
+    pub starts_on: bool,
+}
+
+impl HazardConfig {
+    pub fn new(kind: HazardKind) -> HazardConfig {
+        HazardConfig {
+            kind,
+            damage: kind.default_damage(),
+            interval: DEFAULT_INTERVAL,
+            radius: None,
+            starts_on: true,
+        }
+    }
+
+    ///
+    /// parse
+    ///
+    /// Read a hazard from a design note - `name=value`, separated by `;` or commas, ie
+    /// `hazard=steam; damage=2; interval=0.5; radius=4; on=false`. Only `hazard` is required.
+    pub fn parse(design_note: &str) -> Option<HazardConfig> {
+        let settings: Vec<(&str, &str)> = design_note
+            .split([';', ','])
+            .filter_map(|setting| setting.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .collect();
+
+        let kind = settings
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("hazard"))
+            .and_then(|(_, value)| HazardKind::from_name(value))?;
+
+        let mut config = HazardConfig::new(kind);
+        for (name, value) in settings {
+            match name.to_ascii_lowercase().as_str() {
+                "hazard" => (),
+                "damage" => parse_into(name, value, |damage| config.damage = damage.max(0.0)),
+                "interval" => parse_into(name, value, |interval| {
+                    config.interval = interval.max(MIN_INTERVAL)
+                }),
+                "radius" => parse_into(name, value, |radius| config.radius = Some(radius)),
+                "on" => config.starts_on = !value.eq_ignore_ascii_case("false") && value != "0",
+                _ => warn!("Unknown hazard setting: {}", name),
+            }
+        }
+        Some(config)
+    }
+}
+
+fn parse_into(name: &str, value: &str, mut apply: impl FnMut(f32)) {
+    match value.parse::<f32>() {
+        Ok(number) => apply(number),
+        Err(_) => warn!(
+            "Hazard setting {} should be a number, found '{}'",
+            name, value
+        ),
+    }
+}
+
+///
+/// HazardResistances
+///
+/// How much of each kind of hazard the player shrugs off, from 0.0 (none) to 1.0 (immune) -
+/// lowering both the damage and how long status effects last
+#[derive(Unique, Clone, Debug, Default, PartialEq)]
+pub struct HazardResistances {
+    resistances: [f32; 4],
+}
+
+impl HazardResistances {
+    pub fn new() -> HazardResistances {
+        HazardResistances::default()
+    }
+
+    pub fn get(&self, kind: HazardKind) -> f32 {
+        self.resistances[kind.index()]
+    }
+
+    pub fn set(&mut self, kind: HazardKind, resistance: f32) {
+        self.resistances[kind.index()] = resistance.clamp(0.0, 1.0);
+    }
+
+    /// What's left of `amount` (damage, or a status effect's duration) after the resistance
+    pub fn reduce(&self, kind: HazardKind, amount: f32) -> f32 {
+        amount * (1.0 - self.get(kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_design_note() {
+        let config =
+            HazardConfig::parse("hazard=Steam; damage=2, interval=0.01; radius=4; on=false")
+                .unwrap();
+        assert_eq!(config.kind, HazardKind::Steam);
+        assert_eq!(config.damage, 2.0);
+        assert_eq!(config.interval, MIN_INTERVAL);
+        assert_eq!(config.radius, Some(4.0));
+        assert!(!config.starts_on);
+
+        let defaults = HazardConfig::parse("hazard=radiation; damage=lots").unwrap();
+        assert_eq!(defaults, HazardConfig::new(HazardKind::Radiation));
+
+        assert_eq!(HazardConfig::parse("damage=2"), None);
+        assert_eq!(HazardConfig::parse("hazard=lava"), None);
+    }
+
+    #[test]
+    fn test_resistances() {
+        let mut resistances = HazardResistances::new();
+        resistances.set(HazardKind::Electric, 0.75);
+        resistances.set(HazardKind::Steam, 2.0);
+
+        assert_eq!(resistances.reduce(HazardKind::Electric, 4.0), 1.0);
+        assert_eq!(resistances.reduce(HazardKind::Steam, 4.0), 0.0);
+        assert_eq!(resistances.reduce(HazardKind::Radiation, 4.0), 4.0);
+    }
+}
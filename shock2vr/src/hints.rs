@@ -0,0 +1,363 @@
+///
+/// hints.rs
+///
+/// Tutorial prompts - a hint for each of the VR controls (grabbing, hacking, using a psi amp...),
+/// shown the first time the player runs into them. Once a hint has been shown it's remembered in
+/// the player's profile, so it doesn't come up again in later games.
+///
+/// Hints are data-driven, from `hints.json` in the bundle:
+///
+/// ```json
+/// [
+///   { "id": "grab", "text": "hint-grab", "trigger": { "holding": { "item": "" } } },
+///   { "id": "hack", "text": "hint-hack",
+///     "trigger": { "near": { "entity": "keypad", "distance": 6.0 } } },
+///   { "id": "psi", "text": "hint-psi", "trigger": { "holding": { "item": "psi amp" } } },
+///   { "id": "lockdown", "text": "hint-lockdown",
+///     "trigger": { "condition": { "expression": "qb('note_lockdown') == 2" } } }
+/// ]
+/// ```
+///
+use std::{collections::BTreeSet, fs, io::Read, path::Path};
+
+use cgmath::InnerSpace;
+use dark::{
+    SCALE_FACTOR,
+    properties::{PropObjName, PropObjShortName, PropPosition, PropSymName},
+};
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use shipyard::{EntityId, Get, IntoIter, IntoWithId, UniqueView, View, World};
+use tracing::warn;
+
+use crate::{PlayerInfo, conditions::Condition, localization, quest_info::QuestInfo};
+
+const HINTS_FILE: &str = "hints.json";
+
+// How long a hint stays on screen, in seconds
+const HINT_DURATION: f32 = 6.0;
+
+// Seconds between checking the triggers - `near` looks through every entity
+const CHECK_INTERVAL: f32 = 0.25;
+
+fn default_distance() -> f32 {
+    5.0
+}
+
+///
+/// HintTrigger
+///
+/// When a hint is shown - the player comes within `distance` (in SS2 units) of an entity, or
+/// holds an item, whose name contains the given text (empty matches anything), or a condition
+/// over quest bits and variables (see `conditions`) holds
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HintTrigger {
+    Near {
+        entity: String,
+        #[serde(default = "default_distance")]
+        distance: f32,
+    },
+    Holding {
+        item: String,
+    },
+    Condition {
+        expression: String,
+    },
+}
+
+///
+/// Hint
+///
+/// A single hint - `text` is an id in `ui.ftl`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub id: String,
+    pub text: String,
+    pub trigger: HintTrigger,
+}
+
+pub fn parse_hints(json: &str) -> Result<Vec<Hint>, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+fn import_hints(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> Vec<Hint> {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read hints {}: {}", name, err);
+        return vec![];
+    }
+
+    parse_hints(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse hints {}: {}", name, err);
+        vec![]
+    })
+}
+
+fn process_hints(content: Vec<Hint>, _asset_cache: &mut AssetCache, _config: &()) -> Vec<Hint> {
+    content
+}
+
+static HINTS_IMPORTER: Lazy<AssetImporter<Vec<Hint>, Vec<Hint>, ()>> =
+    Lazy::new(|| AssetImporter::define(import_hints, process_hints));
+
+///
+/// SeenHints
+///
+/// Ids of the hints a profile has already been shown, persisted in the profile directory
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeenHints {
+    seen: BTreeSet<String>,
+}
+
+impl SeenHints {
+    pub fn load(path: &Path) -> SeenHints {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+                warn!("Unable to parse seen hints {:?}: {}", path, err);
+                SeenHints::default()
+            }),
+            Err(_) => SeenHints::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => {
+                if let Err(err) = fs::write(path, json) {
+                    warn!("Unable to write seen hints {:?}: {}", path, err);
+                }
+            }
+            Err(err) => warn!("Unable to serialize seen hints: {}", err),
+        }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.seen.contains(id)
+    }
+
+    pub fn insert(&mut self, id: &str) {
+        self.seen.insert(id.to_owned());
+    }
+}
+
+struct LoadedHint {
+    hint: Hint,
+    // Parsed up front, for `condition` triggers - None if it doesn't parse
+    condition: Option<Condition>,
+}
+
+struct ActiveHint {
+    lines: Vec<String>,
+    remaining: f32,
+}
+
+///
+/// HintSystem
+///
+/// Checks the triggers of the hints the player hasn't seen yet, and shows them one at a time -
+/// a hint that triggers while another is up waits until it's gone
+pub struct HintSystem {
+    hints: Vec<LoadedHint>,
+    seen: SeenHints,
+    active: Option<ActiveHint>,
+    check_timer: f32,
+}
+
+impl HintSystem {
+    pub fn load(asset_cache: &mut AssetCache, seen: SeenHints) -> HintSystem {
+        let hints = asset_cache
+            .get_opt(&HINTS_IMPORTER, HINTS_FILE)
+            .map(|hints| hints.as_ref().clone())
+            .unwrap_or_default();
+        HintSystem::new(hints, seen)
+    }
+
+    pub fn new(hints: Vec<Hint>, seen: SeenHints) -> HintSystem {
+        let hints = hints
+            .into_iter()
+            .map(|hint| {
+                let condition = match &hint.trigger {
+                    HintTrigger::Condition { expression } => match Condition::parse(expression) {
+                        Ok(condition) => Some(condition),
+                        Err(err) => {
+                            warn!("Unable to parse condition for hint {}: {}", hint.id, err);
+                            None
+                        }
+                    },
+                    _ => None,
+                };
+                LoadedHint { hint, condition }
+            })
+            .collect();
+
+        HintSystem {
+            hints,
+            seen,
+            active: None,
+            check_timer: 0.0,
+        }
+    }
+
+    pub fn seen(&self) -> &SeenHints {
+        &self.seen
+    }
+
+    /// Forget the hints that have been shown, so they come up again
+    pub fn reset(&mut self) {
+        self.seen = SeenHints::default();
+        self.active = None;
+    }
+
+    ///
+    /// update
+    ///
+    /// Show the next hint whose trigger is met - returns its id, so the caller can save the
+    /// profile's seen hints
+    pub fn update(&mut self, delta_time: f32, world: &World) -> Option<String> {
+        if let Some(active) = &mut self.active {
+            active.remaining -= delta_time;
+            if active.remaining > 0.0 {
+                return None;
+            }
+            self.active = None;
+        }
+
+        self.check_timer -= delta_time;
+        if self.check_timer > 0.0 {
+            return None;
+        }
+        self.check_timer = CHECK_INTERVAL;
+
+        let hint = self
+            .hints
+            .iter()
+            .filter(|loaded| !self.seen.contains(&loaded.hint.id))
+            .find(|loaded| is_triggered(loaded, world))
+            .map(|loaded| loaded.hint.clone())?;
+
+        self.seen.insert(&hint.id);
+        self.active = Some(ActiveHint {
+            lines: localization::text(&hint.text)
+                .lines()
+                .map(|line| line.to_owned())
+                .collect(),
+            remaining: HINT_DURATION,
+        });
+        Some(hint.id)
+    }
+
+    /// Lines of the hint on screen, if there is one
+    pub fn lines(&self) -> Vec<String> {
+        self.active
+            .as_ref()
+            .map(|active| active.lines.clone())
+            .unwrap_or_default()
+    }
+}
+
+fn is_triggered(loaded: &LoadedHint, world: &World) -> bool {
+    match &loaded.hint.trigger {
+        HintTrigger::Condition { .. } => {
+            let Some(condition) = &loaded.condition else {
+                return false;
+            };
+            world
+                .borrow::<UniqueView<QuestInfo>>()
+                .is_ok_and(|quest_info| condition.is_true(&quest_info))
+        }
+        HintTrigger::Holding { item } => {
+            let Ok(player) = world.borrow::<UniqueView<PlayerInfo>>() else {
+                return false;
+            };
+            [player.left_hand_entity_id, player.right_hand_entity_id]
+                .into_iter()
+                .flatten()
+                .any(|entity_id| name_matches(world, entity_id, item))
+        }
+        HintTrigger::Near { entity, distance } => {
+            let Ok(player) = world.borrow::<UniqueView<PlayerInfo>>() else {
+                return false;
+            };
+            let v_position = world.borrow::<View<PropPosition>>().unwrap();
+            let max_distance = distance / SCALE_FACTOR;
+            v_position
+                .iter()
+                .with_id()
+                .filter(|(id, _)| *id != player.entity_id)
+                .filter(|(_, position)| {
+                    (position.position - player.pos).magnitude() <= max_distance
+                })
+                .any(|(id, _)| name_matches(world, id, entity))
+        }
+    }
+}
+
+// Whether the entity's name (its symbolic name, or its object name) contains `filter`, ignoring case
+fn name_matches(world: &World, entity_id: EntityId, filter: &str) -> bool {
+    let filter = filter.to_ascii_lowercase();
+    world.run(
+        |v_symname: View<PropSymName>,
+         v_objname: View<PropObjName>,
+         v_objshortname: View<PropObjShortName>| {
+            [
+                v_symname.get(entity_id).map(|name| name.0.clone()).ok(),
+                v_objname.get(entity_id).map(|name| name.0.clone()).ok(),
+                v_objshortname
+                    .get(entity_id)
+                    .map(|name| name.0.clone())
+                    .ok(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|name| name.to_ascii_lowercase().contains(&filter))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::QuestBitValue;
+
+    use super::*;
+
+    const HINTS: &str = r#"[
+        { "id": "grab", "text": "hint-grab", "trigger": { "holding": { "item": "" } } },
+        { "id": "lockdown", "text": "hint-lockdown",
+          "trigger": { "condition": { "expression": "qb('note_lockdown') == 2" } } },
+        { "id": "broken", "text": "hint-broken",
+          "trigger": { "condition": { "expression": "qb(" } } }
+    ]"#;
+
+    #[test]
+    fn test_hints_show_once() {
+        let world = World::new();
+        world.add_unique(QuestInfo::new());
+        let mut system = HintSystem::new(parse_hints(HINTS).unwrap(), SeenHints::default());
+
+        // Nothing to hold outside of a mission, and the quest bit isn't set
+        assert_eq!(system.update(1.0, &world), None);
+
+        world
+            .borrow::<shipyard::UniqueViewMut<QuestInfo>>()
+            .unwrap()
+            .set_quest_bit_value("note_lockdown", QuestBitValue::COMPLETE);
+        assert_eq!(system.update(1.0, &world), Some("lockdown".to_owned()));
+        assert!(system.seen().contains("lockdown"));
+
+        // Already seen, so it doesn't show again - even for a new system with the same profile
+        assert_eq!(system.update(HINT_DURATION + 1.0, &world), None);
+        let mut reloaded = HintSystem::new(parse_hints(HINTS).unwrap(), system.seen().clone());
+        assert_eq!(reloaded.update(1.0, &world), None);
+
+        system.reset();
+        assert_eq!(system.update(1.0, &world), Some("lockdown".to_owned()));
+    }
+}
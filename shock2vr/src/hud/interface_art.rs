@@ -0,0 +1,94 @@
+use std::rc::Rc;
+
+use cgmath::{Matrix4, Vector2, Vector4, vec2, vec3};
+use dark::{
+    importers::{INTERFACE_ATLAS_IMPORTER, TEXTURE_IMPORTER},
+    interface_atlas::InterfaceRegion,
+};
+use engine::{
+    assets::asset_cache::AssetCache,
+    materials::ScreenSpaceMaterial,
+    scene::{Geometry, SceneObject, quad, render_order},
+    texture::{Texture, TextureOptions, TextureTrait},
+};
+
+// Bundle manifest listing the interface art to pack into the atlas
+const INTERFACE_ATLAS_FILE: &str = "interface_atlas.json";
+
+///
+/// InterfaceArt
+///
+/// An interface image, ready to draw - either a region of the interface atlas, or its own texture
+/// if it isn't in the atlas
+pub struct InterfaceArt {
+    pub texture: Rc<Texture>,
+    pub region: Option<InterfaceRegion>,
+}
+
+impl InterfaceArt {
+    /// A quad showing just this image - the whole texture, or its region of the atlas
+    pub fn geometry(&self) -> Box<dyn Geometry> {
+        match &self.region {
+            Some(region) => {
+                let (min_x, min_y) = region.uv_min();
+                let (max_x, max_y) = region.uv_max();
+                Box::new(quad::create_with_uvs(
+                    vec2(min_x, min_y),
+                    vec2(max_x, max_y),
+                ))
+            }
+            None => Box::new(quad::create()),
+        }
+    }
+}
+
+///
+/// load_interface_art
+///
+/// Look up interface art by name (ie, "cursor.pcx") - from the atlas when it's packed there,
+/// otherwise loaded on its own
+pub fn load_interface_art(asset_cache: &mut AssetCache, name: &str) -> Option<InterfaceArt> {
+    let from_atlas = asset_cache
+        .get_opt(&INTERFACE_ATLAS_IMPORTER, INTERFACE_ATLAS_FILE)
+        .and_then(|atlas| {
+            atlas.region(name).map(|region| InterfaceArt {
+                texture: atlas.texture(),
+                region: Some(region),
+            })
+        });
+    if from_atlas.is_some() {
+        return from_atlas;
+    }
+
+    let options = TextureOptions { wrap: false };
+    asset_cache
+        .get_ext_opt(&TEXTURE_IMPORTER, name, &options)
+        .map(|texture| InterfaceArt {
+            texture,
+            region: None,
+        })
+}
+
+///
+/// screen_space_art
+///
+/// A screen-space quad showing interface art, tinted by `color`
+pub fn screen_space_art(
+    asset_cache: &mut AssetCache,
+    name: &str,
+    position: Vector2<f32>,
+    size: Vector2<f32>,
+    color: Vector4<f32>,
+) -> Option<SceneObject> {
+    let art = load_interface_art(asset_cache, name)?;
+    let texture: Rc<dyn TextureTrait> = art.texture.clone();
+    let material = ScreenSpaceMaterial::create(texture, color);
+
+    let xform = Matrix4::from_translation(vec3(position.x, position.y, 0.0))
+        * Matrix4::from_nonuniform_scale(size.x, size.y, 1.0)
+        * Matrix4::from_translation(vec3(0.5, 0.5, 0.0));
+    let mut obj = SceneObject::new(material, art.geometry());
+    obj.set_local_transform(xform);
+    obj.set_render_order(render_order::SCREEN_SPACE);
+    Some(obj)
+}
@@ -1,13 +1,18 @@
 use cgmath::{Matrix4, Vector2, point2, vec2, vec3};
 use collision::{Aabb2, Aabb3};
 use dark::{
-    importers::{FONT_IMPORTER, TEXTURE_IMPORTER},
-    properties::{PropHitPoints, PropObjName, PropTemplateId},
+    importers::FONT_IMPORTER,
+    properties::{PropHitPoints, PropObjName},
+};
+use engine::{
+    assets::asset_cache::AssetCache,
+    scene::{SceneObject, render_order},
 };
-use engine::{assets::asset_cache::AssetCache, scene::SceneObject, texture::TextureOptions};
 use shipyard::{EntityId, Get, View, World};
 
-use crate::physics::PhysicsWorld;
+use crate::{accessibility::AccessibilitySettings, localization, physics::PhysicsWorld};
+
+use super::screen_space_art;
 
 pub fn draw_item_name(
     asset_cache: &mut AssetCache,
@@ -18,7 +23,7 @@ pub fn draw_item_name(
     view: Matrix4<f32>,
     projection: Matrix4<f32>,
     screen_size: Vector2<f32>,
-    debug_show_ids: bool,
+    accessibility: &AccessibilitySettings,
 ) -> Vec<SceneObject> {
     let maybe_bbox = physics.get_aabb2(entity_id);
 
@@ -28,8 +33,6 @@ pub fn draw_item_name(
 
     let v_prop_obj_short_name = world.borrow::<View<PropObjName>>().unwrap();
     let maybe_prop_obj_short_name = v_prop_obj_short_name.get(entity_id);
-    let v_prop_template_id = world.borrow::<View<PropTemplateId>>().unwrap();
-    let prop_template_id = v_prop_template_id.get(entity_id).unwrap();
 
     if maybe_prop_obj_short_name.is_err() {
         return vec![];
@@ -42,7 +45,7 @@ pub fn draw_item_name(
     }
 
     let aabb = maybe_bbox.unwrap();
-    let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon");
+    let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
     let extents = project_aabb3(&aabb, view, projection, screen_size);
 
     let v_prop_hitpoints = world.borrow::<View<PropHitPoints>>().unwrap();
@@ -51,30 +54,54 @@ pub fn draw_item_name(
         .map(|hp| hp.hit_points.to_string())
         .unwrap_or("?".to_string());
 
-    let text_content = if debug_show_ids {
-        format!(
-            "{} | {} (Tem {}| Ent {})",
-            prop_obj_short_name.0,
-            &maybe_hitpoints,
-            prop_template_id.template_id,
-            entity_id.inner(),
-        )
-    } else {
-        format!("{} | {}", prop_obj_short_name.0, &maybe_hitpoints,)
-    };
+    let obj_name = localization::localize_game_string("objname", &prop_obj_short_name.0);
+    let text_content = format!("{} | {}", obj_name, &maybe_hitpoints);
 
+    let font_size = accessibility.scaled_font_size(10.0);
     let text_obj_0_0 = SceneObject::screen_space_text(
         &text_content,
         font.clone(),
-        10.0,
+        font_size,
         0.5,
         extents.min.x,
-        extents.min.y - 10.0,
+        extents.min.y - font_size,
     );
 
     vec![text_obj_0_0]
 }
 
+///
+/// draw_interaction_prompt
+///
+/// What frobbing will do (Open, Hack, Pick up, Use), below the item
+pub fn draw_interaction_prompt(
+    asset_cache: &mut AssetCache,
+    physics: &PhysicsWorld,
+    entity_id: EntityId,
+    label: &str,
+    view: Matrix4<f32>,
+    projection: Matrix4<f32>,
+    screen_size: Vector2<f32>,
+    accessibility: &AccessibilitySettings,
+) -> Vec<SceneObject> {
+    let Some(aabb) = physics.get_aabb2(entity_id) else {
+        return vec![];
+    };
+
+    let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+    let extents = project_aabb3(&aabb, view, projection, screen_size);
+    let font_size = accessibility.scaled_font_size(10.0);
+
+    vec![SceneObject::screen_space_text(
+        label,
+        font,
+        font_size,
+        0.5,
+        extents.min.x,
+        extents.max.y + font_size,
+    )]
+}
+
 pub fn draw_item_outline(
     asset_cache: &mut AssetCache,
     physics: &PhysicsWorld,
@@ -83,6 +110,7 @@ pub fn draw_item_outline(
     view: Matrix4<f32>,
     projection: Matrix4<f32>,
     screen_size: Vector2<f32>,
+    accessibility: &AccessibilitySettings,
 ) -> Vec<SceneObject> {
     let maybe_bbox = physics.get_aabb2(entity_id);
 
@@ -90,33 +118,26 @@ pub fn draw_item_outline(
         return vec![];
     }
 
-    let options = TextureOptions { wrap: false };
-
     let aabb = maybe_bbox.unwrap();
-    let top_left_brack = asset_cache.get_ext(&TEXTURE_IMPORTER, "BRACK0.PCX", &options);
-    let top_right_brack = asset_cache.get_ext(&TEXTURE_IMPORTER, "BRACK1.PCX", &options);
-    let bottom_right_brack = asset_cache.get_ext(&TEXTURE_IMPORTER, "BRACK2.PCX", &options);
-    let bottom_left_brack = asset_cache.get_ext(&TEXTURE_IMPORTER, "BRACK3.PCX", &options);
-
     let size = vec2(8.0, 8.0);
+    let color = accessibility.color_palette.outline_color();
     let extents = project_aabb3(&aabb, view, projection, screen_size);
-    let top_left_brack_obj =
-        SceneObject::screen_space_quad(top_left_brack, vec2(extents.min.x, extents.min.y), size);
-    let top_right_brack_obj =
-        SceneObject::screen_space_quad(top_right_brack, vec2(extents.max.x, extents.min.y), size);
-    let bottom_left_brack_obj =
-        SceneObject::screen_space_quad(bottom_left_brack, vec2(extents.min.x, extents.max.y), size);
-    let bottom_right_brack_obj = SceneObject::screen_space_quad(
-        bottom_right_brack,
-        vec2(extents.max.x, extents.max.y),
-        size,
-    );
-    vec![
-        top_left_brack_obj,
-        bottom_left_brack_obj,
-        bottom_right_brack_obj,
-        top_right_brack_obj,
+
+    // Brackets at each corner - top left, bottom left, bottom right, top right
+    [
+        ("brack0.pcx", vec2(extents.min.x, extents.min.y)),
+        ("brack3.pcx", vec2(extents.min.x, extents.max.y)),
+        ("brack2.pcx", vec2(extents.max.x, extents.max.y)),
+        ("brack1.pcx", vec2(extents.max.x, extents.min.y)),
     ]
+    .into_iter()
+    .filter_map(|(art, position)| screen_space_art(asset_cache, art, position, size, color))
+    .map(|mut obj| {
+        // Under the rest of the HUD, like the item's name
+        obj.set_render_order(render_order::OVERLAY);
+        obj
+    })
+    .collect()
 }
 
 pub fn project_aabb3(
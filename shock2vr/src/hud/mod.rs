@@ -1,5 +1,14 @@
+mod interface_art;
+pub use interface_art::*;
+
 mod item_outline;
 pub use item_outline::*;
 
+mod reticle;
+pub use reticle::*;
+
 mod virtual_arms;
 pub use virtual_arms::*;
+
+mod subtitles;
+pub use subtitles::*;
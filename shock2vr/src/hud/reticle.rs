@@ -0,0 +1,274 @@
+///
+/// reticle.rs
+///
+/// A crosshair in the center of the screen, for flat-screen runtimes - in VR, the hands do the
+/// pointing. It's tinted by what's under it (something to interact with, something locked, an
+/// enemy), and can flash a hit marker when the player damages something.
+///
+use std::{f32::consts::PI, rc::Rc};
+
+use cgmath::{Matrix4, SquareMatrix, Vector2, Vector4, point3, vec2, vec3, vec4};
+use dark::properties::{PropAI, PropHitPoints};
+use engine::{
+    materials::ScreenSpaceMaterial,
+    scene::{SceneObject, quad, render_order},
+    texture::{Texture, TextureTrait},
+    texture_format::{PixelFormat, RawTextureData},
+};
+use shipyard::{EntityId, Get, View, World};
+
+use crate::{
+    interaction::InteractionVerb,
+    physics::{InternalCollisionGroups, PhysicsWorld},
+    scripts::internal_lock_script::is_blocked_by_lock,
+    util,
+};
+
+// Size of the crosshair arms, and their gap from the center, in pixels
+const ARM_LENGTH: f32 = 8.0;
+const ARM_THICKNESS: f32 = 2.0;
+const ARM_GAP: f32 = 4.0;
+
+// How long a hit marker shows, in seconds
+const HIT_MARKER_DURATION: f32 = 0.25;
+
+// Hit marker arms - diagonal, further out than the crosshair
+const HIT_MARKER_LENGTH: f32 = 10.0;
+const HIT_MARKER_OFFSET: f32 = 12.0;
+
+// How far the reticle looks for something to tint for
+const MAX_TARGET_DISTANCE: f32 = 100.0;
+
+///
+/// ReticleOptions
+///
+/// Whether to draw the reticle - flat-screen runtimes turn it on
+#[derive(Clone, Debug, Default)]
+pub struct ReticleOptions {
+    pub enabled: bool,
+    /// Flash a marker around the reticle when the player damages something
+    pub hit_markers: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReticleContext {
+    #[default]
+    Default,
+    /// Something that can be picked up or used
+    Interact,
+    /// Locked, without the key
+    Locked,
+    Enemy,
+}
+
+impl ReticleContext {
+    ///
+    /// for_view
+    ///
+    /// What's in the center of the view
+    pub fn for_view(world: &World, physics: &PhysicsWorld, view: Matrix4<f32>) -> ReticleContext {
+        let Some(eye_to_world) = view.invert() else {
+            return ReticleContext::Default;
+        };
+        let camera_position = eye_to_world.w.truncate();
+        let forward = (eye_to_world * vec4(0.0, 0.0, -1.0, 0.0)).truncate();
+
+        let maybe_hit = physics.ray_cast2(
+            point3(camera_position.x, camera_position.y, camera_position.z),
+            forward,
+            MAX_TARGET_DISTANCE,
+            InternalCollisionGroups::ENTITY
+                | InternalCollisionGroups::SELECTABLE
+                | InternalCollisionGroups::WORLD
+                | InternalCollisionGroups::HITBOX,
+            None,
+            true,
+        );
+        let Some(entity_id) = maybe_hit.and_then(|hit| hit.maybe_entity_id) else {
+            return ReticleContext::Default;
+        };
+
+        ReticleContext::for_entity(world, util::resolve_proxy_entity(world, entity_id))
+    }
+
+    fn for_entity(world: &World, entity_id: EntityId) -> ReticleContext {
+        let is_alive_ai = {
+            let v_ai = world.borrow::<View<PropAI>>().unwrap();
+            let v_hit_points = world.borrow::<View<PropHitPoints>>().unwrap();
+            v_ai.contains(entity_id)
+                && v_hit_points
+                    .get(entity_id)
+                    .map(|hit_points| hit_points.hit_points > 0)
+                    .unwrap_or(true)
+        };
+        if is_alive_ai {
+            return ReticleContext::Enemy;
+        }
+
+        match InteractionVerb::for_entity(world, entity_id) {
+            Some(_) if is_blocked_by_lock(world, entity_id) => ReticleContext::Locked,
+            Some(_) => ReticleContext::Interact,
+            None => ReticleContext::Default,
+        }
+    }
+
+    fn color(&self) -> Vector4<f32> {
+        match self {
+            ReticleContext::Default => vec4(1.0, 1.0, 1.0, 0.6),
+            ReticleContext::Interact => vec4(0.3, 1.0, 0.4, 0.9),
+            ReticleContext::Locked => vec4(1.0, 0.6, 0.1, 0.9),
+            ReticleContext::Enemy => vec4(1.0, 0.2, 0.2, 0.9),
+        }
+    }
+}
+
+///
+/// Reticle
+///
+/// Hit marker timing, and drawing the reticle
+pub struct Reticle {
+    hit_marker_remaining: f32,
+    hit_marker_was_kill: bool,
+    texture: Option<Rc<Texture>>,
+}
+
+impl Reticle {
+    pub fn new() -> Reticle {
+        Reticle {
+            hit_marker_remaining: 0.0,
+            hit_marker_was_kill: false,
+            texture: None,
+        }
+    }
+
+    /// The player damaged something - or finished it off
+    pub fn record_hit(&mut self, was_kill: bool) {
+        self.hit_marker_remaining = HIT_MARKER_DURATION;
+        self.hit_marker_was_kill = was_kill;
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.hit_marker_remaining = (self.hit_marker_remaining - delta_time).max(0.0);
+    }
+
+    pub fn render(
+        &mut self,
+        context: ReticleContext,
+        screen_size: Vector2<f32>,
+        options: &ReticleOptions,
+    ) -> Vec<SceneObject> {
+        if !options.enabled {
+            return Vec::new();
+        }
+
+        let texture: Rc<dyn TextureTrait> = self
+            .texture
+            .get_or_insert_with(|| Rc::new(engine::texture::init_from_memory(white_texture())))
+            .clone();
+        let center = screen_size * 0.5;
+        let color = context.color();
+
+        let mut objects = Vec::new();
+        let arm_offset = ARM_GAP + ARM_LENGTH / 2.0;
+        for angle in [0.0, PI / 2.0, PI, PI * 1.5] {
+            objects.push(screen_space_bar(
+                texture.clone(),
+                center + vec2(angle.cos(), angle.sin()) * arm_offset,
+                vec2(ARM_LENGTH, ARM_THICKNESS),
+                angle,
+                color,
+            ));
+        }
+
+        if options.hit_markers && self.hit_marker_remaining > 0.0 {
+            let alpha = self.hit_marker_remaining / HIT_MARKER_DURATION;
+            let hit_color = if self.hit_marker_was_kill {
+                vec4(1.0, 0.2, 0.2, alpha)
+            } else {
+                vec4(1.0, 1.0, 1.0, alpha)
+            };
+            for angle in [PI / 4.0, PI * 0.75, PI * 1.25, PI * 1.75] {
+                objects.push(screen_space_bar(
+                    texture.clone(),
+                    center + vec2(angle.cos(), angle.sin()) * HIT_MARKER_OFFSET,
+                    vec2(HIT_MARKER_LENGTH, ARM_THICKNESS),
+                    angle,
+                    hit_color,
+                ));
+            }
+        }
+
+        objects
+    }
+}
+
+impl Default for Reticle {
+    fn default() -> Self {
+        Reticle::new()
+    }
+}
+
+/// A screen-space bar centered on `center`, rotated by `angle` (radians)
+fn screen_space_bar(
+    texture: Rc<dyn TextureTrait>,
+    center: Vector2<f32>,
+    size: Vector2<f32>,
+    angle: f32,
+    color: Vector4<f32>,
+) -> SceneObject {
+    let material = ScreenSpaceMaterial::create(texture, color);
+    let xform = Matrix4::from_translation(vec3(center.x, center.y, 0.0))
+        * Matrix4::from_angle_z(cgmath::Rad(angle))
+        * Matrix4::from_nonuniform_scale(size.x, size.y, 1.0);
+    let mut obj = SceneObject::new(material, Box::new(quad::create()));
+    obj.set_local_transform(xform);
+    obj.set_render_order(render_order::SCREEN_SPACE);
+    obj
+}
+
+fn white_texture() -> RawTextureData {
+    RawTextureData {
+        bytes: vec![255; 4 * 4 * 4],
+        width: 4,
+        height: 4,
+        format: PixelFormat::RGBA,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_marker_fades() {
+        let mut reticle = Reticle::new();
+        reticle.record_hit(false);
+        reticle.update(HIT_MARKER_DURATION * 0.5);
+        assert!(reticle.hit_marker_remaining > 0.0);
+
+        reticle.update(HIT_MARKER_DURATION);
+        assert_eq!(reticle.hit_marker_remaining, 0.0);
+    }
+
+    #[test]
+    fn test_living_ai_is_an_enemy() {
+        let mut world = World::new();
+        let ai = PropAI(String::new());
+        let enemy = world.add_entity((ai.clone(), PropHitPoints { hit_points: 10 }));
+        let corpse = world.add_entity((ai, PropHitPoints { hit_points: 0 }));
+        let crate_entity = world.add_entity(());
+
+        assert_eq!(
+            ReticleContext::for_entity(&world, enemy),
+            ReticleContext::Enemy
+        );
+        assert_ne!(
+            ReticleContext::for_entity(&world, corpse),
+            ReticleContext::Enemy
+        );
+        assert_eq!(
+            ReticleContext::for_entity(&world, crate_entity),
+            ReticleContext::Default
+        );
+    }
+}
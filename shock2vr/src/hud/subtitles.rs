@@ -0,0 +1,139 @@
+use cgmath::Vector2;
+use dark::importers::FONT_IMPORTER;
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use shipyard::Unique;
+
+use crate::accessibility::AccessibilitySettings;
+
+const FONT_SIZE: f32 = 14.0;
+
+// Subtitles don't come with timing, so estimate how long they take to read
+const MIN_SUBTITLE_DURATION: f32 = 3.0;
+const SECONDS_PER_CHARACTER: f32 = 0.07;
+
+// Longest line before wrapping, in characters
+const MAX_LINE_LENGTH: usize = 60;
+
+// Only a few subtitles are shown at once - the oldest drop off first
+const MAX_SUBTITLES: usize = 3;
+
+struct Subtitle {
+    lines: Vec<String>,
+    remaining: f32,
+}
+
+///
+/// Subtitles
+///
+/// Captions for announcements and other speech, shown at the bottom of the screen
+/// for a time based on their length
+#[derive(Unique, Default)]
+pub struct Subtitles {
+    active: Vec<Subtitle>,
+}
+
+impl Subtitles {
+    pub fn new() -> Subtitles {
+        Subtitles { active: vec![] }
+    }
+
+    pub fn show(&mut self, text: &str) {
+        let duration = (text.len() as f32 * SECONDS_PER_CHARACTER).max(MIN_SUBTITLE_DURATION);
+        self.active.push(Subtitle {
+            lines: wrap_text(text, MAX_LINE_LENGTH),
+            remaining: duration,
+        });
+
+        if self.active.len() > MAX_SUBTITLES {
+            self.active.remove(0);
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for subtitle in &mut self.active {
+            subtitle.remaining -= delta_time;
+        }
+        self.active.retain(|subtitle| subtitle.remaining > 0.0);
+    }
+
+    pub fn render(
+        &self,
+        asset_cache: &mut AssetCache,
+        screen_size: Vector2<f32>,
+        accessibility: &AccessibilitySettings,
+    ) -> Vec<SceneObject> {
+        if !accessibility.subtitles || self.active.is_empty() {
+            return vec![];
+        }
+
+        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+        let font_size = accessibility.scaled_font_size(FONT_SIZE);
+        let line_height = font_size * 1.2;
+        let lines: Vec<&String> = self
+            .active
+            .iter()
+            .flat_map(|subtitle| subtitle.lines.iter())
+            .collect();
+
+        // Stack up from the bottom of the screen, newest last
+        let x = screen_size.x * 0.15;
+        let bottom = screen_size.y * 0.85;
+        let top = bottom - lines.len() as f32 * line_height;
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                SceneObject::screen_space_text(
+                    line,
+                    font.clone(),
+                    font_size,
+                    0.9,
+                    x,
+                    top + idx as f32 * line_height,
+                )
+            })
+            .collect()
+    }
+}
+
+fn wrap_text(text: &str, max_line_length: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > max_line_length {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("attention all crew members", 12),
+            vec!["attention", "all crew", "members"]
+        );
+        assert!(wrap_text("   ", 12).is_empty());
+    }
+
+    #[test]
+    fn test_subtitles_expire() {
+        let mut subtitles = Subtitles::new();
+        subtitles.show("Short");
+        subtitles.update(MIN_SUBTITLE_DURATION - 0.5);
+        assert_eq!(subtitles.active.len(), 1);
+        subtitles.update(1.0);
+        assert!(subtitles.active.is_empty());
+    }
+}
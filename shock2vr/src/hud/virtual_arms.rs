@@ -3,10 +3,21 @@ use dark::{
     importers::TEXTURE_IMPORTER,
     properties::{PropHitPoints, PropMaxHitPoints},
 };
-use engine::{assets::asset_cache::AssetCache, scene::SceneObject, texture::TextureOptions};
+use engine::{
+    assets::asset_cache::AssetCache,
+    scene::{Renderable, SceneObject, TransformSceneObject},
+    texture::TextureOptions,
+};
+use rand::{Rng, thread_rng};
 use shipyard::{Get, UniqueView, View, World};
 
-use crate::{mission::PlayerInfo, vr_config::Handedness};
+use crate::{
+    energy, light_gem,
+    mission::PlayerInfo,
+    noise,
+    player_status::{PlayerStatus, StatusEffect},
+    vr_config::Handedness,
+};
 
 /// Offset from hand position to forearm HUD panel position
 const FOREARM_OFFSET: Vector3<f32> = vec3(0.0, 0.0, 0.25); // 10cm toward elbow from hand
@@ -30,6 +41,48 @@ const HEALTH_BAR_END: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 88.0, 54.0 + BAR_VER
 const PSI_BAR_START: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 8.0, 17.0 + BAR_VERTICAL_OFFSET);
 const PSI_BAR_END: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 88.0, 31.0 + BAR_VERTICAL_OFFSET);
 
+/// Noise meter coordinates (pixel space on BIOFULL.PCX) - a strip under the health bar
+const NOISE_METER_START: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 8.0, 50.0);
+const NOISE_METER_END: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 88.0, 55.0);
+
+/// Noise meter colors, from barely audible to as loud as the player can be
+const NOISE_METER_QUIET_COLOR: Vector3<f32> = vec3(0.2, 0.8, 0.2);
+const NOISE_METER_LOUD_COLOR: Vector3<f32> = vec3(0.9, 0.1, 0.1);
+
+/// Energy meter coordinates (pixel space on BIOFULL.PCX) - a strip between the psi and health bars
+const ENERGY_METER_START: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 8.0, 25.0);
+const ENERGY_METER_END: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 88.0, 30.0);
+
+/// Energy meter colors, from nearly drained to full
+const ENERGY_METER_LOW_COLOR: Vector3<f32> = vec3(0.6, 0.3, 0.0);
+const ENERGY_METER_FULL_COLOR: Vector3<f32> = vec3(0.2, 0.6, 1.0);
+
+/// Light gem coordinates (pixel space on BIOFULL.PCX) - a square beside the psi bar
+const LIGHT_GEM_START: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 92.0, 17.0 + BAR_VERTICAL_OFFSET);
+const LIGHT_GEM_END: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 106.0, 31.0 + BAR_VERTICAL_OFFSET);
+
+/// Light gem colors, from hidden in the dark to fully lit
+const LIGHT_GEM_HIDDEN_COLOR: Vector3<f32> = vec3(0.05, 0.05, 0.1);
+const LIGHT_GEM_LIT_COLOR: Vector3<f32> = vec3(1.0, 0.9, 0.6);
+
+/// Heartbeat light coordinates (pixel space on BIOFULL.PCX) - a square beside the health bar
+const HEARTBEAT_START: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 92.0, 40.0 + BAR_VERTICAL_OFFSET);
+const HEARTBEAT_END: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 106.0, 54.0 + BAR_VERTICAL_OFFSET);
+
+/// Heartbeat light colors, between beats and on a beat
+const HEARTBEAT_REST_COLOR: Vector3<f32> = vec3(0.1, 0.0, 0.0);
+const HEARTBEAT_BEAT_COLOR: Vector3<f32> = vec3(1.0, 0.05, 0.05);
+
+/// Status effect indicators (pixel space on BIOFULL.PCX) - a row of squares after the light gem,
+/// one per active effect
+const STATUS_EFFECT_START: (f32, f32) = (BAR_HORIZONTAL_OFFSET + 112.0, 17.0 + BAR_VERTICAL_OFFSET);
+const STATUS_EFFECT_SIZE: f32 = 14.0;
+const STATUS_EFFECT_SPACING: f32 = 18.0;
+
+/// Status effects blink for their last few seconds
+const STATUS_EFFECT_EXPIRING_TIME: f32 = 3.0;
+const STATUS_EFFECT_BLINK_RATE: f32 = 4.0;
+
 /// Z-offset for overlay layers to ensure proper rendering order
 const OVERLAY_Z_OFFSET: f32 = 0.001;
 
@@ -44,24 +97,25 @@ pub fn create_arm_hud_panels(
 ) -> Vec<SceneObject> {
     let mut scene_objects = Vec::new();
 
-    // Create left arm HUD with health/psi overlays (BIOFULL base)
-    let mut left_hud_layers = create_forearm_hud_with_overlays(
+    // Left arm HUD with health/psi overlays (BIOFULL base)
+    let left_hud = create_forearm_hud(
         asset_cache,
         world,
         left_hand_position,
         left_hand_rotation,
         Handedness::Left,
     );
-    scene_objects.append(&mut left_hud_layers);
+    scene_objects.extend(left_hud.render_objects());
 
-    // Create right arm HUD (AMMOFULL - for ammo)
-    let right_hud = create_forearm_hud_panel(
+    // Right arm HUD (AMMOFULL - for ammo)
+    let right_hud = create_forearm_hud(
         asset_cache,
+        world,
         right_hand_position,
         right_hand_rotation,
         Handedness::Right,
     );
-    scene_objects.push(right_hud);
+    scene_objects.extend(right_hud.render_objects());
 
     scene_objects
 }
@@ -74,14 +128,12 @@ fn pixel_to_uv(pixel_coords: (f32, f32)) -> (f32, f32) {
     )
 }
 
-/// Calculate overlay quad dimensions and position in world space
-fn create_overlay_transform(
-    base_position: Vector3<f32>,
-    base_rotation: Quaternion<f32>,
+/// Where an overlay sits on the HUD panel, relative to the forearm
+fn create_overlay_local_transform(
     pixel_start: (f32, f32),
     pixel_end: (f32, f32),
     z_offset: f32,
-) -> (Matrix4<f32>, f32, f32) {
+) -> Matrix4<f32> {
     // Convert pixel coordinates to UV space
     let uv_start = pixel_to_uv(pixel_start);
     let uv_end = pixel_to_uv(pixel_end);
@@ -94,25 +146,16 @@ fn create_overlay_transform(
     let center_u = (uv_start.0 + uv_end.0) / 2.0 - 0.5; // -0.5 to center
     let center_v = (uv_start.1 + uv_end.1) / 2.0 - 0.5; // -0.5 to center
 
-    // Convert UV offsets to world space offsets
+    // Convert UV offsets to offsets on the panel
     let offset_x = center_u * HUD_PANEL_WIDTH;
     let offset_y = -center_v * HUD_PANEL_HEIGHT; // Flip Y for correct orientation
 
-    // Apply base rotation to offsets
-    let local_offset = vec3(offset_x, offset_y, z_offset);
-    let world_offset = base_rotation.rotate_vector(local_offset);
-
-    let overlay_position = base_position + world_offset;
-
-    let transform = Matrix4::from_translation(overlay_position)
-        * Matrix4::from(base_rotation)
-        * Matrix4::from_nonuniform_scale(overlay_width, overlay_height, 1.0);
-
-    (transform, overlay_width, overlay_height)
+    Matrix4::from_translation(vec3(offset_x, offset_y, z_offset))
+        * Matrix4::from_nonuniform_scale(overlay_width, overlay_height, 1.0)
 }
 
 /// Get player health percentage (0.0 to 1.0)
-fn get_health_percentage(world: &World) -> f32 {
+pub fn get_health_percentage(world: &World) -> f32 {
     // Get player entity from PlayerInfo
     let player_info = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
     let player_entity = player_info.entity_id;
@@ -141,82 +184,251 @@ fn get_psi_percentage(_world: &World) -> f32 {
     0.75 // Placeholder - 75% psi for testing
 }
 
-/// Create layered forearm HUD with health and psi bar overlays
-fn create_forearm_hud_with_overlays(
+/// Transform of the forearm the HUD is worn on - the parent of the panel and its overlays
+fn forearm_transform(
+    hand_position: Vector3<f32>,
+    hand_rotation: Quaternion<f32>,
+    handedness: Handedness,
+) -> Matrix4<f32> {
+    // Calculate forearm position - offset from hand toward elbow
+    let forearm_position = hand_position + hand_rotation.rotate_vector(FOREARM_OFFSET);
+
+    // Calculate wearable computer orientation
+    // For a forearm-mounted display, we need additional rotations:
+    // 1. Yaw rotation to align with forearm direction
+    // 2. Z rotation to make it lie flat on the forearm like a wrist computer
+    let forearm_yaw_rotation = match handedness {
+        Handedness::Left => Quaternion::from(Euler::new(Deg(0.0), Deg(90.0), Deg(0.0))), // Rotate left panel toward body
+        Handedness::Right => Quaternion::from(Euler::new(Deg(0.0), Deg(-90.0), Deg(0.0))), // Rotate right panel toward body
+    };
+
+    // Z rotation to tilt the panel flat against the forearm (like looking down at a wrist watch)
+    let forearm_tilt_rotation = Quaternion::from(Euler::new(Deg(-90.0), Deg(0.0), Deg(180.0)));
+
+    // Combine all rotations: hand rotation + yaw + tilt
+    let final_rotation = hand_rotation * forearm_yaw_rotation * forearm_tilt_rotation;
+
+    Matrix4::from_translation(forearm_position) * Matrix4::from(final_rotation)
+}
+
+/// Create a forearm HUD - the panel, plus the health and psi bars, noise and energy meters, light
+/// gem, heartbeat light and status effects on the left arm, all attached to the forearm
+fn create_forearm_hud(
     asset_cache: &mut AssetCache,
     world: &World,
     hand_position: Vector3<f32>,
     hand_rotation: Quaternion<f32>,
     handedness: Handedness,
-) -> Vec<SceneObject> {
-    let mut layers = Vec::new();
+) -> TransformSceneObject {
+    let mut forearm = TransformSceneObject::with_transform(forearm_transform(
+        hand_position,
+        hand_rotation,
+        handedness,
+    ));
+
+    // Layer 1: Base panel
+    forearm.add_scene_object(create_forearm_hud_panel(asset_cache, handedness));
 
-    // Only add overlays for left hand (BIOFULL display)
+    // Only the left hand has overlays (BIOFULL display)
     if handedness != Handedness::Left {
-        // For right hand, just create basic panel
-        let panel = create_forearm_hud_panel(asset_cache, hand_position, hand_rotation, handedness);
-        layers.push(panel);
-        return layers;
+        return forearm;
     }
 
-    // Calculate base HUD position and rotation
-    let forearm_position = hand_position + hand_rotation.rotate_vector(FOREARM_OFFSET);
-    let forearm_yaw_rotation = Quaternion::from(Euler::new(Deg(0.0), Deg(90.0), Deg(0.0)));
-    let forearm_tilt_rotation = Quaternion::from(Euler::new(Deg(-90.0), Deg(0.0), Deg(180.0)));
-    let final_rotation = hand_rotation * forearm_yaw_rotation * forearm_tilt_rotation;
-
-    // Layer 1: Base BIOFULL panel
-    let base_panel =
-        create_forearm_hud_panel(asset_cache, hand_position, hand_rotation, handedness);
-    layers.push(base_panel);
+    // An EMP scrambles the readouts until it wears off
+    let (is_hud_disrupted, heartbeat_intensity) = world
+        .borrow::<UniqueView<PlayerStatus>>()
+        .map(|status| (status.is_hud_disrupted(), status.heartbeat_intensity()))
+        .unwrap_or((false, 0.0));
+    let scramble = |value: f32| {
+        if is_hud_disrupted {
+            thread_rng().gen_range(0.0..1.0)
+        } else {
+            value
+        }
+    };
 
     // Layer 2: Health bar overlay
-    let health_percentage = get_health_percentage(world);
-    if let Some(health_overlay) = create_bar_overlay(
+    let health_percentage = scramble(get_health_percentage(world));
+    forearm.add_scene_object(create_bar_overlay(
         asset_cache,
         "HPBAR.PCX",
-        forearm_position,
-        final_rotation,
         HEALTH_BAR_START,
         HEALTH_BAR_END,
         health_percentage,
         OVERLAY_Z_OFFSET,
-    ) {
-        layers.push(health_overlay);
-    }
+    ));
 
     // Layer 3: Psi bar overlay
-    let psi_percentage = get_psi_percentage(world);
-    if let Some(psi_overlay) = create_bar_overlay(
+    let psi_percentage = scramble(get_psi_percentage(world));
+    forearm.add_scene_object(create_bar_overlay(
         asset_cache,
         "PSIBAR.PCX",
-        forearm_position,
-        final_rotation,
         PSI_BAR_START,
         PSI_BAR_END,
         psi_percentage,
         OVERLAY_Z_OFFSET * 2.0, // Stack above health bar
-    ) {
-        layers.push(psi_overlay);
+    ));
+
+    // Layer 4: Noise meter, when the player is making any noise
+    let noise_level = scramble(noise::get_noise_level(world));
+    if noise_level > 0.0 {
+        forearm.add_scene_object(create_noise_meter(noise_level, OVERLAY_Z_OFFSET * 3.0));
+    }
+
+    // Layer 5: Light gem
+    let visibility = scramble(light_gem::get_player_visibility(world));
+    forearm.add_scene_object(create_light_gem(visibility, OVERLAY_Z_OFFSET * 3.0));
+
+    // Layer 6: Heartbeat light, when health is low
+    if heartbeat_intensity > 0.0 {
+        forearm.add_scene_object(create_heartbeat_light(
+            heartbeat_intensity,
+            OVERLAY_Z_OFFSET * 3.0,
+        ));
     }
 
-    layers
+    // Layer 7: Energy meter, in a mission - empty shows nothing
+    if let Some(energy_fraction) = energy::get_energy_fraction(world) {
+        let energy_fraction = scramble(energy_fraction);
+        if energy_fraction > 0.0 {
+            forearm.add_scene_object(create_energy_meter(energy_fraction, OVERLAY_Z_OFFSET * 3.0));
+        }
+    }
+
+    // Layer 8: Status effects
+    let status_effects = crate::player_status::get_status_effects(world);
+    for (index, effect) in status_effects.iter().enumerate() {
+        if let Some(indicator) =
+            create_status_effect_indicator(effect, index, OVERLAY_Z_OFFSET * 3.0)
+        {
+            forearm.add_scene_object(indicator);
+        }
+    }
+
+    forearm
+}
+
+/// Create the heartbeat light - flashing red with each beat
+fn create_heartbeat_light(intensity: f32, z_offset: f32) -> SceneObject {
+    let intensity = intensity.clamp(0.0, 1.0);
+    let color = HEARTBEAT_REST_COLOR + (HEARTBEAT_BEAT_COLOR - HEARTBEAT_REST_COLOR) * intensity;
+    let material = engine::scene::color_material::create(color);
+    let geometry = Box::new(engine::scene::quad::create());
+
+    let mut scene_object = SceneObject::new(material, geometry);
+    scene_object.set_transform(create_overlay_local_transform(
+        HEARTBEAT_START,
+        HEARTBEAT_END,
+        z_offset,
+    ));
+
+    scene_object
+}
+
+/// Create the indicator for the `index`th active status effect - None while it blinks off
+fn create_status_effect_indicator(
+    effect: &StatusEffect,
+    index: usize,
+    z_offset: f32,
+) -> Option<SceneObject> {
+    let is_expiring = effect.remaining < STATUS_EFFECT_EXPIRING_TIME;
+    if is_expiring && (effect.remaining * STATUS_EFFECT_BLINK_RATE).fract() < 0.5 {
+        return None;
+    }
+
+    let material = engine::scene::color_material::create(effect.kind.color());
+    let geometry = Box::new(engine::scene::quad::create());
+
+    let start = (
+        STATUS_EFFECT_START.0 + STATUS_EFFECT_SPACING * index as f32,
+        STATUS_EFFECT_START.1,
+    );
+    let end = (start.0 + STATUS_EFFECT_SIZE, start.1 + STATUS_EFFECT_SIZE);
+
+    let mut scene_object = SceneObject::new(material, geometry);
+    scene_object.set_transform(create_overlay_local_transform(start, end, z_offset));
+
+    Some(scene_object)
+}
+
+/// Create the light gem overlay - glowing brighter the more visible the player is
+fn create_light_gem(visibility: f32, z_offset: f32) -> SceneObject {
+    let visibility = visibility.clamp(0.0, 1.0);
+    let color =
+        LIGHT_GEM_HIDDEN_COLOR + (LIGHT_GEM_LIT_COLOR - LIGHT_GEM_HIDDEN_COLOR) * visibility;
+    let material = engine::scene::color_material::create(color);
+    let geometry = Box::new(engine::scene::quad::create());
+
+    let mut scene_object = SceneObject::new(material, geometry);
+    scene_object.set_transform(create_overlay_local_transform(
+        LIGHT_GEM_START,
+        LIGHT_GEM_END,
+        z_offset,
+    ));
+
+    scene_object
+}
+
+/// Create the energy meter overlay - filling, and going from orange to blue, as energy is restored
+fn create_energy_meter(energy_fraction: f32, z_offset: f32) -> SceneObject {
+    let energy_fraction = energy_fraction.clamp(0.0, 1.0);
+    let color = ENERGY_METER_LOW_COLOR
+        + (ENERGY_METER_FULL_COLOR - ENERGY_METER_LOW_COLOR) * energy_fraction;
+    let material = engine::scene::color_material::create(color);
+    let geometry = Box::new(engine::scene::quad::create());
+
+    let meter_end = (
+        ENERGY_METER_START.0 + (ENERGY_METER_END.0 - ENERGY_METER_START.0) * energy_fraction,
+        ENERGY_METER_END.1,
+    );
+
+    let mut scene_object = SceneObject::new(material, geometry);
+    scene_object.set_transform(create_overlay_local_transform(
+        ENERGY_METER_START,
+        meter_end,
+        z_offset,
+    ));
+
+    scene_object
+}
+
+/// Create the noise meter overlay - filling, and going from green to red, as the player gets louder
+fn create_noise_meter(noise_level: f32, z_offset: f32) -> SceneObject {
+    let noise_level = noise_level.clamp(0.0, 1.0);
+    let color =
+        NOISE_METER_QUIET_COLOR + (NOISE_METER_LOUD_COLOR - NOISE_METER_QUIET_COLOR) * noise_level;
+    let material = engine::scene::color_material::create(color);
+    let geometry = Box::new(engine::scene::quad::create());
+
+    let meter_end = (
+        NOISE_METER_START.0 + (NOISE_METER_END.0 - NOISE_METER_START.0) * noise_level,
+        NOISE_METER_END.1,
+    );
+
+    let mut scene_object = SceneObject::new(material, geometry);
+    scene_object.set_transform(create_overlay_local_transform(
+        NOISE_METER_START,
+        meter_end,
+        z_offset,
+    ));
+
+    scene_object
 }
 
 /// Create a clipped bar overlay at specific pixel coordinates
 fn create_bar_overlay(
     asset_cache: &mut AssetCache,
     texture_name: &str,
-    base_position: Vector3<f32>,
-    base_rotation: Quaternion<f32>,
     pixel_start: (f32, f32),
     pixel_end: (f32, f32),
     clip_percentage: f32,
     z_offset: f32,
-) -> Option<SceneObject> {
+) -> SceneObject {
     // Load bar texture
     let texture_options = TextureOptions { wrap: false };
-    let texture = asset_cache.get_ext(&TEXTURE_IMPORTER, texture_name, &texture_options);
+    let texture = asset_cache
+        .get_ext(&TEXTURE_IMPORTER, texture_name, &texture_options)
+        .unwrap();
 
     // Create clipped screen material
     let material = engine::scene::clipped_screen_material::create(
@@ -227,39 +439,28 @@ fn create_bar_overlay(
     // Create geometry
     let geometry = Box::new(engine::scene::quad::create());
 
-    // Calculate overlay transform
-    let (transform, _width, _height) = create_overlay_transform(
-        base_position,
-        base_rotation,
+    // Create scene object, placed relative to the forearm
+    let mut scene_object = SceneObject::new(material, geometry);
+    scene_object.set_transform(create_overlay_local_transform(
         pixel_start,
         pixel_end,
         z_offset,
-    );
-
-    // Create scene object
-    let mut scene_object = SceneObject::new(material, geometry);
-    scene_object.set_transform(transform);
+    ));
 
-    Some(scene_object)
+    scene_object
 }
 
-/// Create a single forearm HUD panel
-fn create_forearm_hud_panel(
-    asset_cache: &mut AssetCache,
-    hand_position: Vector3<f32>,
-    hand_rotation: Quaternion<f32>,
-    handedness: Handedness,
-) -> SceneObject {
-    // Calculate forearm position - offset from hand toward elbow
-    let forearm_position = hand_position + hand_rotation.rotate_vector(FOREARM_OFFSET);
-
+/// Create a single forearm HUD panel, relative to the forearm
+fn create_forearm_hud_panel(asset_cache: &mut AssetCache, handedness: Handedness) -> SceneObject {
     // Load appropriate texture based on handedness
     let texture_options = TextureOptions { wrap: false };
     let texture = match handedness {
-        Handedness::Left => asset_cache.get_ext(&TEXTURE_IMPORTER, "BIOFULL.PCX", &texture_options),
-        Handedness::Right => {
-            asset_cache.get_ext(&TEXTURE_IMPORTER, "AMMOFULL.PCX", &texture_options)
-        }
+        Handedness::Left => asset_cache
+            .get_ext(&TEXTURE_IMPORTER, "BIOFULL.PCX", &texture_options)
+            .unwrap(),
+        Handedness::Right => asset_cache
+            .get_ext(&TEXTURE_IMPORTER, "AMMOFULL.PCX", &texture_options)
+            .unwrap(),
     };
 
     // Create BasicMaterial with the loaded texture (casting to the expected trait object)
@@ -272,29 +473,13 @@ fn create_forearm_hud_panel(
     // Create quad geometry
     let geometry = Box::new(engine::scene::quad::create());
 
-    // Calculate wearable computer orientation
-    // For a forearm-mounted display, we need additional rotations:
-    // 1. Yaw rotation to align with forearm direction
-    // 2. Z rotation to make it lie flat on the forearm like a wrist computer
-    let forearm_yaw_rotation = match handedness {
-        Handedness::Left => Quaternion::from(Euler::new(Deg(0.0), Deg(90.0), Deg(0.0))), // Rotate left panel toward body
-        Handedness::Right => Quaternion::from(Euler::new(Deg(0.0), Deg(-90.0), Deg(0.0))), // Rotate right panel toward body
-    };
-
-    // Z rotation to tilt the panel flat against the forearm (like looking down at a wrist watch)
-    let forearm_tilt_rotation = Quaternion::from(Euler::new(Deg(-90.0), Deg(0.0), Deg(180.0)));
-
-    // Combine all rotations: hand rotation + yaw + tilt
-    let final_rotation = hand_rotation * forearm_yaw_rotation * forearm_tilt_rotation;
-
-    // Calculate transform matrix with proper aspect ratio and wearable orientation
-    let transform = Matrix4::from_translation(forearm_position)
-        * Matrix4::from(final_rotation)
-        * Matrix4::from_nonuniform_scale(HUD_PANEL_WIDTH, HUD_PANEL_HEIGHT, 1.0);
-
-    // Create scene object
+    // Create scene object, scaled to the panel's aspect ratio
     let mut scene_object = SceneObject::new(material, geometry);
-    scene_object.set_transform(transform);
+    scene_object.set_transform(Matrix4::from_nonuniform_scale(
+        HUD_PANEL_WIDTH,
+        HUD_PANEL_HEIGHT,
+        1.0,
+    ));
 
     scene_object
 }
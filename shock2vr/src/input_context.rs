@@ -4,8 +4,9 @@
 // For desktop / PC runtime, the mapping is a bit more interesting..
 
 use cgmath::{Quaternion, Vector2, Vector3, Zero};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct InputContext {
     // Information about the head position
     pub head: Head,
@@ -26,7 +27,7 @@ impl InputContext {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Head {
     pub rotation: Quaternion<f32>,
 }
@@ -43,7 +44,7 @@ impl Head {
 }
 
 // Context for an individual hand (motion controller)
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Hand {
     pub position: Vector3<f32>,
     pub rotation: Quaternion<f32>,
@@ -51,6 +52,8 @@ pub struct Hand {
     pub trigger_value: f32,
     pub squeeze_value: f32,
     pub a_value: f32,
+    // Secondary face button (B / Y) - crouch on the left hand
+    pub b_value: f32,
 }
 
 impl Hand {
@@ -65,6 +68,7 @@ impl Hand {
             trigger_value: 0.0,
             squeeze_value: 0.0,
             a_value: 0.0,
+            b_value: 0.0,
         }
     }
 }
@@ -0,0 +1,297 @@
+// Interaction targeting - decides what each hand would interact with, so the outline / name / prompt HUD
+// and frobbing always agree on the same entity.
+
+use std::cmp::Ordering;
+
+use cgmath::{InnerSpace, Matrix4, Quaternion, Rotation, Vector2, Vector3, point3, vec3};
+use collision::Aabb;
+use dark::properties::{FrobFlag, PropFrobInfo, PropTranslatingDoor};
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use shipyard::{EntityId, Get, View, World};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    entity_tags::{self, EntityTags},
+    hud::{draw_interaction_prompt, draw_item_name, draw_item_outline},
+    localization,
+    physics::{InternalCollisionGroups, PhysicsWorld},
+    scripts::internal_lock_script::is_blocked_by_lock,
+    util,
+    vr_config::Handedness,
+};
+
+// Frobbable entities this close to the hand are candidates, even if the hand isn't pointing at them
+const NEAR_REACH_RADIUS: f32 = 0.25;
+
+///
+/// InteractionVerb
+///
+/// What frobbing an entity will do - shown as a prompt next to the outline
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionVerb {
+    PickUp,
+    Hack,
+    Open,
+    Use,
+}
+
+impl InteractionVerb {
+    pub fn for_entity(world: &World, entity_id: EntityId) -> Option<InteractionVerb> {
+        let v_frob_info = world.borrow::<View<PropFrobInfo>>().unwrap();
+        let world_action = v_frob_info.get(entity_id).ok()?.world_action;
+
+        if world_action.is_empty() || world_action.contains(FrobFlag::IGNORE) {
+            return None;
+        }
+
+        if world_action.intersects(FrobFlag::MOVE | FrobFlag::USE_AMMO) {
+            return Some(InteractionVerb::PickUp);
+        }
+
+        // Locked, without a key - the only thing to do is pick the lock
+        if is_blocked_by_lock(world, entity_id) {
+            return Some(InteractionVerb::Hack);
+        }
+
+        if entity_tags::has_tag(world, entity_id, EntityTags::HACKABLE) {
+            return Some(InteractionVerb::Hack);
+        }
+
+        let v_door = world.borrow::<View<PropTranslatingDoor>>().unwrap();
+        if v_door.contains(entity_id) {
+            return Some(InteractionVerb::Open);
+        }
+
+        Some(InteractionVerb::Use)
+    }
+
+    // Higher wins - the more specific the action, the more likely it's what the player is reaching for
+    fn priority(&self) -> u32 {
+        match self {
+            InteractionVerb::PickUp => 3,
+            InteractionVerb::Hack => 2,
+            InteractionVerb::Open => 1,
+            InteractionVerb::Use => 0,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let id = match self {
+            InteractionVerb::PickUp => "interact-pick-up",
+            InteractionVerb::Hack => "interact-hack",
+            InteractionVerb::Open => "interact-open",
+            InteractionVerb::Use => "interact-use",
+        };
+        localization::text(id)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InteractionTarget {
+    pub entity_id: EntityId,
+    pub verb: InteractionVerb,
+    pub distance: f32,
+}
+
+///
+/// choose_best_target
+///
+/// Highest priority verb wins, and the closest entity breaks ties
+pub fn choose_best_target(candidates: &[InteractionTarget]) -> Option<InteractionTarget> {
+    candidates.iter().copied().max_by(|a, b| {
+        a.verb.priority().cmp(&b.verb.priority()).then_with(|| {
+            b.distance
+                .partial_cmp(&a.distance)
+                .unwrap_or(Ordering::Equal)
+        })
+    })
+}
+
+///
+/// InteractionSystem
+///
+/// Tracks the interaction target for each hand
+pub struct InteractionSystem {
+    left_target: Option<InteractionTarget>,
+    right_target: Option<InteractionTarget>,
+}
+
+impl InteractionSystem {
+    pub fn new() -> InteractionSystem {
+        InteractionSystem {
+            left_target: None,
+            right_target: None,
+        }
+    }
+
+    ///
+    /// update_hand
+    ///
+    /// Find the target for a hand - the entity it points at, or a frobbable entity within reach.
+    /// A hand that's busy (holding or climbing) has no target.
+    pub fn update_hand(
+        &mut self,
+        world: &World,
+        physics: &PhysicsWorld,
+        handedness: Handedness,
+        hand_position: Vector3<f32>,
+        hand_rotation: Quaternion<f32>,
+        is_free: bool,
+    ) -> Option<InteractionTarget> {
+        let target = if is_free {
+            choose_best_target(&find_candidates(
+                world,
+                physics,
+                hand_position,
+                hand_rotation,
+            ))
+        } else {
+            None
+        };
+
+        match handedness {
+            Handedness::Left => self.left_target = target,
+            Handedness::Right => self.right_target = target,
+        }
+        target
+    }
+
+    pub fn render_per_eye(
+        &self,
+        asset_cache: &mut AssetCache,
+        physics: &PhysicsWorld,
+        world: &World,
+        view: Matrix4<f32>,
+        projection: Matrix4<f32>,
+        screen_size: Vector2<f32>,
+        accessibility: &AccessibilitySettings,
+    ) -> Vec<SceneObject> {
+        let mut scene_objects = Vec::new();
+        for target in [self.left_target, self.right_target].into_iter().flatten() {
+            scene_objects.extend(draw_item_outline(
+                asset_cache,
+                physics,
+                target.entity_id,
+                view,
+                projection,
+                screen_size,
+                accessibility,
+            ));
+            scene_objects.extend(draw_item_name(
+                asset_cache,
+                physics,
+                target.entity_id,
+                world,
+                view,
+                projection,
+                screen_size,
+                accessibility,
+            ));
+            scene_objects.extend(draw_interaction_prompt(
+                asset_cache,
+                physics,
+                target.entity_id,
+                &target.verb.label(),
+                view,
+                projection,
+                screen_size,
+                accessibility,
+            ));
+        }
+        scene_objects
+    }
+}
+
+impl Default for InteractionSystem {
+    fn default() -> Self {
+        InteractionSystem::new()
+    }
+}
+
+fn find_candidates(
+    world: &World,
+    physics: &PhysicsWorld,
+    hand_position: Vector3<f32>,
+    hand_rotation: Quaternion<f32>,
+) -> Vec<InteractionTarget> {
+    let mut candidates = Vec::new();
+
+    // What the hand is pointing at - anything frobbable, however far away
+    let forward = hand_rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
+    let maybe_hit = physics.ray_cast(
+        point3(hand_position.x, hand_position.y, hand_position.z),
+        forward,
+        InternalCollisionGroups::ENTITY
+            | InternalCollisionGroups::SELECTABLE
+            | InternalCollisionGroups::WORLD
+            | InternalCollisionGroups::UI
+            | InternalCollisionGroups::RAYCAST,
+    );
+    if let Some(hit) = maybe_hit {
+        if let Some(entity_id) = hit.maybe_entity_id {
+            let entity_id = util::resolve_proxy_entity(world, entity_id);
+            if let Some(verb) = InteractionVerb::for_entity(world, entity_id) {
+                candidates.push(InteractionTarget {
+                    entity_id,
+                    verb,
+                    distance: (util::point3_to_vec3(hit.hit_point) - hand_position).magnitude(),
+                });
+            }
+        }
+    }
+
+    // ...and anything the hand is reaching into
+    let nearby_entities = physics.entities_in_sphere(
+        hand_position,
+        NEAR_REACH_RADIUS,
+        InternalCollisionGroups::ENTITY | InternalCollisionGroups::SELECTABLE,
+    );
+    for entity_id in nearby_entities {
+        let entity_id = util::resolve_proxy_entity(world, entity_id);
+        if candidates.iter().any(|c| c.entity_id == entity_id) {
+            continue;
+        }
+        if let (Some(verb), Some(position)) = (
+            InteractionVerb::for_entity(world, entity_id),
+            physics.get_aabb2(entity_id).map(|aabb| aabb.center()),
+        ) {
+            candidates.push(InteractionTarget {
+                entity_id,
+                verb,
+                distance: (util::point3_to_vec3(position) - hand_position).magnitude(),
+            });
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(world: &mut World, verb: InteractionVerb, distance: f32) -> InteractionTarget {
+        InteractionTarget {
+            entity_id: world.add_entity(()),
+            verb,
+            distance,
+        }
+    }
+
+    #[test]
+    fn test_priority_beats_distance() {
+        let mut world = World::new();
+        let near_button = target(&mut world, InteractionVerb::Use, 0.1);
+        let far_item = target(&mut world, InteractionVerb::PickUp, 2.0);
+        assert_eq!(choose_best_target(&[near_button, far_item]), Some(far_item));
+    }
+
+    #[test]
+    fn test_closest_breaks_ties() {
+        let mut world = World::new();
+        let far_item = target(&mut world, InteractionVerb::PickUp, 2.0);
+        let near_item = target(&mut world, InteractionVerb::PickUp, 0.5);
+        assert_eq!(choose_best_target(&[far_item, near_item]), Some(near_item));
+        assert_eq!(choose_best_target(&[]), None);
+    }
+}
@@ -1,24 +1,57 @@
+pub mod accessibility;
+pub mod attract_mode;
+pub mod benchmark;
 pub mod command;
+pub mod console;
 pub mod game_scene;
 pub mod input_context;
 pub mod inventory;
 pub mod map_renderer;
 pub mod save_load;
 pub mod scenes;
+pub mod state_hash;
 pub mod teleport;
 pub mod time;
 
+mod announcements;
+mod camera_effects;
+mod conditions;
+pub mod crash_report;
 mod creature;
+pub mod energy;
+mod entity_tags;
+pub mod equipment;
+pub mod experimental;
+pub mod factions;
+pub mod frame_phase;
 mod gui;
+pub mod hazards;
+pub mod hints;
 mod hud;
+mod interaction;
+mod light_gem;
+pub mod localization;
+mod loot;
 mod mission;
+pub mod mods;
+mod noise;
+mod object_labels;
 pub mod pathfinding;
 pub mod paths;
+pub mod photo_mode;
 mod physics;
+mod player_status;
+pub mod profiles;
 mod quest_info;
+pub mod replay;
 mod runtime_props;
 mod scripts;
+mod security;
+pub mod sequences;
+pub mod statistics;
 mod systems;
+pub mod template_overrides;
+mod throw_arc;
 mod util;
 mod virtual_hand;
 mod vr_config;
@@ -26,48 +59,166 @@ pub mod zip_asset_path;
 
 use scenes::{SceneInitResult, create_initial_scene, load_mission_from_save_data};
 
+pub use creature::RootMotionConfig;
+pub use experimental::{ExperimentalFeature, ExperimentalFeatureInfo, ExperimentalFeatures};
+pub use hud::ReticleOptions;
+pub use mission::NamedSpawnPoint;
 pub use mission::SpawnLocation;
-pub use mission::visibility_engine::CullingInfo;
-
-use std::{
-    collections::{HashMap, HashSet},
-    fs::{File, OpenOptions},
-    io::BufReader,
-    rc::Rc,
-    sync::Arc,
-};
+pub use mission::entity_inspector::InspectorAction;
+pub use mission::visibility_engine::{CullingInfo, VisibilityEngineKind, VisibilityStats};
+pub use mission::{EntityEvent, EntityEventKind, ResourceLeaks};
+pub use physics::{CollisionLayerDump, CollisionLayerMatrix, PhysicsBodyClass, PhysicsConfig};
+
+use std::{collections::HashMap, fs::OpenOptions, rc::Rc, sync::Arc, time::Duration};
 
 use cgmath::{Matrix4, Quaternion, Vector2, Vector3, vec3};
 use command::Command;
+use console::{Console, ConsoleInput, ConsoleRegistry};
 use dark::{
     gamesys,
-    importers::{AUDIO_IMPORTER, FONT_IMPORTER, STRINGS_IMPORTER},
+    importers::{AUDIO_IMPORTER, FONT_IMPORTER, MOTIONDB_OVERLAY_IMPORTER},
     motion::MotionDB,
 };
 use engine::{
-    assets::{asset_cache::AssetCache, asset_paths::AssetPath, bundle_asset_path::BundleAssetPath},
-    audio::{AudioClip, AudioContext},
+    assets::{
+        asset_cache::AssetCache, asset_paths::AssetPath, bundle_asset_path::BundleAssetPath,
+        file_system_asset_path::FileSystemAssetPath,
+    },
+    audio::AudioContext,
     file_system::Storage,
-    game_log,
+    game_log, profile,
     scene::SceneObject,
 };
 
 use mission::entity_populator::{EntityPopulator, MissionEntityPopulator, SaveFileEntityPopulator};
 use quest_info::QuestInfo;
 
-use save_load::{EntitySaveData, GlobalData, SaveData};
+use save_load::{EntitySaveData, GlobalData, HeldItemSaveData, SaveData};
 use scripts::GlobalEffect;
 use shipyard::*;
 use time::Time;
 use tracing::{Level, info, span, trace, warn};
 
 use crate::{
-    game_scene::GameScene,
-    mission::{GlobalContext, Mission, PlayerInfo},
-    scripts::Effect,
+    accessibility::AccessibilitySettings,
+    camera_effects::CameraEffects,
+    crash_report::{CaughtPanic, CrashContext, CrashReport},
+    frame_phase::{SimulationInput, SimulationOutput},
+    game_scene::{AmbientAudioSystem, GameScene},
+    hints::{HintSystem, SeenHints},
+    mission::{GlobalContext, Mission, MissionPreloader, PlayerInfo, approaching_levels},
+    mods::{ModLoadOrder, ModManager},
+    photo_mode::{PhotoMode, PhotoModeAction},
+    player_status::PlayerStatus,
+    profiles::PlayerProfile,
+    scenes::CutscenePlayerScene,
+    scripts::{Effect, Message, MessagePayload},
+    sequences::SequencePlayer,
+    statistics::{MissionSummary, Statistics},
+    template_overrides::{OverridesFile, TemplateOverrides},
 };
 use zip_asset_path::ZipAssetPath;
 
+// Number of profiler labels shown in the HUD overlay - the most expensive ones first
+const PROFILER_OVERLAY_LINES: usize = 12;
+
+const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+
+// How long an in-mission cutscene plays before it can be skipped, in seconds - so the button press
+// that started it doesn't skip it right away
+const CUTSCENE_SKIP_DELAY: f32 = 1.0;
+
+///
+/// ActiveCutscene
+///
+/// Cutscene played in the middle of a mission (Effect::PlayCutscene) - the mission is paused
+/// until it's over, and the entity that started it is sent CutsceneCompleted
+struct ActiveCutscene {
+    scene: CutscenePlayerScene,
+    from: EntityId,
+    elapsed: f32,
+}
+
+///
+/// ActiveSequence
+///
+/// Scripted sequence played in the middle of a mission (Effect::PlaySequence) - the mission keeps
+/// running, but the camera is the sequence's, and the entity that started it is sent
+/// CutsceneCompleted once it (and any cutscene after it) is over
+struct ActiveSequence {
+    player: SequencePlayer,
+    from: EntityId,
+}
+
+fn render_profiler_overlay(font: Rc<Box<dyn engine::Font>>) -> Vec<SceneObject> {
+    let mut lines = vec!["label: last / avg / p95 (ms)".to_owned()];
+    lines.extend(
+        engine::profiler::stats()
+            .iter()
+            .take(PROFILER_OVERLAY_LINES)
+            .map(|stats| {
+                format!(
+                    "{}: {:.2} / {:.2} / {:.2}",
+                    stats.label, stats.last_ms, stats.average_ms, stats.p95_ms
+                )
+            }),
+    );
+
+    lines.push(format!(
+        "gpu memory: {:.1} MB",
+        engine::gpu_memory::total_bytes() as f32 / BYTES_PER_MB
+    ));
+    lines.extend(engine::gpu_memory::stats().iter().map(|stats| {
+        format!(
+            "  {:?}: {:.1} MB ({})",
+            stats.kind,
+            stats.bytes as f32 / BYTES_PER_MB,
+            stats.count
+        )
+    }));
+
+    render_overlay_lines(font, &lines, 12.0, 10.0, 10.0)
+}
+
+// Status effects and where damage came from, for the screen HUD
+fn player_status_lines(player_status: &PlayerStatus, view: Matrix4<f32>) -> Vec<String> {
+    let mut lines = player_status
+        .effects()
+        .iter()
+        .map(|effect| format!("{} {:.0}s", effect.kind.label(), effect.remaining.ceil()))
+        .collect::<Vec<_>>();
+    if let Some(source) = player_status.damage_source() {
+        lines.push(format!(
+            "HIT FROM {}",
+            player_status::damage_direction(view, source).to_ascii_uppercase()
+        ));
+    }
+    lines
+}
+
+fn render_overlay_lines(
+    font: Rc<Box<dyn engine::Font>>,
+    lines: &[String],
+    font_size: f32,
+    x: f32,
+    y: f32,
+) -> Vec<SceneObject> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            SceneObject::screen_space_text(
+                line,
+                font.clone(),
+                font_size,
+                0.75,
+                x,
+                y + idx as f32 * font_size * 1.5,
+            )
+        })
+        .collect()
+}
+
 pub fn resource_path(str: &str) -> String {
     paths::data_root().join(str).to_string_lossy().into_owned()
 }
@@ -76,15 +227,36 @@ pub struct GameOptions {
     pub mission: String,
     pub spawn_location: SpawnLocation,
     pub save_file: Option<String>,
+    /// An original System Shock 2 save to continue from - its quest state and inventory are
+    /// carried into `mission`
+    pub import_save: Option<String>,
+    /// Player profile - saves and settings are kept separately per profile
+    pub profile: String,
+    /// Mods to load, in place of the profile's enabled mods
+    pub mods: Option<Vec<String>>,
     pub render_particles: bool,
+    /// Render the player's hands and forearm HUD - photo mode turns this off to hide them
+    pub render_hands: bool,
+    /// Crosshair and hit markers - only for flat-screen runtimes, VR aims with the hands
+    pub reticle: ReticleOptions,
     pub debug_physics: bool,
     pub debug_draw: bool,
     pub debug_portals: bool,
+    /// Start with object labels shown - names and ids over the entities around the player
     pub debug_show_ids: bool,
     pub debug_skeletons: bool,
     pub debug_ai: bool,
     pub debug_pathfinding: bool,
-    pub experimental_features: HashSet<String>,
+    /// Show per-system frame timings from `engine::profiler` as a HUD overlay
+    pub debug_profiler: bool,
+    /// How missions decide what to draw - portal traversal, unless checking it against a reference
+    pub visibility_engine: VisibilityEngineKind,
+    pub experimental_features: ExperimentalFeatures,
+    pub accessibility: AccessibilitySettings,
+    /// Language for UI strings and the original game's string resources (ie, "english", "german")
+    pub language: String,
+    pub physics_config: PhysicsConfig,
+    pub root_motion_config: RootMotionConfig,
 }
 
 impl Default for GameOptions {
@@ -93,6 +265,9 @@ impl Default for GameOptions {
             mission: "earth.mis".to_owned(),
             spawn_location: SpawnLocation::MapDefault,
             save_file: None,
+            import_save: None,
+            profile: profiles::DEFAULT_PROFILE.to_owned(),
+            mods: None,
             debug_draw: false,
             debug_portals: false,
             debug_physics: false,
@@ -100,8 +275,16 @@ impl Default for GameOptions {
             debug_skeletons: false,
             debug_ai: false,
             debug_pathfinding: false,
+            debug_profiler: false,
+            visibility_engine: VisibilityEngineKind::default(),
             render_particles: true,
-            experimental_features: HashSet::new(),
+            render_hands: true,
+            reticle: ReticleOptions::default(),
+            experimental_features: ExperimentalFeatures::default(),
+            accessibility: AccessibilitySettings::default(),
+            language: localization::DEFAULT_LANGUAGE.to_owned(),
+            physics_config: PhysicsConfig::default(),
+            root_motion_config: RootMotionConfig::default(),
         }
     }
 }
@@ -120,8 +303,55 @@ pub struct Game {
     //world: World,
     last_music_cue: Option<String>,
     last_env_sound: Option<String>,
+    ambient_audio: AmbientAudioSystem,
 
     mission_to_save_data: HashMap<String, EntitySaveData>,
+
+    // Background loading of the mission the player is approaching
+    mission_preloader: MissionPreloader,
+
+    profile: PlayerProfile,
+
+    // Mods loaded at startup - changing them takes a restart
+    mod_load_order: ModLoadOrder,
+
+    // Watched for edits, which are applied to the running mission
+    overrides_file: OverridesFile,
+
+    // End-of-mission summary, shown for a little while after a level transition
+    mission_summary: Option<MissionSummary>,
+
+    // Tutorial hints, shown the first time the profile runs into each control
+    hints: HintSystem,
+
+    // Free camera - while active, the simulation is paused
+    photo_mode: Option<PhotoMode>,
+
+    // Camera driven by the runtime (ie, a benchmark flythrough) - the simulation keeps running
+    camera_override: Option<(Vector3<f32>, Quaternion<f32>)>,
+
+    // Hide the hands and HUD - for attract mode playback
+    hud_hidden: bool,
+
+    // In-mission cutscene - while playing, the mission is paused
+    active_cutscene: Option<ActiveCutscene>,
+
+    // In-mission scripted sequence - while playing, it has the camera and the player's actions are
+    // ignored
+    active_sequence: Option<ActiveSequence>,
+
+    // Developer console - commands entered are queued up for the next update
+    console: Console,
+    console_registry: ConsoleRegistry,
+    console_commands: Vec<Box<dyn Command>>,
+
+    // Total game time, following the time scale - the runtime's `Time::total` is wall-clock
+    scaled_total_time: Duration,
+
+    // Crash shield state - see `try_update` / `try_render`
+    frame: u64,
+    active_effects: Vec<Effect>,
+    crash_report: Option<CrashReport>,
 }
 
 impl Game {
@@ -133,6 +363,8 @@ impl Game {
             .unwrap()
             .clone();
 
+        let statistics = self.statistics();
+
         let (current_save_data, held_data) =
             save_load::to_save_data(self.active_game_scene.world());
         game_log!(
@@ -159,6 +391,13 @@ impl Game {
             }
         };
 
+        game_log!(
+            DEBUG,
+            "Switching to {} (preloaded: {})",
+            level_name,
+            self.mission_preloader.is_ready(&level_name)
+        );
+
         let active_mission = Mission::load(
             level_name,
             &mut self.asset_cache,
@@ -170,6 +409,34 @@ impl Game {
             held_data,
             &self.options,
         );
+        active_mission.mission_core.world.add_unique(statistics);
+        self.active_game_scene = Box::new(active_mission);
+        // A sequence only makes sense in the mission it started in
+        self.active_sequence = None;
+    }
+
+    ///
+    /// restart
+    ///
+    /// Start a mission from scratch - no quest state, inventory or level state is carried over
+    pub fn restart(&mut self, level_name: &str, spawn_loc: SpawnLocation) {
+        game_log!(DEBUG, "Restarting in {}", level_name);
+
+        self.mission_to_save_data.clear();
+        self.mission_summary = None;
+        self.active_cutscene = None;
+        self.active_sequence = None;
+        let active_mission = Mission::load(
+            level_name.to_owned(),
+            &mut self.asset_cache,
+            &mut self.audio_context,
+            &self.global_context,
+            spawn_loc,
+            QuestInfo::new(),
+            Box::new(MissionEntityPopulator::create()),
+            HeldItemSaveData::empty(),
+            &self.options,
+        );
         self.active_game_scene = Box::new(active_mission);
     }
 
@@ -179,9 +446,16 @@ impl Game {
         spawn_loc: SpawnLocation,
         entities_to_trigger: Vec<String>,
     ) {
+        let previous_mission = self.active_game_scene.scene_name().to_string();
+
         // First, switch to the new mission
         self.switch_mission(level_name, spawn_loc);
 
+        self.mission_summary = Some(MissionSummary::new(
+            &previous_mission,
+            self.statistics().for_mission(&previous_mission),
+        ));
+
         // Then, queue the entities to be triggered after scripts are initialized
         for entity_name in entities_to_trigger {
             println!("Queueing entity trigger for: {}", entity_name);
@@ -189,6 +463,34 @@ impl Game {
         }
     }
 
+    ///
+    /// statistics
+    ///
+    /// The player's statistics for this game so far
+    pub fn statistics(&self) -> Statistics {
+        self.active_game_scene
+            .world()
+            .borrow::<UniqueView<Statistics>>()
+            .map(|statistics| statistics.clone())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// experimental_features
+    ///
+    /// Every known experimental feature, and whether it's turned on for this game
+    pub fn experimental_features(&self) -> Vec<ExperimentalFeatureInfo> {
+        self.options.experimental_features.describe()
+    }
+
+    ///
+    /// spawn_points
+    ///
+    /// The named spawn points in the current mission - usable as `mission.mis:name`
+    pub fn spawn_points(&self) -> Vec<NamedSpawnPoint> {
+        mission::named_spawn_points(self.active_game_scene.world())
+    }
+
     /// Get access to the world for debugging purposes
     pub fn world(&self) -> &shipyard::World {
         self.active_game_scene.world()
@@ -208,6 +510,14 @@ impl Game {
             .map(|mission| mission as &dyn game_scene::DebuggableScene)
     }
 
+    ///
+    /// state_hash
+    ///
+    /// Hash of the simulation state, for determinism checks - None for scenes that don't support it
+    pub fn state_hash(&self) -> Option<u64> {
+        self.debug_scene().map(|scene| scene.state_hash())
+    }
+
     /// Get mutable access to the debug scene interface if available
     ///
     /// Returns a mutable reference to the current scene as a DebuggableScene
@@ -222,24 +532,61 @@ impl Game {
             .map(|mission| mission as &mut dyn game_scene::DebuggableScene)
     }
 
-    pub fn init(options: GameOptions, bundle_storage: Arc<dyn Storage>) -> Game {
+    pub fn init(mut options: GameOptions, bundle_storage: Arc<dyn Storage>) -> Game {
+        let profile = PlayerProfile::new(&options.profile);
+        profile.ensure_dirs();
+        let profile_settings = profile.load_settings();
+        options
+            .experimental_features
+            .extend(profile_settings.experimental_features);
+        if let Some(accessibility) = profile_settings.accessibility {
+            options.accessibility = accessibility;
+        }
+        if let Some(language) = profile_settings.language {
+            options.language = language;
+        }
+        info!("using profile: {}", profile.name());
+
+        let enabled_mods = options
+            .mods
+            .clone()
+            .unwrap_or(profile_settings.enabled_mods);
+        let mod_load_order =
+            ModManager::scan(paths::data_file_system().as_ref()).resolve(&enabled_mods);
+        for issue in mod_load_order.issues.iter() {
+            warn!("mods: {}", issue);
+        }
+        for package in mod_load_order.active.iter() {
+            info!(
+                "loading mod: {} ({} {})",
+                package.id,
+                package.display_name(),
+                package.manifest.version
+            );
+        }
+
+        // Game data is read through the data file system, so runtimes can serve it from somewhere
+        // other than a plain directory (ie, Android scoped storage)
+        let data_file_system = paths::data_file_system();
         let asset_paths = AssetPath::combine(vec![
-            AssetPath::folder(resource_path("res/mesh")),
+            // Mods, ahead of the game data they replace
+            mod_load_order.asset_path(data_file_system.clone()),
+            FileSystemAssetPath::new("res/mesh".to_owned(), data_file_system.clone()),
             // AssetPath::folder(resource_path("res/mesh/txt16")),
-            AssetPath::folder(resource_path("res/obj")),
+            FileSystemAssetPath::new("res/obj".to_owned(), data_file_system.clone()),
             // AssetPath::folder(resource_path("res/obj/txt16")),
-            ZipAssetPath::new(resource_path("res/obj.crf")),
-            ZipAssetPath::new(resource_path("res/bitmap.crf")),
-            ZipAssetPath::new(resource_path("res/fam.crf")),
-            ZipAssetPath::new(resource_path("res/iface.crf")),
-            ZipAssetPath::new(resource_path("res/intrface.crf")),
-            ZipAssetPath::new(resource_path("res/mesh.crf")),
-            ZipAssetPath::new(resource_path("res/motions.crf")),
-            ZipAssetPath::new(resource_path("res/objicon.crf")),
-            ZipAssetPath::new(resource_path("res/snd.crf")),
-            ZipAssetPath::new(resource_path("res/snd2.crf")),
-            ZipAssetPath::new(resource_path("res/song.crf")),
-            ZipAssetPath::new2(resource_path("res/strings.crf"), false),
+            ZipAssetPath::from_data_file("res/obj.crf", true),
+            ZipAssetPath::from_data_file("res/bitmap.crf", true),
+            ZipAssetPath::from_data_file("res/fam.crf", true),
+            ZipAssetPath::from_data_file("res/iface.crf", true),
+            ZipAssetPath::from_data_file("res/intrface.crf", true),
+            ZipAssetPath::from_data_file("res/mesh.crf", true),
+            ZipAssetPath::from_data_file("res/motions.crf", true),
+            ZipAssetPath::from_data_file("res/objicon.crf", true),
+            ZipAssetPath::from_data_file("res/snd.crf", true),
+            ZipAssetPath::from_data_file("res/snd2.crf", true),
+            ZipAssetPath::from_data_file("res/song.crf", true),
+            ZipAssetPath::from_data_file("res/strings.crf", false),
             // Bundle assets
             BundleAssetPath::new("".to_owned(), bundle_storage),
             // Textures
@@ -253,7 +600,7 @@ impl Game {
             // Animations
             //AssetPath::folder("res/motions".to_owned()),
             // Motion db
-            AssetPath::folder("".to_owned()),
+            FileSystemAssetPath::new("".to_owned(), data_file_system),
             // Audio
             // AssetPath::folder("res/snd".to_owned()),
             // AssetPath::folder("res/snd/amb".to_owned()),
@@ -279,16 +626,20 @@ impl Game {
         let base_path = paths::data_root().to_string_lossy().into_owned();
         let mut asset_cache = AssetCache::new(base_path, asset_paths);
 
+        if let Some(collision_layers) = CollisionLayerMatrix::load(&mut asset_cache) {
+            options.physics_config.collision_layers = collision_layers;
+        }
+
         // TODO: Start ffmpeg stuff
         #[cfg(feature = "ffmpeg")]
         engine_ffmpeg::init().unwrap();
 
         let (properties, links, links_with_data) = dark::properties::get();
 
-        let game_file = File::open(resource_path("shock2.gam")).unwrap();
-        let mut game_reader = BufReader::new(game_file);
+        let mut game_reader =
+            paths::open_data_file("shock2.gam").expect("Unable to open shock2.gam");
 
-        let _strings = asset_cache.get(&STRINGS_IMPORTER, "objname.str");
+        localization::init(&mut asset_cache, &options.language);
 
         // vhot logging:
         // let atek_file = File::open(resource_path("res/obj/ar15_w.bin")).unwrap();
@@ -298,18 +649,36 @@ impl Game {
 
         let gamesys = gamesys::read(&mut game_reader, &links, &links_with_data, &properties);
 
-        let motiondb_file = File::open(resource_path("motiondb.bin")).unwrap();
-        let mut motiondb_reader = BufReader::new(motiondb_file);
-        let motiondb = MotionDB::read(&mut motiondb_reader);
+        let mut motiondb_reader =
+            paths::open_data_file("motiondb.bin").expect("Unable to open motiondb.bin");
+        let mut motiondb = MotionDB::read(&mut motiondb_reader);
+        // Mods can add or retag animations without touching motiondb.bin
+        if let Some(overlay) =
+            asset_cache.get_opt(&MOTIONDB_OVERLAY_IMPORTER, "motiondb_overlay.json")
+        {
+            info!(
+                "applying motion db overlay: {} bindings, {} removed",
+                overlay.bindings.len(),
+                overlay.remove.len()
+            );
+            motiondb.apply_overlay(overlay.as_ref().clone());
+        }
 
         let mut audio_context = AudioContext::new();
 
+        let mut overrides_file = OverridesFile::new(template_overrides::OVERRIDES_FILE);
+        let template_overrides = overrides_file.load().unwrap_or_else(|err| {
+            warn!("Unable to load {}: {}", overrides_file.path(), err);
+            TemplateOverrides::default()
+        });
+
         let global_context = GlobalContext {
             links,
             links_with_data,
             properties,
             motiondb,
             gamesys,
+            template_overrides,
         };
 
         // TEST: Load all missions
@@ -379,6 +748,15 @@ impl Game {
         // );
         // panic!();
 
+        if options.debug_profiler {
+            engine::profiler::set_enabled(true);
+        }
+
+        let hints = HintSystem::load(
+            &mut asset_cache,
+            SeenHints::load(&profile.seen_hints_path()),
+        );
+
         Game {
             asset_cache,
             audio_context,
@@ -386,11 +764,35 @@ impl Game {
             global_context,
             last_music_cue: None,
             last_env_sound: None,
+            ambient_audio: AmbientAudioSystem::new(),
             options,
             mission_to_save_data,
+            mission_preloader: MissionPreloader::new(),
+            profile,
+            mod_load_order,
+            overrides_file,
+            mission_summary: None,
+            hints,
+            photo_mode: None,
+            camera_override: None,
+            hud_hidden: false,
+            active_cutscene: None,
+            active_sequence: None,
+            console: Console::new(),
+            console_registry: ConsoleRegistry::default(),
+            console_commands: Vec::new(),
+            scaled_total_time: Duration::ZERO,
+            frame: 0,
+            active_effects: Vec::new(),
+            crash_report: None,
         }
     }
 
+    ///
+    /// update
+    ///
+    /// Run a frame - processing input, simulating, then preparing to render. See `frame_phase` for
+    /// running the phases separately.
     pub fn update(
         &mut self,
         time: &Time,
@@ -399,40 +801,226 @@ impl Game {
     ) {
         let span = span!(Level::INFO, "update");
         let _enter = span.enter();
+
+        if let Some(simulation_input) = self.process_input(time, input_context, commands) {
+            let simulation_output = self.simulate(simulation_input);
+            self.prepare_render(simulation_output);
+        }
+    }
+
+    ///
+    /// process_input
+    ///
+    /// First phase of a frame: advance paused modes (photo mode, cutscenes), scale time, and turn
+    /// commands into effects. None if nothing should be simulated this frame.
+    pub fn process_input(
+        &mut self,
+        time: &Time,
+        input_context: &input_context::InputContext,
+        commands: Vec<Box<dyn Command>>,
+    ) -> Option<SimulationInput> {
         let delta_time = time.elapsed.as_secs_f32();
         trace!("delta_time: {}", delta_time);
+        self.frame += 1;
+        engine::logging::set_log_frame(self.frame);
+        engine::profiler::end_frame();
+
+        // Photo mode pauses the simulation - only the free camera moves
+        if let Some(photo_mode) = &mut self.photo_mode {
+            photo_mode.update(input_context, delta_time);
+            return None;
+        }
+
+        // ...and so does a cutscene
+        if let Some(cutscene) = &mut self.active_cutscene {
+            cutscene.elapsed += delta_time;
+            cutscene.scene.update(
+                time,
+                input_context,
+                &mut self.asset_cache,
+                &self.options,
+                Vec::new(),
+            );
+
+            let is_skipped = cutscene.elapsed > CUTSCENE_SKIP_DELAY
+                && (input_context.left_hand.a_value > 0.5
+                    || input_context.right_hand.a_value > 0.5);
+            if cutscene.scene.is_finished() || is_skipped {
+                self.finish_cutscene();
+            }
+            return None;
+        }
+
+        if let Some(summary) = &mut self.mission_summary {
+            summary.remaining_time -= delta_time;
+        }
+        if self
+            .mission_summary
+            .as_ref()
+            .is_some_and(|summary| summary.remaining_time <= 0.0)
+        {
+            self.mission_summary = None;
+        }
+
+        // Hints wait until the player has control
+        if self.active_sequence.is_none() {
+            let shown = self
+                .hints
+                .update(delta_time, self.active_game_scene.world());
+            if shown.is_some() {
+                self.hints.seen().save(&self.profile.seen_hints_path());
+            }
+        }
+
+        // A scripted sequence has the camera - the mission keeps running, without the player's
+        // actions
+        let mut input_context = input_context.clone();
+        let mut sequence_effects = Vec::new();
+        if let Some(sequence) = &mut self.active_sequence {
+            let wants_skip =
+                input_context.left_hand.a_value > 0.5 || input_context.right_hand.a_value > 0.5;
+            sequence_effects = sequence.player.update(delta_time, wants_skip);
+            input_context = sequences::without_actions(&input_context);
+            if sequence.player.is_finished() {
+                self.finish_sequence();
+            }
+        }
+
+        // Slow motion / fast-forward: everything past this point sees scaled time
+        let time_scale = self.time_scale();
+        let scaled_elapsed = time.elapsed.mul_f32(time_scale);
+        self.scaled_total_time += scaled_elapsed;
+        let time = Time {
+            elapsed: scaled_elapsed,
+            total: self.scaled_total_time,
+        };
+        self.audio_context.set_speed(time_scale);
 
         // Process commands into effects
-        let mut command_effects = Vec::new();
-        for command in commands {
+        let mut command_effects = sequence_effects;
+        for command in self.console_commands.drain(..).chain(commands) {
             let eff = command.execute(self.active_game_scene.world());
             command_effects.push(eff);
         }
 
-        // Update the scene (handles movement, physics, collision, teleport internally)
-        let effects = self.active_game_scene.update(
+        // Pick up edits to overrides.toml
+        match self.overrides_file.poll() {
+            Some(Ok(overrides)) => {
+                self.console.print(&format!(
+                    "Reloaded {} ({} overrides)",
+                    template_overrides::OVERRIDES_FILE,
+                    overrides.entries().len()
+                ));
+                self.global_context.template_overrides = overrides;
+                command_effects.push(Effect::ReloadTemplateOverrides);
+            }
+            Some(Err(err)) => {
+                warn!(
+                    "Unable to reload {}: {}",
+                    template_overrides::OVERRIDES_FILE,
+                    err
+                );
+                self.console.print(&format!(
+                    "Unable to reload {}: {}",
+                    template_overrides::OVERRIDES_FILE,
+                    err
+                ));
+            }
+            None => (),
+        }
+
+        Some(SimulationInput {
             time,
             input_context,
+            command_effects: Effect::flatten(command_effects),
+        })
+    }
+
+    ///
+    /// simulate
+    ///
+    /// Second phase of a frame: update the scene, and handle the effects it produced
+    pub fn simulate(&mut self, input: SimulationInput) -> SimulationOutput {
+        let SimulationInput {
+            time,
+            input_context,
+            command_effects,
+        } = input;
+
+        // Update the scene (handles movement, physics, collision, teleport internally)
+        let effects = self.active_game_scene.update(
+            &time,
+            &input_context,
             &mut self.asset_cache,
             &self.options,
             command_effects,
         );
 
-        // Handle ambient audio
-        let ambient_state = self.active_game_scene.ambient_audio_state();
-        let listener_position = ambient_state
+        // Stream in any mission the player is approaching
+        let maybe_player_pos = self
+            .active_game_scene
+            .world()
+            .borrow::<UniqueView<PlayerInfo>>()
+            .ok()
+            .map(|player_info| player_info.pos);
+        if let Some(player_pos) = maybe_player_pos {
+            for level_file in approaching_levels(self.active_game_scene.world(), player_pos) {
+                self.mission_preloader.request(&level_file);
+            }
+        }
+        self.mission_preloader.update(&mut self.asset_cache);
+
+        // Keep the start of this frame's effects around for crash reports - bounded, as a runaway
+        // script can emit thousands
+        self.active_effects.clear();
+        self.active_effects.extend(
+            effects
+                .iter()
+                .take(crash_report::MAX_REPORTED_EFFECTS)
+                .cloned(),
+        );
+
+        // Handle global effects
+        let global_effects = self.active_game_scene.handle_effects(
+            effects,
+            &self.global_context,
+            &self.options,
+            &mut self.asset_cache,
+            &mut self.audio_context,
+        );
+
+        for effect in global_effects {
+            self.handle_global_effect(effect);
+        }
+
+        SimulationOutput {
+            head_rotation: input_context.head.rotation,
+        }
+    }
+
+    ///
+    /// prepare_render
+    ///
+    /// Last phase of a frame: music, ambient sounds and the audio listener
+    pub fn prepare_render(&mut self, output: SimulationOutput) {
+        // Hear the frame from the scene the effects left us in - it may be a new mission
+        let maybe_player_info = self
+            .active_game_scene
+            .world()
+            .borrow::<UniqueView<PlayerInfo>>()
+            .ok()
+            .map(|player_info| (player_info.pos, player_info.rotation));
+        let ambient_audio_state = self.active_game_scene.ambient_audio_state();
+        let listener_position = ambient_audio_state
             .as_ref()
             .map(|state| state.player_position)
-            .or_else(|| {
-                self.active_game_scene
-                    .world()
-                    .borrow::<UniqueView<PlayerInfo>>()
-                    .ok()
-                    .map(|player_info| player_info.pos)
-            })
+            .or(maybe_player_info.map(|(player_pos, _)| player_pos))
             .unwrap_or(vec3(0.0, 0.0, 0.0));
+        let listener_orientation = maybe_player_info
+            .map(|(_, player_rotation)| player_rotation * output.head_rotation)
+            .unwrap_or(output.head_rotation);
 
-        if let Some(state) = ambient_state {
+        let ambient_emitters = if let Some(state) = ambient_audio_state {
             if let Some(cue) = state.music_cue {
                 self.update_music_cue_if_necessary(cue);
             }
@@ -442,50 +1030,273 @@ impl Game {
                 self.update_env_sound_if_necessary(resolved);
             }
 
-            let ambient_sounds = state
-                .ambient_emitters
-                .into_iter()
-                .filter_map(|(id, position, schema_name)| {
-                    let asset_name = self.resolve_schema(&schema_name);
-                    let maybe_audio_clip = self
-                        .asset_cache
-                        .get_opt(&AUDIO_IMPORTER, &format!("{asset_name}.wav"));
-                    maybe_audio_clip.map(|clip| (id, position, clip.clone()))
-                })
-                .collect::<Vec<(EntityId, Vector3<f32>, Rc<AudioClip>)>>();
-
-            self.audio_context.update(listener_position, ambient_sounds);
+            state.ambient_emitters
         } else {
-            self.audio_context.update(listener_position, Vec::new());
+            Vec::new()
+        };
+
+        profile!(
+            "audio.update",
+            self.audio_context
+                .update(listener_position, listener_orientation, Vec::new())
+        );
+
+        // Scenes without ambient audio stop any emitters left over from the last one
+        let sound_schema = &self.global_context.gamesys.sound_schema;
+        let asset_cache = &mut self.asset_cache;
+        self.ambient_audio.update(
+            listener_position,
+            &ambient_emitters,
+            &mut self.audio_context,
+            |schema| {
+                let sample = sound_schema
+                    .get_random_sample(schema)
+                    .unwrap_or_else(|| schema.to_owned());
+                asset_cache.get_opt(&AUDIO_IMPORTER, &format!("{sample}.wav"))
+            },
+        );
+    }
+
+    ///
+    /// try_update
+    ///
+    /// Like `update`, but catches panics and turns them into a crash report written to disk.
+    /// Once crashed, the game is no longer updated - the world may be in an inconsistent state.
+    pub fn try_update(
+        &mut self,
+        time: &Time,
+        input_context: &input_context::InputContext,
+        commands: Vec<Box<dyn Command>>,
+    ) -> Result<(), CrashReport> {
+        if let Some(report) = &self.crash_report {
+            return Err(report.clone());
         }
 
-        // Handle global effects
-        let global_effects = self.active_game_scene.handle_effects(
-            effects,
-            &self.global_context,
-            &self.options,
+        let result = crash_report::catch_panic(|| self.update(time, input_context, commands));
+        self.record_crash(result, "update")
+    }
+
+    ///
+    /// try_simulate
+    ///
+    /// Like `try_update`, but without render-prep - for stepping the game headless
+    pub fn try_simulate(
+        &mut self,
+        time: &Time,
+        input_context: &input_context::InputContext,
+        commands: Vec<Box<dyn Command>>,
+    ) -> Result<(), CrashReport> {
+        if let Some(report) = &self.crash_report {
+            return Err(report.clone());
+        }
+
+        let result = crash_report::catch_panic(|| {
+            if let Some(simulation_input) = self.process_input(time, input_context, commands) {
+                self.simulate(simulation_input);
+            }
+        });
+        self.record_crash(result, "simulate")
+    }
+
+    ///
+    /// try_render
+    ///
+    /// Like `render`, but catches panics - see `try_update`
+    pub fn try_render(
+        &mut self,
+    ) -> Result<(Vec<SceneObject>, Vector3<f32>, Quaternion<f32>), CrashReport> {
+        if let Some(report) = &self.crash_report {
+            return Err(report.clone());
+        }
+
+        let result = crash_report::catch_panic(|| self.render());
+        self.record_crash(result, "render")
+    }
+
+    /// The crash report, if `try_update` or `try_render` caught a panic
+    pub fn crash_report(&self) -> Option<&CrashReport> {
+        self.crash_report.as_ref()
+    }
+
+    fn crash_context(&self, phase: &'static str) -> CrashContext {
+        CrashContext {
+            phase,
+            mission: self.active_game_scene.scene_name().to_string(),
+            frame: self.frame,
+            active_effects: self
+                .active_effects
+                .iter()
+                .map(|effect| format!("{effect:?}"))
+                .collect(),
+        }
+    }
+
+    fn record_crash<T>(
+        &mut self,
+        result: Result<T, CaughtPanic>,
+        phase: &'static str,
+    ) -> Result<T, CrashReport> {
+        result.map_err(|caught| {
+            let report = crash_report::report_crash(caught, self.crash_context(phase));
+            self.crash_report = Some(report.clone());
+            report
+        })
+    }
+
+    ///
+    /// profile
+    ///
+    /// The active player profile
+    pub fn profile(&self) -> &PlayerProfile {
+        &self.profile
+    }
+
+    ///
+    /// switch_profile
+    ///
+    /// Change the active profile - subsequent saves and loads go to the new profile's directory
+    pub fn switch_profile(&mut self, name: &str) {
+        let profile = PlayerProfile::new(name);
+        profile.ensure_dirs();
+        info!(
+            "switching profile: {} -> {}",
+            self.profile.name(),
+            profile.name()
+        );
+        let profile_settings = profile.load_settings();
+        self.options
+            .experimental_features
+            .extend(profile_settings.experimental_features);
+        if let Some(accessibility) = profile_settings.accessibility {
+            self.options.accessibility = accessibility;
+        }
+        if let Some(language) = profile_settings.language {
+            localization::init(&mut self.asset_cache, &language);
+            self.options.language = language;
+        }
+        self.hints = HintSystem::load(
             &mut self.asset_cache,
-            &mut self.audio_context,
+            SeenHints::load(&profile.seen_hints_path()),
         );
+        self.options.profile = profile.name().to_owned();
+        self.profile = profile;
+    }
 
-        for effect in global_effects {
-            self.handle_global_effect(effect);
+    ///
+    /// reset_hints
+    ///
+    /// Forget the hints the active profile has been shown, so they come up again
+    fn reset_hints(&mut self) {
+        self.hints.reset();
+        self.hints.seen().save(&self.profile.seen_hints_path());
+        self.console
+            .print(&format!("Hints reset for profile {}", self.profile.name()));
+    }
+
+    ///
+    /// mods
+    ///
+    /// The mods loaded at startup, in load order
+    pub fn mods(&self) -> &ModLoadOrder {
+        &self.mod_load_order
+    }
+
+    fn list_mods(&mut self) {
+        let enabled = self
+            .options
+            .mods
+            .clone()
+            .unwrap_or_else(|| self.profile.load_settings().enabled_mods);
+        for line in ModManager::scan(paths::data_file_system().as_ref()).describe(&enabled) {
+            self.console.print(&line);
+        }
+    }
+
+    fn list_missing_assets(&mut self) {
+        let missing = engine::assets::missing_assets::entries();
+        if missing.is_empty() {
+            self.console.print("No missing assets");
+            return;
+        }
+
+        for asset in missing {
+            self.console.print(&format!(
+                "{:?} {} (requested {} times)",
+                asset.kind, asset.name, asset.requests
+            ));
+        }
+    }
+
+    ///
+    /// set_mod_enabled
+    ///
+    /// Enable or disable a mod for the active profile - it's loaded (or not) on the next start
+    fn set_mod_enabled(&mut self, id: &str, enabled: bool) {
+        let manager = ModManager::scan(paths::data_file_system().as_ref());
+        if enabled && !manager.installed().iter().any(|package| package.id == id) {
+            self.console.print(&format!("No mod '{}' installed", id));
+            return;
+        }
+
+        let mut settings = self.profile.load_settings();
+        settings.enabled_mods.retain(|enabled_id| enabled_id != id);
+        if enabled {
+            settings.enabled_mods.push(id.to_owned());
+        }
+        self.profile.save_settings(&settings);
+
+        let verb = if enabled { "Enabled" } else { "Disabled" };
+        self.console.print(&format!(
+            "{} {} for profile {} - restart to apply",
+            verb,
+            id,
+            self.profile.name()
+        ));
+        if self.options.mods.is_some() {
+            self.console
+                .print("(--mods was passed, so the profile's mods are ignored this session)");
         }
     }
 
     fn save_to_file(&self, file_name: String) {
         let save_data = self.build_save_data();
+        let save_path = self.profile.save_path(&file_name);
         let mut zip_file = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true)
-            .open(file_name)
+            .open(&save_path)
             .unwrap();
         save_data.write(&mut zip_file);
+
+        // Keep the profile's statistics up to date with the latest save
+        match serde_json::to_string_pretty(&save_data.global_data.statistics) {
+            Ok(json) => {
+                if let Err(err) = std::fs::write(self.profile.statistics_path(), json) {
+                    warn!("Unable to write profile statistics: {}", err);
+                }
+            }
+            Err(err) => warn!("Unable to serialize statistics: {}", err),
+        }
+
+        // Dump the log buffer alongside the save, to help correlate bug reports with what happened
+        let log_file_name = format!("{}.log.json", save_path.to_string_lossy());
+        let log_entries = engine::logging::log_entries_since(0);
+        match serde_json::to_string_pretty(&log_entries) {
+            Ok(json) => {
+                if let Err(err) = std::fs::write(&log_file_name, json) {
+                    warn!("Unable to write log dump {}: {}", log_file_name, err);
+                }
+            }
+            Err(err) => warn!("Unable to serialize log dump: {}", err),
+        }
     }
 
     fn load_from_file(&mut self, file_name: String) {
-        let mut file = OpenOptions::new().read(true).open(file_name).unwrap();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(self.profile.save_path(&file_name))
+            .unwrap();
         let save_data = SaveData::read(&mut file);
         let (mission, level_map) = Self::load_from_save_data(
             save_data,
@@ -543,6 +1354,7 @@ impl Game {
             rotation,
             quest_info,
             active_mission: self.active_game_scene.scene_name().to_string(),
+            statistics: self.statistics(),
         };
 
         SaveData {
@@ -555,6 +1367,13 @@ impl Game {
         match global_effect {
             GlobalEffect::Save { file_name } => self.save_to_file(file_name),
             GlobalEffect::Load { file_name } => self.load_from_file(file_name),
+            GlobalEffect::SwitchProfile { name } => self.switch_profile(&name),
+            GlobalEffect::SetTimeScale { scale } => self.set_time_scale(scale),
+            GlobalEffect::SetVisibilityEngine { kind } => self.set_visibility_engine(kind),
+            GlobalEffect::ListMods => self.list_mods(),
+            GlobalEffect::ListMissingAssets => self.list_missing_assets(),
+            GlobalEffect::ResetHints => self.reset_hints(),
+            GlobalEffect::SetModEnabled { id, enabled } => self.set_mod_enabled(&id, enabled),
             GlobalEffect::TransitionLevel {
                 level_file,
                 loc,
@@ -567,6 +1386,8 @@ impl Game {
 
                 self.switch_mission_with_trigger(level_file, spawn_loc, entities_to_trigger);
             }
+            GlobalEffect::PlayCutscene { video_name, from } => self.play_cutscene(video_name, from),
+            GlobalEffect::PlaySequence { sequence, from } => self.play_sequence(sequence, from),
             GlobalEffect::TestReload => {
                 let (position, rotation) = {
                     let player_info = self
@@ -584,16 +1405,286 @@ impl Game {
         }
     }
 
+    ///
+    /// play_cutscene
+    ///
+    /// Pause the mission and play a cutscene - if the video can't be loaded, the
+    /// cutscene is reported as completed right away, so the mission doesn't get stuck
+    fn play_cutscene(&mut self, video_name: String, from: EntityId) {
+        let video_path = scenes::resolve_cutscene_path(&video_name)
+            .to_string_lossy()
+            .into_owned();
+        match CutscenePlayerScene::new(video_name.clone(), video_path, &mut self.audio_context) {
+            Ok(scene) => {
+                self.active_cutscene = Some(ActiveCutscene {
+                    scene,
+                    from,
+                    elapsed: 0.0,
+                });
+            }
+            Err(err) => {
+                warn!("Unable to play cutscene '{}': {}", video_name, err);
+                self.notify_cutscene_completed(from);
+            }
+        }
+    }
+
+    fn finish_cutscene(&mut self) {
+        if let Some(cutscene) = self.active_cutscene.take() {
+            cutscene.scene.stop(&mut self.audio_context);
+            self.notify_cutscene_completed(cutscene.from);
+        }
+    }
+
+    ///
+    /// play_sequence
+    ///
+    /// Hand the camera to a scripted sequence from `sequences.json` - if there's no such sequence,
+    /// it's reported as completed right away, so the mission doesn't get stuck
+    fn play_sequence(&mut self, id: String, from: EntityId) {
+        match sequences::load_sequence(&mut self.asset_cache, &id) {
+            Some(sequence) => {
+                self.active_sequence = Some(ActiveSequence {
+                    player: SequencePlayer::start(sequence, self.active_game_scene.world()),
+                    from,
+                });
+            }
+            None => {
+                warn!("Unable to play sequence '{}': not in sequences.json", id);
+                self.notify_cutscene_completed(from);
+            }
+        }
+    }
+
+    fn finish_sequence(&mut self) {
+        if let Some(sequence) = self.active_sequence.take() {
+            match &sequence.player.sequence().cutscene {
+                Some(video_name) => self.play_cutscene(video_name.clone(), sequence.from),
+                None => self.notify_cutscene_completed(sequence.from),
+            }
+        }
+    }
+
+    fn notify_cutscene_completed(&mut self, from: EntityId) {
+        let global_effects = self.active_game_scene.handle_effects(
+            vec![Effect::Send {
+                msg: Message {
+                    to: from,
+                    payload: MessagePayload::CutsceneCompleted,
+                },
+            }],
+            &self.global_context,
+            &self.options,
+            &mut self.asset_cache,
+            &mut self.audio_context,
+        );
+        for effect in global_effects {
+            self.handle_global_effect(effect);
+        }
+    }
+
+    ///
+    /// set_physics_config
+    ///
+    /// Change the physics tuning at runtime - applied to the active scene, and kept for future missions
+    pub fn set_physics_config(&mut self, config: PhysicsConfig) {
+        self.active_game_scene.set_physics_config(&config);
+        self.options.physics_config = config;
+    }
+
+    ///
+    /// set_visibility_engine
+    ///
+    /// Switch visibility engines at runtime - applied to the active scene, and kept for future missions
+    pub fn set_visibility_engine(&mut self, kind: VisibilityEngineKind) {
+        self.active_game_scene.set_visibility_engine(kind);
+        self.options.visibility_engine = kind;
+    }
+
+    pub fn visibility_engine(&self) -> VisibilityEngineKind {
+        self.options.visibility_engine
+    }
+
+    /// Rendered vs culled entities from the last frame - None if the scene doesn't cull
+    pub fn visibility_stats(&self) -> Option<VisibilityStats> {
+        self.active_game_scene.visibility_stats()
+    }
+
+    /// Resources left behind by deleted entities, and cleaned up - None if the scene doesn't check
+    pub fn resource_leaks(&self) -> Option<ResourceLeaks> {
+        self.active_game_scene.resource_leaks()
+    }
+
+    ///
+    /// collision_layers
+    ///
+    /// Dump of the collision layer matrix - which collider profiles collide with each other
+    pub fn collision_layers(&self) -> CollisionLayerDump {
+        self.options.physics_config.collision_layers.dump()
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.options.accessibility.clamped_time_scale()
+    }
+
+    ///
+    /// set_time_scale
+    ///
+    /// Slow down (below 1) or speed up (above 1) the simulation, clamped to 0.1x - 8x
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.options.accessibility.time_scale =
+            scale.clamp(accessibility::MIN_TIME_SCALE, accessibility::MAX_TIME_SCALE);
+    }
+
     /// Get hand spotlights for enhanced lighting when experimental flag is enabled
     pub fn get_hand_spotlights(&self) -> Vec<engine::scene::light::SpotLight> {
+        if self.active_cutscene.is_some() {
+            return Vec::new();
+        }
         self.active_game_scene.get_hand_spotlights(&self.options)
     }
 
+    ///
+    /// console_input
+    ///
+    /// Feed a key press to the developer console - a submitted line is run as a console command
+    pub fn console_input(&mut self, input: ConsoleInput) {
+        if let Some(line) = self.console.handle_input(input, &self.console_registry) {
+            let _ = self.run_console_command(&line);
+        }
+    }
+
+    pub fn console(&self) -> &Console {
+        &self.console
+    }
+
+    pub fn console_registry(&self) -> &ConsoleRegistry {
+        &self.console_registry
+    }
+
+    ///
+    /// run_console_command
+    ///
+    /// Parse a console line, ie `spawn pistol`, and queue it for the next update. Output also
+    /// goes to the console, so commands sent from the debug runtime show up in-game too.
+    pub fn run_console_command(&mut self, line: &str) -> Result<String, String> {
+        self.console.print(&format!("> {}", line));
+
+        let result = if line.trim().eq_ignore_ascii_case("help") {
+            Ok(self.console_registry.help().join("\n"))
+        } else {
+            self.console_registry.parse_line(line).map(|command| {
+                let message = format!("Queued {:?}", command);
+                self.console_commands.push(command);
+                message
+            })
+        };
+
+        match &result {
+            Ok(message) => self.console.print(message),
+            Err(err) => self.console.print(err),
+        }
+        result
+    }
+
+    ///
+    /// photo_mode_action
+    ///
+    /// Enter / leave photo mode, or apply one of its actions while it's active
+    pub fn photo_mode_action(&mut self, action: PhotoModeAction) {
+        if action == PhotoModeAction::Toggle {
+            self.photo_mode = match self.photo_mode {
+                Some(_) => None,
+                None => self
+                    .active_game_scene
+                    .world()
+                    .borrow::<UniqueView<PlayerInfo>>()
+                    .ok()
+                    .map(|player_info| PhotoMode::drone(player_info.pos, player_info.rotation)),
+            };
+            info!("photo mode: {}", self.photo_mode.is_some());
+        } else if let Some(photo_mode) = &mut self.photo_mode {
+            photo_mode.handle_action(action);
+        }
+    }
+
+    pub fn photo_mode(&self) -> Option<&PhotoMode> {
+        self.photo_mode.as_ref()
+    }
+
+    ///
+    /// take_photo_capture_request
+    ///
+    /// Returns the path to save a photo to, if one was asked for since the last call
+    pub fn take_photo_capture_request(&mut self) -> Option<std::path::PathBuf> {
+        let photo_mode = self.photo_mode.as_mut()?;
+        if photo_mode.take_capture_request() {
+            Some(self.profile.photo_path())
+        } else {
+            None
+        }
+    }
+
+    pub fn player_position(&self) -> Option<Vector3<f32>> {
+        self.active_game_scene
+            .world()
+            .borrow::<UniqueView<PlayerInfo>>()
+            .ok()
+            .map(|player_info| player_info.pos)
+    }
+
+    ///
+    /// set_camera_override
+    ///
+    /// Render from this camera instead of the player's - photo mode still takes priority
+    pub fn set_camera_override(&mut self, camera: Option<(Vector3<f32>, Quaternion<f32>)>) {
+        self.camera_override = camera;
+    }
+
+    pub fn set_hud_hidden(&mut self, hidden: bool) {
+        self.hud_hidden = hidden;
+    }
+
     pub fn render(&mut self) -> (Vec<SceneObject>, Vector3<f32>, Quaternion<f32>) {
+        self.options.render_hands = !self.hud_hidden
+            && self.active_sequence.is_none()
+            && !self
+                .photo_mode
+                .as_ref()
+                .is_some_and(|photo_mode| photo_mode.hide_hands);
+
+        if let Some(cutscene) = &mut self.active_cutscene {
+            return cutscene.scene.render(&mut self.asset_cache, &self.options);
+        }
+
         let (scene, pos, rot) = self
             .active_game_scene
             .render(&mut self.asset_cache, &self.options);
 
+        if let Some(photo_mode) = &self.photo_mode {
+            return (scene, photo_mode.position, photo_mode.rotation());
+        }
+        if let Some((camera_pos, camera_rot)) = self.camera_override {
+            return (scene, camera_pos, camera_rot);
+        }
+        if let Some((camera_pos, camera_rot)) = self
+            .active_sequence
+            .as_ref()
+            .and_then(|sequence| sequence.player.camera())
+        {
+            return (scene, camera_pos, camera_rot);
+        }
+
+        // Apply camera shake before the runtime computes the view
+        let pos = match self
+            .active_game_scene
+            .world()
+            .borrow::<UniqueView<CameraEffects>>()
+        {
+            Ok(camera_effects) => pos + camera_effects.camera_offset(),
+            Err(_) => pos,
+        };
+
         // let font = File::open(resource_path("res/fonts/mainfont.FON")).unwrap();
         // let mut font_reader = BufReader::new(font);
         // let font: Rc<Box<dyn engine::Font>> =
@@ -611,13 +1702,28 @@ impl Game {
         projection: Matrix4<f32>,
         screen_size: Vector2<f32>,
     ) -> Vec<SceneObject> {
+        if self
+            .photo_mode
+            .as_ref()
+            .is_some_and(|photo_mode| photo_mode.hide_hud)
+        {
+            return Vec::new();
+        }
+
+        if self.active_cutscene.is_some() {
+            return Vec::new();
+        }
+
         let hand_material = engine::scene::color_material::create(vec3(1.0, 0.0, 0.0));
         let transform = Matrix4::from_scale(0.25) * Matrix4::from_translation(vec3(0.0, 4.0, 0.0));
         let mut hand_obj = SceneObject::new(hand_material, Box::new(engine::scene::cube::create()));
         hand_obj.set_transform(transform);
 
         // Sample for rendering
-        let font = self.asset_cache.get(&FONT_IMPORTER, "mainfont.fon");
+        let font = self
+            .asset_cache
+            .get(&FONT_IMPORTER, "mainfont.fon")
+            .unwrap();
         // let text_obj_0_0 =
         //     SceneObject::screen_space_text("0, 0", font.clone(), 16.0, 0.5, 0.0, 0.0);
 
@@ -628,6 +1734,10 @@ impl Game {
             screen_size,
             &self.options,
         );
+        objs.extend(
+            self.console
+                .render(&mut self.asset_cache, &self.options.accessibility),
+        );
 
         let world_position = vec3(0.0, 1.0, 0.0);
         let screen_width = screen_size.x;
@@ -655,6 +1765,61 @@ impl Game {
         // Ramsey Recruitment Ctr
         // let text_string = "Ramsey Recruitment Ctr.";
         objs.extend(vec![hand_obj /*  text_obj_dynamic*/]);
+
+        if let Ok(camera_effects) = self
+            .active_game_scene
+            .world()
+            .borrow::<UniqueView<CameraEffects>>()
+        {
+            objs.extend(camera_effects.render_overlays(view));
+        }
+
+        if let Ok(player_status) = self
+            .active_game_scene
+            .world()
+            .borrow::<UniqueView<PlayerStatus>>()
+        {
+            let font_size = self.options.accessibility.scaled_font_size(12.0);
+            objs.extend(render_overlay_lines(
+                font.clone(),
+                &player_status_lines(&player_status, view),
+                font_size,
+                10.0,
+                150.0,
+            ));
+        }
+
+        if self.options.debug_profiler {
+            objs.extend(render_profiler_overlay(font.clone()));
+        }
+
+        if let Some(sequence) = &self.active_sequence {
+            objs.extend(sequences::render_fade(view, sequence.player.fade()));
+        }
+
+        let hint_lines = self.hints.lines();
+        if !hint_lines.is_empty() {
+            let font_size = self.options.accessibility.scaled_font_size(14.0);
+            objs.extend(render_overlay_lines(
+                font.clone(),
+                &hint_lines,
+                font_size,
+                10.0,
+                100.0,
+            ));
+        }
+
+        if let Some(summary) = &self.mission_summary {
+            let font_size = self.options.accessibility.scaled_font_size(12.0);
+            objs.extend(render_overlay_lines(
+                font,
+                &summary.lines(),
+                font_size,
+                10.0,
+                250.0,
+            ));
+        }
+
         objs
     }
 
@@ -664,6 +1829,9 @@ impl Game {
         projection: Matrix4<f32>,
         screen_size: Vector2<f32>,
     ) {
+        if self.active_cutscene.is_some() {
+            return;
+        }
         self.active_game_scene
             .finish_render(&mut self.asset_cache, view, projection, screen_size)
     }
@@ -0,0 +1,142 @@
+///
+/// light_gem.rs
+///
+/// How visible the player is - from the light baked into the cell they're standing in, plus the
+/// glow of any animated lights nearby. AI spot a well-lit player from across the room, but have to
+/// get close to notice one lurking in the shadows. Shown on the arm HUD as the light gem.
+///
+use cgmath::{InnerSpace, Vector3};
+use dark::SCALE_FACTOR;
+use shipyard::{Unique, UniqueView, World};
+
+// Lightmaps are mostly dim - a cell this bright, on average, counts as fully lit
+const FULLY_LIT_LIGHT_LEVEL: f32 = 0.5;
+
+// Even in the dark, the player is a shape - AI right next to them will still notice
+const MIN_VISIBILITY: f32 = 0.1;
+
+// How far an animated light's glow reaches
+const ANIMATED_LIGHT_RADIUS: f32 = 15.0 / SCALE_FACTOR;
+
+// How quickly visibility follows the light, per second - so flickering lights don't make it jitter
+const VISIBILITY_CHANGE_RATE: f32 = 2.0;
+
+// How far away AI can spot a fully lit player, and a player in the dark
+const MAX_SIGHT_DISTANCE: f32 = 120.0 / SCALE_FACTOR;
+const MIN_SIGHT_DISTANCE: f32 = 8.0 / SCALE_FACTOR;
+
+///
+/// light_visibility
+///
+/// How visible a player at `position` is, from 0.0 to 1.0 - given the light level of their cell (if
+/// they're in one), and the positions and intensities (0.0 to 1.0) of animated lights
+pub fn light_visibility(
+    position: Vector3<f32>,
+    cell_light_level: Option<f32>,
+    animated_lights: &[(Vector3<f32>, f32)],
+) -> f32 {
+    // Outside of the world rep (ie, debug scenes), there's nowhere to hide
+    let Some(cell_light_level) = cell_light_level else {
+        return 1.0;
+    };
+
+    let animated_light = animated_lights
+        .iter()
+        .map(|(light_position, intensity)| {
+            let distance = (light_position - position).magnitude();
+            (1.0 - distance / ANIMATED_LIGHT_RADIUS).max(0.0) * intensity
+        })
+        .sum::<f32>();
+
+    (cell_light_level / FULLY_LIT_LIGHT_LEVEL + animated_light).clamp(MIN_VISIBILITY, 1.0)
+}
+
+///
+/// PlayerVisibility
+///
+/// How visible the player is, eased toward the light around them each update
+#[derive(Unique)]
+pub struct PlayerVisibility {
+    visibility: f32,
+}
+
+impl PlayerVisibility {
+    pub fn new() -> PlayerVisibility {
+        PlayerVisibility { visibility: 1.0 }
+    }
+
+    pub fn update(&mut self, target_visibility: f32, delta_time: f32) {
+        let max_change = VISIBILITY_CHANGE_RATE * delta_time;
+        self.visibility += (target_visibility - self.visibility).clamp(-max_change, max_change);
+    }
+
+    /// How visible the player is, from 0.0 (hidden in the dark) to 1.0 (fully lit)
+    pub fn visibility(&self) -> f32 {
+        self.visibility
+    }
+
+    /// How far away AI can see the player
+    pub fn sight_distance(&self) -> f32 {
+        MIN_SIGHT_DISTANCE + (MAX_SIGHT_DISTANCE - MIN_SIGHT_DISTANCE) * self.visibility
+    }
+}
+
+impl Default for PlayerVisibility {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the player's visibility (0.0 to 1.0), for the HUD - fully visible if it isn't tracked
+pub fn get_player_visibility(world: &World) -> f32 {
+    world
+        .borrow::<UniqueView<PlayerVisibility>>()
+        .map(|visibility| visibility.visibility())
+        .unwrap_or(1.0)
+}
+
+/// Get how far away AI can see the player - unlimited if visibility isn't tracked
+pub fn get_player_sight_distance(world: &World) -> f32 {
+    world
+        .borrow::<UniqueView<PlayerVisibility>>()
+        .map(|visibility| visibility.sight_distance())
+        .unwrap_or(f32::INFINITY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::vec3;
+
+    #[test]
+    fn test_light_visibility() {
+        let origin = vec3(0.0, 0.0, 0.0);
+
+        // Dark cells hide the player, lit ones don't, and there's no hiding outside the world rep
+        assert_eq!(light_visibility(origin, Some(0.0), &[]), MIN_VISIBILITY);
+        assert_eq!(light_visibility(origin, Some(0.25), &[]), 0.5);
+        assert_eq!(light_visibility(origin, Some(0.9), &[]), 1.0);
+        assert_eq!(light_visibility(origin, None, &[]), 1.0);
+
+        // A nearby lamp lights the player up, a distant one doesn't
+        let lamp = vec3(ANIMATED_LIGHT_RADIUS * 0.5, 0.0, 0.0);
+        let far_lamp = vec3(ANIMATED_LIGHT_RADIUS * 2.0, 0.0, 0.0);
+        assert_eq!(light_visibility(origin, Some(0.0), &[(lamp, 1.0)]), 0.5);
+        assert_eq!(
+            light_visibility(origin, Some(0.0), &[(far_lamp, 1.0)]),
+            MIN_VISIBILITY
+        );
+    }
+
+    #[test]
+    fn test_visibility_eases_toward_light() {
+        let mut visibility = PlayerVisibility::new();
+        visibility.update(0.0, 0.25);
+        assert_eq!(visibility.visibility(), 0.5);
+        assert!(visibility.sight_distance() < MAX_SIGHT_DISTANCE);
+
+        visibility.update(0.0, 1.0);
+        assert_eq!(visibility.visibility(), 0.0);
+        assert_eq!(visibility.sight_distance(), MIN_SIGHT_DISTANCE);
+    }
+}
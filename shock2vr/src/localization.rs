@@ -0,0 +1,324 @@
+use std::{collections::HashMap, io::Read, sync::RwLock};
+
+use dark::{
+    importers::STRINGS_IMPORTER,
+    log_text::{LogEntry, LogKind, LogTexts},
+};
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use tracing::{info, warn};
+
+pub const DEFAULT_LANGUAGE: &str = "english";
+
+// English UI strings are built in, so there is always something to fall back to -
+// even if the bundle is missing the locale folder
+const ENGLISH_UI_STRINGS: &str = include_str!("../../assets/locale/english/ui.ftl");
+
+// Original string resources (from strings.crf) that are looked up at runtime
+const GAME_STRING_FILES: [&str; 3] = ["objname", "objshort", "objdesc"];
+
+// Each deck's email / log text is in its own string resource - `log1.str` through `log9.str`
+const LOG_DECKS: u32 = 9;
+
+static LOCALIZATION: Lazy<RwLock<Localization>> =
+    Lazy::new(|| RwLock::new(Localization::english()));
+
+fn import_fluent(
+    _name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> String {
+    let mut source = String::new();
+    if let Err(err) = reader.read_to_string(&mut source) {
+        warn!("unable to read fluent file: {}", err);
+    }
+    source
+}
+
+fn process_fluent(
+    source: String,
+    _asset_cache: &mut AssetCache,
+    _config: &(),
+) -> HashMap<String, String> {
+    parse_fluent(&source)
+}
+
+static FLUENT_IMPORTER: Lazy<AssetImporter<String, HashMap<String, String>, ()>> =
+    Lazy::new(|| AssetImporter::define(import_fluent, process_fluent));
+
+///
+/// Localization
+///
+/// The strings for the selected language - our own UI strings, from Fluent files in the bundle
+/// (`locale/<language>/ui.ftl`), and the original game's string resources (`<language>/<file>.str` in strings.crf).
+/// Missing UI strings fall back to English, so a partial translation still works.
+struct Localization {
+    language: String,
+    ui_strings: HashMap<String, String>,
+    fallback_ui_strings: HashMap<String, String>,
+    // String resource file -> (lowercase key -> text)
+    game_strings: HashMap<String, HashMap<String, String>>,
+    log_texts: LogTexts,
+}
+
+impl Localization {
+    fn english() -> Localization {
+        Localization {
+            language: DEFAULT_LANGUAGE.to_owned(),
+            ui_strings: HashMap::new(),
+            fallback_ui_strings: parse_fluent(ENGLISH_UI_STRINGS),
+            game_strings: HashMap::new(),
+            log_texts: LogTexts::new(),
+        }
+    }
+
+    fn load(asset_cache: &mut AssetCache, language: &str) -> Localization {
+        let language = language.to_ascii_lowercase();
+
+        let ui_strings = if language == DEFAULT_LANGUAGE {
+            HashMap::new()
+        } else {
+            let ui_file = format!("locale/{}/ui.ftl", language);
+            match asset_cache.get_opt(&FLUENT_IMPORTER, &ui_file) {
+                Some(strings) => strings.as_ref().clone(),
+                None => {
+                    warn!("no UI strings for language {}, using english", language);
+                    HashMap::new()
+                }
+            }
+        };
+
+        let game_strings = GAME_STRING_FILES
+            .iter()
+            .filter_map(|file| {
+                load_game_strings(asset_cache, &language, file)
+                    .map(|strings| (file.to_string(), strings))
+            })
+            .collect();
+
+        let mut log_texts = LogTexts::new();
+        for deck in 1..=LOG_DECKS {
+            if let Some(strings) =
+                load_game_strings(asset_cache, &language, &format!("log{}", deck))
+            {
+                log_texts.add_deck(deck, &strings);
+            }
+        }
+
+        Localization {
+            language,
+            ui_strings,
+            game_strings,
+            log_texts,
+            ..Localization::english()
+        }
+    }
+
+    fn message(&self, id: &str) -> Option<&String> {
+        self.ui_strings
+            .get(id)
+            .or_else(|| self.fallback_ui_strings.get(id))
+    }
+}
+
+fn load_game_strings(
+    asset_cache: &mut AssetCache,
+    language: &str,
+    file: &str,
+) -> Option<HashMap<String, String>> {
+    // Localized releases keep their string resources in a per-language folder
+    asset_cache
+        .get_opt(&STRINGS_IMPORTER, &format!("{}/{}.str", language, file))
+        .or_else(|| asset_cache.get_opt(&STRINGS_IMPORTER, &format!("{}.str", file)))
+        .map(|strings| strings.as_ref().clone())
+}
+
+///
+/// init
+///
+/// Load the strings for `language` (ie, "english", "french", "german") - replaces any previously loaded language
+pub fn init(asset_cache: &mut AssetCache, language: &str) {
+    let localization = Localization::load(asset_cache, language);
+    info!("using language: {}", localization.language);
+    *LOCALIZATION.write().unwrap() = localization;
+}
+
+pub fn language() -> String {
+    LOCALIZATION.read().unwrap().language.clone()
+}
+
+///
+/// text
+///
+/// A UI string by its Fluent message id - falls back to the id itself if it isn't defined anywhere
+pub fn text(id: &str) -> String {
+    text_with_args(id, &[])
+}
+
+///
+/// text_with_args
+///
+/// A UI string, with `{ $name }` placeables filled in from `args`
+pub fn text_with_args(id: &str, args: &[(&str, String)]) -> String {
+    let localization = LOCALIZATION.read().unwrap();
+    match localization.message(id) {
+        Some(pattern) => format_pattern(pattern, args),
+        None => id.to_owned(),
+    }
+}
+
+///
+/// game_string
+///
+/// Look up `key` in one of the original string resources (ie, `game_string("objname", "wrench")`)
+pub fn game_string(file: &str, key: &str) -> Option<String> {
+    LOCALIZATION
+        .read()
+        .unwrap()
+        .game_strings
+        .get(file)
+        .and_then(|strings| strings.get(&key.to_ascii_lowercase()))
+        .cloned()
+}
+
+///
+/// localize_game_string
+///
+/// Like `game_string`, but falls back to `key` when there's no string for it - handy for
+/// properties that may hold either a resource key or the text itself
+pub fn localize_game_string(file: &str, key: &str) -> String {
+    game_string(file, key).unwrap_or_else(|| key.to_owned())
+}
+
+///
+/// log_text
+///
+/// The subject and text of an email, log, note or video, by deck and number (as in PropLog)
+pub fn log_text(kind: LogKind, deck: u32, number: u32) -> Option<LogEntry> {
+    LOCALIZATION
+        .read()
+        .unwrap()
+        .log_texts
+        .get(kind, deck, number)
+        .cloned()
+}
+
+///
+/// parse_fluent
+///
+/// A small subset of the Fluent syntax - `id = value` messages (with indented continuation lines),
+/// comments, and `{ $variable }` / `{ "literal" }` placeables. Attributes and terms are ignored.
+fn parse_fluent(source: &str) -> HashMap<String, String> {
+    let mut messages = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in source.lines() {
+        let is_continuation = line.starts_with(' ') && !line.trim().is_empty();
+        if is_continuation {
+            if let Some((_, value)) = current.as_mut() {
+                let trimmed = line.trim();
+                if !trimmed.starts_with('.') {
+                    if !value.is_empty() {
+                        value.push('\n');
+                    }
+                    value.push_str(trimmed);
+                }
+            }
+            continue;
+        }
+
+        if let Some((id, value)) = current.take() {
+            messages.insert(id, value);
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+            continue;
+        }
+
+        if let Some((id, value)) = trimmed.split_once('=') {
+            current = Some((id.trim().to_owned(), value.trim().to_owned()));
+        }
+    }
+
+    if let Some((id, value)) = current.take() {
+        messages.insert(id, value);
+    }
+
+    messages
+}
+
+fn format_pattern(pattern: &str, args: &[(&str, String)]) -> String {
+    let mut output = String::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+
+        let expression = rest[start + 1..start + end].trim();
+        if let Some(name) = expression.strip_prefix('$') {
+            match args.iter().find(|(arg, _)| *arg == name) {
+                Some((_, value)) => output.push_str(value),
+                // Like Fluent, show the missing variable rather than dropping it
+                None => output.push_str(&format!("{{${}}}", name)),
+            }
+        } else if expression.len() >= 2 && expression.starts_with('"') && expression.ends_with('"')
+        {
+            output.push_str(&expression[1..expression.len() - 1]);
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fluent() {
+        let messages = parse_fluent(
+            "# comment\n\
+             hello = Hello, { $name }!\n\
+             multi = First line\n    second line\n\
+             -term = ignored\n",
+        );
+
+        assert_eq!(messages.get("hello").unwrap(), "Hello, { $name }!");
+        assert_eq!(messages.get("multi").unwrap(), "First line\nsecond line");
+        assert!(!messages.contains_key("-term"));
+    }
+
+    #[test]
+    fn test_format_pattern() {
+        assert_eq!(
+            format_pattern("Kills: { $count }", &[("count", "3".to_owned())]),
+            "Kills: 3"
+        );
+        assert_eq!(
+            format_pattern(
+                "{ \"  \" }{ $creature }",
+                &[("creature", "Hybrid".to_owned())]
+            ),
+            "  Hybrid"
+        );
+        assert_eq!(format_pattern("Missing { $var }", &[]), "Missing {$var}");
+    }
+
+    #[test]
+    fn test_built_in_english_strings() {
+        assert_eq!(
+            text_with_args("summary-kills", &[("count", "2".to_owned())]),
+            "Kills: 2"
+        );
+        assert_eq!(text("not-a-message"), "not-a-message");
+    }
+}
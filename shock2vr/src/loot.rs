@@ -0,0 +1,86 @@
+// Loot - randomized container / corpse contents, from the gamesys' loot info property. Contents
+// are rolled the first time the container is opened; the property is removed afterwards, so the
+// rolled items are what gets saved (and a reload doesn't reroll them).
+
+use dark::properties::PropLootInfo;
+use rand::{SeedableRng, distributions::WeightedIndex, prelude::Distribution, rngs::StdRng};
+
+use crate::state_hash::StateHasher;
+
+///
+/// loot_seed
+///
+/// Seed for rolling a container's loot - the same container in the same mission always rolls the
+/// same contents, so runs can be replayed (see the determinism tests)
+pub fn loot_seed(level_name: &str, entity_id: u64, template_id: i32) -> u64 {
+    let mut hasher = StateHasher::new();
+    hasher.write_str(&level_name.to_ascii_lowercase());
+    hasher.write_u64(entity_id);
+    hasher.write_u64(template_id as u64);
+    hasher.finish()
+}
+
+///
+/// roll_loot
+///
+/// The archetype names picked for a container - `num_picks` weighted picks, with replacement
+pub fn roll_loot(loot: &PropLootInfo, seed: u64) -> Vec<String> {
+    let Ok(distribution) = WeightedIndex::new(loot.items.iter().map(|item| item.weight)) else {
+        return vec![];
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..loot.num_picks)
+        .map(|_| {
+            loot.items[distribution.sample(&mut rng)]
+                .object_name
+                .clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::LootItem;
+
+    use super::*;
+
+    fn loot(num_picks: u32, items: &[(&str, u32)]) -> PropLootInfo {
+        PropLootInfo {
+            num_picks,
+            worth: 0,
+            items: items
+                .iter()
+                .map(|(name, weight)| LootItem {
+                    object_name: name.to_string(),
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_roll_is_deterministic() {
+        let loot = loot(3, &[("nanites", 5), ("hypo", 2), ("ammo", 1)]);
+        let seed = loot_seed("medsci1.mis", 42, -1234);
+
+        let rolled = roll_loot(&loot, seed);
+        assert_eq!(rolled.len(), 3);
+        assert_eq!(rolled, roll_loot(&loot, seed));
+    }
+
+    #[test]
+    fn test_roll_only_picks_weighted_items() {
+        let loot = loot(10, &[("nanites", 1), ("hypo", 0)]);
+        assert!(
+            roll_loot(&loot, 7)
+                .iter()
+                .all(|item| item.as_str() == "nanites")
+        );
+    }
+
+    #[test]
+    fn test_empty_loot_rolls_nothing() {
+        assert!(roll_loot(&loot(3, &[]), 7).is_empty());
+    }
+}
@@ -1,9 +1,16 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
 
 use crate::{
     creature::get_creature_definition,
-    physics::DynamicPhysicsOptions,
+    entity_tags::{self, EntityTags},
+    mission::PooledEntity,
+    physics::{DynamicPhysicsOptions, PhysicsBodyClass},
     runtime_props::*,
+    template_overrides,
     time::Time,
     util::{get_rotation_from_matrix, has_refs, point3_to_vec3},
 };
@@ -15,20 +22,25 @@ use cgmath::{
 };
 use dark::{
     BitmapAnimation, SCALE_FACTOR,
-    importers::{ANIMATION_CLIP_IMPORTER, BITMAP_ANIMATION_IMPORTER, MODELS_IMPORTER},
+    importers::{
+        ANIMATION_CLIP_IMPORTER, BITMAP_ANIMATION_IMPORTER, MODELS_IMPORTER, process_raw_model,
+        read_raw_model,
+    },
     model::Model,
     motion::AnimationPlayer,
     properties::{
         FrobFlag, InternalPropOriginalModelName, Links, PhysicsModelType, PoseType,
         PropCollisionType, PropCreature, PropCreaturePose, PropFrobInfo, PropHUDSelect,
-        PropHasRefs, PropHitPoints, PropImmobile, PropKeySrc, PropModelName, PropPhysAttr,
-        PropPhysDimensions, PropPhysState, PropPhysType, PropPosition, PropRenderType, PropScale,
-        PropSymName, PropTemplateId, PropTripFlags, RenderType, TemplateLinks, WrappedEntityId,
+        PropHasRefs, PropHitPoints, PropImmobile, PropKeySrc, PropLocked, PropLootInfo,
+        PropModelName, PropPhysAttr, PropPhysDimensions, PropPhysState, PropPhysType, PropPosition,
+        PropRenderType, PropScale, PropScripts, PropSymName, PropTemplateId, PropTripFlags,
+        Property, PropertyMerge, RenderType, TemplateLinks, WrappedEntityId,
     },
     ss2_entity_info,
 };
 use engine::assets::asset_cache::AssetCache;
 use rapier3d::prelude::RigidBodyHandle;
+use rayon::prelude::*;
 use shipyard::{EntitiesView, EntityId, Get, UniqueView, View, ViewMut, World};
 use tracing::warn;
 
@@ -61,9 +73,13 @@ pub fn create_entity_with_position(
     obj_name_map: &HashMap<i32, String>, // name override map
     template_to_entity_id: &HashMap<i32, WrappedEntityId>, // realized entities from level start
     additional_options: CreateEntityOptions,
+    recycled_entity: Option<PooledEntity>,
 ) -> EntityCreationInfo {
-    // Create initial entity
-    let entity_id = world.add_entity(());
+    // Create initial entity - or take over a retired one, which has been stripped of its components
+    let (entity_id, recycled_body) = match recycled_entity {
+        Some(pooled) => (pooled.entity_id, pooled.body),
+        None => (world.add_entity(()), None),
+    };
 
     // Add props, based on inheritance
     initialize_entity_with_props(template_id, entity_info, world, entity_id, obj_name_map);
@@ -126,52 +142,142 @@ pub fn create_entity_with_position(
         template_to_entity_id,
         obj_name_map,
         additional_options,
+        recycled_body,
     )
 }
 
-pub fn initialize_entity(
-    entity_id: EntityId,
-    template_id: i32,
-    world: &mut World,
-    physics: &mut PhysicsWorld,
+///
+/// PreparedEntity
+///
+/// The result of the pure part of entity initialization, computed in parallel by
+/// `prepare_entities` and committed to the world by `commit_prepared_entity`
+pub struct PreparedEntity {
+    pub entity_id: EntityId,
+    pub template_id: i32,
+    pub transform: Option<Matrix4<f32>>,
+    pub sym_name: Option<String>,
+    pub tags: EntityTags,
+}
+
+///
+/// prepare_entities
+///
+/// Resolve the parts of entity initialization that don't need mutable access to the world on
+/// the rayon pool - transforms, names and tags from the template hierarchy, and the raw data of
+/// any models the entities use that aren't loaded yet. The models' GPU resources are `Rc`-shared
+/// and bound to the main thread, so they're created here afterwards, one at a time, and added to
+/// the asset cache for `commit_prepared_entity`. Props are merged in parallel earlier, by the
+/// entity populator (see `merge_entity_props`).
+pub fn prepare_entities(
+    entities: Vec<(EntityId, i32)>,
+    world: &World,
     asset_cache: &mut AssetCache,
-    script_world: &mut ScriptWorld,
     entity_info: &ss2_entity_info::SystemShock2EntityInfo,
-    obj_name_map: &HashMap<i32, String>, // name override map
-    template_to_entity_id: &HashMap<i32, WrappedEntityId>, // realized entities from level start
-    additional_options: CreateEntityOptions,
-) -> EntityCreationInfo {
-    let scale = {
-        let v_scale = world.borrow::<View<PropScale>>().unwrap();
-        v_scale
-            .get(entity_id)
-            .map(|p| p.0)
-            .unwrap_or(vec3(1.0, 1.0, 1.0))
+    obj_name_map: &HashMap<i32, String>,
+) -> Vec<PreparedEntity> {
+    // Snapshot the props we need up front, so the parallel phase doesn't touch the world
+    let (inputs, model_files) = {
+        let (v_position, v_scale, v_model_name) = world
+            .borrow::<(View<PropPosition>, View<PropScale>, View<PropModelName>)>()
+            .unwrap();
+        let mut model_files = HashSet::new();
+        let inputs = entities
+            .into_iter()
+            .map(|(entity_id, template_id)| {
+                let position = v_position.get(entity_id).ok().cloned();
+                let scale = v_scale
+                    .get(entity_id)
+                    .map(|p| p.0)
+                    .unwrap_or(vec3(1.0, 1.0, 1.0));
+                if let Ok(model_name) = v_model_name.get(entity_id) {
+                    model_files.insert(format!("{}.bin", model_name.0.to_ascii_lowercase()));
+                }
+                (entity_id, template_id, position, scale)
+            })
+            .collect::<Vec<_>>();
+        (inputs, model_files)
     };
 
-    let v_position = world.borrow::<View<PropPosition>>().unwrap();
-    let maybe_position = v_position.get(entity_id).cloned();
-    drop(v_position);
+    // The asset cache isn't Send, so the model files are opened here, and read on the rayon pool
+    let model_readers = model_files
+        .into_iter()
+        .filter(|model_file| !asset_cache.is_cached(&MODELS_IMPORTER, model_file))
+        .filter_map(|model_file| {
+            asset_cache
+                .open_reader(&model_file)
+                .map(|reader| (model_file, reader))
+        })
+        .collect::<Vec<_>>();
 
-    if let Ok(position) = maybe_position {
-        let transform = Matrix4::from_translation(position.position)
-            * Matrix4::from(position.rotation)
-            * Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
 
-        world.add_component(entity_id, RuntimePropTransform(transform));
-    };
+    let (raw_models, prepared_entities) = rayon::join(
+        || {
+            model_readers
+                .into_par_iter()
+                .map(|(model_file, mut reader)| {
+                    let raw_model = read_raw_model(&mut reader);
+                    (model_file, raw_model)
+                })
+                .collect::<Vec<_>>()
+        },
+        || {
+            inputs
+                .into_par_iter()
+                .map(|(entity_id, template_id, maybe_position, scale)| {
+                    let transform = maybe_position.map(|position| {
+                        Matrix4::from_translation(position.position)
+                            * Matrix4::from(position.rotation)
+                            * Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+                    });
+
+                    PreparedEntity {
+                        entity_id,
+                        template_id,
+                        transform,
+                        sym_name: resolve_sym_name(template_id, hierarchy, obj_name_map),
+                        tags: entity_tags::tags_for_template(template_id, hierarchy, obj_name_map),
+                    }
+                })
+                .collect::<Vec<_>>()
+        },
+    );
 
-    create_entity_core(
-        entity_id,
-        template_id,
+    for (model_file, raw_model) in raw_models {
+        let model = process_raw_model(&model_file, raw_model, asset_cache);
+        asset_cache.insert(&MODELS_IMPORTER, &model_file, model);
+    }
+
+    prepared_entities
+}
+
+///
+/// commit_prepared_entity
+///
+/// Single-threaded half of entity initialization - adds the prepared props, and creates
+/// models, physics and scripts. Models prepared by `prepare_entities` come straight from the
+/// asset cache here.
+pub fn commit_prepared_entity(
+    prepared: PreparedEntity,
+    world: &mut World,
+    physics: &mut PhysicsWorld,
+    asset_cache: &mut AssetCache,
+    script_world: &mut ScriptWorld,
+) -> EntityCreationInfo {
+    if let Some(transform) = prepared.transform {
+        world.add_component(prepared.entity_id, RuntimePropTransform(transform));
+    }
+
+    finish_entity_core(
+        prepared.entity_id,
+        prepared.template_id,
+        prepared.sym_name,
+        prepared.tags,
         world,
         physics,
         asset_cache,
         script_world,
-        entity_info,
-        template_to_entity_id,
-        obj_name_map,
-        additional_options,
+        None,
     )
 }
 
@@ -186,6 +292,35 @@ pub fn create_entity_core(
     _template_to_entity_id: &HashMap<i32, WrappedEntityId>, // realized entities from level start
     obj_map: &HashMap<i32, String>,
     _additional_options: CreateEntityOptions,
+    recycled_body: Option<RigidBodyHandle>,
+) -> EntityCreationInfo {
+    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+    let sym_name = resolve_sym_name(template_id, hierarchy, obj_map);
+    let tags = entity_tags::tags_for_template(template_id, hierarchy, obj_map);
+
+    finish_entity_core(
+        entity_id,
+        template_id,
+        sym_name,
+        tags,
+        world,
+        physics,
+        asset_cache,
+        script_world,
+        recycled_body,
+    )
+}
+
+fn finish_entity_core(
+    entity_id: EntityId,
+    template_id: i32,
+    sym_name: Option<String>,
+    tags: EntityTags,
+    world: &mut World,
+    physics: &mut PhysicsWorld,
+    asset_cache: &mut AssetCache,
+    script_world: &mut ScriptWorld,
+    recycled_body: Option<RigidBodyHandle>,
 ) -> EntityCreationInfo {
     // Add template id
     world.add_component(entity_id, PropTemplateId { template_id });
@@ -198,7 +333,18 @@ pub fn create_entity_core(
     world.add_component(entity_id, RuntimePropSpawnTimeInSeconds(time_in_seconds));
 
     // Initialize sym name based on level obj map
-    initialize_sym_name_from_obj_map(template_id, entity_id, entity_info, obj_map, world);
+    if let Some(sym_name) = sym_name {
+        world.add_component(entity_id, PropSymName(sym_name));
+    }
+
+    // Tag with gameplay categories, from the archetypes and (inherited) scripts
+    let script_tags = world
+        .borrow::<View<PropScripts>>()
+        .unwrap()
+        .get(entity_id)
+        .map(entity_tags::tags_for_scripts)
+        .unwrap_or(EntityTags::empty());
+    world.add_component(entity_id, RuntimePropEntityTags(tags | script_tags));
 
     // Add links, based on template
     // initialize_links_for_entity(
@@ -230,11 +376,43 @@ pub fn create_entity_core(
 
     // Create physics representation
     let rigid_body = if has_refs(world, entity_id) {
-        create_physics_representation(world, physics, &maybe_just_model.as_ref(), entity_id)
+        create_physics_representation(
+            world,
+            physics,
+            &maybe_just_model.as_ref(),
+            entity_id,
+            recycled_body,
+        )
     } else {
         None
     };
 
+    // Mark surfaces with climbable sides (ladders, pipes, ledges), so the hands can grip them
+    let is_climbable = rigid_body.is_some()
+        && world
+            .borrow::<View<PropPhysAttr>>()
+            .unwrap()
+            .get(entity_id)
+            .map(|phys_attr| phys_attr.climbable != 0)
+            .unwrap_or(false);
+    if is_climbable {
+        world.add_component(entity_id, RuntimePropClimbable);
+    }
+
+    // Mark the alarm lights authored in the levels, so they can follow the security alarm
+    let is_alarm_light = world
+        .borrow::<View<PropSymName>>()
+        .unwrap()
+        .get(entity_id)
+        .map(|sym_name| {
+            let name = sym_name.0.to_ascii_lowercase();
+            name.contains("alarm") && name.contains("light")
+        })
+        .unwrap_or(false);
+    if is_alarm_light {
+        world.add_component(entity_id, RuntimePropAlarmLight);
+    }
+
     //let output_scripts = vec![];
     // Create scripts
     let v_scripts = world
@@ -279,6 +457,16 @@ pub fn create_entity_core(
         processed_scripts.push("internal_keycard".to_owned());
     }
 
+    let v_locked = world.borrow::<View<PropLocked>>().unwrap();
+    if v_locked.get(entity_id).is_ok() {
+        processed_scripts.push("internal_lock".to_owned());
+    }
+
+    let v_loot_info = world.borrow::<View<PropLootInfo>>().unwrap();
+    if v_loot_info.get(entity_id).is_ok() {
+        processed_scripts.push("internal_loot".to_owned());
+    }
+
     // ...and remove any duplicates!
     processed_scripts.sort_unstable();
     processed_scripts.dedup();
@@ -298,24 +486,23 @@ pub fn create_entity_core(
     }
 }
 
-fn initialize_sym_name_from_obj_map(
+///
+/// resolve_sym_name
+///
+/// Find the name for a template from the level obj map - the most specific name in the hierarchy wins
+fn resolve_sym_name(
     template_id: i32,
-    entity: EntityId,
-    entity_info: &ss2_entity_info::SystemShock2EntityInfo,
+    hierarchy: &HashMap<i32, Vec<i32>>,
     obj_map: &HashMap<i32, String>,
-    world: &mut World,
-) {
-    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+) -> Option<String> {
     let mut ancestors = ss2_entity_info::get_ancestors(hierarchy, &template_id);
     ancestors.push(template_id);
 
-    let _template_links = TemplateLinks::empty();
-    for parent_id in ancestors {
-        // Add, override name if specified in name map
-        if let Some(name) = obj_map.get(&parent_id) {
-            world.add_component(entity, PropSymName(name.to_owned()))
-        }
-    }
+    ancestors
+        .iter()
+        .rev()
+        .find_map(|id| obj_map.get(id))
+        .cloned()
 }
 
 fn create_model(
@@ -329,7 +516,7 @@ fn create_model(
         v_prop_model,
         v_creature_pose,
         _v_hasrefs,
-        _v_rendertype,
+        v_rendertype,
         v_scale,
         mut rv_vhots,
     ) = world
@@ -347,11 +534,21 @@ fn create_model(
 
     if let (Ok(pos), Ok(model)) = (v_prop_position.get(entity_id), v_prop_model.get(entity_id)) {
         let model_name = model.0.to_owned();
-        let maybe_model = asset_cache.get_opt(&MODELS_IMPORTER, &format!("{model_name}.BIN"));
-
-        maybe_model.as_ref()?;
-
-        let model = maybe_model.unwrap();
+        let model_file = format!("{model_name}.BIN");
+        let model = match asset_cache.get_opt(&MODELS_IMPORTER, &model_file) {
+            Some(model) => model,
+            // Sprites go by a model name too, and things that aren't drawn don't need a model
+            None if !is_rendered(&v_rendertype, entity_id)
+                || asset_cache
+                    .get_opt(&BITMAP_ANIMATION_IMPORTER, &format!("{model_name}.pcx"))
+                    .is_some() =>
+            {
+                return None;
+            }
+            None => asset_cache
+                .get_or_warn(&MODELS_IMPORTER, &model_file)
+                .unwrap_or_else(|| Rc::new(Model::placeholder())),
+        };
         let model_ref = model.as_ref();
 
         let vhots = model.vhots();
@@ -382,10 +579,16 @@ fn create_model(
             if let Ok(creature_pose) = v_creature_pose.get(entity_id) {
                 // let motion_db = { asset_cache.get(&MOTIONDB_IMPORTER, "motiondb.bin".to_owned()) };
                 // TODO: We can only handle motion name props at the moment..
-                if creature_pose.pose_type.contains(PoseType::MOTION_NAME) {
-                    let motion_name = creature_pose.motion_or_tag_name.to_owned();
-                    let animation_clip =
-                        asset_cache.get(&ANIMATION_CLIP_IMPORTER, &format!("{}_.mc", motion_name));
+                let maybe_animation_clip =
+                    if creature_pose.pose_type.contains(PoseType::MOTION_NAME) {
+                        let motion_name = creature_pose.motion_or_tag_name.to_owned();
+                        asset_cache
+                            .get_or_warn(&ANIMATION_CLIP_IMPORTER, &format!("{}_.mc", motion_name))
+                    } else {
+                        None
+                    };
+                // Without the pose's clip, the model is left in its default pose
+                if let Some(animation_clip) = maybe_animation_clip {
                     let posed_model_ref = &model_ref.pose(&animation_clip);
                     let transformed_model = Model::transform(posed_model_ref, transform);
                     (transformed_model, None)
@@ -413,6 +616,15 @@ fn create_model(
     }
 }
 
+fn is_rendered(v_rendertype: &View<PropRenderType>, entity_id: EntityId) -> bool {
+    v_rendertype
+        .get(entity_id)
+        .map(|render_type| {
+            render_type.0 != RenderType::EditorOnly && render_type.0 != RenderType::NoRender
+        })
+        .unwrap_or(true)
+}
+
 ///
 /// create_bitmap
 ///
@@ -429,11 +641,8 @@ fn create_bitmap(
     if let Ok(model) = v_prop_model.get(entity_id) {
         // We have some sort of model, but need to refine
 
-        if v_rendertype.contains(entity_id) {
-            let render_type = v_rendertype.get(entity_id).unwrap();
-            if render_type.0 == RenderType::EditorOnly || render_type.0 == RenderType::NoRender {
-                return None;
-            };
+        if !is_rendered(&v_rendertype, entity_id) {
+            return None;
         }
 
         let model_name = model.0.to_owned();
@@ -493,28 +702,83 @@ pub fn initialize_entity_with_props(
     obj_name_map: &HashMap<i32, String>,
 ) {
     let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+    let merged_props = merge_template_props(
+        template_id,
+        &entity_info.entity_to_properties,
+        hierarchy,
+        obj_name_map,
+    );
+    add_merged_props(merged_props, world, entity_id);
+}
+
+///
+/// MergedEntityProps
+///
+/// The props for a template, merged down its inheritance hierarchy by `merge_entity_props`
+pub struct MergedEntityProps {
+    ancestors: Vec<i32>,
+    props: PropertyMerge,
+}
+
+///
+/// merge_entity_props
+///
+/// Merge the props for each template down its inheritance hierarchy, on the rayon pool. The
+/// merged props are plain component values, added to the world with `add_merged_props`.
+pub fn merge_entity_props(
+    template_ids: &[i32],
+    entity_info: &ss2_entity_info::SystemShock2EntityInfo,
+    obj_name_map: &HashMap<i32, String>,
+) -> Vec<MergedEntityProps> {
+    let entity_to_properties = &entity_info.entity_to_properties;
+    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+
+    template_ids
+        .par_iter()
+        .map(|template_id| {
+            merge_template_props(*template_id, entity_to_properties, hierarchy, obj_name_map)
+        })
+        .collect()
+}
+
+fn merge_template_props(
+    template_id: i32,
+    entity_to_properties: &HashMap<i32, Vec<Arc<Box<dyn Property>>>>,
+    hierarchy: &HashMap<i32, Vec<i32>>,
+    obj_name_map: &HashMap<i32, String>,
+) -> MergedEntityProps {
     let mut ancestors = ss2_entity_info::get_ancestors(hierarchy, &template_id);
     ancestors.push(template_id);
 
-    world.add_component(entity_id, PropTemplateId { template_id });
+    let mut props = PropertyMerge::new();
+    props.set(PropTemplateId { template_id });
 
-    for parent_id in ancestors {
-        let maybe_parent_props = entity_info.entity_to_properties.get(&parent_id);
-
-        match maybe_parent_props {
-            None => {}
-            Some(props) => {
-                for prop in props {
-                    prop.initialize(world, entity_id)
-                }
+    for parent_id in &ancestors {
+        if let Some(parent_props) = entity_to_properties.get(parent_id) {
+            for prop in parent_props {
+                prop.merge(&mut props);
             }
         }
 
         // Add, override name if specified in name map
-        if let Some(name) = obj_name_map.get(&parent_id) {
-            world.add_component(entity_id, PropSymName(name.to_owned()))
+        if let Some(name) = obj_name_map.get(parent_id) {
+            props.set(PropSymName(name.to_owned()));
         }
     }
+
+    MergedEntityProps { ancestors, props }
+}
+
+///
+/// add_merged_props
+///
+/// Add props merged by `merge_entity_props` to the entity, along with the tuning overrides
+pub fn add_merged_props(merged_props: MergedEntityProps, world: &mut World, entity_id: EntityId) {
+    let MergedEntityProps { ancestors, props } = merged_props;
+    props.add_to_entity(world, entity_id);
+
+    // Tuning values from overrides.toml, on top of the gamesys
+    template_overrides::apply_to_entity(world, entity_id, &ancestors);
     // Augment any props
 
     let maybe_mod = {
@@ -536,7 +800,23 @@ pub fn create_physics_representation(
     physics: &mut PhysicsWorld,
     maybe_model: &Option<&Model>,
     entity_id: EntityId,
+    recycled_body: Option<RigidBodyHandle>,
 ) -> Option<RigidBodyHandle> {
+    // A body retired by an entity of the same template has the same shape - just move it
+    if let Some(handle) = recycled_body {
+        let maybe_pos = world
+            .borrow::<View<PropPosition>>()
+            .unwrap()
+            .get(entity_id)
+            .map(|pos| (pos.position, pos.rotation))
+            .ok();
+        if let Some((position, rotation)) = maybe_pos {
+            if physics.recycle_body(handle, entity_id, position, rotation) {
+                return Some(handle);
+            }
+        }
+    }
+
     let (
         v_pos,
         v_phys_attr,
@@ -576,6 +856,7 @@ pub fn create_physics_representation(
     let dynamics_options = if let Ok(phys_attr) = v_phys_attr.get(entity_id) {
         DynamicPhysicsOptions {
             gravity_scale: phys_attr.gravity_scale,
+            ..DynamicPhysicsOptions::default()
         }
     } else {
         DynamicPhysicsOptions::default()
@@ -634,7 +915,10 @@ pub fn create_physics_representation(
                 //is_sensor,
                 CollisionGroup::entity(),
                 false,
-                dynamics_options,
+                DynamicPhysicsOptions {
+                    body_class: PhysicsBodyClass::Creature,
+                    ..dynamics_options
+                },
             );
             physics.set_enabled_rotations(entity_id, false, false, false);
         } else if frob_info.world_action.contains(FrobFlag::MOVE) {
@@ -762,7 +1046,10 @@ pub fn create_physics_representation(
                     //size,
                     CollisionGroup::entity(),
                     is_sensor,
-                    dynamics_options,
+                    DynamicPhysicsOptions {
+                        body_class: PhysicsBodyClass::Projectile,
+                        ..dynamics_options
+                    },
                 )
             } else {
                 physics.add_kinematic(
@@ -0,0 +1,138 @@
+///
+/// entity_events.rs
+///
+/// Lifecycle events for entities created, destroyed, re-modeled, or given scripts while a mission
+/// runs - so systems tracking entities (GUI, hit boxes) are told about changes, instead of
+/// discovering them by diffing maps like `id_to_model`. A history of recent events is kept for
+/// external observers (ie, the debug runtime), who poll for events after the last one they saw.
+///
+/// Entities populated when the mission loads don't get events - they can be listed directly.
+///
+use std::collections::VecDeque;
+
+use dark::properties::{PropSymName, PropTemplateId};
+use serde::Serialize;
+use shipyard::{EntityId, Get, View, World};
+
+// How many events are kept for observers - older ones are dropped
+const MAX_EVENT_HISTORY: usize = 1024;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntityEventKind {
+    Created,
+    Destroyed,
+    ModelChanged { model_name: String },
+    ScriptAttached { script_name: String },
+}
+
+///
+/// EntityEvent
+///
+/// Something that happened to an entity, with its metadata at the time - a destroyed entity's
+/// name and template can't be looked up afterwards
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EntityEvent {
+    /// Increases with every event, for observers to ask for the events after one they've seen
+    pub sequence: u64,
+    #[serde(skip)]
+    pub entity_id: EntityId,
+    pub id: i32,
+    pub name: Option<String>,
+    pub template_id: Option<i32>,
+    #[serde(flatten)]
+    pub kind: EntityEventKind,
+}
+
+///
+/// EntityEventBus
+///
+/// Events emitted since internal consumers last drained them, and the recent history for observers
+#[derive(Default)]
+pub struct EntityEventBus {
+    next_sequence: u64,
+    pending: Vec<EntityEvent>,
+    history: VecDeque<EntityEvent>,
+}
+
+impl EntityEventBus {
+    pub fn new() -> EntityEventBus {
+        EntityEventBus::default()
+    }
+
+    pub fn emit(&mut self, world: &World, entity_id: EntityId, kind: EntityEventKind) {
+        let (name, template_id) = world.run(
+            |v_sym_name: View<PropSymName>, v_template_id: View<PropTemplateId>| {
+                (
+                    v_sym_name.get(entity_id).ok().map(|name| name.0.clone()),
+                    v_template_id
+                        .get(entity_id)
+                        .ok()
+                        .map(|template_id| template_id.template_id),
+                )
+            },
+        );
+
+        let event = EntityEvent {
+            sequence: self.next_sequence,
+            entity_id,
+            id: entity_id.inner() as i32,
+            name,
+            template_id,
+            kind,
+        };
+        self.next_sequence += 1;
+
+        if self.history.len() >= MAX_EVENT_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(event.clone());
+        self.pending.push(event);
+    }
+
+    /// Take the events emitted since the last drain, for internal consumers
+    pub fn drain(&mut self) -> Vec<EntityEvent> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Recent events with a sequence of at least `sequence` - as far back as the history goes
+    pub fn events_since(&self, sequence: u64) -> Vec<EntityEvent> {
+        self.history
+            .iter()
+            .filter(|event| event.sequence >= sequence)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_and_history() {
+        let mut world = World::new();
+        let entity_id = world.add_entity((PropSymName("Door".to_owned()),));
+
+        let mut events = EntityEventBus::new();
+        events.emit(&world, entity_id, EntityEventKind::Created);
+        events.emit(
+            &world,
+            entity_id,
+            EntityEventKind::ModelChanged {
+                model_name: "door_open".to_owned(),
+            },
+        );
+
+        let drained = events.drain();
+        assert_eq!(drained.len(), 2);
+        assert_eq!(drained[0].name, Some("Door".to_owned()));
+        assert_eq!(drained[0].template_id, None);
+        assert!(events.drain().is_empty());
+
+        // Observers still see drained events
+        let since = events.events_since(1);
+        assert_eq!(since.len(), 1);
+        assert_eq!(since[0].sequence, 1);
+    }
+}
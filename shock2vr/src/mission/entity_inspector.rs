@@ -0,0 +1,485 @@
+use std::{collections::HashMap, fs::File};
+
+use dark::{
+    importers::FONT_IMPORTER,
+    properties::{Links, PropPosition, PropScripts, PropTemplateId},
+};
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use serde_json::Value;
+use shipyard::{EntityId, Get, View, World};
+
+use crate::{
+    accessibility::AccessibilitySettings, input_context::InputContext, scripts::ScriptWorld,
+    util::debug_entity,
+};
+
+// Rebuilding the panel serializes every property, so it's throttled rather than done every frame
+const REFRESH_INTERVAL: f32 = 0.5;
+
+// Long property values (ie, arrays) are cut off so the panel stays readable
+const MAX_VALUE_LENGTH: usize = 60;
+
+const FLOAT_STEP: f64 = 0.1;
+const INTEGER_STEP: f64 = 1.0;
+
+const PANEL_X: f32 = 16.0;
+const PANEL_Y: f32 = 32.0;
+const FONT_SIZE: f32 = 8.0;
+
+///
+/// InspectorAction
+///
+/// Entity inspector controls - runtimes map keys / buttons to these
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InspectorAction {
+    Toggle,
+    /// Inspect whatever the right hand is pointing at
+    Select,
+    NextField,
+    PreviousField,
+    Increase,
+    Decrease,
+}
+
+///
+/// EditableField
+///
+/// A numeric value inside a property's serialized form - ie, `P$HitPoints.hit_points`
+#[derive(Clone, Debug, PartialEq)]
+pub struct EditableField {
+    pub property: String,
+    pub path: Vec<String>,
+    pub value: f64,
+    pub is_integer: bool,
+}
+
+impl EditableField {
+    pub fn label(&self) -> String {
+        format!("{}.{}", self.property, self.path.join("."))
+    }
+
+    ///
+    /// stepped
+    ///
+    /// The value after nudging it up (positive direction) or down. Integers that started out
+    /// non-negative stay non-negative - they're most likely unsigned, and wouldn't deserialize.
+    pub fn stepped(&self, direction: f64) -> f64 {
+        if self.is_integer {
+            let value = self.value + INTEGER_STEP * direction.signum();
+            if self.value >= 0.0 {
+                value.max(0.0)
+            } else {
+                value
+            }
+        } else {
+            self.value + FLOAT_STEP * direction.signum()
+        }
+    }
+}
+
+///
+/// collect_fields
+///
+/// All the numeric leaves of a serialized property. For position, only the position itself is
+/// editable - nudging quaternion components or the cell around isn't useful.
+pub fn collect_fields(property: &str, value: &Value) -> Vec<EditableField> {
+    let mut fields = vec![];
+    collect_fields_recursive(property, value, &mut vec![], &mut fields);
+
+    if property == "P$Position" {
+        fields.retain(|field| field.path.first().map(|s| s.as_str()) == Some("position"));
+    }
+
+    fields
+}
+
+fn collect_fields_recursive(
+    property: &str,
+    value: &Value,
+    path: &mut Vec<String>,
+    fields: &mut Vec<EditableField>,
+) {
+    match value {
+        Value::Number(number) => {
+            if let Some(float) = number.as_f64() {
+                fields.push(EditableField {
+                    property: property.to_owned(),
+                    path: path.clone(),
+                    value: float,
+                    is_integer: !number.is_f64(),
+                });
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map {
+                path.push(key.to_owned());
+                collect_fields_recursive(property, child, path, fields);
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                path.push(idx.to_string());
+                collect_fields_recursive(property, child, path, fields);
+                path.pop();
+            }
+        }
+        _ => (),
+    }
+}
+
+///
+/// set_field
+///
+/// Replace the number at `path` - returns false if the path doesn't lead to a number
+pub fn set_field(value: &mut Value, path: &[String], new_value: f64, is_integer: bool) -> bool {
+    let mut current = value;
+    for key in path {
+        current = match current {
+            Value::Object(map) => match map.get_mut(key) {
+                Some(child) => child,
+                None => return false,
+            },
+            Value::Array(items) => match key.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                Some(child) => child,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+
+    if !current.is_number() {
+        return false;
+    }
+
+    let number = if is_integer {
+        serde_json::Number::from(new_value as i64)
+    } else {
+        match serde_json::Number::from_f64(new_value) {
+            Some(number) => number,
+            None => return false,
+        }
+    };
+    *current = Value::Number(number);
+    true
+}
+
+///
+/// EntityInspector
+///
+/// Developer overlay - point at an entity and pull the right trigger to inspect it. Shows its
+/// properties, links, scripts and the messages its scripts recently received, and lets numeric
+/// properties (including position) be nudged live. The in-game analogue of dark_query's `show`.
+pub struct EntityInspector {
+    active: bool,
+    selected: Option<EntityId>,
+    fields: Vec<EditableField>,
+    field_index: usize,
+    lines: Vec<String>,
+    refresh_timer: f32,
+    last_trigger_pressed: bool,
+}
+
+impl EntityInspector {
+    pub fn new() -> EntityInspector {
+        EntityInspector {
+            active: false,
+            selected: None,
+            fields: vec![],
+            field_index: 0,
+            lines: vec![],
+            refresh_timer: 0.0,
+            last_trigger_pressed: false,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn selected(&self) -> Option<EntityId> {
+        self.selected
+    }
+
+    ///
+    /// update
+    ///
+    /// Returns true when the right trigger was just pulled - the caller should select whatever
+    /// the right hand is pointing at. Also keeps the panel contents fresh.
+    pub fn update(
+        &mut self,
+        world: &World,
+        script_world: &ScriptWorld,
+        input_context: &InputContext,
+        delta_time: f32,
+    ) -> bool {
+        let trigger_pressed = input_context.right_hand.trigger_value > 0.5;
+        let select_requested = self.active && trigger_pressed && !self.last_trigger_pressed;
+        self.last_trigger_pressed = trigger_pressed;
+
+        if !self.active {
+            return false;
+        }
+
+        self.refresh_timer -= delta_time;
+        if self.refresh_timer <= 0.0 {
+            self.refresh(world, script_world);
+        }
+
+        select_requested
+    }
+
+    pub fn toggle(&mut self, script_world: &mut ScriptWorld) {
+        self.active = !self.active;
+        self.refresh_timer = 0.0;
+        if !self.active {
+            self.selected = None;
+            self.fields.clear();
+            self.lines.clear();
+            script_world.watch_entity(None);
+        }
+    }
+
+    pub fn select(
+        &mut self,
+        entity_id: Option<EntityId>,
+        world: &World,
+        script_world: &mut ScriptWorld,
+    ) {
+        if entity_id.is_none() || entity_id == self.selected {
+            return;
+        }
+
+        self.selected = entity_id;
+        self.field_index = 0;
+        script_world.watch_entity(entity_id);
+        self.refresh(world, script_world);
+    }
+
+    pub fn next_field(&mut self) {
+        if !self.fields.is_empty() {
+            self.field_index = (self.field_index + 1) % self.fields.len();
+        }
+    }
+
+    pub fn previous_field(&mut self) {
+        if !self.fields.is_empty() {
+            self.field_index = (self.field_index + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    ///
+    /// adjust
+    ///
+    /// Nudge the current field, writing it back through the property's deserializer. Returns the
+    /// new position if the edit moved the entity - the caller needs to move its physics body too.
+    pub fn adjust(
+        &mut self,
+        direction: f64,
+        world: &mut World,
+        script_world: &ScriptWorld,
+    ) -> Option<PropPosition> {
+        let entity_id = self.selected?;
+        let field = self.fields.get(self.field_index)?.clone();
+
+        let (all_properties, _, _) = dark::properties::get::<File>();
+        let property = all_properties
+            .iter()
+            .find(|prop| prop.name() == field.property)?;
+
+        let mut values = property.serialize(world);
+        let mut value = values.remove(&entity_id.inner())?;
+        if !set_field(
+            &mut value,
+            &field.path,
+            field.stepped(direction),
+            field.is_integer,
+        ) {
+            return None;
+        }
+
+        let identity = HashMap::from([(entity_id, entity_id)]);
+        property.deserialize(
+            &HashMap::from([(entity_id.inner(), value)]),
+            world,
+            &identity,
+        );
+        self.refresh(world, script_world);
+
+        if field.property != "P$Position" {
+            return None;
+        }
+
+        let v_position = world.borrow::<View<PropPosition>>().unwrap();
+        v_position.get(entity_id).ok().cloned()
+    }
+
+    fn refresh(&mut self, world: &World, script_world: &ScriptWorld) {
+        self.refresh_timer = REFRESH_INTERVAL;
+        self.lines.clear();
+        self.fields.clear();
+
+        let Some(entity_id) = self.selected else {
+            self.lines
+                .push("Entity inspector: point at an entity and pull the trigger".to_owned());
+            return;
+        };
+
+        self.lines.push(debug_entity(world, entity_id));
+        if let Ok(template_id) = world
+            .borrow::<View<PropTemplateId>>()
+            .unwrap()
+            .get(entity_id)
+        {
+            self.lines
+                .push(format!("Template: {}", template_id.template_id));
+        }
+
+        self.lines.push("-- Properties".to_owned());
+        let (all_properties, _, _) = dark::properties::get::<File>();
+        let mut properties = all_properties
+            .iter()
+            .filter_map(|prop| {
+                prop.serialize(world)
+                    .remove(&entity_id.inner())
+                    .map(|value| (prop.name(), value))
+            })
+            .collect::<Vec<(String, Value)>>();
+        properties.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, value) in &properties {
+            self.lines
+                .push(format!("{}: {}", name, truncate(&value.to_string())));
+            self.fields.extend(collect_fields(name, value));
+        }
+
+        self.lines.push("-- Links".to_owned());
+        if let Ok(links) = world.borrow::<View<Links>>().unwrap().get(entity_id) {
+            for link in &links.to_links {
+                let target = link
+                    .to_entity_id
+                    .map(|id| debug_entity(world, id.0))
+                    .unwrap_or_else(|| format!("template {}", link.to_template_id));
+                self.lines
+                    .push(truncate(&format!("{:?} -> {}", link.link, target)));
+            }
+        }
+
+        self.lines.push("-- Scripts".to_owned());
+        if let Ok(scripts) = world.borrow::<View<PropScripts>>().unwrap().get(entity_id) {
+            self.lines.push(scripts.scripts.join(", "));
+        }
+
+        self.lines.push("-- Recent messages".to_owned());
+        for message in script_world.watched_messages() {
+            self.lines.push(truncate(message));
+        }
+
+        self.lines
+            .push("-- Edit ([ ] select, - = adjust)".to_owned());
+        self.field_index = self.field_index.min(self.fields.len().saturating_sub(1));
+        for (idx, field) in self.fields.iter().enumerate() {
+            let cursor = if idx == self.field_index { ">" } else { " " };
+            self.lines
+                .push(format!("{} {} = {}", cursor, field.label(), field.value));
+        }
+    }
+
+    pub fn render(
+        &self,
+        asset_cache: &mut AssetCache,
+        accessibility: &AccessibilitySettings,
+    ) -> Vec<SceneObject> {
+        if !self.active {
+            return vec![];
+        }
+
+        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+        let font_size = accessibility.scaled_font_size(FONT_SIZE);
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                SceneObject::screen_space_text(
+                    line,
+                    font.clone(),
+                    font_size,
+                    0.8,
+                    PANEL_X,
+                    PANEL_Y + idx as f32 * font_size * 1.2,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Default for EntityInspector {
+    fn default() -> Self {
+        EntityInspector::new()
+    }
+}
+
+fn truncate(value: &str) -> String {
+    if value.chars().count() <= MAX_VALUE_LENGTH {
+        value.to_owned()
+    } else {
+        format!(
+            "{}...",
+            value.chars().take(MAX_VALUE_LENGTH).collect::<String>()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_collect_fields() {
+        let value = json!({ "hit_points": 10, "name": "grub", "scale": [1.5, 2.0] });
+        let fields = collect_fields("P$Test", &value);
+
+        let labels = fields.iter().map(|f| f.label()).collect::<Vec<String>>();
+        assert_eq!(
+            labels,
+            vec!["P$Test.hit_points", "P$Test.scale.0", "P$Test.scale.1"]
+        );
+        assert!(fields[0].is_integer);
+        assert!(!fields[1].is_integer);
+    }
+
+    #[test]
+    fn test_position_only_exposes_position() {
+        let value = json!({
+            "position": { "x": 1.0, "y": 2.0, "z": 3.0 },
+            "cell": 4,
+            "rotation": { "s": 1.0, "v": { "x": 0.0, "y": 0.0, "z": 0.0 } }
+        });
+        let fields = collect_fields("P$Position", &value);
+        assert_eq!(fields.len(), 3);
+        assert!(fields.iter().all(|f| f.path[0] == "position"));
+    }
+
+    #[test]
+    fn test_set_field() {
+        let mut value = json!({ "a": { "b": [1, 2.5] } });
+        let path = vec!["a".to_owned(), "b".to_owned(), "1".to_owned()];
+        assert!(set_field(&mut value, &path, 3.0, false));
+        assert_eq!(value, json!({ "a": { "b": [1, 3.0] } }));
+
+        assert!(!set_field(&mut value, &["a".to_owned()], 1.0, true));
+        assert!(!set_field(&mut value, &["missing".to_owned()], 1.0, true));
+    }
+
+    #[test]
+    fn test_unsigned_integers_stay_non_negative() {
+        let field = EditableField {
+            property: "P$Test".to_owned(),
+            path: vec!["count".to_owned()],
+            value: 0.0,
+            is_integer: true,
+        };
+        assert_eq!(field.stepped(-1.0), 0.0);
+        assert_eq!(field.stepped(1.0), 1.0);
+    }
+}
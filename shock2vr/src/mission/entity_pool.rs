@@ -0,0 +1,143 @@
+///
+/// entity_pool.rs
+///
+/// Recycles the entities of frequently spawned templates - projectiles, shell casings, effects.
+/// Deleting an entity scans every storage, removing its body scans every physics body, and adding
+/// one rebuilds its colliders, so creating and destroying these every shot hitches during
+/// firefights. Instead, a destroyed entity is stripped of its components and scripts, its body is
+/// disabled, and the next entity of the same template takes over both the entity id and the body.
+///
+/// A recycled entity is reset by creating it from its template again - its props are merged
+/// down the template hierarchy and its scripts are attached fresh, like any other spawn. Retired
+/// entities aren't handed out until the systems tracking entities have seen them destroyed, so
+/// nothing left over from the old entity carries over to the new one.
+///
+use std::collections::HashMap;
+
+use rapier3d::prelude::RigidBodyHandle;
+use shipyard::EntityId;
+
+// How many times a template has to be spawned before its entities are pooled - so entities
+// spawned once or twice (ie, a dropped item) aren't held on to
+const POOL_AFTER_SPAWNS: u32 = 4;
+
+// Most entities kept per template - beyond this, destroyed entities are deleted as usual
+const MAX_POOLED_PER_TEMPLATE: usize = 16;
+
+///
+/// PooledEntity
+///
+/// A retired entity - stripped of its components, and its body (if it had one) disabled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PooledEntity {
+    pub entity_id: EntityId,
+    pub body: Option<RigidBodyHandle>,
+}
+
+///
+/// EntityPool
+///
+/// Retired entities, by template id
+#[derive(Default)]
+pub struct EntityPool {
+    spawn_counts: HashMap<i32, u32>,
+    // Retired since the entity events were last dispatched - held back until `release_retired`
+    pending: Vec<(i32, PooledEntity)>,
+    retired: HashMap<i32, Vec<PooledEntity>>,
+}
+
+impl EntityPool {
+    pub fn new() -> EntityPool {
+        EntityPool::default()
+    }
+
+    pub fn record_spawn(&mut self, template_id: i32) {
+        *self.spawn_counts.entry(template_id).or_insert(0) += 1;
+    }
+
+    /// Whether a destroyed entity of this template should be retired to the pool
+    pub fn has_room(&self, template_id: i32) -> bool {
+        let spawn_count = self.spawn_counts.get(&template_id).copied().unwrap_or(0);
+        let pending = self
+            .pending
+            .iter()
+            .filter(|(pending_template_id, _)| *pending_template_id == template_id)
+            .count();
+        let pooled = self.retired.get(&template_id).map(Vec::len).unwrap_or(0);
+        spawn_count >= POOL_AFTER_SPAWNS && pending + pooled < MAX_POOLED_PER_TEMPLATE
+    }
+
+    pub fn retire(&mut self, template_id: i32, entity: PooledEntity) {
+        self.pending.push((template_id, entity));
+    }
+
+    /// Make the entities retired since the last call available - once their destroyed events
+    /// have been dispatched
+    pub fn release_retired(&mut self) {
+        for (template_id, entity) in self.pending.drain(..) {
+            self.retired.entry(template_id).or_default().push(entity);
+        }
+    }
+
+    /// A retired entity to reuse for a new entity of this template, if there is one
+    pub fn take(&mut self, template_id: i32) -> Option<PooledEntity> {
+        self.retired.get_mut(&template_id).and_then(Vec::pop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shipyard::World;
+
+    use super::*;
+
+    fn pooled_entity(world: &mut World, body_index: u32) -> PooledEntity {
+        PooledEntity {
+            entity_id: world.add_entity(()),
+            body: Some(RigidBodyHandle::from_raw_parts(body_index, 0)),
+        }
+    }
+
+    #[test]
+    fn test_pools_frequent_templates() {
+        let mut world = World::new();
+        let mut pool = EntityPool::new();
+        let entity = pooled_entity(&mut world, 1);
+
+        pool.record_spawn(10);
+        assert!(!pool.has_room(10));
+
+        for _ in 1..POOL_AFTER_SPAWNS {
+            pool.record_spawn(10);
+        }
+        assert!(pool.has_room(10));
+        assert!(!pool.has_room(11));
+
+        pool.retire(10, entity);
+        // Not handed out until the destroyed events have gone out
+        assert_eq!(pool.take(10), None);
+
+        pool.release_retired();
+        assert_eq!(pool.take(11), None);
+        assert_eq!(pool.take(10), Some(entity));
+        assert_eq!(pool.take(10), None);
+    }
+
+    #[test]
+    fn test_caps_pooled_entities() {
+        let mut world = World::new();
+        let mut pool = EntityPool::new();
+        for _ in 0..POOL_AFTER_SPAWNS {
+            pool.record_spawn(10);
+        }
+
+        for index in 0..MAX_POOLED_PER_TEMPLATE {
+            assert!(pool.has_room(10));
+            pool.retire(10, pooled_entity(&mut world, index as u32));
+            if index % 2 == 0 {
+                pool.release_retired();
+            }
+        }
+        assert!(!pool.has_room(10));
+    }
+}
@@ -41,16 +41,15 @@ impl EntityPopulator for MissionEntityPopulator {
             all_entities.push((*template_id, entity))
         }
 
-        // Second pass - hydrate properties
-        for (template_id, _props) in &level_entity_info.entity_to_properties {
-            let entity = template_to_entity_id.get(template_id).unwrap();
-            entity_creator::initialize_entity_with_props(
-                *template_id,
-                gamesys_entity_info,
-                world,
-                entity.0,
-                &obj_name_map,
-            );
+        // Second pass - hydrate properties, merging each entity's props in parallel
+        let template_ids = all_entities
+            .iter()
+            .map(|(template_id, _entity)| *template_id)
+            .collect::<Vec<_>>();
+        let merged_props =
+            entity_creator::merge_entity_props(&template_ids, gamesys_entity_info, obj_name_map);
+        for ((_template_id, entity_id), props) in all_entities.iter().zip(merged_props) {
+            entity_creator::add_merged_props(props, world, *entity_id);
         }
 
         // HACK: If the entity is an 'AI' entity, it is rotated 90 degrees to the left.
@@ -0,0 +1,308 @@
+use cgmath::{Quaternion, Rad, Rotation, Rotation3, Vector2, Vector3, vec3};
+use dark::{
+    importers::FONT_IMPORTER,
+    properties::{PropGunState, PropHitPoints, PropMaxHitPoints, PropObjName, PropTemplateId},
+};
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use shipyard::{EntityId, Get, UniqueView, View, World};
+
+use crate::{accessibility::AccessibilitySettings, localization, mission::GlobalEntityMetadata};
+
+// How far in front of the eyes an examined item is held, and how fast it turns
+const EXAMINE_DISTANCE: f32 = 0.35;
+const SPIN_SPEED: f32 = 0.6;
+
+// Descriptions are wrapped so the panel stays readable
+const MAX_LINE_LENGTH: usize = 48;
+
+const FONT_SIZE: f32 = 10.0;
+
+///
+/// ExamineInfo
+///
+/// What the examine panel shows for an item - its name and description from the string
+/// resources, and its condition / loaded ammo when it has them
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExamineInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub condition: Option<String>,
+    pub ammo: Option<u32>,
+}
+
+impl ExamineInfo {
+    pub fn collect(world: &World, entity_id: EntityId) -> ExamineInfo {
+        let name_key = name_key(world, entity_id);
+        let name = name_key
+            .as_ref()
+            .map(|key| localization::localize_game_string("objname", key))
+            .unwrap_or_else(|| localization::text("examine-unknown-item"));
+        let description = name_key
+            .as_ref()
+            .and_then(|key| localization::game_string("objdesc", key));
+
+        let v_gun_state = world.borrow::<View<PropGunState>>().unwrap();
+        let gun_state = v_gun_state.get(entity_id).ok();
+
+        // Guns wear down as they're used - anything else just has hit points
+        let condition = match gun_state {
+            Some(gun_state) => Some(format!("{:.0}%", gun_state.condition)),
+            None => {
+                let v_hit_points = world.borrow::<View<PropHitPoints>>().unwrap();
+                let v_max_hit_points = world.borrow::<View<PropMaxHitPoints>>().unwrap();
+                match (v_hit_points.get(entity_id), v_max_hit_points.get(entity_id)) {
+                    (Ok(hp), Ok(max_hp)) => {
+                        Some(format!("{} / {}", hp.hit_points, max_hp.hit_points))
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        ExamineInfo {
+            name,
+            description,
+            condition,
+            ammo: gun_state.map(|gun_state| gun_state.ammo_count),
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.name.clone()];
+        if let Some(description) = &self.description {
+            lines.extend(wrap(description, MAX_LINE_LENGTH));
+        }
+        if let Some(condition) = &self.condition {
+            lines.push(localization::text_with_args(
+                "examine-condition",
+                &[("value", condition.clone())],
+            ));
+        }
+        if let Some(ammo) = self.ammo {
+            lines.push(localization::text_with_args(
+                "examine-ammo",
+                &[("count", ammo.to_string())],
+            ));
+        }
+        lines
+    }
+}
+
+// The string resource key for an item's name - from its template's metadata, falling back to
+// the entity's own name property
+fn name_key(world: &World, entity_id: EntityId) -> Option<String> {
+    let from_metadata = world
+        .borrow::<View<PropTemplateId>>()
+        .unwrap()
+        .get(entity_id)
+        .ok()
+        .and_then(|template_id| {
+            let entity_metadata = world.borrow::<UniqueView<GlobalEntityMetadata>>().ok()?;
+            entity_metadata
+                .0
+                .values()
+                .find(|metadata| metadata.template_id == template_id.template_id)
+                .and_then(|metadata| {
+                    metadata
+                        .obj_name
+                        .clone()
+                        .or_else(|| metadata.obj_short_name.clone())
+                })
+        });
+
+    from_metadata
+        .or_else(|| {
+            world
+                .borrow::<View<PropObjName>>()
+                .unwrap()
+                .get(entity_id)
+                .map(|name| name.0.clone())
+                .ok()
+        })
+        .filter(|key| !key.is_empty())
+}
+
+fn wrap(text: &str, max_length: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > max_length {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+///
+/// ExamineMode
+///
+/// Looking closely at a held item. In VR, holding the right B button brings the item up in front
+/// of the face, slowly turning; on desktop, the runtime toggles it with a key. Either way, the
+/// item's details are shown in an overlay until it's released or dropped.
+pub struct ExamineMode {
+    examined: Option<EntityId>,
+    // Started by holding the VR button (so letting go ends it), rather than toggled
+    from_button: bool,
+    button_was_pressed: bool,
+    elapsed: f32,
+}
+
+impl ExamineMode {
+    pub fn new() -> ExamineMode {
+        ExamineMode {
+            examined: None,
+            from_button: false,
+            button_was_pressed: false,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn examined(&self) -> Option<EntityId> {
+        self.examined
+    }
+
+    ///
+    /// toggle
+    ///
+    /// Start examining the held item, or stop if already examining
+    pub fn toggle(&mut self, held_entity: Option<EntityId>) {
+        if self.examined.is_some() {
+            self.examined = None;
+        } else {
+            self.start(held_entity, false);
+        }
+    }
+
+    pub fn update(&mut self, held_entity: Option<EntityId>, button_pressed: bool, delta_time: f32) {
+        if button_pressed && !self.button_was_pressed {
+            self.start(held_entity, true);
+        } else if !button_pressed && self.button_was_pressed && self.from_button {
+            self.examined = None;
+        }
+        self.button_was_pressed = button_pressed;
+
+        // Dropped, thrown or swapped out
+        if self.examined.is_some() && self.examined != held_entity {
+            self.examined = None;
+        }
+
+        self.elapsed += delta_time;
+    }
+
+    fn start(&mut self, held_entity: Option<EntityId>, from_button: bool) {
+        if held_entity.is_some() {
+            self.examined = held_entity;
+            self.from_button = from_button;
+            self.elapsed = 0.0;
+        }
+    }
+
+    ///
+    /// pose
+    ///
+    /// Where the examined item is held - in front of the eyes, turning slowly
+    pub fn pose(
+        &self,
+        eye_position: Vector3<f32>,
+        view_rotation: Quaternion<f32>,
+    ) -> (Vector3<f32>, Quaternion<f32>) {
+        let position =
+            eye_position + view_rotation.rotate_vector(vec3(0.0, 0.0, -EXAMINE_DISTANCE));
+        let spin = Quaternion::from_angle_y(Rad(self.elapsed * SPIN_SPEED));
+        (position, view_rotation * spin)
+    }
+
+    pub fn render(
+        &self,
+        asset_cache: &mut AssetCache,
+        world: &World,
+        screen_size: Vector2<f32>,
+        accessibility: &AccessibilitySettings,
+    ) -> Vec<SceneObject> {
+        let Some(entity_id) = self.examined else {
+            return vec![];
+        };
+
+        let lines = ExamineInfo::collect(world, entity_id).lines();
+        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+        let font_size = accessibility.scaled_font_size(FONT_SIZE);
+        let line_height = font_size * 1.2;
+
+        // Under the item, which sits in the middle of the view
+        let x = screen_size.x * 0.35;
+        let y = screen_size.y * 0.65;
+        lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                SceneObject::screen_space_text(
+                    line,
+                    font.clone(),
+                    font_size,
+                    0.9,
+                    x,
+                    y + idx as f32 * line_height,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Default for ExamineMode {
+    fn default() -> Self {
+        ExamineMode::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(
+            wrap("a standard issue wrench", 12),
+            vec!["a standard", "issue wrench"]
+        );
+        assert!(wrap("  ", 12).is_empty());
+    }
+
+    #[test]
+    fn test_button_examines_while_held() {
+        let mut world = World::new();
+        let item = world.add_entity(());
+        let mut examine = ExamineMode::new();
+
+        examine.update(Some(item), true, 0.1);
+        assert_eq!(examine.examined(), Some(item));
+        examine.update(Some(item), true, 0.1);
+        assert_eq!(examine.examined(), Some(item));
+        examine.update(Some(item), false, 0.1);
+        assert_eq!(examine.examined(), None);
+
+        // Nothing held - nothing to examine
+        examine.update(None, true, 0.1);
+        assert_eq!(examine.examined(), None);
+    }
+
+    #[test]
+    fn test_toggle_ends_when_item_dropped() {
+        let mut world = World::new();
+        let item = world.add_entity(());
+        let mut examine = ExamineMode::new();
+
+        examine.toggle(Some(item));
+        examine.update(Some(item), false, 0.1);
+        assert_eq!(examine.examined(), Some(item));
+
+        examine.update(None, false, 0.1);
+        assert_eq!(examine.examined(), None);
+    }
+}
@@ -0,0 +1,47 @@
+use dark::properties::PropPosition;
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, Get, UniqueView, View, World};
+
+use crate::{
+    entity_tags::{self, EntityTags},
+    mission::{PlayerInfo, SurfaceMaterials},
+    physics::PhysicsWorld,
+    scripts::{Effect, script_util::get_environmental_sound_query},
+};
+
+///
+/// impact_effect
+///
+/// The environmental sound for `entity_id` hitting `with` - the schema is picked from the entity's
+/// class tags along with the material of what it hit. The player and creatures are left out, since
+/// their contact sounds come from footsteps.
+pub fn impact_effect(
+    world: &World,
+    physics: &PhysicsWorld,
+    surface_materials: &SurfaceMaterials,
+    entity_id: EntityId,
+    with: EntityId,
+) -> Option<Effect> {
+    let player_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+    if entity_id == player_id || entity_tags::has_tag(world, entity_id, EntityTags::CREATURE) {
+        return None;
+    }
+
+    let position = {
+        let v_position = world.borrow::<View<PropPosition>>().unwrap();
+        v_position.get(entity_id).ok()?.position
+    };
+
+    let additional_tags = surface_materials
+        .material_of_entity(world, physics, with, position)
+        .map(|material| vec![("material", material)])
+        .unwrap_or_default();
+
+    let query = get_environmental_sound_query(world, entity_id, "collision", additional_tags)?;
+    Some(Effect::PlayEnvironmentalSound {
+        audio_handle: AudioHandle::new(),
+        query,
+        position,
+        entity_id: Some(entity_id),
+    })
+}
@@ -1,23 +1,27 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::File,
-    io::BufReader,
     rc::Rc,
     sync::Arc,
     time::{Duration, SystemTime},
 };
 
-use cgmath::{EuclideanSpace, Zero};
 use cgmath::{
-    InnerSpace, Matrix4, Point3, Quaternion, Rotation, Rotation3, SquareMatrix, Transform, Vector2,
-    Vector3, num_traits::ToPrimitive, vec3,
+    Deg, InnerSpace, Matrix4, Point3, Quaternion, Rad, Rotation, Rotation3, SquareMatrix,
+    Transform, Vector2, Vector3, num_traits::ToPrimitive, vec3,
 };
+use cgmath::{EuclideanSpace, Zero};
 
 use crate::SpawnLocation;
+use crate::experimental::ExperimentalFeature;
 use crate::game_scene::DebuggableScene;
 use crate::mission::CullingInfo;
 use crate::mission::VisibilityEngine;
+use crate::mission::entity_inspector::{EntityInspector, InspectorAction};
+use crate::mission::examine::ExamineMode;
 use crate::mission::pathfinding_debug;
+use crate::mission::{BruteForceVisibilityEngine, VisibilityEngineKind, VisibilityStats};
+use crate::mission::{EntityEvent, EntityEventBus, EntityEventKind};
+use crate::mission::{ResourceCollector, ResourceLeaks, orphaned_entities};
 use crate::pathfinding::{PathfindingService, path_visualization::PathVisualizationSystem};
 use crate::{mission::entity_creator, scripts::AIPropertyUpdate};
 
@@ -26,26 +30,31 @@ use dark::{
     audio::SongPlayer,
     gamesys::Gamesys,
     importers::{ANIMATION_CLIP_IMPORTER, AUDIO_IMPORTER, MODELS_IMPORTER, SONG_IMPORTER},
+    log_text::LogKind,
     mission::{SongParams, room_database::RoomDatabase},
     model::Model,
-    motion::{AnimationEvent, AnimationPlayer, MotionDB, MotionQuery, MotionQueryItem},
+    motion::{
+        AnimationEvent, AnimationMarker, AnimationPlayer, MotionDB, MotionQuery, MotionQueryItem,
+    },
     properties::{
         AmbientSoundFlags, Link, LinkDefinition, LinkDefinitionWithData, Links, PhysicsModelType,
-        PropAIAlertness, PropAIMode, PropAmbientHacked, PropClassTag, PropCreature,
-        PropFrameAnimState, PropHasRefs, PropLocalPlayer, PropModelName, PropMotionActorTags,
-        PropParticleGroup, PropParticleLaunchInfo, PropPhysDimensions, PropPhysInitialVelocity,
-        PropPhysState, PropPhysType, PropPosition, PropRenderType, PropScripts, PropTeleported,
+        PropAIAlertness, PropAIMode, PropAmbientHacked, PropAnimLight, PropClassTag, PropCreature,
+        PropExtraLight, PropFrameAnimState, PropHasRefs, PropLocalPlayer, PropLocked, PropLootInfo,
+        PropModelName, PropMotionActorTags, PropParticleGroup, PropParticleLaunchInfo,
+        PropPhysDimensions, PropPhysInitialVelocity, PropPhysState, PropPhysType, PropPosition,
+        PropRenderType, PropScripts, PropSelfIllumination, PropTeleported, PropTemplateId,
         PropTripFlags, PropertyDefinition, RenderType, ToLink, TripFlags, WrappedEntityId,
     },
     ss2_entity_info::{self, SystemShock2EntityInfo},
     tag_database::{TagQuery, TagQueryItem},
 };
 use engine::{
-    assets::asset_cache::AssetCache,
+    assets::{asset_cache::AssetCache, asset_paths::ReadableAndSeekable, placeholders},
     audio::{AudioChannel, AudioContext, AudioHandle},
     game_log, profile,
     scene::{
-        BillboardMaterial, ParticleSystem, SceneObject, VertexPosition, light::SpotLight, quad,
+        BillboardMaterial, ParticlePool, ParticleSystem, SceneObject, VertexPosition,
+        allocate_particle_budgets, light::SpotLight, quad,
     },
     texture::TextureTrait,
 };
@@ -62,37 +71,92 @@ use tracing::{info, trace, warn};
 
 use crate::{
     GameOptions,
-    creature::{HitBoxManager, RagDollManager, get_creature_definition},
-    game_scene::AmbientAudioState,
-    gui::GuiManager,
-    hud::{draw_item_name, draw_item_outline},
-    input_context::{self, InputContext},
+    accessibility::{AccessibilitySettings, ButtonLatch, ButtonMode},
+    announcements::AnnouncementSystem,
+    camera_effects::CameraEffects,
+    creature::{
+        HeldWeapon, HeldWeaponManager, HitBoxManager, RagDollManager, RootMotionConfig,
+        footstep_effect, get_creature_definition,
+    },
+    energy::{MAX_ENERGY, PlayerEnergy},
+    entity_tags::EntityTags,
+    equipment::{self, PlayerEquipment},
+    factions::FactionRelations,
+    game_scene::{AMBIENT_EXIT_RADIUS_SCALE, AmbientAudioState, AmbientEmitter},
+    gui::{GuiManager, GuiTheme},
+    hazards::HazardResistances,
+    hud::{Reticle, ReticleContext, Subtitles, get_health_percentage},
+    input_context::{self, Hand, InputContext},
+    interaction::InteractionSystem,
     inventory::PlayerInventoryEntity,
-    mission::{SpatialQueryEngine, entity_populator::EntityPopulator},
+    light_gem::{PlayerVisibility, light_visibility},
+    localization, loot,
+    mission::{
+        EntityPool, EntitySpatialHash, PlayerMovement, PlayerMovementSounds, PooledEntity,
+        SoundEmitters, SpatialQueryEngine, SpeechArbiter, SurfaceMaterials,
+        entity_populator::EntityPopulator, impact_effect, movement_sound_effects,
+    },
+    noise::{Noise, NoiseEvents, NoiseKind},
+    object_labels::{self, ObjectLabels},
     physics::{self, PlayerHandle},
+    player_status::PlayerStatus,
     quest_info::QuestInfo,
     runtime_props::{
-        RuntimePropDoNotSerialize, RuntimePropJointTransforms, RuntimePropTransform,
-        RuntimePropVhots,
+        RuntimePropAlarmLight, RuntimePropDoNotSerialize, RuntimePropEntityTags,
+        RuntimePropFaction, RuntimePropJointTransforms, RuntimePropTransform, RuntimePropVhots,
     },
     save_load::HeldItemSaveData,
     scripts::{
         self, Effect, GlobalEffect, Message, MessagePayload,
         internal_fast_projectile::InternalFastProjectileScript,
+        internal_lock_script,
         script_util::{get_all_links_with_template, get_environmental_sound_query},
         speech_registry::SpeechVoiceRegistry,
     },
-    systems::{run_bitmap_animation, run_tweq, turn_off_tweqs, turn_on_tweqs},
+    security::{AlarmChange, SecuritySystem},
+    statistics::{StatisticEvent, Statistics, kill_event},
+    systems::{
+        LightAnimationSystem, run_bitmap_animation, run_light_animation, run_tweq, turn_off_tweqs,
+        turn_on_tweqs, update_attachments,
+    },
     teleport::{TeleportSystem, TeleportUI, TeleportVisualStyle},
+    template_overrides::{self, TemplateOverrides},
+    throw_arc,
     time::Time,
-    util::{get_email_sound_file, has_refs, vec3_to_point3},
-    virtual_hand::{VirtualHand, VirtualHandEffect},
+    util::{
+        get_email_sound_file, get_entity_position, get_position_from_matrix,
+        get_rotation_from_matrix, has_refs, point3_to_vec3, vec3_to_point3,
+    },
+    virtual_hand::{HAND_COLLIDER_RADIUS, VirtualHand, VirtualHandEffect},
     vr_config,
 };
 
 use crate::mission::entity_creator::{CreateEntityOptions, EntityCreationInfo};
 pub use crate::resource_path;
 
+// How far the view drops while crouching
+const CROUCH_VIEW_OFFSET: f32 = 2.5 / dark::SCALE_FACTOR;
+
+// Alarm lights that cast light at once - the renderer has 6 spotlight slots, and the hands may use 2
+const MAX_ALARM_SPOTLIGHTS: usize = 4;
+
+// Peak brightness of an alarm light's spotlight
+const ALARM_SPOTLIGHT_INTENSITY: f32 = 1.5;
+
+// Spotlight slots in the renderer - animated lights fill whatever the alarm and hands leave free
+const MAX_SPOTLIGHTS: usize = 6;
+
+// Brightness of an animated light's spotlight at its max brightness, and how far it reaches
+const ANIMATED_SPOTLIGHT_INTENSITY: f32 = 1.0;
+const ANIMATED_SPOTLIGHT_RANGE: f32 = 8.0;
+
+// Emissivity of a fully self-illuminated object - on top of the ambient term, this draws its texture
+// at full brightness, even in an unlit room
+const FULL_SELF_ILLUMINATION_EMISSIVITY: f32 = 0.5;
+
+// Cap on particles simulated at once, across all particle systems
+const MAX_SIMULATED_PARTICLES: usize = 1024;
+
 #[derive(Unique, Clone)]
 pub struct PlayerInfo {
     pub pos: Vector3<f32>,
@@ -109,9 +173,22 @@ pub struct PlayerInfo {
 pub struct DebugOptions {
     pub debug_ai: bool,
     pub debug_pathfinding: bool,
+    /// Only draw AI debug visuals for these entities - None means all of them
+    pub debug_ai_entities: Option<HashSet<EntityId>>,
 }
 
-#[derive(Unique, Clone)]
+impl DebugOptions {
+    pub fn is_ai_debugged(&self, entity_id: EntityId) -> bool {
+        self.debug_ai
+            && self
+                .debug_ai_entities
+                .as_ref()
+                .map(|entities| entities.contains(&entity_id))
+                .unwrap_or(true)
+    }
+}
+
+#[derive(Unique, Clone, Default)]
 pub struct EffectQueue {
     effects: Vec<Effect>,
 }
@@ -123,12 +200,17 @@ pub struct DebugLine {
     pub remaining_life_in_seconds: f32,
 }
 
+pub struct DebugText {
+    pub position: Point3<f32>,
+    pub text: String,
+    pub remaining_life_in_seconds: f32,
+}
+
 #[derive(Clone)]
 pub struct EntityMetadata {
     pub template_id: i32,
     pub obj_icon: Option<String>,
     pub obj_short_name: Option<String>,
-    #[allow(dead_code)]
     pub obj_name: Option<String>,
 }
 
@@ -159,7 +241,9 @@ pub struct MissionCore {
     pub gui: GuiManager,
     pub hit_boxes: HitBoxManager,
     pub rag_doll_manager: RagDollManager,
+    pub held_weapons: HeldWeaponManager,
     pub debug_lines: Vec<DebugLine>,
+    pub debug_texts: Vec<DebugText>,
     pub entity_info: Arc<SystemShock2EntityInfo>,
     pub physics: PhysicsWorld,
     pub script_world: ScriptWorld,
@@ -169,6 +253,7 @@ pub struct MissionCore {
     pub id_to_bitmap: HashMap<EntityId, Rc<BitmapAnimation>>,
     pub id_to_physics: HashMap<EntityId, RigidBodyHandle>,
     pub id_to_particle_system: HashMap<EntityId, ParticleSystem>,
+    particle_pool: ParticlePool,
     #[allow(dead_code)]
     pub template_to_entity_id: HashMap<i32, WrappedEntityId>,
     pub template_name_to_template_id: HashMap<String, EntityMetadata>,
@@ -178,21 +263,48 @@ pub struct MissionCore {
     pub spatial_data: Option<Box<dyn SpatialQueryEngine>>,
     pub left_hand: VirtualHand,
     pub right_hand: VirtualHand,
+    // What each hand would interact with - drives the outlines, prompts and frobbing
+    interaction_system: InteractionSystem,
+    // Kinematic colliders following the hands, for pushing props
+    left_hand_body: RigidBodyHandle,
+    right_hand_body: RigidBodyHandle,
+    // Player movement from hands gripping climbable surfaces, applied on the next physics update
+    climb_movement: Vector3<f32>,
     pub visibility_engine: Box<dyn VisibilityEngine>,
+    // What's in the view frustum, to check the visibility engine against
+    visibility_reference: BruteForceVisibilityEngine,
+    visibility_stats: VisibilityStats,
     pub teleport_system: TeleportSystem,
     pub pending_entity_triggers: Vec<String>,
     pub path_database: Option<dark::mission::PathDatabase>,
     pub pathfinding_service: Option<PathfindingService>,
     pub path_visualization: PathVisualizationSystem,
     pub pathfinding_test: crate::mission::pathfinding_test::PathfindingTest,
+    pub entity_inspector: EntityInspector,
+    examine: ExamineMode,
+    pub root_motion: RootMotionConfig,
+    pub surface_materials: SurfaceMaterials,
+    left_grab: ButtonLatch,
+    right_grab: ButtonLatch,
+    crouch: ButtonLatch,
+    announcements: AnnouncementSystem,
+    sound_emitters: SoundEmitters,
+    speech_arbiter: SpeechArbiter,
+    movement_sounds: PlayerMovementSounds,
+    entity_events: EntityEventBus,
+    resource_collector: ResourceCollector,
+    entity_pool: EntityPool,
+    reticle: Reticle,
 }
 
 pub struct GlobalContext {
-    pub properties: Vec<Box<dyn PropertyDefinition<BufReader<File>>>>,
+    pub properties: Vec<Box<dyn PropertyDefinition<Box<dyn ReadableAndSeekable>>>>,
     pub links: Vec<Box<dyn LinkDefinition>>,
     pub links_with_data: Vec<Box<dyn LinkDefinitionWithData>>,
     pub gamesys: Gamesys,
     pub motiondb: MotionDB,
+    // Tuning values from overrides.toml - see `template_overrides`
+    pub template_overrides: TemplateOverrides,
 }
 
 pub struct AbstractMission {
@@ -200,6 +312,7 @@ pub struct AbstractMission {
     pub song_params: SongParams,
     pub room_db: RoomDatabase,
     pub physics_geometry: Option<Collider>,
+    pub surface_materials: SurfaceMaterials,
     pub spatial_data: Option<Box<dyn SpatialQueryEngine>>,
     pub entity_info: SystemShock2EntityInfo,
     pub obj_map: HashMap<i32, String>,
@@ -248,13 +361,27 @@ impl MissionCore {
 
         world.add_unique(GlobalEntityMetadata(template_name_to_template_id.clone()));
         world.add_unique(Time::default());
+        world.add_unique(CameraEffects::new());
+        world.add_unique(SecuritySystem::new());
+        world.add_unique(EntitySpatialHash::new());
+        world.add_unique(LightAnimationSystem::new());
+        world.add_unique(Subtitles::new());
+        world.add_unique(NoiseEvents::new());
+        world.add_unique(PlayerVisibility::new());
+        world.add_unique(PlayerStatus::new());
+        world.add_unique(HazardResistances::new());
+        world.add_unique(FactionRelations::new());
+        world.add_unique(GuiTheme::load(asset_cache));
         world.add_unique(speech_registry);
         world.add_unique(DebugOptions {
             debug_ai: game_options.debug_ai,
             debug_pathfinding: game_options.debug_pathfinding,
+            debug_ai_entities: None,
         });
+        world.add_unique(ObjectLabels::new(game_options.debug_show_ids));
         let template_class_tags = create_template_class_tag_map(&entity_info_rc);
         world.add_unique(GlobalTemplateClassTags(template_class_tags));
+        world.add_unique(global_context.template_overrides.clone());
 
         // ** Entity creation
 
@@ -268,7 +395,7 @@ impl MissionCore {
         // Instantiate held items
         let mut left_hand = VirtualHand::new(vr_config::Handedness::Left);
         let mut right_hand = VirtualHand::new(vr_config::Handedness::Right);
-        let (left_hand_entity, right_hand_entity, maybe_inventory_entity) =
+        let (left_hand_entity, right_hand_entity, maybe_inventory_entity, equipped_entities) =
             held_item_save_data.instantiate(&mut world);
 
         // Instantiate inventory
@@ -311,7 +438,7 @@ impl MissionCore {
             },
         );
 
-        let mut physics = PhysicsWorld::new();
+        let mut physics = PhysicsWorld::with_config(game_options.physics_config.clone());
         let mut id_to_physics = HashMap::new();
         let mut id_to_bitmap = HashMap::new();
         let mut script_world = ScriptWorld::new();
@@ -320,20 +447,32 @@ impl MissionCore {
         if let Some(collider) = abstract_mission.physics_geometry {
             physics.add_collider(world_entity_id, collider);
         }
+        let mut surface_materials = abstract_mission.surface_materials;
+        surface_materials.set_level_entity(world_entity_id);
+
+        // Finally, instantiate these entities - resolve transforms, names, tags and raw models in
+        // parallel, and then create the model GPU resources, physics and scripts one at a time
+        let prepare_start = SystemTime::now();
+        let prepared_entities = entity_creator::prepare_entities(
+            entities_to_instantiate.into_iter().collect(),
+            &world,
+            asset_cache,
+            &entity_info_rc,
+            &abstract_mission.obj_map,
+        );
+        info!(
+            "preparing {} entities took {}s",
+            prepared_entities.len(),
+            prepare_start.elapsed().unwrap_or_default().as_secs_f32()
+        );
 
-        // Finally, instantiate these entities
-        for (entity_id, template_id) in entities_to_instantiate {
-            let created_entity = entity_creator::initialize_entity(
-                entity_id,
-                template_id,
+        for prepared_entity in prepared_entities {
+            let created_entity = entity_creator::commit_prepared_entity(
+                prepared_entity,
                 &mut world,
                 &mut physics,
                 asset_cache,
                 &mut script_world,
-                &entity_info_rc,
-                &abstract_mission.obj_map,
-                &template_to_entity_id,
-                CreateEntityOptions::default(),
             );
 
             Self::finish_instantiating_entity(
@@ -348,6 +487,8 @@ impl MissionCore {
                 Matrix4::identity(),
             );
         }
+        // Entities populated at load don't get lifecycle events
+        script_world.take_attached_scripts();
 
         // If the player is holding anything, we should un-physical it
 
@@ -370,6 +511,8 @@ impl MissionCore {
         );
 
         let player_handle = physics.create_player(start_pos, player_entity);
+        let left_hand_body = physics.create_hand(start_pos, HAND_COLLIDER_RADIUS);
+        let right_hand_body = physics.create_hand(start_pos, HAND_COLLIDER_RADIUS);
 
         world.add_unique(PlayerInfo {
             rotation: start_rotation,
@@ -380,14 +523,30 @@ impl MissionCore {
             inventory_entity_id: inventory,
         });
 
+        // Put the equipment carried over from the last level (or save) back on
+        let mut player_equipment = PlayerEquipment::new();
+        for (slot, entity_id) in equipped_entities {
+            let modifiers = equipment::modifiers_for(&world, entity_id, slot.kind());
+            player_equipment.equip_in(slot, entity_id, modifiers);
+        }
+        world.add_unique(player_equipment);
+        world.add_unique(PlayerEnergy::with_energy(
+            held_item_save_data.energy.unwrap_or(MAX_ENERGY),
+        ));
+        equipment::apply_modifiers(&world);
+
         world.add_unique(quest_info);
+        world.add_unique(Statistics::default());
 
         world.add_unique(EffectQueue {
             effects: Vec::new(),
         });
 
         // Initialize teleport system based on game options
-        let teleport_system = if game_options.experimental_features.contains("teleport") {
+        let teleport_system = if game_options
+            .experimental_features
+            .contains(ExperimentalFeature::Teleport)
+        {
             let teleport_config = crate::teleport::TeleportConfig {
                 enabled: true,
                 button_mapping: crate::teleport::TeleportButton::Trigger,
@@ -404,9 +563,15 @@ impl MissionCore {
             TeleportSystem::new(teleport_config)
         };
 
+        let announcements = AnnouncementSystem::load(asset_cache, &mission);
+
         MissionCore {
             left_hand,
             right_hand,
+            interaction_system: InteractionSystem::new(),
+            left_hand_body,
+            right_hand_body,
+            climb_movement: vec3(0.0, 0.0, 0.0),
             level_name: mission,
             entity_info: entity_info_rc.clone(),
             script_world,
@@ -414,6 +579,7 @@ impl MissionCore {
             id_to_animation_player,
             id_to_bitmap,
             id_to_particle_system: HashMap::new(),
+            particle_pool: ParticlePool::new(),
             template_name_to_template_id,
             scene_objects: scene,
             physics,
@@ -423,10 +589,14 @@ impl MissionCore {
             player_handle,
             spatial_data: abstract_mission.spatial_data,
             debug_lines: Vec::new(),
+            debug_texts: Vec::new(),
             gui: GuiManager::new(),
             hit_boxes: HitBoxManager::new(),
             rag_doll_manager: RagDollManager::new(),
+            held_weapons: HeldWeaponManager::new(),
             visibility_engine: abstract_mission.visibility_engine,
+            visibility_reference: BruteForceVisibilityEngine::new(),
+            visibility_stats: VisibilityStats::default(),
             teleport_system,
             pending_entity_triggers: Vec::new(),
             obj_map: abstract_mission.obj_map,
@@ -437,6 +607,21 @@ impl MissionCore {
                 .map(|db| PathfindingService::new(Arc::new(db.clone()))),
             path_visualization: PathVisualizationSystem::new(),
             pathfinding_test: crate::mission::pathfinding_test::PathfindingTest::new(),
+            entity_inspector: EntityInspector::new(),
+            examine: ExamineMode::new(),
+            root_motion: game_options.root_motion_config.clone(),
+            surface_materials,
+            left_grab: ButtonLatch::new(),
+            right_grab: ButtonLatch::new(),
+            crouch: ButtonLatch::new(),
+            announcements,
+            sound_emitters: SoundEmitters::new(),
+            speech_arbiter: SpeechArbiter::new(),
+            movement_sounds: PlayerMovementSounds::new(),
+            entity_events: EntityEventBus::new(),
+            resource_collector: ResourceCollector::new(),
+            entity_pool: EntityPool::new(),
+            reticle: Reticle::new(),
         }
     }
 
@@ -450,6 +635,10 @@ impl MissionCore {
     ) -> Vec<Effect> {
         let _ = self.world.remove_unique::<Time>();
         self.world.add_unique(time.clone());
+        self.world
+            .borrow::<UniqueViewMut<NoiseEvents>>()
+            .unwrap()
+            .advance(time.elapsed.as_secs_f32());
         let mut effects = command_effects;
 
         let player = {
@@ -461,7 +650,10 @@ impl MissionCore {
         let delta_time = time.elapsed.as_secs_f32();
 
         // Update teleport system and add effects (only if experimental flag enabled)
-        if game_options.experimental_features.contains("teleport") {
+        if game_options
+            .experimental_features
+            .contains(ExperimentalFeature::Teleport)
+        {
             let teleport_effects =
                 self.teleport_system
                     .update(input_context, player.pos, player.rotation, delta_time);
@@ -485,18 +677,22 @@ impl MissionCore {
 
         let up_value = input_context.left_hand.thumbstick.y / dark::SCALE_FACTOR;
 
+        // Visibility is from the last render, which is close enough for deciding what can sleep
+        self.physics.sleep_distant_bodies(player.pos, |entity_id| {
+            self.visibility_engine.is_visible(entity_id)
+        });
+
         let (new_character_pos, collision_events) = {
             profile!(
                 "shock2.update.physics",
                 self.physics.update(
-                    forward + cgmath::vec3(0.0, up_value, 0.0),
+                    delta_time,
+                    forward + cgmath::vec3(0.0, up_value, 0.0) + self.climb_movement,
                     &mut self.player_handle,
                 )
             )
         };
 
-        // Clear forces
-        self.physics.clear_forces();
         self.rag_doll_manager.update(&self.physics);
 
         let (left_hand_entity_id, right_hand_entity_id) = {
@@ -514,6 +710,50 @@ impl MissionCore {
         player_info.right_hand_entity_id = right_hand_entity_id;
         drop(player_info);
 
+        // Footsteps, landings, ladder rungs and swimming
+        if self.player_handle.is_noclip() {
+            self.movement_sounds.reset();
+        } else {
+            let swimming = self
+                .spatial_data
+                .as_ref()
+                .and_then(|spatial_data| spatial_data.get_cell_from_position(new_character_pos))
+                .map(|cell| cell.is_water())
+                .unwrap_or(false);
+            let movement = PlayerMovement {
+                position: new_character_pos,
+                grounded: self.player_handle.is_grounded(),
+                climbing: self.player_handle.is_climbing(),
+                swimming,
+                crouching: self.crouch.is_active(),
+            };
+            let movement_sounds = self.movement_sounds.update(&movement, delta_time);
+            effects.extend(movement_sound_effects(
+                &self.world,
+                &self.physics,
+                &self.surface_materials,
+                player.entity_id,
+                new_character_pos,
+                &movement_sounds,
+            ));
+        }
+
+        // How visible the player is, for AI vision and the light gem
+        let cell_light_level = self
+            .spatial_data
+            .as_ref()
+            .and_then(|spatial_data| spatial_data.get_cell_from_position(new_character_pos))
+            .map(|cell| cell.light_level());
+        let target_visibility = light_visibility(
+            new_character_pos,
+            cell_light_level,
+            &self.get_animated_light_intensities(),
+        );
+        self.world
+            .borrow::<UniqueViewMut<PlayerVisibility>>()
+            .unwrap()
+            .update(target_visibility, delta_time);
+
         // Handle collision events
         for ce in collision_events {
             info!("event: {:?}", ce);
@@ -549,6 +789,16 @@ impl MissionCore {
                         to: entity2_id,
                         payload: MessagePayload::Collided { with: entity1_id },
                     });
+
+                    for (entity_id, with) in [(entity1_id, entity2_id), (entity2_id, entity1_id)] {
+                        effects.extend(impact_effect(
+                            &self.world,
+                            &self.physics,
+                            &self.surface_materials,
+                            entity_id,
+                            with,
+                        ));
+                    }
                 }
             }
         }
@@ -583,8 +833,79 @@ impl MissionCore {
         self.debug_lines
             .retain(|p| p.remaining_life_in_seconds > 0.0);
 
-        self.update_animations(time);
+        self.debug_texts.iter_mut().for_each(|p| {
+            p.remaining_life_in_seconds -= time.elapsed.as_secs_f32();
+        });
+
+        self.debug_texts
+            .retain(|p| p.remaining_life_in_seconds > 0.0);
+
+        // Powered equipment drains the player's energy, and stops working once it's gone
+        let power_changed = {
+            let mut player_equipment = self
+                .world
+                .borrow::<UniqueViewMut<PlayerEquipment>>()
+                .unwrap();
+            let mut player_energy = self.world.borrow::<UniqueViewMut<PlayerEnergy>>().unwrap();
+            player_energy.update(player_equipment.power_drain(), delta_time);
+            player_equipment.set_powered(!player_energy.is_empty())
+        };
+        if power_changed {
+            equipment::apply_modifiers(&self.world);
+        }
+
+        let health_fraction = get_health_percentage(&self.world);
+        let (status_damage, heartbeat_intensity) = {
+            let mut player_status = self.world.borrow::<UniqueViewMut<PlayerStatus>>().unwrap();
+            let status_damage = player_status.update(delta_time, health_fraction);
+            (status_damage, player_status.heartbeat_intensity())
+        };
+        if status_damage > 0 {
+            let player_entity_id = self
+                .world
+                .borrow::<UniqueView<PlayerInfo>>()
+                .unwrap()
+                .entity_id;
+            // Radiation and toxins hurt from the inside - there's no direction to show
+            effects.push(Effect::AdjustHitPoints {
+                entity_id: player_entity_id,
+                delta: -status_damage,
+                source: None,
+            });
+        }
+        self.reticle.update(delta_time);
+        {
+            let mut camera_effects = self.world.borrow::<UniqueViewMut<CameraEffects>>().unwrap();
+            camera_effects.set_reduced_flashing(game_options.accessibility.reduced_flashing);
+            camera_effects.set_heartbeat_intensity(heartbeat_intensity);
+            camera_effects.update(delta_time, player_pos, health_fraction);
+        }
+
+        let alarm_change = self
+            .world
+            .borrow::<UniqueViewMut<SecuritySystem>>()
+            .unwrap()
+            .update(delta_time);
+        if let Some(alarm_change) = alarm_change {
+            self.switch_alarm_lights(alarm_change);
+        }
+
+        {
+            let mut quest_info = self.world.borrow::<UniqueViewMut<QuestInfo>>().unwrap();
+            effects.extend(self.announcements.update(delta_time, &mut quest_info));
+        }
+        self.world
+            .borrow::<UniqueViewMut<Subtitles>>()
+            .unwrap()
+            .update(delta_time);
+
+        let mut animation_effects = profile!("animation.update", self.update_animations(time));
+        effects.append(&mut animation_effects);
 
+        self.dispatch_entity_events();
+        if self.resource_collector.update(delta_time) {
+            self.collect_orphaned_resources();
+        }
         self.hit_boxes.update(
             &mut self.world,
             &mut self.physics,
@@ -593,13 +914,40 @@ impl MissionCore {
             &mut self.id_to_physics,
         );
 
-        self.update_avatar_hands(asset_cache, player_pos, player_rot, input_context);
+        self.crouch.update(
+            game_options.accessibility.crouch_mode,
+            input_context.left_hand.b_value > 0.5,
+        );
+        self.examine.update(
+            self.held_entity(),
+            input_context.right_hand.b_value > 0.5,
+            delta_time,
+        );
+        self.update_avatar_hands(
+            asset_cache,
+            player_pos + self.crouch_offset(),
+            player_rot,
+            input_context,
+            &game_options.accessibility,
+        );
+
+        if self
+            .entity_inspector
+            .update(&self.world, &self.script_world, input_context, delta_time)
+        {
+            self.entity_inspector_action(InspectorAction::Select);
+        }
 
         // Sync up the position of all the physics objects
         // The timing of this is important - things like the GUI rendering depend on an up-to-date position
         // from physics
         self.synchronize_physics_positions();
 
+        // Attachments (like weapons in an AI's hand joint) follow their parent, so this has to happen
+        // after animations and physics sync
+        self.attach_held_weapons(asset_cache);
+        update_attachments(&self.world);
+
         // Update scripts
         let mut script_effects = profile!(
             scope: "game", level: DEBUG, "script_world.update",
@@ -625,58 +973,102 @@ impl MissionCore {
         }
 
         self.world.run(run_tweq);
+        self.world.run(run_light_animation);
         self.world.run(run_bitmap_animation);
 
         self.gui.update();
 
+        // Update particle systems
+        self.update_particle_systems(time, player.pos);
+
         let mut current_effects = self.world.borrow::<UniqueViewMut<EffectQueue>>().unwrap();
         effects.append(&mut current_effects.flush());
 
-        // Update particle systems
-        self.world.run(
-            |prop_particle_group: View<PropParticleGroup>,
-             prop_particle_launch_info: View<PropParticleLaunchInfo>,
-             transform: View<RuntimePropTransform>| {
-                for (id, (pg, launch_info, transform)) in
-                    (&prop_particle_group, &prop_particle_launch_info, &transform)
-                        .iter()
-                        .with_id()
-                {
-                    let particle_system =
-                        self.id_to_particle_system.entry(id).or_insert_with(|| {
-                            ParticleSystem::new()
-                                .with_lifetime(launch_info.min_time, launch_info.max_time)
-                                .with_velocity(
-                                    launch_info.vel_min / SCALE_FACTOR,
-                                    launch_info.vel_max / SCALE_FACTOR,
-                                )
-                                .with_acceleration(pg.gravity / SCALE_FACTOR)
-                                .with_launch_bounding_box(
-                                    launch_info.loc_min / SCALE_FACTOR,
-                                    launch_info.loc_max / SCALE_FACTOR,
-                                )
-                                .with_particle_size(
-                                    2.0 * pg.size / SCALE_FACTOR,
-                                    2.0 * pg.size / SCALE_FACTOR,
-                                )
-                                .with_num_particles(pg.num as usize)
-                                .with_launch_time(Duration::from_secs_f32(pg.launch_time))
-                                .with_alpha(pg.a as f32 / 255.0)
-                                .with_fade_time(pg.fade_time)
-                        });
-                    particle_system.update(time.elapsed, transform.0);
-                }
-            },
-        );
-
         effects
     }
 
+    ///
+    /// update_particle_systems
+    ///
+    /// Simulate the particle systems that were visible last frame, sharing a cap on simulated
+    /// particles between them - nearest to the player first. Systems out of view keep their
+    /// particles, paused, until they come back into view.
+    fn update_particle_systems(&mut self, time: &Time, player_position: Vector3<f32>) {
+        let (v_particle_group, v_particle_launch_info, v_transform) = self
+            .world
+            .borrow::<(
+                View<PropParticleGroup>,
+                View<PropParticleLaunchInfo>,
+                View<RuntimePropTransform>,
+            )>()
+            .unwrap();
+
+        let mut active = Vec::new();
+        for (id, (pg, launch_info, transform)) in
+            (&v_particle_group, &v_particle_launch_info, &v_transform)
+                .iter()
+                .with_id()
+        {
+            let particle_pool = &mut self.particle_pool;
+            let particle_system = self.id_to_particle_system.entry(id).or_insert_with(|| {
+                ParticleSystem::new()
+                    .with_pool(particle_pool)
+                    .with_lifetime(launch_info.min_time, launch_info.max_time)
+                    .with_velocity(
+                        launch_info.vel_min / SCALE_FACTOR,
+                        launch_info.vel_max / SCALE_FACTOR,
+                    )
+                    .with_acceleration(pg.gravity / SCALE_FACTOR)
+                    .with_launch_bounding_box(
+                        launch_info.loc_min / SCALE_FACTOR,
+                        launch_info.loc_max / SCALE_FACTOR,
+                    )
+                    .with_particle_size(2.0 * pg.size / SCALE_FACTOR, 2.0 * pg.size / SCALE_FACTOR)
+                    .with_num_particles(pg.num as usize)
+                    .with_launch_time(Duration::from_secs_f32(pg.launch_time))
+                    .with_alpha(pg.a as f32 / 255.0)
+                    .with_fade_time(pg.fade_time)
+            });
+            active.push((id, transform.0, particle_system.max_particles()));
+        }
+
+        // Systems whose entity is gone hand their particles back to the pool
+        let active_ids = active.iter().map(|(id, _, _)| *id).collect::<HashSet<_>>();
+        let particle_pool = &mut self.particle_pool;
+        self.id_to_particle_system.retain(|id, particle_system| {
+            let keep = active_ids.contains(id);
+            if !keep {
+                particle_system.release(particle_pool);
+            }
+            keep
+        });
+
+        let visible = active
+            .into_iter()
+            .filter(|(id, _, _)| self.visibility_engine.is_visible(*id))
+            .collect::<Vec<_>>();
+        let requests = visible
+            .iter()
+            .map(|(_, xform, max_particles)| {
+                let distance = (xform.w.truncate() - player_position).magnitude();
+                (distance, *max_particles)
+            })
+            .collect::<Vec<_>>();
+        let budgets = allocate_particle_budgets(&requests, MAX_SIMULATED_PARTICLES);
+
+        for ((id, xform, _), budget) in visible.into_iter().zip(budgets) {
+            if let Some(particle_system) = self.id_to_particle_system.get_mut(&id) {
+                particle_system.update_with_budget(time.elapsed, xform, budget);
+            }
+        }
+    }
+
     ///
     /// synchronize_physics_positions
     ///
     /// Populate the PropPosition and RuntimePropTransform components,
-    /// based on the current values in the physics engine
+    /// based on the current values in the physics engine. PropPosition is the simulated pose,
+    /// RuntimePropTransform is interpolated between physics steps for smooth rendering.
     fn synchronize_physics_positions(&mut self) {
         {
             let v_scale = self
@@ -688,7 +1080,13 @@ impl MissionCore {
                 .borrow::<ViewMut<RuntimePropTransform>>()
                 .unwrap();
             let mut v_prop_position = self.world.borrow::<ViewMut<PropPosition>>().unwrap();
+            let v_tags = self.world.borrow::<View<RuntimePropEntityTags>>().unwrap();
+            let mut u_spatial_hash = self
+                .world
+                .borrow::<UniqueViewMut<EntitySpatialHash>>()
+                .unwrap();
             let v_entities = self.world.borrow::<EntitiesView>().unwrap();
+            u_spatial_hash.retain(|entity_id| self.id_to_physics.contains_key(&entity_id));
             for (entity_id, handle) in &self.id_to_physics {
                 let scale = v_scale
                     .get(*entity_id)
@@ -696,10 +1094,14 @@ impl MissionCore {
                     .unwrap_or(vec3(1.0, 1.0, 1.0));
                 let position = self.physics.get_position(*handle).unwrap();
                 let rotation = self.physics.get_rotation(*handle).unwrap();
+                let (render_position, render_rotation) = self
+                    .physics
+                    .get_interpolated_position_rotation(*handle)
+                    .unwrap();
                 let scale_xform =
                     Matrix4::from_nonuniform_scale(scale.x.abs(), scale.y.abs(), scale.z.abs());
-                let translation_xform = Matrix4::from_translation(position);
-                let rotation_xform = Matrix4::from(rotation);
+                let translation_xform = Matrix4::from_translation(render_position);
+                let rotation_xform = Matrix4::from(render_rotation);
                 let xform = translation_xform * rotation_xform * scale_xform;
                 v_entities.add_component(
                     *entity_id,
@@ -711,19 +1113,33 @@ impl MissionCore {
                     },
                 );
                 v_entities.add_component(*entity_id, &mut v_transform, RuntimePropTransform(xform));
+                let tags = v_tags
+                    .get(*entity_id)
+                    .map(|tags| tags.0)
+                    .unwrap_or(EntityTags::empty());
+                u_spatial_hash.update(*entity_id, position, tags);
             }
         };
     }
 
-    fn update_animations(&mut self, time: &Time) {
+    fn update_animations(&mut self, time: &Time) -> Vec<Effect> {
+        let mut effects = Vec::new();
         for (id, player) in self.id_to_animation_player.iter_mut() {
             // self.id_to_animation_player.entry(*id).and_modify(|player| {
             //     *player = AnimationPlayer::update(player, time.elapsed);
             // });
-            let (new_player, flags, events, velocity) =
+            let turning_clip = player.current_clip().map(|(clip, _)| clip.clone());
+
+            let (new_player, flags, events, _velocity) =
                 AnimationPlayer::update(player, time.elapsed);
             *player = new_player;
 
+            // Root motion - the velocity from the clip's (or overridden) mode, with foot-lock applied
+            let velocity = player
+                .current_clip()
+                .map(|(clip, frame)| self.root_motion.root_velocity(clip, frame))
+                .unwrap_or(vec3(0.0, 0.0, 0.0));
+
             if let Some(model) = self.id_to_model.get(id) {
                 let joint_transforms = model.get_joint_transforms(player);
                 self.world
@@ -744,6 +1160,25 @@ impl MissionCore {
                 self.physics.set_velocity(*id, adj_velocity * 1.0);
             }
 
+            // Turning assistance - spread the clip's end rotation over its duration
+            let spread_turn = turning_clip
+                .filter(|_| self.root_motion.spread_turn_over_clip)
+                .map(|clip| {
+                    self.root_motion
+                        .turn_for_frame(&clip, time.elapsed.as_secs_f32())
+                })
+                .unwrap_or(Deg(0.0));
+            if let Some(current_rotation) = self
+                .physics
+                .get_rotation2(*id)
+                .filter(|_| spread_turn != Deg(0.0))
+            {
+                self.physics.set_rotation2(
+                    *id,
+                    current_rotation * Quaternion::from_angle_y(-spread_turn),
+                );
+            }
+
             if !flags.is_empty() {
                 self.script_world.dispatch(Message {
                     to: *id,
@@ -759,22 +1194,63 @@ impl MissionCore {
                         to: *id,
                         payload: MessagePayload::AnimationCompleted,
                     }),
+                    AnimationEvent::DirectionChanged(_)
+                        if self.root_motion.spread_turn_over_clip =>
+                    {
+                        // Already applied over the course of the clip
+                    }
                     AnimationEvent::DirectionChanged(ang) => {
-                        game_log!(DEBUG, "Animation direction changed: {:?}", ang);
+                        game_log!(
+                            DEBUG,
+                            entity: id.inner(),
+                            "Animation direction changed: {:?}",
+                            ang
+                        );
                         let maybe_current_rotation = self.physics.get_rotation2(*id);
                         if let Some(current_rotation) = maybe_current_rotation {
-                            let new_rotation =
-                                current_rotation * Quaternion::from_angle_y(ang * -0.5);
+                            let new_rotation = current_rotation
+                                * Quaternion::from_angle_y(-ang * self.root_motion.turn_assist);
                             self.physics.set_rotation2(*id, new_rotation);
                         }
                     }
                     AnimationEvent::VelocityChanged(_velocity) => (),
+                    AnimationEvent::Marker(marker) => {
+                        if matches!(
+                            marker,
+                            AnimationMarker::FootstepLeft | AnimationMarker::FootstepRight
+                        ) {
+                            effects.extend(footstep_effect(
+                                &self.world,
+                                &self.physics,
+                                &self.surface_materials,
+                                *id,
+                            ));
+                        }
+
+                        self.script_world.dispatch(Message {
+                            to: *id,
+                            payload: MessagePayload::AnimationMarker { marker },
+                        })
+                    }
                 }
             }
         }
+
+        effects
+    }
+
+    fn record_statistic(&self, event: StatisticEvent) {
+        if let Ok(mut statistics) = self.world.borrow::<UniqueViewMut<Statistics>>() {
+            statistics.record(&self.level_name, &event);
+        }
     }
 
     pub fn slay_entity(&mut self, entity_id: EntityId, asset_cache: &mut AssetCache) -> bool {
+        // Drop whatever the creature was holding as a physical pickup
+        if let Some(held_weapon) = self.held_weapons.detach(entity_id) {
+            self.drop_held_weapon(held_weapon, asset_cache);
+        }
+
         let world = &self.world;
         let flinderize_links = get_all_links_with_template(world, entity_id, |link| match link {
             Link::Flinderize(opts) => Some(*opts),
@@ -792,6 +1268,14 @@ impl MissionCore {
             let position = self.physics.get_position(**handle).unwrap();
             let rotation = self.physics.get_rotation(**handle).unwrap();
 
+            // Shattering is loud
+            if !flinderize_links.is_empty() {
+                self.world
+                    .borrow::<UniqueViewMut<NoiseEvents>>()
+                    .unwrap()
+                    .emit(Noise::new(NoiseKind::Breaking, position, 1.0));
+            }
+
             for (template_id, _flinderize_options) in flinderize_links {
                 // let flinderize_position = flinderize.position;
                 // let flinderize_orientation = flinderize.orientation;
@@ -876,6 +1360,7 @@ impl MissionCore {
             &mut self.physics,
             &maybe_model,
             entity_id,
+            None,
         );
 
         if let Some(phys_obj) = maybe_phys_obj {
@@ -929,6 +1414,9 @@ impl MissionCore {
         root_transform: Matrix4<f32>,
         additional_options: CreateEntityOptions,
     ) -> EntityCreationInfo {
+        self.entity_pool.record_spawn(template_id);
+        let recycled_entity = self.entity_pool.take(template_id);
+
         let created_entity = {
             entity_creator::create_entity_with_position(
                 template_id,
@@ -943,10 +1431,18 @@ impl MissionCore {
                 &self.obj_map,
                 &self.template_to_entity_id,
                 additional_options,
+                recycled_entity,
             )
         };
 
-        Self::finish_instantiating_entity(
+        // The entity didn't take over the pooled body - it won't be recycled again
+        if let Some(handle) = recycled_entity.and_then(|pooled| pooled.body) {
+            if created_entity.rigid_body != Some(handle) {
+                self.physics.remove_rigid_body_handle(handle);
+            }
+        }
+
+        let created_entity = Self::finish_instantiating_entity(
             &mut self.id_to_model,
             &mut self.id_to_bitmap,
             &mut self.id_to_physics,
@@ -956,7 +1452,132 @@ impl MissionCore {
             &mut self.script_world,
             created_entity,
             root_transform,
-        )
+        );
+
+        self.entity_events.emit(
+            &self.world,
+            created_entity.entity_id,
+            EntityEventKind::Created,
+        );
+        self.emit_attached_scripts();
+        created_entity
+    }
+
+    // Scripts attached by name since the last call, as entity events
+    fn emit_attached_scripts(&mut self) {
+        for (entity_id, script_name) in self.script_world.take_attached_scripts() {
+            self.entity_events.emit(
+                &self.world,
+                entity_id,
+                EntityEventKind::ScriptAttached { script_name },
+            );
+        }
+    }
+
+    ///
+    /// dispatch_entity_events
+    ///
+    /// Tell the systems tracking entities about the lifecycle events since the last dispatch
+    fn dispatch_entity_events(&mut self) {
+        self.emit_attached_scripts();
+
+        for event in self.entity_events.drain() {
+            match event.kind {
+                EntityEventKind::Destroyed => {
+                    self.gui.remove_entity(
+                        event.entity_id,
+                        &mut self.world,
+                        &mut self.physics,
+                        &mut self.script_world,
+                        &mut self.id_to_physics,
+                    );
+                    self.hit_boxes.remove_entity(
+                        event.entity_id,
+                        &mut self.world,
+                        &mut self.script_world,
+                        &mut self.physics,
+                        &mut self.id_to_physics,
+                    );
+                }
+                // The hit boxes came from the old model - they're rebuilt from the new one on the
+                // next update
+                EntityEventKind::ModelChanged { .. } => {
+                    self.hit_boxes.remove_entity(
+                        event.entity_id,
+                        &mut self.world,
+                        &mut self.script_world,
+                        &mut self.physics,
+                        &mut self.id_to_physics,
+                    );
+                }
+                EntityEventKind::Created | EntityEventKind::ScriptAttached { .. } => (),
+            }
+        }
+
+        // Everything tracking the retired entities has seen them destroyed, so they can be reused
+        self.entity_pool.release_retired();
+    }
+
+    ///
+    /// collect_orphaned_resources
+    ///
+    /// Clean up the resources of entities deleted without `remove_entity`
+    fn collect_orphaned_resources(&mut self) {
+        let physics_bodies = orphaned_entities(&self.world, &self.id_to_physics);
+        for entity_id in &physics_bodies {
+            self.id_to_physics.remove(entity_id);
+            self.physics.remove(*entity_id);
+        }
+
+        let models = orphaned_entities(&self.world, &self.id_to_model);
+        for entity_id in &models {
+            self.id_to_model.remove(entity_id);
+        }
+
+        let bitmaps = orphaned_entities(&self.world, &self.id_to_bitmap);
+        for entity_id in &bitmaps {
+            self.id_to_bitmap.remove(entity_id);
+        }
+
+        let animation_players = orphaned_entities(&self.world, &self.id_to_animation_player);
+        for entity_id in &animation_players {
+            self.id_to_animation_player.remove(entity_id);
+        }
+
+        let particle_systems = orphaned_entities(&self.world, &self.id_to_particle_system);
+        for entity_id in &particle_systems {
+            if let Some(mut particle_system) = self.id_to_particle_system.remove(entity_id) {
+                particle_system.release(&mut self.particle_pool);
+            }
+        }
+
+        let found = ResourceLeaks {
+            physics_bodies: physics_bodies.len(),
+            models: models.len(),
+            bitmaps: bitmaps.len(),
+            animation_players: animation_players.len(),
+            particle_systems: particle_systems.len(),
+            speech_cooldowns: self.speech_arbiter.remove_orphans(&self.world),
+            ..ResourceLeaks::default()
+        };
+        if found.total() > 0 {
+            warn!(
+                "Cleaned up {} resources left behind by deleted entities: {:?}",
+                found.total(),
+                found
+            );
+        }
+        self.resource_collector.record(&found);
+    }
+
+    /// Resources found belonging to deleted entities since the mission loaded
+    pub fn resource_leaks(&self) -> ResourceLeaks {
+        self.resource_collector.leaks().clone()
+    }
+
+    /// Recent entity lifecycle events, from `sequence` on - for external observers
+    pub fn entity_events_since(&self, sequence: u64) -> Vec<EntityEvent> {
+        self.entity_events.events_since(sequence)
     }
 
     fn finish_instantiating_entity(
@@ -1013,14 +1634,13 @@ impl MissionCore {
     }
 
     pub fn remove_entity(&mut self, entity_id: EntityId) {
-        // TODO: gui - remove entity
-        self.hit_boxes.remove_entity(
-            entity_id,
-            &mut self.world,
-            &mut self.script_world,
-            &mut self.physics,
-            &mut self.id_to_physics,
-        );
+        if let Some(held_weapon) = self.held_weapons.detach(entity_id) {
+            self.remove_entity(held_weapon.entity_id);
+        }
+
+        // GUIs and hit boxes are removed when the event is dispatched
+        self.entity_events
+            .emit(&self.world, entity_id, EntityEventKind::Destroyed);
         self.rag_doll_manager
             .remove_entity(entity_id, &mut self.physics);
 
@@ -1028,11 +1648,107 @@ impl MissionCore {
         self.id_to_bitmap.remove(&entity_id);
         self.id_to_model.remove(&entity_id);
         self.id_to_physics.remove(&entity_id);
-        self.physics.remove(entity_id);
+        self.delete_or_retire_entity(entity_id);
+    }
+
+    // Keep the entity and body of a frequently spawned template for the next spawn, instead of
+    // deleting them - the entity is stripped of its components, and rebuilt from its template
+    // when it's reused
+    fn delete_or_retire_entity(&mut self, entity_id: EntityId) {
+        let maybe_template_id = self
+            .world
+            .borrow::<View<PropTemplateId>>()
+            .unwrap()
+            .get(entity_id)
+            .map(|template_id| template_id.template_id)
+            .ok();
 
+        if let Some(template_id) = maybe_template_id {
+            if self.entity_pool.has_room(template_id) {
+                let body = self.physics.retire_body(entity_id);
+                if body.is_none() {
+                    self.physics.remove(entity_id);
+                }
+
+                // Retired entities stay alive, so orphaned resource collection won't catch these
+                self.id_to_animation_player.remove(&entity_id);
+                if let Some(mut particle_system) = self.id_to_particle_system.remove(&entity_id) {
+                    particle_system.release(&mut self.particle_pool);
+                }
+
+                self.world.strip(entity_id);
+                self.entity_pool
+                    .retire(template_id, PooledEntity { entity_id, body });
+                return;
+            }
+        }
+
+        self.physics.remove(entity_id);
         self.world.delete_entity(entity_id);
     }
 
+    ///
+    /// attach_held_weapons
+    ///
+    /// Create the weapon models for any AIs that picked up (or spawned with) a weapon since the last frame
+    fn attach_held_weapons(&mut self, asset_cache: &mut AssetCache) {
+        for (creature_entity_id, template_id, joint) in
+            self.held_weapons.creatures_needing_weapons(&self.world)
+        {
+            let created = self.create_entity_with_position(
+                asset_cache,
+                template_id,
+                Point3::origin(),
+                Quaternion::from_angle_y(Deg(0.0)),
+                Matrix4::identity(),
+                CreateEntityOptions {
+                    force_visible: true,
+                },
+            );
+
+            // The held weapon is just a model - the creature's hit boxes handle collision,
+            // and it shouldn't be frobbed or picked up until it's dropped.
+            let weapon_entity_id = created.entity_id;
+            self.id_to_physics.remove(&weapon_entity_id);
+            self.physics.remove(weapon_entity_id);
+            self.script_world.remove_entity(weapon_entity_id);
+            self.world
+                .add_component(weapon_entity_id, RuntimePropDoNotSerialize);
+
+            let held_weapon = HeldWeapon {
+                entity_id: weapon_entity_id,
+                template_id,
+                joint,
+            };
+            self.world
+                .add_component(weapon_entity_id, held_weapon.attachment(creature_entity_id));
+            self.held_weapons.attach(creature_entity_id, held_weapon);
+        }
+    }
+
+    fn drop_held_weapon(&mut self, held_weapon: HeldWeapon, asset_cache: &mut AssetCache) {
+        let maybe_transform = self
+            .world
+            .borrow::<View<RuntimePropTransform>>()
+            .unwrap()
+            .get(held_weapon.entity_id)
+            .map(|t| t.0)
+            .ok();
+
+        self.remove_entity(held_weapon.entity_id);
+
+        if let Some(transform) = maybe_transform {
+            self.create_entity_with_position(
+                asset_cache,
+                held_weapon.template_id,
+                get_position_from_matrix(&transform),
+                get_rotation_from_matrix(&transform),
+                Matrix4::identity(),
+                CreateEntityOptions::default(),
+            );
+        }
+    }
+
     pub fn spawn_debug_ragdoll(&mut self, entity_id: EntityId) {
         let model = match self.id_to_model.get(&entity_id) {
             Some(model) if model.can_create_rag_doll() => model,
@@ -1089,23 +1805,144 @@ impl MissionCore {
                     drop(quests);
                 }
 
-                Effect::AdjustHitPoints { entity_id, delta } => {
+                Effect::AdjustHitPoints {
+                    entity_id,
+                    delta,
+                    source,
+                } => {
+                    // The player's armor soaks up some of the damage
+                    let delta = if delta < 0 && entity_id == player_entity {
+                        let (damage, absorbed) = self
+                            .world
+                            .borrow::<UniqueView<PlayerEquipment>>()
+                            .unwrap()
+                            .absorb_damage(-delta);
+                        if absorbed > 0 {
+                            self.record_statistic(StatisticEvent::DamageAbsorbed {
+                                amount: absorbed,
+                            });
+                        }
+                        -damage
+                    } else {
+                        delta
+                    };
+
                     let mut v_hit_points = self
                         .world
                         .borrow::<ViewMut<dark::properties::PropHitPoints>>()
                         .unwrap();
 
+                    let mut remaining_hit_points = None;
                     if let Ok(hit_points) = (&mut v_hit_points).get(entity_id) {
                         hit_points.hit_points += delta;
+                        remaining_hit_points = Some(hit_points.hit_points);
+                    }
+                    drop(v_hit_points);
+
+                    let player_entity_id = self
+                        .world
+                        .borrow::<UniqueView<PlayerInfo>>()
+                        .unwrap()
+                        .entity_id;
+                    if delta < 0 && entity_id != player_entity_id {
+                        if let Some(remaining_hit_points) = remaining_hit_points {
+                            self.reticle.record_hit(remaining_hit_points <= 0);
+                        }
+                    }
+                    if delta < 0 && entity_id == player_entity_id {
+                        let mut camera_effects =
+                            self.world.borrow::<UniqueViewMut<CameraEffects>>().unwrap();
+                        camera_effects.add_damage(source);
+                        drop(camera_effects);
+                        self.world
+                            .borrow::<UniqueViewMut<PlayerStatus>>()
+                            .unwrap()
+                            .record_damage(source);
+
+                        self.record_statistic(StatisticEvent::DamageTaken { amount: -delta });
                     }
                 }
 
+                Effect::RecordStatistic { event } => self.record_statistic(event),
+
+                Effect::CameraShake {
+                    origin,
+                    intensity,
+                    duration,
+                } => {
+                    let mut camera_effects =
+                        self.world.borrow::<UniqueViewMut<CameraEffects>>().unwrap();
+                    camera_effects.add_shake(origin, intensity, duration);
+                }
+
+                Effect::DamageIndicator { source } => {
+                    let mut camera_effects =
+                        self.world.borrow::<UniqueViewMut<CameraEffects>>().unwrap();
+                    camera_effects.add_damage(source);
+                    drop(camera_effects);
+                    self.world
+                        .borrow::<UniqueViewMut<PlayerStatus>>()
+                        .unwrap()
+                        .record_damage(source);
+                }
+
+                Effect::EquipItem { entity_id, kind } => {
+                    let modifiers = equipment::modifiers_for(&self.world, entity_id, kind);
+                    let (slot, replaced) = self
+                        .world
+                        .borrow::<UniqueViewMut<PlayerEquipment>>()
+                        .unwrap()
+                        .equip(entity_id, kind, modifiers);
+                    equipment::apply_modifiers(&self.world);
+                    game_log!(
+                        INFO,
+                        "Equipped {:?} in {:?}, replacing {:?}",
+                        entity_id,
+                        slot,
+                        replaced
+                    );
+                }
+
+                Effect::UnequipItem { entity_id } => {
+                    self.world
+                        .borrow::<UniqueViewMut<PlayerEquipment>>()
+                        .unwrap()
+                        .unequip(entity_id);
+                    equipment::apply_modifiers(&self.world);
+                }
+
+                Effect::AdjustEnergy { amount } => {
+                    self.world
+                        .borrow::<UniqueViewMut<PlayerEnergy>>()
+                        .unwrap()
+                        .adjust(amount);
+                }
+
+                Effect::RechargeEnergy => {
+                    self.world
+                        .borrow::<UniqueViewMut<PlayerEnergy>>()
+                        .unwrap()
+                        .recharge();
+                }
+
+                Effect::ApplyStatusEffect { kind, duration } => {
+                    self.world
+                        .borrow::<UniqueViewMut<PlayerStatus>>()
+                        .unwrap()
+                        .apply_effect(kind, duration);
+                }
+
+                Effect::SetFaction { entity_id, faction } => {
+                    self.world
+                        .add_component(entity_id, RuntimePropFaction(faction));
+                }
+
                 Effect::AwardXP { amount } => {
                     warn!("!! TODO !!: Award XP {}", amount);
                 }
 
                 Effect::DrawDebugLines { lines } => {
-                    if game_options.debug_draw {
+                    if game_options.debug_draw || self.is_debugging_ai() {
                         for line in lines {
                             self.debug_lines.push(DebugLine {
                                 start: line.0,
@@ -1117,11 +1954,39 @@ impl MissionCore {
                     }
                 }
 
+                Effect::DrawDebugText { position, text } => {
+                    if game_options.debug_draw || self.is_debugging_ai() {
+                        self.debug_texts.push(DebugText {
+                            position,
+                            text,
+                            remaining_life_in_seconds: 0.1,
+                        })
+                    }
+                }
+
+                Effect::SetAiDebug { enabled, entities } => {
+                    let mut debug_options =
+                        self.world.borrow::<UniqueViewMut<DebugOptions>>().unwrap();
+                    debug_options.debug_ai = enabled;
+                    debug_options.debug_ai_entities = entities;
+                }
+
+                Effect::SetObjectLabels { labels } => {
+                    *self.world.borrow::<UniqueViewMut<ObjectLabels>>().unwrap() = labels;
+                }
+
                 Effect::CreateEntityByTemplateName {
                     template_name,
                     position,
                     orientation,
                 } => {
+                    // Explosions don't have a script of their own to drive feedback, so shake from here
+                    if template_name.to_ascii_lowercase().contains("explosion") {
+                        let mut camera_effects =
+                            self.world.borrow::<UniqueViewMut<CameraEffects>>().unwrap();
+                        camera_effects.add_shake(point3_to_vec3(position), 0.05, 0.5);
+                    }
+
                     self.create_entity_by_template_name(
                         asset_cache,
                         &template_name,
@@ -1146,6 +2011,102 @@ impl MissionCore {
                         options,
                     );
                 }
+                Effect::GiveItem { template_name } => {
+                    let player_pos = {
+                        let player_info = self.world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+                        player_info.pos
+                    };
+                    let maybe_inventory = self
+                        .world
+                        .borrow::<View<PlayerInventoryEntity>>()
+                        .unwrap()
+                        .iter()
+                        .with_id()
+                        .map(|(id, _)| id)
+                        .next();
+                    let maybe_created = self.create_entity_by_template_name(
+                        asset_cache,
+                        &template_name,
+                        vec3_to_point3(player_pos),
+                        Quaternion::from_angle_y(Deg(0.0)),
+                    );
+
+                    match (maybe_inventory, maybe_created) {
+                        (Some(inventory), Some(created)) => {
+                            // Same as dropping the item into the inventory by hand
+                            global_effects.extend(self.handle_effects(
+                                vec![Effect::DropEntityInfo {
+                                    parent_entity_id: inventory,
+                                    dropped_entity_id: created.entity_id,
+                                }],
+                                global_context,
+                                game_options,
+                                asset_cache,
+                                audio_context,
+                            ));
+                        }
+                        (_, None) => game_log!(WARN, "give: unknown template {template_name}"),
+                        (None, Some(_)) => game_log!(WARN, "give: no player inventory"),
+                    }
+                }
+                Effect::RollLoot { entity_id } => {
+                    let maybe_loot_info = self
+                        .world
+                        .borrow::<View<PropLootInfo>>()
+                        .unwrap()
+                        .get(entity_id)
+                        .ok()
+                        .cloned();
+                    let Some(loot_info) = maybe_loot_info else {
+                        continue;
+                    };
+
+                    // Only ever rolled once - the rolled items are saved, not the loot table
+                    self.world.remove::<(PropLootInfo,)>(entity_id);
+
+                    let template_id = self
+                        .world
+                        .borrow::<View<PropTemplateId>>()
+                        .unwrap()
+                        .get(entity_id)
+                        .map(|template_id| template_id.template_id)
+                        .unwrap_or(0);
+                    let position = self
+                        .world
+                        .borrow::<View<PropPosition>>()
+                        .unwrap()
+                        .get(entity_id)
+                        .map(|position| position.position)
+                        .unwrap_or(vec3(0.0, 0.0, 0.0));
+
+                    let seed = loot::loot_seed(&self.level_name, entity_id.inner(), template_id);
+                    let rolled = loot::roll_loot(&loot_info, seed);
+                    info!("Rolled loot for {:?}: {:?}", entity_id, rolled);
+
+                    for template_name in rolled {
+                        let Some(created) = self.create_entity_by_template_name(
+                            asset_cache,
+                            &template_name,
+                            vec3_to_point3(position),
+                            Quaternion::from_angle_y(Deg(0.0)),
+                        ) else {
+                            game_log!(WARN, "loot: unknown template {template_name}");
+                            continue;
+                        };
+
+                        // Same as dropping the item into the container by hand
+                        global_effects.extend(self.handle_effects(
+                            vec![Effect::DropEntityInfo {
+                                parent_entity_id: entity_id,
+                                dropped_entity_id: created.entity_id,
+                            }],
+                            global_context,
+                            game_options,
+                            asset_cache,
+                            audio_context,
+                        ));
+                    }
+                }
                 Effect::DropEntityInfo {
                     parent_entity_id,
                     dropped_entity_id,
@@ -1280,12 +2241,18 @@ impl MissionCore {
                                 } else {
                                     game_log!(
                                         WARN,
+                                        entity: entity_id.inner(),
                                         "Unable to load animation clip: {:?}_.mc",
                                         next_animation
                                     );
                                 }
                             } else {
-                                game_log!(WARN, "Unable to find animation for query: {:?}", &query);
+                                game_log!(
+                                    WARN,
+                                    entity: entity_id.inner(),
+                                    "Unable to find animation for query: {:?}",
+                                    &query
+                                );
                                 // If we couldn't find an animation... just stop the current one
                                 self.script_world.dispatch(Message {
                                     payload: MessagePayload::AnimationCompleted,
@@ -1308,7 +2275,10 @@ impl MissionCore {
                     world_size,
                     components,
                 } => {
-                    if game_options.experimental_features.contains("gui") {
+                    if game_options
+                        .experimental_features
+                        .contains(ExperimentalFeature::Gui)
+                    {
                         self.gui.update_ui(
                             &mut self.world,
                             &mut self.physics,
@@ -1382,8 +2352,9 @@ impl MissionCore {
                         //drop(scene_obj);
 
                         let _ext_name = model_name.clone();
-                        let orig_model =
-                            asset_cache.get(&MODELS_IMPORTER, &format!("{model_name}.BIN"));
+                        let orig_model = asset_cache
+                            .get_or_warn(&MODELS_IMPORTER, &format!("{model_name}.BIN"))
+                            .unwrap_or_else(|| Rc::new(Model::placeholder()));
 
                         let orig_model_ref = orig_model.as_ref();
 
@@ -1392,7 +2363,12 @@ impl MissionCore {
                         let vhots = new_model.vhots();
                         self.id_to_model.insert(entity_id, new_model);
                         self.world
-                            .add_component(entity_id, PropModelName(model_name));
+                            .add_component(entity_id, PropModelName(model_name.clone()));
+                        self.entity_events.emit(
+                            &self.world,
+                            entity_id,
+                            EntityEventKind::ModelChanged { model_name },
+                        );
 
                         self.world.add_component(entity_id, RuntimePropVhots(vhots));
                     }
@@ -1403,29 +2379,75 @@ impl MissionCore {
                     let has_read = quests.has_played_email(&email_file);
                     if !has_read || force {
                         quests.mark_email_as_played(&email_file);
-                        let audio_clip =
-                            asset_cache.get(&AUDIO_IMPORTER, &format!("{email_file}.wav"));
+                        let audio_clip = asset_cache
+                            .get_or_warn(&AUDIO_IMPORTER, &format!("{email_file}.wav"))
+                            .unwrap_or_else(placeholders::sound);
                         engine::audio::play_audio(
                             audio_context,
                             AudioHandle::new(),
                             Some(AudioChannel::new("email".to_owned())),
                             audio_clip,
                         );
+
+                        if let Some(subject) = localization::log_text(LogKind::Email, deck, email)
+                            .and_then(|entry| entry.subject)
+                        {
+                            self.world
+                                .borrow::<UniqueViewMut<Subtitles>>()
+                                .unwrap()
+                                .show(&subject);
+                        }
                     }
                     drop(quests);
                 }
+                Effect::PlayAnnouncement { sound, subtitle } => {
+                    let audio_file = resolve_schema(global_context, &sound);
+                    let audio_clip = asset_cache
+                        .get_or_warn(&AUDIO_IMPORTER, &format!("{audio_file}.wav"))
+                        .unwrap_or_else(placeholders::sound);
+                    engine::audio::play_audio(
+                        audio_context,
+                        AudioHandle::new(),
+                        Some(AudioChannel::new("announcement".to_owned())),
+                        audio_clip,
+                    );
+
+                    if let Some(subtitle) = subtitle {
+                        self.world
+                            .borrow::<UniqueViewMut<Subtitles>>()
+                            .unwrap()
+                            .show(&localization::text(&subtitle));
+                    }
+                }
                 Effect::PlaySound { handle, name } => {
                     println!("Trying to play sound: {}", &name);
                     let audio_file = resolve_schema(global_context, &name.to_string());
-                    let maybe_audio_clip =
-                        asset_cache.get_opt(&AUDIO_IMPORTER, &format!("{audio_file}.wav"));
-
-                    if let Some(audio_clip) = maybe_audio_clip {
-                        info!("Playing clip: {} handle: {:?}", name, &handle);
-                        engine::audio::play_audio(audio_context, handle, None, audio_clip);
-                    } else {
-                        warn!("Unable to load clip: {}", name)
-                    }
+                    let audio_clip = asset_cache
+                        .get_or_warn(&AUDIO_IMPORTER, &format!("{audio_file}.wav"))
+                        .unwrap_or_else(placeholders::sound);
+                    info!("Playing clip: {} handle: {:?}", name, &handle);
+                    engine::audio::play_audio(audio_context, handle, None, audio_clip);
+                }
+                Effect::PlaySoundAt {
+                    handle,
+                    name,
+                    position,
+                } => {
+                    let audio_file = resolve_schema(global_context, &name);
+                    let audio_clip = asset_cache
+                        .get_or_warn(&AUDIO_IMPORTER, &format!("{audio_file}.wav"))
+                        .unwrap_or_else(placeholders::sound);
+                    info!(
+                        "Playing clip: {} handle: {:?} position: {:?}",
+                        name, &handle, position
+                    );
+                    engine::audio::play_spatial_audio(
+                        audio_context,
+                        position,
+                        handle,
+                        None,
+                        audio_clip,
+                    );
                 }
                 Effect::PlaySpeech {
                     entity_id,
@@ -1442,8 +2464,25 @@ impl MissionCore {
                         let audio_path = format!("{sample_name}.wav");
                         if let Some(audio_clip) = asset_cache.get_opt(&AUDIO_IMPORTER, &audio_path)
                         {
+                            let now = self
+                                .world
+                                .borrow::<UniqueView<Time>>()
+                                .map(|time| time.total.as_secs_f32())
+                                .unwrap_or(0.0);
+                            if !self.speech_arbiter.try_begin(
+                                entity_id,
+                                &concept,
+                                now,
+                                audio_context,
+                            ) {
+                                continue;
+                            }
+
                             let handle = AudioHandle::new();
+                            self.speech_arbiter
+                                .began(handle.clone(), entity_id, &concept, now);
                             if let Some(position) = get_entity_position(&self.world, entity_id) {
+                                self.sound_emitters.attach(handle.clone(), entity_id);
                                 engine::audio::play_spatial_audio(
                                     audio_context,
                                     position,
@@ -1471,7 +2510,11 @@ impl MissionCore {
                     query,
                     position,
                     audio_handle,
+                    entity_id,
                 } => {
+                    if let Some(entity_id) = entity_id {
+                        self.sound_emitters.attach(audio_handle.clone(), entity_id);
+                    }
                     play_environmental_sound(
                         &global_context.gamesys,
                         asset_cache,
@@ -1481,10 +2524,36 @@ impl MissionCore {
                         position,
                     );
                 }
+                Effect::LoopEnvironmentalSound {
+                    audio_handle,
+                    query,
+                    entity_id,
+                } => {
+                    if let Some(position) = get_entity_position(&self.world, entity_id) {
+                        self.sound_emitters.start_loop(
+                            audio_handle.clone(),
+                            query.clone(),
+                            entity_id,
+                        );
+                        play_environmental_sound(
+                            &global_context.gamesys,
+                            asset_cache,
+                            audio_context,
+                            query,
+                            audio_handle,
+                            position,
+                        );
+                    }
+                }
                 Effect::SlayEntity { entity_id } => {
+                    let maybe_kill_event = kill_event(&self.world, entity_id);
                     let did_slay = self.slay_entity(entity_id, asset_cache);
 
                     if did_slay {
+                        if let Some(event) = maybe_kill_event {
+                            self.record_statistic(event);
+                        }
+
                         let maybe_env_sound_query =
                             get_environmental_sound_query(&self.world, entity_id, "death", vec![]);
 
@@ -1507,8 +2576,18 @@ impl MissionCore {
                     }
                 }
                 Effect::StopSound { handle } => {
+                    self.sound_emitters.stop_loop(&handle);
                     engine::audio::stop_audio(audio_context, handle);
                 }
+                Effect::MakeNoise { noise } => {
+                    self.world
+                        .borrow::<UniqueViewMut<NoiseEvents>>()
+                        .unwrap()
+                        .emit(noise);
+                }
+                Effect::SetSoundVolume { handle, volume } => {
+                    engine::audio::set_audio_volume(audio_context, &handle, volume);
+                }
                 Effect::DestroyEntity { entity_id } => {
                     info!("!!!Destroying entity: {:?}", entity_id);
                     self.left_hand = self.left_hand.destroy_entity(entity_id);
@@ -1549,6 +2628,49 @@ impl MissionCore {
                         quest_bit_name, quest_bit_value, quests_new
                     );
                 }
+                Effect::SetGlobalVariable { name, value } => {
+                    info!("Setting global variable {} to {:?}", name, value);
+                    let mut quests = self.world.borrow::<UniqueViewMut<QuestInfo>>().unwrap();
+                    quests.set_variable(&name, value);
+                }
+
+                Effect::TriggerAlarm { duration } => {
+                    self.world
+                        .borrow::<UniqueViewMut<SecuritySystem>>()
+                        .unwrap()
+                        .trigger_alarm(duration);
+                }
+                Effect::ResetAlarm => {
+                    self.world
+                        .borrow::<UniqueViewMut<SecuritySystem>>()
+                        .unwrap()
+                        .reset_alarm();
+                }
+                Effect::SetLocked { entity_id, locked } => {
+                    self.world.add_component(entity_id, PropLocked(locked));
+                }
+                Effect::ReloadTemplateOverrides => {
+                    let overrides = global_context.template_overrides.clone();
+                    let changed = {
+                        let previous = self
+                            .world
+                            .borrow::<UniqueView<TemplateOverrides>>()
+                            .unwrap();
+                        overrides.changed_since(&previous)
+                    };
+                    self.world.add_unique(overrides);
+
+                    let patched = template_overrides::apply_to_all(
+                        &mut self.world,
+                        &self.entity_info,
+                        &changed,
+                    );
+                    info!(
+                        "Applied {} changed template overrides to {} entities",
+                        changed.entries().len(),
+                        patched
+                    );
+                }
 
                 Effect::SetAIProperty { entity_id, update } => match update {
                     AIPropertyUpdate::Alertness { level, peak } => {
@@ -1613,13 +2735,57 @@ impl MissionCore {
                     let result = self.pathfinding_test_action("cycle");
                     info!("Pathfinding test: {}", result);
                 }
+                Effect::EntityInspector(action) => self.entity_inspector_action(action),
+                Effect::ToggleExamine => self.examine.toggle(self.held_entity()),
+                Effect::ToggleNoclip => {
+                    if cfg!(debug_assertions)
+                        || game_options
+                            .experimental_features
+                            .contains(ExperimentalFeature::Noclip)
+                    {
+                        let noclip = !self.player_handle.is_noclip();
+                        self.physics
+                            .set_player_noclip(noclip, &mut self.player_handle);
+                        info!("Noclip: {}", noclip);
+                    } else {
+                        game_log!(
+                            WARN,
+                            "Noclip is only available in debug builds, or with --experimental noclip"
+                        );
+                    }
+                }
                 Effect::GlobalEffect(global_effect) => global_effects.push(global_effect),
+                Effect::PlayCutscene { video_name, from } => {
+                    global_effects.push(GlobalEffect::PlayCutscene { video_name, from })
+                }
+                Effect::PlaySequence { sequence, from } => {
+                    global_effects.push(GlobalEffect::PlaySequence { sequence, from })
+                }
                 _ => {
                     game_log!(WARN, "Unhandled effect: {effect:?}");
                 }
             }
         }
 
+        // Restart looping sounds that have run out
+        for (handle, query, entity_id) in self.sound_emitters.finished_loops(audio_context) {
+            if let Some(position) = get_entity_position(&self.world, entity_id) {
+                play_environmental_sound(
+                    &global_context.gamesys,
+                    asset_cache,
+                    audio_context,
+                    query,
+                    handle,
+                    position,
+                );
+            }
+        }
+
+        // Move sounds attached to entities - including any started above
+        self.sound_emitters.update(&self.world, audio_context);
+
+        self.dispatch_entity_events();
+
         global_effects
     }
     pub fn render_per_eye(
@@ -1630,49 +2796,39 @@ impl MissionCore {
         screen_size: Vector2<f32>,
         options: &crate::GameOptions,
     ) -> Vec<SceneObject> {
-        let mut ret = vec![];
-        if let Some(hit_entity) = self.left_hand.get_raytraced_entity() {
-            ret.extend(draw_item_outline(
-                asset_cache,
-                &self.physics,
-                hit_entity,
-                view,
-                projection,
-                screen_size,
-            ));
+        let mut ret = self.interaction_system.render_per_eye(
+            asset_cache,
+            &self.physics,
+            &self.world,
+            view,
+            projection,
+            screen_size,
+            &options.accessibility,
+        );
 
-            ret.extend(draw_item_name(
-                asset_cache,
-                &self.physics,
-                hit_entity,
-                &self.world,
-                view,
-                projection,
-                screen_size,
-                options.debug_show_ids,
-            ));
-        };
+        ret.extend(
+            self.entity_inspector
+                .render(asset_cache, &options.accessibility),
+        );
 
-        if let Some(hit_entity) = self.right_hand.get_raytraced_entity() {
-            ret.extend(draw_item_outline(
-                asset_cache,
-                &self.physics,
-                hit_entity,
-                view,
-                projection,
-                screen_size,
-            ));
-            ret.extend(draw_item_name(
-                asset_cache,
-                &self.physics,
-                hit_entity,
-                &self.world,
-                view,
-                projection,
-                screen_size,
-                options.debug_show_ids,
-            ));
-        };
+        ret.extend(self.examine.render(
+            asset_cache,
+            &self.world,
+            screen_size,
+            &options.accessibility,
+        ));
+
+        ret.extend(
+            self.world
+                .borrow::<UniqueView<Subtitles>>()
+                .unwrap()
+                .render(asset_cache, screen_size, &options.accessibility),
+        );
+
+        if options.reticle.enabled {
+            let context = ReticleContext::for_view(&self.world, &self.physics, view);
+            ret.extend(self.reticle.render(context, screen_size, &options.reticle));
+        }
 
         ret.extend(self.visibility_engine.debug_render(asset_cache));
 
@@ -1733,6 +2889,44 @@ impl MissionCore {
             self.visibility_engine
                 .prepare(self.spatial_data.as_deref(), &self.world, &culling_info)
         );
+
+        profile!(
+            scope: "render", level: DEBUG, "visibility_engine.stats",
+            {
+                self.visibility_reference.prepare(
+                    self.spatial_data.as_deref(),
+                    &self.world,
+                    &culling_info,
+                );
+                self.visibility_stats = VisibilityStats::compare(
+                    &self.world,
+                    self.visibility_engine.as_mut(),
+                    &mut self.visibility_reference,
+                );
+            }
+        );
+    }
+
+    ///
+    /// set_visibility_engine
+    ///
+    /// Switch to another visibility engine - missions without a world rep have nothing to cull
+    /// against, so they keep everything visible
+    pub fn set_visibility_engine(&mut self, kind: VisibilityEngineKind) {
+        if self.spatial_data.is_none() {
+            warn!(
+                "Not switching to the {} visibility engine - no world rep to cull against",
+                kind.name()
+            );
+            return;
+        }
+
+        info!("visibility engine: {}", kind.name());
+        self.visibility_engine = kind.create();
+    }
+
+    pub fn visibility_stats(&self) -> VisibilityStats {
+        self.visibility_stats.clone()
     }
 
     pub fn ambient_audio_state(&self) -> Option<AmbientAudioState> {
@@ -1755,38 +2949,37 @@ impl MissionCore {
 
         let mut music_cue = None;
         let mut environmental_cue = None;
-        let mut emitter_candidates: Vec<(f32, EntityId, Vector3<f32>, String)> = Vec::new();
+        let mut ambient_emitters = Vec::new();
+
+        // Positional emitters are picked a little past their radius, so the ambient audio system
+        // can keep playing ones that are already on (see `AMBIENT_EXIT_RADIUS_SCALE`)
+        let exit_scale_squared = AMBIENT_EXIT_RADIUS_SCALE * AMBIENT_EXIT_RADIUS_SCALE;
 
         for (id, (ambient_sound, position)) in (&v_ambient_hacked, &v_position).iter().with_id() {
             let dist_squared = (position.position - player_position).magnitude2();
 
-            if dist_squared < ambient_sound.radius_squared {
-                if ambient_sound.sound_flags.contains(AmbientSoundFlags::MUSIC) {
+            if ambient_sound.sound_flags.contains(AmbientSoundFlags::MUSIC) {
+                if dist_squared < ambient_sound.radius_squared {
                     music_cue = Some(ambient_sound.schema.clone());
-                } else if ambient_sound
-                    .sound_flags
-                    .contains(AmbientSoundFlags::ENVIRONMENTAL)
-                {
+                }
+            } else if ambient_sound
+                .sound_flags
+                .contains(AmbientSoundFlags::ENVIRONMENTAL)
+            {
+                if dist_squared < ambient_sound.radius_squared {
                     environmental_cue = Some(ambient_sound.schema.clone());
-                } else {
-                    emitter_candidates.push((
-                        dist_squared,
-                        id,
-                        position.position,
-                        ambient_sound.schema.clone(),
-                    ));
                 }
+            } else if dist_squared < ambient_sound.radius_squared * exit_scale_squared {
+                ambient_emitters.push(AmbientEmitter {
+                    entity_id: id,
+                    position: position.position,
+                    schema: ambient_sound.schema.clone(),
+                    radius: ambient_sound.radius as f32,
+                    volume: ambient_sound.volume,
+                });
             }
         }
 
-        emitter_candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
-
-        let ambient_emitters = emitter_candidates
-            .into_iter()
-            .take(8)
-            .map(|(_, id, position, sample_name)| (id, position, sample_name))
-            .collect();
-
         Some(AmbientAudioState {
             player_position,
             music_cue,
@@ -1800,10 +2993,12 @@ impl MissionCore {
         asset_cache: &mut AssetCache,
         options: &GameOptions,
     ) -> (Vec<SceneObject>, Vector3<f32>, Quaternion<f32>) {
-        let _v_position = self.world.borrow::<View<PropPosition>>().unwrap();
+        let v_position = self.world.borrow::<View<PropPosition>>().unwrap();
         let v_transform = self.world.borrow::<View<RuntimePropTransform>>().unwrap();
         let v_frame_state = self.world.borrow::<View<PropFrameAnimState>>().unwrap();
         let v_render_type = self.world.borrow::<View<PropRenderType>>().unwrap();
+        let v_self_illumination = self.world.borrow::<View<PropSelfIllumination>>().unwrap();
+        let v_extra_light = self.world.borrow::<View<PropExtraLight>>().unwrap();
         let v_joint_transforms = self
             .world
             .borrow::<View<RuntimePropJointTransforms>>()
@@ -1844,11 +3039,16 @@ impl MissionCore {
                 }
             };
             let is_animated_model = objs.is_animated();
+            let emissivity = object_emissivity(
+                v_self_illumination.get(*entity_id).ok(),
+                v_extra_light.get(*entity_id).ok(),
+            );
 
             if let Ok(xform) = v_transform.get(*entity_id).map(|p| p.0) {
                 for obj in scene_objs {
                     let mut xformed_obj = obj.clone();
                     xformed_obj.set_transform(xform);
+                    xformed_obj.set_emissivity(emissivity);
                     if options.debug_skeletons && is_animated_model {
                         xformed_obj.set_depth_write(false);
                         xformed_obj.set_skinned_transparency(Some(0.35));
@@ -1888,6 +3088,13 @@ impl MissionCore {
             }
 
             if let Ok(xform) = v_transform.get(*entity_id).map(|p| p.0) {
+                if !self
+                    .visibility_engine
+                    .is_sphere_in_view(xform.w.truncate(), bitmap_radius(&xform))
+                {
+                    continue;
+                }
+
                 let current_frame = v_frame_state
                     .get(*entity_id)
                     .map(|c| c.current_frame)
@@ -1904,7 +3111,12 @@ impl MissionCore {
         // Render particle systems
         if options.render_particles {
             for (particle_entity_id, particle_system) in &self.id_to_particle_system {
-                if !self.visibility_engine.is_visible(*particle_entity_id) {
+                if !self.visibility_engine.is_visible(*particle_entity_id)
+                    || !self.visibility_engine.is_sphere_in_view(
+                        particle_system.world_position(),
+                        particle_system.bounding_radius(),
+                    )
+                {
                     continue;
                 }
 
@@ -1920,27 +3132,57 @@ impl MissionCore {
         let mut _player = SceneObject::new(player_mat, Box::new(engine::scene::cube::create()));
         _player.set_transform(Matrix4::from_translation(player.pos));
 
-        // Render hands
-        scene.append(&mut self.left_hand.render());
-        scene.append(&mut self.right_hand.render());
+        // Render hands, and the forearm HUD panels with health/psi overlays
+        if options.render_hands {
+            scene.append(&mut self.left_hand.render());
+            scene.append(&mut self.right_hand.render());
 
-        // Render forearm HUD panels with health/psi overlays
-        let mut hud_panels = crate::hud::create_arm_hud_panels(
-            asset_cache,
-            &self.world,
-            self.left_hand.get_position(),
-            self.left_hand.get_rotation(),
-            self.right_hand.get_position(),
-            self.right_hand.get_rotation(),
-        );
-        scene.append(&mut hud_panels);
+            let mut hud_panels = crate::hud::create_arm_hud_panels(
+                asset_cache,
+                &self.world,
+                self.left_hand.get_position(),
+                self.left_hand.get_rotation(),
+                self.right_hand.get_position(),
+                self.right_hand.get_rotation(),
+            );
+            scene.append(&mut hud_panels);
+        }
+
+        // Render where a primed throwable will land
+        if options.accessibility.throw_arc {
+            let animation_time = self.world.borrow::<UniqueView<Time>>().unwrap().total;
+            for hand in [&self.left_hand, &self.right_hand] {
+                let Some(entity_id) = hand.get_held_entity() else {
+                    continue;
+                };
+                if !hand.is_primed_throwable(&self.world) {
+                    continue;
+                }
+                let start = v_position
+                    .get(entity_id)
+                    .map(|position| position.position)
+                    .unwrap_or_else(|_| hand.get_position());
+                let trajectory = throw_arc::predict(
+                    &self.physics,
+                    entity_id,
+                    start,
+                    throw_arc::throw_velocity(hand.get_rotation()),
+                );
+                scene.append(&mut throw_arc::render(
+                    &trajectory,
+                    animation_time.as_secs_f32(),
+                ));
+            }
+        }
 
         // Render inventory
         let inventory_objs = PlayerInventoryEntity::render(&self.world);
         scene.extend(inventory_objs);
 
         // Render teleport arc + landing indicator
-        if options.experimental_features.contains("teleport")
+        if options
+            .experimental_features
+            .contains(ExperimentalFeature::Teleport)
             && self.teleport_system.get_config().enabled
         {
             let style = TeleportVisualStyle::default();
@@ -2017,8 +3259,36 @@ impl MissionCore {
             scene.push(debug);
         }
 
+        // Render debug text, turned towards the player
+        if !self.debug_texts.is_empty() {
+            let font = asset_cache
+                .get(&dark::importers::FONT_IMPORTER, "mainfont.fon")
+                .unwrap();
+            let player_pos = self.world.borrow::<UniqueView<PlayerInfo>>().unwrap().pos;
+            for debug_text in &self.debug_texts {
+                let position = point3_to_vec3(debug_text.position);
+                let to_player = player_pos - position;
+                let yaw = Rad(to_player.x.atan2(to_player.z));
+                let mut text = SceneObject::world_space_text(&debug_text.text, font.clone(), 0.0);
+                text.set_transform(
+                    Matrix4::from_translation(position) * Matrix4::from_angle_y(yaw),
+                );
+                scene.push(text);
+            }
+        }
+
+        scene.extend(object_labels::render(
+            &self.world,
+            asset_cache,
+            &self.entity_info,
+            &self.obj_map,
+        ));
+
         // Render gui
-        if options.experimental_features.contains("gui") {
+        if options
+            .experimental_features
+            .contains(ExperimentalFeature::Gui)
+        {
             let guis = self.gui.render(asset_cache, &self.world);
 
             scene.extend(guis);
@@ -2046,15 +3316,35 @@ impl MissionCore {
             }
         }
 
-        (scene, player.pos, player.rotation)
+        (scene, player.pos + self.crouch_offset(), player.rotation)
+    }
+
+    // The right hand's item comes first - it's the one the examine button is on
+    fn held_entity(&self) -> Option<EntityId> {
+        self.right_hand
+            .get_held_entity()
+            .or_else(|| self.left_hand.get_held_entity())
+    }
+
+    // Crouching only lowers the view (and hands with it) - the player's collider is unchanged
+    fn crouch_offset(&self) -> Vector3<f32> {
+        if self.crouch.is_active() {
+            vec3(0.0, -CROUCH_VIEW_OFFSET, 0.0)
+        } else {
+            vec3(0.0, 0.0, 0.0)
+        }
     }
 
     /// Get hand spotlights for testing enhanced lighting system
-    /// Returns a vector of SpotLight objects positioned at the player's hands
+    /// Returns a vector of SpotLight objects positioned at the player's hands,
+    /// along with the nearest alarm lights while the security alarm is active
     pub fn get_hand_spotlights(&self, options: &GameOptions) -> Vec<SpotLight> {
-        let mut lights = Vec::new();
+        let mut lights = self.get_alarm_spotlights(&options.accessibility);
 
-        if options.experimental_features.contains("enhanced_lighting") {
+        if options
+            .experimental_features
+            .contains(ExperimentalFeature::EnhancedLighting)
+        {
             // Right hand spotlight
             let right_hand_pos = self.right_hand.get_position();
             let right_hand_rot = self.right_hand.get_rotation();
@@ -2090,7 +3380,153 @@ impl MissionCore {
             lights.push(left_spotlight);
         }
 
+        let free_slots = MAX_SPOTLIGHTS.saturating_sub(lights.len());
+        lights.extend(self.get_animated_spotlights(free_slots));
+
+        lights
+    }
+
+    ///
+    /// switch_alarm_lights
+    ///
+    /// Run the alarm lights' tweqs (rotating beacons, flickers) while the alarm is going,
+    /// and stop them once it's over
+    fn switch_alarm_lights(&mut self, alarm_change: AlarmChange) {
+        let alarm_lights = self
+            .world
+            .borrow::<View<RuntimePropAlarmLight>>()
+            .unwrap()
+            .iter()
+            .with_id()
+            .map(|(entity_id, _)| entity_id)
+            .collect::<Vec<_>>();
+
+        for entity_id in alarm_lights {
+            match alarm_change {
+                AlarmChange::Started => self.world.run_with_data(turn_on_tweqs, entity_id),
+                AlarmChange::Ended => self.world.run_with_data(turn_off_tweqs, entity_id),
+            }
+        }
+    }
+
+    ///
+    /// get_alarm_spotlights
+    ///
+    /// Red light cast down from the alarm lights closest to the player
+    fn get_alarm_spotlights(&self, accessibility: &AccessibilitySettings) -> Vec<SpotLight> {
+        let intensity = self
+            .world
+            .borrow::<UniqueView<SecuritySystem>>()
+            .unwrap()
+            .alarm_light_intensity(accessibility.reduced_flashing);
+        if intensity <= 0.0 {
+            return Vec::new();
+        }
+
+        let player_pos = self.world.borrow::<UniqueView<PlayerInfo>>().unwrap().pos;
+        let color = accessibility.color_palette.highlight_color();
+
+        let v_alarm_light = self.world.borrow::<View<RuntimePropAlarmLight>>().unwrap();
+        let v_position = self.world.borrow::<View<PropPosition>>().unwrap();
+        let mut positions = (&v_alarm_light, &v_position)
+            .iter()
+            .map(|(_, position)| position.position)
+            .collect::<Vec<_>>();
+        positions.sort_by(|a, b| {
+            (a - player_pos)
+                .magnitude2()
+                .partial_cmp(&(b - player_pos).magnitude2())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        positions
+            .into_iter()
+            .take(MAX_ALARM_SPOTLIGHTS)
+            .map(|position| SpotLight {
+                position,
+                direction: vec3(0.0, -1.0, 0.0),
+                color_intensity: cgmath::Vector4::new(
+                    color.x,
+                    color.y,
+                    color.z,
+                    ALARM_SPOTLIGHT_INTENSITY * intensity,
+                ),
+                inner_cone_angle: 50.0_f32.to_radians(),
+                outer_cone_angle: 80.0_f32.to_radians(),
+                range: 10.0,
+            })
+            .collect()
+    }
+
+    ///
+    /// get_animated_light_intensities
+    ///
+    /// Positions of the animated lights, with their current brightness (0.0 to 1.0) relative to
+    /// their maximum
+    fn get_animated_light_intensities(&self) -> Vec<(Vector3<f32>, f32)> {
+        let u_light_animation = self
+            .world
+            .borrow::<UniqueView<LightAnimationSystem>>()
+            .unwrap();
+        let v_anim_light = self.world.borrow::<View<PropAnimLight>>().unwrap();
+        let v_position = self.world.borrow::<View<PropPosition>>().unwrap();
+
+        (&v_anim_light, &v_position)
+            .iter()
+            .with_id()
+            .filter_map(|(entity_id, (anim_light, position))| {
+                let brightness = u_light_animation.brightness(entity_id)?;
+                let intensity = if anim_light.max_brightness > 0.0 {
+                    (brightness / anim_light.max_brightness).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                Some((position.position, intensity))
+            })
+            .collect()
+    }
+
+    ///
+    /// get_animated_spotlights
+    ///
+    /// Light cast down from the animated lights (flickering and pulsing lamps) closest to the player,
+    /// following their current brightness
+    fn get_animated_spotlights(&self, count: usize) -> Vec<SpotLight> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let player_pos = self.world.borrow::<UniqueView<PlayerInfo>>().unwrap().pos;
+        let mut lights = self
+            .get_animated_light_intensities()
+            .into_iter()
+            .filter(|(position, _)| {
+                (position - player_pos).magnitude() <= ANIMATED_SPOTLIGHT_RANGE * 2.0
+            })
+            .collect::<Vec<_>>();
+        lights.sort_by(|(a, _), (b, _)| {
+            (a - player_pos)
+                .magnitude2()
+                .total_cmp(&(b - player_pos).magnitude2())
+        });
+
         lights
+            .into_iter()
+            .take(count)
+            .map(|(position, intensity)| SpotLight {
+                position,
+                direction: vec3(0.0, -1.0, 0.0),
+                color_intensity: cgmath::Vector4::new(
+                    1.0,
+                    1.0,
+                    0.9,
+                    ANIMATED_SPOTLIGHT_INTENSITY * intensity,
+                ),
+                inner_cone_angle: 60.0_f32.to_radians(),
+                outer_cone_angle: 85.0_f32.to_radians(),
+                range: ANIMATED_SPOTLIGHT_RANGE,
+            })
+            .collect()
     }
 
     fn update_avatar_hands(
@@ -2099,27 +3535,104 @@ impl MissionCore {
         player_pos: Vector3<f32>,
         player_rotation: Quaternion<f32>,
         input_context: &input_context::InputContext,
+        accessibility: &AccessibilitySettings,
     ) {
+        // The inspector uses the right trigger for picking entities
+        let mut right_hand_input = input_context.right_hand.clone();
+        if self.entity_inspector.is_active() {
+            right_hand_input.trigger_value = 0.0;
+        }
+        let right_input = grab_input(
+            &mut self.right_grab,
+            accessibility.grab_mode,
+            &right_hand_input,
+        );
+        let (right_position, right_rotation) =
+            VirtualHand::world_pose(player_pos, player_rotation, &right_input);
+        let right_target = self.interaction_system.update_hand(
+            &self.world,
+            &self.physics,
+            vr_config::Handedness::Right,
+            right_position,
+            right_rotation,
+            self.right_hand.is_free(),
+        );
         let (right_hand, mut right_hand_msgs) = VirtualHand::update(
             &self.right_hand,
             &self.physics,
             &self.world,
             player_pos,
             player_rotation,
-            &input_context.right_hand,
+            &right_input,
+            right_target,
+            accessibility.physical_hands,
         );
         self.right_hand = right_hand;
 
+        let left_input = grab_input(
+            &mut self.left_grab,
+            accessibility.grab_mode,
+            &input_context.left_hand,
+        );
+        let (left_position, left_rotation) =
+            VirtualHand::world_pose(player_pos, player_rotation, &left_input);
+        let left_target = self.interaction_system.update_hand(
+            &self.world,
+            &self.physics,
+            vr_config::Handedness::Left,
+            left_position,
+            left_rotation,
+            self.left_hand.is_free(),
+        );
         let (left_hand, mut left_hand_msgs) = VirtualHand::update(
             &self.left_hand,
             &self.physics,
             &self.world,
             player_pos,
             player_rotation,
-            &input_context.left_hand,
+            &left_input,
+            left_target,
+            accessibility.physical_hands,
         );
         self.left_hand = left_hand;
 
+        // Held items are taken out of the simulation, so the hand holding one shouldn't push either
+        for (hand, body) in [
+            (&self.left_hand, self.left_hand_body),
+            (&self.right_hand, self.right_hand_body),
+        ] {
+            let enabled = accessibility.physical_hands && hand.get_held_entity().is_none();
+            self.physics
+                .set_hand_position(body, hand.get_fingertip_position(), enabled);
+        }
+
+        // Climbing - move the player so the gripping hands stay where they grabbed on
+        let climb_movements = [
+            self.left_hand.get_climb_movement(),
+            self.right_hand.get_climb_movement(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        self.player_handle.set_climbing(!climb_movements.is_empty());
+        self.climb_movement = if climb_movements.is_empty() {
+            vec3(0.0, 0.0, 0.0)
+        } else {
+            let average =
+                climb_movements.iter().sum::<Vector3<f32>>() / climb_movements.len() as f32;
+            // Movement from last frame may not have been simulated yet - don't ask for it twice
+            average - self.physics.pending_player_movement()
+        };
+
+        // A toggled grab that didn't catch anything shouldn't stay on - otherwise the hand would
+        // snatch the next item it points at
+        if self.right_hand.get_held_entity().is_none() {
+            self.right_grab.release();
+        }
+        if self.left_hand.get_held_entity().is_none() {
+            self.left_grab.release();
+        }
+
         left_hand_msgs.append(&mut right_hand_msgs);
 
         for msg in left_hand_msgs {
@@ -2141,6 +3654,12 @@ impl MissionCore {
                     rotation,
                     scale,
                 } => {
+                    let (position, rotation) = if self.examine.examined() == Some(entity_id) {
+                        self.examine
+                            .pose(player_pos, player_rotation * input_context.head.rotation)
+                    } else {
+                        (position, rotation)
+                    };
                     self.set_entity_position_rotation(entity_id, position, rotation, scale);
                 }
                 VirtualHandEffect::SpawnEntity {
@@ -2167,6 +3686,18 @@ impl MissionCore {
                 VirtualHandEffect::DropItem { entity_id } => {
                     self.make_physical(entity_id);
 
+                    self.script_world.dispatch(Message {
+                        payload: MessagePayload::Drop,
+                        to: entity_id,
+                    });
+                }
+                VirtualHandEffect::ThrowItem {
+                    entity_id,
+                    velocity,
+                } => {
+                    self.make_physical(entity_id);
+                    self.physics.set_velocity(entity_id, velocity);
+
                     self.script_world.dispatch(Message {
                         payload: MessagePayload::Drop,
                         to: entity_id,
@@ -2183,6 +3714,52 @@ impl MissionCore {
     }
 
     /// Interactive pathfinding test system
+    ///
+    /// entity_inspector_action
+    ///
+    /// Apply an inspector control
+    fn is_debugging_ai(&self) -> bool {
+        self.world
+            .borrow::<UniqueView<DebugOptions>>()
+            .map(|debug_options| debug_options.debug_ai)
+            .unwrap_or(false)
+    }
+
+    pub fn entity_inspector_action(&mut self, action: InspectorAction) {
+        match action {
+            InspectorAction::Toggle => self.entity_inspector.toggle(&mut self.script_world),
+            InspectorAction::Select => self.entity_inspector.select(
+                self.right_hand.get_raytraced_entity(),
+                &self.world,
+                &mut self.script_world,
+            ),
+            InspectorAction::NextField => self.entity_inspector.next_field(),
+            InspectorAction::PreviousField => self.entity_inspector.previous_field(),
+            InspectorAction::Increase | InspectorAction::Decrease => {
+                let direction = if action == InspectorAction::Increase {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let Some(entity_id) = self.entity_inspector.selected() else {
+                    return;
+                };
+                // Position edits have to move the physics body too, or the next sync would undo them
+                if let Some(position) =
+                    self.entity_inspector
+                        .adjust(direction, &mut self.world, &self.script_world)
+                {
+                    self.set_entity_position_rotation(
+                        entity_id,
+                        position.position,
+                        position.rotation,
+                        vec3(1.0, 1.0, 1.0),
+                    );
+                }
+            }
+        }
+    }
+
     pub fn pathfinding_test_action(&mut self, action: &str) -> String {
         let player_pos = self.player_position();
         self.pathfinding_test.handle_action(
@@ -2302,13 +3879,13 @@ fn initialize_background_music(
     let song_file_name = &song_params.song;
     info!("loading music for level: {}", song_file_name);
     if !song_file_name.is_empty() {
-        let song = {
-            asset_cache
-                .get(&SONG_IMPORTER, &format!("{song_file_name}.snc"))
-                .clone()
-        };
-        let background_music_player = SongPlayer::new(&song, asset_cache);
-        audio_context.set_background_music(Box::new(background_music_player));
+        match asset_cache.get_or_warn(&SONG_IMPORTER, &format!("{song_file_name}.snc")) {
+            Some(song) => {
+                let background_music_player = SongPlayer::new(&song, asset_cache);
+                audio_context.set_background_music(Box::new(background_music_player));
+            }
+            None => audio_context.stop_background_music(),
+        }
     } else {
         audio_context.stop_background_music();
     }
@@ -2382,6 +3959,52 @@ fn create_room_entities(
     }
 }
 
+///
+/// object_emissivity
+///
+/// How much an object glows on its own - from its self-illumination, plus any extra light
+/// (screens, buttons, bioluminescent creatures). None for objects that only show the light they receive.
+fn object_emissivity(
+    self_illumination: Option<&PropSelfIllumination>,
+    extra_light: Option<&PropExtraLight>,
+) -> Option<f32> {
+    let self_illumination = self_illumination.map(|prop| prop.0).unwrap_or(0.0);
+    let extra_light = extra_light.map(|prop| prop.amount).unwrap_or(0.0);
+    let amount = (self_illumination + extra_light).clamp(0.0, 1.0);
+
+    if amount > 0.0 {
+        Some(amount * FULL_SELF_ILLUMINATION_EMISSIVITY)
+    } else {
+        None
+    }
+}
+
+///
+/// bitmap_radius
+///
+/// Radius of a sphere around a bitmap's unit quad, once transformed
+fn bitmap_radius(xform: &Matrix4<f32>) -> f32 {
+    let half_width = xform.x.truncate().magnitude() * 0.5;
+    let half_height = xform.y.truncate().magnitude() * 0.5;
+    (half_width * half_width + half_height * half_height).sqrt()
+}
+
+///
+/// grab_input
+///
+/// The hand input as the virtual hand should see it - in toggle mode, the squeeze is
+/// replaced with the latched grab state
+fn grab_input(latch: &mut ButtonLatch, mode: ButtonMode, hand: &Hand) -> Hand {
+    let is_grabbing = latch.update(mode, hand.squeeze_value > 0.5);
+    match mode {
+        ButtonMode::Hold => hand.clone(),
+        ButtonMode::Toggle => Hand {
+            squeeze_value: if is_grabbing { 1.0 } else { 0.0 },
+            ..hand.clone()
+        },
+    }
+}
+
 fn option_to_vec<T>(option: Option<T>) -> Vec<T> {
     match option {
         None => vec![],
@@ -2403,15 +4026,6 @@ pub fn make_un_physical2(
     id_to_physics.remove(&entity_id);
 }
 
-fn get_entity_position(world: &World, entity_id: EntityId) -> Option<Vector3<f32>> {
-    if let Ok(positions) = world.borrow::<View<PropPosition>>() {
-        if let Ok(prop) = positions.get(entity_id) {
-            return Some(prop.position);
-        }
-    }
-    None
-}
-
 fn resolve_schema(global_context: &GlobalContext, name: &str) -> String {
     let sound_schema = &global_context.gamesys.sound_schema;
     let ret = sound_schema
@@ -2499,9 +4113,10 @@ fn play_environmental_sound(
     position: Vector3<f32>,
 ) {
     let maybe_audio_file = gamesys.get_random_environmental_sound(&query);
-    if maybe_audio_file.is_some() {
-        let audio_file = maybe_audio_file.unwrap();
-        let audio_clip = asset_cache.get(&AUDIO_IMPORTER, &format!("{audio_file}.wav").to_owned());
+    if let Some(audio_file) = maybe_audio_file {
+        let audio_clip = asset_cache
+            .get_or_warn(&AUDIO_IMPORTER, &format!("{audio_file}.wav"))
+            .unwrap_or_else(placeholders::sound);
 
         info!(
             "Playing clip: {} handle: {:?} position: {:?}",
@@ -2645,6 +4260,38 @@ impl crate::game_scene::DebuggableScene for MissionCore {
                     });
                 }
 
+                // Add lock state
+                if let Ok(locked) = self
+                    .world
+                    .borrow::<View<dark::properties::PropLocked>>()
+                    .unwrap()
+                    .get(id)
+                {
+                    let state = if !locked.0 {
+                        "Unlocked"
+                    } else if internal_lock_script::has_key(&self.world, id) {
+                        "Locked (key available)"
+                    } else {
+                        "Locked"
+                    };
+                    let key = self
+                        .world
+                        .borrow::<View<dark::properties::PropKeyDst>>()
+                        .unwrap()
+                        .get(id)
+                        .map(|key_dst| {
+                            format!(
+                                " - region {}, lock {}",
+                                key_dst.0.region_id, key_dst.0.lock_id
+                            )
+                        })
+                        .unwrap_or_default();
+                    properties.push(DebugPropertyInfo {
+                        name: "Lock".to_string(),
+                        value: format!("{}{}", state, key),
+                    });
+                }
+
                 // Build links
                 let mut outgoing_links = Vec::new();
                 let incoming_links = Vec::new();
@@ -2694,48 +4341,40 @@ impl crate::game_scene::DebuggableScene for MissionCore {
     ) -> crate::game_scene::DebugRayHit {
         use crate::game_scene::DebugRayHit;
 
-        // Convert mask to collision groups
-        let collision_groups = if mask.groups.contains(&"all".to_string()) {
-            crate::physics::InternalCollisionGroups::ALL
-        } else {
-            let mut groups = crate::physics::InternalCollisionGroups::empty();
-            for group_name in &mask.groups {
-                match group_name.as_str() {
-                    "world" => groups |= crate::physics::InternalCollisionGroups::WORLD,
-                    "entity" => groups |= crate::physics::InternalCollisionGroups::ENTITY,
-                    "selectable" => groups |= crate::physics::InternalCollisionGroups::SELECTABLE,
-                    "player" => groups |= crate::physics::InternalCollisionGroups::PLAYER,
-                    "ui" => groups |= crate::physics::InternalCollisionGroups::UI,
-                    "hitbox" => groups |= crate::physics::InternalCollisionGroups::HITBOX,
-                    "raycast" => groups |= crate::physics::InternalCollisionGroups::RAYCAST,
-                    _ => {} // Ignore unknown groups
-                }
-            }
-            groups
-        };
-
-        // Perform raycast using existing physics system
+        // Sensors (triggers, room volumes) are reported too - `is_sensor` tells them apart
         match self
             .physics
-            .ray_cast3(start, end, collision_groups, None, false)
+            .ray_cast_profile(start, end, mask.collision_groups(), false)
         {
-            Some(hit) => {
+            Some((hit, maybe_profile)) => {
                 let entity_name = hit.maybe_entity_id.and_then(|id| {
                     self.world.run(
-                        |v_sym_name: shipyard::View<dark::properties::PropSymName>| {
-                            v_sym_name.get(id).ok().map(|s| s.0.clone())
+                        |v_sym_name: shipyard::View<dark::properties::PropSymName>,
+                         v_obj_name: shipyard::View<dark::properties::PropObjName>| {
+                            v_sym_name
+                                .get(id)
+                                .map(|s| s.0.clone())
+                                .or_else(|_| v_obj_name.get(id).map(|n| n.0.clone()))
+                                .ok()
                         },
                     )
                 });
 
+                // Serialized the same way as the profiles in /v1/physics/layers
+                let collision_group = maybe_profile.and_then(|profile| {
+                    serde_json::to_value(profile)
+                        .ok()
+                        .and_then(|value| value.as_str().map(str::to_owned))
+                });
+
                 DebugRayHit {
                     hit: true,
                     hit_point: Some([hit.hit_point.x, hit.hit_point.y, hit.hit_point.z]),
                     hit_normal: Some([hit.hit_normal.x, hit.hit_normal.y, hit.hit_normal.z]),
-                    distance: Some((end - start).magnitude()),
+                    distance: Some((hit.hit_point - start).magnitude()),
                     entity_id: hit.maybe_entity_id.map(|id| id.inner() as i32),
                     entity_name,
-                    collision_group: None, // TODO: Add collision group info
+                    collision_group,
                     is_sensor: hit.is_sensor,
                 }
             }
@@ -2795,21 +4434,15 @@ impl crate::game_scene::DebuggableScene for MissionCore {
         None
     }
 
-    fn get_input_state(&self) -> crate::input_context::InputContext {
-        // MissionCore doesn't store InputContext directly - it's passed to update()
-        // For debugging purposes, return a default state
-        tracing::warn!("get_input_state called on MissionCore - returning default state");
-        crate::input_context::InputContext::default()
+    fn state_hash(&self) -> u64 {
+        let mut hasher = crate::state_hash::StateHasher::new();
+        crate::state_hash::hash_world(&self.world, &mut hasher);
+        self.physics.hash_state(&mut hasher);
+        hasher.finish()
     }
 
-    fn set_input(&mut self, channel: &str, _value: serde_json::Value) -> bool {
-        // MissionCore doesn't manage InputContext directly - this needs to be handled
-        // at the debug runtime level where InputContext is maintained
-        tracing::warn!(
-            "set_input called on MissionCore for channel '{}' - not implemented",
-            channel
-        );
-        false
+    fn entity_events_since(&self, sequence: u64) -> Vec<EntityEvent> {
+        self.entity_events.events_since(sequence)
     }
 }
 
@@ -2900,6 +4533,22 @@ impl crate::game_scene::GameScene for MissionCore {
         )
     }
 
+    fn set_physics_config(&mut self, config: &crate::PhysicsConfig) {
+        self.physics.set_config(config.clone());
+    }
+
+    fn set_visibility_engine(&mut self, kind: VisibilityEngineKind) {
+        self.set_visibility_engine(kind);
+    }
+
+    fn visibility_stats(&self) -> Option<VisibilityStats> {
+        Some(self.visibility_stats())
+    }
+
+    fn resource_leaks(&self) -> Option<ResourceLeaks> {
+        Some(self.resource_leaks())
+    }
+
     fn get_hand_spotlights(&self, options: &GameOptions) -> Vec<SpotLight> {
         self.get_hand_spotlights(options)
     }
@@ -0,0 +1,140 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::mpsc::{Receiver, Sender, channel},
+};
+
+use cgmath::{InnerSpace, Vector3};
+use dark::{
+    importers::TEXTURE_IMPORTER,
+    mission::texture_list::read_texture_asset_names,
+    properties::{PropDestLevel, PropPosition},
+};
+use engine::assets::asset_cache::AssetCache;
+use shipyard::{IntoIter, View, World};
+use tracing::{info, warn};
+
+use crate::paths;
+
+// How close the player needs to be to a level transition before we start streaming in the next mission
+const PRELOAD_DISTANCE: f32 = 15.0;
+
+// Textures to pull into the asset cache per frame - keeps the warm-up from causing hitches
+const PRELOAD_TEXTURES_PER_FRAME: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PreloadState {
+    // Reading the mission's texture list on the rayon pool
+    Reading,
+    // Warming the asset cache with the remaining textures
+    Warming,
+    Ready,
+    Failed,
+}
+
+///
+/// MissionPreloader
+///
+/// Streams in the mission the player is approaching, so the level transition doesn't have to load
+/// everything from scratch. The mission file is scanned for its textures on the rayon pool, and then
+/// the textures are pulled into the (shared) asset cache a few per frame, where `Mission::load` picks them up.
+pub struct MissionPreloader {
+    states: HashMap<String, PreloadState>,
+    pending_textures: VecDeque<String>,
+    sender: Sender<(String, Option<Vec<String>>)>,
+    receiver: Receiver<(String, Option<Vec<String>>)>,
+}
+
+impl MissionPreloader {
+    pub fn new() -> MissionPreloader {
+        let (sender, receiver) = channel();
+        MissionPreloader {
+            states: HashMap::new(),
+            pending_textures: VecDeque::new(),
+            sender,
+            receiver,
+        }
+    }
+
+    ///
+    /// request
+    ///
+    /// Start preloading `level_file` (ie, "medsci1.mis") if it hasn't been already
+    pub fn request(&mut self, level_file: &str) {
+        let key = level_file.to_ascii_lowercase();
+        if self.states.contains_key(&key) {
+            return;
+        }
+
+        info!("preloading mission: {}", key);
+        self.states.insert(key.clone(), PreloadState::Reading);
+
+        let sender = self.sender.clone();
+        let path = level_file.to_owned();
+        rayon::spawn(move || {
+            let maybe_textures = paths::open_data_file(&path)
+                .map(|mut reader| read_texture_asset_names(&mut reader));
+            if maybe_textures.is_none() {
+                warn!("unable to preload mission {}", path);
+            }
+            let _ = sender.send((key, maybe_textures));
+        });
+    }
+
+    pub fn is_ready(&self, level_file: &str) -> bool {
+        self.states.get(&level_file.to_ascii_lowercase()) == Some(&PreloadState::Ready)
+    }
+
+    ///
+    /// update
+    ///
+    /// Pick up finished background reads, and warm the asset cache with the next few textures
+    pub fn update(&mut self, asset_cache: &mut AssetCache) {
+        while let Ok((key, maybe_textures)) = self.receiver.try_recv() {
+            let state = match maybe_textures {
+                Some(textures) => {
+                    self.pending_textures.extend(textures);
+                    PreloadState::Warming
+                }
+                None => PreloadState::Failed,
+            };
+            self.states.insert(key, state);
+        }
+
+        for _ in 0..PRELOAD_TEXTURES_PER_FRAME {
+            match self.pending_textures.pop_front() {
+                Some(texture_name) => {
+                    let _ = asset_cache.get(&TEXTURE_IMPORTER, &texture_name);
+                }
+                None => break,
+            }
+        }
+
+        if self.pending_textures.is_empty() {
+            for state in self.states.values_mut() {
+                if *state == PreloadState::Warming {
+                    *state = PreloadState::Ready;
+                }
+            }
+        }
+    }
+}
+
+///
+/// approaching_levels
+///
+/// Mission files of the level transitions (bulkheads, level change buttons) within preload range of the player
+pub fn approaching_levels(world: &World, player_position: Vector3<f32>) -> Vec<String> {
+    let v_dest_level = world.borrow::<View<PropDestLevel>>().unwrap();
+    let v_position = world.borrow::<View<PropPosition>>().unwrap();
+
+    let mut levels = (&v_dest_level, &v_position)
+        .iter()
+        .filter(|(_, position)| {
+            (position.position - player_position).magnitude() < PRELOAD_DISTANCE
+        })
+        .map(|(dest_level, _)| format!("{}.mis", dest_level.0))
+        .collect::<Vec<String>>();
+    levels.sort();
+    levels.dedup();
+    levels
+}
@@ -1,18 +1,36 @@
 pub mod entity_creator;
-use std::{fs::File, io::BufReader};
-
+mod entity_events;
+pub mod entity_inspector;
+mod entity_pool;
+pub mod examine;
+mod impact_sounds;
 use tracing::info;
 pub mod entity_populator;
 pub mod mission_core;
+mod mission_preloader;
+mod movement_sounds;
 pub mod pathfinding_debug;
 pub mod pathfinding_test;
+mod resource_gc;
+mod sound_emitters;
 pub mod spatial_query;
 mod spawn_location;
+mod speech_arbiter;
+mod surface_materials;
 pub mod visibility_engine;
 
+pub use entity_events::*;
+pub use entity_pool::*;
+pub use impact_sounds::*;
 pub use mission_core::*;
+pub use mission_preloader::*;
+pub use movement_sounds::*;
+pub use resource_gc::*;
+pub use sound_emitters::*;
 pub use spatial_query::*;
 pub use spawn_location::*;
+pub use speech_arbiter::*;
+pub use surface_materials::*;
 pub use visibility_engine::*;
 
 use cgmath::{Matrix4, Quaternion, Vector2, Vector3};
@@ -28,10 +46,11 @@ use shipyard::World;
 use shipyard::*;
 
 use crate::{
-    GameOptions,
+    GameOptions, PhysicsConfig,
     game_scene::AmbientAudioState,
     input_context::{self, InputContext},
     mission::entity_populator::EntityPopulator,
+    paths,
     quest_info::QuestInfo,
     save_load::HeldItemSaveData,
     scripts::{Effect, GlobalEffect},
@@ -61,8 +80,8 @@ impl Mission {
 
         info!("starting level load");
 
-        let f = File::open(resource_path(&mission)).unwrap();
-        let mut reader = BufReader::new(f);
+        let mut reader = paths::open_data_file(&mission)
+            .unwrap_or_else(|| panic!("Unable to open mission: {}", mission));
         let level = dark::mission::read(
             asset_cache,
             &mut reader,
@@ -76,6 +95,7 @@ impl Mission {
         let song_params = level.song_params.clone();
         let room_db = level.room_database.clone();
         let physics_geometry = create_physics_collider(&level);
+        let surface_materials = SurfaceMaterials::from_level(&level);
         let spatial_data = LevelSpatialData::from_level(&level);
         let obj_map = level.obj_map.clone();
 
@@ -84,10 +104,11 @@ impl Mission {
             song_params,
             room_db,
             physics_geometry,
+            surface_materials,
             spatial_data: Some(Box::new(spatial_data)),
             entity_info: level.entity_info,
             obj_map,
-            visibility_engine: Box::new(PortalVisibilityEngine::new()),
+            visibility_engine: game_options.visibility_engine.create(),
             path_database: level.path_database,
         };
 
@@ -191,6 +212,22 @@ impl crate::game_scene::GameScene for Mission {
         )
     }
 
+    fn set_physics_config(&mut self, config: &PhysicsConfig) {
+        self.mission_core.physics.set_config(config.clone());
+    }
+
+    fn set_visibility_engine(&mut self, kind: VisibilityEngineKind) {
+        self.mission_core.set_visibility_engine(kind);
+    }
+
+    fn visibility_stats(&self) -> Option<VisibilityStats> {
+        Some(self.mission_core.visibility_stats())
+    }
+
+    fn resource_leaks(&self) -> Option<ResourceLeaks> {
+        Some(self.mission_core.resource_leaks())
+    }
+
     fn get_hand_spotlights(&self, options: &GameOptions) -> Vec<SpotLight> {
         self.mission_core.get_hand_spotlights(options)
     }
@@ -268,12 +305,12 @@ impl crate::game_scene::DebuggableScene for Mission {
         self.mission_core.physics_body_detail(body_id)
     }
 
-    fn get_input_state(&self) -> crate::input_context::InputContext {
-        self.mission_core.get_input_state()
+    fn state_hash(&self) -> u64 {
+        self.mission_core.state_hash()
     }
 
-    fn set_input(&mut self, channel: &str, value: serde_json::Value) -> bool {
-        self.mission_core.set_input(channel, value)
+    fn entity_events_since(&self, sequence: u64) -> Vec<EntityEvent> {
+        self.mission_core.entity_events_since(sequence)
     }
 }
 
@@ -0,0 +1,257 @@
+use cgmath::{InnerSpace, Vector3, vec3};
+use dark::{EnvSoundQuery, SCALE_FACTOR};
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, World};
+
+use crate::{
+    noise::{Noise, NoiseKind},
+    physics::PhysicsWorld,
+    scripts::{Effect, script_util::get_environmental_sound_query},
+};
+
+use super::SurfaceMaterials;
+
+// Distance walked between footsteps
+const STRIDE_LENGTH: f32 = 4.0 / SCALE_FACTOR;
+
+// Distance climbed between ladder rungs
+const RUNG_SPACING: f32 = 1.5 / SCALE_FACTOR;
+
+// Distance swum between strokes
+const STROKE_LENGTH: f32 = 6.0 / SCALE_FACTOR;
+
+// Movement at this speed (or faster) is at full loudness - the speed of a full push on the thumbstick
+const RUN_SPEED: f32 = 25.0 / SCALE_FACTOR;
+
+// Quietest a movement sound can be - creeping along
+const MIN_LOUDNESS: f32 = 0.25;
+
+// Crouching muffles footsteps
+const CROUCH_LOUDNESS_SCALE: f32 = 0.5;
+
+// Shortest fall that makes a landing thump, and the fall that makes the loudest one
+const MIN_LANDING_FALL: f32 = 1.5 / SCALE_FACTOR;
+const MAX_LANDING_FALL: f32 = 12.0 / SCALE_FACTOR;
+
+// Moving farther than this in a frame is a teleport, not a step
+const TELEPORT_DISTANCE: f32 = 10.0 / SCALE_FACTOR;
+
+///
+/// PlayerMovement
+///
+/// How the player is moving this frame
+pub struct PlayerMovement {
+    pub position: Vector3<f32>,
+    pub grounded: bool,
+    pub climbing: bool,
+    pub swimming: bool,
+    pub crouching: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MovementSoundKind {
+    Footstep,
+    Landing,
+    LadderRung,
+    SwimStroke,
+}
+
+///
+/// MovementSound
+///
+/// A sound the player made moving, and how loud it was - from 0.0 (silent) to 1.0 (running)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MovementSound {
+    pub kind: MovementSoundKind,
+    pub loudness: f32,
+}
+
+///
+/// PlayerMovementSounds
+///
+/// Footsteps, landing thumps, ladder rungs and swimming strokes for the player - paced by the
+/// distance they move, and as loud as they are moving fast
+#[derive(Default)]
+pub struct PlayerMovementSounds {
+    last_position: Option<Vector3<f32>>,
+    distance_since_sound: f32,
+    // Highest point since the player left the ground
+    fall_start_height: Option<f32>,
+}
+
+impl PlayerMovementSounds {
+    pub fn new() -> PlayerMovementSounds {
+        PlayerMovementSounds {
+            last_position: None,
+            distance_since_sound: 0.0,
+            fall_start_height: None,
+        }
+    }
+
+    /// Forget the last movement - ie, when noclip moves the player without touching anything
+    pub fn reset(&mut self) {
+        *self = PlayerMovementSounds::new();
+    }
+
+    pub fn update(&mut self, movement: &PlayerMovement, delta_time: f32) -> Vec<MovementSound> {
+        let Some(last_position) = self.last_position.replace(movement.position) else {
+            return vec![];
+        };
+
+        let delta = movement.position - last_position;
+        if delta.magnitude() > TELEPORT_DISTANCE {
+            self.distance_since_sound = 0.0;
+            self.fall_start_height = None;
+            return vec![];
+        }
+
+        let mut sounds = vec![];
+
+        if movement.climbing || movement.swimming {
+            self.fall_start_height = None;
+        } else if !movement.grounded {
+            let height = self.fall_start_height.get_or_insert(movement.position.y);
+            *height = height.max(movement.position.y);
+        } else if let Some(fall_start_height) = self.fall_start_height.take() {
+            let fall = fall_start_height - movement.position.y;
+            if fall >= MIN_LANDING_FALL {
+                sounds.push(MovementSound {
+                    kind: MovementSoundKind::Landing,
+                    loudness: (fall / MAX_LANDING_FALL).clamp(MIN_LOUDNESS, 1.0),
+                });
+                self.distance_since_sound = 0.0;
+            }
+        }
+
+        let (distance, spacing, kind) = if movement.swimming {
+            (
+                delta.magnitude(),
+                STROKE_LENGTH,
+                MovementSoundKind::SwimStroke,
+            )
+        } else if movement.climbing {
+            (delta.y.abs(), RUNG_SPACING, MovementSoundKind::LadderRung)
+        } else if movement.grounded {
+            let horizontal = vec3(delta.x, 0.0, delta.z).magnitude();
+            (horizontal, STRIDE_LENGTH, MovementSoundKind::Footstep)
+        } else {
+            return sounds;
+        };
+
+        self.distance_since_sound += distance;
+        if self.distance_since_sound >= spacing {
+            self.distance_since_sound %= spacing;
+
+            let speed = distance / delta_time.max(f32::EPSILON);
+            let mut loudness = (speed / RUN_SPEED).clamp(MIN_LOUDNESS, 1.0);
+            if movement.crouching && kind == MovementSoundKind::Footstep {
+                loudness *= CROUCH_LOUDNESS_SCALE;
+            }
+            sounds.push(MovementSound { kind, loudness });
+        }
+
+        sounds
+    }
+}
+
+///
+/// movement_sound_effects
+///
+/// Environmental sounds for the player's movement sounds - footsteps and landings pick up the
+/// material underfoot, and each plays at its loudness, making a noise AI can hear
+pub fn movement_sound_effects(
+    world: &World,
+    physics: &PhysicsWorld,
+    surface_materials: &SurfaceMaterials,
+    player_id: EntityId,
+    position: Vector3<f32>,
+    sounds: &[MovementSound],
+) -> Vec<Effect> {
+    let mut effects = Vec::new();
+    for sound in sounds {
+        let material = match sound.kind {
+            MovementSoundKind::Footstep | MovementSoundKind::Landing => {
+                surface_materials.material_below(physics, position)
+            }
+            MovementSoundKind::LadderRung => Some("ladder"),
+            MovementSoundKind::SwimStroke => Some("water"),
+        };
+
+        let mut tags = material
+            .map(|material| vec![("material", material)])
+            .unwrap_or_default();
+        if sound.kind == MovementSoundKind::Landing {
+            tags.push(("landing", "true"));
+        }
+
+        // The player may not have class tags of their own
+        let query = get_environmental_sound_query(world, player_id, "footstep", tags.clone())
+            .unwrap_or_else(|| {
+                let mut query = vec![("event", "footstep"), ("creaturetype", "player")];
+                query.extend(tags);
+                EnvSoundQuery::from_tag_values(query)
+            });
+
+        let audio_handle = AudioHandle::new();
+        effects.push(Effect::PlayEnvironmentalSound {
+            audio_handle: audio_handle.clone(),
+            query,
+            position,
+            entity_id: Some(player_id),
+        });
+        effects.push(Effect::SetSoundVolume {
+            handle: audio_handle,
+            volume: sound.loudness,
+        });
+
+        let noise_kind = match sound.kind {
+            MovementSoundKind::Landing => NoiseKind::Landing,
+            _ => NoiseKind::Footstep,
+        };
+        effects.push(Effect::MakeNoise {
+            noise: Noise::new(noise_kind, position, sound.loudness),
+        });
+    }
+    effects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walking(position: Vector3<f32>, grounded: bool) -> PlayerMovement {
+        PlayerMovement {
+            position,
+            grounded,
+            climbing: false,
+            swimming: false,
+            crouching: false,
+        }
+    }
+
+    #[test]
+    fn test_footsteps_and_landing() {
+        let mut sounds = PlayerMovementSounds::new();
+        let mut move_to = |x: f32, y: f32, grounded: bool| {
+            sounds.update(&walking(vec3(x, y, 0.0), grounded), 0.1)
+        };
+
+        // Running - a step once a stride has been covered
+        let step = STRIDE_LENGTH * 0.6;
+        assert!(move_to(0.0, 0.0, true).is_empty());
+        assert!(move_to(step, 0.0, true).is_empty());
+        let steps = move_to(step * 2.0, 0.0, true);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].kind, MovementSoundKind::Footstep);
+
+        // Falling off a ledge, then landing
+        assert!(move_to(step * 2.0, -0.1, false).is_empty());
+        assert!(move_to(step * 2.0, -3.0, false).is_empty());
+        let landing = move_to(step * 2.0, -6.0, true);
+        assert_eq!(landing[0].kind, MovementSoundKind::Landing);
+        assert_eq!(landing[0].loudness, 1.0);
+
+        // Teleporting isn't a step
+        assert!(move_to(100.0, -6.0, true).is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+///
+/// resource_gc.rs
+///
+/// Periodic check that the runtime resources kept per entity - physics bodies, models, particle
+/// systems, speech cooldowns - all belong to entities that still exist. `MissionCore::remove_entity`
+/// cleans these up, but entities deleted straight from the world leave them behind; this catches
+/// those, cleans them up, and counts them so leaks show up in the debug runtime.
+///
+use std::collections::HashMap;
+
+use serde::Serialize;
+use shipyard::{EntitiesView, EntityId, World};
+
+// How often resources are checked, in seconds
+const CHECK_INTERVAL: f32 = 5.0;
+
+///
+/// ResourceLeaks
+///
+/// How many resources were found belonging to deleted entities, since the mission loaded
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ResourceLeaks {
+    /// How many times resources have been checked
+    pub checks: u64,
+    pub physics_bodies: usize,
+    pub models: usize,
+    pub bitmaps: usize,
+    pub animation_players: usize,
+    pub particle_systems: usize,
+    pub speech_cooldowns: usize,
+}
+
+impl ResourceLeaks {
+    pub fn total(&self) -> usize {
+        self.physics_bodies
+            + self.models
+            + self.bitmaps
+            + self.animation_players
+            + self.particle_systems
+            + self.speech_cooldowns
+    }
+}
+
+///
+/// ResourceCollector
+///
+/// Decides when to check, and keeps the running leak counts
+#[derive(Default)]
+pub struct ResourceCollector {
+    time_since_check: f32,
+    leaks: ResourceLeaks,
+}
+
+impl ResourceCollector {
+    pub fn new() -> ResourceCollector {
+        ResourceCollector::default()
+    }
+
+    /// Whether it's time for another check
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        self.time_since_check += delta_time;
+        if self.time_since_check < CHECK_INTERVAL {
+            return false;
+        }
+
+        self.time_since_check = 0.0;
+        self.leaks.checks += 1;
+        true
+    }
+
+    pub fn record(&mut self, found: &ResourceLeaks) {
+        self.leaks.physics_bodies += found.physics_bodies;
+        self.leaks.models += found.models;
+        self.leaks.bitmaps += found.bitmaps;
+        self.leaks.animation_players += found.animation_players;
+        self.leaks.particle_systems += found.particle_systems;
+        self.leaks.speech_cooldowns += found.speech_cooldowns;
+    }
+
+    pub fn leaks(&self) -> &ResourceLeaks {
+        &self.leaks
+    }
+}
+
+///
+/// orphaned_entities
+///
+/// The entities in `map` that have been deleted from the world
+pub fn orphaned_entities<V>(world: &World, map: &HashMap<EntityId, V>) -> Vec<EntityId> {
+    let v_entities = world.borrow::<EntitiesView>().unwrap();
+    map.keys()
+        .filter(|entity_id| !v_entities.is_alive(**entity_id))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphaned_entities() {
+        let mut world = World::new();
+        let alive = world.add_entity(());
+        let deleted = world.add_entity(());
+        world.delete_entity(deleted);
+
+        let map = HashMap::from([(alive, "alive"), (deleted, "deleted")]);
+        assert_eq!(orphaned_entities(&world, &map), vec![deleted]);
+    }
+
+    #[test]
+    fn test_checks_periodically() {
+        let mut collector = ResourceCollector::new();
+        assert!(!collector.update(CHECK_INTERVAL * 0.5));
+        assert!(collector.update(CHECK_INTERVAL * 0.5));
+        assert!(!collector.update(CHECK_INTERVAL * 0.5));
+
+        collector.record(&ResourceLeaks {
+            models: 2,
+            physics_bodies: 1,
+            ..ResourceLeaks::default()
+        });
+        assert_eq!(collector.leaks().checks, 1);
+        assert_eq!(collector.leaks().total(), 3);
+    }
+}
@@ -0,0 +1,78 @@
+use dark::{EnvSoundQuery, properties::PropPosition};
+use engine::audio::{self, AudioContext, AudioHandle};
+use shipyard::{EntityId, Get, View, World};
+
+///
+/// SoundEmitters
+///
+/// Spatial sounds attached to an entity - each frame the sound is moved to the entity's
+/// position, so walking enemies, floating bots and thrown objects sound like they're moving.
+/// A sound stops following once it finishes, or the entity loses its position.
+///
+/// Looping sounds (ie a door moving) are restarted with the same handle whenever they
+/// finish, until they're stopped.
+#[derive(Default)]
+pub struct SoundEmitters {
+    attached: Vec<(AudioHandle, EntityId)>,
+    looping: Vec<(AudioHandle, EnvSoundQuery, EntityId)>,
+}
+
+impl SoundEmitters {
+    pub fn new() -> SoundEmitters {
+        SoundEmitters {
+            attached: vec![],
+            looping: vec![],
+        }
+    }
+
+    pub fn start_loop(&mut self, handle: AudioHandle, query: EnvSoundQuery, entity_id: EntityId) {
+        self.looping.retain(|(existing, _, _)| *existing != handle);
+        self.looping.push((handle.clone(), query, entity_id));
+        self.attach(handle, entity_id);
+    }
+
+    pub fn stop_loop(&mut self, handle: &AudioHandle) {
+        self.looping.retain(|(existing, _, _)| existing != handle);
+    }
+
+    ///
+    /// finished_loops
+    ///
+    /// Looping sounds that have finished playing, and need to be started again
+    pub fn finished_loops(
+        &self,
+        audio_context: &AudioContext<EntityId, String>,
+    ) -> Vec<(AudioHandle, EnvSoundQuery, EntityId)> {
+        self.looping
+            .iter()
+            .filter(|(handle, _, _)| !audio::is_audio_playing(audio_context, handle))
+            .cloned()
+            .collect()
+    }
+
+    pub fn attach(&mut self, handle: AudioHandle, entity_id: EntityId) {
+        self.attached.retain(|(existing, _)| *existing != handle);
+        self.attached.push((handle, entity_id));
+    }
+
+    pub fn update(&mut self, world: &World, audio_context: &mut AudioContext<EntityId, String>) {
+        if self.attached.is_empty() {
+            return;
+        }
+
+        let Ok(v_position) = world.borrow::<View<PropPosition>>() else {
+            return;
+        };
+
+        self.attached.retain(|(handle, entity_id)| {
+            let Ok(position) = v_position.get(*entity_id) else {
+                return false;
+            };
+            audio::set_audio_position(audio_context, handle, position.position)
+        });
+
+        // A loop whose entity is gone has nothing left to play from
+        self.looping
+            .retain(|(_, _, entity_id)| v_position.get(*entity_id).is_ok());
+    }
+}
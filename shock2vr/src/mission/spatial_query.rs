@@ -1,5 +1,13 @@
-use cgmath::Vector3;
+use std::collections::HashMap;
+
+use cgmath::{MetricSpace, Vector3};
 use dark::mission::{BspTree, Cell, SystemShock2Level};
+use shipyard::{EntityId, Unique};
+
+use crate::entity_tags::EntityTags;
+
+// Bucket size for the entity hash - about a room across, so most radius queries only touch a few buckets
+const ENTITY_HASH_CELL_SIZE: f32 = 8.0;
 
 /// Spatial query interface for level data
 /// Provides position-based lookups without requiring the full SystemShock2Level
@@ -72,3 +80,190 @@ impl SpatialQueryEngine for SystemShock2Level {
         self.cells.get(index)
     }
 }
+
+/// An entity found by `EntitySpatialHash::entities_within`
+#[derive(Clone, Copy, Debug)]
+pub struct NearbyEntity {
+    pub entity_id: EntityId,
+    pub position: Vector3<f32>,
+    pub distance: f32,
+}
+
+type BucketKey = (i32, i32, i32);
+
+///
+/// EntitySpatialHash
+///
+/// Where the physics-driven entities are, bucketed on a grid - kept up to date by
+/// `MissionCore::synchronize_physics_positions`, so scripts looking for nearby objects
+/// don't have to scan every entity in the world.
+#[derive(Unique)]
+pub struct EntitySpatialHash {
+    cell_size: f32,
+    buckets: HashMap<BucketKey, Vec<EntityId>>,
+    entries: HashMap<EntityId, (Vector3<f32>, EntityTags, BucketKey)>,
+}
+
+impl EntitySpatialHash {
+    pub fn new() -> EntitySpatialHash {
+        EntitySpatialHash::with_cell_size(ENTITY_HASH_CELL_SIZE)
+    }
+
+    pub fn with_cell_size(cell_size: f32) -> EntitySpatialHash {
+        EntitySpatialHash {
+            cell_size,
+            buckets: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add an entity, or move it - it only changes bucket when it crosses into another cell
+    pub fn update(&mut self, entity_id: EntityId, position: Vector3<f32>, tags: EntityTags) {
+        let key = self.bucket_key(position);
+        if let Some((old_position, old_tags, old_key)) = self.entries.get_mut(&entity_id) {
+            *old_position = position;
+            *old_tags = tags;
+            if *old_key == key {
+                return;
+            }
+            let previous_key = *old_key;
+            *old_key = key;
+            self.remove_from_bucket(entity_id, previous_key);
+        } else {
+            self.entries.insert(entity_id, (position, tags, key));
+        }
+        self.buckets.entry(key).or_default().push(entity_id);
+    }
+
+    pub fn remove(&mut self, entity_id: EntityId) {
+        if let Some((_, _, key)) = self.entries.remove(&entity_id) {
+            self.remove_from_bucket(entity_id, key);
+        }
+    }
+
+    /// Drop every entity the predicate rejects - ie, ones that no longer have a physics body
+    pub fn retain(&mut self, mut keep: impl FnMut(EntityId) -> bool) {
+        let removed: Vec<EntityId> = self
+            .entries
+            .keys()
+            .copied()
+            .filter(|entity_id| !keep(*entity_id))
+            .collect();
+        for entity_id in removed {
+            self.remove(entity_id);
+        }
+    }
+
+    ///
+    /// entities_within
+    ///
+    /// Entities within `radius` of `position` that have all of `tags` (empty matches everything),
+    /// nearest first
+    pub fn entities_within(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+        tags: EntityTags,
+    ) -> Vec<NearbyEntity> {
+        let (min_x, min_y, min_z) =
+            self.bucket_key(position - Vector3::new(radius, radius, radius));
+        let (max_x, max_y, max_z) =
+            self.bucket_key(position + Vector3::new(radius, radius, radius));
+
+        let mut nearby = Vec::new();
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                for z in min_z..=max_z {
+                    let Some(bucket) = self.buckets.get(&(x, y, z)) else {
+                        continue;
+                    };
+                    for entity_id in bucket {
+                        let (entity_position, entity_tags, _) = self.entries[entity_id];
+                        let distance = entity_position.distance(position);
+                        if distance <= radius && entity_tags.contains(tags) {
+                            nearby.push(NearbyEntity {
+                                entity_id: *entity_id,
+                                position: entity_position,
+                                distance,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        nearby.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        nearby
+    }
+
+    fn bucket_key(&self, position: Vector3<f32>) -> BucketKey {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn remove_from_bucket(&mut self, entity_id: EntityId, key: BucketKey) {
+        if let Some(bucket) = self.buckets.get_mut(&key) {
+            bucket.retain(|id| *id != entity_id);
+            if bucket.is_empty() {
+                self.buckets.remove(&key);
+            }
+        }
+    }
+}
+
+impl Default for EntitySpatialHash {
+    fn default() -> Self {
+        EntitySpatialHash::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::vec3;
+    use shipyard::World;
+
+    use super::*;
+
+    #[test]
+    fn test_entities_within() {
+        let mut world = World::new();
+        let robot = world.add_entity(());
+        let creature = world.add_entity(());
+        let far_robot = world.add_entity(());
+
+        let mut hash = EntitySpatialHash::with_cell_size(4.0);
+        hash.update(
+            robot,
+            vec3(1.0, 0.0, 0.0),
+            EntityTags::CREATURE | EntityTags::ROBOT,
+        );
+        hash.update(creature, vec3(-3.0, 0.0, 0.0), EntityTags::CREATURE);
+        hash.update(far_robot, vec3(20.0, 0.0, 0.0), EntityTags::ROBOT);
+
+        let creatures = hash.entities_within(vec3(0.0, 0.0, 0.0), 5.0, EntityTags::CREATURE);
+        let ids: Vec<_> = creatures.iter().map(|nearby| nearby.entity_id).collect();
+        assert_eq!(ids, vec![robot, creature], "nearest first, across buckets");
+
+        let robots = hash.entities_within(vec3(0.0, 0.0, 0.0), 5.0, EntityTags::ROBOT);
+        assert_eq!(robots.len(), 1);
+
+        // Moving into another bucket, and out of the world
+        hash.update(far_robot, vec3(2.0, 0.0, 0.0), EntityTags::ROBOT);
+        hash.remove(robot);
+        let robots = hash.entities_within(vec3(0.0, 0.0, 0.0), 5.0, EntityTags::ROBOT);
+        assert_eq!(robots.len(), 1);
+        assert_eq!(robots[0].entity_id, far_robot);
+        assert_eq!(hash.len(), 2);
+    }
+}
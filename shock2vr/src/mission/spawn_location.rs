@@ -1,23 +1,77 @@
 use std::collections::HashMap;
 
-use cgmath::{Quaternion, Vector3};
+use cgmath::{Quaternion, Vector3, vec3};
 use dark::{
-    properties::{Link, PropPosition, PropStartLoc, WrappedEntityId},
+    properties::{Link, PropPosition, PropStartLoc, PropSymName, WrappedEntityId},
     ss2_entity_info::SystemShock2EntityInfo,
 };
 use num_traits::Zero;
+use serde::Serialize;
 use shipyard::{Get, IntoIter, IntoWithId, View, World};
+use tracing::warn;
 
 use crate::scripts::script_util::{get_all_links_of_type, get_first_link_of_type};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum SpawnLocation {
     MapDefault,
     Marker(i32),
+    /// A landing point or marker, looked up by its symbolic name (ie, `landing_b`)
+    Named(String),
     PositionRotation(Vector3<f32>, Quaternion<f32>),
 }
 
+/// A named place the player can be spawned at, via `SpawnLocation::Named`
+#[derive(Clone, Debug, Serialize)]
+pub struct NamedSpawnPoint {
+    pub name: String,
+    pub entity_id: u64,
+    pub position: [f32; 3],
+}
+
 impl SpawnLocation {
+    ///
+    /// parse_mission_argument
+    ///
+    /// Parse a mission argument of the form `mission[:spawn]`, where spawn is one of:
+    /// - `x,y,z` - a raw position
+    /// - `12` - a start location marker number
+    /// - `landing_b` - the symbolic name of a landing point or marker
+    pub fn parse_mission_argument(argument: &str) -> Result<(String, SpawnLocation), String> {
+        let Some((mission, spawn)) = argument.split_once(':') else {
+            return Ok((argument.to_owned(), SpawnLocation::MapDefault));
+        };
+
+        if spawn.contains(':') {
+            return Err(format!("Unable to parse mission argument: {}", argument));
+        }
+
+        let spawn = spawn.trim();
+        let spawn_location = if spawn.is_empty() {
+            SpawnLocation::MapDefault
+        } else if spawn.contains(',') {
+            let coordinates = spawn
+                .split(',')
+                .map(|part| part.trim().parse::<f32>())
+                .collect::<Result<Vec<f32>, _>>()
+                .map_err(|_| format!("Unable to parse position: {}", spawn))?;
+            if coordinates.len() != 3 {
+                return Err(format!("Unable to parse position: {}", spawn));
+            }
+            SpawnLocation::PositionRotation(
+                vec3(coordinates[0], coordinates[1], coordinates[2]),
+                Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            )
+        } else {
+            match spawn.parse::<i32>() {
+                Ok(num) => SpawnLocation::Marker(num),
+                Err(_) => SpawnLocation::Named(spawn.to_owned()),
+            }
+        };
+
+        Ok((mission.to_owned(), spawn_location))
+    }
+
     pub fn calculate_start_position(
         &self,
         world: &World,
@@ -68,6 +122,27 @@ impl SpawnLocation {
                     },
                 );
             }
+            Self::Named(name) => match find_named_spawn_point(world, name) {
+                Some((position, rotation)) => {
+                    start_pos = position;
+                    start_rotation = rotation;
+                }
+                None => {
+                    let available = named_spawn_points(world)
+                        .into_iter()
+                        .map(|spawn_point| spawn_point.name)
+                        .collect::<Vec<_>>();
+                    warn!(
+                        "Unable to find spawn point '{}', using map default. Available: {:?}",
+                        name, available
+                    );
+                    return Self::MapDefault.calculate_start_position(
+                        world,
+                        entity_info,
+                        template_to_entity_id,
+                    );
+                }
+            },
             Self::MapDefault => {
                 world.run(|v_position: View<PropPosition>| {
                     for link in &entity_info.link_playerfactories {
@@ -83,3 +158,107 @@ impl SpawnLocation {
         (start_pos, start_rotation)
     }
 }
+
+///
+/// named_spawn_points
+///
+/// All the spawn points in the mission that have a symbolic name - landing points (the targets of
+/// LandingPoint links) and start location markers - sorted by name
+pub fn named_spawn_points(world: &World) -> Vec<NamedSpawnPoint> {
+    let v_position = world.borrow::<View<PropPosition>>().unwrap();
+    let v_sym_name = world.borrow::<View<PropSymName>>().unwrap();
+    let v_start_loc = world.borrow::<View<PropStartLoc>>().unwrap();
+
+    let mut spawn_entities = Vec::new();
+    for (entity_id, _) in v_start_loc.iter().with_id() {
+        spawn_entities.push(entity_id);
+        spawn_entities.extend(get_all_links_of_type(world, entity_id, Link::LandingPoint));
+    }
+
+    let mut spawn_points: Vec<NamedSpawnPoint> = spawn_entities
+        .into_iter()
+        .filter_map(|entity_id| {
+            let name = v_sym_name.get(entity_id).ok()?;
+            let position = v_position.get(entity_id).ok()?.position;
+            Some(NamedSpawnPoint {
+                name: name.0.clone(),
+                entity_id: entity_id.inner(),
+                position: [position.x, position.y, position.z],
+            })
+        })
+        .collect();
+
+    spawn_points
+        .sort_by_key(|spawn_point| (spawn_point.name.to_ascii_lowercase(), spawn_point.entity_id));
+    spawn_points.dedup_by_key(|spawn_point| spawn_point.entity_id);
+    spawn_points
+}
+
+// Prefer the listed spawn points, but any named marker in the mission will do
+fn find_named_spawn_point(world: &World, name: &str) -> Option<(Vector3<f32>, Quaternion<f32>)> {
+    let v_position = world.borrow::<View<PropPosition>>().unwrap();
+    let v_sym_name = world.borrow::<View<PropSymName>>().unwrap();
+
+    let listed = named_spawn_points(world)
+        .into_iter()
+        .find(|spawn_point| spawn_point.name.eq_ignore_ascii_case(name))
+        .map(|spawn_point| spawn_point.entity_id);
+
+    let (_, position) = (&v_sym_name, &v_position)
+        .iter()
+        .with_id()
+        .filter(|(_, (sym_name, _))| sym_name.0.eq_ignore_ascii_case(name))
+        .min_by_key(|(entity_id, _)| Some(entity_id.inner()) != listed)?;
+
+    Some((position.position, position.rotation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(argument: &str) -> (String, SpawnLocation) {
+        SpawnLocation::parse_mission_argument(argument).unwrap()
+    }
+
+    #[test]
+    fn test_parse_mission_only() {
+        assert_eq!(
+            parse("medsci1.mis"),
+            ("medsci1.mis".to_owned(), SpawnLocation::MapDefault)
+        );
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let (mission, spawn_location) = parse("medsci1.mis:1.0,2.5,-3");
+        assert_eq!(mission, "medsci1.mis");
+        assert_eq!(
+            spawn_location,
+            SpawnLocation::PositionRotation(
+                vec3(1.0, 2.5, -3.0),
+                Quaternion::new(1.0, 0.0, 0.0, 0.0)
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_marker() {
+        assert_eq!(parse("eng1.mis:21").1, SpawnLocation::Marker(21));
+    }
+
+    #[test]
+    fn test_parse_named() {
+        assert_eq!(
+            parse("medsci1.mis:landing_b").1,
+            SpawnLocation::Named("landing_b".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(SpawnLocation::parse_mission_argument("medsci1.mis:1,2").is_err());
+        assert!(SpawnLocation::parse_mission_argument("medsci1.mis:a,b,c").is_err());
+        assert!(SpawnLocation::parse_mission_argument("medsci1.mis:a:b").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use engine::audio::{self, AudioContext, AudioHandle};
+use shipyard::{EntityId, World};
+
+use super::orphaned_entities;
+
+// Most AI lines that can play at once
+const MAX_CONCURRENT_SPEECH: usize = 3;
+
+// Seconds an entity waits after starting a line, before it can start another
+const ENTITY_SPEECH_COOLDOWN: f32 = 2.0;
+
+// Seconds before any entity can repeat a concept - so a room of alerted hybrids doesn't all
+// shout the same line
+const CONCEPT_SPEECH_COOLDOWN: f32 = 4.0;
+
+///
+/// SpeechPriority
+///
+/// How important a line is - a higher priority line can cut off a lower priority one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SpeechPriority {
+    Idle,
+    Alert,
+    Combat,
+    Death,
+}
+
+impl SpeechPriority {
+    pub fn from_concept(concept: &str) -> SpeechPriority {
+        let concept = concept.to_ascii_lowercase();
+        if concept.starts_with("comdie") {
+            SpeechPriority::Death
+        } else if concept.starts_with("com") {
+            SpeechPriority::Combat
+        } else if concept.starts_with("tolevel")
+            || concept.starts_with("atlevel")
+            || concept == "lostcontact"
+            || concept == "spotplayer"
+        {
+            SpeechPriority::Alert
+        } else {
+            SpeechPriority::Idle
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum SpeechDecision {
+    Play,
+    Interrupt(AudioHandle),
+    Skip,
+}
+
+struct ActiveSpeech {
+    handle: AudioHandle,
+    entity_id: EntityId,
+    priority: SpeechPriority,
+}
+
+///
+/// SpeechArbiter
+///
+/// Decides which AI lines get to play, so barks don't pile on top of each other: each entity and
+/// each concept has a cooldown, only a few lines play at once, and combat lines cut off idle chatter.
+/// Death lines skip the cooldowns - they can only happen once.
+#[derive(Default)]
+pub struct SpeechArbiter {
+    playing: Vec<ActiveSpeech>,
+    entity_last_spoke: HashMap<EntityId, f32>,
+    concept_last_spoken: HashMap<String, f32>,
+}
+
+impl SpeechArbiter {
+    pub fn new() -> SpeechArbiter {
+        SpeechArbiter {
+            playing: Vec::new(),
+            entity_last_spoke: HashMap::new(),
+            concept_last_spoken: HashMap::new(),
+        }
+    }
+
+    ///
+    /// try_begin
+    ///
+    /// Whether the entity can start saying the concept at `now` (in seconds) - stopping any line
+    /// it interrupts. If it returns true, the line should be played and passed to `began`.
+    pub fn try_begin(
+        &mut self,
+        entity_id: EntityId,
+        concept: &str,
+        now: f32,
+        audio_context: &mut AudioContext<EntityId, String>,
+    ) -> bool {
+        self.playing
+            .retain(|speech| audio::is_audio_playing(audio_context, &speech.handle));
+
+        match self.decide(entity_id, concept, now) {
+            SpeechDecision::Play => true,
+            SpeechDecision::Interrupt(handle) => {
+                self.playing.retain(|speech| speech.handle != handle);
+                audio::stop_audio(audio_context, handle);
+                true
+            }
+            SpeechDecision::Skip => false,
+        }
+    }
+
+    pub fn began(&mut self, handle: AudioHandle, entity_id: EntityId, concept: &str, now: f32) {
+        self.playing.push(ActiveSpeech {
+            handle,
+            entity_id,
+            priority: SpeechPriority::from_concept(concept),
+        });
+        self.entity_last_spoke.insert(entity_id, now);
+        self.concept_last_spoken
+            .insert(concept.to_ascii_lowercase(), now);
+    }
+
+    /// Forget the cooldowns of deleted entities - returns how many there were
+    pub fn remove_orphans(&mut self, world: &World) -> usize {
+        let orphans = orphaned_entities(world, &self.entity_last_spoke);
+        for entity_id in &orphans {
+            self.entity_last_spoke.remove(entity_id);
+        }
+        orphans.len()
+    }
+
+    fn decide(&self, entity_id: EntityId, concept: &str, now: f32) -> SpeechDecision {
+        let priority = SpeechPriority::from_concept(concept);
+
+        if priority < SpeechPriority::Death {
+            let on_cooldown = |last: Option<&f32>, cooldown: f32| {
+                last.map(|last| now - last < cooldown).unwrap_or(false)
+            };
+
+            if on_cooldown(
+                self.entity_last_spoke.get(&entity_id),
+                ENTITY_SPEECH_COOLDOWN,
+            ) || on_cooldown(
+                self.concept_last_spoken.get(&concept.to_ascii_lowercase()),
+                CONCEPT_SPEECH_COOLDOWN,
+            ) {
+                return SpeechDecision::Skip;
+            }
+        }
+
+        // An entity only says one line at a time
+        if let Some(speech) = self.playing.iter().find(|s| s.entity_id == entity_id) {
+            return if priority > speech.priority {
+                SpeechDecision::Interrupt(speech.handle.clone())
+            } else {
+                SpeechDecision::Skip
+            };
+        }
+
+        if self.playing.len() < MAX_CONCURRENT_SPEECH {
+            return SpeechDecision::Play;
+        }
+
+        // All channels busy - cut off the least important line, if this one matters more
+        match self.playing.iter().min_by_key(|s| s.priority) {
+            Some(speech) if priority > speech.priority => {
+                SpeechDecision::Interrupt(speech.handle.clone())
+            }
+            _ => SpeechDecision::Skip,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shipyard::World;
+
+    #[test]
+    fn test_speech_arbitration() {
+        let mut world = World::new();
+        let hybrids = (0..5).map(|_| world.add_entity(())).collect::<Vec<_>>();
+        let mut arbiter = SpeechArbiter::new();
+
+        // The same concept doesn't repeat within the cooldown, even from another entity
+        arbiter.began(AudioHandle::new(), hybrids[0], "tolevelone", 0.0);
+        assert_eq!(
+            arbiter.decide(hybrids[1], "tolevelone", 1.0),
+            SpeechDecision::Skip
+        );
+        assert_eq!(
+            arbiter.decide(hybrids[1], "tolevelone", 5.0),
+            SpeechDecision::Play
+        );
+
+        // An entity that just spoke waits, unless it's dying
+        assert_eq!(
+            arbiter.decide(hybrids[0], "atleveltwo", 1.0),
+            SpeechDecision::Skip
+        );
+        assert!(matches!(
+            arbiter.decide(hybrids[0], "comdieloud", 1.0),
+            SpeechDecision::Interrupt(_)
+        ));
+
+        // With every channel busy, combat cuts off idle chatter, but idle chatter waits
+        let idle_handle = AudioHandle::new();
+        arbiter.began(idle_handle.clone(), hybrids[1], "idle", 10.0);
+        arbiter.began(AudioHandle::new(), hybrids[2], "comattack", 10.0);
+        assert_eq!(
+            arbiter.decide(hybrids[3], "comsucc", 20.0),
+            SpeechDecision::Interrupt(idle_handle)
+        );
+        assert_eq!(
+            arbiter.decide(hybrids[4], "backtozero", 20.0),
+            SpeechDecision::Skip
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use cgmath::{Point3, Vector3, vec3};
+use dark::{MaterialSoundTable, mission::SystemShock2Level, properties::PropTemplateId};
+use shipyard::{EntityId, Get, View, World};
+
+use crate::physics::PhysicsWorld;
+
+// How far below a position to look for the floor
+const SURFACE_QUERY_DISTANCE: f32 = 8.0;
+
+///
+/// SurfaceMaterials
+///
+/// The surface material (from the level's `MaterialSoundTable`) of each triangle in the level
+/// collider, and of each object, so footsteps and impacts can pick a sound for what they're touching
+#[derive(Clone, Debug, Default)]
+pub struct SurfaceMaterials {
+    // Entity owning the level collider - set once it's added to physics
+    level_entity_id: Option<EntityId>,
+    // Texture index for each triangle, in the same order as the level collider
+    triangle_textures: Vec<u16>,
+    texture_materials: Vec<Option<String>>,
+    material_sounds: MaterialSoundTable,
+}
+
+impl SurfaceMaterials {
+    pub fn from_level(level: &SystemShock2Level) -> SurfaceMaterials {
+        let triangle_textures = level
+            .all_geometry
+            .iter()
+            .flat_map(|geo| std::iter::repeat_n(geo.texture_idx, geo.verts.len() / 3))
+            .collect();
+
+        let texture_materials = level
+            .textures
+            .0
+            .iter()
+            .map(|texture| {
+                level
+                    .material_sounds
+                    .material_for_texture(&texture.family, &texture.texture_filename)
+                    .map(|material| material.to_owned())
+            })
+            .collect();
+
+        SurfaceMaterials {
+            level_entity_id: None,
+            triangle_textures,
+            texture_materials,
+            material_sounds: level.material_sounds.clone(),
+        }
+    }
+
+    pub fn set_level_entity(&mut self, entity_id: EntityId) {
+        self.level_entity_id = Some(entity_id);
+    }
+
+    ///
+    /// material_for_triangle
+    ///
+    /// The material for a triangle index reported by the physics world. Back-face hits
+    /// come through offset by the triangle count, so they wrap around to the same triangle.
+    pub fn material_for_triangle(&self, triangle_idx: u32) -> Option<&str> {
+        if self.triangle_textures.is_empty() {
+            return None;
+        }
+
+        let triangle_idx = triangle_idx as usize % self.triangle_textures.len();
+        let texture_idx = self.triangle_textures[triangle_idx] as usize;
+        self.texture_materials
+            .get(texture_idx)
+            .and_then(|material| material.as_deref())
+    }
+
+    ///
+    /// material_below
+    ///
+    /// The material of the level surface directly below `position`, if any
+    pub fn material_below(&self, physics: &PhysicsWorld, position: Vector3<f32>) -> Option<&str> {
+        let level_entity_id = self.level_entity_id?;
+        let start_point = Point3::new(position.x, position.y, position.z);
+        physics
+            .ray_cast_triangle(
+                start_point,
+                vec3(0.0, -1.0, 0.0),
+                SURFACE_QUERY_DISTANCE,
+                level_entity_id,
+            )
+            .and_then(|triangle_idx| self.material_for_triangle(triangle_idx))
+    }
+
+    ///
+    /// material_of_entity
+    ///
+    /// The material of something an entity touched - the level surface below `position` if it's the
+    /// level, otherwise the object's own material
+    pub fn material_of_entity(
+        &self,
+        world: &World,
+        physics: &PhysicsWorld,
+        entity_id: EntityId,
+        position: Vector3<f32>,
+    ) -> Option<&str> {
+        if self.level_entity_id == Some(entity_id) {
+            return self.material_below(physics, position);
+        }
+
+        let v_template_id = world.borrow::<View<PropTemplateId>>().unwrap();
+        let template_id = v_template_id.get(entity_id).ok()?.template_id;
+        self.material_sounds.material_for_archetype(template_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_material_for_triangle() {
+        let materials = SurfaceMaterials {
+            level_entity_id: None,
+            triangle_textures: vec![0, 1, 2],
+            texture_materials: vec![Some("metal".to_owned()), None, Some("carpet".to_owned())],
+            material_sounds: MaterialSoundTable::default(),
+        };
+
+        assert_eq!(materials.material_for_triangle(0), Some("metal"));
+        assert_eq!(materials.material_for_triangle(1), None);
+        assert_eq!(materials.material_for_triangle(2), Some("carpet"));
+        // Back face of triangle 2
+        assert_eq!(materials.material_for_triangle(5), Some("carpet"));
+    }
+
+    #[test]
+    fn test_empty_materials() {
+        let materials = SurfaceMaterials::default();
+        assert_eq!(materials.material_for_triangle(0), None);
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3};
+use collision::{Frustum, Relation, Sphere};
+use dark::properties::{PropPhysDimensions, PropPosition};
+use shipyard::{EntityId, Get, IntoIter, IntoWithId, View, World};
+
+use crate::{mission::SpatialQueryEngine, util::has_refs};
+
+use super::{CullingInfo, VisibilityEngine, VisibilityEngineKind};
+
+// Bounding radius for entities without physics dimensions
+const DEFAULT_ENTITY_RADIUS: f32 = 0.5;
+
+///
+/// entity_bounding_spheres
+///
+/// A bounding sphere for every entity that can be rendered - from its physics dimensions where it has
+/// them. Entities without refs (not placed in the world) are None.
+pub(super) fn entity_bounding_spheres(world: &World) -> Vec<(EntityId, Option<Sphere<f32>>)> {
+    let v_prop_position = world.borrow::<View<PropPosition>>().unwrap();
+    let v_prop_phys_dimensions = world.borrow::<View<PropPhysDimensions>>().unwrap();
+
+    v_prop_position
+        .iter()
+        .with_id()
+        .map(|(id, pos)| {
+            if !has_refs(world, id) {
+                return (id, None);
+            }
+
+            let radius = v_prop_phys_dimensions
+                .get(id)
+                .map(|dimensions| (dimensions.size * 0.5).magnitude())
+                .unwrap_or(DEFAULT_ENTITY_RADIUS);
+            let sphere = Sphere {
+                center: Point3::from_vec(pos.position),
+                radius,
+            };
+            (id, Some(sphere))
+        })
+        .collect()
+}
+
+///
+/// BruteForceVisibilityEngine
+///
+/// Every entity whose bounds are in the view frustum is visible, regardless of walls in the way.
+/// Never culls anything that could be on screen, so it's the reference `PortalVisibilityEngine` is
+/// checked against - anything the portal engine shows should be visible here too.
+pub struct BruteForceVisibilityEngine {
+    is_visible: HashMap<EntityId, bool>,
+    frustum: Option<Frustum<f32>>,
+}
+
+impl BruteForceVisibilityEngine {
+    pub fn new() -> Self {
+        BruteForceVisibilityEngine {
+            is_visible: HashMap::new(),
+            frustum: None,
+        }
+    }
+}
+
+impl VisibilityEngine for BruteForceVisibilityEngine {
+    fn prepare(
+        &mut self,
+        _spatial_data: Option<&dyn SpatialQueryEngine>,
+        world: &World,
+        culling_info: &CullingInfo,
+    ) {
+        self.is_visible.clear();
+        self.frustum = Frustum::from_matrix4(culling_info.projection * culling_info.view);
+
+        for (id, maybe_sphere) in entity_bounding_spheres(world) {
+            let is_entity_visible = match (&self.frustum, maybe_sphere) {
+                (_, None) => false,
+                (Some(frustum), Some(sphere)) => frustum.contains(&sphere) != Relation::Out,
+                // Without a frustum, there's nothing to cull against
+                (None, Some(_)) => true,
+            };
+            self.is_visible.insert(id, is_entity_visible);
+        }
+    }
+
+    fn is_visible(&mut self, entity_id: EntityId) -> bool {
+        *self.is_visible.get(&entity_id).unwrap_or(&false)
+    }
+
+    fn kind(&self) -> Option<VisibilityEngineKind> {
+        Some(VisibilityEngineKind::BruteForce)
+    }
+
+    fn is_sphere_in_view(&self, center: Vector3<f32>, radius: f32) -> bool {
+        let Some(frustum) = &self.frustum else {
+            return true;
+        };
+
+        let sphere = Sphere {
+            center: Point3::from_vec(center),
+            radius,
+        };
+        frustum.contains(&sphere) != Relation::Out
+    }
+}
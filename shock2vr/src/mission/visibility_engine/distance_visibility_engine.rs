@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use cgmath::{EuclideanSpace, InnerSpace};
+use dark::SCALE_FACTOR;
+use shipyard::{EntityId, World};
+
+use crate::mission::SpatialQueryEngine;
+
+use super::{
+    CullingInfo, VisibilityEngine, VisibilityEngineKind, camera_position_from_view_matrix,
+    entity_bounding_spheres,
+};
+
+// How far from the camera entities are drawn
+const MAX_VISIBLE_DISTANCE: f32 = 200.0 / SCALE_FACTOR;
+
+///
+/// DistanceVisibilityEngine
+///
+/// Every entity within a fixed distance of the camera is visible, in any direction - the simplest
+/// culling there is, for ruling out the portal engine when something goes missing
+pub struct DistanceVisibilityEngine {
+    is_visible: HashMap<EntityId, bool>,
+}
+
+impl DistanceVisibilityEngine {
+    pub fn new() -> Self {
+        DistanceVisibilityEngine {
+            is_visible: HashMap::new(),
+        }
+    }
+}
+
+impl VisibilityEngine for DistanceVisibilityEngine {
+    fn prepare(
+        &mut self,
+        _spatial_data: Option<&dyn SpatialQueryEngine>,
+        world: &World,
+        culling_info: &CullingInfo,
+    ) {
+        self.is_visible.clear();
+        let camera_position = camera_position_from_view_matrix(culling_info.view);
+
+        for (id, maybe_sphere) in entity_bounding_spheres(world) {
+            let is_entity_visible = maybe_sphere
+                .map(|sphere| {
+                    (sphere.center.to_vec() - camera_position).magnitude()
+                        <= MAX_VISIBLE_DISTANCE + sphere.radius
+                })
+                .unwrap_or(false);
+            self.is_visible.insert(id, is_entity_visible);
+        }
+    }
+
+    fn is_visible(&mut self, entity_id: EntityId) -> bool {
+        *self.is_visible.get(&entity_id).unwrap_or(&false)
+    }
+
+    fn kind(&self) -> Option<VisibilityEngineKind> {
+        Some(VisibilityEngineKind::DistanceOnly)
+    }
+}
@@ -1,7 +1,13 @@
+mod brute_force_visibility_engine;
 mod culling_info;
+mod distance_visibility_engine;
 mod portal_visibility_engine;
 mod visibility_engine;
+mod visibility_stats;
 
+pub use brute_force_visibility_engine::*;
 pub use culling_info::*;
+pub use distance_visibility_engine::*;
 pub use portal_visibility_engine::*;
 pub use visibility_engine::*;
+pub use visibility_stats::*;
@@ -3,8 +3,8 @@ use std::collections::{HashMap, HashSet};
 use engine::render_log;
 
 use crate::mission::SpatialQueryEngine;
-use cgmath::{Matrix4, Point3, SquareMatrix, Vector3, point2, vec3};
-use collision::{Aabb2, Contains, Frustum, Relation, Union};
+use cgmath::{EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3, point2, vec3};
+use collision::{Aabb2, Contains, Frustum, Relation, Sphere, Union};
 use dark::{
     mission::Cell,
     properties::{PropPhysDimensions, PropPosition},
@@ -14,7 +14,7 @@ use shipyard::{EntityId, Get, IntoIter, IntoWithId, View, World};
 
 use crate::util::has_refs;
 
-use super::{CullingInfo, VisibilityEngine};
+use super::{CullingInfo, VisibilityEngine, VisibilityEngineKind};
 
 pub struct PortalDebugInfo {
     #[allow(dead_code)]
@@ -39,6 +39,9 @@ pub struct PortalVisibilityEngine {
 
     is_visible: HashMap<EntityId, bool>,
 
+    /// View frustum from the last prepare, if there was one
+    frustum: Option<Frustum<f32>>,
+
     debug_portals: Vec<PortalDebugInfo>,
     #[allow(dead_code)]
     is_debug: bool,
@@ -71,6 +74,7 @@ impl PortalVisibilityEngine {
         PortalVisibilityEngine {
             entity_cell_cache: HashMap::new(),
             is_visible: HashMap::new(),
+            frustum: None,
             debug_portals: Vec::new(),
             is_debug: false,
         }
@@ -210,7 +214,7 @@ impl PortalVisibilityEngine {
         maybe_cell_idx
     }
 }
-fn camera_position_from_view_matrix(view_matrix: Matrix4<f32>) -> Vector3<f32> {
+pub(super) fn camera_position_from_view_matrix(view_matrix: Matrix4<f32>) -> Vector3<f32> {
     let inverse_view = view_matrix.invert().unwrap();
 
     // The translation part of the matrix holds the camera position
@@ -225,6 +229,7 @@ impl VisibilityEngine for PortalVisibilityEngine {
         culling_info: &CullingInfo,
     ) {
         self.debug_portals.clear();
+        self.frustum = None;
         let camera_position = camera_position_from_view_matrix(culling_info.view);
 
         // If no spatial data is available, mark all entities as visible
@@ -253,6 +258,7 @@ impl VisibilityEngine for PortalVisibilityEngine {
         }
 
         let frustum = maybe_frustum.unwrap();
+        self.frustum = Some(frustum);
 
         let mut visible_cells = HashSet::new();
         let mut visited_cells = HashMap::new();
@@ -330,6 +336,22 @@ impl VisibilityEngine for PortalVisibilityEngine {
         *self.is_visible.get(&entity_id).unwrap_or(&false)
     }
 
+    fn kind(&self) -> Option<VisibilityEngineKind> {
+        Some(VisibilityEngineKind::Portal)
+    }
+
+    fn is_sphere_in_view(&self, center: Vector3<f32>, radius: f32) -> bool {
+        let Some(frustum) = &self.frustum else {
+            return true;
+        };
+
+        let sphere = Sphere {
+            center: Point3::from_vec(center),
+            radius,
+        };
+        frustum.contains(&sphere) != Relation::Out
+    }
+
     fn debug_render(&self, _asset_cache: &mut AssetCache) -> Vec<SceneObject> {
         vec![]
         // let mut debug_objs = self
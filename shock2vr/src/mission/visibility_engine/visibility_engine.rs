@@ -1,9 +1,73 @@
+use cgmath::Vector3;
 use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use serde::Serialize;
 use shipyard::{EntityId, World};
+use tracing::warn;
 
-use super::CullingInfo;
+use super::{
+    BruteForceVisibilityEngine, CullingInfo, DistanceVisibilityEngine, PortalVisibilityEngine,
+};
 use crate::mission::SpatialQueryEngine;
 
+///
+/// VisibilityEngineKind
+///
+/// The visibility engines that can be picked in the settings or the debug API. Portal traversal is
+/// the default; the others are simpler references to check it against.
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VisibilityEngineKind {
+    /// Entities in cells reachable through on-screen portals
+    #[default]
+    Portal,
+    /// Entities whose bounds are in the view frustum, ignoring walls
+    BruteForce,
+    /// Entities near the camera, in any direction
+    DistanceOnly,
+}
+
+impl VisibilityEngineKind {
+    pub const ALL: [VisibilityEngineKind; 3] = [
+        VisibilityEngineKind::Portal,
+        VisibilityEngineKind::BruteForce,
+        VisibilityEngineKind::DistanceOnly,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VisibilityEngineKind::Portal => "portal",
+            VisibilityEngineKind::BruteForce => "brute_force",
+            VisibilityEngineKind::DistanceOnly => "distance_only",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<VisibilityEngineKind> {
+        let name = name.trim().replace('-', "_").to_ascii_lowercase();
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Parse an engine name from the command line - the default (with a warning) if it's unknown
+    pub fn parse(name: &str) -> VisibilityEngineKind {
+        Self::from_name(name).unwrap_or_else(|| {
+            let known = Self::ALL.map(|kind| kind.name()).join(", ");
+            warn!(
+                "Unknown visibility engine '{}' - known engines: {}",
+                name.trim(),
+                known
+            );
+            VisibilityEngineKind::default()
+        })
+    }
+
+    pub fn create(&self) -> Box<dyn VisibilityEngine> {
+        match self {
+            VisibilityEngineKind::Portal => Box::new(PortalVisibilityEngine::new()),
+            VisibilityEngineKind::BruteForce => Box::new(BruteForceVisibilityEngine::new()),
+            VisibilityEngineKind::DistanceOnly => Box::new(DistanceVisibilityEngine::new()),
+        }
+    }
+}
+
 pub trait VisibilityEngine {
     fn prepare(
         &mut self,
@@ -15,12 +79,22 @@ pub trait VisibilityEngine {
 
     fn is_visible(&mut self, entity_id: EntityId) -> bool;
 
+    /// Which selectable engine this is - None for fixed engines, like `AlwaysVisible`
+    fn kind(&self) -> Option<VisibilityEngineKind> {
+        None
+    }
+
+    /// Whether a bounding sphere could be on screen - for things, like particles, that extend past
+    /// their entity's position
+    fn is_sphere_in_view(&self, _center: Vector3<f32>, _radius: f32) -> bool {
+        true
+    }
+
     fn debug_render(&self, _asset_cache: &mut AssetCache) -> Vec<SceneObject> {
         Vec::new()
     }
 }
 
-#[allow(dead_code)]
 pub struct AlwaysVisible;
 
 impl VisibilityEngine for AlwaysVisible {
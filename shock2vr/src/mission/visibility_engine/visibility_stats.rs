@@ -0,0 +1,91 @@
+use serde::Serialize;
+use shipyard::World;
+
+use super::{
+    BruteForceVisibilityEngine, VisibilityEngine, VisibilityEngineKind, entity_bounding_spheres,
+};
+
+///
+/// VisibilityStats
+///
+/// How many entities the active visibility engine rendered and culled in the last frame, compared
+/// against the brute force frustum check. Anything rendered outside the frustum was drawn for
+/// nothing; anything culled inside it was either behind a wall, or wrongly culled - so a missing
+/// object with a nonzero `culled_in_frustum` points at the portal traversal.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct VisibilityStats {
+    /// The active engine - None if the scene doesn't cull (ie, debug scenes)
+    pub engine: Option<VisibilityEngineKind>,
+    pub rendered: usize,
+    pub culled: usize,
+    /// Entities whose bounds are in the view frustum
+    pub in_frustum: usize,
+    pub rendered_outside_frustum: usize,
+    pub culled_in_frustum: usize,
+}
+
+impl VisibilityStats {
+    ///
+    /// compare
+    ///
+    /// Stats for the entities that can be rendered, from an engine and the reference - both already
+    /// prepared for the same frame
+    pub fn compare(
+        world: &World,
+        engine: &mut dyn VisibilityEngine,
+        reference: &mut BruteForceVisibilityEngine,
+    ) -> VisibilityStats {
+        let mut stats = VisibilityStats {
+            engine: engine.kind(),
+            ..VisibilityStats::default()
+        };
+
+        for (entity_id, maybe_sphere) in entity_bounding_spheres(world) {
+            if maybe_sphere.is_some() {
+                stats.record(
+                    engine.is_visible(entity_id),
+                    reference.is_visible(entity_id),
+                );
+            }
+        }
+        stats
+    }
+
+    fn record(&mut self, rendered: bool, in_frustum: bool) {
+        if rendered {
+            self.rendered += 1;
+        } else {
+            self.culled += 1;
+        }
+
+        if in_frustum {
+            self.in_frustum += 1;
+        }
+
+        match (rendered, in_frustum) {
+            (true, false) => self.rendered_outside_frustum += 1,
+            (false, true) => self.culled_in_frustum += 1,
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = VisibilityStats::default();
+        stats.record(true, true);
+        stats.record(false, false);
+        stats.record(false, true);
+        stats.record(true, false);
+
+        assert_eq!(stats.rendered, 2);
+        assert_eq!(stats.culled, 2);
+        assert_eq!(stats.in_frustum, 2);
+        assert_eq!(stats.rendered_outside_frustum, 1);
+        assert_eq!(stats.culled_in_frustum, 1);
+    }
+}
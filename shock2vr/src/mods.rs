@@ -0,0 +1,502 @@
+///
+/// mods.rs
+///
+/// Mod packages - directories under `<data root>/mods`, each with a `mod.json` manifest next to
+/// the files it adds or replaces (laid out like the game data, ie `res/obj/...`). Enabled mods
+/// are kept per profile (`enabled_mods` in settings.json), or passed with `--mods`.
+///
+/// Enabled mods are validated - missing dependencies and declared conflicts drop a mod - and
+/// sorted by load order, dependencies first. Later mods win when two replace the same file.
+///
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::Read,
+    sync::Arc,
+};
+
+use engine::{
+    assets::{
+        asset_paths::{AbstractAssetPath, AssetPath},
+        file_system_asset_path::FileSystemAssetPath,
+    },
+    file_system::FileSystem,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+// Directory mods are installed in, relative to the data root
+pub const MODS_DIR: &str = "mods";
+
+const MANIFEST_FILE: &str = "mod.json";
+
+///
+/// ModManifest
+///
+/// A mod's `mod.json`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModManifest {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    /// Ids (directory names) of the mods this one needs - they're loaded before it
+    pub dependencies: Vec<String>,
+    /// Ids of mods this one can't be used with
+    pub conflicts: Vec<String>,
+    /// Lower loads first, so higher load orders win when mods replace the same file
+    pub load_order: i32,
+}
+
+impl Default for ModManifest {
+    fn default() -> Self {
+        ModManifest {
+            name: String::new(),
+            version: "0.0.0".to_owned(),
+            description: String::new(),
+            dependencies: Vec::new(),
+            conflicts: Vec::new(),
+            load_order: 0,
+        }
+    }
+}
+
+///
+/// ModPackage
+///
+/// An installed mod
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModPackage {
+    /// The mod's directory name - what dependencies, conflicts and `--mods` refer to it by
+    pub id: String,
+    pub manifest: ModManifest,
+    /// Files in the mod, relative to its directory, lowercase
+    pub files: Vec<String>,
+}
+
+impl ModPackage {
+    pub fn display_name(&self) -> &str {
+        if self.manifest.name.is_empty() {
+            &self.id
+        } else {
+            &self.manifest.name
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModIssue {
+    InvalidManifest {
+        id: String,
+        error: String,
+    },
+    NotInstalled {
+        id: String,
+    },
+    MissingDependency {
+        id: String,
+        dependency: String,
+    },
+    Incompatible {
+        id: String,
+        other: String,
+    },
+    DependencyCycle {
+        id: String,
+    },
+    /// Not a problem by itself - `winner` loads later and replaces `loser`'s file
+    FileOverride {
+        file: String,
+        winner: String,
+        loser: String,
+    },
+}
+
+impl ModIssue {
+    /// Whether the issue kept a mod from loading
+    pub fn is_error(&self) -> bool {
+        !matches!(self, ModIssue::FileOverride { .. })
+    }
+}
+
+impl fmt::Display for ModIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModIssue::InvalidManifest { id, error } => {
+                write!(f, "{}: unable to read {}: {}", id, MANIFEST_FILE, error)
+            }
+            ModIssue::NotInstalled { id } => write!(f, "{}: not installed", id),
+            ModIssue::MissingDependency { id, dependency } => {
+                write!(f, "{}: needs {}, which isn't enabled", id, dependency)
+            }
+            ModIssue::Incompatible { id, other } => {
+                write!(f, "{}: conflicts with {}", id, other)
+            }
+            ModIssue::DependencyCycle { id } => write!(f, "{}: circular dependencies", id),
+            ModIssue::FileOverride {
+                file,
+                winner,
+                loser,
+            } => write!(f, "{}: replaces {} from {}", winner, file, loser),
+        }
+    }
+}
+
+///
+/// ModLoadOrder
+///
+/// The mods that passed validation, in the order they load, and what went wrong with the rest
+#[derive(Clone, Debug, Default)]
+pub struct ModLoadOrder {
+    pub active: Vec<ModPackage>,
+    pub issues: Vec<ModIssue>,
+}
+
+impl ModLoadOrder {
+    pub fn is_active(&self, id: &str) -> bool {
+        self.active.iter().any(|package| package.id == id)
+    }
+
+    ///
+    /// asset_path
+    ///
+    /// The active mods' directories, for looking up assets ahead of the game data - the last
+    /// mod to load is searched first
+    pub fn asset_path(&self, file_system: Arc<dyn FileSystem>) -> Box<dyn AbstractAssetPath> {
+        AssetPath::combine(
+            self.active
+                .iter()
+                .rev()
+                .map(|package| {
+                    FileSystemAssetPath::new(
+                        format!("{}/{}", MODS_DIR, package.id),
+                        file_system.clone(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+///
+/// ModManager
+///
+/// The installed mods, scanned from the mods directory
+#[derive(Clone, Debug, Default)]
+pub struct ModManager {
+    installed: Vec<ModPackage>,
+    scan_issues: Vec<ModIssue>,
+}
+
+impl ModManager {
+    pub fn new(installed: Vec<ModPackage>) -> ModManager {
+        ModManager {
+            installed,
+            scan_issues: Vec::new(),
+        }
+    }
+
+    ///
+    /// scan
+    ///
+    /// Find the mods installed in `file_system`'s mods directory - each subdirectory with a
+    /// `mod.json`
+    pub fn scan(file_system: &dyn FileSystem) -> ModManager {
+        let mut manager = ModManager::default();
+        for id in file_system.open_dir(MODS_DIR) {
+            let dir = format!("{}/{}", MODS_DIR, id);
+            let manifest_path = format!("{}/{}", dir, MANIFEST_FILE);
+            if !file_system.file_exists(&manifest_path) {
+                continue;
+            }
+
+            let manifest = read_to_string(file_system, &manifest_path).and_then(|json| {
+                serde_json::from_str::<ModManifest>(&json).map_err(|err| err.to_string())
+            });
+            match manifest {
+                Ok(manifest) => {
+                    let mut files = Vec::new();
+                    collect_files(file_system, &dir, "", &mut files);
+                    files.sort();
+                    manager.installed.push(ModPackage {
+                        id,
+                        manifest,
+                        files,
+                    });
+                }
+                Err(error) => {
+                    warn!("Unable to read mod manifest for {}: {}", id, error);
+                    manager
+                        .scan_issues
+                        .push(ModIssue::InvalidManifest { id, error });
+                }
+            }
+        }
+
+        manager.installed.sort_by(|a, b| a.id.cmp(&b.id));
+        manager
+    }
+
+    pub fn installed(&self) -> &[ModPackage] {
+        &self.installed
+    }
+
+    ///
+    /// resolve
+    ///
+    /// Validate the enabled mods and put them in load order
+    pub fn resolve(&self, enabled: &[String]) -> ModLoadOrder {
+        let mut issues = Vec::new();
+
+        let mut candidates: Vec<&ModPackage> = Vec::new();
+        for id in enabled {
+            if candidates.iter().any(|package| &package.id == id) {
+                continue;
+            }
+            match self.installed.iter().find(|package| &package.id == id) {
+                Some(package) => candidates.push(package),
+                None => {
+                    let invalid_manifest = self.scan_issues.iter().find(|issue| {
+                        matches!(issue, ModIssue::InvalidManifest { id: bad, .. } if bad == id)
+                    });
+                    issues.push(
+                        invalid_manifest
+                            .cloned()
+                            .unwrap_or_else(|| ModIssue::NotInstalled { id: id.clone() }),
+                    );
+                }
+            }
+        }
+        candidates
+            .sort_by(|a, b| (a.manifest.load_order, &a.id).cmp(&(b.manifest.load_order, &b.id)));
+
+        // Declared conflicts - the mod that loads first is kept
+        let mut compatible: Vec<&ModPackage> = Vec::new();
+        for package in candidates {
+            let conflict = compatible.iter().find(|other| {
+                package.manifest.conflicts.contains(&other.id)
+                    || other.manifest.conflicts.contains(&package.id)
+            });
+            match conflict {
+                Some(other) => issues.push(ModIssue::Incompatible {
+                    id: package.id.clone(),
+                    other: other.id.clone(),
+                }),
+                None => compatible.push(package),
+            }
+        }
+
+        // Dropping a mod can leave others without a dependency, so repeat until nothing changes
+        loop {
+            let ids: HashSet<&str> = compatible.iter().map(|p| p.id.as_str()).collect();
+            let missing = compatible.iter().find_map(|package| {
+                package
+                    .manifest
+                    .dependencies
+                    .iter()
+                    .find(|dependency| !ids.contains(dependency.as_str()))
+                    .map(|dependency| (package.id.clone(), dependency.clone()))
+            });
+            let Some((id, dependency)) = missing else {
+                break;
+            };
+            compatible.retain(|package| package.id != id);
+            issues.push(ModIssue::MissingDependency { id, dependency });
+        }
+
+        // Dependencies first, otherwise by load order
+        let mut active: Vec<ModPackage> = Vec::new();
+        while !compatible.is_empty() {
+            let ready = compatible.iter().position(|package| {
+                package
+                    .manifest
+                    .dependencies
+                    .iter()
+                    .all(|dependency| active.iter().any(|loaded| &loaded.id == dependency))
+            });
+            match ready {
+                Some(index) => active.push(compatible.remove(index).clone()),
+                None => {
+                    for package in compatible.drain(..) {
+                        issues.push(ModIssue::DependencyCycle {
+                            id: package.id.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut file_owners: HashMap<&str, &str> = HashMap::new();
+        for package in &active {
+            for file in &package.files {
+                if let Some(loser) = file_owners.insert(file.as_str(), package.id.as_str()) {
+                    issues.push(ModIssue::FileOverride {
+                        file: file.clone(),
+                        winner: package.id.clone(),
+                        loser: loser.to_owned(),
+                    });
+                }
+            }
+        }
+
+        ModLoadOrder { active, issues }
+    }
+
+    ///
+    /// describe
+    ///
+    /// The installed mods and any problems with the enabled ones, for the console and `--list-mods`
+    pub fn describe(&self, enabled: &[String]) -> Vec<String> {
+        let load_order = self.resolve(enabled);
+        let mut lines = Vec::new();
+        if self.installed.is_empty() {
+            lines.push(format!("No mods installed in {}/", MODS_DIR));
+        }
+
+        for (index, package) in load_order.active.iter().enumerate() {
+            lines.push(format!(
+                "  {:>2}. {} ({} {})",
+                index + 1,
+                package.id,
+                package.display_name(),
+                package.manifest.version
+            ));
+        }
+        for package in &self.installed {
+            if !load_order.is_active(&package.id) {
+                lines.push(format!(
+                    "   -  {} ({} {}){}",
+                    package.id,
+                    package.display_name(),
+                    package.manifest.version,
+                    if enabled.contains(&package.id) {
+                        " - not loaded"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+        }
+        for issue in &load_order.issues {
+            lines.push(format!("  {}", issue));
+        }
+        lines
+    }
+}
+
+/// Parse a comma-separated list of mod ids, ie from `--mods`
+pub fn parse_mod_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_owned())
+        .collect()
+}
+
+fn read_to_string(file_system: &dyn FileSystem, path: &str) -> Result<String, String> {
+    let mut reader = file_system
+        .open_reader(path)
+        .ok_or_else(|| format!("unable to open {}", path))?;
+    let mut contents = String::new();
+    reader
+        .read_to_string(&mut contents)
+        .map_err(|err| err.to_string())?;
+    Ok(contents)
+}
+
+/// Collect the files under `dir`, relative to the mod - file systems only list directories, so
+/// an entry that lists children is a directory, and anything else that exists is a file
+fn collect_files(file_system: &dyn FileSystem, dir: &str, prefix: &str, files: &mut Vec<String>) {
+    for name in file_system.open_dir(dir) {
+        let path = format!("{}/{}", dir, name);
+        let name = name.to_ascii_lowercase();
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if !file_system.open_dir(&path).is_empty() {
+            collect_files(file_system, &path, &relative, files);
+        } else if relative != MANIFEST_FILE && file_system.file_exists(&path) {
+            files.push(relative);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(id: &str, load_order: i32, dependencies: &[&str], files: &[&str]) -> ModPackage {
+        ModPackage {
+            id: id.to_owned(),
+            manifest: ModManifest {
+                load_order,
+                dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+                ..ModManifest::default()
+            },
+            files: files.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    fn active_ids(load_order: &ModLoadOrder) -> Vec<&str> {
+        load_order.active.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_dependencies_load_first() {
+        let manager = ModManager::new(vec![
+            package("base", 10, &[], &[]),
+            package("addon", 0, &["base"], &[]),
+            package("other", 5, &[], &[]),
+        ]);
+        let enabled = parse_mod_list("addon, base,other");
+        let load_order = manager.resolve(&enabled);
+        assert_eq!(active_ids(&load_order), vec!["other", "base", "addon"]);
+        assert!(load_order.issues.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_mods_are_dropped() {
+        let mut conflicting = package("conflicting", 1, &[], &[]);
+        conflicting.manifest.conflicts = vec!["base".to_owned()];
+        let manager = ModManager::new(vec![
+            package("base", 0, &[], &[]),
+            package("addon", 0, &["missing"], &[]),
+            package("addon_addon", 0, &["addon"], &[]),
+            conflicting,
+        ]);
+        let enabled = parse_mod_list("base,addon,addon_addon,conflicting,unknown");
+        let load_order = manager.resolve(&enabled);
+        assert_eq!(active_ids(&load_order), vec!["base"]);
+        assert!(load_order.issues.contains(&ModIssue::NotInstalled {
+            id: "unknown".to_owned()
+        }));
+        assert!(load_order.issues.contains(&ModIssue::Incompatible {
+            id: "conflicting".to_owned(),
+            other: "base".to_owned()
+        }));
+        assert!(load_order.issues.contains(&ModIssue::MissingDependency {
+            id: "addon_addon".to_owned(),
+            dependency: "addon".to_owned()
+        }));
+    }
+
+    #[test]
+    fn test_later_mods_override_files() {
+        let manager = ModManager::new(vec![
+            package("a", 0, &[], &["res/obj/crate.bin"]),
+            package("b", 1, &[], &["res/obj/crate.bin"]),
+        ]);
+        let load_order = manager.resolve(&parse_mod_list("b,a"));
+        assert_eq!(
+            load_order.issues,
+            vec![ModIssue::FileOverride {
+                file: "res/obj/crate.bin".to_owned(),
+                winner: "b".to_owned(),
+                loser: "a".to_owned(),
+            }]
+        );
+        assert!(!load_order.issues[0].is_error());
+    }
+}
@@ -0,0 +1,198 @@
+///
+/// noise.rs
+///
+/// Noises the player makes - footsteps, landings, gunfire, breaking things - each heard by AI within
+/// its radius. Quiet movement carries a short way, so a patient player can sneak past, while a gunshot
+/// brings everything nearby running.
+///
+/// Noises are emitted through `Effect::MakeNoise`, and heard by AI on the following update.
+///
+use cgmath::{InnerSpace, Vector3};
+use dark::{SCALE_FACTOR, properties::AIAlertLevel};
+use shipyard::{Unique, UniqueView, World};
+
+// How far the loudest of each kind of noise carries
+const FOOTSTEP_RADIUS: f32 = 20.0 / SCALE_FACTOR;
+const LANDING_RADIUS: f32 = 30.0 / SCALE_FACTOR;
+const WEAPON_FIRE_RADIUS: f32 = 80.0 / SCALE_FACTOR;
+const BREAKING_RADIUS: f32 = 40.0 / SCALE_FACTOR;
+
+// Noises heard at least this loud (after falling off with distance) put AI on moderate alert
+const MODERATE_ALERT_LOUDNESS: f32 = 0.5;
+
+// How quickly the HUD noise meter falls back to silent, per second
+const NOISE_LEVEL_DECAY: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoiseKind {
+    Footstep,
+    Landing,
+    WeaponFire,
+    Breaking,
+}
+
+impl NoiseKind {
+    pub fn max_radius(&self) -> f32 {
+        match self {
+            NoiseKind::Footstep => FOOTSTEP_RADIUS,
+            NoiseKind::Landing => LANDING_RADIUS,
+            NoiseKind::WeaponFire => WEAPON_FIRE_RADIUS,
+            NoiseKind::Breaking => BREAKING_RADIUS,
+        }
+    }
+}
+
+///
+/// Noise
+///
+/// A noise at a position - `loudness` (0.0 to 1.0) scales how far it carries
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+    pub kind: NoiseKind,
+    pub position: Vector3<f32>,
+    pub loudness: f32,
+}
+
+impl Noise {
+    pub fn new(kind: NoiseKind, position: Vector3<f32>, loudness: f32) -> Noise {
+        Noise {
+            kind,
+            position,
+            loudness: loudness.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.kind.max_radius() * self.loudness
+    }
+
+    ///
+    /// loudness_at
+    ///
+    /// How loud the noise is at a listener, falling off to nothing at its radius. `hearing` scales
+    /// the radius, for AI with better or worse than average hearing.
+    pub fn loudness_at(&self, listener: Vector3<f32>, hearing: f32) -> f32 {
+        let radius = self.radius() * hearing;
+        if radius <= 0.0 {
+            return 0.0;
+        }
+
+        let distance = (listener - self.position).magnitude();
+        (1.0 - distance / radius).max(0.0) * self.loudness
+    }
+}
+
+///
+/// NoiseEvents
+///
+/// Noises from the last update, for AI to hear, and how much noise the player is making for the HUD
+#[derive(Unique, Default)]
+pub struct NoiseEvents {
+    audible: Vec<Noise>,
+    pending: Vec<Noise>,
+    noise_level: f32,
+}
+
+impl NoiseEvents {
+    pub fn new() -> NoiseEvents {
+        NoiseEvents::default()
+    }
+
+    pub fn emit(&mut self, noise: Noise) {
+        self.noise_level = self.noise_level.max(noise.loudness);
+        self.pending.push(noise);
+    }
+
+    /// Make the noises emitted since the last update audible, replacing the previous ones
+    pub fn advance(&mut self, delta_time: f32) {
+        self.audible = std::mem::take(&mut self.pending);
+        self.noise_level = (self.noise_level - NOISE_LEVEL_DECAY * delta_time).max(0.0);
+    }
+
+    pub fn audible(&self) -> &[Noise] {
+        &self.audible
+    }
+
+    /// How much noise the player has made recently, from 0.0 (silent) to 1.0
+    pub fn noise_level(&self) -> f32 {
+        self.noise_level
+    }
+
+    ///
+    /// alert_level_heard
+    ///
+    /// The alert level the loudest noise heard at `listener` should raise an AI to, if any
+    pub fn alert_level_heard(&self, listener: Vector3<f32>, hearing: f32) -> Option<AIAlertLevel> {
+        let loudest = self
+            .audible
+            .iter()
+            .map(|noise| noise.loudness_at(listener, hearing))
+            .fold(0.0, f32::max);
+
+        if loudest >= MODERATE_ALERT_LOUDNESS {
+            Some(AIAlertLevel::Moderate)
+        } else if loudest > 0.0 {
+            Some(AIAlertLevel::Low)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the player's noise level (0.0 to 1.0), for the HUD
+pub fn get_noise_level(world: &World) -> f32 {
+    world
+        .borrow::<UniqueView<NoiseEvents>>()
+        .map(|noise_events| noise_events.noise_level())
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::vec3;
+
+    #[test]
+    fn test_noise_heard_by_distance() {
+        let mut noise_events = NoiseEvents::new();
+        noise_events.emit(Noise::new(NoiseKind::Footstep, vec3(0.0, 0.0, 0.0), 0.5));
+        noise_events.emit(Noise::new(
+            NoiseKind::WeaponFire,
+            vec3(100.0, 0.0, 0.0),
+            1.0,
+        ));
+
+        // Nothing is heard until the update after it's made
+        assert_eq!(
+            noise_events.alert_level_heard(vec3(0.0, 0.0, 0.0), 1.0),
+            None
+        );
+        noise_events.advance(0.1);
+
+        // A quiet footstep only carries a short way
+        let footstep_radius = FOOTSTEP_RADIUS * 0.5;
+        assert_eq!(
+            noise_events.alert_level_heard(vec3(0.0, 0.0, footstep_radius * 0.5), 1.0),
+            Some(AIAlertLevel::Low)
+        );
+        assert_eq!(
+            noise_events.alert_level_heard(vec3(0.0, 0.0, footstep_radius * 1.1), 1.0),
+            None
+        );
+        // ...and deaf AI don't hear anything
+        assert_eq!(
+            noise_events.alert_level_heard(vec3(0.0, 0.0, 0.0), 0.0),
+            None
+        );
+
+        // Gunfire up close is alarming
+        assert_eq!(
+            noise_events.alert_level_heard(vec3(100.0, 1.0, 0.0), 1.0),
+            Some(AIAlertLevel::Moderate)
+        );
+
+        // Noises only last an update
+        noise_events.advance(0.1);
+        assert!(noise_events.audible().is_empty());
+    }
+}
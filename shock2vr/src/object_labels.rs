@@ -0,0 +1,331 @@
+///
+/// object_labels.rs
+///
+/// Debug labels floating over entities - their names, ids, template ids, scripts and distance
+/// from the player, turned to face the player. They start on with `--debug-show-ids`, and the
+/// `labels` console command switches them and picks what's shown:
+///
+/// - `labels on` / `labels off`
+/// - `labels show name,id,template,scripts,distance` (or `all`)
+/// - `labels archetype <name>` - only entities inheriting from it, ie `labels archetype monsters`
+///   (`labels archetype any` labels everything again)
+/// - `labels range <distance>`
+///
+use std::collections::HashMap;
+
+use bitflags::bitflags;
+use cgmath::{InnerSpace, Matrix4, Rad, vec3};
+use dark::{
+    importers::FONT_IMPORTER,
+    properties::{PropObjName, PropPosition, PropScripts, PropSymName, PropTemplateId},
+    ss2_entity_info::{self, SystemShock2EntityInfo},
+};
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+use shipyard::{Get, IntoIter, IntoWithId, Unique, UniqueView, View, World};
+
+use crate::{
+    PlayerInfo,
+    command::Command,
+    console::{ConsoleCommand, ConsoleRegistry},
+    scripts::Effect,
+};
+
+// Entities further than this from the player aren't labelled, unless the range is changed
+const DEFAULT_RANGE: f32 = 10.0;
+
+// Cap on entities labelled at once - the nearest win
+const MAX_LABELS: usize = 64;
+
+// Spacing between the lines of a label, a little more than the height of `world_space_text`
+const LINE_HEIGHT: f32 = 0.055;
+
+bitflags! {
+    pub struct LabelFields: u32 {
+        const NAME = 1 << 0;
+        const ID = 1 << 1;
+        const TEMPLATE = 1 << 2;
+        const SCRIPTS = 1 << 3;
+        const DISTANCE = 1 << 4;
+    }
+}
+
+impl LabelFields {
+    ///
+    /// parse
+    ///
+    /// A comma separated list of fields, ie `name,id,distance` - or `all`
+    pub fn parse(list: &str) -> Result<LabelFields, String> {
+        list.split(',').filter(|name| !name.is_empty()).try_fold(
+            LabelFields::empty(),
+            |fields, name| {
+                let field = match name.to_ascii_lowercase().as_str() {
+                    "all" => LabelFields::all(),
+                    "name" => LabelFields::NAME,
+                    "id" => LabelFields::ID,
+                    "template" => LabelFields::TEMPLATE,
+                    "scripts" => LabelFields::SCRIPTS,
+                    "distance" => LabelFields::DISTANCE,
+                    _ => return Err(format!("Unknown label field '{}'", name)),
+                };
+                Ok(fields | field)
+            },
+        )
+    }
+}
+
+///
+/// ObjectLabels
+///
+/// Whether labels are shown, what's on them, and which entities get one
+#[derive(Unique, Clone, Debug, PartialEq)]
+pub struct ObjectLabels {
+    pub enabled: bool,
+    pub fields: LabelFields,
+    /// Only label entities inheriting from this archetype - None labels everything in range
+    pub archetype: Option<String>,
+    pub range: f32,
+}
+
+impl ObjectLabels {
+    pub fn new(enabled: bool) -> ObjectLabels {
+        ObjectLabels {
+            enabled,
+            fields: LabelFields::NAME | LabelFields::ID | LabelFields::TEMPLATE,
+            archetype: None,
+            range: DEFAULT_RANGE,
+        }
+    }
+
+    /// Apply a change from the console - changing what's shown turns the labels on, too
+    fn apply(&mut self, change: &LabelsChange) {
+        match change {
+            LabelsChange::Enabled(enabled) => self.enabled = *enabled,
+            LabelsChange::Fields(fields) => {
+                self.enabled = true;
+                self.fields = *fields;
+            }
+            LabelsChange::Archetype(archetype) => {
+                self.enabled = true;
+                self.archetype = archetype.clone();
+            }
+            LabelsChange::Range(range) => {
+                self.enabled = true;
+                self.range = *range;
+            }
+        }
+    }
+}
+
+///
+/// inherits_from
+///
+/// Whether the template, or one of its ancestors, is the named archetype
+fn inherits_from(
+    template_id: i32,
+    archetype: &str,
+    hierarchy: &HashMap<i32, Vec<i32>>,
+    obj_map: &HashMap<i32, String>,
+) -> bool {
+    let mut ancestors = ss2_entity_info::get_ancestors(hierarchy, &template_id);
+    ancestors.push(template_id);
+
+    ancestors
+        .iter()
+        .filter_map(|id| obj_map.get(id))
+        .any(|name| name.eq_ignore_ascii_case(archetype))
+}
+
+///
+/// render
+///
+/// Labels for the entities around the player, nearest first, each line turned towards the player
+pub fn render(
+    world: &World,
+    asset_cache: &mut AssetCache,
+    entity_info: &SystemShock2EntityInfo,
+    obj_map: &HashMap<i32, String>,
+) -> Vec<SceneObject> {
+    let Ok(labels) = world.borrow::<UniqueView<ObjectLabels>>() else {
+        return vec![];
+    };
+
+    if !labels.enabled || labels.fields.is_empty() {
+        return vec![];
+    }
+
+    let (player_pos, player_entity_id) = {
+        let player_info = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+        (player_info.pos, player_info.entity_id)
+    };
+    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+
+    let mut nearby = world.run(
+        |v_position: View<PropPosition>, v_template_id: View<PropTemplateId>| {
+            v_position
+                .iter()
+                .with_id()
+                .filter(|(entity_id, _)| *entity_id != player_entity_id)
+                .filter_map(|(entity_id, position)| {
+                    let distance = (position.position - player_pos).magnitude();
+                    if distance > labels.range {
+                        return None;
+                    }
+
+                    if let Some(archetype) = &labels.archetype {
+                        let template_id = v_template_id.get(entity_id).ok()?.template_id;
+                        if !inherits_from(template_id, archetype, hierarchy, obj_map) {
+                            return None;
+                        }
+                    }
+
+                    Some((entity_id, position.position, distance))
+                })
+                .collect::<Vec<_>>()
+        },
+    );
+    nearby.sort_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+    nearby.truncate(MAX_LABELS);
+
+    let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+    world.run(
+        |v_template_id: View<PropTemplateId>,
+         v_sym_name: View<PropSymName>,
+         v_obj_name: View<PropObjName>,
+         v_scripts: View<PropScripts>| {
+            let mut scene_objects = Vec::new();
+            for (entity_id, position, distance) in nearby {
+                let template_id = v_template_id.get(entity_id).map(|t| t.template_id).ok();
+
+                let mut lines = Vec::new();
+                if labels.fields.contains(LabelFields::NAME) {
+                    let name = v_sym_name
+                        .get(entity_id)
+                        .map(|s| s.0.clone())
+                        .or_else(|_| v_obj_name.get(entity_id).map(|o| o.0.clone()))
+                        .ok()
+                        .or_else(|| template_id.and_then(|id| obj_map.get(&id).cloned()))
+                        .unwrap_or_else(|| "?".to_owned());
+                    lines.push(name);
+                }
+
+                let mut ids = Vec::new();
+                if labels.fields.contains(LabelFields::ID) {
+                    ids.push(format!("Ent {}", entity_id.inner()));
+                }
+                if labels.fields.contains(LabelFields::TEMPLATE) {
+                    let template_id = template_id
+                        .map(|id| id.to_string())
+                        .unwrap_or_else(|| "None".to_owned());
+                    ids.push(format!("Tem {}", template_id));
+                }
+                if !ids.is_empty() {
+                    lines.push(ids.join(" | "));
+                }
+
+                if labels.fields.contains(LabelFields::SCRIPTS) {
+                    if let Ok(scripts) = v_scripts.get(entity_id) {
+                        if !scripts.scripts.is_empty() {
+                            lines.push(scripts.scripts.join(", "));
+                        }
+                    }
+                }
+
+                if labels.fields.contains(LabelFields::DISTANCE) {
+                    lines.push(format!("{:.1}", distance));
+                }
+
+                let to_player = player_pos - position;
+                let yaw = Rad(to_player.x.atan2(to_player.z));
+
+                // Bottom line first, so the first line ends up on top
+                for (idx, line) in lines.iter().rev().enumerate() {
+                    let mut text = SceneObject::world_space_text(line, font.clone(), 0.0);
+                    text.set_transform(
+                        Matrix4::from_translation(
+                            position + vec3(0.0, LINE_HEIGHT * idx as f32, 0.0),
+                        ) * Matrix4::from_angle_y(yaw),
+                    );
+                    scene_objects.push(text);
+                }
+            }
+            scene_objects
+        },
+    )
+}
+
+pub fn register_console_commands(registry: &mut ConsoleRegistry) {
+    registry.register(ConsoleCommand {
+        name: "labels",
+        usage: "labels <on|off> | labels show <name,id,template,scripts,distance|all> | labels archetype <name|any> | labels range <distance>",
+        help: "label entities with their names, ids, template ids, scripts or distances",
+        parse: |args| {
+            let change = match args {
+                ["on"] => LabelsChange::Enabled(true),
+                ["off"] => LabelsChange::Enabled(false),
+                ["show", fields] => LabelsChange::Fields(LabelFields::parse(fields)?),
+                ["archetype", "any"] => LabelsChange::Archetype(None),
+                ["archetype", archetype] => LabelsChange::Archetype(Some(archetype.to_string())),
+                ["range", range] => match range.parse::<f32>() {
+                    Ok(range) if range > 0.0 => LabelsChange::Range(range),
+                    _ => return Err(format!("'{}' is not a distance", range)),
+                },
+                _ => return Err("Expected on, off, show, archetype or range".to_owned()),
+            };
+            Ok(Box::new(ObjectLabelsCommand { change }))
+        },
+    });
+}
+
+#[derive(Debug)]
+enum LabelsChange {
+    Enabled(bool),
+    Fields(LabelFields),
+    Archetype(Option<String>),
+    Range(f32),
+}
+
+#[derive(Debug)]
+struct ObjectLabelsCommand {
+    change: LabelsChange,
+}
+
+impl Command for ObjectLabelsCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let mut labels = world
+            .borrow::<UniqueView<ObjectLabels>>()
+            .map(|labels| labels.clone())
+            .unwrap_or_else(|_| ObjectLabels::new(false));
+        labels.apply(&self.change);
+        Effect::SetObjectLabels { labels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_fields_parse() {
+        assert_eq!(
+            LabelFields::parse("name,distance"),
+            Ok(LabelFields::NAME | LabelFields::DISTANCE)
+        );
+        assert_eq!(LabelFields::parse("all"), Ok(LabelFields::all()));
+        assert!(LabelFields::parse("name,colour").is_err());
+    }
+
+    #[test]
+    fn test_inherits_from_ancestor_archetype() {
+        // -2 (MedSciBot) -> -1 (Monsters)
+        let hierarchy = HashMap::from([(-2, vec![-1])]);
+        let obj_map = HashMap::from([
+            (-1, "Monsters".to_owned()),
+            (-2, "MedSciBot".to_owned()),
+            (-3, "Crate".to_owned()),
+        ]);
+
+        assert!(inherits_from(-2, "monsters", &hierarchy, &obj_map));
+        assert!(inherits_from(-2, "medscibot", &hierarchy, &obj_map));
+        assert!(!inherits_from(-3, "monsters", &hierarchy, &obj_map));
+    }
+}
@@ -1,14 +1,56 @@
-use std::path::Path;
+use std::{
+    path::Path,
+    sync::{Arc, OnceLock},
+};
 
-#[cfg(not(target_os = "android"))]
-use std::path::PathBuf;
+use engine::{
+    assets::asset_paths::ReadableAndSeekable,
+    file_system::{DefaultFileSystem, FileSystem},
+};
 
 #[cfg(not(target_os = "android"))]
-use std::sync::OnceLock;
+use std::path::PathBuf;
 
 #[cfg(not(target_os = "android"))]
 use tracing::warn;
 
+static DATA_FILE_SYSTEM: OnceLock<Arc<dyn FileSystem>> = OnceLock::new();
+
+///
+/// set_data_file_system
+///
+/// Read the game data through the given file system, instead of from `data_root()` - for
+/// platforms where the data isn't a plain directory, like the Android asset manager or a
+/// scoped-storage tree. Must be called before the game is initialized; returns false if the data
+/// file system was already set (or already used).
+pub fn set_data_file_system(file_system: Arc<dyn FileSystem>) -> bool {
+    DATA_FILE_SYSTEM.set(file_system).is_ok()
+}
+
+///
+/// data_file_system
+///
+/// The file system the game data is read from - `data_root()` on disk, unless a runtime has
+/// provided another with `set_data_file_system`
+pub fn data_file_system() -> Arc<dyn FileSystem> {
+    DATA_FILE_SYSTEM
+        .get_or_init(|| {
+            Arc::new(DefaultFileSystem {
+                root_path: Box::new(data_root()),
+            })
+        })
+        .clone()
+}
+
+/// Open a file in the game data, relative to the data root
+pub fn open_data_file(path: &str) -> Option<Box<dyn ReadableAndSeekable>> {
+    data_file_system().open_reader(path)
+}
+
+pub fn data_file_exists(path: &str) -> bool {
+    data_file_system().file_exists(path)
+}
+
 #[cfg(target_os = "android")]
 pub fn data_root() -> &'static Path {
     Path::new("/sdcard/shock2quest")
@@ -0,0 +1,271 @@
+use cgmath::{Deg, InnerSpace, Quaternion, Rotation, Rotation3, Vector3, vec3};
+
+use crate::input_context::InputContext;
+
+pub const DEFAULT_FOV: f32 = 45.0;
+const MIN_FOV: f32 = 10.0;
+const MAX_FOV: f32 = 120.0;
+
+// Speeds are per second, with the thumbstick fully pushed
+const MOVE_SPEED: f32 = 8.0 / dark::SCALE_FACTOR;
+const LOOK_SPEED: f32 = 90.0;
+const ROLL_SPEED: f32 = 45.0;
+const ZOOM_SPEED: f32 = 30.0;
+
+// Where the drone starts, relative to the player's head - behind and above, looking down a bit
+const DRONE_HEAD_HEIGHT: f32 = 4.0 / dark::SCALE_FACTOR;
+const DRONE_START_BACK: f32 = 6.0 / dark::SCALE_FACTOR;
+const DRONE_START_UP: f32 = 3.0 / dark::SCALE_FACTOR;
+const DRONE_START_PITCH: f32 = -20.0;
+
+// Analog inputs count as pressed past this point
+const PRESS_THRESHOLD: f32 = 0.5;
+
+///
+/// PhotoFilter
+///
+/// Color filter applied to captured photos
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PhotoFilter {
+    #[default]
+    None,
+    Grayscale,
+    Sepia,
+    HighContrast,
+}
+
+impl PhotoFilter {
+    pub fn next(&self) -> PhotoFilter {
+        match self {
+            PhotoFilter::None => PhotoFilter::Grayscale,
+            PhotoFilter::Grayscale => PhotoFilter::Sepia,
+            PhotoFilter::Sepia => PhotoFilter::HighContrast,
+            PhotoFilter::HighContrast => PhotoFilter::None,
+        }
+    }
+
+    ///
+    /// apply
+    ///
+    /// Filter tightly-packed RGB pixels in place
+    pub fn apply(&self, rgb: &mut [u8]) {
+        if *self == PhotoFilter::None {
+            return;
+        }
+
+        for pixel in rgb.chunks_exact_mut(3) {
+            let r = pixel[0] as f32;
+            let g = pixel[1] as f32;
+            let b = pixel[2] as f32;
+
+            let (r, g, b) = match self {
+                PhotoFilter::None => (r, g, b),
+                PhotoFilter::Grayscale => {
+                    let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+                    (luma, luma, luma)
+                }
+                PhotoFilter::Sepia => (
+                    0.393 * r + 0.769 * g + 0.189 * b,
+                    0.349 * r + 0.686 * g + 0.168 * b,
+                    0.272 * r + 0.534 * g + 0.131 * b,
+                ),
+                PhotoFilter::HighContrast => {
+                    let contrast = |v: f32| (v - 128.0) * 1.5 + 128.0;
+                    (contrast(r), contrast(g), contrast(b))
+                }
+            };
+
+            pixel[0] = r.clamp(0.0, 255.0) as u8;
+            pixel[1] = g.clamp(0.0, 255.0) as u8;
+            pixel[2] = b.clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+///
+/// PhotoModeAction
+///
+/// Discrete photo mode controls - runtimes map keys / buttons to these
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhotoModeAction {
+    Toggle,
+    Capture,
+    CycleFilter,
+    ToggleHud,
+    ToggleHands,
+}
+
+///
+/// PhotoMode
+///
+/// A free-flying camera, used while the simulation is paused. In VR, this acts as a
+/// third-person drone - the headset still looks around from the drone's position.
+///
+/// Controls (from the input context, so they work the same for every runtime):
+/// - right thumbstick: move, right A / left B (Y): rise / descend
+/// - left thumbstick: look around - or, with the left trigger held, roll (x) and zoom (y)
+/// - right trigger: capture, right squeeze: cycle filter, left squeeze: toggle HUD
+#[derive(Clone, Debug)]
+pub struct PhotoMode {
+    pub position: Vector3<f32>,
+    /// Degrees
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub fov: f32,
+    pub filter: PhotoFilter,
+    pub hide_hud: bool,
+    pub hide_hands: bool,
+
+    capture_requested: bool,
+    last_input: PhotoButtons,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct PhotoButtons {
+    capture: bool,
+    cycle_filter: bool,
+    toggle_hud: bool,
+}
+
+impl PhotoMode {
+    ///
+    /// drone
+    ///
+    /// Start the camera behind and above the player, facing the same way they are
+    pub fn drone(player_position: Vector3<f32>, player_rotation: Quaternion<f32>) -> PhotoMode {
+        let forward = player_rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
+        let flat_forward = vec3(forward.x, 0.0, forward.z);
+        let flat_forward = if flat_forward.magnitude2() > 0.0001 {
+            flat_forward.normalize()
+        } else {
+            vec3(0.0, 0.0, -1.0)
+        };
+        let yaw = Deg::from(cgmath::Rad((-flat_forward.x).atan2(-flat_forward.z))).0;
+
+        let position = player_position + vec3(0.0, DRONE_HEAD_HEIGHT + DRONE_START_UP, 0.0)
+            - flat_forward * DRONE_START_BACK;
+
+        PhotoMode {
+            position,
+            yaw,
+            pitch: DRONE_START_PITCH,
+            roll: 0.0,
+            fov: DEFAULT_FOV,
+            filter: PhotoFilter::None,
+            hide_hud: true,
+            hide_hands: true,
+            capture_requested: false,
+            last_input: PhotoButtons::default(),
+        }
+    }
+
+    pub fn rotation(&self) -> Quaternion<f32> {
+        Quaternion::from_angle_y(Deg(self.yaw))
+            * Quaternion::from_angle_x(Deg(self.pitch))
+            * Quaternion::from_angle_z(Deg(self.roll))
+    }
+
+    pub fn update(&mut self, input_context: &InputContext, delta_time: f32) {
+        let left = &input_context.left_hand;
+        let right = &input_context.right_hand;
+
+        if left.trigger_value > PRESS_THRESHOLD {
+            self.roll += left.thumbstick.x * ROLL_SPEED * delta_time;
+            self.fov =
+                (self.fov - left.thumbstick.y * ZOOM_SPEED * delta_time).clamp(MIN_FOV, MAX_FOV);
+        } else {
+            // Thumbstick x is positive to the left (see the runtimes)
+            self.yaw += left.thumbstick.x * LOOK_SPEED * delta_time;
+            self.pitch =
+                (self.pitch + left.thumbstick.y * LOOK_SPEED * delta_time).clamp(-89.0, 89.0);
+        }
+
+        let rotation = self.rotation();
+        let forward = rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
+        let right_dir = rotation.rotate_vector(vec3(1.0, 0.0, 0.0));
+        let vertical = right.a_value - left.b_value;
+        let movement = forward * right.thumbstick.y - right_dir * right.thumbstick.x
+            + vec3(0.0, vertical, 0.0);
+        self.position += movement * MOVE_SPEED * delta_time;
+
+        let buttons = PhotoButtons {
+            capture: right.trigger_value > PRESS_THRESHOLD,
+            cycle_filter: right.squeeze_value > PRESS_THRESHOLD,
+            toggle_hud: left.squeeze_value > PRESS_THRESHOLD,
+        };
+        if buttons.capture && !self.last_input.capture {
+            self.handle_action(PhotoModeAction::Capture);
+        }
+        if buttons.cycle_filter && !self.last_input.cycle_filter {
+            self.handle_action(PhotoModeAction::CycleFilter);
+        }
+        if buttons.toggle_hud && !self.last_input.toggle_hud {
+            self.handle_action(PhotoModeAction::ToggleHud);
+        }
+        self.last_input = buttons;
+    }
+
+    ///
+    /// handle_action
+    ///
+    /// Apply an action while photo mode is active - `Toggle` is handled by the game
+    pub fn handle_action(&mut self, action: PhotoModeAction) {
+        match action {
+            PhotoModeAction::Toggle => (),
+            PhotoModeAction::Capture => self.capture_requested = true,
+            PhotoModeAction::CycleFilter => self.filter = self.filter.next(),
+            PhotoModeAction::ToggleHud => self.hide_hud = !self.hide_hud,
+            PhotoModeAction::ToggleHands => self.hide_hands = !self.hide_hands,
+        }
+    }
+
+    ///
+    /// take_capture_request
+    ///
+    /// Whether a capture was asked for since the last call - the runtime does the actual capture,
+    /// since it owns the framebuffers
+    pub fn take_capture_request(&mut self) -> bool {
+        std::mem::take(&mut self.capture_requested)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let mut pixels = [255, 0, 0, 10, 200, 30];
+        PhotoFilter::Grayscale.apply(&mut pixels);
+        assert_eq!(pixels[0], pixels[1]);
+        assert_eq!(pixels[1], pixels[2]);
+        assert_eq!(pixels[3], pixels[5]);
+
+        let mut pixels = [255, 255, 255];
+        PhotoFilter::Sepia.apply(&mut pixels);
+        assert_eq!(pixels, [255, 255, 238]);
+
+        let mut pixels = [100, 128, 200];
+        PhotoFilter::HighContrast.apply(&mut pixels);
+        assert_eq!(pixels, [86, 128, 236]);
+    }
+
+    #[test]
+    fn test_filter_cycles_back_to_none() {
+        let mut filter = PhotoFilter::None;
+        for _ in 0..4 {
+            filter = filter.next();
+        }
+        assert_eq!(filter, PhotoFilter::None);
+    }
+
+    #[test]
+    fn test_drone_starts_behind_player() {
+        let photo_mode = PhotoMode::drone(vec3(0.0, 0.0, 0.0), Quaternion::from_angle_y(Deg(0.0)));
+        // The player faces -z, so the drone should be at +z, looking the same way
+        assert!(photo_mode.position.z > 0.0);
+        assert!(photo_mode.position.y > DRONE_HEAD_HEIGHT);
+        assert!(photo_mode.yaw.abs() < 0.001);
+    }
+}
@@ -0,0 +1,421 @@
+///
+/// collision_layers.rs
+///
+/// Data-driven version of the collision group pairings. Each kind of collider (`ColliderProfile`)
+/// gets a rule saying which layers it belongs to, and which layers it collides with. Rapier only
+/// lets two colliders interact if each one's filter accepts the other's memberships - so when
+/// 'the player collides with X but not Y', dumping the matrix shows which side is rejecting.
+///
+/// The rules are loaded from `collision_layers.json` in the bundle - any profile left out keeps
+/// its default:
+///
+/// ```json
+/// {
+///   "hand": { "memberships": ["hand"], "collides_with": ["entity", "selectable"] }
+/// }
+/// ```
+///
+use std::{collections::BTreeMap, io::Read};
+
+use engine::assets::{asset_cache::AssetCache, asset_importer::AssetImporter};
+use once_cell::sync::Lazy;
+use rapier3d::prelude::InteractionGroups;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::InternalCollisionGroups;
+
+const COLLISION_LAYERS_FILE: &str = "collision_layers.json";
+
+///
+/// CollisionLayer
+///
+/// A single bit in the collision groups - the names used in `collision_layers.json`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionLayer {
+    World,
+    Entity,
+    Selectable,
+    Player,
+    Ui,
+    Hitbox,
+    Raycast,
+    Hand,
+}
+
+impl CollisionLayer {
+    pub const ALL: [CollisionLayer; 8] = [
+        CollisionLayer::World,
+        CollisionLayer::Entity,
+        CollisionLayer::Selectable,
+        CollisionLayer::Player,
+        CollisionLayer::Ui,
+        CollisionLayer::Hitbox,
+        CollisionLayer::Raycast,
+        CollisionLayer::Hand,
+    ];
+
+    pub fn group(&self) -> InternalCollisionGroups {
+        match self {
+            CollisionLayer::World => InternalCollisionGroups::WORLD,
+            CollisionLayer::Entity => InternalCollisionGroups::ENTITY,
+            CollisionLayer::Selectable => InternalCollisionGroups::SELECTABLE,
+            CollisionLayer::Player => InternalCollisionGroups::PLAYER,
+            CollisionLayer::Ui => InternalCollisionGroups::UI,
+            CollisionLayer::Hitbox => InternalCollisionGroups::HITBOX,
+            CollisionLayer::Raycast => InternalCollisionGroups::RAYCAST,
+            CollisionLayer::Hand => InternalCollisionGroups::HAND,
+        }
+    }
+
+    fn all_collidable() -> Vec<CollisionLayer> {
+        vec![
+            CollisionLayer::World,
+            CollisionLayer::Entity,
+            CollisionLayer::Selectable,
+            CollisionLayer::Player,
+        ]
+    }
+}
+
+fn to_groups(layers: &[CollisionLayer]) -> InternalCollisionGroups {
+    layers
+        .iter()
+        .fold(InternalCollisionGroups::empty(), |acc, layer| {
+            acc | layer.group()
+        })
+}
+
+fn to_layers(groups: InternalCollisionGroups) -> Vec<CollisionLayer> {
+    CollisionLayer::ALL
+        .into_iter()
+        .filter(|layer| groups.contains(layer.group()))
+        .collect()
+}
+
+///
+/// ColliderProfile
+///
+/// The kinds of collider the game builds - each gets its collision groups from the layer matrix
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ColliderProfile {
+    /// Level geometry, and other static colliders added with `add_collider`
+    Level,
+    Player,
+    Entity,
+    /// Entities the player can pick up, and rag doll bones
+    Selectable,
+    Ui,
+    /// Creature hitboxes - only hit by raycasts
+    Hitbox,
+    /// The kinematic spheres following the VR hands
+    Hand,
+}
+
+impl ColliderProfile {
+    pub const ALL: [ColliderProfile; 7] = [
+        ColliderProfile::Level,
+        ColliderProfile::Player,
+        ColliderProfile::Entity,
+        ColliderProfile::Selectable,
+        ColliderProfile::Ui,
+        ColliderProfile::Hitbox,
+        ColliderProfile::Hand,
+    ];
+}
+
+///
+/// LayerRule
+///
+/// The layers a collider is a member of, and the layers it is willing to collide with
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct LayerRule {
+    pub memberships: Vec<CollisionLayer>,
+    pub collides_with: Vec<CollisionLayer>,
+}
+
+impl LayerRule {
+    fn new(memberships: Vec<CollisionLayer>, collides_with: Vec<CollisionLayer>) -> LayerRule {
+        LayerRule {
+            memberships,
+            collides_with,
+        }
+    }
+
+    pub fn memberships(&self) -> InternalCollisionGroups {
+        to_groups(&self.memberships)
+    }
+
+    pub fn filter(&self) -> InternalCollisionGroups {
+        to_groups(&self.collides_with)
+    }
+}
+
+///
+/// CollisionLayerMatrix
+///
+/// The collision rule for every collider profile. Part of `PhysicsConfig`, applied when colliders are built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollisionLayerMatrix {
+    rules: BTreeMap<ColliderProfile, LayerRule>,
+}
+
+impl Default for CollisionLayerMatrix {
+    fn default() -> CollisionLayerMatrix {
+        use CollisionLayer::*;
+
+        let mut selectable_filter = CollisionLayer::all_collidable();
+        selectable_filter.push(Hand);
+
+        let rules = BTreeMap::from([
+            (
+                ColliderProfile::Level,
+                LayerRule::new(
+                    CollisionLayer::all_collidable(),
+                    CollisionLayer::all_collidable(),
+                ),
+            ),
+            (
+                ColliderProfile::Player,
+                LayerRule::new(vec![Player], CollisionLayer::all_collidable()),
+            ),
+            (
+                ColliderProfile::Entity,
+                LayerRule::new(vec![Entity], vec![World, Player, Selectable, Entity, Hand]),
+            ),
+            (
+                ColliderProfile::Selectable,
+                LayerRule::new(vec![Selectable], selectable_filter),
+            ),
+            (
+                ColliderProfile::Ui,
+                LayerRule::new(vec![Ui], CollisionLayer::all_collidable()),
+            ),
+            (
+                ColliderProfile::Hitbox,
+                LayerRule::new(vec![Hitbox, Raycast], vec![Raycast]),
+            ),
+            // Hands touch entities, but not the world or the player - so they can't hold the player back
+            (
+                ColliderProfile::Hand,
+                LayerRule::new(vec![Hand], vec![Entity, Selectable]),
+            ),
+        ]);
+
+        CollisionLayerMatrix { rules }
+    }
+}
+
+impl CollisionLayerMatrix {
+    ///
+    /// from_json
+    ///
+    /// Parse a map of profile -> rule, applied on top of the defaults
+    pub fn from_json(json: &str) -> Result<CollisionLayerMatrix, serde_json::Error> {
+        let overrides: BTreeMap<ColliderProfile, LayerRule> = serde_json::from_str(json)?;
+        let mut matrix = CollisionLayerMatrix::default();
+        matrix.rules.extend(overrides);
+        Ok(matrix)
+    }
+
+    pub fn load(asset_cache: &mut AssetCache) -> Option<CollisionLayerMatrix> {
+        asset_cache
+            .get_opt(&COLLISION_LAYERS_IMPORTER, COLLISION_LAYERS_FILE)
+            .map(|matrix| matrix.as_ref().clone())
+    }
+
+    pub fn rule(&self, profile: ColliderProfile) -> &LayerRule {
+        // Every profile has a rule - the defaults are always filled in
+        &self.rules[&profile]
+    }
+
+    pub fn interaction_groups(&self, profile: ColliderProfile) -> InteractionGroups {
+        let rule = self.rule(profile);
+        InteractionGroups::new(rule.memberships().bits.into(), rule.filter().bits.into())
+    }
+
+    ///
+    /// collides
+    ///
+    /// Whether colliders of the two profiles interact - both filters have to accept the other's memberships
+    pub fn collides(&self, a: ColliderProfile, b: ColliderProfile) -> bool {
+        self.accepts(a, b) && self.accepts(b, a)
+    }
+
+    fn accepts(&self, a: ColliderProfile, b: ColliderProfile) -> bool {
+        self.rule(a).filter().intersects(self.rule(b).memberships())
+    }
+
+    ///
+    /// dump
+    ///
+    /// Every profile's groups, and how each pair of profiles interacts - for the debug runtime
+    pub fn dump(&self) -> CollisionLayerDump {
+        let profiles = ColliderProfile::ALL
+            .into_iter()
+            .map(|profile| {
+                let rule = self.rule(profile);
+                ProfileDump {
+                    profile,
+                    memberships: to_layers(rule.memberships()),
+                    collides_with: to_layers(rule.filter()),
+                    membership_bits: rule.memberships().bits,
+                    filter_bits: rule.filter().bits,
+                }
+            })
+            .collect();
+
+        let mut interactions = Vec::new();
+        for (idx, a) in ColliderProfile::ALL.into_iter().enumerate() {
+            for b in ColliderProfile::ALL.into_iter().skip(idx) {
+                interactions.push(InteractionDump {
+                    a,
+                    b,
+                    a_accepts_b: self.accepts(a, b),
+                    b_accepts_a: self.accepts(b, a),
+                    collides: self.collides(a, b),
+                });
+            }
+        }
+
+        CollisionLayerDump {
+            profiles,
+            interactions,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProfileDump {
+    pub profile: ColliderProfile,
+    pub memberships: Vec<CollisionLayer>,
+    pub collides_with: Vec<CollisionLayer>,
+    pub membership_bits: u32,
+    pub filter_bits: u32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct InteractionDump {
+    pub a: ColliderProfile,
+    pub b: ColliderProfile,
+    pub a_accepts_b: bool,
+    pub b_accepts_a: bool,
+    pub collides: bool,
+}
+
+///
+/// CollisionLayerDump
+///
+/// Snapshot of the layer matrix, returned by the debug runtime's `/v1/physics/layers`
+#[derive(Serialize, Clone, Debug)]
+pub struct CollisionLayerDump {
+    pub profiles: Vec<ProfileDump>,
+    pub interactions: Vec<InteractionDump>,
+}
+
+fn import_collision_layers(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> CollisionLayerMatrix {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read collision layers {}: {}", name, err);
+        return CollisionLayerMatrix::default();
+    }
+
+    CollisionLayerMatrix::from_json(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse collision layers {}: {}", name, err);
+        CollisionLayerMatrix::default()
+    })
+}
+
+fn process_collision_layers(
+    content: CollisionLayerMatrix,
+    _asset_cache: &mut AssetCache,
+    _config: &(),
+) -> CollisionLayerMatrix {
+    content
+}
+
+static COLLISION_LAYERS_IMPORTER: Lazy<
+    AssetImporter<CollisionLayerMatrix, CollisionLayerMatrix, ()>,
+> = Lazy::new(|| AssetImporter::define(import_collision_layers, process_collision_layers));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(
+        memberships: InternalCollisionGroups,
+        filter: InternalCollisionGroups,
+    ) -> InteractionGroups {
+        InteractionGroups::new(memberships.bits.into(), filter.bits.into())
+    }
+
+    #[test]
+    fn test_defaults_match_builtin_groups() {
+        let matrix = CollisionLayerMatrix::default();
+        assert_eq!(
+            matrix.interaction_groups(ColliderProfile::Level),
+            groups(
+                InternalCollisionGroups::ALL_COLLIDABLE,
+                InternalCollisionGroups::ALL_COLLIDABLE
+            )
+        );
+        assert_eq!(
+            matrix.interaction_groups(ColliderProfile::Player),
+            groups(
+                InternalCollisionGroups::PLAYER,
+                InternalCollisionGroups::ALL_COLLIDABLE
+            )
+        );
+        assert_eq!(
+            matrix.interaction_groups(ColliderProfile::Hitbox),
+            groups(
+                InternalCollisionGroups::HITBOX | InternalCollisionGroups::RAYCAST,
+                InternalCollisionGroups::RAYCAST
+            )
+        );
+        assert_eq!(
+            matrix.interaction_groups(ColliderProfile::Selectable),
+            groups(
+                InternalCollisionGroups::SELECTABLE,
+                InternalCollisionGroups::ALL_COLLIDABLE | InternalCollisionGroups::HAND
+            )
+        );
+    }
+
+    #[test]
+    fn test_shipped_layers_match_defaults() {
+        let json = include_str!("../../../assets/collision_layers.json");
+        assert_eq!(
+            CollisionLayerMatrix::from_json(json).unwrap(),
+            CollisionLayerMatrix::default()
+        );
+    }
+
+    #[test]
+    fn test_overrides_and_pairs() {
+        let matrix = CollisionLayerMatrix::from_json(
+            r#"{ "hand": { "memberships": ["hand"], "collides_with": ["entity", "selectable", "world"] } }"#,
+        )
+        .unwrap();
+
+        // The level doesn't accept hands, so adding world on the hand side alone isn't enough
+        assert!(matrix.accepts(ColliderProfile::Hand, ColliderProfile::Level));
+        assert!(!matrix.collides(ColliderProfile::Hand, ColliderProfile::Level));
+        assert!(matrix.collides(ColliderProfile::Hand, ColliderProfile::Selectable));
+        assert!(!matrix.collides(ColliderProfile::Hand, ColliderProfile::Player));
+        assert!(!matrix.collides(ColliderProfile::Hitbox, ColliderProfile::Player));
+
+        // Profiles left out keep their defaults
+        assert_eq!(
+            matrix.rule(ColliderProfile::Player),
+            CollisionLayerMatrix::default().rule(ColliderProfile::Player)
+        );
+    }
+}
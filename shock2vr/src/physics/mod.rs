@@ -1,10 +1,15 @@
+mod collision_layers;
 mod debug_render_pipeline;
+mod physics_config;
 mod physics_events;
 pub(crate) mod util;
 
 use collision::Aabb3;
 use engine::profile;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+};
 use util::*;
 
 use bitflags::bitflags;
@@ -12,18 +17,37 @@ use cgmath::{InnerSpace, Point3, Quaternion, Vector3, point3};
 use dark::{SCALE_FACTOR, mission::SystemShock2Level};
 use engine::scene::SceneObject;
 use rapier3d::{
-    control::{CharacterLength, KinematicCharacterController},
+    control::{CharacterAutostep, CharacterLength, KinematicCharacterController},
     na::UnitQuaternion,
     prelude::*,
 };
+use rayon::prelude::*;
 use shipyard::EntityId;
 
+pub use collision_layers::{
+    ColliderProfile, CollisionLayer, CollisionLayerDump, CollisionLayerMatrix, LayerRule,
+};
+pub use physics_config::{PhysicsBodyClass, PhysicsConfig};
 use physics_events::*;
 
 use self::debug_render_pipeline::DebugRenderer;
+use crate::state_hash::StateHasher;
 
 const MOVEMENT_STEP_SIZE: f32 = 20.0;
 
+// Fixed simulation step - the physics always advances in increments of this size,
+// regardless of the frame rate
+pub const PHYSICS_TIMESTEP: f32 = 1.0 / 60.0;
+
+// Cap on steps per update, enough for the max time scale at 30fps
+const MAX_STEPS_PER_UPDATE: u32 = 16;
+
+// Smallest number of rays worth handing to another thread in `ray_cast_batch`
+const RAY_CAST_BATCH_MIN_LEN: usize = 8;
+
+// User data for bodies waiting in an entity pool - they don't belong to any entity
+const RETIRED_BODY_USER_DATA: u128 = u64::MAX as u128;
+
 bitflags! {
     pub struct InternalCollisionGroups: u32 {
         const WORLD = 1 << 0; // 1
@@ -33,6 +57,7 @@ bitflags! {
         const UI = 1 << 4;
         const HITBOX = 1 << 5;
         const RAYCAST = 1 << 6;
+        const HAND = 1 << 7;
         const ALL_COLLIDABLE = Self::WORLD.bits | Self::ENTITY.bits | Self::PLAYER.bits | Self::SELECTABLE.bits;
         const ALL = Self::ALL_COLLIDABLE.bits | Self::UI.bits | Self::HITBOX.bits | Self::RAYCAST.bits;
     }
@@ -40,49 +65,106 @@ bitflags! {
 
 pub struct DynamicPhysicsOptions {
     pub gravity_scale: f32,
+    pub body_class: PhysicsBodyClass,
 }
 
 impl Default for DynamicPhysicsOptions {
     fn default() -> DynamicPhysicsOptions {
-        DynamicPhysicsOptions { gravity_scale: 1.0 }
+        DynamicPhysicsOptions {
+            gravity_scale: 1.0,
+            body_class: PhysicsBodyClass::Prop,
+        }
     }
 }
 
-pub struct CollisionGroup(InteractionGroups);
+///
+/// CollisionGroup
+///
+/// Which kind of collider is being built - the actual groups come from the `CollisionLayerMatrix`
+/// in the physics config
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionGroup(pub ColliderProfile);
 
 impl CollisionGroup {
     pub fn hitbox() -> CollisionGroup {
-        CollisionGroup(InteractionGroups {
-            memberships: (InternalCollisionGroups::HITBOX.bits
-                | InternalCollisionGroups::RAYCAST.bits)
-                .into(),
-            filter: InternalCollisionGroups::RAYCAST.bits.into(),
-        })
+        CollisionGroup(ColliderProfile::Hitbox)
     }
 
     pub fn ui() -> CollisionGroup {
-        CollisionGroup(InteractionGroups {
-            memberships: InternalCollisionGroups::UI.bits.into(),
-            filter: InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-        })
+        CollisionGroup(ColliderProfile::Ui)
     }
 
     pub fn entity() -> CollisionGroup {
-        CollisionGroup(InteractionGroups {
-            memberships: InternalCollisionGroups::ENTITY.bits.into(),
-            filter: (InternalCollisionGroups::WORLD.bits
-                | InternalCollisionGroups::PLAYER.bits
-                | InternalCollisionGroups::SELECTABLE.bits
-                | InternalCollisionGroups::ENTITY.bits)
-                .into(),
-        })
+        CollisionGroup(ColliderProfile::Entity)
     }
 
     pub fn selectable() -> CollisionGroup {
-        CollisionGroup(InteractionGroups {
-            memberships: InternalCollisionGroups::SELECTABLE.bits.into(),
-            filter: InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-        })
+        CollisionGroup(ColliderProfile::Selectable)
+    }
+
+    pub fn hand() -> CollisionGroup {
+        CollisionGroup(ColliderProfile::Hand)
+    }
+}
+
+///
+/// HandContactFilter
+///
+/// Kinematic bodies push with unlimited force, so only let the hands push props -
+/// not creatures or projectiles
+struct HandContactFilter<'a> {
+    dynamic_body_classes: &'a HashMap<RigidBodyHandle, PhysicsBodyClass>,
+}
+
+impl PhysicsHooks for HandContactFilter<'_> {
+    fn filter_contact_pair(&self, context: &PairFilterContext) -> Option<SolverFlags> {
+        let is_prop = |handle: Option<RigidBodyHandle>| {
+            handle.and_then(|handle| self.dynamic_body_classes.get(&handle))
+                == Some(&PhysicsBodyClass::Prop)
+        };
+
+        if is_prop(context.rigid_body1) || is_prop(context.rigid_body2) {
+            Some(SolverFlags::COMPUTE_IMPULSES)
+        } else {
+            None
+        }
+    }
+}
+
+///
+/// RayQuery
+///
+/// A single ray for `ray_cast_batch` - the same parameters as `ray_cast2`
+#[derive(Clone, Debug)]
+pub struct RayQuery {
+    pub start_point: Point3<f32>,
+    pub direction: Vector3<f32>,
+    pub max_toi: f32,
+    pub collision_groups: InternalCollisionGroups,
+    pub entity_to_ignore: Option<EntityId>,
+    pub ignore_sensors: bool,
+}
+
+impl RayQuery {
+    ///
+    /// between
+    ///
+    /// Ray from `start_point` to `end_point`, ignoring sensors
+    pub fn between(
+        start_point: Point3<f32>,
+        end_point: Point3<f32>,
+        collision_groups: InternalCollisionGroups,
+        entity_to_ignore: Option<EntityId>,
+    ) -> RayQuery {
+        let direction = end_point - start_point;
+        RayQuery {
+            start_point,
+            direction,
+            max_toi: direction.magnitude(),
+            collision_groups,
+            entity_to_ignore,
+            ignore_sensors: true,
+        }
     }
 }
 
@@ -124,11 +206,42 @@ pub struct PlayerHandle {
     // Player
     controller: KinematicCharacterController,
     character_handle: RigidBodyHandle,
+    // Debug flying mode - no collision or gravity for the player
+    noclip: bool,
+    // Hanging on to a climbable surface - no gravity, but still collides
+    climbing: bool,
+    // Standing on something, as of the last step
+    grounded: bool,
+}
+
+impl PlayerHandle {
+    pub fn is_noclip(&self) -> bool {
+        self.noclip
+    }
+
+    pub fn set_climbing(&mut self, climbing: bool) {
+        self.climbing = climbing;
+    }
+
+    pub fn is_climbing(&self) -> bool {
+        self.climbing
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
 }
 
 pub struct PhysicsWorld {
+    config: PhysicsConfig,
     gravity: Vector<Real>,
     integration_parameters: IntegrationParameters,
+    // Unsimulated time, less than one step - see `update`
+    time_accumulator: f32,
+    // Player movement requested since the last step
+    pending_player_movement: Vector3<f32>,
+    // Poses before the latest step, for interpolating render transforms between steps
+    previous_poses: HashMap<RigidBodyHandle, Isometry<Real>>,
     physics_pipeline: PhysicsPipeline,
     island_manager: IslandManager,
     broad_phase: BroadPhaseMultiSap,
@@ -144,6 +257,12 @@ pub struct PhysicsWorld {
 
     entity_id_to_body: HashMap<EntityId, RigidBodyHandle>,
 
+    // Class of each dynamic body, so config changes can be re-applied
+    dynamic_body_classes: HashMap<RigidBodyHandle, PhysicsBodyClass>,
+
+    // Profile each collider was built with, so layer matrix changes can be re-applied
+    collider_profiles: HashMap<ColliderHandle, ColliderProfile>,
+
     // TODO:
     // physics_hooks: Box<dyn PhysicsHooks>,
     // event_handler: Box<dyn EventHandler>,
@@ -186,20 +305,18 @@ impl PhysicsWorld {
 
         let mut collider = ColliderBuilder::trimesh(vertices, indices).build();
         collider.user_data = entity_id.inner() as u128;
-        collider.set_collision_groups(InteractionGroups {
-            memberships: InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-            filter: InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-        });
-        self.collider_set.insert(collider);
+        collider.set_collision_groups(self.groups_for(ColliderProfile::Level));
+        let collider_handle = self.collider_set.insert(collider);
+        self.collider_profiles
+            .insert(collider_handle, ColliderProfile::Level);
     }
 
     pub fn add_collider(&mut self, entity_id: EntityId, mut collider: Collider) {
         collider.user_data = entity_id.inner() as u128;
-        collider.set_collision_groups(InteractionGroups {
-            memberships: InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-            filter: InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-        });
-        self.collider_set.insert(collider);
+        collider.set_collision_groups(self.groups_for(ColliderProfile::Level));
+        let collider_handle = self.collider_set.insert(collider);
+        self.collider_profiles
+            .insert(collider_handle, ColliderProfile::Level);
     }
 
     pub fn set_position_rotation2(
@@ -234,6 +351,8 @@ impl PhysicsWorld {
             xform.translation = Translation {
                 vector: vec_to_nvec(position),
             };
+            // Teleported - don't interpolate from the old pose
+            self.previous_poses.remove(&handle);
             if rigid_body.is_kinematic() {
                 rigid_body.set_next_kinematic_position(xform);
             } else {
@@ -284,7 +403,7 @@ impl PhysicsWorld {
         }
     }
 
-    pub fn clear_forces(&mut self) {
+    fn clear_forces(&mut self) {
         for rigid_body_handle in &self.rigid_bodies_with_forces {
             let rigid_body = &mut self.rigid_body_set[*rigid_body_handle];
             rigid_body.reset_forces(true);
@@ -310,7 +429,8 @@ impl PhysicsWorld {
     }
 
     pub fn remove_rigid_body_handle(&mut self, handle: RigidBodyHandle) {
-        self.rigid_body_set.remove(
+        self.dynamic_body_classes.remove(&handle);
+        let removed = self.rigid_body_set.remove(
             handle,
             &mut self.island_manager,
             &mut self.collider_set,
@@ -318,6 +438,7 @@ impl PhysicsWorld {
             &mut self.multibody_joint_set,
             true,
         );
+        self.forget_collider_profiles(removed);
     }
 
     pub fn remove_impulse_joint(&mut self, handle: ImpulseJointHandle) {
@@ -342,7 +463,137 @@ impl PhysicsWorld {
             .rigid_body_set
             .get_mut(player_handle.character_handle)
             .unwrap();
-        character_body.set_translation(vec_to_nvec(position), true)
+        character_body.set_translation(vec_to_nvec(position), true);
+        self.previous_poses.remove(&player_handle.character_handle);
+    }
+
+    ///
+    /// set_player_noclip
+    ///
+    /// Detach the player from collision and gravity, so they can fly through level geometry.
+    /// The player's collider is disabled too, so physics objects pass through them as well.
+    pub fn set_player_noclip(&mut self, noclip: bool, player_handle: &mut PlayerHandle) {
+        player_handle.noclip = noclip;
+
+        let character_body = &self.rigid_body_set[player_handle.character_handle];
+        let collider_handle = character_body.colliders()[0];
+        if let Some(collider) = self.collider_set.get_mut(collider_handle) {
+            collider.set_enabled(!noclip);
+        }
+    }
+
+    ///
+    /// create_hand
+    ///
+    /// Kinematic sphere that follows a VR hand, so it can push props around
+    pub fn create_hand(&mut self, position: Vector3<f32>, radius: f32) -> RigidBodyHandle {
+        let rigid_body = RigidBodyBuilder::kinematic_position_based()
+            .translation(vec_to_nvec(position))
+            .build();
+        let handle = self.rigid_body_set.insert(rigid_body);
+
+        let collider = ColliderBuilder::ball(radius)
+            .collision_groups(self.groups_for(ColliderProfile::Hand))
+            .active_hooks(ActiveHooks::FILTER_CONTACT_PAIRS)
+            .build();
+        let collider_handle =
+            self.collider_set
+                .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+        self.collider_profiles
+            .insert(collider_handle, ColliderProfile::Hand);
+        handle
+    }
+
+    ///
+    /// set_hand_position
+    ///
+    /// Move a hand created by `create_hand` - a disabled hand passes through everything
+    pub fn set_hand_position(
+        &mut self,
+        handle: RigidBodyHandle,
+        position: Vector3<f32>,
+        enabled: bool,
+    ) {
+        let Some(rigid_body) = self.rigid_body_set.get_mut(handle) else {
+            return;
+        };
+        rigid_body.set_next_kinematic_translation(vec_to_nvec(position));
+
+        for collider_handle in rigid_body.colliders() {
+            if let Some(collider) = self.collider_set.get_mut(*collider_handle) {
+                collider.set_enabled(enabled);
+            }
+        }
+    }
+
+    ///
+    /// entities_in_sphere
+    ///
+    /// Entities with a (non-sensor) collider touching the sphere
+    pub fn entities_in_sphere(
+        &self,
+        center: Vector3<f32>,
+        radius: f32,
+        collision_groups: InternalCollisionGroups,
+    ) -> Vec<EntityId> {
+        let mut entities = Vec::new();
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .groups(InteractionGroups::new(
+                InternalCollisionGroups::ALL.bits.into(),
+                collision_groups.bits.into(),
+            ));
+        self.query_pipeline.intersections_with_shape(
+            &self.rigid_body_set,
+            &self.collider_set,
+            &Isometry::translation(center.x, center.y, center.z),
+            &Ball::new(radius),
+            filter,
+            |handle| {
+                let collider = self.collider_set.get(handle).unwrap();
+                if let Some(entity_id) = EntityId::from_inner(collider.user_data as u64) {
+                    if !entities.contains(&entity_id) {
+                        entities.push(entity_id);
+                    }
+                }
+                true
+            },
+        );
+        entities
+    }
+
+    ///
+    /// entities_at_point
+    ///
+    /// Entities whose (non-sensor) colliders contain `point` - ie, what a fingertip is pressing into
+    pub fn entities_at_point(
+        &self,
+        point: Vector3<f32>,
+        collision_groups: InternalCollisionGroups,
+    ) -> Vec<EntityId> {
+        let mut entities = Vec::new();
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .groups(InteractionGroups::new(
+                InternalCollisionGroups::ALL.bits.into(),
+                collision_groups.bits.into(),
+            ));
+        self.query_pipeline.intersections_with_point(
+            &self.rigid_body_set,
+            &self.collider_set,
+            &vec_to_npoint(point),
+            filter,
+            |handle| {
+                let collider = self.collider_set.get(handle).unwrap();
+                if let Some(entity_id) = EntityId::from_inner(collider.user_data as u64) {
+                    if !entities.contains(&entity_id) {
+                        entities.push(entity_id);
+                    }
+                }
+                true
+            },
+        );
+        entities
     }
 
     pub fn get_aabb2(&self, entity_id: EntityId) -> Option<Aabb3<f32>> {
@@ -427,12 +678,12 @@ impl PhysicsWorld {
         };
 
         let mut rigid_body = RigidBodyBuilder::dynamic()
-            // TODO: How can we make this more reliable? Seems to slow down the projectile randomly...
-            // .ccd_enabled(true)
+            .ccd_enabled(self.config.ccd_enabled(opts.body_class))
             .position(test)
             .build();
         rigid_body.user_data = entity_id.inner() as u128;
         rigid_body.set_gravity_scale(opts.gravity_scale, false);
+        apply_sleep_thresholds(&self.config, &mut rigid_body);
         //rigid_body.set_additional_mass(5.0, false);
         let handle = &self.rigid_body_set.insert(rigid_body);
         let mut collider = match shape {
@@ -468,15 +719,19 @@ impl PhysicsWorld {
         };
 
         self.entity_id_to_body.insert(entity_id, *handle);
+        self.dynamic_body_classes.insert(*handle, opts.body_class);
         collider.set_density(0.1);
         collider.set_enabled(true);
         collider.set_sensor(is_sensor);
-        collider.set_collision_groups(collision_group.0);
+        collider.set_collision_groups(self.groups_for(collision_group.0));
         collider
             .set_active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS);
         collider.user_data = entity_id.inner() as u128;
-        self.collider_set
-            .insert_with_parent(collider, *handle, &mut self.rigid_body_set);
+        let collider_handle =
+            self.collider_set
+                .insert_with_parent(collider, *handle, &mut self.rigid_body_set);
+        self.collider_profiles
+            .insert(collider_handle, collision_group.0);
         *handle
     }
 
@@ -529,10 +784,13 @@ impl PhysicsWorld {
         collider.set_enabled(true);
         collider.set_sensor(is_sensor);
         collider.user_data = entity_id.inner() as u128;
-        collider.set_collision_groups(collision_groups.0);
+        collider.set_collision_groups(self.groups_for(collision_groups.0));
 
-        self.collider_set
-            .insert_with_parent(collider, *handle, &mut self.rigid_body_set);
+        let collider_handle =
+            self.collider_set
+                .insert_with_parent(collider, *handle, &mut self.rigid_body_set);
+        self.collider_profiles
+            .insert(collider_handle, collision_groups.0);
         *handle
     }
 
@@ -545,7 +803,8 @@ impl PhysicsWorld {
             }
         }
         for handle in bodies_to_remove {
-            self.rigid_body_set.remove(
+            self.dynamic_body_classes.remove(&handle);
+            let removed = self.rigid_body_set.remove(
                 handle,
                 &mut self.island_manager,
                 &mut self.collider_set,
@@ -553,10 +812,72 @@ impl PhysicsWorld {
                 &mut self.multibody_joint_set,
                 true,
             );
+            self.forget_collider_profiles(removed);
         }
         self.entity_id_to_body.remove(&entity_id);
     }
 
+    ///
+    /// retire_body
+    ///
+    /// Detach an entity's body and disable it, instead of removing it - so it can be handed to
+    /// a new entity of the same template with `recycle_body`. Any other bodies the entity had
+    /// are removed.
+    pub fn retire_body(&mut self, entity_id: EntityId) -> Option<RigidBodyHandle> {
+        let handle = *self.entity_id_to_body.get(&entity_id)?;
+        let rigid_body = self.rigid_body_set.get_mut(handle)?;
+        rigid_body.set_enabled(false);
+        rigid_body.user_data = RETIRED_BODY_USER_DATA;
+        for collider_handle in rigid_body.colliders().to_vec() {
+            if let Some(collider) = self.collider_set.get_mut(collider_handle) {
+                collider.user_data = RETIRED_BODY_USER_DATA;
+            }
+        }
+        self.previous_poses.remove(&handle);
+
+        // The retired body no longer belongs to the entity, so this only removes the rest
+        self.remove(entity_id);
+        Some(handle)
+    }
+
+    ///
+    /// recycle_body
+    ///
+    /// Hand a body retired with `retire_body` to a new entity, at rest at the given pose.
+    /// Returns false if the body no longer exists.
+    pub fn recycle_body(
+        &mut self,
+        handle: RigidBodyHandle,
+        entity_id: EntityId,
+        position: Vector3<f32>,
+        rotation: Quaternion<f32>,
+    ) -> bool {
+        let entity_as_int = entity_id.inner() as u128;
+        let Some(rigid_body) = self.rigid_body_set.get_mut(handle) else {
+            return false;
+        };
+        rigid_body.user_data = entity_as_int;
+        rigid_body.set_linvel(vector![0.0, 0.0, 0.0], false);
+        rigid_body.set_angvel(vector![0.0, 0.0, 0.0], false);
+        rigid_body.set_enabled(true);
+        for collider_handle in rigid_body.colliders().to_vec() {
+            if let Some(collider) = self.collider_set.get_mut(collider_handle) {
+                collider.user_data = entity_as_int;
+            }
+        }
+
+        // Kinematic bodies move on the next step - place them now, so they don't sweep
+        // from where they were retired
+        if let Some(rigid_body) = self.rigid_body_set.get_mut(handle) {
+            if rigid_body.is_kinematic() {
+                rigid_body.set_translation(vec_to_nvec(position), false);
+            }
+        }
+        self.set_position_rotation(handle, position, rotation);
+        self.entity_id_to_body.insert(entity_id, handle);
+        true
+    }
+
     pub fn create_player(
         &mut self,
         start_pos: Vector3<f32>,
@@ -572,20 +893,22 @@ impl PhysicsWorld {
         let character_handle = self.rigid_body_set.insert(rigid_body);
         let mut collider =
             ColliderBuilder::cuboid(0.8 / SCALE_FACTOR, 2.4 / SCALE_FACTOR, 0.8 / SCALE_FACTOR);
-        collider = collider.collision_groups(InteractionGroups::new(
-            InternalCollisionGroups::PLAYER.bits.into(),
-            InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-        ));
+        collider = collider.collision_groups(self.groups_for(ColliderProfile::Player));
         collider = collider.user_data(player_entity_user_data);
 
-        self.collider_set
-            .insert_with_parent(collider, character_handle, &mut self.rigid_body_set);
+        let collider_handle = self.collider_set.insert_with_parent(
+            collider,
+            character_handle,
+            &mut self.rigid_body_set,
+        );
+        self.collider_profiles
+            .insert(collider_handle, ColliderProfile::Player);
 
         let mut controller = KinematicCharacterController::default();
 
         controller.offset = CharacterLength::Absolute(0.1 / SCALE_FACTOR);
-        controller.snap_to_ground = Some(CharacterLength::Absolute(0.1 / SCALE_FACTOR));
         controller.normal_nudge_factor = 0.1;
+        configure_character_controller(&self.config, &mut controller);
 
         self.entity_id_to_body
             .insert(player_entity, character_handle);
@@ -593,19 +916,23 @@ impl PhysicsWorld {
         PlayerHandle {
             controller,
             character_handle,
+            noclip: false,
+            climbing: false,
+            grounded: false,
         }
     }
 
     pub fn new() -> PhysicsWorld {
+        PhysicsWorld::with_config(PhysicsConfig::default())
+    }
+
+    pub fn with_config(config: PhysicsConfig) -> PhysicsWorld {
         let rigid_body_set = RigidBodySet::new();
         let collider_set = ColliderSet::new();
 
         /* Create other structures necessary for the simulation. */
         let gravity = vector![0.0, -9.81, 0.0];
-        let integration_parameters = IntegrationParameters {
-            max_ccd_substeps: 1,
-            ..IntegrationParameters::default()
-        };
+        let integration_parameters = integration_parameters_from_config(&config);
         let physics_pipeline = PhysicsPipeline::new();
         let island_manager = IslandManager::new();
         let broad_phase = BroadPhaseMultiSap::new();
@@ -625,8 +952,12 @@ impl PhysicsWorld {
 
         query_pipeline.update(&rigid_body_set, &collider_set);
         PhysicsWorld {
+            config,
             gravity,
             integration_parameters,
+            time_accumulator: 0.0,
+            pending_player_movement: Vector3::new(0.0, 0.0, 0.0),
+            previous_poses: HashMap::new(),
             collider_set,
             physics_pipeline,
             island_manager,
@@ -642,6 +973,8 @@ impl PhysicsWorld {
             // physics_hooks: Box::new(physics_hooks),
             // event_handler: Box::new(event_handler),
             entity_id_to_body: HashMap::new(),
+            dynamic_body_classes: HashMap::new(),
+            collider_profiles: HashMap::new(),
 
             debug_pipeline,
 
@@ -651,6 +984,95 @@ impl PhysicsWorld {
         }
     }
 
+    ///
+    /// set_config
+    ///
+    /// Replace the physics config, re-applying it to the solver, all existing dynamic bodies,
+    /// and the collision groups of existing colliders
+    pub fn set_config(&mut self, config: PhysicsConfig) {
+        self.integration_parameters = integration_parameters_from_config(&config);
+
+        for (handle, class) in &self.dynamic_body_classes {
+            if let Some(rigid_body) = self.rigid_body_set.get_mut(*handle) {
+                rigid_body.enable_ccd(config.ccd_enabled(*class));
+                apply_sleep_thresholds(&config, rigid_body);
+            }
+        }
+
+        let collider_set = &mut self.collider_set;
+        self.collider_profiles.retain(|handle, profile| {
+            let Some(collider) = collider_set.get_mut(*handle) else {
+                return false;
+            };
+            collider.set_collision_groups(config.collision_layers.interaction_groups(*profile));
+            true
+        });
+
+        self.config = config;
+    }
+
+    ///
+    /// collision_layers
+    ///
+    /// The layer matrix colliders are currently built with
+    pub fn collision_layers(&self) -> &CollisionLayerMatrix {
+        &self.config.collision_layers
+    }
+
+    fn groups_for(&self, profile: ColliderProfile) -> InteractionGroups {
+        self.config.collision_layers.interaction_groups(profile)
+    }
+
+    fn forget_collider_profiles(&mut self, removed_body: Option<RigidBody>) {
+        if let Some(body) = removed_body {
+            for collider_handle in body.colliders() {
+                self.collider_profiles.remove(collider_handle);
+            }
+        }
+    }
+
+    ///
+    /// sleep_distant_bodies
+    ///
+    /// Put awake dynamic bodies to sleep if they are far from the player and not visible.
+    /// Rapier wakes them back up on contact, so this is mostly invisible to the player -
+    /// but it saves a lot of solver time in prop-heavy areas.
+    /// Projectiles are left alone, so they don't freeze mid-flight.
+    pub fn sleep_distant_bodies(
+        &mut self,
+        player_position: Vector3<f32>,
+        mut is_visible: impl FnMut(EntityId) -> bool,
+    ) {
+        if !self.config.sleep_distant_bodies {
+            return;
+        }
+
+        let max_distance_squared = self.config.distant_sleep_distance.powi(2);
+        for (handle, class) in &self.dynamic_body_classes {
+            if *class == PhysicsBodyClass::Projectile {
+                continue;
+            }
+
+            let Some(rigid_body) = self.rigid_body_set.get_mut(*handle) else {
+                continue;
+            };
+
+            if rigid_body.is_sleeping() {
+                continue;
+            }
+
+            let position = nvec_to_cgmath(*rigid_body.translation());
+            if (position - player_position).magnitude2() < max_distance_squared {
+                continue;
+            }
+
+            let entity_id = EntityId::from_inner(rigid_body.user_data as u64);
+            if entity_id.is_some_and(|entity_id| !is_visible(entity_id)) {
+                rigid_body.sleep();
+            }
+        }
+    }
+
     pub fn debug_render(&mut self) -> Vec<SceneObject> {
         let mut debug_renderer = DebugRenderer::new();
 
@@ -666,12 +1088,103 @@ impl PhysicsWorld {
         debug_renderer.render()
     }
 
+    ///
+    /// update
+    ///
+    /// Advance the simulation by `delta_time`, in fixed steps of `PHYSICS_TIMESTEP` - leftover time
+    /// carries over to the next update, so bodies behave the same at any frame rate. The player's
+    /// movement is spread over the steps taken. Returns the player's (interpolated) position.
     pub fn update(
         &mut self,
+        delta_time: f32,
         desired_movement: Vector3<f32>,
         player_handle: &mut PlayerHandle,
     ) -> (Vector3<f32>, Vec<CollisionEvent>) {
-        /* Run the game loop, stepping the simulation once per frame. */
+        self.time_accumulator += delta_time;
+        self.pending_player_movement += desired_movement;
+
+        let mut steps = (self.time_accumulator / PHYSICS_TIMESTEP) as u32;
+        if steps > MAX_STEPS_PER_UPDATE {
+            // Too far behind (ie, a hitch, or a high time scale) - drop the time instead of
+            // spending even longer catching up next frame
+            steps = MAX_STEPS_PER_UPDATE;
+            self.time_accumulator = steps as f32 * PHYSICS_TIMESTEP;
+        }
+
+        let mut collision_events = Vec::new();
+        if steps > 0 {
+            let movement_per_step = self.pending_player_movement / steps as f32;
+            self.pending_player_movement = Vector3::new(0.0, 0.0, 0.0);
+
+            for step in 0..steps {
+                if step == steps - 1 {
+                    self.capture_previous_poses(player_handle);
+                }
+                collision_events.append(&mut self.step(movement_per_step, player_handle));
+                self.time_accumulator -= PHYSICS_TIMESTEP;
+            }
+
+            // Forces apply over all the steps of a frame - only clear them once they've been used
+            self.clear_forces();
+        }
+
+        let (translation, _) = self
+            .get_interpolated_position_rotation(player_handle.character_handle)
+            .unwrap();
+        (translation, collision_events)
+    }
+
+    fn capture_previous_poses(&mut self, player_handle: &PlayerHandle) {
+        self.previous_poses.clear();
+        let handles = self
+            .dynamic_body_classes
+            .keys()
+            .chain(std::iter::once(&player_handle.character_handle));
+        for handle in handles {
+            if let Some(rigid_body) = self.rigid_body_set.get(*handle) {
+                if !rigid_body.is_sleeping() {
+                    self.previous_poses.insert(*handle, *rigid_body.position());
+                }
+            }
+        }
+    }
+
+    ///
+    /// pending_player_movement
+    ///
+    /// Player movement passed to `update` that hasn't been simulated yet (the frame was shorter than a step)
+    pub fn pending_player_movement(&self) -> Vector3<f32> {
+        self.pending_player_movement
+    }
+
+    ///
+    /// get_interpolated_position_rotation
+    ///
+    /// Pose of the body between the last two steps, matching how far we are into the next one -
+    /// for rendering, so motion looks smooth when the frame rate doesn't match the step rate
+    pub fn get_interpolated_position_rotation(
+        &self,
+        handle: RigidBodyHandle,
+    ) -> Option<(Vector3<f32>, Quaternion<f32>)> {
+        let rigid_body = self.rigid_body_set.get(handle)?;
+        let current = rigid_body.position();
+        let alpha = (self.time_accumulator / PHYSICS_TIMESTEP).clamp(0.0, 1.0);
+        let pose = match self.previous_poses.get(&handle) {
+            Some(previous) => previous.lerp_slerp(current, alpha),
+            None => *current,
+        };
+
+        Some((
+            nvec_to_cgmath(pose.translation.vector),
+            nquat_to_quat(pose.rotation),
+        ))
+    }
+
+    fn step(
+        &mut self,
+        desired_movement: Vector3<f32>,
+        player_handle: &mut PlayerHandle,
+    ) -> Vec<CollisionEvent> {
         profile!(scope: "physics", level: TRACE, "physics.step", {
             self.physics_pipeline.step(
                 &self.gravity,
@@ -685,7 +1198,9 @@ impl PhysicsWorld {
                 &mut self.multibody_joint_set,
                 &mut self.ccd_solver,
                 Some(&mut self.query_pipeline),
-                &(),
+                &HandContactFilter {
+                    dynamic_body_classes: &self.dynamic_body_classes,
+                },
                 &self.events,
             )
         });
@@ -697,16 +1212,13 @@ impl PhysicsWorld {
 
         // Update character controller
         let desired_movement = vec_to_nvec(desired_movement);
-        let (mut collision_events, character_body) =
-            { self.move_player(desired_movement, player_handle) };
-        let translation = nvec_to_cgmath(*character_body.translation());
+        let (mut collision_events, _) = { self.move_player(desired_movement, player_handle) };
 
         let mut additional_collision_events = { self.events.get_and_clear_events() };
 
         collision_events.append(&mut additional_collision_events);
 
-        // Output result
-        (translation, collision_events)
+        collision_events
     }
 
     fn move_player(
@@ -714,6 +1226,9 @@ impl PhysicsWorld {
         desired_movement: Vector<Real>,
         player_handle: &mut PlayerHandle,
     ) -> (Vec<CollisionEvent>, &RigidBody) {
+        // Pick up any step / snap changes from `set_config`
+        configure_character_controller(&self.config, &mut player_handle.controller);
+
         let character_body = &self.rigid_body_set[player_handle.character_handle];
         let original_position = *character_body.position();
         let character_collider = &self.collider_set[character_body.colliders()[0]];
@@ -726,6 +1241,13 @@ impl PhysicsWorld {
         // Second: Drop the player down for gravity
         // This wasn't necessary until upgrading to rapier v0.19.0 - when we upgraded to that version,
         // we started to snag on geometry.
+        // With auto-stepping on, the controller climbs lips and stairs itself - and the upward nudge
+        // would stop it from snapping to the ground - so it's only needed when stepping is disabled.
+        let step_size = if self.config.player_step_height > 0.0 {
+            Vector::zeros()
+        } else {
+            step_size
+        };
         let movement_with_upward = desired_movement + step_size;
 
         let mut gravity = -0.5 / SCALE_FACTOR;
@@ -733,50 +1255,65 @@ impl PhysicsWorld {
 
         let gravity_movement = Vector::y() * gravity - step_size;
 
+        // Climbing: the hands hold the player up, so skip the step up and gravity
+        let (movement_with_upward, gravity_movement) = if player_handle.climbing {
+            (desired_movement, Vector::zeros())
+        } else {
+            (movement_with_upward, gravity_movement)
+        };
+
         //let mut collisions = vec![];
-        let (mvt1, mvt2) = profile!(scope: "physics", level: TRACE, "physics.move_player", {
-            // HACK: For rapier v0.19.0, our previous strategy of combining the movement + gravity
-            // caused us to snag on physics geometry. In order to counter this, we'll do the movement in two phases
-            // a forward phase to move and then an application of gravity
-            (player_handle.controller.move_shape(
-                self.integration_parameters.dt,
-                &self.rigid_body_set,
-                &self.collider_set,
-                &self.query_pipeline,
-                character_collider.shape(),
-                character_collider.position(),
-                movement_with_upward.cast::<Real>(),
-                QueryFilter::new()
-                    .groups(InteractionGroups::new(
-                        InternalCollisionGroups::PLAYER.bits.into(),
-                        InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-                    ))
-                    .exclude_rigid_body(player_handle.character_handle)
-                    .exclude_sensors(),
-                |_c| (),
-                //|c| collisions.push(c),
-            ),
-
-            // Second pass: Apply gravity and undo our step size
-            player_handle.controller.move_shape(
-                self.integration_parameters.dt,
-                &self.rigid_body_set,
-                &self.collider_set,
-                &self.query_pipeline,
-                character_collider.shape(),
-                character_collider.position(),
-                gravity_movement.cast::<Real>(),
-                QueryFilter::new()
-                    .groups(InteractionGroups::new(
-                        InternalCollisionGroups::PLAYER.bits.into(),
-                        InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-                    ))
-                    .exclude_rigid_body(player_handle.character_handle)
-                    .exclude_sensors(),
-                |_c| (),
-                //|c| collisions.push(c),
-            ))
-        });
+        let translation = if player_handle.noclip {
+            // Noclip: go exactly where the input says - no sliding along walls, no gravity
+            player_handle.grounded = false;
+            desired_movement
+        } else {
+            let (mvt1, mvt2) = profile!(scope: "physics", level: TRACE, "physics.move_player", {
+                // HACK: For rapier v0.19.0, our previous strategy of combining the movement + gravity
+                // caused us to snag on physics geometry. In order to counter this, we'll do the movement in two phases
+                // a forward phase to move and then an application of gravity
+                (player_handle.controller.move_shape(
+                    self.integration_parameters.dt,
+                    &self.rigid_body_set,
+                    &self.collider_set,
+                    &self.query_pipeline,
+                    character_collider.shape(),
+                    character_collider.position(),
+                    movement_with_upward.cast::<Real>(),
+                    QueryFilter::new()
+                        .groups(InteractionGroups::new(
+                            InternalCollisionGroups::PLAYER.bits.into(),
+                            InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
+                        ))
+                        .exclude_rigid_body(player_handle.character_handle)
+                        .exclude_sensors(),
+                    |_c| (),
+                    //|c| collisions.push(c),
+                ),
+
+                // Second pass: Apply gravity and undo our step size
+                player_handle.controller.move_shape(
+                    self.integration_parameters.dt,
+                    &self.rigid_body_set,
+                    &self.collider_set,
+                    &self.query_pipeline,
+                    character_collider.shape(),
+                    character_collider.position(),
+                    gravity_movement.cast::<Real>(),
+                    QueryFilter::new()
+                        .groups(InteractionGroups::new(
+                            InternalCollisionGroups::PLAYER.bits.into(),
+                            InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
+                        ))
+                        .exclude_rigid_body(player_handle.character_handle)
+                        .exclude_sensors(),
+                    |_c| (),
+                    //|c| collisions.push(c),
+                ))
+            });
+            player_handle.grounded = mvt2.grounded && !player_handle.climbing;
+            mvt1.translation + mvt2.translation
+        };
 
         let mut collision_events = Vec::new();
         let mut current_sensor_intersections = HashSet::new();
@@ -855,9 +1392,7 @@ impl PhysicsWorld {
         let character_body = &mut self.rigid_body_set[player_handle.character_handle];
         let _original_pos = character_body.position().translation.vector;
         let pos = character_body.position();
-        character_body.set_next_kinematic_translation(
-            pos.translation.vector + mvt1.translation + mvt2.translation,
-        );
+        character_body.set_next_kinematic_translation(pos.translation.vector + translation);
         (collision_events, character_body)
     }
 
@@ -870,68 +1405,114 @@ impl PhysicsWorld {
         entity_to_ignore: Option<EntityId>,
         ignore_sensors: bool,
     ) -> Option<RayCastResult> {
+        let query = RayQuery {
+            start_point,
+            direction,
+            max_toi,
+            collision_groups,
+            entity_to_ignore,
+            ignore_sensors,
+        };
+        cast_ray(
+            &self.query_pipeline,
+            &self.rigid_body_set,
+            &self.collider_set,
+            &query,
+        )
+    }
+
+    ///
+    /// ray_cast_profile
+    ///
+    /// Like `ray_cast3`, but also reports the profile of the collider that was hit - so the debug
+    /// runtime can say whether a ray stopped on the level, an entity, a hitbox...
+    pub fn ray_cast_profile(
+        &self,
+        start_point: Point3<f32>,
+        end_point: Point3<f32>,
+        collision_groups: InternalCollisionGroups,
+        ignore_sensors: bool,
+    ) -> Option<(RayCastResult, Option<ColliderProfile>)> {
+        let direction = end_point - start_point;
+        let query = RayQuery {
+            start_point,
+            direction,
+            max_toi: direction.magnitude(),
+            collision_groups,
+            entity_to_ignore: None,
+            ignore_sensors,
+        };
+        let (collider_handle, result) = cast_ray_with_collider(
+            &self.query_pipeline,
+            &self.rigid_body_set,
+            &self.collider_set,
+            &query,
+        )?;
+        Some((
+            result,
+            self.collider_profiles.get(&collider_handle).copied(),
+        ))
+    }
+
+    ///
+    /// ray_cast_batch
+    ///
+    /// Cast many rays at once - results are in the same order as `queries`. The query pipeline is
+    /// read-only between physics steps, so larger batches are split across the rayon pool.
+    pub fn ray_cast_batch(&self, queries: &[RayQuery]) -> Vec<Option<RayCastResult>> {
+        let query_pipeline = &self.query_pipeline;
+        let rigid_body_set = &self.rigid_body_set;
+        let collider_set = &self.collider_set;
+        let cast = |query: &RayQuery| cast_ray(query_pipeline, rigid_body_set, collider_set, query);
+
+        if queries.len() < RAY_CAST_BATCH_MIN_LEN {
+            queries.iter().map(cast).collect()
+        } else {
+            queries
+                .par_iter()
+                .with_min_len(RAY_CAST_BATCH_MIN_LEN)
+                .map(cast)
+                .collect()
+        }
+    }
+
+    ///
+    /// ray_cast_triangle
+    ///
+    /// Cast a ray against only the colliders belonging to `entity_id`, returning the index of the
+    /// triangle hit if it's a trimesh (like the level geometry). Back-face hits are reported as
+    /// `index + triangle_count`, as rapier does.
+    pub fn ray_cast_triangle(
+        &self,
+        start_point: Point3<f32>,
+        direction: Vector3<f32>,
+        max_toi: f32,
+        entity_id: EntityId,
+    ) -> Option<u32> {
         let direction = direction.normalize();
         let ray = Ray::new(
             point![start_point.x, start_point.y, start_point.z],
             vector![direction.x, direction.y, direction.z],
         );
-        // TODO: Take end point instead
-        let solid = true;
-        let mut filter = QueryFilter::default();
-
-        if ignore_sensors {
-            filter = filter.exclude_sensors()
-        };
 
         let binding = |_collider_handle: ColliderHandle, collider: &Collider| {
-            let data = collider.user_data;
-            let maybe_entity_id = EntityId::from_inner(data as u64);
-
-            maybe_entity_id != entity_to_ignore
+            EntityId::from_inner(collider.user_data as u64) == Some(entity_id)
         };
-        filter = filter.predicate(&binding);
-
-        filter = filter.groups(InteractionGroups::new(
-            InternalCollisionGroups::ALL.bits.into(),
-            collision_groups.bits.into(),
-        ));
+        let filter = QueryFilter::default().exclude_sensors().predicate(&binding);
 
-        if let Some((handle, intersection)) = self.query_pipeline.cast_ray_and_get_normal(
-            &self.rigid_body_set,
-            &self.collider_set,
-            &ray,
-            max_toi,
-            solid,
-            filter,
-        ) {
-            // This is similar to `QueryPipeline::cast_ray` illustrated above except
-            // that it also returns the normal of the collider shape at the hit point.
-            let hit_point = ray.point_at(intersection.time_of_impact);
-            let hit_normal = intersection.normal;
-            let collider = self.collider_set.get(handle).unwrap();
-            let maybe_rigid_body_handle = collider.parent();
-            let data = collider.user_data;
-
-            let maybe_entity_id = EntityId::from_inner(data as u64);
-
-            // let rigid_body_handle = self.collider_set.get(handle).unwrap().parent().unwrap();
-            // let rigid_body = self.rigid_body_set.get(rigid_body_handle).unwrap();
-
-            // println!(
-            //     "Collider {:?} hit at point {} with normal {} collider_data: {}",
-            //     handle, hit_point, hit_normal, data
-            // );
-
-            Some(RayCastResult {
-                hit_point: npoint_to_cgmath(hit_point),
-                hit_normal: nvec_to_cgmath(hit_normal),
-                maybe_entity_id,
-                maybe_rigid_body_handle,
-                is_sensor: collider.is_sensor(),
+        self.query_pipeline
+            .cast_ray_and_get_normal(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &ray,
+                max_toi,
+                true,
+                filter,
+            )
+            .and_then(|(_handle, intersection)| match intersection.feature {
+                FeatureId::Face(idx) => Some(idx),
+                _ => None,
             })
-        } else {
-            None
-        }
     }
 
     pub fn ray_cast(
@@ -1025,12 +1606,15 @@ impl PhysicsWorld {
     ) {
         let collider = ColliderBuilder::new(shape)
             .density(density)
-            .collision_groups(collision_group.0)
+            .collision_groups(self.groups_for(collision_group.0))
             .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
             .build();
 
-        self.collider_set
-            .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+        let collider_handle =
+            self.collider_set
+                .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+        self.collider_profiles
+            .insert(collider_handle, collision_group.0);
     }
 
     /// Create an impulse joint between two rigid bodies
@@ -1048,4 +1632,132 @@ impl PhysicsWorld {
     pub fn get_body_transform(&self, handle: RigidBodyHandle) -> Option<Isometry<Real>> {
         self.rigid_body_set.get(handle).map(|body| *body.position())
     }
+
+    /// Feed every rigid body's position, rotation and velocity into `hasher`, in handle order
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f32(self.time_accumulator);
+
+        let mut bodies = self.rigid_body_set.iter().collect::<Vec<_>>();
+        bodies.sort_by_key(|(handle, _)| handle.into_raw_parts());
+
+        for (handle, body) in bodies {
+            let (index, generation) = handle.into_raw_parts();
+            hasher.write_u64(index as u64);
+            hasher.write_u64(generation as u64);
+
+            let values = body
+                .translation()
+                .iter()
+                .chain(body.rotation().coords.iter())
+                .chain(body.linvel().iter())
+                .chain(body.angvel().iter());
+            for value in values {
+                hasher.write_f32(*value);
+            }
+        }
+    }
+}
+
+fn integration_parameters_from_config(config: &PhysicsConfig) -> IntegrationParameters {
+    IntegrationParameters {
+        num_solver_iterations: NonZeroUsize::new(config.solver_iterations.max(1)).unwrap(),
+        max_ccd_substeps: config.max_ccd_substeps,
+        dt: PHYSICS_TIMESTEP,
+        ..IntegrationParameters::default()
+    }
+}
+
+fn configure_character_controller(
+    config: &PhysicsConfig,
+    controller: &mut KinematicCharacterController,
+) {
+    controller.autostep = (config.player_step_height > 0.0).then_some(CharacterAutostep {
+        max_height: CharacterLength::Absolute(config.player_step_height),
+        min_width: CharacterLength::Absolute(config.player_step_min_width),
+        include_dynamic_bodies: false,
+    });
+    controller.snap_to_ground = (config.player_snap_to_ground > 0.0)
+        .then_some(CharacterLength::Absolute(config.player_snap_to_ground));
+}
+
+fn apply_sleep_thresholds(config: &PhysicsConfig, rigid_body: &mut RigidBody) {
+    let activation = rigid_body.activation_mut();
+    activation.normalized_linear_threshold = config.linear_sleep_threshold;
+    activation.angular_threshold = config.angular_sleep_threshold;
+    activation.time_until_sleep = config.time_until_sleep;
+}
+
+// Shared by `ray_cast2` and `ray_cast_batch` - only borrows what the query needs, so it can run across threads
+fn cast_ray(
+    query_pipeline: &QueryPipeline,
+    rigid_body_set: &RigidBodySet,
+    collider_set: &ColliderSet,
+    query: &RayQuery,
+) -> Option<RayCastResult> {
+    cast_ray_with_collider(query_pipeline, rigid_body_set, collider_set, query)
+        .map(|(_collider_handle, result)| result)
+}
+
+fn cast_ray_with_collider(
+    query_pipeline: &QueryPipeline,
+    rigid_body_set: &RigidBodySet,
+    collider_set: &ColliderSet,
+    query: &RayQuery,
+) -> Option<(ColliderHandle, RayCastResult)> {
+    let direction = query.direction.normalize();
+    let ray = Ray::new(
+        point![
+            query.start_point.x,
+            query.start_point.y,
+            query.start_point.z
+        ],
+        vector![direction.x, direction.y, direction.z],
+    );
+    // TODO: Take end point instead
+    let solid = true;
+    let mut filter = QueryFilter::default();
+
+    if query.ignore_sensors {
+        filter = filter.exclude_sensors()
+    };
+
+    let entity_to_ignore = query.entity_to_ignore;
+    let binding = |_collider_handle: ColliderHandle, collider: &Collider| {
+        let data = collider.user_data;
+        let maybe_entity_id = EntityId::from_inner(data as u64);
+
+        maybe_entity_id != entity_to_ignore
+    };
+    filter = filter.predicate(&binding);
+
+    filter = filter.groups(InteractionGroups::new(
+        InternalCollisionGroups::ALL.bits.into(),
+        query.collision_groups.bits.into(),
+    ));
+
+    let (handle, intersection) = query_pipeline.cast_ray_and_get_normal(
+        rigid_body_set,
+        collider_set,
+        &ray,
+        query.max_toi,
+        solid,
+        filter,
+    )?;
+
+    // This is similar to `QueryPipeline::cast_ray`, except that it also returns
+    // the normal of the collider shape at the hit point.
+    let hit_point = ray.point_at(intersection.time_of_impact);
+    let hit_normal = intersection.normal;
+    let collider = collider_set.get(handle).unwrap();
+
+    Some((
+        handle,
+        RayCastResult {
+            hit_point: npoint_to_cgmath(hit_point),
+            hit_normal: nvec_to_cgmath(hit_normal),
+            maybe_entity_id: EntityId::from_inner(collider.user_data as u64),
+            maybe_rigid_body_handle: collider.parent(),
+            is_sensor: collider.is_sensor(),
+        },
+    ))
 }
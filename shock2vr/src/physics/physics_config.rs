@@ -0,0 +1,90 @@
+use dark::SCALE_FACTOR;
+
+use super::CollisionLayerMatrix;
+
+///
+/// PhysicsBodyClass
+///
+/// Broad category of a dynamic body, used to pick per-class settings like CCD
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PhysicsBodyClass {
+    /// Creatures / AI capsules
+    Creature,
+    /// Movable world objects - crates, items, junk
+    Prop,
+    /// Small fast-moving bodies - projectiles, grenades, hitbox spheres
+    Projectile,
+}
+
+///
+/// PhysicsConfig
+///
+/// Tuning knobs for the rapier simulation. Set through `GameOptions::physics_config`,
+/// and can be changed at runtime with `PhysicsWorld::set_config`.
+#[derive(Clone, Debug)]
+pub struct PhysicsConfig {
+    /// Number of solver iterations per step - fewer is faster but less stable stacking
+    pub solver_iterations: usize,
+    /// Maximum number of CCD substeps per step
+    pub max_ccd_substeps: usize,
+
+    /// Linear velocity (normalized by rapier's length unit) below which a body can fall asleep
+    pub linear_sleep_threshold: f32,
+    /// Angular velocity (in rad/s) below which a body can fall asleep
+    pub angular_sleep_threshold: f32,
+    /// How long a body needs to be below the thresholds before it falls asleep, in seconds
+    pub time_until_sleep: f32,
+
+    pub ccd_creatures: bool,
+    pub ccd_props: bool,
+    pub ccd_projectiles: bool,
+
+    /// Put dynamic bodies to sleep when they are not in a visible cell and further
+    /// than `distant_sleep_distance` from the player
+    pub sleep_distant_bodies: bool,
+    pub distant_sleep_distance: f32,
+
+    /// Tallest lip or stair the player walks up without jumping - 0 disables auto-stepping
+    pub player_step_height: f32,
+    /// Minimum free space needed on top of a step for the player to climb it
+    pub player_step_min_width: f32,
+    /// How far the player is pulled down to stay on the ground walking down stairs and slopes - 0 disables snapping
+    pub player_snap_to_ground: f32,
+
+    /// Which collider profiles collide with each other - loaded from `collision_layers.json`
+    pub collision_layers: CollisionLayerMatrix,
+}
+
+impl PhysicsConfig {
+    pub fn ccd_enabled(&self, class: PhysicsBodyClass) -> bool {
+        match class {
+            PhysicsBodyClass::Creature => self.ccd_creatures,
+            PhysicsBodyClass::Prop => self.ccd_props,
+            PhysicsBodyClass::Projectile => self.ccd_projectiles,
+        }
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> PhysicsConfig {
+        PhysicsConfig {
+            solver_iterations: 4,
+            max_ccd_substeps: 1,
+            // Rapier's defaults
+            linear_sleep_threshold: 0.4,
+            angular_sleep_threshold: 0.5,
+            time_until_sleep: 2.0,
+            // TODO: CCD on projectiles seems to slow them down randomly, so it's off by default
+            ccd_creatures: false,
+            ccd_props: false,
+            ccd_projectiles: false,
+            sleep_distant_bodies: true,
+            distant_sleep_distance: 30.0 / SCALE_FACTOR,
+            // Tall enough for the stairs and door lips across the decks
+            player_step_height: 1.25 / SCALE_FACTOR,
+            player_step_min_width: 0.5 / SCALE_FACTOR,
+            player_snap_to_ground: 0.75 / SCALE_FACTOR,
+            collision_layers: CollisionLayerMatrix::default(),
+        }
+    }
+}
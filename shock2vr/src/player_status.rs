@@ -0,0 +1,284 @@
+///
+/// player_status.rs
+///
+/// What's happening to the player, for the HUDs - status effects (radiation, toxins, an EMP
+/// scrambling the HUD), where they were last hit from, and how fast their heart is racing.
+/// Updated by the mission as the player takes damage (`Effect::AdjustHitPoints`,
+/// `Effect::DamageIndicator`) and picks up status effects (`Effect::ApplyStatusEffect`).
+///
+/// Radiation and toxins also wear down the player's health while they last.
+///
+use std::f32::consts::PI;
+
+use cgmath::{Matrix4, Vector3, vec3};
+use shipyard::{Unique, UniqueView, World};
+
+// Health fraction below which the heartbeat kicks in
+pub const LOW_HEALTH_THRESHOLD: f32 = 0.25;
+
+// Heartbeats per second, at the low health threshold and near death
+const HEARTBEAT_RATE_LOW_HEALTH: f32 = 1.2;
+const HEARTBEAT_RATE_NEAR_DEATH: f32 = 2.5;
+
+// How long the HUDs show where damage came from, in seconds
+const DAMAGE_SOURCE_DURATION: f32 = 1.5;
+
+// Hit points lost per second to each status effect
+const RADIATION_DAMAGE_PER_SECOND: f32 = 0.5;
+const TOXIN_DAMAGE_PER_SECOND: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusEffectKind {
+    Radiation,
+    Toxin,
+    /// An EMP scrambling the HUD - the readouts can't be trusted until it wears off
+    HudDisrupted,
+}
+
+impl StatusEffectKind {
+    fn damage_per_second(&self) -> f32 {
+        match self {
+            StatusEffectKind::Radiation => RADIATION_DAMAGE_PER_SECOND,
+            StatusEffectKind::Toxin => TOXIN_DAMAGE_PER_SECOND,
+            StatusEffectKind::HudDisrupted => 0.0,
+        }
+    }
+
+    /// Parse the name used by the console, ie 'radiation' or 'emp'
+    pub fn from_name(name: &str) -> Option<StatusEffectKind> {
+        match name {
+            "radiation" => Some(StatusEffectKind::Radiation),
+            "toxin" => Some(StatusEffectKind::Toxin),
+            "emp" => Some(StatusEffectKind::HudDisrupted),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusEffectKind::Radiation => "RADIATION",
+            StatusEffectKind::Toxin => "TOXIN",
+            StatusEffectKind::HudDisrupted => "EMP",
+        }
+    }
+
+    pub fn color(&self) -> Vector3<f32> {
+        match self {
+            StatusEffectKind::Radiation => vec3(0.9, 0.9, 0.1),
+            StatusEffectKind::Toxin => vec3(0.2, 0.9, 0.2),
+            StatusEffectKind::HudDisrupted => vec3(0.3, 0.6, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    /// Seconds until it wears off
+    pub remaining: f32,
+}
+
+///
+/// PlayerStatus
+///
+/// The player's status effects, recent damage and heartbeat
+#[derive(Unique, Default)]
+pub struct PlayerStatus {
+    effects: Vec<StatusEffect>,
+    damage_source: Option<Vector3<f32>>,
+    time_since_damage: f32,
+    health_fraction: f32,
+    heartbeat_phase: f32,
+    // Status effect damage that hasn't added up to a whole hit point yet
+    pending_damage: f32,
+}
+
+impl PlayerStatus {
+    pub fn new() -> PlayerStatus {
+        PlayerStatus {
+            health_fraction: 1.0,
+            ..PlayerStatus::default()
+        }
+    }
+
+    ///
+    /// record_damage
+    ///
+    /// The player was hurt - from `source`, if it's known
+    pub fn record_damage(&mut self, source: Option<Vector3<f32>>) {
+        self.time_since_damage = 0.0;
+        if source.is_some() {
+            self.damage_source = source;
+        }
+    }
+
+    ///
+    /// apply_effect
+    ///
+    /// Start a status effect, or extend it if it's already active
+    pub fn apply_effect(&mut self, kind: StatusEffectKind, duration: f32) {
+        if duration <= 0.0 {
+            return;
+        }
+
+        match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+            Some(effect) => effect.remaining = effect.remaining.max(duration),
+            None => self.effects.push(StatusEffect {
+                kind,
+                remaining: duration,
+            }),
+        }
+    }
+
+    ///
+    /// update
+    ///
+    /// Wear down the status effects, returning the whole hit points they took from the player
+    pub fn update(&mut self, delta_time: f32, health_fraction: f32) -> i32 {
+        self.health_fraction = health_fraction.clamp(0.0, 1.0);
+        self.time_since_damage += delta_time;
+        if self.time_since_damage > DAMAGE_SOURCE_DURATION {
+            self.damage_source = None;
+        }
+
+        if let Some(rate) = heartbeat_rate(self.health_fraction) {
+            self.heartbeat_phase = (self.heartbeat_phase + delta_time * rate).fract();
+        }
+
+        for effect in self.effects.iter() {
+            self.pending_damage +=
+                effect.kind.damage_per_second() * delta_time.min(effect.remaining);
+        }
+        for effect in self.effects.iter_mut() {
+            effect.remaining -= delta_time;
+        }
+        self.effects.retain(|effect| effect.remaining > 0.0);
+
+        let damage = self.pending_damage.floor();
+        self.pending_damage -= damage;
+        damage as i32
+    }
+
+    pub fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    pub fn has_effect(&self, kind: StatusEffectKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    pub fn is_hud_disrupted(&self) -> bool {
+        self.has_effect(StatusEffectKind::HudDisrupted)
+    }
+
+    /// Where the player was last hurt from, if it was recently
+    pub fn damage_source(&self) -> Option<Vector3<f32>> {
+        self.damage_source
+    }
+
+    ///
+    /// heartbeat_intensity
+    ///
+    /// How strongly the heart is beating right now, 0.0 to 1.0 - always 0.0 unless health is low
+    pub fn heartbeat_intensity(&self) -> f32 {
+        if heartbeat_rate(self.health_fraction).is_none() {
+            return 0.0;
+        }
+
+        let severity = 1.0 - self.health_fraction / LOW_HEALTH_THRESHOLD;
+        let pulse = 0.5 + 0.5 * (self.heartbeat_phase * 2.0 * PI).sin();
+        severity * pulse
+    }
+}
+
+///
+/// heartbeat_rate
+///
+/// Heartbeats per second at a given health fraction - None when health isn't low enough to notice
+pub fn heartbeat_rate(health_fraction: f32) -> Option<f32> {
+    if health_fraction >= LOW_HEALTH_THRESHOLD {
+        return None;
+    }
+
+    let severity = 1.0 - health_fraction.max(0.0) / LOW_HEALTH_THRESHOLD;
+    Some(
+        HEARTBEAT_RATE_LOW_HEALTH
+            + (HEARTBEAT_RATE_NEAR_DEATH - HEARTBEAT_RATE_LOW_HEALTH) * severity,
+    )
+}
+
+///
+/// damage_direction
+///
+/// Which way `source` is from the camera, for the HUD text - 'ahead', 'right', 'behind' or 'left'
+pub fn damage_direction(view: Matrix4<f32>, source: Vector3<f32>) -> &'static str {
+    let source_in_view = view * source.extend(1.0);
+    // Clockwise from straight ahead
+    let angle = source_in_view.x.atan2(-source_in_view.z);
+    let quadrant = ((angle + PI / 4.0).rem_euclid(2.0 * PI) / (PI / 2.0)) as usize;
+    ["ahead", "right", "behind", "left"][quadrant.min(3)]
+}
+
+/// Get the player's status effects, for the HUD - empty outside of a mission
+pub fn get_status_effects(world: &World) -> Vec<StatusEffect> {
+    world
+        .borrow::<UniqueView<PlayerStatus>>()
+        .map(|status| status.effects().to_vec())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_deal_damage_and_expire() {
+        let mut status = PlayerStatus::new();
+        status.apply_effect(StatusEffectKind::Toxin, 2.5);
+        status.apply_effect(StatusEffectKind::Toxin, 1.0);
+        assert_eq!(status.effects().len(), 1);
+
+        assert_eq!(status.update(1.0, 1.0), 1);
+        assert_eq!(status.update(1.0, 1.0), 1);
+        assert!(status.has_effect(StatusEffectKind::Toxin));
+
+        // Only the half second left of the effect counts
+        assert_eq!(status.update(1.0, 1.0), 0);
+        assert!(!status.has_effect(StatusEffectKind::Toxin));
+        assert_eq!(status.update(1.0, 1.0), 0);
+    }
+
+    #[test]
+    fn test_heartbeat_quickens_with_low_health() {
+        assert_eq!(heartbeat_rate(1.0), None);
+        assert_eq!(heartbeat_rate(LOW_HEALTH_THRESHOLD), None);
+
+        let low = heartbeat_rate(LOW_HEALTH_THRESHOLD * 0.9).unwrap();
+        let near_death = heartbeat_rate(0.0).unwrap();
+        assert!(near_death > low);
+        assert!((near_death - HEARTBEAT_RATE_NEAR_DEATH).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_damage_direction() {
+        use cgmath::SquareMatrix;
+
+        // Looking down -z
+        let view = Matrix4::identity();
+        assert_eq!(damage_direction(view, vec3(0.0, 0.0, -5.0)), "ahead");
+        assert_eq!(damage_direction(view, vec3(5.0, 0.0, 0.0)), "right");
+        assert_eq!(damage_direction(view, vec3(0.0, 0.0, 5.0)), "behind");
+        assert_eq!(damage_direction(view, vec3(-5.0, 0.0, 0.0)), "left");
+    }
+
+    #[test]
+    fn test_damage_source_fades() {
+        let mut status = PlayerStatus::new();
+        status.record_damage(Some(vec3(1.0, 0.0, 0.0)));
+        status.update(DAMAGE_SOURCE_DURATION * 0.5, 1.0);
+        assert_eq!(status.damage_source(), Some(vec3(1.0, 0.0, 0.0)));
+
+        status.update(DAMAGE_SOURCE_DURATION, 1.0);
+        assert_eq!(status.damage_source(), None);
+    }
+}
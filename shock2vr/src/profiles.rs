@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{accessibility::AccessibilitySettings, experimental::ExperimentalFeatures, paths};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+const SETTINGS_FILE: &str = "settings.json";
+const STATISTICS_FILE: &str = "statistics.json";
+const SEEN_HINTS_FILE: &str = "seen_hints.json";
+
+///
+/// ProfileSettings
+///
+/// Per-profile settings, persisted as `settings.json` in the profile directory
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileSettings {
+    /// Experimental features enabled for this profile, in addition to any passed on the command line
+    pub experimental_features: ExperimentalFeatures,
+    /// Accessibility options for this profile - when set, these replace the ones passed on the command line
+    pub accessibility: Option<AccessibilitySettings>,
+    /// Language for this profile - when set, replaces the one passed on the command line
+    pub language: Option<String>,
+    /// Ids of the mods to load for this profile - see `mods`
+    pub enabled_mods: Vec<String>,
+}
+
+///
+/// PlayerProfile
+///
+/// A named player - settings, saves and statistics live under `<data root>/profiles/<name>`,
+/// so shared machines (or test matrices) don't overwrite each other's progress
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayerProfile {
+    name: String,
+}
+
+impl PlayerProfile {
+    pub fn new(name: &str) -> PlayerProfile {
+        PlayerProfile {
+            name: sanitize_profile_name(name),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        profiles_root().join(&self.name)
+    }
+
+    ///
+    /// save_path
+    ///
+    /// Where a save called `file_name` lives for this profile. Absolute paths are left alone.
+    pub fn save_path(&self, file_name: &str) -> PathBuf {
+        let path = Path::new(file_name);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.dir().join("saves").join(path)
+        }
+    }
+
+    pub fn statistics_path(&self) -> PathBuf {
+        self.dir().join(STATISTICS_FILE)
+    }
+
+    /// Where the hints this profile has been shown are kept - see `hints`
+    pub fn seen_hints_path(&self) -> PathBuf {
+        self.dir().join(SEEN_HINTS_FILE)
+    }
+
+    ///
+    /// photo_path
+    ///
+    /// A new, timestamped file name for a photo mode capture
+    pub fn photo_path(&self) -> PathBuf {
+        let timestamp_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        self.dir()
+            .join("photos")
+            .join(format!("photo_{}.png", timestamp_millis))
+    }
+
+    ///
+    /// ensure_dirs
+    ///
+    /// Create the profile's directories, if they don't exist yet
+    pub fn ensure_dirs(&self) {
+        if let Err(err) = fs::create_dir_all(self.dir().join("saves")) {
+            warn!(
+                "Unable to create profile directory for {}: {}",
+                self.name, err
+            );
+        }
+    }
+
+    pub fn load_settings(&self) -> ProfileSettings {
+        let path = self.dir().join(SETTINGS_FILE);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+                warn!("Unable to parse profile settings {:?}: {}", path, err);
+                ProfileSettings::default()
+            }),
+            Err(_) => ProfileSettings::default(),
+        }
+    }
+
+    pub fn save_settings(&self, settings: &ProfileSettings) {
+        self.ensure_dirs();
+        let path = self.dir().join(SETTINGS_FILE);
+        match serde_json::to_string_pretty(settings) {
+            Ok(json) => {
+                if let Err(err) = fs::write(&path, json) {
+                    warn!("Unable to write profile settings {:?}: {}", path, err);
+                }
+            }
+            Err(err) => warn!("Unable to serialize profile settings: {}", err),
+        }
+    }
+}
+
+impl Default for PlayerProfile {
+    fn default() -> Self {
+        PlayerProfile::new(DEFAULT_PROFILE)
+    }
+}
+
+pub fn profiles_root() -> PathBuf {
+    paths::data_root().join("profiles")
+}
+
+///
+/// list_profiles
+///
+/// Names of all the profiles that have been created, sorted
+pub fn list_profiles() -> Vec<String> {
+    let mut profiles = fs::read_dir(profiles_root())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles
+}
+
+// Profile names become directory names - keep them to a safe set of characters
+fn sanitize_profile_name(name: &str) -> String {
+    let sanitized = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    if sanitized.trim_matches('_').is_empty() {
+        DEFAULT_PROFILE.to_owned()
+    } else {
+        sanitized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_profile_name() {
+        assert_eq!(sanitize_profile_name("Alice"), "alice");
+        assert_eq!(sanitize_profile_name("test run/1"), "test_run_1");
+        assert_eq!(sanitize_profile_name("../.."), DEFAULT_PROFILE);
+        assert_eq!(sanitize_profile_name(""), DEFAULT_PROFILE);
+    }
+
+    #[test]
+    fn test_save_path_is_profile_scoped() {
+        let profile = PlayerProfile::new("alice");
+        let save_path = profile.save_path("save1.sav");
+        assert!(save_path.ends_with("profiles/alice/saves/save1.sav"));
+    }
+}
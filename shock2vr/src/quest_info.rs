@@ -7,13 +7,72 @@ use std::collections::{HashMap, HashSet};
 
 use dark::properties::{KeyCard, QuestBitValue};
 use serde::{Deserialize, Serialize};
-use shipyard::Unique;
+use shipyard::{Unique, World};
+
+use crate::{
+    command::Command,
+    console::{ConsoleCommand, ConsoleRegistry},
+    scripts::Effect,
+};
+
+///
+/// GlobalValue
+///
+/// Value of a script-visible global variable - set from scripts (`TrapSetVar`) or the console
+/// (`var_set`), and read by trap conditions (see `conditions.rs`)
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum GlobalValue {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl GlobalValue {
+    ///
+    /// parse
+    ///
+    /// `true` / `false`, a number, or anything else as a string (optionally quoted)
+    pub fn parse(value: &str) -> GlobalValue {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "true" => GlobalValue::Bool(true),
+            "false" => GlobalValue::Bool(false),
+            _ => value
+                .parse::<i64>()
+                .map(GlobalValue::Int)
+                .unwrap_or_else(|_| {
+                    GlobalValue::Str(value.trim_matches(|c| c == '\'' || c == '"').to_owned())
+                }),
+        }
+    }
+
+    pub fn is_true(&self) -> bool {
+        match self {
+            GlobalValue::Bool(b) => *b,
+            GlobalValue::Int(i) => *i != 0,
+            GlobalValue::Str(s) => !s.is_empty(),
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            GlobalValue::Bool(b) => Some(*b as i64),
+            GlobalValue::Int(i) => Some(*i),
+            GlobalValue::Str(_) => None,
+        }
+    }
+}
 
 #[derive(Deserialize, Serialize, Unique, Clone, Debug)]
 pub struct QuestInfo {
     quest_bit_values: HashMap<String, QuestBitValue>,
     played_emails: HashSet<String>,
     key_cards: Vec<KeyCard>,
+    #[serde(default)]
+    played_announcements: HashSet<String>,
+    #[serde(default)]
+    variables: HashMap<String, GlobalValue>,
 }
 
 impl QuestInfo {
@@ -22,6 +81,8 @@ impl QuestInfo {
             quest_bit_values: HashMap::new(),
             played_emails: HashSet::new(),
             key_cards: Vec::new(),
+            played_announcements: HashSet::new(),
+            variables: HashMap::new(),
         }
     }
 
@@ -46,6 +107,21 @@ impl QuestInfo {
             .insert(quest_name.to_ascii_lowercase(), quest_value);
     }
 
+    ///
+    /// read_variable
+    ///
+    /// Value of a global variable - unset variables read as `0`
+    pub fn read_variable(&self, name: &str) -> GlobalValue {
+        self.variables
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or(GlobalValue::Int(0))
+    }
+
+    pub fn set_variable(&mut self, name: &str, value: GlobalValue) {
+        self.variables.insert(name.to_ascii_lowercase(), value);
+    }
+
     pub fn has_played_email(&self, email: &str) -> bool {
         self.played_emails.contains(email)
     }
@@ -53,4 +129,89 @@ impl QuestInfo {
     pub fn mark_email_as_played(&mut self, email: &str) {
         self.played_emails.insert(email.to_owned());
     }
+
+    pub fn has_played_announcement(&self, announcement_id: &str) -> bool {
+        self.played_announcements.contains(announcement_id)
+    }
+
+    pub fn mark_announcement_as_played(&mut self, announcement_id: &str) {
+        self.played_announcements.insert(announcement_id.to_owned());
+    }
+}
+
+///
+/// register_console_commands
+///
+/// `quest_set <name> <value>` - value is `unknown`, `incomplete`, `complete` or a raw number
+pub fn register_console_commands(registry: &mut ConsoleRegistry) {
+    registry.register(ConsoleCommand {
+        name: "quest_set",
+        usage: "quest_set <quest bit> <unknown|incomplete|complete|number>",
+        help: "set a quest bit",
+        parse: |args| match args {
+            [name, value] => {
+                let value = parse_quest_bit_value(value)
+                    .ok_or_else(|| format!("'{}' is not a quest bit value", value))?;
+                Ok(Box::new(SetQuestBitCommand {
+                    quest_bit_name: name.to_string(),
+                    quest_bit_value: value,
+                }))
+            }
+            _ => Err("Expected a quest bit and a value".to_owned()),
+        },
+    });
+    registry.register(ConsoleCommand {
+        name: "var_set",
+        usage: "var_set <variable> <true|false|number|string>",
+        help: "set a global script variable",
+        parse: |args| match args {
+            [name, value @ ..] if !value.is_empty() => Ok(Box::new(SetGlobalVariableCommand {
+                name: name.to_string(),
+                value: GlobalValue::parse(&value.join(" ")),
+            })),
+            _ => Err("Expected a variable and a value".to_owned()),
+        },
+    });
+}
+
+fn parse_quest_bit_value(value: &str) -> Option<QuestBitValue> {
+    match value.to_ascii_lowercase().as_str() {
+        "unknown" => Some(QuestBitValue::UNKNOWN),
+        "incomplete" => Some(QuestBitValue::INCOMPLETE),
+        "complete" => Some(QuestBitValue::COMPLETE),
+        number => number
+            .parse::<u32>()
+            .ok()
+            .and_then(QuestBitValue::from_bits),
+    }
+}
+
+#[derive(Debug)]
+struct SetQuestBitCommand {
+    quest_bit_name: String,
+    quest_bit_value: QuestBitValue,
+}
+
+impl Command for SetQuestBitCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::SetQuestBit {
+            quest_bit_name: self.quest_bit_name.clone(),
+            quest_bit_value: self.quest_bit_value,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SetGlobalVariableCommand {
+    name: String,
+    value: GlobalValue,
+}
+
+impl Command for SetGlobalVariableCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::SetGlobalVariable {
+            name: self.name.clone(),
+            value: self.value.clone(),
+        }
+    }
 }
@@ -0,0 +1,173 @@
+///
+/// replay.rs
+///
+/// Recording the player's input frame by frame, and playing it back from the same starting point.
+/// Playback uses the recorded frame times, so a replay sees the same inputs at the same game times
+/// as the original session (see `state_hash` for checking the simulation stays in step).
+///
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{input_context::InputContext, time::Time};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub delta_seconds: f32,
+    pub input: InputContext,
+}
+
+///
+/// InputRecording
+///
+/// A play session's inputs, along with where it started
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// Mission argument the session started with, ie `medsci1.mis:landing_b`
+    pub mission: String,
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn new(mission: &str) -> InputRecording {
+        InputRecording {
+            mission: mission.to_owned(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, time: &Time, input: &InputContext) {
+        self.frames.push(RecordedFrame {
+            delta_seconds: time.elapsed.as_secs_f32(),
+            input: input.clone(),
+        });
+    }
+
+    /// Length of the recording, in seconds
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.delta_seconds).sum()
+    }
+
+    pub fn load(path: &Path) -> Result<InputRecording, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("Unable to read {}: {}", path.display(), err))?;
+        serde_json::from_str(&json)
+            .map_err(|err| format!("Unable to parse {}: {}", path.display(), err))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        std::fs::write(path, json)
+            .map_err(|err| format!("Unable to write {}: {}", path.display(), err))
+    }
+
+    ///
+    /// load_dir
+    ///
+    /// Every recording (`*.json`) in a folder, in file name order - unreadable ones are skipped
+    pub fn load_dir(dir: &Path) -> Vec<InputRecording> {
+        let mut paths: Vec<_> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            Err(err) => {
+                warn!("Unable to read recordings from {}: {}", dir.display(), err);
+                return Vec::new();
+            }
+        };
+        paths.sort();
+
+        paths
+            .iter()
+            .filter_map(|path| {
+                InputRecording::load(path)
+                    .map_err(|err| warn!("{}", err))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+///
+/// ReplayPlayer
+///
+/// Steps through a recording, one frame per game update
+pub struct ReplayPlayer {
+    recording: InputRecording,
+    frame: usize,
+    total: Duration,
+}
+
+impl ReplayPlayer {
+    pub fn new(recording: InputRecording) -> ReplayPlayer {
+        ReplayPlayer {
+            recording,
+            frame: 0,
+            total: Duration::ZERO,
+        }
+    }
+
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.recording.frames.len()
+    }
+
+    /// The next frame's time and input, or None once the recording is over
+    pub fn next_frame(&mut self) -> Option<(Time, InputContext)> {
+        let frame = self.recording.frames.get(self.frame)?;
+        self.frame += 1;
+
+        let elapsed = Duration::from_secs_f32(frame.delta_seconds.max(0.0));
+        self.total += elapsed;
+        Some((
+            Time {
+                elapsed,
+                total: self.total,
+            },
+            frame.input.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::vec2;
+
+    use super::*;
+
+    fn frame_time(seconds: f32) -> Time {
+        Time {
+            elapsed: Duration::from_secs_f32(seconds),
+            total: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_record_and_play_back() {
+        let mut recording = InputRecording::new("earth.mis");
+        let mut input = InputContext::default();
+        input.right_hand.thumbstick = vec2(0.0, 1.0);
+        recording.record(&frame_time(0.5), &input);
+        recording.record(&frame_time(0.25), &InputContext::default());
+        assert_eq!(recording.duration(), 0.75);
+
+        // Round-trip through JSON, as it would through a file
+        let json = serde_json::to_string(&recording).unwrap();
+        let recording: InputRecording = serde_json::from_str(&json).unwrap();
+
+        let mut player = ReplayPlayer::new(recording);
+        let (time, input) = player.next_frame().unwrap();
+        assert_eq!(time.elapsed, Duration::from_secs_f32(0.5));
+        assert_eq!(input.right_hand.thumbstick, vec2(0.0, 1.0));
+
+        let (time, _) = player.next_frame().unwrap();
+        assert_eq!(time.total, Duration::from_secs_f32(0.75));
+        assert!(player.is_finished());
+        assert!(player.next_frame().is_none());
+    }
+}
@@ -8,8 +8,10 @@
  * - They are not serialized / deserialized
  */
 use cgmath::Matrix4;
-use dark::ss2_bin_obj_loader::Vhot;
-use shipyard::Component;
+use dark::{motion::JointId, ss2_bin_obj_loader::Vhot};
+use shipyard::{Component, EntityId};
+
+use crate::{entity_tags::EntityTags, factions::Faction};
 
 // RuntimePropGazeAmount - track how much the player is gazing at a prop
 #[derive(Component)]
@@ -35,6 +37,30 @@ pub struct RuntimePropVhots(pub Vec<Vhot>);
 #[derive(Component)]
 pub struct RuntimePropDoNotSerialize;
 
+// RuntimePropClimbable - surface the player can grip and climb with their hands (see VirtualHand)
+#[derive(Component)]
+pub struct RuntimePropClimbable;
+
+// RuntimePropAlarmLight - light that turns on and flashes while the security alarm is active (see SecuritySystem)
+#[derive(Component)]
+pub struct RuntimePropAlarmLight;
+
 // RuntimePropProxyEntity - pointer to the parent entity (for example, hitboxes use this to point to the parent entity)
 #[derive(Component)]
-pub struct RuntimePropProxyEntity(pub shipyard::EntityId);
+pub struct RuntimePropProxyEntity(pub EntityId);
+
+// RuntimePropEntityTags - gameplay categories from the entity's archetypes and scripts, added at creation (see entity_tags)
+#[derive(Component, Clone, Copy, Debug)]
+pub struct RuntimePropEntityTags(pub EntityTags);
+
+// RuntimePropFaction - faction the entity was switched to at runtime, replacing its default (see factions)
+#[derive(Component, Clone, Copy, Debug)]
+pub struct RuntimePropFaction(pub Faction);
+
+// RuntimePropAttachment - entity that follows another entity, or one of its joints - like a weapon in an AI's hand (see systems::update_attachments)
+#[derive(Component, Clone, Copy, Debug)]
+pub struct RuntimePropAttachment {
+    pub parent: EntityId,
+    pub joint: Option<JointId>,
+    pub local_transform: Matrix4<f32>,
+}
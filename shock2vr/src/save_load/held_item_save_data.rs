@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use shipyard::{EntityId, World};
 
+use crate::equipment::EquipSlot;
+
 use super::EntitySaveData;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -9,6 +11,12 @@ pub struct HeldItemSaveData {
     pub entity_in_left_hand: Option<u64>,
     pub entity_in_right_hand: Option<u64>,
     pub inventory_entity: Option<u64>,
+    // Older saves don't have equipment - nothing was equipped
+    #[serde(default)]
+    pub equipped_entities: Vec<(EquipSlot, u64)>,
+    // Older saves don't track energy - the player starts out full
+    #[serde(default)]
+    pub energy: Option<f32>,
 }
 
 impl HeldItemSaveData {
@@ -18,13 +26,20 @@ impl HeldItemSaveData {
             entity_in_left_hand: None,
             entity_in_right_hand: None,
             inventory_entity: None,
+            equipped_entities: Vec::new(),
+            energy: None,
         }
     }
 
     pub fn instantiate(
         &self,
         world: &mut World,
-    ) -> (Option<EntityId>, Option<EntityId>, Option<EntityId>) {
+    ) -> (
+        Option<EntityId>,
+        Option<EntityId>,
+        Option<EntityId>,
+        Vec<(EquipSlot, EntityId)>,
+    ) {
         let (_, entity_id_map) = self.held_entities.instantiate(world);
 
         let mut left_hand_entity_id = None;
@@ -49,10 +64,21 @@ impl HeldItemSaveData {
             }
         }
 
+        let equipped_entities = self
+            .equipped_entities
+            .iter()
+            .filter_map(|(slot, ent)| {
+                entity_id_map
+                    .get(&EntityId::from_inner(*ent).unwrap())
+                    .map(|new_entity_id| (*slot, *new_entity_id))
+            })
+            .collect();
+
         (
             left_hand_entity_id,
             right_hand_entity_id,
             inventory_entity_id,
+            equipped_entities,
         )
     }
 }
@@ -1,10 +1,12 @@
 mod entity_save_data;
 mod held_item_save_data;
 mod save_data;
+mod ss2_import;
 
 pub use entity_save_data::*;
 pub use held_item_save_data::*;
 pub use save_data::*;
+pub use ss2_import::*;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -15,7 +17,8 @@ use dark::properties::{Link, Links};
 use shipyard::{EntitiesView, EntityId, IntoIter, IntoWithId, UniqueView, View, World};
 
 use crate::{
-    creature::RuntimePropHitBox,
+    energy::PlayerEnergy,
+    equipment::PlayerEquipment,
     gui::GuiPropProxyEntity,
     mission::{GlobalTemplateIdMap, PlayerInfo},
     runtime_props::RuntimePropDoNotSerialize,
@@ -158,6 +161,19 @@ pub fn to_save_data(world: &World) -> (EntitySaveData, HeldItemSaveData) {
         entity_in_right_hand: player.right_hand_entity_id.map(|ent| ent.inner()),
         held_entities: held_entity_data,
         inventory_entity: Some(player.inventory_entity_id.inner()),
+        equipped_entities: world
+            .borrow::<UniqueView<PlayerEquipment>>()
+            .map(|equipment| {
+                equipment
+                    .equipped()
+                    .map(|(slot, item)| (slot, item.entity_id.inner()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        energy: world
+            .borrow::<UniqueView<PlayerEnergy>>()
+            .map(|energy| energy.energy())
+            .ok(),
     };
     (world_entity_data, held_metadata)
 }
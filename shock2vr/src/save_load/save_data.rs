@@ -4,7 +4,7 @@
  * Data type for information we serialize to load/save the game
  */
 use super::{EntitySaveData, HeldItemSaveData};
-use crate::quest_info::QuestInfo;
+use crate::{quest_info::QuestInfo, statistics::Statistics};
 use cgmath::{Quaternion, Vector3};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -39,4 +39,7 @@ pub struct GlobalData {
     pub quest_info: QuestInfo,
     pub held_items: HeldItemSaveData,
     pub active_mission: String,
+    // Older saves don't have statistics - start those from zero
+    #[serde(default)]
+    pub statistics: Statistics,
 }
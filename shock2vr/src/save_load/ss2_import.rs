@@ -0,0 +1,75 @@
+use std::{fs::File, io::BufReader};
+
+use dark::{Gamesys, properties::QuestBitValue, ss2_save_game};
+use tracing::info;
+
+use crate::quest_info::{GlobalValue, QuestInfo};
+
+///
+/// ImportedSave
+///
+/// Progress carried over from an original System Shock 2 save - the quest state, and the items to
+/// give the player once the mission has loaded
+pub struct ImportedSave {
+    pub quest_info: QuestInfo,
+    pub inventory: Vec<String>,
+}
+
+///
+/// import_ss2_save
+///
+/// Read an original save (ie, `save_0/current.sav`). Object state in the level isn't carried
+/// over - the mission starts fresh, with the save's quest variables and inventory.
+pub fn import_ss2_save(path: &str, gamesys: &Gamesys) -> Result<ImportedSave, String> {
+    let file =
+        File::open(path).map_err(|err| format!("Unable to open savegame {}: {}", path, err))?;
+    let saved_game = ss2_save_game::read(&mut BufReader::new(file), gamesys)
+        .map_err(|err| format!("Unable to read savegame {}: {}", path, err))?;
+
+    let mut quest_info = QuestInfo::new();
+    for variable in &saved_game.quest_variables {
+        apply_quest_variable(&mut quest_info, &variable.name, variable.value);
+    }
+
+    info!(
+        "imported savegame {}: {} quest variables, inventory: {:?}",
+        path,
+        saved_game.quest_variables.len(),
+        saved_game.inventory
+    );
+
+    Ok(ImportedSave {
+        quest_info,
+        inventory: saved_game.inventory,
+    })
+}
+
+/// Quest variables that look like quest bits (unknown / incomplete / complete) become quest bits,
+/// and the rest - counters and the like - become global variables
+fn apply_quest_variable(quest_info: &mut QuestInfo, name: &str, value: i32) {
+    match u32::try_from(value).ok().and_then(QuestBitValue::from_bits) {
+        Some(quest_bit) => quest_info.set_quest_bit_value(name, quest_bit),
+        None => quest_info.set_variable(name, GlobalValue::Int(value as i64)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_quest_variable() {
+        let mut quest_info = QuestInfo::new();
+        apply_quest_variable(&mut quest_info, "Note_Earth_1", 2);
+        apply_quest_variable(&mut quest_info, "nanites_found", 150);
+
+        assert_eq!(
+            quest_info.read_quest_bit_value("note_earth_1"),
+            QuestBitValue::COMPLETE
+        );
+        assert_eq!(
+            quest_info.read_variable("nanites_found"),
+            GlobalValue::Int(150)
+        );
+    }
+}
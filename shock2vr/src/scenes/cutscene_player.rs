@@ -3,7 +3,7 @@ use std::{collections::HashMap, rc::Rc, time::Duration};
 use cgmath::{InnerSpace, Matrix3, Matrix4, Quaternion, Vector3, vec3};
 use engine::{
     assets::asset_cache::AssetCache,
-    audio::AudioContext,
+    audio::{AudioContext, AudioHandle, play_audio, stop_audio},
     scene::{SceneObject, basic_material, light::SpotLight},
     texture::{TextureOptions, TextureTrait, init_from_memory2},
 };
@@ -23,8 +23,9 @@ use crate::{
 #[cfg(feature = "ffmpeg")]
 use engine_ffmpeg::{AudioPlayer, VideoPlayer};
 
+// Without ffmpeg, fall back to the pure-Rust player - uncompressed / Motion JPEG .avi files only
 #[cfg(not(feature = "ffmpeg"))]
-use engine::texture_format::{PixelFormat, RawTextureData};
+use engine::video::AviPlayer as VideoPlayer;
 
 /// Displays a flat panel in front of the player and plays back a video file.
 pub struct CutscenePlayerScene {
@@ -37,7 +38,8 @@ pub struct CutscenePlayerScene {
     screen_vertical_offset: f32,
     video_name: String,
     total_time: Duration,
-    #[cfg(feature = "ffmpeg")]
+    // Handle for the soundtrack, so it can be cut off when the cutscene is skipped
+    audio_handle: AudioHandle,
     video_player: VideoPlayer,
 }
 
@@ -49,44 +51,36 @@ impl CutscenePlayerScene {
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let world = Self::initialize_world();
 
-        #[cfg(feature = "ffmpeg")]
-        {
-            use engine::audio::{AudioHandle, play_audio};
-
-            let video_player = VideoPlayer::from_filename(&video_path)?;
-            let audio_clip = Rc::new(AudioPlayer::from_filename(&video_path)?);
-            play_audio(audio_context, AudioHandle::new(), None, audio_clip);
-
-            return Ok(Self {
-                world,
-                head_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-                player_position: vec3(0.0, 0.0, 0.0),
-                player_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-                head_height: 4.0 / dark::SCALE_FACTOR,
-                screen_distance: 6.0 / dark::SCALE_FACTOR,
-                screen_vertical_offset: 1.5 / dark::SCALE_FACTOR,
-                video_name,
-                total_time: Duration::ZERO,
-                video_player,
-            });
-        }
+        let video_player = VideoPlayer::from_filename(&video_path)?;
 
+        #[cfg(feature = "ffmpeg")]
+        let maybe_audio_clip = Some(AudioPlayer::from_filename(&video_path)?);
         #[cfg(not(feature = "ffmpeg"))]
-        {
-            let _ = audio_context;
-            let _ = video_path;
-            Ok(Self {
-                world,
-                head_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-                player_position: vec3(0.0, 0.0, 0.0),
-                player_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
-                head_height: 4.0 / dark::SCALE_FACTOR,
-                screen_distance: 6.0 / dark::SCALE_FACTOR,
-                screen_vertical_offset: 1.5 / dark::SCALE_FACTOR,
-                video_name,
-                total_time: Duration::ZERO,
-            })
+        let maybe_audio_clip = video_player.audio_clip();
+
+        let audio_handle = AudioHandle::new();
+        if let Some(audio_clip) = maybe_audio_clip {
+            play_audio(
+                audio_context,
+                audio_handle.clone(),
+                None,
+                Rc::new(audio_clip),
+            );
         }
+
+        Ok(Self {
+            world,
+            head_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            player_position: vec3(0.0, 0.0, 0.0),
+            player_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            head_height: 4.0 / dark::SCALE_FACTOR,
+            screen_distance: 6.0 / dark::SCALE_FACTOR,
+            screen_vertical_offset: 1.5 / dark::SCALE_FACTOR,
+            video_name,
+            total_time: Duration::ZERO,
+            audio_handle,
+            video_player,
+        })
     }
 
     fn initialize_world() -> World {
@@ -116,6 +110,22 @@ impl CutscenePlayerScene {
         world
     }
 
+    ///
+    /// is_finished
+    ///
+    /// Whether the video has played to the end
+    pub fn is_finished(&self) -> bool {
+        self.video_player.is_finished()
+    }
+
+    ///
+    /// stop
+    ///
+    /// Cut off the soundtrack - for cutscenes that are skipped, or end before their audio
+    pub fn stop(&self, audio_context: &mut AudioContext<EntityId, String>) {
+        stop_audio(audio_context, self.audio_handle.clone());
+    }
+
     fn head_base(&self) -> Vector3<f32> {
         self.player_position + vec3(0.0, self.head_height, 0.0)
     }
@@ -165,41 +175,19 @@ impl CutscenePlayerScene {
     }
 
     fn build_video_texture(&self) -> (Rc<dyn TextureTrait>, f32) {
-        #[cfg(feature = "ffmpeg")]
-        {
-            let texture_data = self.video_player.get_current_frame();
-            let aspect_ratio = if texture_data.height == 0 {
-                16.0 / 9.0
-            } else {
-                texture_data.width as f32 / texture_data.height as f32
-            };
-            (
-                Rc::new(init_from_memory2(
-                    texture_data,
-                    &TextureOptions { wrap: false },
-                )),
-                aspect_ratio,
-            )
-        }
-
-        #[cfg(not(feature = "ffmpeg"))]
-        {
-            let white_pixel = vec![255u8, 255u8, 255u8, 255u8];
-            let aspect_ratio = 16.0 / 9.0;
-            let texture_data = RawTextureData {
-                width: 1,
-                height: 1,
-                bytes: white_pixel,
-                format: PixelFormat::RGBA,
-            };
-            (
-                Rc::new(init_from_memory2(
-                    texture_data,
-                    &TextureOptions { wrap: false },
-                )),
-                aspect_ratio,
-            )
-        }
+        let texture_data = self.video_player.get_current_frame();
+        let aspect_ratio = if texture_data.height == 0 {
+            16.0 / 9.0
+        } else {
+            texture_data.width as f32 / texture_data.height as f32
+        };
+        (
+            Rc::new(init_from_memory2(
+                texture_data,
+                &TextureOptions { wrap: false },
+            )),
+            aspect_ratio,
+        )
     }
 }
 
@@ -224,10 +212,7 @@ impl GameScene for CutscenePlayerScene {
         self.total_time += time.elapsed;
         self.update_player_info();
 
-        #[cfg(feature = "ffmpeg")]
-        {
-            self.video_player.advance_by_time(time.elapsed);
-        }
+        self.video_player.advance_by_time(time.elapsed);
 
         Vec::new()
     }
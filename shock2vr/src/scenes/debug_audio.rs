@@ -0,0 +1,254 @@
+use cgmath::{Matrix4, Quaternion, Rad, Vector3, vec3};
+use dark::{SCALE_FACTOR, importers::FONT_IMPORTER};
+use engine::{
+    assets::asset_cache::AssetCache,
+    audio::{AudioContext, AudioHandle},
+    scene::{SceneObject, color_material},
+};
+use rapier3d::prelude::{ColliderBuilder, Isometry, SharedShape};
+use shipyard::{EntityId, UniqueView};
+
+use crate::{
+    GameOptions, PlayerInfo,
+    game_scene::GameScene,
+    input_context::InputContext,
+    mission::{GlobalContext, mission_core::MissionCore},
+    scenes::debug_common::{DebugSceneBuildOptions, DebugSceneBuilder, DebugSceneHooks},
+    scripts::Effect,
+    time::Time,
+};
+
+// Schema the emitters play - swap in anything else with the `sound` console command
+const DEFAULT_SCHEMA: &str = "bkeypad";
+
+// Seconds between emitters taking their turn
+const EMITTER_INTERVAL: f32 = 1.5;
+
+// Distance between emitters in the grid, in SS2 units
+const GRID_SPACING: f32 = 15.0;
+
+// Height emitters sit at - around the player's ears, in SS2 units
+const EAR_HEIGHT: f32 = 5.0;
+
+// Wall between the player and the occluded emitter (center and size, in SS2 units)
+const WALL_CENTER: Vector3<f32> = Vector3::new(0.0, 6.0, 30.0);
+const WALL_SIZE: Vector3<f32> = Vector3::new(16.0, 12.0, 1.0);
+
+// Matches `DebugSceneBuilder::with_default_floor`, which the wall's collider replaces
+const FLOOR_SIZE: Vector3<f32> = Vector3::new(120.0, 0.5, 120.0);
+
+struct Emitter {
+    label: String,
+    position: Vector3<f32>,
+}
+
+///
+/// DebugAudioScene
+///
+/// Labeled sound emitters around the player for checking spatialization - a grid at ear height,
+/// one overhead, one far away and one behind a wall (for occlusion and reverb, once the engine
+/// simulates them). The emitters play in turn, and the right trigger pauses them.
+pub struct DebugAudioScene;
+
+impl DebugAudioScene {
+    pub fn new(
+        global_context: &GlobalContext,
+        game_options: &GameOptions,
+        asset_cache: &mut AssetCache,
+        audio_context: &mut AudioContext<EntityId, String>,
+    ) -> Box<dyn GameScene> {
+        let emitters = create_emitters();
+
+        let mut builder = DebugSceneBuilder::new("debug_audio")
+            .with_default_floor()
+            .with_physics_geometry(create_physics_geometry());
+
+        let mut wall = SceneObject::new(
+            color_material::create(vec3(0.4, 0.4, 0.45)),
+            Box::new(engine::scene::cube::create()),
+        );
+        wall.set_transform(
+            Matrix4::from_translation(WALL_CENTER / SCALE_FACTOR)
+                * Matrix4::from_nonuniform_scale(
+                    WALL_SIZE.x / SCALE_FACTOR,
+                    WALL_SIZE.y / SCALE_FACTOR,
+                    WALL_SIZE.z / SCALE_FACTOR,
+                ),
+        );
+        builder = builder.add_scene_object(wall);
+
+        for emitter in &emitters {
+            let mut marker = SceneObject::new(
+                color_material::create(vec3(0.2, 0.8, 1.0)),
+                Box::new(engine::scene::cube::create()),
+            );
+            marker.set_transform(
+                Matrix4::from_translation(emitter.position) * Matrix4::from_scale(0.2),
+            );
+            builder = builder.add_scene_object(marker);
+        }
+
+        let build_options = DebugSceneBuildOptions {
+            global_context,
+            game_options,
+            asset_cache,
+            audio_context,
+        };
+
+        builder.build_with_hooks(build_options, AudioHooks::new(emitters))
+    }
+}
+
+fn create_emitters() -> Vec<Emitter> {
+    let mut emitters = Vec::new();
+    for x in -1..=1 {
+        for z in -1..=1 {
+            if x == 0 && z == 0 {
+                continue;
+            }
+            let position = vec3(x as f32, 0.0, z as f32) * GRID_SPACING;
+            emitters.push(Emitter {
+                label: format!("x {} z {}", position.x, position.z),
+                position: vec3(position.x, EAR_HEIGHT, position.z) / SCALE_FACTOR,
+            });
+        }
+    }
+
+    emitters.push(Emitter {
+        label: "above".to_owned(),
+        position: vec3(0.0, EAR_HEIGHT + GRID_SPACING, 0.0) / SCALE_FACTOR,
+    });
+    emitters.push(Emitter {
+        label: "far".to_owned(),
+        position: vec3(-GRID_SPACING * 3.5, EAR_HEIGHT, 0.0) / SCALE_FACTOR,
+    });
+    emitters.push(Emitter {
+        label: "behind wall".to_owned(),
+        position: vec3(0.0, EAR_HEIGHT, WALL_CENTER.z + GRID_SPACING * 0.5) / SCALE_FACTOR,
+    });
+    emitters
+}
+
+fn create_physics_geometry() -> rapier3d::prelude::Collider {
+    let floor_half_size = FLOOR_SIZE / SCALE_FACTOR * 0.5;
+    let wall_half_size = WALL_SIZE / SCALE_FACTOR * 0.5;
+    let wall_center = WALL_CENTER / SCALE_FACTOR;
+
+    ColliderBuilder::compound(vec![
+        (
+            Isometry::identity(),
+            SharedShape::cuboid(floor_half_size.x, floor_half_size.y, floor_half_size.z),
+        ),
+        (
+            Isometry::translation(wall_center.x, wall_center.y, wall_center.z),
+            SharedShape::cuboid(wall_half_size.x, wall_half_size.y, wall_half_size.z),
+        ),
+    ])
+    .build()
+}
+
+struct AudioHooks {
+    emitters: Vec<Emitter>,
+    next_emitter: usize,
+    playing_emitter: Option<usize>,
+    timer: f32,
+    is_paused: bool,
+    last_trigger: bool,
+}
+
+impl AudioHooks {
+    fn new(emitters: Vec<Emitter>) -> Self {
+        println!(
+            "[debug_audio] Controls:\n\
+             - Right trigger to pause / resume the emitters\n\
+             - `sound <schema> [x y z]` in the console to play anything else"
+        );
+
+        Self {
+            emitters,
+            next_emitter: 0,
+            playing_emitter: None,
+            timer: 0.0,
+            is_paused: false,
+            last_trigger: false,
+        }
+    }
+}
+
+impl DebugSceneHooks for AudioHooks {
+    fn before_update(
+        &mut self,
+        _core: &mut MissionCore,
+        time: &Time,
+        input_context: &InputContext,
+        _asset_cache: &mut AssetCache,
+        _game_options: &GameOptions,
+    ) {
+        let trigger = input_context.right_hand.trigger_value > 0.5;
+        if trigger && !self.last_trigger {
+            self.is_paused = !self.is_paused;
+            println!(
+                "[debug_audio] Emitters {}",
+                if self.is_paused { "paused" } else { "resumed" }
+            );
+        }
+        self.last_trigger = trigger;
+
+        if !self.is_paused {
+            self.timer -= time.elapsed.as_secs_f32();
+        }
+    }
+
+    fn before_handle_effects(
+        &mut self,
+        _core: &mut MissionCore,
+        effects: &mut Vec<Effect>,
+        _global_context: &GlobalContext,
+        _game_options: &GameOptions,
+        _asset_cache: &mut AssetCache,
+        _audio_context: &mut AudioContext<EntityId, String>,
+    ) {
+        if self.is_paused || self.timer > 0.0 || self.emitters.is_empty() {
+            return;
+        }
+
+        let index = self.next_emitter;
+        effects.push(Effect::PlaySoundAt {
+            handle: AudioHandle::new(),
+            name: DEFAULT_SCHEMA.to_owned(),
+            position: self.emitters[index].position,
+        });
+
+        self.playing_emitter = Some(index);
+        self.next_emitter = (index + 1) % self.emitters.len();
+        self.timer = EMITTER_INTERVAL;
+    }
+
+    fn after_render(
+        &mut self,
+        core: &mut MissionCore,
+        scene_objects: &mut Vec<SceneObject>,
+        _camera_position: &mut Vector3<f32>,
+        _camera_rotation: &mut Quaternion<f32>,
+        asset_cache: &mut AssetCache,
+        _options: &GameOptions,
+    ) {
+        let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon").unwrap();
+        let player_pos = core.world().borrow::<UniqueView<PlayerInfo>>().unwrap().pos;
+
+        for (index, emitter) in self.emitters.iter().enumerate() {
+            let label = if self.playing_emitter == Some(index) {
+                format!("> {} <", emitter.label)
+            } else {
+                emitter.label.clone()
+            };
+
+            let position = emitter.position + vec3(0.0, 1.0 / SCALE_FACTOR, 0.0);
+            let to_player = player_pos - position;
+            let yaw = Rad(to_player.x.atan2(to_player.z));
+            let mut text = SceneObject::world_space_text(&label, font.clone(), 0.0);
+            text.set_transform(Matrix4::from_translation(position) * Matrix4::from_angle_y(yaw));
+            scene_objects.push(text);
+        }
+    }
+}
@@ -18,11 +18,11 @@ use rapier3d::prelude::{Collider, ColliderBuilder};
 use shipyard::EntityId;
 
 use crate::{
-    GameOptions,
+    GameOptions, PhysicsConfig,
     game_scene::GameScene,
     input_context::InputContext,
     mission::{
-        AbstractMission, AlwaysVisible, GlobalContext, SpawnLocation,
+        AbstractMission, AlwaysVisible, GlobalContext, SpawnLocation, SurfaceMaterials,
         entity_populator::empty_entity_populator::EmptyEntityPopulator, mission_core::MissionCore,
     },
     quest_info::QuestInfo,
@@ -134,6 +134,7 @@ impl DebugSceneBuilder {
             },
             room_db: RoomDatabase { rooms: Vec::new() },
             physics_geometry,
+            surface_materials: SurfaceMaterials::default(),
             spatial_data: None,
             entity_info: SystemShock2EntityInfo::empty(),
             obj_map: HashMap::new(),
@@ -244,6 +245,10 @@ impl GameScene for DebugScene {
         )
     }
 
+    fn set_physics_config(&mut self, config: &PhysicsConfig) {
+        self.core.physics.set_config(config.clone());
+    }
+
     fn get_hand_spotlights(&self, options: &GameOptions) -> Vec<SpotLight> {
         self.core.get_hand_spotlights(options)
     }
@@ -400,6 +405,10 @@ impl<H: DebugSceneHooks> GameScene for HookedDebugScene<H> {
         )
     }
 
+    fn set_physics_config(&mut self, config: &PhysicsConfig) {
+        self.core.physics.set_config(config.clone());
+    }
+
     fn get_hand_spotlights(&self, options: &GameOptions) -> Vec<SpotLight> {
         self.core.get_hand_spotlights(options)
     }
@@ -525,7 +534,7 @@ impl DebugSceneFloor {
                 uv_scale,
             } => {
                 // Use plane geometry with textured material
-                let grid_texture = asset_cache.get(&TEXTURE_IMPORTER, &texture_name);
+                let grid_texture = asset_cache.get(&TEXTURE_IMPORTER, &texture_name).unwrap();
                 let texture_trait: std::rc::Rc<dyn engine::texture::TextureTrait> = grid_texture;
                 let floor_material =
                     basic_material::create(texture_trait, emissivity, transparency);
@@ -58,7 +58,9 @@ impl DebugSceneHooks for GloveHooks {
 }
 
 fn load_glove_template(asset_cache: &mut AssetCache) -> Vec<SceneObject> {
-    let model = asset_cache.get(&GLB_MODELS_IMPORTER, "vr_glove_model.glb");
+    let model = asset_cache
+        .get(&GLB_MODELS_IMPORTER, "vr_glove_model.glb")
+        .unwrap();
     let mut scene_objects = model.clone_scene_objects();
 
     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
@@ -152,7 +154,9 @@ impl DebugGlovesScene {
 
         let core = builder.build_core(build_options);
 
-        let glove_model = asset_cache.get(&GLB_MODELS_IMPORTER, "vr_glove_model.glb");
+        let glove_model = asset_cache
+            .get(&GLB_MODELS_IMPORTER, "vr_glove_model.glb")
+            .unwrap();
         let glove_template = load_glove_template(asset_cache);
 
         info!(
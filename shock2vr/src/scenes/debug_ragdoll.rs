@@ -140,6 +140,7 @@ impl RagdollHooks {
             let damage_effect = Effect::AdjustHitPoints {
                 entity_id,
                 delta: -1000,
+                source: None,
             };
             let effects = vec![damage_effect];
             let _global_effects = core.handle_effects(
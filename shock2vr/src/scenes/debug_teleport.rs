@@ -1,7 +1,7 @@
 use engine::{assets::asset_cache::AssetCache, audio::AudioContext};
 use shipyard::EntityId;
 
-use crate::{GameOptions, mission::GlobalContext};
+use crate::{GameOptions, experimental::ExperimentalFeature, mission::GlobalContext};
 
 use super::debug_common::{DebugScene, DebugSceneBuildOptions, DebugSceneBuilder};
 
@@ -17,13 +17,17 @@ impl DebugTeleportScene {
     ) -> DebugScene {
         // Create new game options with teleport feature enabled
         let mut experimental_features = game_options.experimental_features.clone();
-        experimental_features.insert("teleport".to_string());
+        experimental_features.insert(ExperimentalFeature::Teleport);
 
         let teleport_options = GameOptions {
             mission: game_options.mission.clone(),
             spawn_location: game_options.spawn_location.clone(),
             save_file: game_options.save_file.clone(),
+            profile: game_options.profile.clone(),
+            mods: game_options.mods.clone(),
             render_particles: game_options.render_particles,
+            render_hands: game_options.render_hands,
+            reticle: game_options.reticle.clone(),
             debug_physics: game_options.debug_physics,
             debug_draw: game_options.debug_draw,
             debug_portals: game_options.debug_portals,
@@ -31,7 +35,12 @@ impl DebugTeleportScene {
             debug_skeletons: game_options.debug_skeletons,
             debug_ai: game_options.debug_ai,
             debug_pathfinding: game_options.debug_pathfinding,
+            debug_profiler: game_options.debug_profiler,
             experimental_features,
+            accessibility: game_options.accessibility.clone(),
+            language: game_options.language.clone(),
+            physics_config: game_options.physics_config.clone(),
+            root_motion_config: game_options.root_motion_config.clone(),
         };
 
         let builder = DebugSceneBuilder::new("debug_teleport").with_default_floor();
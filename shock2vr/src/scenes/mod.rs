@@ -5,20 +5,23 @@ use std::{
 };
 
 use engine::{assets::asset_cache::AssetCache, audio::AudioContext};
-use shipyard::EntityId;
+use shipyard::{EntityId, UniqueViewMut};
+use tracing::warn;
 
 use crate::{
     GameOptions, QuestInfo, SpawnLocation,
     game_scene::GameScene,
     mission::{
-        GlobalContext, Mission,
+        EffectQueue, GlobalContext, Mission,
         entity_populator::{EntityPopulator, MissionEntityPopulator, SaveFileEntityPopulator},
     },
     paths,
-    save_load::{EntitySaveData, HeldItemSaveData, SaveData},
+    save_load::{self, EntitySaveData, HeldItemSaveData, SaveData},
+    scripts::Effect,
 };
 
 pub mod cutscene_player;
+pub mod debug_audio;
 pub mod debug_camera;
 pub mod debug_common;
 pub mod debug_gloves;
@@ -32,6 +35,7 @@ pub mod debug_turret;
 pub mod hand_pose;
 
 pub use cutscene_player::CutscenePlayerScene;
+pub use debug_audio::DebugAudioScene;
 pub use debug_camera::DebugCameraScene;
 pub use debug_gloves::DebugGlovesScene;
 pub use debug_hud::DebugHudScene;
@@ -105,6 +109,13 @@ pub fn create_initial_scene(
         };
     }
 
+    if options.mission.eq_ignore_ascii_case("debug_audio") {
+        return SceneInitResult {
+            scene: DebugAudioScene::new(global_context, options, asset_cache, audio_context),
+            mission_save_data: HashMap::new(),
+        };
+    }
+
     if options.mission.eq_ignore_ascii_case("debug_turret") {
         return SceneInitResult {
             scene: DebugTurretScene::new(global_context, options, asset_cache, audio_context),
@@ -171,6 +182,42 @@ pub fn create_initial_scene(
         };
     }
 
+    if let Some(import_path) = &options.import_save {
+        match save_load::import_ss2_save(import_path, &global_context.gamesys) {
+            Ok(imported) => {
+                let active_mission = Mission::load(
+                    options.mission.to_owned(),
+                    asset_cache,
+                    audio_context,
+                    global_context,
+                    options.spawn_location.clone(),
+                    imported.quest_info,
+                    Box::new(MissionEntityPopulator::create()),
+                    HeldItemSaveData::empty(),
+                    options,
+                );
+
+                // Hand the items over once the mission is running, the same way `give` does
+                {
+                    let mut effect_queue = active_mission
+                        .mission_core
+                        .world
+                        .borrow::<UniqueViewMut<EffectQueue>>()
+                        .unwrap();
+                    for template_name in imported.inventory {
+                        effect_queue.push(Effect::GiveItem { template_name });
+                    }
+                }
+
+                return SceneInitResult {
+                    scene: Box::new(active_mission),
+                    mission_save_data: HashMap::new(),
+                };
+            }
+            Err(err) => warn!("{} - starting {} fresh", err, options.mission),
+        }
+    }
+
     let mission_save_data = HashMap::new();
     let active_mission = Mission::load(
         options.mission.to_owned(),
@@ -228,6 +275,10 @@ pub fn load_mission_from_save_data(
         save_data.global_data.held_items,
         game_options,
     );
+    active_mission
+        .mission_core
+        .world
+        .add_unique(save_data.global_data.statistics);
 
     (active_mission, save_data.level_data)
 }
@@ -236,7 +287,7 @@ fn is_cutscene_mission(name: &str) -> bool {
     name.trim().to_ascii_lowercase().ends_with(".avi")
 }
 
-fn resolve_cutscene_path(name: &str) -> PathBuf {
+pub(crate) fn resolve_cutscene_path(name: &str) -> PathBuf {
     let trimmed = name.trim();
     let raw_path = Path::new(trimmed);
 
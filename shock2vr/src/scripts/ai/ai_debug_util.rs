@@ -1,18 +1,28 @@
 //! Debug visualization utilities for AI entities
 //!
 //! Provides shared debug drawing functions for visualizing AI state
-//! such as alertness levels, visibility status, and field of view.
+//! such as alertness levels, visibility status, field of view, and
+//! the current behavior with its target and waypoints.
 
 use cgmath::{Deg, InnerSpace, Quaternion, Rotation, Rotation3, Vector3, point3, vec3, vec4};
 use dark::properties::AIAlertLevel;
 use shipyard::{EntityId, Get, UniqueView, View, World};
 
-use crate::mission::DebugOptions;
+use crate::mission::{DebugOptions, PlayerInfo};
 use dark::properties::PropPosition;
 
-use super::alertness::AlertnessState;
+use super::{alertness::AlertnessState, behavior::Behavior};
 use crate::scripts::Effect;
 
+/// Whether debug visuals should be drawn for this AI - debug_ai is on, and the entity
+/// passes the console filter (if any)
+pub fn is_ai_debug_enabled(world: &World, entity_id: EntityId) -> bool {
+    world
+        .borrow::<UniqueView<DebugOptions>>()
+        .map(|debug_options| debug_options.is_ai_debugged(entity_id))
+        .unwrap_or(false)
+}
+
 /// Configuration for debug alertness visualization
 pub struct AlertnessDebugConfig {
     /// Offset from entity position to base of alertness bar
@@ -96,9 +106,7 @@ pub fn draw_debug_alertness(
     is_visible: bool,
     config: &AlertnessDebugConfig,
 ) -> Effect {
-    // Check if debug_ai is enabled
-    let debug_options = world.borrow::<UniqueView<DebugOptions>>().ok();
-    if !debug_options.map(|d| d.debug_ai).unwrap_or(false) {
+    if !is_ai_debug_enabled(world, entity_id) {
         return Effect::NoEffect;
     }
 
@@ -207,9 +215,7 @@ pub fn draw_debug_fov(
     is_visible: bool,
     config: &FovDebugConfig,
 ) -> Effect {
-    // Check if debug_ai is enabled
-    let debug_options = world.borrow::<UniqueView<DebugOptions>>().ok();
-    if !debug_options.map(|d| d.debug_ai).unwrap_or(false) {
+    if !is_ai_debug_enabled(world, entity_id) {
         return Effect::NoEffect;
     }
 
@@ -280,3 +286,80 @@ pub fn draw_debug_fov(
 
     Effect::NoEffect
 }
+
+/// Draw debug visualization showing what an AI's behavior is doing
+///
+/// Draws:
+/// - A label above the entity with the behavior name and alertness level
+/// - A line to the behavior's target entity (magenta)
+/// - The waypoints the behavior is heading through (cyan)
+///
+/// Returns `Effect::NoEffect` if debug_ai is not enabled for the entity or it has no position.
+pub fn draw_debug_behavior(
+    world: &World,
+    entity_id: EntityId,
+    behavior: &dyn Behavior,
+    alertness: &AlertnessState,
+    label_height: f32,
+) -> Effect {
+    if !is_ai_debug_enabled(world, entity_id) {
+        return Effect::NoEffect;
+    }
+
+    let origin = {
+        let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+        match v_pos.get(entity_id) {
+            Ok(pose) => pose.position,
+            Err(_) => return Effect::NoEffect,
+        }
+    };
+    let to_point = |v: Vector3<f32>| point3(v.x, v.y, v.z);
+
+    let mut effects = vec![Effect::DrawDebugText {
+        position: to_point(origin + vec3(0.0, label_height, 0.0)),
+        text: format!("{} - {:?}", behavior.name(), alertness.current_level),
+    }];
+
+    let mut lines = vec![];
+    if let Some(target_pos) = behavior
+        .debug_target(world)
+        .and_then(|target| target_position(world, target))
+    {
+        lines.push((
+            to_point(origin),
+            to_point(target_pos),
+            vec4(1.0, 0.0, 1.0, 1.0),
+        ));
+    }
+
+    let waypoints = behavior.debug_waypoints(world);
+    let mut previous = origin;
+    for waypoint in waypoints {
+        lines.push((
+            to_point(previous),
+            to_point(waypoint),
+            vec4(0.0, 1.0, 1.0, 1.0),
+        ));
+        // Small vertical marker at each waypoint
+        lines.push((
+            to_point(waypoint),
+            to_point(waypoint + vec3(0.0, 0.5, 0.0)),
+            vec4(0.0, 1.0, 1.0, 1.0),
+        ));
+        previous = waypoint;
+    }
+    effects.push(Effect::DrawDebugLines { lines });
+
+    Effect::combine(effects)
+}
+
+// The player's PropPosition isn't kept up to date, so go through PlayerInfo for them
+fn target_position(world: &World, target: EntityId) -> Option<Vector3<f32>> {
+    let player = world.borrow::<UniqueView<PlayerInfo>>().ok()?;
+    if player.entity_id == target {
+        return Some(player.pos);
+    }
+
+    let v_pos = world.borrow::<View<PropPosition>>().ok()?;
+    v_pos.get(target).ok().map(|pose| pose.position)
+}
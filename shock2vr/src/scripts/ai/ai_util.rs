@@ -10,13 +10,16 @@ use rand::{Rng, thread_rng};
 use shipyard::{EntityId, Get, IntoIter, IntoWithId, UniqueView, View, World};
 
 use crate::{
-    creature,
+    creature, factions, light_gem,
     mission::{PlayerInfo, entity_creator::CreateEntityOptions},
     physics::{InternalCollisionGroups, PhysicsWorld},
-    runtime_props::{RuntimePropJointTransforms, RuntimePropTransform},
+    runtime_props::{RuntimePropJointTransforms, RuntimePropTransform, RuntimePropVhots},
     scripts::{Effect, script_util::get_first_link_with_template_and_data},
 };
 
+// Seconds an AI falls back after a volley, per step of cover desire (low, moderate, high)
+const COVER_RETREAT_SECONDS: f32 = 1.0;
+
 ///
 /// random_binomial
 ///
@@ -83,7 +86,7 @@ pub(crate) fn does_entity_have_hitboxes(world: &World, entity_id: EntityId) -> b
 /// Fire Ranged Weapon
 ///
 /// Handles firing a projectile through the AIRangedWeapon link, which is a proxy between the main entity link
-/// Used primarily by turrets
+/// Used primarily by turrets. The first vhot on the entity's model is the gun's muzzle, if it has one.
 ///
 pub fn fire_ranged_weapon(world: &World, entity_id: EntityId, rotation: Quaternion<f32>) -> Effect {
     // First, let's find the link
@@ -111,11 +114,30 @@ pub fn fire_ranged_weapon(world: &World, entity_id: EntityId, rotation: Quaterni
     let up_offset = 0.5 / SCALE_FACTOR;
     let right_offset = 0.5 / SCALE_FACTOR;
     let forward = vec3(right_offset, up_offset, 1.0 * forward_offset);
-    let position =
-        root_transform
-            .0
-            .transform_point(point3(right_offset, up_offset, forward_offset))
-            + forward;
+
+    // The vhot is in the model's rest pose - the gun joint rotates it along with the barrel
+    let v_vhots = world.borrow::<View<RuntimePropVhots>>().unwrap();
+    let maybe_muzzle = v_vhots
+        .get(entity_id)
+        .ok()
+        .and_then(|vhots| vhots.0.first().map(|vhot| vhot.point));
+    drop(v_vhots);
+
+    let (local_muzzle, position) = match maybe_muzzle {
+        Some(muzzle) => (
+            muzzle,
+            root_transform
+                .0
+                .transform_point(rotation.rotate_point(muzzle)),
+        ),
+        None => (
+            point3(0.0, 0.0, 0.0) + forward,
+            root_transform
+                .0
+                .transform_point(point3(right_offset, up_offset, forward_offset))
+                + forward,
+        ),
+    };
 
     if maybe_ranged_weapon_entity_id.is_none() {
         // Let's create the proxy entity...
@@ -157,7 +179,7 @@ pub fn fire_ranged_weapon(world: &World, entity_id: EntityId, rotation: Quaterni
                 // template_id: -1415, // rocket turret
                 // template_id: -1414, // laser turret
                 template_id: projectile_template_id,
-                position: point3(0.0, 0.0, 0.0) + forward,
+                position: local_muzzle,
                 orientation: Quaternion::from_angle_y(Deg(90.0)),
                 root_transform: root_transform.0 * rot_matrix,
                 options: CreateEntityOptions::default(),
@@ -183,7 +205,7 @@ pub fn fire_ranged_weapon(world: &World, entity_id: EntityId, rotation: Quaterni
         if let Some((muzzle_flash_template_id, _muzzle_flash_options)) = maybe_muzzle_flash {
             fire_effects.push(Effect::CreateEntity {
                 template_id: muzzle_flash_template_id,
-                position: point3(0.0, 0.0, 0.0) + forward,
+                position: local_muzzle,
                 orientation: Quaternion::from_angle_y(Deg(90.0)),
                 root_transform: root_transform.0 * rot_matrix,
                 options: CreateEntityOptions::default(),
@@ -284,6 +306,96 @@ pub fn is_killed(entity_id: EntityId, world: &World) -> bool {
     maybe_prop_hit_points.unwrap().hit_points <= 0
 }
 
+/// Engagement distances used to pick between chase, melee and ranged attacks, and how much the
+/// AI wants to break line-of-sight between volleys
+#[derive(Clone, Copy, Debug)]
+pub struct CombatRanges {
+    pub melee_distance: f32,
+    pub ranged_min_distance: f32,
+    pub ranged_max_distance: f32,
+    pub cover_desire: AICoverDesire,
+}
+
+impl Default for CombatRanges {
+    fn default() -> Self {
+        CombatRanges {
+            melee_distance: 8.0 / SCALE_FACTOR,
+            ranged_min_distance: 15.0 / SCALE_FACTOR,
+            ranged_max_distance: 40.0 / SCALE_FACTOR,
+            cover_desire: AICoverDesire::None,
+        }
+    }
+}
+
+impl CombatRanges {
+    ///
+    /// cover_retreat_duration
+    ///
+    /// How long (in seconds) the AI falls back out of sight after a volley - None if it has no
+    /// desire for cover, and goes straight back to chasing
+    pub fn cover_retreat_duration(&self) -> Option<f32> {
+        match self.cover_desire {
+            AICoverDesire::None => None,
+            desire => Some(desire as u32 as f32 * COVER_RETREAT_SECONDS),
+        }
+    }
+}
+
+///
+/// get_combat_ranges
+///
+/// Reads the engagement distances and cover desire from the AI's ranged combat property,
+/// falling back to the hardcoded defaults for creatures that don't specify them.
+pub fn get_combat_ranges(world: &World, entity_id: EntityId) -> CombatRanges {
+    let defaults = CombatRanges::default();
+    let v_ranged_combat = world.borrow::<View<PropAIRangedCombat>>().unwrap();
+
+    match v_ranged_combat.get(entity_id) {
+        Ok(ranged_combat) if ranged_combat.max_distance > ranged_combat.min_distance => {
+            CombatRanges {
+                melee_distance: defaults.melee_distance.min(ranged_combat.min_distance),
+                ranged_min_distance: ranged_combat.min_distance,
+                ranged_max_distance: ranged_combat.max_distance,
+                cover_desire: ranged_combat.cover_desire,
+            }
+        }
+        Ok(ranged_combat) => CombatRanges {
+            cover_desire: ranged_combat.cover_desire,
+            ..defaults
+        },
+        _ => defaults,
+    }
+}
+
+///
+/// should_flee
+///
+/// Returns the flee duration (in seconds) if the entity's hit points have dropped
+/// below the threshold in its flee conditions property.
+pub fn should_flee(world: &World, entity_id: EntityId) -> Option<f32> {
+    let v_flee_conditions = world.borrow::<View<PropAIFleeConditions>>().unwrap();
+    let v_hit_points = world.borrow::<View<PropHitPoints>>().unwrap();
+    let v_max_hit_points = world.borrow::<View<PropMaxHitPoints>>().unwrap();
+
+    let flee_conditions = v_flee_conditions.get(entity_id).ok()?;
+    if !flee_conditions.is_enabled() {
+        return None;
+    }
+
+    let hit_points = v_hit_points.get(entity_id).ok()?.hit_points;
+    let max_hit_points = v_max_hit_points.get(entity_id).ok()?.hit_points;
+    if max_hit_points == 0 || hit_points <= 0 {
+        return None;
+    }
+
+    let percent = (hit_points as f32 / max_hit_points as f32) * 100.0;
+    if percent < flee_conditions.hit_point_percent as f32 {
+        Some(flee_conditions.duration.max(1.0))
+    } else {
+        None
+    }
+}
+
 /// Check if an entity has a ranged weapon capability
 ///
 /// Returns true if the entity has either an AIRangedWeapon link (used by turrets)
@@ -328,6 +440,8 @@ pub fn play_positional_sound(
         .map(|p| p.class_tags())
         .unwrap_or(vec![]);
 
+    // Sounds at the producer's position move with it - overridden positions stay put
+    let follow_entity = override_position.is_none().then_some(producing_entity);
     let pos = match override_position {
         None => {
             let v_pos = world.borrow::<View<PropPosition>>().unwrap();
@@ -342,6 +456,7 @@ pub fn play_positional_sound(
         audio_handle: AudioHandle::new(),
         query: EnvSoundQuery::from_tag_values(query),
         position: pos,
+        entity_id: follow_entity,
     }
 }
 
@@ -350,12 +465,22 @@ pub fn play_positional_sound(
 /// This is a basic visibility check that only verifies line-of-sight.
 /// For FOV-aware visibility, use `is_player_visible_in_fov`.
 pub fn is_player_visible(from_entity: EntityId, world: &World, physics: &PhysicsWorld) -> bool {
-    let u_player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+    let player_pos = world.borrow::<UniqueView<PlayerInfo>>().unwrap().pos;
+    has_line_of_sight(from_entity, world, physics, player_pos)
+}
+
+/// Check if there's nothing but air between an entity and a point (raycast only, no FOV check)
+pub fn has_line_of_sight(
+    from_entity: EntityId,
+    world: &World,
+    physics: &PhysicsWorld,
+    target: Vector3<f32>,
+) -> bool {
     let v_current_pos = world.borrow::<View<PropPosition>>().unwrap();
 
     if let Ok(ent_pos) = v_current_pos.get(from_entity) {
         let start_point = point3(0.0, 0.0, 0.0) + ent_pos.position;
-        let end_point = point3(0.0, 0.0, 0.0) + u_player.pos;
+        let end_point = point3(0.0, 0.0, 0.0) + target;
         let direction = (end_point - start_point).normalize();
         let distance = (end_point - start_point).magnitude();
         let result = physics.ray_cast2(
@@ -367,7 +492,7 @@ pub fn is_player_visible(from_entity: EntityId, world: &World, physics: &Physics
             true,
         );
 
-        // If we didn't hit anything - player visible!
+        // If we didn't hit anything - target visible!
         // Currently, the ray cast doesn't intersect player...
         // TODO: Check for entities, but pass-through transparent ones (ie, glass/windows)
         return result.is_none();
@@ -379,7 +504,8 @@ pub fn is_player_visible(from_entity: EntityId, world: &World, physics: &Physics
 /// Check if the player is visible from an entity within a field of view
 ///
 /// This combines a raycast check with an FOV cone check based on the entity's
-/// current heading and FOV half-angle.
+/// current heading and FOV half-angle. The player is only seen within their sight
+/// distance - the darker it is around them, the closer the entity has to be.
 ///
 /// # Arguments
 /// * `from_entity` - The entity doing the looking
@@ -403,6 +529,8 @@ pub fn is_player_visible(from_entity: EntityId, world: &World, physics: &Physics
 /// - **Turrets**: Pass `-current_heading` - similar to cameras but with negated heading
 ///   due to how the turret joint rotation is calculated.
 ///
+/// Entities that aren't hostile to the player (see `factions`) don't notice them at all.
+///
 /// # Returns
 /// `true` if the player is within the FOV cone AND there's line-of-sight
 pub fn is_player_visible_in_fov(
@@ -412,6 +540,10 @@ pub fn is_player_visible_in_fov(
     heading: Deg<f32>,
     fov_half_angle: f32,
 ) -> bool {
+    if !factions::is_hostile_to_player(world, from_entity) {
+        return false;
+    }
+
     let u_player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
     let v_current_pos = world.borrow::<View<PropPosition>>().unwrap();
 
@@ -421,6 +553,10 @@ pub fn is_player_visible_in_fov(
 
         // Calculate direction to player
         let to_player = player_pos - entity_pos;
+        if to_player.magnitude() > light_gem::get_player_sight_distance(world) {
+            return false;
+        }
+
         let to_player_2d = Vector3::new(to_player.x, 0.0, to_player.z);
 
         if to_player_2d.magnitude2() < 1e-6 {
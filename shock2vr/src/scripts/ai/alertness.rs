@@ -156,6 +156,28 @@ pub fn process_alertness_update(
     }
 }
 
+/// Raise alertness level on hearing a noise.
+///
+/// Returns `Some((old_level, new_level))` if the noise raised the level. A noise never lowers it, and
+/// restarts the decay timer - the AI stays on edge while it keeps hearing things.
+pub fn hear_noise(
+    state: &mut AlertnessState,
+    heard_level: AIAlertLevel,
+    alert_cap: &PropAIAlertCap,
+) -> Option<(AIAlertLevel, AIAlertLevel)> {
+    state.hidden_time = 0.0;
+
+    if level_to_u32(heard_level) <= level_to_u32(state.current_level) {
+        return None;
+    }
+
+    let old_level = state.current_level;
+    if set_level(state, heard_level, alert_cap) {
+        return Some((old_level, state.current_level));
+    }
+    None
+}
+
 /// Attempt to escalate alertness level based on visibility time.
 fn try_escalate(
     state: &mut AlertnessState,
@@ -480,6 +502,23 @@ mod tests {
         assert_eq!(state.visible_time, 0.1);
     }
 
+    #[test]
+    fn test_hear_noise() {
+        let mut state = AlertnessState::new(AIAlertLevel::Lowest);
+        let cap = default_cap();
+
+        // A loud noise jumps straight to the heard level
+        let result = hear_noise(&mut state, AIAlertLevel::Moderate, &cap);
+        assert_eq!(result, Some((AIAlertLevel::Lowest, AIAlertLevel::Moderate)));
+
+        // A quieter one doesn't lower it, but does hold off decay
+        state.hidden_time = 0.5;
+        let result = hear_noise(&mut state, AIAlertLevel::Low, &cap);
+        assert_eq!(result, None);
+        assert_eq!(state.current_level, AIAlertLevel::Moderate);
+        assert_eq!(state.hidden_time, 0.0);
+    }
+
     #[test]
     fn test_from_aware_delay() {
         let delay = PropAIAwareDelay {
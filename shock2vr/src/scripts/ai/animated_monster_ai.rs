@@ -3,16 +3,19 @@ use std::{cell::RefCell, collections::HashSet};
 use cgmath::{Deg, MetricSpace, Quaternion, Rotation3, vec3, vec4};
 use dark::{
     SCALE_FACTOR,
-    motion::{MotionFlags, MotionQueryItem},
+    motion::{AnimationMarker, MotionFlags, MotionQueryItem},
     properties::{
-        AIAlertLevel, Link, PropAIAlertCap, PropAIAwareDelay, PropAISignalResponse, PropPosition,
+        AIAlertLevel, Link, PropAIAlertCap, PropAIAwareDelay, PropAIHearing, PropAISignalResponse,
+        PropPosition,
     },
 };
 use rand;
-use shipyard::{EntityId, Get, View, World};
+use shipyard::{EntityId, Get, UniqueView, View, World};
 
 use crate::{
+    factions,
     mission::PlayerInfo,
+    noise::NoiseEvents,
     physics::{InternalCollisionGroups, PhysicsWorld},
     scripts::script_util,
     time::Time,
@@ -24,17 +27,24 @@ use super::{
     ai_util::*,
     alertness::{self, AlertnessState, AlertnessTimings},
     behavior::*,
+    robot_profile::{self, RobotProfile},
     steering::{Steering, SteeringOutput},
 };
 // Default timing constants for monsters (in seconds)
 const DEFAULT_ESCALATE_SECONDS: f32 = 1.5;
 const DEFAULT_DECAY_SECONDS: f32 = 3.0;
+// Debug label sits just above the alertness bar (AlertnessDebugConfig::monster())
+const MONSTER_DEBUG_LABEL_HEIGHT: f32 = 2.75;
+// Monster FOV is 60 degrees half-angle (matches FovDebugConfig::monster())
+const MONSTER_FOV_HALF_ANGLE: f32 = 60.0;
 
 /// Configuration for monster alertness behavior
 #[derive(Clone)]
 struct MonsterConfig {
     alert_cap: PropAIAlertCap,
     timings: AlertnessTimings,
+    /// How far noises carry for this monster, relative to average hearing
+    hearing: f32,
 }
 
 pub struct AnimatedMonsterAI {
@@ -43,6 +53,8 @@ pub struct AnimatedMonsterAI {
     current_heading: Deg<f32>,
     is_dead: bool,
     took_damage: bool,
+    /// Whether the AI has already fled once (from P$AI_FleeCo) - only flee once per life
+    has_fled: bool,
     animation_seq: u32,
     locomotion_seq: u32,
 
@@ -52,6 +64,11 @@ pub struct AnimatedMonsterAI {
     alertness: AlertnessState,
     /// Alertness configuration (loaded from entity properties)
     config: Option<MonsterConfig>,
+
+    /// Archetype-specific parameters, for robots
+    robot: Option<RobotProfile>,
+    /// Seconds until a maintenance bot can repair again
+    time_to_repair: f32,
 }
 
 impl AnimatedMonsterAI {
@@ -59,6 +76,7 @@ impl AnimatedMonsterAI {
         AnimatedMonsterAI {
             is_dead: false,
             took_damage: false,
+            has_fled: false,
             current_behavior: Box::new(RefCell::new(IdleBehavior)),
             current_heading: Deg(0.0),
             animation_seq: 0,
@@ -67,6 +85,8 @@ impl AnimatedMonsterAI {
             played_ai_watch_obj: HashSet::new(),
             alertness: AlertnessState::default(),
             config: None,
+            robot: None,
+            time_to_repair: 0.0,
         }
     }
 
@@ -74,6 +94,7 @@ impl AnimatedMonsterAI {
         AnimatedMonsterAI {
             is_dead: false,
             took_damage: false,
+            has_fled: false,
             // Start with IdleBehavior - alertness will drive behavior changes
             current_behavior: Box::new(RefCell::new(IdleBehavior)),
             current_heading: Deg(0.0),
@@ -83,6 +104,8 @@ impl AnimatedMonsterAI {
             played_ai_watch_obj: HashSet::new(),
             alertness: AlertnessState::default(),
             config: None,
+            robot: None,
+            time_to_repair: 0.0,
         }
     }
 
@@ -117,7 +140,34 @@ impl AnimatedMonsterAI {
 
         let timings = AlertnessTimings::from_aware_delay(&aware_delay);
 
-        Some(MonsterConfig { alert_cap, timings })
+        let hearing = world
+            .borrow::<View<PropAIHearing>>()
+            .ok()
+            .and_then(|v_hearing| {
+                v_hearing
+                    .get(entity_id)
+                    .ok()
+                    .map(|hearing| hearing.0.scale())
+            })
+            .unwrap_or(1.0);
+
+        Some(MonsterConfig {
+            alert_cap,
+            timings,
+            hearing,
+        })
+    }
+
+    /// The alert level the loudest noise the monster heard this update should raise it to, if any
+    fn hear_noises(world: &World, entity_id: EntityId, hearing: f32) -> Option<AIAlertLevel> {
+        // Hearing something sends a monster looking for the player - unless it isn't hostile to them
+        if !factions::is_hostile_to_player(world, entity_id) {
+            return None;
+        }
+        let v_position = world.borrow::<View<PropPosition>>().ok()?;
+        let position = v_position.get(entity_id).ok()?.position;
+        let noise_events = world.borrow::<UniqueView<NoiseEvents>>().ok()?;
+        noise_events.alert_level_heard(position, hearing)
     }
 
     /// Get the appropriate behavior for the current alertness level
@@ -148,7 +198,12 @@ impl AnimatedMonsterAI {
         time: &Time,
         entity_id: EntityId,
     ) -> Effect {
-        let turn_velocity = self.current_behavior.borrow().turn_speed().0;
+        let behavior_turn_speed = self.current_behavior.borrow().turn_speed();
+        let turn_velocity = self
+            .robot
+            .map(|robot| robot.turn_speed(behavior_turn_speed))
+            .unwrap_or(behavior_turn_speed)
+            .0;
         let delta =
             clamp_to_minimal_delta_angle(steering_output.desired_heading - self.current_heading);
 
@@ -235,17 +290,67 @@ impl AnimatedMonsterAI {
 
         self.last_hit_sensor = maybe_hit_sensor;
 
-        let debug_effect = Effect::DrawDebugLines {
-            lines: vec![(
-                position,
-                position + ((forward + down_vector) * distance),
-                color,
-            )],
+        let debug_effect = if ai_debug_util::is_ai_debug_enabled(world, entity_id) {
+            Effect::DrawDebugLines {
+                lines: vec![(
+                    position,
+                    position + ((forward + down_vector) * distance),
+                    color,
+                )],
+            }
+        } else {
+            Effect::NoEffect
         };
 
         Effect::combine(vec![sensor_effect, debug_effect])
     }
 
+    ///
+    /// update_robot
+    ///
+    /// Archetype-specific robot behavior, on top of what every monster does
+    fn update_robot(&mut self, entity_id: EntityId, world: &World, delta: f32) -> Effect {
+        let Some(robot) = self.robot else {
+            return Effect::NoEffect;
+        };
+        if self.is_dead || is_killed(entity_id, world) {
+            return Effect::NoEffect;
+        }
+
+        // Protocol droids walk right up to the player before blowing themselves up
+        if let Some(explosion) = robot.explode_on_approach {
+            let is_alerted = matches!(
+                self.alertness.current_level,
+                AIAlertLevel::Moderate | AIAlertLevel::High
+            );
+            if is_alerted
+                && robot_profile::is_player_within(world, entity_id, explosion.radius / 2.0)
+            {
+                self.is_dead = true;
+                self.current_behavior = Box::new(RefCell::new(DeadBehavior {}));
+                return Effect::combine(vec![
+                    robot_profile::explode(world, entity_id, &explosion),
+                    Effect::SlayEntity { entity_id },
+                ]);
+            }
+        }
+
+        // Maintenance bots only repair when there's no fight going on
+        if let Some(repair) = robot.repair {
+            self.time_to_repair -= delta;
+            let is_relaxed = matches!(
+                self.alertness.current_level,
+                AIAlertLevel::Lowest | AIAlertLevel::Low
+            );
+            if is_relaxed && self.time_to_repair <= 0.0 {
+                self.time_to_repair = repair.interval;
+                return robot_profile::repair_nearby_robot(world, entity_id, &repair);
+            }
+        }
+
+        Effect::NoEffect
+    }
+
     fn next_selection(
         &mut self,
         is_locomotion: bool,
@@ -268,6 +373,7 @@ impl Script for AnimatedMonsterAI {
 
         // Load alertness configuration from entity properties
         self.config = Self::build_config(world, entity_id);
+        self.robot = RobotProfile::for_entity(world, entity_id);
 
         // Initialize alertness state
         let alertness_effect = if let Some(config) = &self.config {
@@ -297,22 +403,39 @@ impl Script for AnimatedMonsterAI {
     ) -> Effect {
         let delta = time.elapsed.as_secs_f32();
 
-        // Monster FOV is 60 degrees half-angle (matches FovDebugConfig::monster())
         // Monster rotation is set directly via Effect::SetRotation, so pose.rotation
         // already contains the heading. Pass Deg(0.0) to avoid applying it twice.
-        const MONSTER_FOV_HALF_ANGLE: f32 = 60.0;
+        let fov_half_angle = self
+            .robot
+            .map(|robot| robot.fov_half_angle)
+            .unwrap_or(MONSTER_FOV_HALF_ANGLE);
         let is_visible =
-            is_player_visible_in_fov(entity_id, world, physics, Deg(0.0), MONSTER_FOV_HALF_ANGLE);
+            is_player_visible_in_fov(entity_id, world, physics, Deg(0.0), fov_half_angle);
+
+        let was_dead = self.is_dead;
+        let robot_effect = self.update_robot(entity_id, world, delta);
+        if !was_dead && self.is_dead {
+            // Blew itself up
+            return robot_effect;
+        }
 
-        // Update alertness state
+        // Update alertness state - from what the monster sees, and what it hears
         let (alertness_effect, behavior_change_effect) = if let Some(config) = &self.config {
-            if let Some((_old_level, _new_level)) = alertness::process_alertness_update(
+            let heard_level = Self::hear_noises(world, entity_id, config.hearing);
+            let level_change = alertness::process_alertness_update(
                 &mut self.alertness,
                 is_visible,
                 delta,
                 &config.timings,
                 &config.alert_cap,
-            ) {
+            )
+            .or_else(|| {
+                heard_level.and_then(|level| {
+                    alertness::hear_noise(&mut self.alertness, level, &config.alert_cap)
+                })
+            });
+
+            if let Some((_old_level, _new_level)) = level_change {
                 // Level changed - sync to ECS and potentially change behavior
                 let sync_effect = alertness::sync_alertness_effect(entity_id, &self.alertness);
 
@@ -399,7 +522,17 @@ impl Script for AnimatedMonsterAI {
             &FovDebugConfig::monster(),
         );
 
+        // Debug visualization - behavior, target and waypoints
+        let behavior_debug_effect = ai_debug_util::draw_debug_behavior(
+            world,
+            entity_id,
+            &*self.current_behavior.borrow(),
+            &self.alertness,
+            MONSTER_DEBUG_LABEL_HEIGHT,
+        );
+
         Effect::combine(vec![
+            robot_effect,
             alertness_effect,
             behavior_change_effect,
             steering_effects,
@@ -407,6 +540,7 @@ impl Script for AnimatedMonsterAI {
             sensor_effect,
             alertness_debug_effect,
             fov_debug_effect,
+            behavior_debug_effect,
         ])
     }
 
@@ -423,12 +557,13 @@ impl Script for AnimatedMonsterAI {
                 .handle_message(entity_id, world, physics, msg);
         }
         match msg {
-            MessagePayload::Damage { amount } => {
+            MessagePayload::Damage { amount, source } => {
                 // TODO: Let behavior handle this?
                 self.took_damage = true;
                 Effect::AdjustHitPoints {
                     entity_id,
                     delta: -(amount.round() as i32),
+                    source: *source,
                 }
             }
             MessagePayload::TurnOn { from: _ } => {
@@ -477,8 +612,17 @@ impl Script for AnimatedMonsterAI {
                 if self.is_dead {
                     Effect::NoEffect
                 } else if is_killed(entity_id, world) {
+                    let was_dead = self.current_behavior.borrow().name() == "dead";
                     self.current_behavior = Box::new(RefCell::new(DeadBehavior {}));
 
+                    let explosion_effect = match self.robot.and_then(|robot| robot.explode_on_death)
+                    {
+                        Some(explosion) if !was_dead => {
+                            robot_profile::explode(world, entity_id, &explosion)
+                        }
+                        _ => Effect::NoEffect,
+                    };
+
                     // Play death sound effect immediately
                     let death_sound_effect = if let Some(voice_index) =
                         crate::scripts::speech_util::resolve_entity_voice_index(world, entity_id)
@@ -506,7 +650,7 @@ impl Script for AnimatedMonsterAI {
                         selection_strategy: dark::motion::MotionQuerySelectionStrategy::Random,
                     };
 
-                    Effect::combine(vec![death_sound_effect, death_animation])
+                    Effect::combine(vec![death_sound_effect, death_animation, explosion_effect])
                 } else if self.took_damage {
                     self.took_damage = false;
                     Effect::QueueAnimationBySchema {
@@ -515,7 +659,16 @@ impl Script for AnimatedMonsterAI {
                         selection_strategy: dark::motion::MotionQuerySelectionStrategy::Random,
                     }
                 } else {
-                    let next_behavior = {
+                    let flee_duration = if self.has_fled {
+                        None
+                    } else {
+                        should_flee(world, entity_id)
+                    };
+
+                    let next_behavior = if let Some(duration) = flee_duration {
+                        self.has_fled = true;
+                        NextBehavior::Next(Box::new(RefCell::new(FleeBehavior::new(duration))))
+                    } else {
                         self.current_behavior
                             .borrow_mut()
                             .next_behavior(world, physics, entity_id)
@@ -615,6 +768,10 @@ impl Script for AnimatedMonsterAI {
                     Effect::NoEffect
                 }
             }
+            // Marked-up hit point, for attacks whose motion doesn't carry a FIRE flag
+            MessagePayload::AnimationMarker {
+                marker: AnimationMarker::AttackHit,
+            } => fire_ranged_projectile(world, entity_id),
             _ => Effect::NoEffect,
         }
     }
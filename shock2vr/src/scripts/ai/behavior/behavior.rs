@@ -6,6 +6,7 @@ use rand::Rng;
 use shipyard::*;
 
 use crate::{
+    mission::PlayerInfo,
     physics::PhysicsWorld,
     scripts::{
         Effect, MessagePayload,
@@ -23,6 +24,28 @@ pub enum NextBehavior {
 }
 
 pub trait Behavior {
+    ///
+    /// name
+    ///
+    /// Short name shown by the AI debug visuals
+    fn name(&self) -> &'static str;
+
+    ///
+    /// debug_target
+    ///
+    /// Entity the behavior is going after, if any - only used for debug visuals
+    fn debug_target(&self, _world: &World) -> Option<EntityId> {
+        None
+    }
+
+    ///
+    /// debug_waypoints
+    ///
+    /// Positions the behavior is heading through, in order - only used for debug visuals
+    fn debug_waypoints(&self, _world: &World) -> Vec<Vector3<f32>> {
+        vec![]
+    }
+
     fn animation(&self) -> Vec<MotionQueryItem> {
         vec![]
     }
@@ -70,6 +93,17 @@ pub trait Behavior {
     }
 }
 
+///
+/// player_entity_id
+///
+/// Debug target for the behaviors that go after the player
+pub fn player_entity_id(world: &World) -> Option<EntityId> {
+    world
+        .borrow::<UniqueView<PlayerInfo>>()
+        .ok()
+        .map(|player| player.entity_id)
+}
+
 #[allow(dead_code)]
 pub fn random_behavior() -> Box<RefCell<dyn Behavior>> {
     let mut potential_behaviors: Vec<Box<RefCell<dyn Behavior>>> = vec![
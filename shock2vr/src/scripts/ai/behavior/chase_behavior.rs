@@ -1,7 +1,7 @@
 use std::cell::RefCell;
 
 use cgmath::{Deg, InnerSpace};
-use dark::{SCALE_FACTOR, motion::MotionQueryItem, properties::PropPosition};
+use dark::{motion::MotionQueryItem, properties::PropPosition};
 use rand::Rng;
 use shipyard::*;
 
@@ -10,6 +10,7 @@ use crate::{
     physics::PhysicsWorld,
     scripts::{
         Effect,
+        ai::ai_util,
         ai::steering::{
             self, ChasePlayerSteeringStrategy, CollisionAvoidanceSteeringStrategy, SteeringOutput,
             SteeringStrategy,
@@ -18,7 +19,7 @@ use crate::{
     time::Time,
 };
 
-use super::{Behavior, MeleeAttackBehavior, NextBehavior, RangedAttackBehavior};
+use super::{Behavior, MeleeAttackBehavior, NextBehavior, RangedAttackBehavior, player_entity_id};
 
 pub struct ChaseBehavior {
     steering_strategy: Box<dyn SteeringStrategy>,
@@ -38,6 +39,14 @@ impl ChaseBehavior {
 }
 
 impl Behavior for ChaseBehavior {
+    fn name(&self) -> &'static str {
+        "chase"
+    }
+
+    fn debug_target(&self, world: &World) -> Option<EntityId> {
+        player_entity_id(world)
+    }
+
     fn turn_speed(&self) -> Deg<f32> {
         Deg(360.0)
     }
@@ -77,9 +86,10 @@ impl Behavior for ChaseBehavior {
         let v_current_pos = world.borrow::<View<PropPosition>>().unwrap();
         //let v_transform = world.borrow::<View<RuntimePropTransform>>().unwrap();
 
-        let melee_attack_distance = 8.0 / SCALE_FACTOR;
-        let ranged_max_attack_distance = 40.0 / SCALE_FACTOR;
-        let ranged_min_attack_distance = 15.0 / SCALE_FACTOR;
+        let ranges = ai_util::get_combat_ranges(world, entity_id);
+        let melee_attack_distance = ranges.melee_distance;
+        let ranged_max_attack_distance = ranges.ranged_max_distance;
+        let ranged_min_attack_distance = ranges.ranged_min_distance;
 
         if let Ok(prop_pos) = v_current_pos.get(entity_id) {
             let distance = (prop_pos.position - u_player.pos).magnitude();
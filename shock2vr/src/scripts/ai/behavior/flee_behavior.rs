@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+
+use cgmath::Deg;
+use dark::motion::MotionQueryItem;
+use shipyard::{EntityId, World};
+
+use crate::{
+    physics::PhysicsWorld,
+    scripts::{
+        Effect,
+        ai::steering::{
+            self, CollisionAvoidanceSteeringStrategy, FleePlayerSteeringStrategy, SteeringOutput,
+            SteeringStrategy,
+        },
+    },
+    time::Time,
+};
+
+use super::{Behavior, ChaseBehavior, NextBehavior, player_entity_id};
+
+///
+/// FleeBehavior
+///
+/// Runs away from the player for a fixed duration (from P$AI_FleeCo),
+/// then goes back to chasing.
+pub struct FleeBehavior {
+    steering_strategy: Box<dyn SteeringStrategy>,
+    remaining_time: f32,
+}
+
+impl FleeBehavior {
+    pub fn new(duration: f32) -> FleeBehavior {
+        FleeBehavior {
+            steering_strategy: steering::chained(vec![
+                Box::new(CollisionAvoidanceSteeringStrategy::comprehensive()),
+                Box::new(FleePlayerSteeringStrategy),
+            ]),
+            remaining_time: duration,
+        }
+    }
+}
+
+impl Behavior for FleeBehavior {
+    fn name(&self) -> &'static str {
+        "flee"
+    }
+
+    fn debug_target(&self, world: &World) -> Option<EntityId> {
+        player_entity_id(world)
+    }
+
+    fn turn_speed(&self) -> Deg<f32> {
+        Deg(360.0)
+    }
+
+    fn steer(
+        &mut self,
+        current_heading: Deg<f32>,
+        world: &World,
+        physics: &PhysicsWorld,
+        entity_id: EntityId,
+        time: &Time,
+    ) -> Option<(SteeringOutput, Effect)> {
+        self.remaining_time -= time.elapsed.as_secs_f32();
+        self.steering_strategy
+            .steer(current_heading, world, physics, entity_id, time)
+    }
+
+    fn animation(&self) -> Vec<MotionQueryItem> {
+        vec![
+            MotionQueryItem::new("locomote"),
+            MotionQueryItem::new("locourgent").optional(),
+        ]
+    }
+
+    fn is_locomotion(&self) -> bool {
+        true
+    }
+
+    fn next_behavior(
+        &mut self,
+        _world: &World,
+        _physics: &PhysicsWorld,
+        _entity_id: EntityId,
+    ) -> NextBehavior {
+        if self.remaining_time <= 0.0 {
+            NextBehavior::Next(Box::new(RefCell::new(ChaseBehavior::new())))
+        } else {
+            NextBehavior::Stay
+        }
+    }
+}
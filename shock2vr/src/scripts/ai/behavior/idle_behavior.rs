@@ -5,6 +5,10 @@ use super::Behavior;
 pub struct IdleBehavior;
 
 impl Behavior for IdleBehavior {
+    fn name(&self) -> &'static str {
+        "idle"
+    }
+
     fn animation(self: &IdleBehavior) -> Vec<MotionQueryItem> {
         vec![MotionQueryItem::new("idlegesture")]
         //vec![MotionQueryItem::new("stand")]
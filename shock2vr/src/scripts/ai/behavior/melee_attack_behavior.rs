@@ -15,11 +15,19 @@ use crate::{
     time::Time,
 };
 
-use super::{Behavior, ChaseBehavior, NextBehavior};
+use super::{Behavior, ChaseBehavior, NextBehavior, player_entity_id};
 
 pub struct MeleeAttackBehavior;
 
 impl Behavior for MeleeAttackBehavior {
+    fn name(&self) -> &'static str {
+        "melee attack"
+    }
+
+    fn debug_target(&self, world: &World) -> Option<EntityId> {
+        player_entity_id(world)
+    }
+
     fn animation(self: &MeleeAttackBehavior) -> Vec<MotionQueryItem> {
         vec![
             MotionQueryItem::new("meleecombat"),
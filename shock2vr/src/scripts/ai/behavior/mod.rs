@@ -1,6 +1,7 @@
 mod behavior;
 mod chase_behavior;
 mod dead_behavior;
+mod flee_behavior;
 mod idle_behavior;
 mod melee_attack_behavior;
 mod noop_behavior;
@@ -12,6 +13,7 @@ mod wander_behavior;
 pub use behavior::*;
 pub use chase_behavior::*;
 pub use dead_behavior::*;
+pub use flee_behavior::*;
 pub use idle_behavior::*;
 pub use melee_attack_behavior::*;
 pub use ranged_attack_behavior::*;
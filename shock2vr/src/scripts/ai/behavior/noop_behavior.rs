@@ -3,4 +3,8 @@ use super::Behavior;
 #[allow(dead_code)]
 pub struct NoopBehavior;
 
-impl Behavior for NoopBehavior {}
+impl Behavior for NoopBehavior {
+    fn name(&self) -> &'static str {
+        "noop"
+    }
+}
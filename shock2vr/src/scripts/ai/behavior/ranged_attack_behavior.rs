@@ -9,16 +9,27 @@ use crate::{
     physics::PhysicsWorld,
     scripts::{
         Effect,
-        ai::steering::{ChasePlayerSteeringStrategy, SteeringOutput, SteeringStrategy},
+        ai::{
+            ai_util,
+            steering::{ChasePlayerSteeringStrategy, SteeringOutput, SteeringStrategy},
+        },
     },
     time::Time,
 };
 
-use super::{Behavior, ChaseBehavior, NextBehavior};
+use super::{Behavior, ChaseBehavior, FleeBehavior, NextBehavior, player_entity_id};
 
 pub struct RangedAttackBehavior;
 
 impl Behavior for RangedAttackBehavior {
+    fn name(&self) -> &'static str {
+        "ranged attack"
+    }
+
+    fn debug_target(&self, world: &World) -> Option<EntityId> {
+        player_entity_id(world)
+    }
+
     fn animation(self: &RangedAttackBehavior) -> Vec<MotionQueryItem> {
         vec![
             MotionQueryItem::new("rangedcombat").optional(),
@@ -40,10 +51,16 @@ impl Behavior for RangedAttackBehavior {
 
     fn next_behavior(
         &mut self,
-        _world: &World,
+        world: &World,
         _physics: &PhysicsWorld,
-        _entity_id: EntityId,
+        entity_id: EntityId,
     ) -> NextBehavior {
-        NextBehavior::Next(Box::new(RefCell::new(ChaseBehavior::new())))
+        // AIs that want cover break line-of-sight after a volley, before closing in again
+        match ai_util::get_combat_ranges(world, entity_id).cover_retreat_duration() {
+            Some(duration) => {
+                NextBehavior::Next(Box::new(RefCell::new(FleeBehavior::new(duration))))
+            }
+            None => NextBehavior::Next(Box::new(RefCell::new(ChaseBehavior::new()))),
+        }
     }
 }
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, time::Duration};
 
-use cgmath::{Deg, InnerSpace, vec3};
+use cgmath::{Deg, InnerSpace, Vector3, vec3};
 use dark::{
     SCALE_FACTOR,
     motion::MotionQueryItem,
@@ -48,6 +48,30 @@ impl ScriptedSequenceBehavior {
 }
 
 impl Behavior for ScriptedSequenceBehavior {
+    fn name(&self) -> &'static str {
+        "scripted sequence"
+    }
+
+    fn debug_target(&self, _world: &World) -> Option<EntityId> {
+        self.current_scripted_action.borrow().target()
+    }
+
+    fn debug_waypoints(&self, world: &World) -> Vec<Vector3<f32>> {
+        let v_prop_pos = world.borrow::<View<PropPosition>>().unwrap();
+        self.actions
+            .iter()
+            .skip(self.current_action_idx.max(0) as usize)
+            .filter_map(|action| match &action.action_type {
+                AIScriptedActionType::Goto { waypoint_name, .. } => {
+                    script_util::get_first_entity_by_name(world, waypoint_name)
+                }
+                _ => None,
+            })
+            .filter_map(|waypoint_id| v_prop_pos.get(waypoint_id).ok())
+            .map(|waypoint_pos| waypoint_pos.position)
+            .collect()
+    }
+
     fn animation(&self) -> Vec<MotionQueryItem> {
         self.current_scripted_action.borrow().animation()
     }
@@ -171,6 +195,10 @@ trait ScriptedAction {
         true
     }
 
+    fn target(&self) -> Option<EntityId> {
+        None
+    }
+
     fn update(
         &mut self,
         current_heading: Deg<f32>,
@@ -324,6 +352,9 @@ impl GotoScriptedAction {
 }
 
 impl ScriptedAction for GotoScriptedAction {
+    fn target(&self) -> Option<EntityId> {
+        self.target_id
+    }
     fn turn_speed(&self) -> Deg<f32> {
         Deg(540.0)
     }
@@ -389,6 +420,9 @@ impl FaceScriptedAction {
 }
 
 impl ScriptedAction for FaceScriptedAction {
+    fn target(&self) -> Option<EntityId> {
+        self.target_id
+    }
     fn turn_speed(&self) -> Deg<f32> {
         Deg(180.0)
     }
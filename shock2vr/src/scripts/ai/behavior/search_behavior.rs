@@ -6,6 +6,10 @@ use super::Behavior;
 pub struct SearchBehavior;
 
 impl Behavior for SearchBehavior {
+    fn name(&self) -> &'static str {
+        "search"
+    }
+
     fn animation(self: &SearchBehavior) -> Vec<MotionQueryItem> {
         vec![
             MotionQueryItem::new("search"),
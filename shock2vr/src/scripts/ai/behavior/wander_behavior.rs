@@ -26,6 +26,10 @@ impl WanderBehavior {
 }
 
 impl Behavior for WanderBehavior {
+    fn name(&self) -> &'static str {
+        "wander"
+    }
+
     fn steer(
         &mut self,
         current_heading: Deg<f32>,
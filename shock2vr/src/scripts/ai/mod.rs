@@ -1,6 +1,7 @@
 pub mod ai_debug_util;
 pub mod ai_util;
 pub mod alertness;
+pub mod robot_profile;
 pub mod steering;
 
 mod animated_monster_ai;
@@ -0,0 +1,248 @@
+// Robot profiles - the parameters that set the robotic AI archetypes apart from the other monsters:
+// protocol droids that walk up to the player and blow themselves up, maintenance bots that patch
+// up damaged robots nearby, and assault droids that go out with a bang.
+
+use cgmath::{Deg, InnerSpace, vec3, vec4};
+use dark::{
+    SCALE_FACTOR,
+    properties::{
+        PropAI, PropAIAggression, PropAIVision, PropHitPoints, PropMaxHitPoints, PropPosition,
+    },
+};
+use shipyard::{EntityId, Get, UniqueView, View, World};
+
+use crate::{
+    entity_tags::{self, EntityTags},
+    mission::{EntitySpatialHash, PlayerInfo},
+    scripts::Effect,
+};
+
+use super::{ai_debug_util, ai_util};
+
+// Monster FOV is 60 degrees half-angle for an average AI (matches FovDebugConfig::monster())
+const BASE_FOV_HALF_ANGLE: f32 = 60.0;
+
+const EXPLOSION_TEMPLATE_NAME: &str = "HE Explosion";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RobotKind {
+    ProtocolDroid,
+    MaintenanceBot,
+    AssaultDroid,
+}
+
+impl RobotKind {
+    ///
+    /// for_entity
+    ///
+    /// Robots are tagged from their archetype - the behavior set in P$AI tells the kinds apart
+    pub fn for_entity(world: &World, entity_id: EntityId) -> Option<RobotKind> {
+        let v_ai = world.borrow::<View<PropAI>>().unwrap();
+        let ai = v_ai.get(entity_id).ok()?.0.to_ascii_lowercase();
+
+        match ai.as_str() {
+            "protocol" => Some(RobotKind::ProtocolDroid),
+            _ if !entity_tags::has_tag(world, entity_id, EntityTags::ROBOT) => None,
+            "rangedexplode" => Some(RobotKind::AssaultDroid),
+            _ => Some(RobotKind::MaintenanceBot),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Explosion {
+    /// Anything within this distance takes damage (world units)
+    pub radius: f32,
+    /// Damage at the center - falls off linearly to nothing at the radius
+    pub damage: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Repair {
+    /// How far the bot looks for damaged robots (world units)
+    pub radius: f32,
+    /// Hit points restored each time
+    pub amount: i32,
+    /// Seconds between repairs
+    pub interval: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RobotProfile {
+    /// Perception - half-angle of the vision cone, scaled by the vision rating
+    pub fov_half_angle: f32,
+    /// Movement - multiplier on the behavior's turn speed
+    pub turn_speed_scale: f32,
+    /// Attack - self-destruct when the player gets this close
+    pub explode_on_approach: Option<Explosion>,
+    /// Attack - explode when killed
+    pub explode_on_death: Option<Explosion>,
+    pub repair: Option<Repair>,
+}
+
+impl RobotProfile {
+    pub fn for_entity(world: &World, entity_id: EntityId) -> Option<RobotProfile> {
+        let kind = RobotKind::for_entity(world, entity_id)?;
+
+        let vision_scale = world
+            .borrow::<View<PropAIVision>>()
+            .unwrap()
+            .get(entity_id)
+            .map(|vision| vision.0.scale())
+            .unwrap_or(1.0);
+        // More aggressive robots close in faster
+        let aggression_scale = world
+            .borrow::<View<PropAIAggression>>()
+            .unwrap()
+            .get(entity_id)
+            .map(|aggression| aggression.0.scale().max(0.5))
+            .unwrap_or(1.0);
+
+        let fov_half_angle = (BASE_FOV_HALF_ANGLE * vision_scale).min(180.0);
+
+        let profile = match kind {
+            RobotKind::ProtocolDroid => RobotProfile {
+                fov_half_angle,
+                turn_speed_scale: 0.75 * aggression_scale,
+                explode_on_approach: Some(Explosion {
+                    radius: 6.0 / SCALE_FACTOR,
+                    damage: 15.0 * aggression_scale,
+                }),
+                explode_on_death: None,
+                repair: None,
+            },
+            RobotKind::MaintenanceBot => RobotProfile {
+                fov_half_angle,
+                turn_speed_scale: aggression_scale,
+                explode_on_approach: None,
+                explode_on_death: None,
+                repair: Some(Repair {
+                    radius: 20.0 / SCALE_FACTOR,
+                    amount: 2,
+                    interval: 1.0,
+                }),
+            },
+            RobotKind::AssaultDroid => RobotProfile {
+                fov_half_angle,
+                turn_speed_scale: 1.25 * aggression_scale,
+                explode_on_approach: None,
+                explode_on_death: Some(Explosion {
+                    radius: 10.0 / SCALE_FACTOR,
+                    damage: 20.0,
+                }),
+                repair: None,
+            },
+        };
+
+        Some(profile)
+    }
+
+    pub fn turn_speed(&self, behavior_turn_speed: Deg<f32>) -> Deg<f32> {
+        behavior_turn_speed * self.turn_speed_scale
+    }
+}
+
+///
+/// is_player_within
+///
+/// Whether the player is within the given distance of the entity
+pub fn is_player_within(world: &World, entity_id: EntityId, radius: f32) -> bool {
+    let player_pos = world.borrow::<UniqueView<PlayerInfo>>().unwrap().pos;
+    let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+    v_pos
+        .get(entity_id)
+        .map(|pos| (pos.position - player_pos).magnitude() <= radius)
+        .unwrap_or(false)
+}
+
+///
+/// explode
+///
+/// Blow the robot up - an explosion at its position, with damage to the player falling off with distance
+pub fn explode(world: &World, entity_id: EntityId, explosion: &Explosion) -> Effect {
+    let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+    let Ok(pos) = v_pos.get(entity_id) else {
+        return Effect::NoEffect;
+    };
+
+    let u_player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+    let distance = (pos.position - u_player.pos).magnitude();
+    let player_damage_effect = if distance < explosion.radius {
+        let damage = explosion.damage * (1.0 - distance / explosion.radius);
+        Effect::AdjustHitPoints {
+            entity_id: u_player.entity_id,
+            delta: -(damage.round() as i32),
+            source: Some(pos.position),
+        }
+    } else {
+        Effect::NoEffect
+    };
+
+    Effect::combine(vec![
+        Effect::CreateEntityByTemplateName {
+            template_name: EXPLOSION_TEMPLATE_NAME.to_owned(),
+            position: crate::util::vec3_to_point3(pos.position),
+            orientation: pos.rotation,
+        },
+        player_damage_effect,
+    ])
+}
+
+///
+/// repair_nearby_robot
+///
+/// Patch up the most damaged robot within reach - returns NoEffect if they're all in one piece
+pub fn repair_nearby_robot(world: &World, entity_id: EntityId, repair: &Repair) -> Effect {
+    let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+    let v_hit_points = world.borrow::<View<PropHitPoints>>().unwrap();
+    let v_max_hit_points = world.borrow::<View<PropMaxHitPoints>>().unwrap();
+    let u_spatial_hash = world.borrow::<UniqueView<EntitySpatialHash>>().unwrap();
+
+    let Ok(repairer_pos) = v_pos.get(entity_id).map(|pos| pos.position) else {
+        return Effect::NoEffect;
+    };
+
+    let maybe_target = u_spatial_hash
+        .entities_within(repairer_pos, repair.radius, EntityTags::ROBOT)
+        .into_iter()
+        .filter(|nearby| nearby.entity_id != entity_id)
+        .filter_map(|nearby| {
+            let hit_points = v_hit_points.get(nearby.entity_id).ok()?.hit_points;
+            let max_hit_points = v_max_hit_points.get(nearby.entity_id).ok()?.hit_points;
+            // Dead robots are scrap - there's nothing left to repair
+            (hit_points > 0 && (hit_points as u32) < max_hit_points).then_some((
+                nearby.entity_id,
+                nearby.position,
+                hit_points,
+                max_hit_points as i32 - hit_points,
+            ))
+        })
+        .min_by_key(|(_, _, hit_points, _)| *hit_points)
+        .map(|(id, position, _, missing)| (id, position, missing));
+
+    let Some((target_id, target_pos, missing)) = maybe_target else {
+        return Effect::NoEffect;
+    };
+
+    let debug_effect = if ai_debug_util::is_ai_debug_enabled(world, entity_id) {
+        Effect::DrawDebugLines {
+            lines: vec![(
+                crate::util::vec3_to_point3(repairer_pos + vec3(0.0, 0.5, 0.0)),
+                crate::util::vec3_to_point3(target_pos + vec3(0.0, 0.5, 0.0)),
+                vec4(0.0, 1.0, 0.0, 1.0),
+            )],
+        }
+    } else {
+        Effect::NoEffect
+    };
+
+    Effect::combine(vec![
+        Effect::AdjustHitPoints {
+            entity_id: target_id,
+            delta: repair.amount.min(missing),
+            source: None,
+        },
+        ai_util::play_positional_sound(entity_id, world, None, vec![("event", "activate")]),
+        debug_effect,
+    ])
+}
@@ -4,7 +4,7 @@ use dark::SCALE_FACTOR;
 use shipyard::{EntityId, World};
 
 use crate::{
-    physics::{InternalCollisionGroups, PhysicsWorld},
+    physics::{InternalCollisionGroups, PhysicsWorld, RayQuery},
     scripts::{
         Effect,
         ai::ai_util::{self},
@@ -82,20 +82,27 @@ impl SteeringStrategy for CollisionAvoidanceSteeringStrategy {
             //(rotation, main_whisker_distance, None),
         ];
 
+        let whisker_queries = whiskers_to_check
+            .iter()
+            .map(|(rotation, distance, _)| RayQuery {
+                start_point: position,
+                direction: rotation.rotate_vector(vec3(0.0, 0.0, 1.0)).normalize(),
+                max_toi: *distance,
+                collision_groups: InternalCollisionGroups::ALL_COLLIDABLE,
+                entity_to_ignore: Some(entity_id),
+                ignore_sensors: true,
+            })
+            .collect::<Vec<_>>();
+        let whisker_hits = physics.ray_cast_batch(&whisker_queries);
+
         let mut maybe_steering_output = None;
         let _dist = f32::MAX;
-        for (rotation, distance, mitigation) in whiskers_to_check {
-            let forward = rotation.rotate_vector(vec3(0.0, 0.0, 1.0)).normalize();
-            let end_point = position + forward * distance;
-
-            let main_whisker_hit_result = physics.ray_cast2(
-                position,
-                forward,
-                distance,
-                InternalCollisionGroups::ALL_COLLIDABLE,
-                Some(entity_id),
-                true,
-            );
+        for ((query, (_, _, mitigation)), main_whisker_hit_result) in whisker_queries
+            .iter()
+            .zip(whiskers_to_check)
+            .zip(whisker_hits)
+        {
+            let end_point = position + query.direction * query.max_toi;
 
             if let Some(main_whisker_hit_result) = main_whisker_hit_result {
                 debug_lines.push((
@@ -1,15 +1,21 @@
-use cgmath::{Deg, Matrix4, Quaternion, Rotation3, vec3};
-use dark::properties::{AIAlertLevel, PropAIAlertCap, PropAIAwareDelay};
-use shipyard::{EntityId, Get, View, World};
-
-use crate::{physics::PhysicsWorld, time::Time};
+use cgmath::{Deg, InnerSpace, Matrix4, Quaternion, Rotation3, Vector3, vec3};
+use dark::properties::{AIAlertLevel, PropAIAlertCap, PropAIAwareDelay, PropPosition};
+use shipyard::{EntityId, Get, UniqueView, View, World};
+
+use crate::{
+    entity_tags::EntityTags,
+    factions::{self, Faction},
+    mission::{EntitySpatialHash, PlayerInfo},
+    physics::PhysicsWorld,
+    statistics::StatisticEvent,
+    time::Time,
+};
 
 use super::{
     Effect, MessagePayload, Script,
     ai_debug_util::{self, AlertnessDebugConfig, FovDebugConfig},
     ai_util,
     alertness::{self, AlertnessState, AlertnessTimings},
-    steering::{ChasePlayerSteeringStrategy, SteeringStrategy},
 };
 
 pub enum TurretState {
@@ -25,11 +31,11 @@ impl TurretState {
         entity_id: EntityId,
         time: &Time,
         world: &World,
-        is_player_visible: bool,
+        should_deploy: bool,
     ) -> (TurretState, Effect) {
         match current_state {
             TurretState::Closed => {
-                if is_player_visible {
+                if should_deploy {
                     (
                         TurretState::Opening { progress: 0.0 },
                         ai_util::play_positional_sound(
@@ -68,7 +74,7 @@ impl TurretState {
                 }
             }
             TurretState::Open => {
-                if !is_player_visible {
+                if !should_deploy {
                     (
                         TurretState::Closing { progress: 0.0 },
                         ai_util::play_positional_sound(
@@ -84,10 +90,37 @@ impl TurretState {
             }
         }
     }
+
+    pub fn open_amount(&self) -> f32 {
+        match self {
+            TurretState::Closed => 0.0,
+            TurretState::Opening { progress } => progress.min(1.0),
+            TurretState::Closing { progress } => 1.0 - progress.min(1.0),
+            TurretState::Open => 1.0,
+        }
+    }
 }
 
 const OPEN_TIME: f32 = 2.5;
 
+// Joints on the turret models - the gun rotates on the first, the cap slides open on the second
+const GUN_JOINT: u32 = 1;
+const CAP_JOINT: u32 = 2;
+const CAP_OPEN_OFFSET: f32 = -0.75;
+
+// Tracking limits, relative to the direction the turret was placed facing (in degrees)
+const TURRET_YAW_LIMIT: f32 = 150.0;
+const TURRET_MIN_PITCH: f32 = -60.0;
+const TURRET_MAX_PITCH: f32 = 45.0;
+const TURRET_TURN_SPEED: f32 = 120.0;
+
+// Only fire when the gun is pointing this close to the target (in degrees)
+const TURRET_FIRE_EPSILON: f32 = 10.0;
+const TURRET_FIRE_INTERVAL: f32 = 1.0;
+
+// How far a turret looks for creatures to shoot
+const TURRET_MAX_RANGE: f32 = 40.0;
+
 // Default timing constants for turrets (in seconds)
 const DEFAULT_ESCALATE_SECONDS: f32 = 2.0;
 const DEFAULT_DECAY_SECONDS: f32 = 4.0;
@@ -103,8 +136,10 @@ pub struct TurretAI {
     next_fire: f32,
     initial_yaw: Deg<f32>,
     current_heading: Deg<f32>,
-    steering: ChasePlayerSteeringStrategy,
+    current_pitch: Deg<f32>,
     current_state: TurretState,
+    /// Seconds left until an EMP wears off
+    emp_remaining: f32,
     /// Alertness state tracking
     alertness: AlertnessState,
     /// Alertness configuration (loaded from entity properties)
@@ -116,9 +151,10 @@ impl TurretAI {
         TurretAI {
             next_fire: 0.0,
             initial_yaw: Deg(0.0),
-            steering: ChasePlayerSteeringStrategy,
             current_heading: Deg(0.0),
+            current_pitch: Deg(0.0),
             current_state: TurretState::Closed,
+            emp_remaining: 0.0,
             alertness: AlertnessState::default(),
             config: None,
         }
@@ -158,51 +194,133 @@ impl TurretAI {
         Some(TurretConfig { alert_cap, timings })
     }
 
-    fn try_to_shoot(
-        &mut self,
-        time: &Time,
-        world: &World,
+    fn is_disabled(&self, entity_id: EntityId, world: &World) -> bool {
+        self.emp_remaining > 0.0 || ai_util::is_killed(entity_id, world)
+    }
+
+    ///
+    /// find_target
+    ///
+    /// Where the turret should be shooting - the player, if they're in view and the turret is
+    /// hostile to them, or otherwise the closest hostile creature it can see (see `factions`).
+    /// Hacking a turret brings it over to the player's side.
+    fn find_target(
+        &self,
         entity_id: EntityId,
+        world: &World,
         physics: &PhysicsWorld,
-    ) -> Effect {
-        let _quat = Quaternion::from_angle_x(Deg(time.total.as_secs_f32().sin() * 90.0));
-        let fire_projectile = if self.next_fire < time.total.as_secs_f32() {
-            self.next_fire = time.total.as_secs_f32() + 1.0;
-            let rotation = Quaternion::from_angle_y(self.current_heading - self.initial_yaw);
-            ai_util::fire_ranged_weapon(world, entity_id, rotation)
-        } else {
-            Effect::NoEffect
+    ) -> Option<Vector3<f32>> {
+        // Turret FOV is 30 degrees half-angle (matches FovDebugConfig::turret())
+        // Turret uses joint transforms for rotation, negate heading to match visual direction
+        const TURRET_FOV_HALF_ANGLE: f32 = 30.0;
+        let is_player_visible = ai_util::is_player_visible_in_fov(
+            entity_id,
+            world,
+            physics,
+            -self.current_heading,
+            TURRET_FOV_HALF_ANGLE,
+        );
+        if is_player_visible {
+            return Some(world.borrow::<UniqueView<PlayerInfo>>().unwrap().pos);
+        }
+
+        let candidates = {
+            let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+            let u_spatial_hash = world.borrow::<UniqueView<EntitySpatialHash>>().unwrap();
+            let turret_pos = v_pos.get(entity_id).ok()?.position;
+
+            u_spatial_hash
+                .entities_within(turret_pos, TURRET_MAX_RANGE, EntityTags::CREATURE)
+                .into_iter()
+                .filter(|nearby| nearby.entity_id != entity_id)
+                .map(|nearby| (nearby.entity_id, nearby.position, nearby.distance))
+                .collect::<Vec<_>>()
         };
 
-        let maybe_desired_yaw =
-            self.steering
-                .steer(self.current_heading, world, physics, entity_id, time);
-
-        let rotation_effect = {
-            if let Some((steering_output, _effect)) = maybe_desired_yaw {
-                self.current_heading = steering_output.desired_heading;
-                let rotate = Quaternion::from_angle_x(Deg(self.initial_yaw.0
-                    - self.current_heading.0
-                    - 90.0));
-                let rotate_animation = Effect::SetJointTransform {
-                    entity_id,
-                    joint_id: 1,
-                    transform: rotate.into(),
-                };
-
-                Effect::combine(vec![rotate_animation, _effect])
-            } else {
-                Effect::NoEffect
+        candidates
+            .into_iter()
+            .find(|(id, position, _)| {
+                factions::is_hostile(world, entity_id, *id)
+                    && !ai_util::is_killed(*id, world)
+                    && ai_util::has_line_of_sight(entity_id, world, physics, *position)
+            })
+            .map(|(_, position, _)| position)
+    }
+
+    ///
+    /// track_target
+    ///
+    /// Turn the gun towards the target, within the turret's yaw and pitch limits.
+    /// Returns whether the gun is lined up well enough to take a shot.
+    fn track_target(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        target: Vector3<f32>,
+        delta: f32,
+    ) -> bool {
+        let turret_pos = {
+            let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+            match v_pos.get(entity_id) {
+                Ok(pos) => pos.position,
+                Err(_) => return false,
             }
         };
 
-        Effect::combine(vec![fire_projectile, rotation_effect])
+        let desired_yaw = ai_util::yaw_between_vectors(turret_pos, target);
+        let desired_relative_yaw =
+            ai_util::clamp_to_minimal_delta_angle(desired_yaw - self.initial_yaw);
+        let limited_relative_yaw = desired_relative_yaw
+            .0
+            .clamp(-TURRET_YAW_LIMIT, TURRET_YAW_LIMIT);
+
+        let to_target = target - turret_pos;
+        let horizontal_distance = vec3(to_target.x, 0.0, to_target.z).magnitude();
+        let desired_pitch = to_target.y.atan2(horizontal_distance).to_degrees();
+        let limited_pitch = desired_pitch.clamp(TURRET_MIN_PITCH, TURRET_MAX_PITCH);
+
+        let max_turn = TURRET_TURN_SPEED * delta;
+        let current_relative_yaw =
+            ai_util::clamp_to_minimal_delta_angle(self.current_heading - self.initial_yaw).0;
+        let next_relative_yaw = move_towards(current_relative_yaw, limited_relative_yaw, max_turn);
+        self.current_heading = self.initial_yaw + Deg(next_relative_yaw);
+        self.current_pitch = Deg(move_towards(self.current_pitch.0, limited_pitch, max_turn));
+
+        // Out of reach of the gun - keep tracking, but don't waste shots
+        let in_limits = (desired_relative_yaw.0 - limited_relative_yaw).abs() < f32::EPSILON
+            && (desired_pitch - limited_pitch).abs() < f32::EPSILON;
+
+        in_limits
+            && (desired_relative_yaw.0 - next_relative_yaw).abs() <= TURRET_FIRE_EPSILON
+            && (desired_pitch - self.current_pitch.0).abs() <= TURRET_FIRE_EPSILON
+    }
+
+    fn try_to_shoot(&mut self, time: &Time, world: &World, entity_id: EntityId) -> Effect {
+        if self.next_fire < time.total.as_secs_f32() {
+            self.next_fire = time.total.as_secs_f32() + TURRET_FIRE_INTERVAL;
+            let rotation = Quaternion::from_angle_y(self.current_heading - self.initial_yaw)
+                * Quaternion::from_angle_x(-self.current_pitch);
+            ai_util::fire_ranged_weapon(world, entity_id, rotation)
+        } else {
+            Effect::NoEffect
+        }
+    }
+
+    fn gun_animation(&self, entity_id: EntityId) -> Effect {
+        let rotate =
+            Quaternion::from_angle_x(Deg(self.initial_yaw.0 - self.current_heading.0 - 90.0));
+        Effect::SetJointTransform {
+            entity_id,
+            joint_id: GUN_JOINT,
+            transform: rotate.into(),
+        }
     }
 }
 
 impl Script for TurretAI {
     fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
         self.initial_yaw = ai_util::current_yaw(entity_id, world);
+        self.current_heading = self.initial_yaw;
 
         // Load alertness configuration
         self.config = Self::build_config(world, entity_id);
@@ -227,17 +345,16 @@ impl Script for TurretAI {
         time: &Time,
     ) -> Effect {
         let delta = time.elapsed.as_secs_f32();
+        self.emp_remaining = (self.emp_remaining - delta).max(0.0);
 
-        // Turret FOV is 30 degrees half-angle (matches FovDebugConfig::turret())
-        // Turret uses joint transforms for rotation, negate heading to match visual direction
-        const TURRET_FOV_HALF_ANGLE: f32 = 30.0;
-        let is_visible = ai_util::is_player_visible_in_fov(
-            entity_id,
-            world,
-            physics,
-            -self.current_heading,
-            TURRET_FOV_HALF_ANGLE,
-        );
+        // A disabled turret doesn't see anything - it folds up and waits out the EMP (or stays dead)
+        let is_disabled = self.is_disabled(entity_id, world);
+        let maybe_target = if is_disabled {
+            None
+        } else {
+            self.find_target(entity_id, world, physics)
+        };
+        let is_visible = maybe_target.is_some();
 
         // Update alertness state
         let alertness_effect = if let Some(config) = &self.config {
@@ -257,28 +374,29 @@ impl Script for TurretAI {
             Effect::NoEffect
         };
 
-        // Existing turret state machine (now uses FOV-aware visibility)
         let (new_state, state_eff) =
             TurretState::update(&self.current_state, entity_id, time, world, is_visible);
         self.current_state = new_state;
 
-        let open_amount = match self.current_state {
-            TurretState::Closed => 0.0,
-            TurretState::Opening { progress } => progress,
-            TurretState::Closing { progress } => 1.0 - progress,
-            TurretState::Open => 1.0,
-        };
-
         let cap_animation = Effect::SetJointTransform {
             entity_id,
-            joint_id: 2,
-            transform: Matrix4::from_translation(vec3(-0.75 * open_amount, 0.0, 0.0)),
+            joint_id: CAP_JOINT,
+            transform: Matrix4::from_translation(vec3(
+                CAP_OPEN_OFFSET * self.current_state.open_amount(),
+                0.0,
+                0.0,
+            )),
         };
 
-        let attack_eff = if matches!(self.current_state, TurretState::Open) {
-            self.try_to_shoot(time, world, entity_id, physics)
-        } else {
-            Effect::NoEffect
+        let attack_eff = match (&self.current_state, maybe_target) {
+            (TurretState::Open, Some(target)) => {
+                if self.track_target(entity_id, world, target, delta) {
+                    self.try_to_shoot(time, world, entity_id)
+                } else {
+                    Effect::NoEffect
+                }
+            }
+            _ => Effect::NoEffect,
         };
 
         // Debug visualization - alertness bar
@@ -303,6 +421,7 @@ impl Script for TurretAI {
         Effect::combine(vec![
             alertness_effect,
             cap_animation,
+            self.gun_animation(entity_id),
             state_eff,
             attack_eff,
             alertness_debug_eff,
@@ -312,11 +431,77 @@ impl Script for TurretAI {
 
     fn handle_message(
         &mut self,
-        _entity_id: EntityId,
-        _world: &World,
+        entity_id: EntityId,
+        world: &World,
         _physics: &PhysicsWorld,
-        _msg: &MessagePayload,
+        msg: &MessagePayload,
     ) -> Effect {
-        Effect::NoEffect
+        match msg {
+            MessagePayload::Damage { amount, source } => Effect::AdjustHitPoints {
+                entity_id,
+                delta: -(amount.round() as i32),
+                source: *source,
+            },
+            MessagePayload::Emp { duration } => {
+                self.emp_remaining = self.emp_remaining.max(*duration);
+                Effect::NoEffect
+            }
+            // Frobbing a turret hacks it, like in the original game
+            // TODO: There's no hacking minigame yet, so hacking always succeeds
+            MessagePayload::Frob | MessagePayload::Hack => {
+                let is_hacked = factions::faction_of(world, entity_id) == Some(Faction::Player);
+                if is_hacked || ai_util::is_killed(entity_id, world) {
+                    return Effect::NoEffect;
+                }
+
+                Effect::combine(vec![
+                    Effect::SetFaction {
+                        entity_id,
+                        faction: Faction::Player,
+                    },
+                    ai_util::play_positional_sound(
+                        entity_id,
+                        world,
+                        None,
+                        vec![("event", "deactivate")],
+                    ),
+                    Effect::RecordStatistic {
+                        event: StatisticEvent::HackAttempted { success: true },
+                    },
+                ])
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
+
+fn move_towards(current: f32, target: f32, max_delta: f32) -> f32 {
+    let delta = target - current;
+    if delta.abs() <= max_delta {
+        target
+    } else {
+        current + delta.signum() * max_delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_towards_is_limited_by_max_delta() {
+        assert_eq!(move_towards(0.0, 90.0, 10.0), 10.0);
+        assert_eq!(move_towards(0.0, -90.0, 10.0), -10.0);
+        assert_eq!(move_towards(85.0, 90.0, 10.0), 90.0);
+    }
+
+    #[test]
+    fn test_open_amount_follows_deploy_progress() {
+        assert_eq!(TurretState::Closed.open_amount(), 0.0);
+        assert_eq!(TurretState::Open.open_amount(), 1.0);
+        assert_eq!(TurretState::Opening { progress: 0.25 }.open_amount(), 0.25);
+        assert_eq!(TurretState::Closing { progress: 0.25 }.open_amount(), 0.75);
+        // Progress overshoots on the last frame of the animation
+        assert_eq!(TurretState::Opening { progress: 1.2 }.open_amount(), 1.0);
     }
 }
@@ -1,25 +1,36 @@
 use std::{collections::HashSet, ops::Rem};
 
-use cgmath::{InnerSpace, Vector3, Zero};
+use cgmath::{Vector3, Zero};
 use dark::properties::{Link, PropPosition, PropTemplateId, TPathData};
 use engine::audio::AudioHandle;
 use shipyard::{EntityId, Get, View, World};
-use tracing::{info, trace};
+use tracing::info;
 
 use crate::{physics::PhysicsWorld, time::Time};
 
 use super::{
-    Effect, MessagePayload, Script,
-    script_util::{get_first_link_of_type, get_first_link_with_data},
+    Effect, MessagePayload, ParallelScript, Script,
+    script_util::{
+        get_environmental_sound_query, get_first_link_of_type, get_first_link_with_data,
+    },
+    trans_mover::{TransMover, TransMoverState},
 };
 
+// Elevators without a statechange schema fall back to this sound when they start moving
+const FALLBACK_START_SOUND: &str = "Devices/DOOR1OP";
+
+///
+/// BaseElevator
+///
+/// Script for elevators - drives a TransMover along the stops of the elevator's TPath links,
+/// moving to the next stop on each TurnOn. Elevators only ever 'open' toward their next stop,
+/// so the player riding one never blocks it.
+///
 pub struct BaseElevator {
     path_offset: Vector3<f32>,
     current_index: u32,
-    current_position: Vector3<f32>,
-    desired_position: Vector3<f32>,
     speed: f32,
-    is_moving: bool,
+    mover: TransMover,
     path: Vec<(PropPosition, Option<TPathData>)>,
     is_dontstop_elevator: bool, // Flag for if the elevator came from a 'DontStopElevator' script, where it should keep moving when it hits boundaries.
 }
@@ -28,11 +39,9 @@ impl BaseElevator {
     pub fn new() -> BaseElevator {
         BaseElevator {
             path_offset: Vector3::zero(),
-            current_position: Vector3::zero(),
             current_index: 0,
-            desired_position: Vector3::zero(),
-            is_moving: false,
             speed: 10.0,
+            mover: TransMover::new(Vector3::zero()),
             path: Vec::new(),
             is_dontstop_elevator: false,
         }
@@ -44,16 +53,18 @@ impl BaseElevator {
         elevator
     }
 
-    fn move_to_next_target(&mut self, world: &World) {
-        let _v_template = world.borrow::<View<PropTemplateId>>().unwrap();
+    fn move_to_next_target(&mut self, world: &World, entity_id: EntityId) -> Effect {
+        if self.path.is_empty() {
+            return Effect::NoEffect;
+        }
+
         info!("BaseElevator: Got turn on... paths are: {:?}", self.path);
-        let _v_position = world.borrow::<View<PropPosition>>().unwrap();
 
         let next_position_idx = (self.current_index + 1).rem(self.path.len() as u32);
         self.current_index = next_position_idx;
 
         let (next_position, next_data) = &self.path[next_position_idx as usize];
-        self.desired_position = next_position.position;
+        let desired_position = next_position.position;
 
         // If we have path data available, use it to set the speed
         if let Some(path_data) = next_data {
@@ -62,26 +73,33 @@ impl BaseElevator {
 
         info!(
             "BaseElevator: Moving to index {} position {:?} with speed {}",
-            self.current_index, self.desired_position, self.speed
+            self.current_index, desired_position, self.speed
         );
-        //self.target_entity = next_dest_entity;
-        self.is_moving = true;
+
+        // Arriving at a stop leaves the mover open, so this opens on toward the next one
+        self.mover
+            .open(world, entity_id, desired_position, self.speed)
     }
 }
+impl ParallelScript for BaseElevator {}
+
 impl Script for BaseElevator {
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        Some(self)
+    }
+
     fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
         let v_position = world.borrow::<View<PropPosition>>().unwrap();
         let initial_path = get_first_link_of_type(world, entity_id, Link::TPathInit);
 
         let position = v_position.get(entity_id).unwrap();
-        self.current_position = position.position;
-        self.desired_position = self.current_position;
+        self.mover = TransMover::new(position.position);
 
         // Figure out where the next link goes... if there is no TPathInit, revert back to entity
         let mut target_entity = entity_id;
         if let Some(init_entity_id) = initial_path {
             if let Ok(initial_position) = v_position.get(init_entity_id) {
-                self.path_offset = self.current_position - initial_position.position;
+                self.path_offset = position.position - initial_position.position;
             }
             target_entity = init_entity_id;
         }
@@ -103,64 +121,49 @@ impl Script for BaseElevator {
         &mut self,
         entity_id: EntityId,
         world: &World,
-        _physics: &PhysicsWorld,
+        physics: &PhysicsWorld,
         time: &Time,
     ) -> Effect {
-        let dir = self.desired_position - self.current_position;
-        if dir.magnitude2() > 0.001 {
-            let normalized = dir.normalize();
-
-            trace!(
-                "desired: {:?} current: {:?} dir: {:?}",
-                self.desired_position, self.current_position, normalized
-            );
-
-            self.is_moving = true;
-            self.current_position += normalized * time.elapsed.as_secs_f32() * self.speed;
-            Effect::SetPosition {
-                entity_id,
-                position: self.current_position,
-            }
-        } else if self.is_moving {
-            if self.is_dontstop_elevator {
-                self.move_to_next_target(world);
-            } else {
-                self.is_moving = false;
-            }
-            Effect::NoEffect
+        let move_effect = self.mover.update(entity_id, world, physics, time);
+
+        // A 'dontstop' elevator heads for the next stop as soon as it arrives
+        if self.is_dontstop_elevator && self.mover.state() == TransMoverState::Open {
+            Effect::combine(vec![
+                move_effect,
+                self.move_to_next_target(world, entity_id),
+            ])
         } else {
-            Effect::NoEffect
+            move_effect
         }
     }
 
     fn handle_message(
         &mut self,
-        _entity_id: EntityId,
+        entity_id: EntityId,
         world: &World,
         _physics: &PhysicsWorld,
         msg: &MessagePayload,
     ) -> Effect {
         match msg {
             MessagePayload::TurnOn { from: _ } => {
-                if !self.is_moving {
-                    self.move_to_next_target(world);
-                    Effect::PlaySound {
-                        handle: AudioHandle::new(),
-                        name: "Devices/DOOR1OP".to_owned(),
-                    }
+                if self.mover.is_moving() {
+                    return Effect::NoEffect;
+                }
+
+                let move_effect = self.move_to_next_target(world, entity_id);
+                if get_environmental_sound_query(world, entity_id, "statechange", vec![]).is_some()
+                {
+                    move_effect
                 } else {
-                    Effect::NoEffect
+                    Effect::combine(vec![
+                        move_effect,
+                        Effect::PlaySound {
+                            handle: AudioHandle::new(),
+                            name: FALLBACK_START_SOUND.to_owned(),
+                        },
+                    ])
                 }
             }
-            // MessagePayload::TurnOff => {
-            //     //self.current_position = trans_door.base_closed_location;
-            //     self.desired_position = trans_door.base_closed_location;
-            //     self.is_moving = true;
-            //     Effect::PlaySound {
-            //         handle: AudioHandle::new(),
-            //         name: "Devices/DOOR1CL".to_owned(),
-            //     }
-            // }
             _ => Effect::NoEffect,
         }
     }
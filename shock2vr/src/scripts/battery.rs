@@ -0,0 +1,38 @@
+use shipyard::{EntityId, World};
+
+use crate::{energy::BATTERY_ENERGY, physics::PhysicsWorld};
+
+use super::{Effect, MessagePayload, Script, script_util::is_in_player_inventory};
+
+///
+/// Battery
+///
+/// A portable battery - frobbing it in the player's inventory tops up their energy, and uses it up
+pub struct Battery;
+impl Battery {
+    pub fn new() -> Battery {
+        Battery
+    }
+}
+
+impl Script for Battery {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::Frob if is_in_player_inventory(world, entity_id) => {
+                Effect::combine(vec![
+                    Effect::AdjustEnergy {
+                        amount: BATTERY_ENERGY,
+                    },
+                    Effect::DestroyEntity { entity_id },
+                ])
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
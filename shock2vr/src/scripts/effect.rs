@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use cgmath::{Matrix4, Point3, Quaternion, Vector2, Vector3, Vector4};
 use dark::{
     EnvSoundQuery,
@@ -8,8 +10,15 @@ use engine::audio::AudioHandle;
 use shipyard::EntityId;
 
 use crate::{
+    equipment::EquipmentKind,
+    factions::Faction,
     gui::{GuiComponentRenderInfo, GuiHandle},
-    mission::entity_creator::CreateEntityOptions,
+    mission::{VisibilityEngineKind, entity_creator::CreateEntityOptions},
+    noise::Noise,
+    object_labels::ObjectLabels,
+    player_status::StatusEffectKind,
+    quest_info::GlobalValue,
+    statistics::StatisticEvent,
     vr_config::Handedness,
 };
 
@@ -35,6 +44,48 @@ pub enum GlobalEffect {
 
     // Test the reload functionality (as if saving + loading)
     TestReload,
+
+    // Switch to the named player profile
+    SwitchProfile {
+        name: String,
+    },
+
+    // Change the game speed - see AccessibilitySettings::time_scale
+    SetTimeScale {
+        scale: f32,
+    },
+
+    // Switch how missions decide what to draw - see VisibilityEngineKind
+    SetVisibilityEngine {
+        kind: VisibilityEngineKind,
+    },
+
+    // Print the installed mods, and problems with the enabled ones, to the console
+    ListMods,
+
+    // Print the assets that failed to load this session to the console
+    ListMissingAssets,
+
+    // Forget the tutorial hints the active profile has been shown, so they come up again
+    ResetHints,
+
+    // Enable or disable a mod for the active profile - applied on the next start
+    SetModEnabled {
+        id: String,
+        enabled: bool,
+    },
+
+    // Pause the mission and play a cutscene video - `from` gets CutsceneCompleted when it's over
+    PlayCutscene {
+        video_name: String,
+        from: EntityId,
+    },
+
+    // Hand the camera to a scripted sequence (see `sequences`) - `from` gets CutsceneCompleted when it's over
+    PlaySequence {
+        sequence: String,
+        from: EntityId,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -59,6 +110,8 @@ pub enum Effect {
     AdjustHitPoints {
         entity_id: EntityId,
         delta: i32,
+        /// Where the damage came from, for the player's damage direction indicator
+        source: Option<Vector3<f32>>,
     },
 
     ApplyForce {
@@ -66,6 +119,48 @@ pub enum Effect {
         force: Vector3<f32>,
     },
 
+    /// Shake the player's camera, with intensity falling off from the origin
+    CameraShake {
+        origin: Vector3<f32>,
+        intensity: f32,
+        duration: f32,
+    },
+
+    /// Flash the screen red, showing the direction of the damage source if available
+    DamageIndicator {
+        source: Option<Vector3<f32>>,
+    },
+
+    /// Start (or extend) a status effect on the player, ie radiation or an EMP scrambling the HUD
+    ApplyStatusEffect {
+        kind: StatusEffectKind,
+        duration: f32,
+    },
+
+    /// Switch the side an entity is on (see `factions`) - ie, a hacked turret joining the player
+    SetFaction {
+        entity_id: EntityId,
+        faction: Faction,
+    },
+
+    /// Put an item from the player's inventory in an equip slot (see `equipment`) - frobbing it
+    /// again takes it off
+    EquipItem {
+        entity_id: EntityId,
+        kind: EquipmentKind,
+    },
+    UnequipItem {
+        entity_id: EntityId,
+    },
+
+    /// Add (or, if negative, drain) the player's energy (see `energy`) - ie from a battery
+    AdjustEnergy {
+        amount: f32,
+    },
+
+    /// Refill the player's energy, ie at an energy station
+    RechargeEnergy,
+
     ChangeModel {
         entity_id: EntityId,
         model_name: String,
@@ -76,6 +171,16 @@ pub enum Effect {
         orientation: Quaternion<f32>,
     },
 
+    /// Create an entity straight into the player's inventory
+    GiveItem {
+        template_name: String,
+    },
+
+    /// Roll a container's loot into it, if it hasn't been already
+    RollLoot {
+        entity_id: EntityId,
+    },
+
     CreateEntity {
         template_id: i32,
         position: Point3<f32>,
@@ -88,6 +193,22 @@ pub enum Effect {
         lines: Vec<(Point3<f32>, Point3<f32>, Vector4<f32>)>,
     },
 
+    DrawDebugText {
+        position: Point3<f32>,
+        text: String,
+    },
+
+    /// Turn AI debug visuals on or off, optionally only for some entities
+    SetAiDebug {
+        enabled: bool,
+        entities: Option<HashSet<EntityId>>,
+    },
+
+    /// Change the debug labels over entities (from the `labels` console command)
+    SetObjectLabels {
+        labels: ObjectLabels,
+    },
+
     DestroyEntity {
         entity_id: EntityId,
     },
@@ -124,10 +245,21 @@ pub enum Effect {
         email: u32,
         force: bool,
     },
+    // Ship-wide PA announcement, with an optional subtitle (an id in ui.ftl)
+    PlayAnnouncement {
+        sound: String,
+        subtitle: Option<String>,
+    },
     PlaySound {
         handle: AudioHandle,
         name: String,
     },
+    // Play a sound schema (or raw sample) from a point in the world
+    PlaySoundAt {
+        handle: AudioHandle,
+        name: String,
+        position: Vector3<f32>,
+    },
     PlaySpeech {
         entity_id: EntityId,
         voice_index: usize,
@@ -138,6 +270,14 @@ pub enum Effect {
         audio_handle: AudioHandle,
         query: EnvSoundQuery,
         position: Vector3<f32>,
+        // Entity the sound follows as it moves, if any
+        entity_id: Option<EntityId>,
+    },
+    // Play an environmental sound from an entity over and over, until stopped with StopSound
+    LoopEnvironmentalSound {
+        audio_handle: AudioHandle,
+        query: EnvSoundQuery,
+        entity_id: EntityId,
     },
     PositionInventory {
         position: Vector3<f32>,
@@ -146,6 +286,15 @@ pub enum Effect {
     StopSound {
         handle: AudioHandle,
     },
+    // A noise AI can hear - see noise.rs
+    MakeNoise {
+        noise: Noise,
+    },
+    // Change the volume of a playing sound - 1.0 is the clip's original volume
+    SetSoundVolume {
+        handle: AudioHandle,
+        volume: f32,
+    },
     SetPosition {
         entity_id: EntityId,
         position: Vector3<f32>,
@@ -183,6 +332,11 @@ pub enum Effect {
         quest_bit_value: QuestBitValue,
     },
 
+    SetGlobalVariable {
+        name: String,
+        value: GlobalValue,
+    },
+
     SetAIProperty {
         entity_id: EntityId,
         update: AIPropertyUpdate,
@@ -218,6 +372,51 @@ pub enum Effect {
 
     /// Interactive pathfinding test system
     PathfindingTest,
+
+    /// Developer entity inspector overlay
+    EntityInspector(crate::mission::entity_inspector::InspectorAction),
+
+    /// Debug flying mode - the player ignores collision and gravity
+    ToggleNoclip,
+
+    /// Start / stop examining the held item
+    ToggleExamine,
+
+    /// Count something towards the player's statistics
+    RecordStatistic {
+        event: StatisticEvent,
+    },
+
+    /// Start the security alarm, or extend it if it's already going
+    TriggerAlarm {
+        duration: f32,
+    },
+
+    /// Switch off the security alarm
+    ResetAlarm,
+
+    /// Pause the mission to play a cutscene (.avi) - the `from` entity is sent
+    /// CutsceneCompleted once it's over
+    PlayCutscene {
+        video_name: String,
+        from: EntityId,
+    },
+
+    /// Play a scripted sequence from `sequences.json` - the `from` entity is sent
+    /// CutsceneCompleted once it's over
+    PlaySequence {
+        sequence: String,
+        from: EntityId,
+    },
+
+    /// Lock or unlock a door / container / button
+    SetLocked {
+        entity_id: EntityId,
+        locked: bool,
+    },
+
+    /// `overrides.toml` changed - apply the new values in the global context to the mission
+    ReloadTemplateOverrides,
 }
 
 impl Effect {
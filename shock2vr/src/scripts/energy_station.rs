@@ -40,12 +40,28 @@ impl Script for EnergyStation {
                 }
             }
             MessagePayload::Collided { with } => do_recharge(world, entity_id, with),
+            // Frobbing the station refills the player's own energy
+            MessagePayload::Frob => Effect::combine(vec![
+                Effect::RechargeEnergy,
+                activate_sound(world, entity_id),
+            ]),
             _ => Effect::NoEffect,
         }
     }
 }
 
 fn do_recharge(world: &World, entity_id: EntityId, with: &EntityId) -> Effect {
+    let recharge_effect = Effect::Send {
+        msg: Message {
+            to: *with,
+            payload: MessagePayload::Recharge,
+        },
+    };
+
+    Effect::combine(vec![recharge_effect, activate_sound(world, entity_id)])
+}
+
+fn activate_sound(world: &World, entity_id: EntityId) -> Effect {
     let v_pos = world.borrow::<View<PropPosition>>().unwrap();
     let v_class_tag = world.borrow::<View<PropClassTag>>().unwrap();
     let mut class_tags = v_class_tag
@@ -57,21 +73,14 @@ fn do_recharge(world: &World, entity_id: EntityId, with: &EntityId) -> Effect {
     // panic!();
     //log_property::<PropDeviceTag>(world);
     let pos = v_pos.get(entity_id).unwrap();
-    let recharge_effect = Effect::Send {
-        msg: Message {
-            to: *with,
-            payload: MessagePayload::Recharge,
-        },
-    };
 
     let mut query = vec![("event", "activate")];
     query.append(&mut class_tags);
     script_log!(DEBUG, "Energy station activate query: {:?}", query);
-    let sound_effect = Effect::PlayEnvironmentalSound {
+    Effect::PlayEnvironmentalSound {
         audio_handle: AudioHandle::new(),
         query: EnvSoundQuery::from_tag_values(query),
         position: pos.position,
-    };
-
-    Effect::combine(vec![recharge_effect, sound_effect])
+        entity_id: None,
+    }
 }
@@ -0,0 +1,54 @@
+use shipyard::{EntityId, UniqueView, World};
+
+use crate::{
+    equipment::{EquipmentKind, PlayerEquipment},
+    physics::PhysicsWorld,
+};
+
+use super::{Effect, MessagePayload, Script, script_util::is_in_player_inventory};
+
+///
+/// Equippable
+///
+/// Armor or an implant - frobbing it in the player's inventory equips it, and frobbing it again
+/// takes it off
+pub struct Equippable {
+    kind: EquipmentKind,
+}
+
+impl Equippable {
+    pub fn new(kind: EquipmentKind) -> Equippable {
+        Equippable { kind }
+    }
+}
+
+impl Script for Equippable {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::Frob => {
+                if !is_in_player_inventory(world, entity_id) {
+                    return Effect::NoEffect;
+                }
+
+                let is_equipped = world
+                    .borrow::<UniqueView<PlayerEquipment>>()
+                    .is_ok_and(|equipment| equipment.slot_of(entity_id).is_some());
+                if is_equipped {
+                    Effect::UnequipItem { entity_id }
+                } else {
+                    Effect::EquipItem {
+                        entity_id,
+                        kind: self.kind,
+                    }
+                }
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
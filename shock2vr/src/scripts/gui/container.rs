@@ -1,12 +1,14 @@
 use cgmath::{Vector2, Vector3, vec2};
 use dark::properties::{Link, PropInventoryDimensions, PropObjIcon};
 
-use shipyard::{EntityId, Get, View, World};
+use shipyard::{EntityId, Get, UniqueView, View, World};
 
 use crate::{
+    equipment::{EquipSlot, EquipmentKind, PlayerEquipment},
     gui::{Gui, GuiComponent, GuiConfig, GuiCursor},
     inventory::Inventory,
-    scripts::{Message, script_util},
+    localization,
+    scripts::{Message, internal_lock_script::is_locked, script_util},
 };
 
 use crate::gui;
@@ -21,8 +23,16 @@ pub struct ContainerGui {
     inv_offset_y: f32,
     num_slots_x: usize,
     num_slots_y: usize,
+    // The player's inventory also shows their equip slots, beside the grid
+    show_equipment: bool,
 }
 
+// Where the equip slots go on the inventory background, in pixels - a column to the right of
+// the grid, one row per slot
+const EQUIP_LABEL_X: f32 = 536.0;
+const EQUIP_SLOT_X: f32 = 592.0;
+const EQUIP_SLOT_Y: f32 = 18.0;
+
 impl ContainerGui {
     pub fn loot_container() -> ContainerGui {
         ContainerGui {
@@ -33,6 +43,7 @@ impl ContainerGui {
             inv_offset_y: 160.0,
             num_slots_x: 4,
             num_slots_y: 4,
+            show_equipment: false,
         }
     }
 
@@ -45,6 +56,7 @@ impl ContainerGui {
             inv_offset_y: 18.0,
             num_slots_x: 15,
             num_slots_y: 3,
+            show_equipment: true,
         }
     }
 }
@@ -73,6 +85,11 @@ impl Gui<ContainerGuiState, ContainerGuiMsg> for ContainerGui {
                 .with_size(vec2(self.width, self.height)),
         ];
 
+        // Nothing to take out of a locked container
+        if is_locked(world, entity_id) {
+            return components;
+        }
+
         let mut contained_entities =
             script_util::get_all_links_with_data(world, entity_id, |link| match link {
                 Link::Contains(ordinal) => Some(*ordinal),
@@ -81,6 +98,14 @@ impl Gui<ContainerGuiState, ContainerGuiMsg> for ContainerGui {
 
         contained_entities.sort_by(|a, b| a.1.cmp(&b.1));
 
+        // Equipped items have their own slots, rather than a place in the grid
+        let equipment = world.borrow::<UniqueView<PlayerEquipment>>();
+        if self.show_equipment {
+            if let Ok(equipment) = &equipment {
+                contained_entities.retain(|(ent, _)| equipment.slot_of(*ent).is_none());
+            }
+        }
+
         let mut inventory = Inventory::new(self.num_slots_x, self.num_slots_y);
 
         let v_inv_dims = world.borrow::<View<PropInventoryDimensions>>().unwrap();
@@ -136,6 +161,34 @@ impl Gui<ContainerGuiState, ContainerGuiMsg> for ContainerGui {
             )
         }
 
+        if let (true, Ok(equipment)) = (self.show_equipment, &equipment) {
+            for (row, slot) in EquipSlot::ALL.iter().enumerate() {
+                let position_y = EQUIP_SLOT_Y + slot_pixel_height * row as f32;
+                let label = match slot.kind() {
+                    EquipmentKind::Armor => "equip-armor",
+                    EquipmentKind::Implant => "equip-implant",
+                };
+                components.push(
+                    gui::text(&localization::text(label))
+                        .with_position(vec2(EQUIP_LABEL_X, position_y + 8.0)),
+                );
+
+                let Some(item) = equipment.get(*slot) else {
+                    continue;
+                };
+                let Ok(obj_icon) = v_obj_icon.get(item.entity_id) else {
+                    continue;
+                };
+                // Click an equipped item to take it off
+                components.push(
+                    gui::button(ContainerGuiMsg::Frob(item.entity_id))
+                        .with_image(&format!("{}.pcx", obj_icon.0))
+                        .with_position(vec2(EQUIP_SLOT_X, position_y))
+                        .with_size(vec2(slot_pixel_width, slot_pixel_height)),
+                );
+            }
+        }
+
         if let Some(cursor) = maybe_cursor {
             if let Some(ent) = cursor.held_entity_id {
                 let maybe_obj_icon = v_obj_icon.get(ent);
@@ -5,6 +5,7 @@ use shipyard::{EntityId, World};
 use crate::gui::{Gui, GuiComponent, GuiConfig, GuiCursor};
 
 use crate::gui;
+use crate::localization;
 
 use crate::scripts::Effect;
 
@@ -33,11 +34,11 @@ impl Gui<ElevatorGuiState, ElevatorGuiMsg> for ElevatorGui {
         let button_padding = 4.0;
 
         let stops = vec![
-            ("rec1.mis", "elev50.pcx", "elev51.pcx", "5: Recreation"),
-            ("ops2.mis", "elev40.pcx", "elev41.pcx", "4: Operations"),
-            ("hydro2.mis", "elev30.pcx", "elev31.pcx", "3: Hydropondics"),
-            ("medsci1.mis", "elev20.pcx", "elev21.pcx", "2: Med/Sci"),
-            ("eng1.mis", "elev10.pcx", "elev11.pcx", "1: Engineering"),
+            ("rec1.mis", "elev50.pcx", "elev51.pcx", "elevator-deck-5"),
+            ("ops2.mis", "elev40.pcx", "elev41.pcx", "elevator-deck-4"),
+            ("hydro2.mis", "elev30.pcx", "elev31.pcx", "elevator-deck-3"),
+            ("medsci1.mis", "elev20.pcx", "elev21.pcx", "elevator-deck-2"),
+            ("eng1.mis", "elev10.pcx", "elev11.pcx", "elevator-deck-1"),
         ];
 
         let mut components: Vec<GuiComponent<ElevatorGuiMsg>> = vec![
@@ -58,7 +59,7 @@ impl Gui<ElevatorGuiState, ElevatorGuiMsg> for ElevatorGui {
             );
 
             components.push(
-                gui::text(label.to_owned())
+                gui::text(&localization::text(label))
                     .with_position(vec2(button_x + 60.0, button_y + 30.0))
                     .with_size(vec2(100.0, 20.0))
                     .with_alpha(0.7),
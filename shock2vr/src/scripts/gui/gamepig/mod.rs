@@ -5,6 +5,7 @@ use shipyard::{EntityId, World};
 use crate::gui::{Gui, GuiComponent, GuiConfig, GuiCursor};
 
 use crate::gui;
+use crate::localization;
 
 use crate::scripts::Effect;
 
@@ -31,11 +32,11 @@ impl Gui<GamePigGuiState, GamePigGuiMsg> for GamePigGui {
         let button_padding = 4.0;
 
         let stops = vec![
-            ("rec1.mis", "elev50.pcx", "elev51.pcx", "5: Recreation"),
-            ("ops2.mis", "elev40.pcx", "elev41.pcx", "4: Operations"),
-            ("hydro2.mis", "elev30.pcx", "elev31.pcx", "3: Hydropondics"),
-            ("medsci1.mis", "elev20.pcx", "elev21.pcx", "2: Med/Sci"),
-            ("eng1.mis", "elev10.pcx", "elev11.pcx", "1: Engineering"),
+            ("rec1.mis", "elev50.pcx", "elev51.pcx", "elevator-deck-5"),
+            ("ops2.mis", "elev40.pcx", "elev41.pcx", "elevator-deck-4"),
+            ("hydro2.mis", "elev30.pcx", "elev31.pcx", "elevator-deck-3"),
+            ("medsci1.mis", "elev20.pcx", "elev21.pcx", "elevator-deck-2"),
+            ("eng1.mis", "elev10.pcx", "elev11.pcx", "elevator-deck-1"),
         ];
 
         let mut components: Vec<GuiComponent<GamePigGuiMsg>> = vec![
@@ -46,7 +47,7 @@ impl Gui<GamePigGuiState, GamePigGuiMsg> for GamePigGui {
         ];
 
         components.push(
-            gui::text("GamePig")
+            gui::text(&localization::text("gamepig-title"))
                 .with_position(vec2(32.0, 48.0))
                 .with_size(vec2(100.0, 40.0))
                 .with_alpha(0.7),
@@ -63,7 +64,7 @@ impl Gui<GamePigGuiState, GamePigGuiMsg> for GamePigGui {
             // );
 
             components.push(
-                gui::text(label.to_owned())
+                gui::text(&localization::text(label))
                     .with_position(vec2(button_x + 60.0, button_y + 30.0))
                     .with_size(vec2(100.0, 20.0))
                     .with_alpha(0.7),
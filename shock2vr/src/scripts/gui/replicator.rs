@@ -12,6 +12,7 @@ use crate::{
 };
 
 use crate::gui;
+use crate::localization;
 
 use crate::scripts::{Effect, script_util::*};
 
@@ -83,7 +84,8 @@ impl Gui<ReplicatorState, ReplicatorMsg> for ReplicatorGui {
             components.push(replicator_icon(obj_icon, float_i));
 
             if let Some(short_name) = metadata.obj_short_name.as_ref() {
-                components.push(gui::text(short_name).with_position(vec2(
+                let short_name = localization::localize_game_string("objshort", short_name);
+                components.push(gui::text(&short_name).with_position(vec2(
                     50.0,
                     button_height / 2.0 + (button_height + button_padding) * float_i,
                 )));
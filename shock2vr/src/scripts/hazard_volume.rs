@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use cgmath::InnerSpace;
+use dark::properties::{
+    PropAI, PropDesignNote, PropPosition, PropTweqEmitterState, TweqAnimationState,
+};
+use shipyard::{EntityId, Get, UniqueView, View, World};
+use tracing::warn;
+
+use crate::{
+    hazards::{HazardConfig, HazardKind, HazardResistances},
+    mission::PlayerInfo,
+    physics::PhysicsWorld,
+    time::Time,
+    util::get_entity_position,
+};
+
+use super::{Effect, Message, MessagePayload, ParallelScript, Script};
+
+struct Exposure {
+    // Seconds until the next damage tick
+    next_tick: f32,
+    // Fractional damage carried over, so small resisted ticks still add up
+    pending_damage: f32,
+}
+
+///
+/// HazardVolume
+///
+/// Periodically damages the player, and creatures, inside the entity's volume - configured from
+/// the design note (see `HazardConfig::parse`). Steam jets and other emitters only hurt while
+/// their emitter tweq is running.
+pub struct HazardVolume {
+    default_kind: Option<HazardKind>,
+    config: Option<HazardConfig>,
+    is_on: bool,
+    occupants: HashMap<EntityId, Exposure>,
+}
+
+impl HazardVolume {
+    pub fn new() -> HazardVolume {
+        HazardVolume {
+            default_kind: None,
+            config: None,
+            is_on: false,
+            occupants: HashMap::new(),
+        }
+    }
+
+    /// A hazard of `kind` when the design note doesn't configure one, ie for `RadRoom`
+    pub fn with_kind(kind: HazardKind) -> HazardVolume {
+        HazardVolume {
+            default_kind: Some(kind),
+            ..HazardVolume::new()
+        }
+    }
+
+    fn is_emitting(&self, entity_id: EntityId, world: &World) -> bool {
+        let v_emitter_state = world.borrow::<View<PropTweqEmitterState>>().unwrap();
+        match v_emitter_state.get(entity_id) {
+            Ok(emitter_state) => emitter_state
+                .animation_state
+                .contains(TweqAnimationState::ON),
+            Err(_) => true,
+        }
+    }
+
+    fn enter(&mut self, entity_id: EntityId) {
+        self.occupants.entry(entity_id).or_insert(Exposure {
+            next_tick: 0.0,
+            pending_damage: 0.0,
+        });
+    }
+}
+
+impl ParallelScript for HazardVolume {}
+
+impl Script for HazardVolume {
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        Some(self)
+    }
+
+    fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
+        let v_design_note = world.borrow::<View<PropDesignNote>>().unwrap();
+        let maybe_config = v_design_note
+            .get(entity_id)
+            .ok()
+            .and_then(|design_note| HazardConfig::parse(&design_note.0));
+
+        self.config = maybe_config.or_else(|| self.default_kind.map(HazardConfig::new));
+        match &self.config {
+            Some(config) => self.is_on = config.starts_on,
+            None => warn!(
+                "HazardVolume on {:?} has no hazard in its design note, ie 'hazard=steam'",
+                entity_id
+            ),
+        }
+        Effect::NoEffect
+    }
+
+    fn update(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        time: &Time,
+    ) -> Effect {
+        let Some(config) = self.config.clone() else {
+            return Effect::NoEffect;
+        };
+
+        let player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+
+        // Without a volume of its own, the hazard reaches out to the player
+        if let Some(radius) = config.radius {
+            let v_position = world.borrow::<View<PropPosition>>().unwrap();
+            if let Ok(position) = v_position.get(entity_id) {
+                if (player.pos - position.position).magnitude2() <= radius * radius {
+                    self.enter(player.entity_id);
+                } else {
+                    self.occupants.remove(&player.entity_id);
+                }
+            }
+        }
+
+        if !self.is_on || self.occupants.is_empty() || !self.is_emitting(entity_id, world) {
+            return Effect::NoEffect;
+        }
+
+        let resistances = world
+            .borrow::<UniqueView<HazardResistances>>()
+            .map(|resistances| (*resistances).clone())
+            .unwrap_or_default();
+
+        // Damage comes from the hazard itself, for the player's damage direction indicator
+        let source = get_entity_position(world, entity_id);
+
+        let delta = time.elapsed.as_secs_f32();
+        let mut effects = Vec::new();
+        for (occupant, exposure) in self.occupants.iter_mut() {
+            exposure.next_tick -= delta;
+            if exposure.next_tick > 0.0 {
+                continue;
+            }
+            exposure.next_tick += config.interval;
+
+            if *occupant != player.entity_id {
+                effects.push(Effect::Send {
+                    msg: Message {
+                        to: *occupant,
+                        payload: MessagePayload::Damage {
+                            amount: config.damage,
+                            source,
+                        },
+                    },
+                });
+                continue;
+            }
+
+            exposure.pending_damage += resistances.reduce(config.kind, config.damage);
+            let damage = exposure.pending_damage.floor();
+            if damage >= 1.0 {
+                exposure.pending_damage -= damage;
+                effects.push(Effect::AdjustHitPoints {
+                    entity_id: *occupant,
+                    delta: -(damage as i32),
+                    source,
+                });
+            }
+
+            if let Some((kind, duration)) = config.kind.status_effect() {
+                let duration = resistances.reduce(config.kind, duration);
+                if duration > 0.0 {
+                    effects.push(Effect::ApplyStatusEffect { kind, duration });
+                }
+            }
+        }
+
+        Effect::combine(effects)
+    }
+
+    fn handle_message(
+        &mut self,
+        _entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::SensorBeginIntersect { with } => {
+                let player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+                let v_ai = world.borrow::<View<PropAI>>().unwrap();
+                if *with == player.entity_id || v_ai.contains(*with) {
+                    self.enter(*with);
+                }
+            }
+            MessagePayload::SensorEndIntersect { with } => {
+                self.occupants.remove(with);
+            }
+            MessagePayload::TurnOn { from: _ } => self.is_on = true,
+            MessagePayload::TurnOff { from: _ } => self.is_on = false,
+            _ => (),
+        }
+        Effect::NoEffect
+    }
+}
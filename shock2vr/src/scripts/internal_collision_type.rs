@@ -1,7 +1,7 @@
 use dark::properties::{CollisionType, PropCollisionType};
 use shipyard::{EntityId, Get, View, World};
 
-use crate::physics::PhysicsWorld;
+use crate::{physics::PhysicsWorld, util::get_entity_position};
 
 use super::{Effect, Message, MessagePayload, Script};
 
@@ -32,7 +32,7 @@ impl Script for InternalCollisionType {
     fn handle_message(
         &mut self,
         entity_id: EntityId,
-        _world: &World,
+        world: &World,
         _physics: &PhysicsWorld,
         msg: &MessagePayload,
     ) -> Effect {
@@ -53,7 +53,10 @@ impl Script for InternalCollisionType {
                 let damage_effect = Effect::Send {
                     msg: Message {
                         to: *with,
-                        payload: MessagePayload::Damage { amount: 1.0 },
+                        payload: MessagePayload::Damage {
+                            amount: 1.0,
+                            source: get_entity_position(world, entity_id),
+                        },
                     },
                 };
                 Effect::Multiple(vec![initial_effect, damage_effect])
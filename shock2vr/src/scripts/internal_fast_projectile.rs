@@ -1,5 +1,6 @@
 use cgmath::{
-    Deg, InnerSpace, Matrix4, Point3, Quaternion, Rotation3, SquareMatrix, Vector3, vec3, vec4,
+    Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, Quaternion, Rotation3, SquareMatrix, Vector3,
+    vec3, vec4,
 };
 use dark::{SCALE_FACTOR, properties::Link};
 
@@ -14,11 +15,12 @@ use crate::{
         Message, ai::ai_util::does_entity_have_hitboxes,
         script_util::get_first_link_with_template_and_data,
     },
+    statistics::StatisticEvent,
     time::Time,
     util::{get_position_from_transform, get_rotation_from_forward_vector},
 };
 
-use super::{Effect, MessagePayload, Script};
+use super::{Effect, MessagePayload, ParallelScript, Script};
 
 pub struct InternalFastProjectileScript {
     velocity: Vector3<f32>,
@@ -29,7 +31,13 @@ impl InternalFastProjectileScript {
     }
 }
 
+impl ParallelScript for InternalFastProjectileScript {}
+
 impl Script for InternalFastProjectileScript {
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        Some(self)
+    }
+
     fn update(
         &mut self,
         entity_id: EntityId,
@@ -75,7 +83,10 @@ impl Script for InternalFastProjectileScript {
                     msg: Message {
                         to: hit_entity_id,
                         // TODO: Properly calculate damage
-                        payload: MessagePayload::Damage { amount: 6.0 },
+                        payload: MessagePayload::Damage {
+                            amount: 6.0,
+                            source: Some(start_point.to_vec()),
+                        },
                     },
                 },
                 Effect::DrawDebugLines {
@@ -84,6 +95,12 @@ impl Script for InternalFastProjectileScript {
                 Effect::DestroyEntity { entity_id },
             ];
 
+            if did_hit_hitbox {
+                effects.push(Effect::RecordStatistic {
+                    event: StatisticEvent::ShotHit,
+                });
+            }
+
             let miss_spang = get_first_link_with_template_and_data(world, entity_id, |link| {
                 if matches!(link, Link::MissSpang) {
                     Some(())
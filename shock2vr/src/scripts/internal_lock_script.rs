@@ -0,0 +1,147 @@
+use dark::properties::{PropKeyDst, PropKeySrc, PropLocked, PropSymName};
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, Get, UniqueView, View, World};
+
+use crate::{
+    mission::PlayerInfo, physics::PhysicsWorld, quest_info::QuestInfo, statistics::StatisticEvent,
+};
+
+use super::{Effect, MessagePayload, Script, script_util::play_environmental_sound};
+
+// Consumable that picks any lock it's used on
+const ICE_PICK_SYM_NAME: &str = "ICE-Pick";
+
+///
+/// is_locked
+///
+/// Whether the entity has a lock that is currently locked - regardless of whether the player has the key
+pub fn is_locked(world: &World, entity_id: EntityId) -> bool {
+    let v_locked = world.borrow::<View<PropLocked>>().unwrap();
+    v_locked
+        .get(entity_id)
+        .map(|locked| locked.0)
+        .unwrap_or(false)
+}
+
+///
+/// has_key
+///
+/// Whether the player can open the entity's lock - with a keycard they've picked up,
+/// or a key item in either hand
+pub fn has_key(world: &World, entity_id: EntityId) -> bool {
+    let v_key_dst = world.borrow::<View<PropKeyDst>>().unwrap();
+    let Ok(key_dst) = v_key_dst.get(entity_id) else {
+        return false;
+    };
+
+    let quest_info = world.borrow::<UniqueView<QuestInfo>>().unwrap();
+    if quest_info.can_unlock(&key_dst.0) {
+        return true;
+    }
+
+    let player_info = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+    let v_key_src = world.borrow::<View<PropKeySrc>>().unwrap();
+    [
+        player_info.left_hand_entity_id,
+        player_info.right_hand_entity_id,
+    ]
+    .into_iter()
+    .flatten()
+    .any(|held_entity_id| {
+        v_key_src
+            .get(held_entity_id)
+            .map(|key_src| key_src.0.can_unlock(&key_dst.0))
+            .unwrap_or(false)
+    })
+}
+
+///
+/// is_blocked_by_lock
+///
+/// Locked, and the player has no key - doors, buttons and containers shouldn't respond to a frob.
+/// With a key, the frob goes through, and `LockScript` unlocks the entity.
+pub fn is_blocked_by_lock(world: &World, entity_id: EntityId) -> bool {
+    is_locked(world, entity_id) && !has_key(world, entity_id)
+}
+
+///
+/// LockScript
+///
+/// Added to every entity with a lock (PropLocked). Unlocks with a key on frob, or by picking
+/// the lock - hacking it, or using an ICE-pick on it.
+pub struct LockScript {}
+impl LockScript {
+    pub fn new() -> LockScript {
+        LockScript {}
+    }
+}
+
+impl Script for LockScript {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        if !is_locked(world, entity_id) {
+            return Effect::NoEffect;
+        }
+
+        match msg {
+            MessagePayload::Frob => {
+                if has_key(world, entity_id) {
+                    unlock(world, entity_id)
+                } else {
+                    Effect::PlaySound {
+                        handle: AudioHandle::new(),
+                        name: "hackfail".to_owned(),
+                    }
+                }
+            }
+            // TODO: There's no hacking minigame yet, so hacking always succeeds
+            MessagePayload::Hack => Effect::combine(vec![
+                unlock(world, entity_id),
+                Effect::RecordStatistic {
+                    event: StatisticEvent::HackAttempted { success: true },
+                },
+            ]),
+            MessagePayload::ProvideForConsumption { entity } => {
+                let v_sym_name = world.borrow::<View<PropSymName>>().unwrap();
+                let is_ice_pick = v_sym_name
+                    .get(*entity)
+                    .map(|sym_name| sym_name.0.eq_ignore_ascii_case(ICE_PICK_SYM_NAME))
+                    .unwrap_or(false);
+
+                if is_ice_pick {
+                    Effect::combine(vec![
+                        unlock(world, entity_id),
+                        Effect::DestroyEntity { entity_id: *entity },
+                        Effect::RecordStatistic {
+                            event: StatisticEvent::HackAttempted { success: true },
+                        },
+                    ])
+                } else {
+                    Effect::NoEffect
+                }
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
+
+fn unlock(world: &World, entity_id: EntityId) -> Effect {
+    Effect::combine(vec![
+        Effect::SetLocked {
+            entity_id,
+            locked: false,
+        },
+        play_environmental_sound(
+            world,
+            entity_id,
+            "statechange",
+            vec![("lockstate", "unlocked")],
+            AudioHandle::new(),
+        ),
+    ])
+}
@@ -0,0 +1,36 @@
+use shipyard::{EntityId, World};
+
+use crate::physics::PhysicsWorld;
+
+use super::{Effect, MessagePayload, Script};
+
+///
+/// LootScript
+///
+/// Added to anything with loot info - rolls its contents the first time it's opened
+pub struct LootScript {
+    has_rolled: bool,
+}
+impl LootScript {
+    pub fn new() -> LootScript {
+        LootScript { has_rolled: false }
+    }
+}
+
+impl Script for LootScript {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        _world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::Frob | MessagePayload::GUIHover { .. } if !self.has_rolled => {
+                self.has_rolled = true;
+                Effect::RollLoot { entity_id }
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
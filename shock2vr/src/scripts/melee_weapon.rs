@@ -1,6 +1,6 @@
 use shipyard::{EntityId, World};
 
-use crate::physics::PhysicsWorld;
+use crate::{physics::PhysicsWorld, util::get_entity_position};
 
 use super::{Effect, Message, MessagePayload, Script};
 
@@ -16,8 +16,8 @@ impl MeleeWeapon {
 impl Script for MeleeWeapon {
     fn handle_message(
         &mut self,
-        _entity_id: EntityId,
-        _world: &World,
+        entity_id: EntityId,
+        world: &World,
         _physics: &PhysicsWorld,
         msg: &MessagePayload,
     ) -> Effect {
@@ -25,7 +25,10 @@ impl Script for MeleeWeapon {
             MessagePayload::Collided { with } => Effect::Send {
                 msg: Message {
                     to: *with,
-                    payload: MessagePayload::Damage { amount: 1.0 },
+                    payload: MessagePayload::Damage {
+                        amount: 1.0,
+                        source: get_entity_position(world, entity_id),
+                    },
                 },
             },
             _ => Effect::NoEffect,
@@ -6,6 +6,7 @@ pub mod speech_util;
 mod base_button;
 mod base_elevator;
 mod base_monster;
+mod battery;
 mod choose_mission;
 mod choose_service;
 mod core_room;
@@ -13,11 +14,15 @@ mod create_sound;
 mod dead_power_cell;
 mod destroy_all_by_name;
 mod energy_station;
+mod equippable;
 mod frob_qb;
 mod gui;
+mod hazard_volume;
 mod internal_collision_type;
 pub mod internal_fast_projectile;
 mod internal_keycard_script;
+pub mod internal_lock_script;
+mod internal_loot;
 mod internal_simple_health;
 mod internal_switch_held_model;
 mod level_change_button;
@@ -29,8 +34,9 @@ mod once_router;
 mod room_trigger;
 pub mod script_util;
 mod setup_initial_debrief;
-mod std_door;
 mod tool_consumable;
+mod trans_mover;
+mod trap_condition;
 mod trap_delay;
 mod trap_destroyer;
 mod trap_email;
@@ -44,6 +50,8 @@ mod trap_qb_neg_filter;
 mod trap_qb_set;
 mod trap_questbit_simple;
 mod trap_router;
+mod trap_sequence;
+mod trap_set_var;
 mod trap_signal;
 mod trap_slayer;
 mod trap_sound;
@@ -57,15 +65,18 @@ mod tweq_depressable;
 mod tweqable;
 mod use_sound;
 mod weapon_script;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use cgmath::{Point2, Vector3};
-use dark::motion::MotionFlags;
+use dark::motion::{AnimationMarker, MotionFlags};
 pub use effect::*;
+use rayon::prelude::*;
 
 use shipyard::{EntityId, World};
 use tracing::{Level, info, span, warn};
 
+use crate::equipment::EquipmentKind;
+use crate::hazards::HazardKind;
 use crate::util::debug_entity;
 use crate::vr_config::Handedness;
 use crate::{physics::PhysicsWorld, time::Time};
@@ -78,19 +89,22 @@ use self::gui::{ContainerGui, ElevatorGui, GamePigGui, KeyPadGui, ReplicatorGui}
 use self::internal_switch_held_model::InternalSwitchHeldModelScript;
 use self::trap_signal::TrapSignal;
 use self::{
-    base_button::BaseButton, base_elevator::BaseElevator, base_monster::BaseMonster, core_room::*,
-    create_sound::*, dead_power_cell::DeadPowerCell, destroy_all_by_name::DestroyAllByName,
-    energy_station::EnergyStation, frob_qb::FrobQB, internal_collision_type::InternalCollisionType,
-    internal_keycard_script::KeyCardScript, internal_simple_health::InternalSimpleHealth,
+    base_button::BaseButton, base_elevator::BaseElevator, base_monster::BaseMonster,
+    battery::Battery, core_room::*, create_sound::*, dead_power_cell::DeadPowerCell,
+    destroy_all_by_name::DestroyAllByName, energy_station::EnergyStation, equippable::Equippable,
+    frob_qb::FrobQB, hazard_volume::HazardVolume, internal_collision_type::InternalCollisionType,
+    internal_keycard_script::KeyCardScript, internal_lock_script::LockScript,
+    internal_loot::LootScript, internal_simple_health::InternalSimpleHealth,
     level_change_button::LevelChangeButton, logdiscscript::LogDiscScript,
     melee_weapon::MeleeWeapon, obj_consume_button::ObjConsumeButton, once_room::OnceRoom,
-    once_router::OnceRouter, room_trigger::RoomTrigger, std_door::StdDoor,
-    tool_consumable::ToolConsumable, trap_delay::TrapDelay, trap_destroyer::TrapDestroyer,
-    trap_email::TrapEmail, trap_exp_once::TrapEXPOnce, trap_inverter::TrapInverter,
-    trap_new_tripwire::TrapNewTripwire, trap_on_filter::TrapOffFilter,
+    once_router::OnceRouter, room_trigger::RoomTrigger, tool_consumable::ToolConsumable,
+    trans_mover::TransDoor, trap_condition::TrapCondition, trap_delay::TrapDelay,
+    trap_destroyer::TrapDestroyer, trap_email::TrapEmail, trap_exp_once::TrapEXPOnce,
+    trap_inverter::TrapInverter, trap_new_tripwire::TrapNewTripwire, trap_on_filter::TrapOffFilter,
     trap_qb_filter::TrapQBFilter, trap_qb_neg_filter::TrapQBNegFilter, trap_qb_set::TrapQBSet,
-    trap_questbit_simple::TrapQuestbitSimple, trap_router::TrapRouter, trap_slayer::TrapSlayer,
-    trap_sound::TrapSound, trap_teleport::TrapTeleport, trap_teleport_player::TrapTeleportPlayer,
+    trap_questbit_simple::TrapQuestbitSimple, trap_router::TrapRouter, trap_sequence::TrapSequence,
+    trap_set_var::TrapSetVar, trap_slayer::TrapSlayer, trap_sound::TrapSound,
+    trap_teleport::TrapTeleport, trap_teleport_player::TrapTeleportPlayer,
     trap_trip_level::TrapTripLevel, trap_tweq::TrapTweq, trigger_collide::TriggerCollide,
     trigger_multi::TriggerMulti, tweq_depressable::TweqDepressable, tweqable::Tweqable,
     use_sound::UseSound, weapon_script::WeaponScript,
@@ -115,6 +129,10 @@ pub enum MessagePayload {
     AnimationFlagTriggered {
         motion_flags: MotionFlags,
     },
+    // Custom event from animation markup (animation_events.json)
+    AnimationMarker {
+        marker: AnimationMarker,
+    },
     AnimationCompleted,
 
     // Gameplay events
@@ -124,7 +142,12 @@ pub enum MessagePayload {
     }, // propose to consume this entity
     Damage {
         amount: f32,
+        /// Where the damage came from, if known - the attacker, projectile or hazard
+        source: Option<Vector3<f32>>,
     }, // damage the entity
+    Emp {
+        duration: f32,
+    }, // electromagnetic pulse - knocks out electronics (turrets, cameras) for a while
 
     // AI Signal
     Signal {
@@ -133,6 +156,10 @@ pub enum MessagePayload {
 
     Slay, // kill the entity
 
+    Hack, // player is picking the entity's lock
+
+    CutsceneCompleted, // a cutscene or sequence started by this entity finished (or was skipped)
+
     // Interaction events
     // Raw hover event
     Hover {
@@ -195,8 +222,27 @@ pub trait Script {
     ) -> Effect {
         Effect::NoEffect
     }
+
+    /// Scripts that implement `ParallelScript` should return `Some(self)` here,
+    /// so their `update` can be run in a parallel batch.
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        None
+    }
 }
 
+///
+/// ParallelScript
+///
+/// Marker for scripts whose `update` only reads the world and physics, and only affects
+/// the game through the effects it returns. These are updated in parallel batches before
+/// the rest of the scripts, but their effects keep their place in script order - so the frame
+/// plays out the same as if every script ran serially. Initialization and message handling are
+/// always serial.
+pub trait ParallelScript: Script + Send {}
+
+// Minimum number of parallel scripts handed to a single worker - smaller batches aren't worth the overhead
+const PARALLEL_SCRIPT_BATCH_SIZE: usize = 16;
+
 struct UnimplementedScript {
     name: String,
 }
@@ -347,14 +393,25 @@ pub struct ScriptWorld {
     entity_has_initialized: HashMap<EntityId, bool>,
     entity_to_scripts: HashMap<EntityId, Vec<Box<dyn Script>>>,
     message_queue: Vec<Message>,
+    // Messages received by the entity under the inspector, most recent last
+    watched_entity: Option<EntityId>,
+    watched_messages: VecDeque<String>,
+    // Scripts attached by name since the last `take_attached_scripts`, for entity events
+    attached_scripts: Vec<(EntityId, String)>,
 }
 
+// How many messages to keep for the watched entity
+const WATCHED_MESSAGE_HISTORY: usize = 8;
+
 impl ScriptWorld {
     pub fn new() -> ScriptWorld {
         ScriptWorld {
             entity_has_initialized: HashMap::new(),
             entity_to_scripts: HashMap::new(),
             message_queue: Vec::new(),
+            watched_entity: None,
+            watched_messages: VecDeque::new(),
+            attached_scripts: Vec::new(),
         }
     }
 
@@ -396,7 +453,7 @@ impl ScriptWorld {
             "changeinterface" => Box::new(NoopScript::new()),
             "reducehp" => Box::new(NoopScript::new()),
             "engineremoverad" => Box::new(NoopScript::new()),
-            "radroom" => Box::new(NoopScript::new()),
+            "radroom" => Box::new(HazardVolume::with_kind(HazardKind::Radiation)),
             "trapspawn" => Box::new(NoopScript::new()),
             // ops1 cutscene
             "transluceinoutholo" => Box::new(NoopScript::new()),
@@ -410,8 +467,8 @@ impl ScriptWorld {
             "triggerdamage" => Box::new(NoopScript::new()),
             // many.micontain
             "brain" => Box::new(NoopScript::new()),
-            "wormheartimplant" => Box::new(NoopScript::new()),
-            "wormskin" => Box::new(NoopScript::new()),
+            "wormheartimplant" => Box::new(Equippable::new(EquipmentKind::Implant)),
+            "wormskin" => Box::new(Equippable::new(EquipmentKind::Armor)),
             // shodan.mis
             "toggleshodantexture" => Box::new(NoopScript::new()),
             "changedelay" => Box::new(NoopScript::new()), //?
@@ -436,6 +493,8 @@ impl ScriptWorld {
             "internal_inventory" => gui_script(Box::new(ContainerGui::inv_container())),
             // "internal_inventory" => Box::new(PanicOnLoadScript::new("internal_inventory")),
             "internal_keycard" => Box::new(KeyCardScript::new()),
+            "internal_lock" => Box::new(LockScript::new()),
+            "internal_loot" => Box::new(LootScript::new()),
             "internal_room_trigger" => Box::new(RoomTrigger::new()),
             "internal_simple_health" => Box::new(InternalSimpleHealth::new()),
             // Implemented
@@ -445,7 +504,7 @@ impl ScriptWorld {
             "levelchangebutton" => Box::new(LevelChangeButton::new()),
             "logdiscscript" => Box::new(LogDiscScript::new()),
             "oncerouter" => Box::new(OnceRouter::new()),
-            "stddoor" => Box::new(StdDoor::new()),
+            "stddoor" => Box::new(TransDoor::new()),
             "trapdelay" => Box::new(TrapDelay::new()),
             "trapterminator" => Box::new(TrapDestroyer::new()), // TODO: What is the difference between Terminate vs Destroyer vs Destroy?
             "trapdestroyer" => Box::new(TrapDestroyer::new()),
@@ -497,6 +556,12 @@ impl ScriptWorld {
             "trapqbset" => Box::new(TrapQBSet::new()),
             "trapquestbitsimple" => Box::new(TrapQuestbitSimple::new()),
 
+            // Global variables / conditions (design note driven):
+            "trapcondition" => Box::new(TrapCondition::new()),
+            "trapsetvar" => Box::new(TrapSetVar::new()),
+            "trapsequence" => Box::new(TrapSequence::new()),
+            "hazardvolume" => Box::new(HazardVolume::new()),
+
             // TODO:
             "simplelevelchangebutton" => Box::new(UnimplementedScript::new(&script_name)), // rec1
             "freezefx" => Box::new(UnimplementedScript::new(&script_name)), // command1
@@ -551,7 +616,7 @@ impl ScriptWorld {
             "autoinstallsoft" => Box::new(UnimplementedScript::new(&script_name)), // auto install software
             "strboost" => Box::new(UnimplementedScript::new(&script_name)),        // strength boost
             "intboost" => Box::new(UnimplementedScript::new(&script_name)),
-            "statboostimplant" => Box::new(UnimplementedScript::new(&script_name)),
+            "statboostimplant" => Box::new(Equippable::new(EquipmentKind::Implant)),
 
             // earth:
             "comestible" => Box::new(UnimplementedScript::new(&script_name)),
@@ -581,8 +646,8 @@ impl ScriptWorld {
             "forcedoor" => Box::new(UnimplementedScript::new(&script_name)),
             "wormpilescript" => Box::new(UnimplementedScript::new(&script_name)),
             "trapradcleanse" => Box::new(UnimplementedScript::new(&script_name)),
-            "armorscript" => Box::new(NoopScript::new()),
-            "battery" => Box::new(UnimplementedScript::new(&script_name)),
+            "armorscript" => Box::new(Equippable::new(EquipmentKind::Armor)),
+            "battery" => Box::new(Battery::new()),
             "healingstation" => Box::new(UnimplementedScript::new(&script_name)),
             "brokenhealingstation" => Box::new(UnimplementedScript::new(&script_name)),
 
@@ -612,7 +677,7 @@ impl ScriptWorld {
             "freehack" => Box::new(UnimplementedScript::new(&script_name)),
 
             // hydro3
-            "poweredarmor" => Box::new(UnimplementedScript::new(&script_name)),
+            "poweredarmor" => Box::new(Equippable::new(EquipmentKind::Armor)),
 
             // ops2
             "slotmachine" => Box::new(UnimplementedScript::new(&script_name)),
@@ -621,7 +686,7 @@ impl ScriptWorld {
             // elevator buttons
             "elevatorbutton" => gui_script(Box::new(ElevatorGui)),
             "pictureswap" => Box::new(NoopScript::new()),
-            "testimplant" => Box::new(UnimplementedScript::new(&script_name)),
+            "testimplant" => Box::new(Equippable::new(EquipmentKind::Implant)),
 
             // ric2:
             "shakeyourbooty" => Box::new(UnimplementedScript::new(&script_name)), // what does this one do?
@@ -673,6 +738,13 @@ impl ScriptWorld {
     pub fn add_entity(&mut self, entity_id: EntityId, script_name: &str) {
         let script = Self::create_script(script_name.to_ascii_lowercase());
         self.add_entity2(entity_id, script);
+        self.attached_scripts
+            .push((entity_id, script_name.to_owned()));
+    }
+
+    /// Take the scripts attached by name since the last call - internal scripts aren't included
+    pub fn take_attached_scripts(&mut self) -> Vec<(EntityId, String)> {
+        std::mem::take(&mut self.attached_scripts)
     }
 
     pub fn add_entity2(&mut self, entity_id: EntityId, script: Box<dyn Script>) {
@@ -689,6 +761,19 @@ impl ScriptWorld {
         self.entity_has_initialized.remove(&entity_id);
     }
 
+    ///
+    /// watch_entity
+    ///
+    /// Start keeping a short history of the messages sent to an entity (for the entity inspector)
+    pub fn watch_entity(&mut self, entity_id: Option<EntityId>) {
+        self.watched_entity = entity_id;
+        self.watched_messages.clear();
+    }
+
+    pub fn watched_messages(&self) -> &VecDeque<String> {
+        &self.watched_messages
+    }
+
     pub fn dispatch(&mut self, message: Message) {
         self.message_queue.push(message);
     }
@@ -719,6 +804,14 @@ impl ScriptWorld {
         for msg in &self.message_queue {
             let to_entity_id = msg.to;
 
+            if self.watched_entity == Some(to_entity_id) {
+                if self.watched_messages.len() >= WATCHED_MESSAGE_HISTORY {
+                    self.watched_messages.pop_front();
+                }
+                self.watched_messages
+                    .push_back(format!("{:?}", msg.payload));
+            }
+
             if matches!(msg.payload, MessagePayload::Slay) {
                 slayed_entities.insert(to_entity_id);
             }
@@ -755,9 +848,34 @@ impl ScriptWorld {
 
         self.message_queue.clear();
 
+        // Update phase 1: read-only scripts, in parallel batches. Their effects are held back, and
+        // merged in below, so results don't depend on scheduling.
+        let mut parallel_scripts = self
+            .entity_to_scripts
+            .iter_mut()
+            .flat_map(|(entity_id, scripts)| {
+                scripts
+                    .iter_mut()
+                    .filter_map(move |script| script.as_parallel().map(|s| (*entity_id, s)))
+            })
+            .collect::<Vec<(EntityId, &mut dyn ParallelScript)>>();
+
+        let mut parallel_effects = parallel_scripts
+            .par_iter_mut()
+            .with_min_len(PARALLEL_SCRIPT_BATCH_SIZE)
+            .map(|(entity_id, script)| script.update(*entity_id, world, physics, time))
+            .collect::<Vec<Effect>>()
+            .into_iter();
+
+        // Update phase 2: everything else, serially - with the parallel effects slotted back in
+        // script order, so effects on the same entity resolve as if every script ran serially
         for (entity_id, scripts) in self.entity_to_scripts.iter_mut() {
             for script in scripts.iter_mut() {
-                let eff = script.update(*entity_id, world, physics, time);
+                let eff = if script.as_parallel().is_some() {
+                    parallel_effects.next().unwrap_or(Effect::NoEffect)
+                } else {
+                    script.update(*entity_id, world, physics, time)
+                };
                 produced_effects.push(eff);
             }
         }
@@ -777,3 +895,50 @@ impl ScriptWorld {
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SerialXp(i32);
+
+    impl Script for SerialXp {
+        fn update(&mut self, _: EntityId, _: &World, _: &PhysicsWorld, _: &Time) -> Effect {
+            Effect::AwardXP { amount: self.0 }
+        }
+    }
+
+    struct ParallelXp(i32);
+
+    impl ParallelScript for ParallelXp {}
+
+    impl Script for ParallelXp {
+        fn update(&mut self, _: EntityId, _: &World, _: &PhysicsWorld, _: &Time) -> Effect {
+            Effect::AwardXP { amount: self.0 }
+        }
+
+        fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+            Some(self)
+        }
+    }
+
+    #[test]
+    fn test_parallel_effects_keep_script_order() {
+        let mut world = World::new();
+        let entity_id = world.add_entity(());
+        let mut script_world = ScriptWorld::new();
+        script_world.add_entity2(entity_id, Box::new(SerialXp(1)));
+        script_world.add_entity2(entity_id, Box::new(ParallelXp(2)));
+        script_world.add_entity2(entity_id, Box::new(SerialXp(3)));
+
+        let effects = script_world.update(&world, &PhysicsWorld::new(), &Time::default());
+        let amounts = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::AwardXP { amount } => Some(*amount),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(amounts, vec![1, 2, 3]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::mission::entity_creator::initialize_entity_with_props;
+use crate::mission::{PlayerInfo, entity_creator::initialize_entity_with_props};
 use crate::{runtime_props::RuntimePropTransform, util::point3_to_vec3};
 use cgmath::{Transform, point3};
 use dark::{
@@ -9,7 +9,7 @@ use dark::{
     ss2_entity_info::SystemShock2EntityInfo,
 };
 use engine::audio::AudioHandle;
-use shipyard::{Component, EntityId, Get, IntoIter, IntoWithId, View, World};
+use shipyard::{Component, EntityId, Get, IntoIter, IntoWithId, UniqueView, View, World};
 use std::collections::HashMap;
 
 use super::{Effect, Message, MessagePayload};
@@ -100,6 +100,19 @@ pub fn get_all_links_of_type(
     linked_entities
 }
 
+pub fn is_in_player_inventory(world: &World, entity_id: EntityId) -> bool {
+    let inventory_entity_id = world
+        .borrow::<UniqueView<PlayerInfo>>()
+        .unwrap()
+        .inventory_entity_id;
+    get_all_links_with_data(world, inventory_entity_id, |link| match link {
+        Link::Contains(_) => Some(()),
+        _ => None,
+    })
+    .iter()
+    .any(|(contained_entity_id, _)| *contained_entity_id == entity_id)
+}
+
 pub fn get_entities_by_name(world: &World, name: &str) -> Vec<EntityId> {
     let mut entities = Vec::new();
     world.run(|v_prop_symyname: View<PropSymName>| {
@@ -221,6 +234,7 @@ pub fn play_environmental_sound(
             audio_handle,
             query,
             position: point3_to_vec3(position),
+            entity_id: Some(entity_id),
         }
     } else {
         Effect::NoEffect
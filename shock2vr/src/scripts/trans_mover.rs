@@ -0,0 +1,419 @@
+use cgmath::{InnerSpace, Vector3, Zero};
+use dark::{SCALE_FACTOR, properties::PropTranslatingDoor};
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, Get, UniqueView, View, World};
+use tracing::trace;
+
+use crate::{mission::PlayerInfo, physics::PhysicsWorld, time::Time};
+
+use super::{
+    Effect, MessagePayload, Script,
+    internal_lock_script::is_blocked_by_lock,
+    script_util::{get_environmental_sound_query, play_environmental_sound},
+};
+
+// How close the player can get to a closing mover before it is considered blocked
+const BLOCKED_MARGIN: f32 = 1.0 / SCALE_FACTOR;
+
+// How long a blocked mover waits before reversing back to open, in seconds
+const BLOCKED_REVERSE_DELAY: f32 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransMoverState {
+    Closed,
+    Opening,
+    Open,
+    Closing,
+    Blocked,
+}
+
+impl TransMoverState {
+    ///
+    /// schema_tag
+    ///
+    /// The value used for the 'openstate' tag of the statechange sound schema
+    fn schema_tag(&self) -> &'static str {
+        match self {
+            TransMoverState::Closed => "closed",
+            TransMoverState::Opening => "opening",
+            TransMoverState::Open => "open",
+            TransMoverState::Closing => "closing",
+            // Dark doesn't have a schema for blocked - it sounds like a door stopping
+            TransMoverState::Blocked => "open",
+        }
+    }
+}
+
+///
+/// TransMover
+///
+/// Reusable state machine for moving terrain - doors and elevators. Moves the entity toward a
+/// target location at a given speed, looping the move sound until it arrives. While closing,
+/// the player being in the way stops the mover, and it reverses back to where it was opening to.
+///
+pub struct TransMover {
+    state: TransMoverState,
+    current_position: Vector3<f32>,
+    target_position: Vector3<f32>,
+    reverse_position: Vector3<f32>,
+    speed: f32,
+    blocked_time: f32,
+
+    /// Handle for the sound looping while moving, stopped when the mover arrives
+    move_audio_handle: AudioHandle,
+}
+
+impl TransMover {
+    pub fn new(position: Vector3<f32>) -> TransMover {
+        TransMover {
+            state: TransMoverState::Closed,
+            current_position: position,
+            target_position: position,
+            reverse_position: position,
+            speed: 0.0,
+            blocked_time: 0.0,
+            move_audio_handle: AudioHandle::new(),
+        }
+    }
+
+    pub fn state(&self) -> TransMoverState {
+        self.state
+    }
+
+    pub fn is_moving(&self) -> bool {
+        matches!(
+            self.state,
+            TransMoverState::Opening | TransMoverState::Closing | TransMoverState::Blocked
+        )
+    }
+
+    ///
+    /// open
+    ///
+    /// Start moving toward the open location, or the next stop of an elevator
+    pub fn open(
+        &mut self,
+        world: &World,
+        entity_id: EntityId,
+        target: Vector3<f32>,
+        speed: f32,
+    ) -> Effect {
+        self.target_position = target;
+        self.speed = speed;
+        self.transition(world, entity_id, TransMoverState::Opening)
+    }
+
+    ///
+    /// close
+    ///
+    /// Start moving toward the closed location - if blocked on the way, the mover goes back
+    /// to the location it was last opening to
+    pub fn close(
+        &mut self,
+        world: &World,
+        entity_id: EntityId,
+        target: Vector3<f32>,
+        speed: f32,
+    ) -> Effect {
+        if self.state != TransMoverState::Blocked {
+            self.reverse_position = self.target_position;
+        }
+        self.target_position = target;
+        self.speed = speed;
+        self.transition(world, entity_id, TransMoverState::Closing)
+    }
+
+    ///
+    /// transition
+    ///
+    /// Switch to a new state - the opening / closing sound loops while moving, and the
+    /// open / closed sound plays on arrival
+    fn transition(
+        &mut self,
+        world: &World,
+        entity_id: EntityId,
+        new_state: TransMoverState,
+    ) -> Effect {
+        let old_state = self.state;
+        if old_state == new_state {
+            return Effect::NoEffect;
+        }
+
+        trace!(
+            "TransMover {:?}: {:?} -> {:?}",
+            entity_id, old_state, new_state
+        );
+        self.state = new_state;
+
+        // Any state change ends the current movement sound
+        let stop_effect = Effect::StopSound {
+            handle: self.move_audio_handle.clone(),
+        };
+
+        let tags = vec![
+            ("openstate", new_state.schema_tag()),
+            ("oldopenstate", old_state.schema_tag()),
+        ];
+
+        let sound_effect = if matches!(
+            new_state,
+            TransMoverState::Opening | TransMoverState::Closing
+        ) {
+            // Keep a fresh handle around so the loop can be stopped on arrival
+            self.move_audio_handle = AudioHandle::new();
+            match get_environmental_sound_query(world, entity_id, "statechange", tags) {
+                Some(query) => Effect::LoopEnvironmentalSound {
+                    audio_handle: self.move_audio_handle.clone(),
+                    query,
+                    entity_id,
+                },
+                None => Effect::NoEffect,
+            }
+        } else {
+            play_environmental_sound(world, entity_id, "statechange", tags, AudioHandle::new())
+        };
+
+        Effect::combine(vec![stop_effect, sound_effect])
+    }
+
+    fn is_player_blocking(
+        &self,
+        entity_id: EntityId,
+        world: &World,
+        physics: &PhysicsWorld,
+    ) -> bool {
+        let u_player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+        let player_pos = u_player.pos;
+
+        if let Some(aabb) = physics.get_aabb2(entity_id) {
+            player_pos.x > aabb.min.x - BLOCKED_MARGIN
+                && player_pos.x < aabb.max.x + BLOCKED_MARGIN
+                && player_pos.y > aabb.min.y - BLOCKED_MARGIN
+                && player_pos.y < aabb.max.y + BLOCKED_MARGIN
+                && player_pos.z > aabb.min.z - BLOCKED_MARGIN
+                && player_pos.z < aabb.max.z + BLOCKED_MARGIN
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// update
+    ///
+    /// Step the mover toward its target, returning the effects for the new position and any
+    /// state change
+    pub fn update(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        physics: &PhysicsWorld,
+        time: &Time,
+    ) -> Effect {
+        let delta = time.elapsed.as_secs_f32();
+
+        match self.state {
+            TransMoverState::Closed | TransMoverState::Open => Effect::NoEffect,
+            TransMoverState::Blocked => {
+                self.blocked_time += delta;
+                if self.blocked_time >= BLOCKED_REVERSE_DELAY {
+                    self.target_position = self.reverse_position;
+                    self.transition(world, entity_id, TransMoverState::Opening)
+                } else {
+                    Effect::NoEffect
+                }
+            }
+            TransMoverState::Opening | TransMoverState::Closing => {
+                if self.state == TransMoverState::Closing
+                    && self.is_player_blocking(entity_id, world, physics)
+                {
+                    self.blocked_time = 0.0;
+                    return self.transition(world, entity_id, TransMoverState::Blocked);
+                }
+
+                let target = self.target_position;
+                let dir = target - self.current_position;
+                let step = self.speed * delta;
+
+                if dir.magnitude() <= step || dir.magnitude2() < 0.001 {
+                    self.current_position = target;
+                    let arrived_state = if self.state == TransMoverState::Opening {
+                        TransMoverState::Open
+                    } else {
+                        TransMoverState::Closed
+                    };
+                    Effect::combine(vec![
+                        Effect::SetPosition {
+                            entity_id,
+                            position: target,
+                        },
+                        self.transition(world, entity_id, arrived_state),
+                    ])
+                } else {
+                    self.current_position += dir.normalize() * step;
+                    Effect::SetPosition {
+                        entity_id,
+                        position: self.current_position,
+                    }
+                }
+            }
+        }
+    }
+}
+
+///
+/// TransDoor
+///
+/// Script for translating doors - drives a TransMover between the closed and open locations of
+/// the PropTranslatingDoor. Doors without the property (ie rotating doors) are left alone.
+///
+/// Besides TurnOn / TurnOff from switches, frobbing the door itself toggles it open or closed,
+/// unless it is locked and the player has no key.
+///
+pub struct TransDoor {
+    mover: Option<TransMover>,
+    closed_position: Vector3<f32>,
+    open_position: Vector3<f32>,
+    speed: f32,
+}
+
+impl TransDoor {
+    pub fn new() -> TransDoor {
+        TransDoor {
+            mover: None,
+            closed_position: Vector3::zero(),
+            open_position: Vector3::zero(),
+            speed: 0.0,
+        }
+    }
+
+    fn open(&mut self, world: &World, entity_id: EntityId) -> Effect {
+        let (open_position, speed) = (self.open_position, self.speed);
+        match self.mover.as_mut() {
+            Some(mover) => mover.open(world, entity_id, open_position, speed),
+            None => Effect::NoEffect,
+        }
+    }
+
+    fn close(&mut self, world: &World, entity_id: EntityId) -> Effect {
+        let (closed_position, speed) = (self.closed_position, self.speed);
+        match self.mover.as_mut() {
+            Some(mover) => mover.close(world, entity_id, closed_position, speed),
+            None => Effect::NoEffect,
+        }
+    }
+}
+
+impl Script for TransDoor {
+    fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
+        let v_trans_door = world.borrow::<View<PropTranslatingDoor>>().unwrap();
+        if let Ok(trans_door) = v_trans_door.get(entity_id) {
+            self.closed_position = trans_door.base_closed_location;
+            self.open_position = trans_door.base_open_location;
+            self.speed = trans_door.speed;
+            self.mover = Some(TransMover::new(trans_door.base_closed_location));
+
+            Effect::SetPosition {
+                entity_id,
+                position: trans_door.base_closed_location,
+            }
+        } else {
+            Effect::NoEffect
+        }
+    }
+
+    fn update(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        physics: &PhysicsWorld,
+        time: &Time,
+    ) -> Effect {
+        match self.mover.as_mut() {
+            Some(mover) => mover.update(entity_id, world, physics, time),
+            None => Effect::NoEffect,
+        }
+    }
+
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        let Some(state) = self.mover.as_ref().map(|mover| mover.state()) else {
+            return Effect::NoEffect;
+        };
+
+        match msg {
+            MessagePayload::Frob => {
+                if is_blocked_by_lock(world, entity_id) {
+                    return Effect::NoEffect;
+                }
+                match state {
+                    TransMoverState::Closed
+                    | TransMoverState::Closing
+                    | TransMoverState::Blocked => self.open(world, entity_id),
+                    TransMoverState::Open | TransMoverState::Opening => {
+                        self.close(world, entity_id)
+                    }
+                }
+            }
+            MessagePayload::TurnOn { from: _ } => match state {
+                TransMoverState::Closed | TransMoverState::Closing | TransMoverState::Blocked => {
+                    self.open(world, entity_id)
+                }
+                TransMoverState::Opening | TransMoverState::Open => Effect::NoEffect,
+            },
+            MessagePayload::TurnOff { from: _ } => match state {
+                TransMoverState::Open | TransMoverState::Opening => self.close(world, entity_id),
+                TransMoverState::Closed | TransMoverState::Closing | TransMoverState::Blocked => {
+                    Effect::NoEffect
+                }
+            },
+            _ => Effect::NoEffect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn door_world() -> (World, EntityId) {
+        let mut world = World::new();
+        let entity_id = world.add_entity(PropTranslatingDoor {
+            door_type: 0,
+            closed: 0.0,
+            open: 1.0,
+            speed: 1.0,
+            axis: 0,
+            base_closed_location: Vector3::zero(),
+            base_open_location: Vector3::new(0.0, 1.0, 0.0),
+            base_location: Vector3::zero(),
+        });
+        (world, entity_id)
+    }
+
+    fn door_state(door: &TransDoor) -> TransMoverState {
+        door.mover.as_ref().unwrap().state()
+    }
+
+    #[test]
+    fn test_frob_toggles_door() {
+        let (world, entity_id) = door_world();
+        let physics = PhysicsWorld::new();
+        let mut door = TransDoor::new();
+        door.initialize(entity_id, &world);
+        assert_eq!(door_state(&door), TransMoverState::Closed);
+
+        door.handle_message(entity_id, &world, &physics, &MessagePayload::Frob);
+        assert_eq!(door_state(&door), TransMoverState::Opening);
+
+        door.handle_message(entity_id, &world, &physics, &MessagePayload::Frob);
+        assert_eq!(door_state(&door), TransMoverState::Closing);
+
+        door.handle_message(entity_id, &world, &physics, &MessagePayload::Frob);
+        assert_eq!(door_state(&door), TransMoverState::Opening);
+    }
+}
@@ -0,0 +1,64 @@
+use dark::properties::PropDesignNote;
+use shipyard::{EntityId, Get, UniqueView, View, World};
+use tracing::{info, warn};
+
+use crate::{conditions::Condition, physics::PhysicsWorld, quest_info::QuestInfo};
+
+use super::{
+    Effect, MessagePayload, Script,
+    script_util::{is_message_turnon_or_turnoff, send_to_all_switch_links},
+};
+
+///
+/// TrapCondition
+///
+/// Like `TrapQBFilter`, but the check is an expression in the entity's design note,
+/// ie `qb('found_cyborg') == 1 && var('power_on')` - TurnOn / TurnOff only pass through
+/// to the switch links while it holds
+pub struct TrapCondition {
+    // Parsed on first use - `None` if the design note is missing or invalid
+    condition: Option<Option<Condition>>,
+}
+impl TrapCondition {
+    pub fn new() -> TrapCondition {
+        TrapCondition { condition: None }
+    }
+}
+impl Script for TrapCondition {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        if !is_message_turnon_or_turnoff(msg) {
+            return Effect::NoEffect;
+        }
+
+        let condition = self.condition.get_or_insert_with(|| {
+            let v_design_note = world.borrow::<View<PropDesignNote>>().unwrap();
+            let source = v_design_note
+                .get(entity_id)
+                .map(|note| note.0.clone())
+                .ok()?;
+            Condition::parse(&source)
+                .map_err(|err| warn!("TrapCondition on {:?}: {}", entity_id, err))
+                .ok()
+        });
+
+        let Some(condition) = condition else {
+            return Effect::NoEffect;
+        };
+
+        let quest_info = world.borrow::<UniqueView<QuestInfo>>().unwrap();
+        let passes = condition.is_true(&quest_info);
+        info!("evaluated condition {:?} - {}", condition, passes);
+
+        if passes {
+            send_to_all_switch_links(world, entity_id, msg.clone())
+        } else {
+            Effect::NoEffect
+        }
+    }
+}
@@ -4,7 +4,9 @@ use tracing::info;
 
 use crate::{physics::PhysicsWorld, scripts::script_util::template_id_string, time::Time};
 
-use super::{Effect, MessagePayload, Script, script_util::send_to_all_switch_links};
+use super::{
+    Effect, MessagePayload, ParallelScript, Script, script_util::send_to_all_switch_links,
+};
 
 pub struct TrapDelay {
     delay_time_in_seconds: f32,
@@ -18,7 +20,13 @@ impl TrapDelay {
         }
     }
 }
+impl ParallelScript for TrapDelay {}
+
 impl Script for TrapDelay {
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        Some(self)
+    }
+
     fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
         let v_delay_time = world.borrow::<View<PropDelayTime>>().unwrap();
         let delay_time = if let Ok(v) = v_delay_time.get(entity_id) {
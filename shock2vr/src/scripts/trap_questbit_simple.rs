@@ -4,7 +4,9 @@ use tracing::info;
 
 use crate::{physics::PhysicsWorld, quest_info::QuestInfo, time::Time};
 
-use super::{Effect, MessagePayload, Script, script_util::send_to_all_switch_links};
+use super::{
+    Effect, MessagePayload, ParallelScript, Script, script_util::send_to_all_switch_links,
+};
 
 pub struct TrapQuestbitSimple {
     qb_name: String,
@@ -18,7 +20,13 @@ impl TrapQuestbitSimple {
         }
     }
 }
+impl ParallelScript for TrapQuestbitSimple {}
+
 impl Script for TrapQuestbitSimple {
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        Some(self)
+    }
+
     fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
         let v_qbname = world.borrow::<View<PropQuestBitName>>().unwrap();
         let qb_name = &v_qbname.get(entity_id).unwrap().0;
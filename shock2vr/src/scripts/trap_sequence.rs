@@ -0,0 +1,63 @@
+use dark::properties::PropDesignNote;
+use shipyard::{EntityId, Get, View, World};
+use tracing::warn;
+
+use crate::physics::PhysicsWorld;
+
+use super::{Effect, MessagePayload, Script, script_util::send_to_all_switch_links};
+
+///
+/// TrapSequence
+///
+/// Plays a scripted sequence (see `sequences`) when turned on - the sequence's id is in the
+/// entity's design note, ie `sequence=many_vision`. Once it's over, or skipped, the trap turns
+/// on everything it's switch linked to.
+pub struct TrapSequence {}
+impl TrapSequence {
+    pub fn new() -> TrapSequence {
+        TrapSequence {}
+    }
+}
+impl Script for TrapSequence {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::TurnOn { from: _ } => {
+                let v_design_note = world.borrow::<View<PropDesignNote>>().unwrap();
+                let maybe_sequence = v_design_note.get(entity_id).ok().and_then(|design_note| {
+                    design_note
+                        .0
+                        .split([';', ','])
+                        .filter_map(|setting| setting.split_once('='))
+                        .find(|(name, _)| name.trim().eq_ignore_ascii_case("sequence"))
+                        .map(|(_, value)| value.trim().to_owned())
+                });
+
+                match maybe_sequence {
+                    Some(sequence) => Effect::PlaySequence {
+                        sequence,
+                        from: entity_id,
+                    },
+                    None => {
+                        warn!(
+                            "TrapSequence on {:?} has no sequence in its design note, ie 'sequence=many_vision'",
+                            entity_id
+                        );
+                        Effect::NoEffect
+                    }
+                }
+            }
+            MessagePayload::CutsceneCompleted => send_to_all_switch_links(
+                world,
+                entity_id,
+                MessagePayload::TurnOn { from: entity_id },
+            ),
+            _ => Effect::NoEffect,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use dark::properties::PropDesignNote;
+use shipyard::{EntityId, Get, View, World};
+use tracing::warn;
+
+use crate::{physics::PhysicsWorld, quest_info::GlobalValue};
+
+use super::{Effect, MessagePayload, Script};
+
+///
+/// TrapSetVar
+///
+/// Sets global variables from the entity's design note when turned on - `name=value`,
+/// separated by `;` or commas, ie `power_on=true; pods_found=2`
+pub struct TrapSetVar {}
+impl TrapSetVar {
+    pub fn new() -> TrapSetVar {
+        TrapSetVar {}
+    }
+}
+impl Script for TrapSetVar {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::TurnOn { from: _ } => {
+                let v_design_note = world.borrow::<View<PropDesignNote>>().unwrap();
+                let Ok(design_note) = v_design_note.get(entity_id) else {
+                    return Effect::NoEffect;
+                };
+
+                let effects = design_note
+                    .0
+                    .split([';', ','])
+                    .filter(|assignment| !assignment.trim().is_empty())
+                    .filter_map(|assignment| match assignment.split_once('=') {
+                        Some((name, value)) => Some(Effect::SetGlobalVariable {
+                            name: name.trim().to_owned(),
+                            value: GlobalValue::parse(value),
+                        }),
+                        None => {
+                            warn!(
+                                "TrapSetVar on {:?}: expected name=value, found '{}'",
+                                entity_id, assignment
+                            );
+                            None
+                        }
+                    })
+                    .collect();
+
+                Effect::Combined { effects }
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
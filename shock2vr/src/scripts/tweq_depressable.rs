@@ -3,7 +3,7 @@ use shipyard::{EntityId, World};
 use crate::{physics::PhysicsWorld, time::Time};
 
 use super::{
-    Effect, MessagePayload, Script,
+    Effect, MessagePayload, ParallelScript, Script,
     script_util::{change_to_first_model, change_to_last_model},
 };
 
@@ -17,7 +17,13 @@ impl TweqDepressable {
         }
     }
 }
+impl ParallelScript for TweqDepressable {}
+
 impl Script for TweqDepressable {
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelScript> {
+        Some(self)
+    }
+
     fn handle_message(
         &mut self,
         entity_id: EntityId,
@@ -4,9 +4,14 @@ use engine::audio::AudioHandle;
 use shipyard::{EntityId, Get, UniqueView, View, World};
 
 use crate::{
+    energy::{ENERGY_PER_SHOT, PlayerEnergy},
+    entity_tags::{self, EntityTags},
     mission::{entity_creator::CreateEntityOptions, mission_core::GlobalTemplateClassTags},
+    noise::{Noise, NoiseKind},
     physics::PhysicsWorld,
     runtime_props::{RuntimePropTransform, RuntimePropVhots},
+    statistics::StatisticEvent,
+    util::point3_to_vec3,
     vr_config,
 };
 
@@ -44,6 +49,18 @@ impl Script for WeaponScript {
     ) -> Effect {
         match msg {
             MessagePayload::TriggerPull => {
+                // Energy weapons fire from the player's energy, and do nothing once it's gone
+                let is_energy_weapon =
+                    entity_tags::has_tag(world, entity_id, EntityTags::ENERGY_WEAPON);
+                if is_energy_weapon {
+                    let has_energy = world
+                        .borrow::<UniqueView<PlayerEnergy>>()
+                        .is_ok_and(|energy| energy.energy() >= ENERGY_PER_SHOT);
+                    if !has_energy {
+                        return Effect::NoEffect;
+                    }
+                }
+
                 //Create muzzle flash
                 let muzzle_flashes =
                     get_all_links_with_template(world, entity_id, |link| match link {
@@ -90,6 +107,22 @@ impl Script for WeaponScript {
                     AudioHandle::new(),
                 );
 
+                let energy_effect = if is_energy_weapon {
+                    Effect::AdjustEnergy {
+                        amount: -ENERGY_PER_SHOT,
+                    }
+                } else {
+                    Effect::NoEffect
+                };
+
+                let stat_effect = if maybe_projectile.is_some() {
+                    Effect::RecordStatistic {
+                        event: StatisticEvent::ShotFired,
+                    }
+                } else {
+                    Effect::NoEffect
+                };
+
                 let projectile_effect = Effect::Multiple(
                     maybe_projectile
                         .into_iter()
@@ -116,7 +149,28 @@ impl Script for WeaponScript {
                 //         * Quaternion::from_axis_angle(vec3(0.0, 1.0, 0.0), Rad(PI / 2.0)),
                 // };
 
-                Effect::Multiple(vec![sound_effect, muzzle_flash_effect, projectile_effect])
+                // Gunfire carries - AI nearby will come looking
+                let noise_effect = world
+                    .borrow::<View<RuntimePropTransform>>()
+                    .unwrap()
+                    .get(entity_id)
+                    .map(|transform| Effect::MakeNoise {
+                        noise: Noise::new(
+                            NoiseKind::WeaponFire,
+                            point3_to_vec3(transform.0.transform_point(point3(0.0, 0.0, 0.0))),
+                            1.0,
+                        ),
+                    })
+                    .unwrap_or(Effect::NoEffect);
+
+                Effect::Multiple(vec![
+                    sound_effect,
+                    muzzle_flash_effect,
+                    projectile_effect,
+                    stat_effect,
+                    noise_effect,
+                    energy_effect,
+                ])
             }
             MessagePayload::TriggerRelease => Effect::NoEffect,
             _ => Effect::NoEffect,
@@ -0,0 +1,144 @@
+use std::f32::consts::PI;
+
+use shipyard::Unique;
+
+// How long an alarm lasts if nobody resets it, in seconds
+pub const DEFAULT_ALARM_DURATION: f32 = 60.0;
+
+// Flashes per second for alarm lights
+const ALARM_FLASH_RATE: f32 = 1.0;
+
+// Brightness of the alarm lights when flashing is turned off (accessibility)
+const ALARM_STEADY_INTENSITY: f32 = 0.75;
+
+///
+/// AlarmChange
+///
+/// Returned from `SecuritySystem::update` when the alarm switches on or off,
+/// so the mission can switch the alarm lights over
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlarmChange {
+    Started,
+    Ended,
+}
+
+///
+/// SecuritySystem
+///
+/// Tracks the level's security alarm - triggered and reset through effects, counting down
+/// while active. Alarm lights (RuntimePropAlarmLight) follow its state.
+#[derive(Unique)]
+pub struct SecuritySystem {
+    // Seconds left on the alarm, if it's active
+    alarm_remaining: Option<f32>,
+    // Time since the alarm started, for the flash cycle
+    alarm_time: f32,
+    // Whether the alarm lights are currently switched over - trails the alarm state by one update
+    lights_active: bool,
+}
+
+impl SecuritySystem {
+    pub fn new() -> SecuritySystem {
+        SecuritySystem {
+            alarm_remaining: None,
+            alarm_time: 0.0,
+            lights_active: false,
+        }
+    }
+
+    ///
+    /// trigger_alarm
+    ///
+    /// Start the alarm, or extend it if it's already going
+    pub fn trigger_alarm(&mut self, duration: f32) {
+        if self.alarm_remaining.is_none() {
+            self.alarm_time = 0.0;
+        }
+        self.alarm_remaining = Some(self.alarm_remaining.unwrap_or(0.0).max(duration));
+    }
+
+    pub fn reset_alarm(&mut self) {
+        self.alarm_remaining = None;
+    }
+
+    pub fn is_alarm_active(&self) -> bool {
+        self.alarm_remaining.is_some()
+    }
+
+    pub fn update(&mut self, delta_time: f32) -> Option<AlarmChange> {
+        if let Some(remaining) = self.alarm_remaining {
+            self.alarm_time += delta_time;
+            let remaining = remaining - delta_time;
+            self.alarm_remaining = if remaining > 0.0 {
+                Some(remaining)
+            } else {
+                None
+            };
+        }
+
+        let is_active = self.is_alarm_active();
+        if is_active == self.lights_active {
+            return None;
+        }
+
+        self.lights_active = is_active;
+        if is_active {
+            Some(AlarmChange::Started)
+        } else {
+            Some(AlarmChange::Ended)
+        }
+    }
+
+    ///
+    /// alarm_light_intensity
+    ///
+    /// Brightness (0 - 1) of the alarm lights - flashing, or steady with reduced flashing.
+    /// Zero while the alarm is off.
+    pub fn alarm_light_intensity(&self, reduced_flashing: bool) -> f32 {
+        if !self.lights_active {
+            0.0
+        } else if reduced_flashing {
+            ALARM_STEADY_INTENSITY
+        } else {
+            0.5 - 0.5 * (self.alarm_time * ALARM_FLASH_RATE * 2.0 * PI).cos()
+        }
+    }
+}
+
+impl Default for SecuritySystem {
+    fn default() -> Self {
+        SecuritySystem::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alarm_starts_and_times_out() {
+        let mut security = SecuritySystem::new();
+        assert_eq!(security.update(0.1), None);
+
+        security.trigger_alarm(1.0);
+        assert_eq!(security.update(0.5), Some(AlarmChange::Started));
+        assert_eq!(security.update(0.25), None);
+        assert!(security.is_alarm_active());
+
+        assert_eq!(security.update(0.5), Some(AlarmChange::Ended));
+        assert!(!security.is_alarm_active());
+        assert_eq!(security.alarm_light_intensity(false), 0.0);
+    }
+
+    #[test]
+    fn test_reduced_flashing_is_steady() {
+        let mut security = SecuritySystem::new();
+        security.trigger_alarm(DEFAULT_ALARM_DURATION);
+        security.update(0.0);
+        assert_eq!(security.alarm_light_intensity(false), 0.0);
+
+        security.update(0.5 / ALARM_FLASH_RATE);
+        assert!((security.alarm_light_intensity(false) - 1.0).abs() < 0.001);
+        assert_eq!(security.alarm_light_intensity(true), ALARM_STEADY_INTENSITY);
+    }
+}
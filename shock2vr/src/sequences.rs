@@ -0,0 +1,442 @@
+///
+/// sequences.rs
+///
+/// Scripted sequences - short in-engine story beats (ie, the Many's visions, or the ending) that
+/// take over the player's camera and hands. A sequence is a series of shots, each a camera path;
+/// the view cuts over between them through black, instead of moving the player's head, so it's
+/// comfortable in VR. The mission keeps running underneath, but the player's actions are ignored
+/// until the sequence is over, or skipped.
+///
+/// Sequences are data-driven, from `sequences.json` in the bundle, and played by the
+/// `TrapSequence` script or the `sequence` console command:
+///
+/// ```json
+/// [
+///   {
+///     "id": "many_vision",
+///     "shots": [
+///       {
+///         "duration": 6.0,
+///         "anchor": "vision_marker",
+///         "camera": { "keyframes": [
+///           { "time": 0.0, "position": [0.0, 0.3, 0.0], "yaw": 0.0 },
+///           { "time": 1.0, "position": [0.0, 0.3, -1.0], "yaw": 20.0 }
+///         ] },
+///         "sound": "many_vision1",
+///         "subtitle": "sequence-many-vision-1"
+///       }
+///     ],
+///     "cutscene": "cs3"
+///   }
+/// ]
+/// ```
+///
+use std::{io::Read, sync::Arc};
+
+use cgmath::{Matrix4, Quaternion, SquareMatrix, Vector2, Vector3, Zero, vec3};
+use dark::properties::PropPosition;
+use engine::{
+    assets::{asset_cache::AssetCache, asset_importer::AssetImporter},
+    scene::{SceneObject, basic_material, quad, render_order},
+    texture::{Texture, TextureTrait},
+    texture_format::{PixelFormat, RawTextureData},
+};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Deserialize;
+use shipyard::{Get, UniqueView, View, World};
+use tracing::warn;
+
+use crate::{
+    benchmark::CameraPath,
+    input_context::InputContext,
+    mission::PlayerInfo,
+    scripts::{Effect, script_util::get_first_entity_by_name},
+};
+
+const SEQUENCES_FILE: &str = "sequences.json";
+
+// Seconds to fade to (or from) black, when cutting over between the player's view and a shot
+const FADE_SECONDS: f32 = 0.5;
+
+// How long a sequence plays before it can be skipped, in seconds - so the button press that
+// started it doesn't skip it right away
+const SKIP_DELAY: f32 = 1.0;
+
+// The fade is drawn just in front of the eye, large enough to cover the whole view
+const FADE_DISTANCE: f32 = 0.15;
+const FADE_SIZE: f32 = 2.0;
+
+///
+/// SequenceShot
+///
+/// A single shot - the camera follows `camera` over `duration` seconds. Keyframe positions are
+/// relative to the `anchor` entity (by name), or to where the player was when the sequence
+/// started. `sound` plays as the shot starts, with `subtitle` (an id in `ui.ftl`).
+#[derive(Deserialize, Clone, Debug)]
+pub struct SequenceShot {
+    pub duration: f32,
+    pub camera: CameraPath,
+    #[serde(default)]
+    pub anchor: Option<String>,
+    #[serde(default)]
+    pub sound: Option<String>,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+}
+
+///
+/// Sequence
+///
+/// A scripted sequence - its shots play in order, then the view cuts back to the player. A
+/// `cutscene` (ie, the ending video) plays straight after, if set.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Sequence {
+    pub id: String,
+    pub shots: Vec<SequenceShot>,
+    #[serde(default = "default_skippable")]
+    pub skippable: bool,
+    #[serde(default)]
+    pub cutscene: Option<String>,
+}
+
+fn default_skippable() -> bool {
+    true
+}
+
+impl Sequence {
+    fn shots_duration(&self) -> f32 {
+        self.shots.iter().map(|shot| shot.duration).sum()
+    }
+
+    /// Total length - the shots, plus cutting over from the player's view and back
+    pub fn duration(&self) -> f32 {
+        self.shots_duration() + FADE_SECONDS * 2.0
+    }
+}
+
+pub fn parse_sequences(json: &str) -> Result<Vec<Sequence>, serde_json::Error> {
+    let mut sequences: Vec<Sequence> = serde_json::from_str(json)?;
+    for shot in sequences
+        .iter_mut()
+        .flat_map(|sequence| &mut sequence.shots)
+    {
+        shot.duration = shot.duration.max(0.0);
+        shot.camera
+            .keyframes
+            .sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+    Ok(sequences)
+}
+
+fn import_sequences(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> Vec<Sequence> {
+    let mut json = String::new();
+    if let Err(err) = reader.read_to_string(&mut json) {
+        warn!("Unable to read sequences {}: {}", name, err);
+        return vec![];
+    }
+
+    parse_sequences(&json).unwrap_or_else(|err| {
+        warn!("Unable to parse sequences {}: {}", name, err);
+        vec![]
+    })
+}
+
+fn process_sequences(
+    content: Vec<Sequence>,
+    _asset_cache: &mut AssetCache,
+    _config: &(),
+) -> Vec<Sequence> {
+    content
+}
+
+static SEQUENCES_IMPORTER: Lazy<AssetImporter<Vec<Sequence>, Vec<Sequence>, ()>> =
+    Lazy::new(|| AssetImporter::define(import_sequences, process_sequences));
+
+/// Look up a sequence in `sequences.json` by id
+pub fn load_sequence(asset_cache: &mut AssetCache, id: &str) -> Option<Sequence> {
+    asset_cache
+        .get_opt(&SEQUENCES_IMPORTER, SEQUENCES_FILE)
+        .and_then(|sequences| {
+            sequences
+                .iter()
+                .find(|sequence| sequence.id.eq_ignore_ascii_case(id))
+                .cloned()
+        })
+}
+
+///
+/// SequencePlayer
+///
+/// Plays a sequence - where the camera is, how far faded to black, and the narration for each
+/// shot as it starts
+pub struct SequencePlayer {
+    sequence: Sequence,
+    // Where each shot's camera path is relative to
+    anchors: Vec<Vector3<f32>>,
+    elapsed: f32,
+    // Shots that have started, and had their narration played
+    started_shots: usize,
+}
+
+impl SequencePlayer {
+    pub fn new(sequence: Sequence, anchors: Vec<Vector3<f32>>) -> SequencePlayer {
+        SequencePlayer {
+            sequence,
+            anchors,
+            elapsed: 0.0,
+            started_shots: 0,
+        }
+    }
+
+    ///
+    /// start
+    ///
+    /// Play a sequence in the current mission - anchors that can't be found fall back to the
+    /// player's position
+    pub fn start(sequence: Sequence, world: &World) -> SequencePlayer {
+        let player_position = world
+            .borrow::<UniqueView<PlayerInfo>>()
+            .map(|player| player.pos)
+            .unwrap_or(vec3(0.0, 0.0, 0.0));
+        let v_position = world.borrow::<View<PropPosition>>().unwrap();
+
+        let anchors = sequence
+            .shots
+            .iter()
+            .map(|shot| match &shot.anchor {
+                None => player_position,
+                Some(name) => get_first_entity_by_name(world, name)
+                    .and_then(|entity_id| v_position.get(entity_id).ok())
+                    .map(|position| position.position)
+                    .unwrap_or_else(|| {
+                        warn!("Sequence {} can't find its anchor '{}'", sequence.id, name);
+                        player_position
+                    }),
+            })
+            .collect();
+
+        SequencePlayer::new(sequence, anchors)
+    }
+
+    pub fn sequence(&self) -> &Sequence {
+        &self.sequence
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.sequence.duration()
+    }
+
+    /// The shot playing, and how far into it - None while cutting over from or back to the player
+    fn current_shot(&self) -> Option<(usize, f32)> {
+        let mut shot_start = FADE_SECONDS;
+        for (index, shot) in self.sequence.shots.iter().enumerate() {
+            let shot_time = self.elapsed - shot_start;
+            if shot_time < 0.0 {
+                return None;
+            }
+            if shot_time < shot.duration {
+                return Some((index, shot_time));
+            }
+            shot_start += shot.duration;
+        }
+        None
+    }
+
+    ///
+    /// update
+    ///
+    /// Advance the sequence, skipping to the end if asked (and allowed) - returns the narration
+    /// for shots that started this frame
+    pub fn update(&mut self, delta_time: f32, wants_skip: bool) -> Vec<Effect> {
+        self.elapsed += delta_time;
+
+        // Skipping cuts straight to black, then back to the player
+        let outro_start = FADE_SECONDS + self.sequence.shots_duration();
+        if wants_skip
+            && self.sequence.skippable
+            && self.elapsed > SKIP_DELAY
+            && self.elapsed < outro_start
+        {
+            self.elapsed = outro_start;
+            self.started_shots = self.sequence.shots.len();
+        }
+
+        let mut effects = Vec::new();
+        if let Some((index, _)) = self.current_shot() {
+            while self.started_shots <= index {
+                let shot = &self.sequence.shots[self.started_shots];
+                if let Some(sound) = &shot.sound {
+                    effects.push(Effect::PlayAnnouncement {
+                        sound: sound.clone(),
+                        subtitle: shot.subtitle.clone(),
+                    });
+                }
+                self.started_shots += 1;
+            }
+        }
+        effects
+    }
+
+    /// Camera for the current shot - None while the player's own view is showing
+    pub fn camera(&self) -> Option<(Vector3<f32>, Quaternion<f32>)> {
+        let (index, shot_time) = self.current_shot()?;
+        let shot = &self.sequence.shots[index];
+        let fraction = if shot.duration > 0.0 {
+            shot_time / shot.duration
+        } else {
+            0.0
+        };
+        let (position, rotation) = shot.camera.sample(fraction);
+        Some((self.anchors[index] + position, rotation))
+    }
+
+    ///
+    /// fade
+    ///
+    /// How far faded to black, from 0.0 (clear) to 1.0 - fading in at the start of each shot
+    /// and out at the end, so every cut happens in the dark
+    pub fn fade(&self) -> f32 {
+        let outro_start = FADE_SECONDS + self.sequence.shots_duration();
+        let fade = if self.elapsed < FADE_SECONDS {
+            self.elapsed / FADE_SECONDS
+        } else if self.elapsed >= outro_start {
+            1.0 - (self.elapsed - outro_start) / FADE_SECONDS
+        } else {
+            match self.current_shot() {
+                Some((index, shot_time)) => {
+                    let remaining = self.sequence.shots[index].duration - shot_time;
+                    1.0 - (shot_time.min(remaining) / FADE_SECONDS).min(1.0)
+                }
+                None => 1.0,
+            }
+        };
+        fade.clamp(0.0, 1.0)
+    }
+}
+
+///
+/// without_actions
+///
+/// The player's input with their actions (moving, turning, firing, buttons) cleared - hand
+/// poses and grips are kept, so nothing held gets dropped while a sequence has the camera
+pub fn without_actions(input_context: &InputContext) -> InputContext {
+    let mut input_context = input_context.clone();
+    for hand in [&mut input_context.left_hand, &mut input_context.right_hand] {
+        hand.thumbstick = Vector2::zero();
+        hand.trigger_value = 0.0;
+        hand.a_value = 0.0;
+        hand.b_value = 0.0;
+    }
+    input_context
+}
+
+/// The per-eye fade to black, if there's any
+pub fn render_fade(view: Matrix4<f32>, fade: f32) -> Option<SceneObject> {
+    if fade <= 0.0 {
+        return None;
+    }
+    let eye_to_world = view.invert()?;
+
+    let texture: Arc<dyn TextureTrait> = black_texture();
+    // Keep a small amount of transparency so the fade always goes through the blended pass
+    let material = basic_material::create(texture, 0.0, (1.0 - fade).max(0.02));
+    let mut obj = SceneObject::new(material, Box::new(quad::create()));
+    obj.set_transform(
+        eye_to_world
+            * Matrix4::from_translation(vec3(0.0, 0.0, -FADE_DISTANCE))
+            * Matrix4::from_scale(FADE_SIZE),
+    );
+    obj.set_depth_write(false);
+    obj.set_render_order(render_order::OVERLAY);
+    Some(obj)
+}
+
+static BLACK_TEXTURE: OnceCell<Arc<Texture>> = OnceCell::new();
+
+fn black_texture() -> Arc<Texture> {
+    BLACK_TEXTURE
+        .get_or_init(|| {
+            Arc::new(engine::texture::init_from_memory(RawTextureData {
+                bytes: vec![0, 0, 0, 255],
+                width: 1,
+                height: 1,
+                format: PixelFormat::RGBA,
+            }))
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEQUENCES: &str = r#"[
+        { "id": "vision", "cutscene": "cs3", "shots": [
+            { "duration": 2.0, "sound": "vision1", "subtitle": "vision-1",
+              "camera": { "keyframes": [
+                { "time": 1.0, "position": [0.0, 0.0, -2.0], "yaw": 0.0 },
+                { "time": 0.0, "position": [0.0, 0.0, 0.0], "yaw": 0.0 }
+              ] } },
+            { "duration": 2.0, "sound": "vision2",
+              "camera": { "keyframes": [ { "time": 0.0, "position": [1.0, 0.0, 0.0], "yaw": 0.0 } ] } }
+        ] }
+    ]"#;
+
+    fn sounds(effects: &[Effect]) -> Vec<String> {
+        effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::PlayAnnouncement { sound, .. } => Some(sound.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn vision() -> SequencePlayer {
+        let sequence = parse_sequences(SEQUENCES).unwrap().remove(0);
+        SequencePlayer::new(sequence, vec![vec3(10.0, 0.0, 0.0), vec3(20.0, 0.0, 0.0)])
+    }
+
+    #[test]
+    fn test_shots_cut_over_through_black() {
+        let mut player = vision();
+        assert_eq!(player.sequence().cutscene.as_deref(), Some("cs3"));
+        assert!(player.update(0.25, false).is_empty());
+        assert_eq!(player.camera(), None);
+        assert_eq!(player.fade(), 0.5);
+
+        // Halfway through the first shot - fully faded in
+        assert_eq!(sounds(&player.update(1.25, false)), vec!["vision1"]);
+        assert_eq!(player.fade(), 0.0);
+        let (position, _) = player.camera().unwrap();
+        assert_eq!(position, vec3(10.0, 0.0, -1.0));
+
+        // Into the second shot, relative to its own anchor
+        assert_eq!(sounds(&player.update(1.0, false)), vec!["vision2"]);
+        assert_eq!(player.camera().unwrap().0, vec3(21.0, 0.0, 0.0));
+
+        assert!(player.update(2.5, false).is_empty());
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn test_skip_cuts_to_black() {
+        let mut player = vision();
+
+        // Too early to skip - the button that started it may still be held
+        player.update(0.25, true);
+        player.update(0.5, true);
+        assert!(player.camera().is_some());
+
+        assert!(player.update(0.5, true).is_empty());
+        assert_eq!(player.camera(), None);
+        assert_eq!(player.fade(), 1.0);
+        player.update(FADE_SECONDS, false);
+        assert!(player.is_finished());
+    }
+}
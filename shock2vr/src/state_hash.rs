@@ -0,0 +1,158 @@
+use std::{collections::BTreeMap, fs::File};
+
+use dark::properties::Links;
+use shipyard::{IntoIter, IntoWithId, UniqueView, View, World};
+
+use crate::{mission::PlayerInfo, runtime_props::RuntimePropDoNotSerialize};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+///
+/// StateHasher
+///
+/// FNV-1a - unlike `DefaultHasher`, the output is stable across Rust versions and platforms,
+/// so hashes can be compared between machines (ie, a CI run and a local repro)
+pub struct StateHasher {
+    state: u64,
+}
+
+impl StateHasher {
+    pub fn new() -> StateHasher {
+        StateHasher {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    // Bit-exact, so even the smallest drift shows up
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_bytes(&value.to_bits().to_le_bytes());
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_u64(value.len() as u64);
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        StateHasher::new()
+    }
+}
+
+///
+/// hash_world
+///
+/// Hash the world's serializable state - every property and link that would be written to a save,
+/// plus the player's position. Properties and entities are visited in sorted order, so the hash
+/// only depends on the contents of the world.
+pub fn hash_world(world: &World, hasher: &mut StateHasher) {
+    let do_not_serialize = world.borrow::<View<RuntimePropDoNotSerialize>>().unwrap();
+    let skipped = do_not_serialize
+        .iter()
+        .with_id()
+        .map(|(entity_id, _)| entity_id.inner())
+        .collect::<std::collections::HashSet<u64>>();
+
+    let (all_properties, _, _) = dark::properties::get::<File>();
+    let properties = all_properties
+        .iter()
+        .map(|prop| (prop.name(), prop.serialize(world)))
+        .collect::<BTreeMap<_, _>>();
+
+    for (name, values) in properties {
+        hasher.write_str(&name);
+        let values = values
+            .into_iter()
+            .filter(|(entity_id, _)| !skipped.contains(entity_id))
+            .collect::<BTreeMap<_, _>>();
+        for (entity_id, value) in values {
+            hasher.write_u64(entity_id);
+            hasher.write_str(&value.to_string());
+        }
+    }
+
+    let v_links = world.borrow::<View<Links>>().unwrap();
+    let links = v_links
+        .iter()
+        .with_id()
+        .filter(|(entity_id, _)| !skipped.contains(&entity_id.inner()))
+        .map(|(entity_id, links)| (entity_id.inner(), serde_json::to_string(links).unwrap()))
+        .collect::<BTreeMap<_, _>>();
+    for (entity_id, links) in links {
+        hasher.write_u64(entity_id);
+        hasher.write_str(&links);
+    }
+
+    if let Ok(player) = world.borrow::<UniqueView<PlayerInfo>>() {
+        for value in [player.pos.x, player.pos.y, player.pos.z] {
+            hasher.write_f32(value);
+        }
+        for value in [
+            player.rotation.s,
+            player.rotation.v.x,
+            player.rotation.v.y,
+            player.rotation.v.z,
+        ] {
+            hasher.write_f32(value);
+        }
+    }
+}
+
+///
+/// format_hash
+///
+/// Hashes are reported as hex strings - a u64 doesn't survive a round trip through JSON numbers
+pub fn format_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hasher_is_stable() {
+        // Known FNV-1a 64 value for "a"
+        let mut hasher = StateHasher::new();
+        hasher.write_bytes(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(format_hash(hasher.finish()), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_float_bits_matter() {
+        let mut a = StateHasher::new();
+        a.write_f32(0.0);
+        let mut b = StateHasher::new();
+        b.write_f32(-0.0);
+        assert_ne!(a.finish(), b.finish());
+    }
+
+    #[test]
+    fn test_strings_are_length_prefixed() {
+        let mut a = StateHasher::new();
+        a.write_str("ab");
+        a.write_str("c");
+        let mut b = StateHasher::new();
+        b.write_str("a");
+        b.write_str("bc");
+        assert_ne!(a.finish(), b.finish());
+    }
+}
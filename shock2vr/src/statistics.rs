@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use dark::properties::{PropClassTag, PropCreature};
+use serde::{Deserialize, Serialize};
+use shipyard::{EntityId, Get, Unique, View, World};
+
+use crate::localization;
+
+// How long the end-of-mission summary stays on screen, in seconds
+pub const MISSION_SUMMARY_DURATION: f32 = 10.0;
+
+///
+/// StatisticEvent
+///
+/// Something the player did that is worth counting - emitted by scripts via `Effect::RecordStatistic`,
+/// or recorded directly by the mission as it processes effects
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum StatisticEvent {
+    Kill { creature_type: String },
+    DamageTaken { amount: i32 },
+    DamageAbsorbed { amount: i32 },
+    ShotFired,
+    ShotHit,
+    NanitesSpent { amount: i32 },
+    HackAttempted { success: bool },
+    SecretFound,
+}
+
+///
+/// MissionStatistics
+///
+/// Counters for a single mission (or the whole game, for the totals)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MissionStatistics {
+    pub kills: HashMap<String, u32>,
+    pub damage_taken: i32,
+    /// Damage soaked up by the player's armor
+    pub damage_absorbed: i32,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    pub nanites_spent: i32,
+    pub hacks_attempted: u32,
+    pub hacks_succeeded: u32,
+    pub secrets_found: u32,
+}
+
+impl MissionStatistics {
+    pub fn record(&mut self, event: &StatisticEvent) {
+        match event {
+            StatisticEvent::Kill { creature_type } => {
+                *self.kills.entry(creature_type.clone()).or_insert(0) += 1;
+            }
+            StatisticEvent::DamageTaken { amount } => self.damage_taken += amount,
+            StatisticEvent::DamageAbsorbed { amount } => self.damage_absorbed += amount,
+            StatisticEvent::ShotFired => self.shots_fired += 1,
+            StatisticEvent::ShotHit => self.shots_hit += 1,
+            StatisticEvent::NanitesSpent { amount } => self.nanites_spent += amount,
+            StatisticEvent::HackAttempted { success } => {
+                self.hacks_attempted += 1;
+                if *success {
+                    self.hacks_succeeded += 1;
+                }
+            }
+            StatisticEvent::SecretFound => self.secrets_found += 1,
+        }
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    ///
+    /// accuracy
+    ///
+    /// Fraction of shots that hit, or None if nothing was fired
+    pub fn accuracy(&self) -> Option<f32> {
+        if self.shots_fired == 0 {
+            None
+        } else {
+            Some(self.shots_hit as f32 / self.shots_fired as f32)
+        }
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        let accuracy = self
+            .accuracy()
+            .map(|accuracy| format!("{:.0}%", accuracy * 100.0))
+            .unwrap_or_else(|| "-".to_owned());
+
+        let mut kills = self.kills.iter().collect::<Vec<(&String, &u32)>>();
+        kills.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+        let mut lines = vec![localization::text_with_args(
+            "summary-kills",
+            &[("count", self.total_kills().to_string())],
+        )];
+        lines.extend(kills.into_iter().map(|(creature_type, count)| {
+            localization::text_with_args(
+                "summary-kills-by-type",
+                &[
+                    ("creature", creature_type.clone()),
+                    ("count", count.to_string()),
+                ],
+            )
+        }));
+        lines.extend(vec![
+            localization::text_with_args(
+                "summary-damage-taken",
+                &[("amount", self.damage_taken.to_string())],
+            ),
+            localization::text_with_args(
+                "summary-damage-absorbed",
+                &[("amount", self.damage_absorbed.to_string())],
+            ),
+            localization::text_with_args(
+                "summary-shots",
+                &[
+                    ("fired", self.shots_fired.to_string()),
+                    ("hit", self.shots_hit.to_string()),
+                    ("accuracy", accuracy),
+                ],
+            ),
+            localization::text_with_args(
+                "summary-nanites-spent",
+                &[("amount", self.nanites_spent.to_string())],
+            ),
+            localization::text_with_args(
+                "summary-hacks",
+                &[
+                    ("attempted", self.hacks_attempted.to_string()),
+                    ("succeeded", self.hacks_succeeded.to_string()),
+                ],
+            ),
+            localization::text_with_args(
+                "summary-secrets",
+                &[("count", self.secrets_found.to_string())],
+            ),
+        ]);
+        lines
+    }
+}
+
+///
+/// Statistics
+///
+/// Game-wide statistics, kept as a unique in the world and carried across missions and saves
+#[derive(Unique, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub totals: MissionStatistics,
+    /// Per-mission statistics, keyed by (lowercase) mission name
+    pub missions: HashMap<String, MissionStatistics>,
+}
+
+impl Statistics {
+    pub fn record(&mut self, mission: &str, event: &StatisticEvent) {
+        self.totals.record(event);
+        self.missions
+            .entry(mission.to_ascii_lowercase())
+            .or_default()
+            .record(event);
+    }
+
+    pub fn for_mission(&self, mission: &str) -> MissionStatistics {
+        self.missions
+            .get(&mission.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+///
+/// MissionSummary
+///
+/// The end-of-mission summary shown after leaving a level
+#[derive(Clone, Debug)]
+pub struct MissionSummary {
+    pub mission: String,
+    pub statistics: MissionStatistics,
+    pub remaining_time: f32,
+}
+
+impl MissionSummary {
+    pub fn new(mission: &str, statistics: MissionStatistics) -> MissionSummary {
+        MissionSummary {
+            mission: mission.to_owned(),
+            statistics,
+            remaining_time: MISSION_SUMMARY_DURATION,
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![localization::text_with_args(
+            "summary-title",
+            &[("mission", self.mission.clone())],
+        )];
+        lines.extend(self.statistics.summary_lines());
+        lines
+    }
+}
+
+///
+/// kill_event
+///
+/// The statistic for slaying `entity_id`, if it's a creature - keyed by its `CreatureType` class tag
+pub fn kill_event(world: &World, entity_id: EntityId) -> Option<StatisticEvent> {
+    let v_creature = world.borrow::<View<PropCreature>>().ok()?;
+    if !v_creature.contains(entity_id) {
+        return None;
+    }
+
+    let v_class_tag = world.borrow::<View<PropClassTag>>().ok()?;
+    let creature_type = v_class_tag
+        .get(entity_id)
+        .ok()
+        .and_then(|class_tag| {
+            class_tag
+                .class_tags()
+                .into_iter()
+                .find(|(tag, _)| *tag == "creaturetype")
+                .map(|(_, value)| value.to_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    Some(StatisticEvent::Kill { creature_type })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_per_mission_and_totals() {
+        let mut statistics = Statistics::default();
+        statistics.record("MedSci1.mis", &StatisticEvent::ShotFired);
+        statistics.record("medsci1.mis", &StatisticEvent::ShotHit);
+        statistics.record(
+            "eng1.mis",
+            &StatisticEvent::Kill {
+                creature_type: "hybrid".to_owned(),
+            },
+        );
+
+        assert_eq!(statistics.totals.shots_fired, 1);
+        assert_eq!(statistics.totals.total_kills(), 1);
+        assert_eq!(statistics.for_mission("medsci1.mis").accuracy(), Some(1.0));
+        assert_eq!(
+            statistics.for_mission("eng1.mis").kills.get("hybrid"),
+            Some(&1)
+        );
+        assert_eq!(statistics.for_mission("unvisited.mis").accuracy(), None);
+    }
+
+    #[test]
+    fn test_hacks() {
+        let mut statistics = MissionStatistics::default();
+        statistics.record(&StatisticEvent::HackAttempted { success: false });
+        statistics.record(&StatisticEvent::HackAttempted { success: true });
+        assert_eq!(statistics.hacks_attempted, 2);
+        assert_eq!(statistics.hacks_succeeded, 1);
+    }
+}
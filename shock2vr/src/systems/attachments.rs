@@ -0,0 +1,73 @@
+use cgmath::{Matrix4, SquareMatrix};
+use dark::properties::PropPosition;
+use engine::scene::SceneGraph;
+use shipyard::{EntitiesView, EntityId, Get, IntoIter, IntoWithId, View, ViewMut, World};
+
+use crate::{
+    runtime_props::{RuntimePropAttachment, RuntimePropJointTransforms, RuntimePropTransform},
+    util::{get_position_from_matrix, get_rotation_from_matrix, point3_to_vec3},
+};
+
+///
+/// update_attachments
+///
+/// Move attached entities (see RuntimePropAttachment) along with their parents. Runs after animation and
+/// physics sync, so attachments follow where their parent - and its joints - are this frame.
+pub fn update_attachments(world: &World) {
+    let graph = build_scene_graph(world);
+
+    let v_entities = world.borrow::<EntitiesView>().unwrap();
+    let mut v_transform = world.borrow::<ViewMut<RuntimePropTransform>>().unwrap();
+    let mut v_position = world.borrow::<ViewMut<PropPosition>>().unwrap();
+    for (entity_id, xform) in graph.child_world_transforms() {
+        v_entities.add_component(entity_id, &mut v_transform, RuntimePropTransform(xform));
+        v_entities.add_component(
+            entity_id,
+            &mut v_position,
+            PropPosition {
+                position: point3_to_vec3(get_position_from_matrix(&xform)),
+                rotation: get_rotation_from_matrix(&xform),
+                cell: 0,
+            },
+        );
+    }
+}
+
+fn build_scene_graph(world: &World) -> SceneGraph<EntityId> {
+    let v_attachment = world.borrow::<View<RuntimePropAttachment>>().unwrap();
+    let v_transform = world.borrow::<View<RuntimePropTransform>>().unwrap();
+    let v_joint_transforms = world.borrow::<View<RuntimePropJointTransforms>>().unwrap();
+
+    let mut graph = SceneGraph::new();
+    for (entity_id, attachment) in v_attachment.iter().with_id() {
+        let joint_transform = match attachment.joint {
+            None => Matrix4::identity(),
+            Some(joint) => {
+                let maybe_joint = v_joint_transforms
+                    .get(attachment.parent)
+                    .ok()
+                    .and_then(|joints| joints.0.get(joint as usize).copied());
+                // Not animated yet - leave it where it is until it is
+                let Some(joint_transform) = maybe_joint else {
+                    continue;
+                };
+                joint_transform
+            }
+        };
+        graph.attach(
+            entity_id,
+            attachment.parent,
+            joint_transform * attachment.local_transform,
+        );
+
+        // Parents that aren't attached to anything are where the world put them
+        let maybe_root_transform = v_transform
+            .get(attachment.parent)
+            .ok()
+            .filter(|_| !v_attachment.contains(attachment.parent));
+        if let Some(root_transform) = maybe_root_transform {
+            graph.set_root(attachment.parent, root_transform.0);
+        }
+    }
+    graph
+}
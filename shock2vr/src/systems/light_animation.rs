@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use dark::properties::{
+    AnimLightMode, PropAnimLight, PropTweqEmitterState, PropTweqLockState, PropTweqModelState,
+    PropTweqRotateState, PropTweqScaleState, TweqAnimationState,
+};
+use rand::{Rng, thread_rng};
+use shipyard::{EntityId, Get, IntoIter, IntoWithId, Unique, UniqueView, UniqueViewMut, View};
+
+use crate::time::Time;
+
+// Fallback period (seconds) for lights with no rise or fall time set
+const DEFAULT_PERIOD: f32 = 0.5;
+
+///
+/// AnimatedLight
+///
+/// Where a light is in its animation
+#[derive(Clone, Debug)]
+struct AnimatedLight {
+    brightness: f32,
+    // Brightness at the start of the current ramp, and where it's heading
+    from: f32,
+    target: f32,
+    // Seconds into the current phase
+    phase_time: f32,
+    rising: bool,
+}
+
+impl AnimatedLight {
+    fn new(prop: &PropAnimLight) -> AnimatedLight {
+        let brightness = match prop.mode {
+            AnimLightMode::Minimum | AnimLightMode::SmoothBrighten => prop.min_brightness,
+            AnimLightMode::Extinguish => 0.0,
+            _ => prop.max_brightness,
+        };
+        AnimatedLight {
+            brightness,
+            from: brightness,
+            target: brightness,
+            phase_time: 0.0,
+            rising: prop.mode == AnimLightMode::SmoothBrighten,
+        }
+    }
+
+    ///
+    /// step
+    ///
+    /// Advance the animation - `random` gives a value in [0, 1) for the random modes
+    fn step(&mut self, prop: &PropAnimLight, delta: f32, mut random: impl FnMut() -> f32) {
+        let (min, max) = (prop.min_brightness, prop.max_brightness);
+        let phase_length = |rising: bool| {
+            let time = if rising {
+                prop.rise_time
+            } else {
+                prop.fall_time
+            };
+            let seconds = time.as_secs_f32();
+            if seconds > 0.0 {
+                seconds
+            } else {
+                DEFAULT_PERIOD
+            }
+        };
+
+        self.phase_time += delta;
+        let length = phase_length(self.rising);
+        let progress = (self.phase_time / length).min(1.0);
+        let phase_over = self.phase_time >= length;
+        if phase_over {
+            self.phase_time = 0.0;
+        }
+
+        self.brightness = match prop.mode {
+            AnimLightMode::Minimum => min,
+            AnimLightMode::Maximum => max,
+            AnimLightMode::Extinguish => 0.0,
+            // Max through the fall time, min through the rise time - a flicker's rise time is short
+            AnimLightMode::Flip | AnimLightMode::Flicker => {
+                if phase_over {
+                    self.rising = !self.rising;
+                }
+                if self.rising { min } else { max }
+            }
+            AnimLightMode::Smooth => {
+                let brightness = if self.rising {
+                    min + (max - min) * progress
+                } else {
+                    max - (max - min) * progress
+                };
+                if phase_over {
+                    self.rising = !self.rising;
+                }
+                brightness
+            }
+            AnimLightMode::SmoothBrighten => min + (max - min) * progress,
+            AnimLightMode::SmoothDim => max - (max - min) * progress,
+            AnimLightMode::Random => {
+                if phase_over {
+                    self.target = min + (max - min) * random();
+                }
+                self.target
+            }
+            AnimLightMode::RandomCoherent => {
+                let brightness = self.from + (self.target - self.from) * progress;
+                if phase_over {
+                    self.from = brightness;
+                    self.target = min + (max - min) * random();
+                    self.rising = self.target > self.from;
+                }
+                brightness
+            }
+        };
+
+        // One-shot ramps hold once they've finished
+        if phase_over
+            && matches!(
+                prop.mode,
+                AnimLightMode::SmoothBrighten | AnimLightMode::SmoothDim
+            )
+        {
+            self.phase_time = length;
+        }
+    }
+}
+
+///
+/// LightAnimationSystem
+///
+/// Brightness of the mission's animated lights (PropAnimLight) - flickering fluorescents and
+/// pulsing lamps. A light whose object has tweqs follows them: once its tweqs are switched
+/// off (ie, by `turn_off_tweqs`), it drops to its minimum brightness until they're back on.
+#[derive(Unique, Default)]
+pub struct LightAnimationSystem {
+    lights: HashMap<EntityId, AnimatedLight>,
+}
+
+impl LightAnimationSystem {
+    pub fn new() -> LightAnimationSystem {
+        LightAnimationSystem {
+            lights: HashMap::new(),
+        }
+    }
+
+    /// Current brightness of an animated light, in the light's own units (see PropAnimLight)
+    pub fn brightness(&self, entity_id: EntityId) -> Option<f32> {
+        self.lights.get(&entity_id).map(|light| light.brightness)
+    }
+}
+
+///
+/// run_light_animation
+///
+/// Steps every animated light, in sync with the object's tweqs
+pub fn run_light_animation(
+    u_time: UniqueView<Time>,
+    mut u_light_animation: UniqueViewMut<LightAnimationSystem>,
+    v_anim_light: View<PropAnimLight>,
+    v_tweq_rotate_state: View<PropTweqRotateState>,
+    v_tweq_scale_state: View<PropTweqScaleState>,
+    v_tweq_lock_state: View<PropTweqLockState>,
+    v_tweq_model_state: View<PropTweqModelState>,
+    v_tweq_emit_state: View<PropTweqEmitterState>,
+) {
+    let delta = u_time.elapsed.as_secs_f32();
+    let mut rng = thread_rng();
+    let lights = &mut u_light_animation.lights;
+
+    lights.retain(|entity_id, _| v_anim_light.contains(*entity_id));
+
+    for (entity_id, prop) in v_anim_light.iter().with_id() {
+        let light = lights
+            .entry(entity_id)
+            .or_insert_with(|| AnimatedLight::new(prop));
+
+        let tweq_states = [
+            v_tweq_rotate_state
+                .get(entity_id)
+                .ok()
+                .map(|state| state.animation_state),
+            v_tweq_scale_state
+                .get(entity_id)
+                .ok()
+                .map(|state| state.animation_state),
+            v_tweq_lock_state
+                .get(entity_id)
+                .ok()
+                .map(|state| state.animation_state),
+            v_tweq_model_state
+                .get(entity_id)
+                .ok()
+                .map(|state| state.animation_state),
+            v_tweq_emit_state
+                .get(entity_id)
+                .ok()
+                .map(|state| state.animation_state),
+        ];
+        if !tweqs_running(&tweq_states) {
+            light.brightness = prop.min_brightness;
+            continue;
+        }
+
+        light.step(prop, delta, || rng.gen_range(0.0..1.0));
+    }
+}
+
+/// Whether a light's tweqs let it run - lights without any tweqs always do
+fn tweqs_running(states: &[Option<TweqAnimationState>]) -> bool {
+    let mut states = states.iter().flatten().peekable();
+    states.peek().is_none() || states.any(|state| state.contains(TweqAnimationState::ON))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn smooth_light() -> PropAnimLight {
+        PropAnimLight {
+            mode: AnimLightMode::Smooth,
+            rise_time: Duration::from_secs(1),
+            fall_time: Duration::from_secs(2),
+            min_brightness: 0.0,
+            max_brightness: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_smooth_light_ramps_down_then_up() {
+        let prop = smooth_light();
+        let mut light = AnimatedLight::new(&prop);
+        assert_eq!(light.brightness, 100.0);
+
+        light.step(&prop, 1.0, || 0.0);
+        assert_eq!(light.brightness, 50.0, "halfway through the fall");
+
+        light.step(&prop, 1.0, || 0.0);
+        assert_eq!(light.brightness, 0.0);
+
+        light.step(&prop, 0.5, || 0.0);
+        assert_eq!(light.brightness, 50.0, "halfway through the rise");
+    }
+
+    #[test]
+    fn test_tweqs_running() {
+        assert!(tweqs_running(&[None, None]));
+        assert!(tweqs_running(&[Some(TweqAnimationState::ON), None]));
+        assert!(!tweqs_running(&[Some(TweqAnimationState::empty()), None]));
+    }
+}
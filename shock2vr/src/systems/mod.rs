@@ -1,6 +1,10 @@
+mod attachments;
 mod bitmap_animation;
+mod light_animation;
 mod tweq;
 mod update_teleported_state;
 
+pub use attachments::*;
 pub use bitmap_animation::*;
+pub use light_animation::*;
 pub use tweq::*;
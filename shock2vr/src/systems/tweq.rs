@@ -1,40 +1,194 @@
-use std::time::Duration;
+use std::{f32::consts::PI, time::Duration};
 
-use cgmath::{Deg, Quaternion, Rotation3};
+use cgmath::{Deg, Matrix4, Quaternion, Rotation3, vec3};
 use dark::properties::{
-    PropPosition, PropTweqDeleteConfig, PropTweqDeleteState, PropTweqEmitterConfig,
-    PropTweqEmitterState, PropTweqRotateState, TweqAnimationState, TweqHalt,
+    PropPosition, PropScale, PropTweqDeleteConfig, PropTweqDeleteState, PropTweqEmitterConfig,
+    PropTweqEmitterState, PropTweqLockConfig, PropTweqLockState, PropTweqRotateConfig,
+    PropTweqRotateState, PropTweqScaleConfig, PropTweqScaleState, TweqAnimationConfig,
+    TweqAnimationState, TweqCurve, TweqHalt, TweqVectorLimits,
+};
+use rand::{Rng, thread_rng};
+use shipyard::{
+    EntitiesView, EntityId, Get, IntoIter, IntoWithId, UniqueView, UniqueViewMut, View, ViewMut,
 };
-use shipyard::{EntityId, Get, IntoIter, IntoWithId, UniqueView, UniqueViewMut, View, ViewMut};
 
 use crate::{mission::EffectQueue, scripts::Effect, time::Time, util::vec3_to_point3};
 
+// Fallback rotation speed (degrees / s) for rotate tweqs without a config
+const DEFAULT_ROTATE_RATE: f32 = 20.0;
+
 ///
 /// run_tweq
 ///
 /// Runs all tweq components
 ///
+#[allow(clippy::too_many_arguments)]
 pub fn run_tweq(
     u_time: UniqueView<Time>,
+    v_entities: EntitiesView,
     v_prop_position: View<PropPosition>,
-    v_tweq_rotate_state: View<PropTweqRotateState>,
+    mut v_prop_scale: ViewMut<PropScale>,
+    mut v_tweq_rotate_state: ViewMut<PropTweqRotateState>,
+    v_tweq_rotate_config: View<PropTweqRotateConfig>,
+    mut v_tweq_scale_state: ViewMut<PropTweqScaleState>,
+    v_tweq_scale_config: View<PropTweqScaleConfig>,
+    mut v_tweq_lock_state: ViewMut<PropTweqLockState>,
+    v_tweq_lock_config: View<PropTweqLockConfig>,
     mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
     mut v_tweq_emit_config: ViewMut<PropTweqEmitterConfig>,
     mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
     mut v_tweq_delete_config: ViewMut<PropTweqDeleteConfig>,
     mut effects: UniqueViewMut<EffectQueue>,
 ) {
-    for (id, tweq) in v_tweq_rotate_state.iter().with_id() {
-        if tweq.animation_state.contains(TweqAnimationState::ON) {
-            effects.push(Effect::SetRotation {
-                entity_id: id,
-                rotation: Quaternion::from_angle_y(Deg(u_time.total.as_secs_f32() * 20.0)),
-            });
+    let delta = u_time.elapsed.as_secs_f32();
+
+    // Run rotate tweq
+    for (id, tweq) in (&mut v_tweq_rotate_state).iter().with_id() {
+        let tweq = &mut *tweq;
+        if !tweq.animation_state.contains(TweqAnimationState::ON) {
+            continue;
+        }
+
+        let base_rotation = match tweq.base_rotation {
+            Some(rotation) => rotation,
+            None => {
+                let rotation = v_prop_position
+                    .get(id)
+                    .map(|p| p.rotation)
+                    .unwrap_or(Quaternion::from_angle_y(Deg(0.0)));
+                tweq.base_rotation = Some(rotation);
+                rotation
+            }
+        };
+
+        if let Ok(PropTweqRotateConfig(config)) = v_tweq_rotate_config.get(id) {
+            let mut halted = false;
+            let axis_states = [
+                &mut tweq.axis1_animation_state,
+                &mut tweq.axis2_animation_state,
+                &mut tweq.axis3_animation_state,
+            ];
+            for (axis, axis_state) in axis_states.into_iter().enumerate() {
+                halted |= step_tweq_value(
+                    &mut tweq.current_angles[axis],
+                    axis_state,
+                    &config.limits[axis],
+                    config.animation_config,
+                    config.curve,
+                    delta,
+                );
+            }
+
+            if halted {
+                halt_tweq(id, &config.halt, &mut tweq.animation_state, &mut effects);
+            }
+        } else {
+            tweq.current_angles.y = (tweq.current_angles.y + DEFAULT_ROTATE_RATE * delta) % 360.0;
+        }
+
+        // Compose the same way as the facing vector in PropPosition
+        let angles = tweq.current_angles;
+        let offset = Quaternion::from_angle_y(Deg(angles.y))
+            * Quaternion::from_angle_z(Deg(angles.z))
+            * Quaternion::from_angle_x(Deg(angles.x));
+
+        effects.push(Effect::SetRotation {
+            entity_id: id,
+            rotation: base_rotation * offset,
+        });
+    }
+
+    // Run scale tweq
+    for (id, (tweq_state, PropTweqScaleConfig(config))) in
+        (&mut v_tweq_scale_state, &v_tweq_scale_config)
+            .iter()
+            .with_id()
+    {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON) {
+            continue;
+        }
+
+        let mut scale = tweq_state.current_scale.unwrap_or_else(|| {
+            let initial = v_prop_scale
+                .get(id)
+                .map(|p| p.0)
+                .unwrap_or(vec3(1.0, 1.0, 1.0));
+            // Axes that animate start from their low limit
+            let mut scale = initial;
+            for (axis, limits) in config.limits.iter().enumerate() {
+                if limits.rate != 0.0 {
+                    scale[axis] = limits.low;
+                }
+            }
+            scale
+        });
+
+        let mut halted = false;
+        for (axis, axis_state) in tweq_state.axis_animation_states.iter_mut().enumerate() {
+            halted |= step_tweq_value(
+                &mut scale[axis],
+                axis_state,
+                &config.limits[axis],
+                config.animation_config,
+                config.curve,
+                delta,
+            );
+        }
+
+        tweq_state.current_scale = Some(scale);
+        v_entities.add_component(id, &mut v_prop_scale, PropScale(scale));
+
+        if halted {
+            halt_tweq(
+                id,
+                &config.halt,
+                &mut tweq_state.animation_state,
+                &mut effects,
+            );
+        }
+    }
+
+    // Run lock (joint) tweq
+    for (id, (tweq_state, tweq_config)) in (&mut v_tweq_lock_state, &v_tweq_lock_config)
+        .iter()
+        .with_id()
+    {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON) {
+            continue;
+        }
+
+        let limits = &tweq_config.limits;
+        let target = tweq_state
+            .target_angle
+            .clamp(limits.low.min(limits.high), limits.low.max(limits.high));
+        let max_step = limits.rate.abs() * delta;
+        let remaining = target - tweq_state.current_angle;
+
+        let reached_target = remaining.abs() <= max_step;
+        tweq_state.current_angle = if reached_target {
+            target
+        } else {
+            tweq_state.current_angle + max_step * remaining.signum()
+        };
+
+        effects.push(Effect::SetJointTransform {
+            entity_id: id,
+            joint_id: tweq_config.lock_joint,
+            transform: Matrix4::from_angle_z(Deg(tweq_state.current_angle)),
+        });
+
+        if reached_target {
+            halt_tweq(
+                id,
+                &tweq_config.halt,
+                &mut tweq_state.animation_state,
+                &mut effects,
+            );
         }
     }
 
     // Run emit tweq
-    for (_id, (tweq_state, tweq_config, position)) in (
+    for (id, (tweq_state, tweq_config, position)) in (
         &mut v_tweq_emit_state,
         &mut v_tweq_emit_config,
         &v_prop_position,
@@ -46,23 +200,46 @@ pub fn run_tweq(
             let time_since_last_event = tweq_state.time_since_last_event + u_time.elapsed;
             tweq_state.time_since_last_event = time_since_last_event;
 
+            let is_unlimited = tweq_config
+                .animation_config
+                .contains(TweqAnimationConfig::NOLIMT);
+
             if time_since_last_event > tweq_config.rate
-                && tweq_state.num_iterations < tweq_config.max_frames
+                && (is_unlimited || tweq_state.num_iterations < tweq_config.max_frames)
             {
                 tweq_state.num_iterations += 1;
                 tweq_state.time_since_last_event = Duration::from_secs(0);
+
+                let template_name = if tweq_config.emit_what.is_empty() {
+                    "HE Explosion".to_string()
+                } else {
+                    tweq_config.emit_what.clone()
+                };
+
+                let mut rng = thread_rng();
+                let jitter = tweq_config.angle_random;
+                let random_rotation =
+                    Quaternion::from_angle_y(Deg(rng.gen_range(-1.0..=1.0) * jitter.y))
+                        * Quaternion::from_angle_z(Deg(rng.gen_range(-1.0..=1.0) * jitter.z))
+                        * Quaternion::from_angle_x(Deg(rng.gen_range(-1.0..=1.0) * jitter.x));
+
                 effects.push(Effect::CreateEntityByTemplateName {
-                    template_name: "HE Explosion".to_string(),
+                    template_name,
                     position: vec3_to_point3(position.position),
-                    orientation: position.rotation,
+                    orientation: position.rotation * random_rotation,
                 });
             }
 
             // Did we finish emitting frames?
-            if tweq_state.num_iterations >= tweq_config.max_frames {
-                tweq_state.animation_state = tweq_state
-                    .animation_state
-                    .difference(TweqAnimationState::ON);
+            if !is_unlimited && tweq_state.num_iterations >= tweq_config.max_frames {
+                halt_tweq(
+                    id,
+                    &tweq_config.halt,
+                    &mut tweq_state.animation_state,
+                    &mut effects,
+                );
+                // The emitter has nothing left to emit, even if the halt action says to continue
+                tweq_state.animation_state.remove(TweqAnimationState::ON);
             }
         }
     }
@@ -86,10 +263,122 @@ pub fn run_tweq(
     }
 }
 
+///
+/// step_tweq_value
+///
+/// Advances a single animated value (an axis of a rotate or scale tweq) by its rate,
+/// applying the curve and limit behavior. Returns true if the tweq reached a halt condition.
+fn step_tweq_value(
+    value: &mut f32,
+    axis_state: &mut TweqAnimationState,
+    limits: &TweqVectorLimits,
+    config: TweqAnimationConfig,
+    curve: TweqCurve,
+    delta: f32,
+) -> bool {
+    if limits.rate == 0.0 {
+        return false;
+    }
+
+    let low = limits.low.min(limits.high);
+    let high = limits.low.max(limits.high);
+    let has_range = high > low;
+
+    let mut rate = limits.rate.abs();
+    if curve.contains(TweqCurve::PENDULUM) && has_range {
+        // Slow down towards the ends of the swing
+        let t = ((*value - low) / (high - low)).clamp(0.0, 1.0);
+        rate *= (t * PI).sin().max(0.1);
+    }
+    if curve.contains(TweqCurve::JITTER_HIGH) {
+        rate *= thread_rng().gen_range(0.0..2.0);
+    } else if curve.contains(TweqCurve::JITTER_LOW) {
+        rate *= thread_rng().gen_range(0.5..1.5);
+    }
+
+    let direction = if limits.rate < 0.0 { -1.0 } else { 1.0 };
+    let direction = if axis_state.contains(TweqAnimationState::REVERSE) {
+        -direction
+    } else {
+        direction
+    };
+
+    *value += direction * rate * delta;
+
+    if config.contains(TweqAnimationConfig::NOLIMT) || !has_range {
+        return false;
+    }
+
+    if config.contains(TweqAnimationConfig::WRAP) {
+        let span = high - low;
+        if *value > high {
+            *value = low + (*value - high) % span;
+            return config.contains(TweqAnimationConfig::ONEBOUNCE);
+        }
+        if *value < low {
+            *value = high - (low - *value) % span;
+            return config.contains(TweqAnimationConfig::ONEBOUNCE);
+        }
+        return false;
+    }
+
+    if *value > high || *value < low {
+        *value = value.clamp(low, high);
+
+        let bounces = curve.intersects(TweqCurve::BOUNCE | TweqCurve::PENDULUM)
+            || config.contains(TweqAnimationConfig::ONEBOUNCE);
+        if !bounces {
+            return true;
+        }
+
+        axis_state.toggle(TweqAnimationState::REVERSE);
+        if config.contains(TweqAnimationConfig::ONEBOUNCE) {
+            // Halt once we've made it back to where we started
+            if axis_state.contains(TweqAnimationState::LAPONE) {
+                return true;
+            }
+            axis_state.insert(TweqAnimationState::LAPONE);
+        }
+    }
+
+    false
+}
+
+///
+/// halt_tweq
+///
+/// Applies the configured halt action once a tweq finishes
+fn halt_tweq(
+    entity_id: EntityId,
+    halt: &TweqHalt,
+    animation_state: &mut TweqAnimationState,
+    effects: &mut EffectQueue,
+) {
+    // Destroying and slaying also stop the tweq, so the effect is only sent once while the
+    // entity waits to be removed
+    match halt {
+        TweqHalt::DestroyObject => {
+            effects.push(Effect::DestroyEntity { entity_id });
+            animation_state.remove(TweqAnimationState::ON);
+        }
+        TweqHalt::SlayObj => {
+            effects.push(Effect::SlayEntity { entity_id });
+            animation_state.remove(TweqAnimationState::ON);
+        }
+        TweqHalt::Continue => (),
+        TweqHalt::RemoveProp | TweqHalt::StopTweq => {
+            animation_state.remove(TweqAnimationState::ON);
+        }
+    }
+}
+
 pub fn turn_on_tweqs(
     entity_id: EntityId,
     mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
     mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
+    mut v_tweq_rotate_state: ViewMut<PropTweqRotateState>,
+    mut v_tweq_scale_state: ViewMut<PropTweqScaleState>,
+    mut v_tweq_lock_state: ViewMut<PropTweqLockState>,
 ) {
     if let Ok(tweq_state) = (&mut v_tweq_emit_state).get(entity_id) {
         tweq_state.animation_state.insert(TweqAnimationState::ON);
@@ -100,12 +389,27 @@ pub fn turn_on_tweqs(
         tweq_state.animation_state.insert(TweqAnimationState::ON);
         tweq_state.time_since_last_event = Duration::from_secs(0);
     }
+
+    if let Ok(tweq_state) = (&mut v_tweq_rotate_state).get(entity_id) {
+        tweq_state.animation_state.insert(TweqAnimationState::ON);
+    }
+
+    if let Ok(tweq_state) = (&mut v_tweq_scale_state).get(entity_id) {
+        tweq_state.animation_state.insert(TweqAnimationState::ON);
+    }
+
+    if let Ok(tweq_state) = (&mut v_tweq_lock_state).get(entity_id) {
+        tweq_state.animation_state.insert(TweqAnimationState::ON);
+    }
 }
 
 pub fn turn_off_tweqs(
     entity_id: EntityId,
     mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
     mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
+    mut v_tweq_rotate_state: ViewMut<PropTweqRotateState>,
+    mut v_tweq_scale_state: ViewMut<PropTweqScaleState>,
+    mut v_tweq_lock_state: ViewMut<PropTweqLockState>,
 ) {
     if let Ok(tweq_state) = (&mut v_tweq_emit_state).get(entity_id) {
         tweq_state.animation_state.remove(TweqAnimationState::ON);
@@ -114,4 +418,111 @@ pub fn turn_off_tweqs(
     if let Ok(tweq_state) = (&mut v_tweq_delete_state).get(entity_id) {
         tweq_state.animation_state.remove(TweqAnimationState::ON);
     }
+
+    if let Ok(tweq_state) = (&mut v_tweq_rotate_state).get(entity_id) {
+        tweq_state.animation_state.remove(TweqAnimationState::ON);
+    }
+
+    if let Ok(tweq_state) = (&mut v_tweq_scale_state).get(entity_id) {
+        tweq_state.animation_state.remove(TweqAnimationState::ON);
+    }
+
+    if let Ok(tweq_state) = (&mut v_tweq_lock_state).get(entity_id) {
+        tweq_state.animation_state.remove(TweqAnimationState::ON);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shipyard::World;
+
+    use super::*;
+
+    fn limits(rate: f32, low: f32, high: f32) -> TweqVectorLimits {
+        TweqVectorLimits { rate, low, high }
+    }
+
+    #[test]
+    fn test_wrap_keeps_value_in_range() {
+        let mut value = 350.0;
+        let mut state = TweqAnimationState::ON;
+        let halted = step_tweq_value(
+            &mut value,
+            &mut state,
+            &limits(20.0, 0.0, 360.0),
+            TweqAnimationConfig::WRAP,
+            TweqCurve::empty(),
+            1.0,
+        );
+        assert!(!halted);
+        assert!((value - 10.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_limit_without_bounce_halts() {
+        let mut value = 80.0;
+        let mut state = TweqAnimationState::ON;
+        let halted = step_tweq_value(
+            &mut value,
+            &mut state,
+            &limits(30.0, 0.0, 90.0),
+            TweqAnimationConfig::empty(),
+            TweqCurve::empty(),
+            1.0,
+        );
+        assert!(halted);
+        assert_eq!(value, 90.0);
+    }
+
+    #[test]
+    fn test_one_bounce_halts_on_return() {
+        let mut value = 0.0;
+        let mut state = TweqAnimationState::ON;
+        let config = TweqAnimationConfig::ONEBOUNCE;
+
+        // Out to the high limit, bounce...
+        assert!(!step_tweq_value(
+            &mut value,
+            &mut state,
+            &limits(10.0, 0.0, 5.0),
+            config,
+            TweqCurve::empty(),
+            1.0,
+        ));
+        assert!(state.contains(TweqAnimationState::REVERSE));
+
+        // ...and back to the low limit, where we halt
+        assert!(step_tweq_value(
+            &mut value,
+            &mut state,
+            &limits(10.0, 0.0, 5.0),
+            config,
+            TweqCurve::empty(),
+            1.0,
+        ));
+        assert_eq!(value, 0.0);
+    }
+
+    #[test]
+    fn test_destroy_halt_is_sent_once() {
+        let entity_id = World::new().add_entity(());
+        let mut state = TweqAnimationState::ON;
+        let mut effects = EffectQueue::default();
+
+        // Halted tweqs stay halted over the frames before the entity is removed
+        for _ in 0..3 {
+            if state.contains(TweqAnimationState::ON) {
+                halt_tweq(
+                    entity_id,
+                    &TweqHalt::DestroyObject,
+                    &mut state,
+                    &mut effects,
+                );
+            }
+        }
+
+        let effects = effects.flush();
+        assert_eq!(effects.len(), 1);
+        assert!(matches!(effects[0], Effect::DestroyEntity { .. }));
+    }
 }
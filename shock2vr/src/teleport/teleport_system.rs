@@ -259,6 +259,7 @@ impl TeleportSystem {
             trigger_value: hand.trigger_value,
             squeeze_value: hand.squeeze_value,
             a_value: hand.a_value,
+            b_value: hand.b_value,
         }
     }
 }
@@ -0,0 +1,492 @@
+///
+/// template_overrides.rs
+///
+/// Tuning values for templates, without touching the gamesys - an optional `overrides.toml` in
+/// the data root patches numeric properties (damage, hit points, speeds) as entities are created
+/// from their templates. Each table is a template name (or id) and property, and its keys are the
+/// property's fields:
+///
+/// ```toml
+/// [midwife."P$HitPoints"]
+/// hit_points = 40
+///
+/// ["assault rifle".PlayerGun]
+/// reload_rate = 200
+/// ```
+///
+/// An override applies to the template and everything that inherits from it - when templates in
+/// the same hierarchy override the same field, the most derived one wins. The file is watched,
+/// and edits are applied to the running mission.
+///
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::Read,
+    time::{Duration, Instant},
+};
+
+use dark::{
+    properties::{PropTemplateId, PropertyDefinition},
+    ss2_entity_info::{self, SystemShock2EntityInfo},
+};
+use once_cell::sync::Lazy;
+use shipyard::{EntityId, IntoIter, IntoWithId, Unique, UniqueView, View, World};
+use tracing::{debug, info, warn};
+
+use crate::{
+    mission::{GlobalEntityMetadata, entity_inspector},
+    paths,
+};
+
+pub const OVERRIDES_FILE: &str = "overrides.toml";
+
+// How often the overrides file is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// The property definitions overrides are checked and written through - built once, since spawns
+// consult them too
+static DEFINITIONS: Lazy<Vec<Box<dyn PropertyDefinition<File>>>> =
+    Lazy::new(|| dark::properties::get::<File>().0);
+
+///
+/// PropertyOverride
+///
+/// A single numeric field to replace - ie, `hit_points` of `P$HitPoints` on `midwife`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyOverride {
+    /// Lowercase template name, or a template id
+    pub template: String,
+    pub property: String,
+    pub path: Vec<String>,
+    pub value: f64,
+}
+
+impl PropertyOverride {
+    pub fn label(&self) -> String {
+        if self.path.is_empty() {
+            format!("{}.{}", self.template, self.property)
+        } else {
+            format!(
+                "{}.{}.{}",
+                self.template,
+                self.property,
+                self.path.join(".")
+            )
+        }
+    }
+}
+
+///
+/// TemplateOverrides
+///
+/// The parsed contents of `overrides.toml` - kept as a unique in the mission world, so entities
+/// created at runtime pick up the overrides too
+#[derive(Unique, Clone, Debug, Default, PartialEq)]
+pub struct TemplateOverrides {
+    entries: Vec<PropertyOverride>,
+}
+
+impl TemplateOverrides {
+    ///
+    /// parse
+    ///
+    /// Read overrides from TOML - property names may leave off the `P$` prefix
+    pub fn parse(source: &str) -> Result<TemplateOverrides, String> {
+        let table: toml::Table = toml::from_str(source).map_err(|err| err.to_string())?;
+
+        let mut entries = Vec::new();
+        for (template, properties) in table {
+            let toml::Value::Table(properties) = properties else {
+                return Err(format!("{template}: expected a table of properties"));
+            };
+
+            let template = template.to_ascii_lowercase();
+            for (property, fields) in properties {
+                let property = if property.starts_with("P$") {
+                    property
+                } else {
+                    format!("P${property}")
+                };
+                collect_entries(&template, &property, &fields, &mut vec![], &mut entries)?;
+            }
+        }
+
+        Ok(TemplateOverrides { entries })
+    }
+
+    pub fn entries(&self) -> &[PropertyOverride] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    ///
+    /// changed_since
+    ///
+    /// The overrides that are new, or have a different value than in `previous` - on reload, only
+    /// these are applied, so tuning a gun's damage doesn't heal every monster in the level
+    pub fn changed_since(&self, previous: &TemplateOverrides) -> TemplateOverrides {
+        TemplateOverrides {
+            entries: self
+                .entries
+                .iter()
+                .filter(|entry| !previous.entries.contains(entry))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Drop overrides for properties the game doesn't know about, with a warning for each
+    fn retain_known_properties(&mut self, definitions: &[Box<dyn PropertyDefinition<File>>]) {
+        let known: HashSet<String> = definitions.iter().map(|prop| prop.name()).collect();
+        self.entries.retain(|entry| {
+            let is_known = known.contains(&entry.property);
+            if !is_known {
+                warn!("Unknown property in {}: {}", OVERRIDES_FILE, entry.label());
+            }
+            is_known
+        });
+    }
+
+    /// The overrides for an entity, by its template and ancestors (root first) - in the order
+    /// they should be applied, so the most derived template's values win
+    fn matching(
+        &self,
+        ancestors: &[i32],
+        metadata: Option<&GlobalEntityMetadata>,
+    ) -> Vec<PropertyOverride> {
+        let resolve = |template: &str| {
+            template.parse::<i32>().ok().or_else(|| {
+                metadata
+                    .and_then(|metadata| metadata.0.get(template))
+                    .map(|metadata| metadata.template_id)
+            })
+        };
+
+        let mut matching = Vec::new();
+        for template_id in ancestors {
+            for entry in &self.entries {
+                if resolve(&entry.template) == Some(*template_id) {
+                    matching.push(entry.clone());
+                }
+            }
+        }
+        matching
+    }
+}
+
+fn collect_entries(
+    template: &str,
+    property: &str,
+    value: &toml::Value,
+    path: &mut Vec<String>,
+    entries: &mut Vec<PropertyOverride>,
+) -> Result<(), String> {
+    let number = match value {
+        toml::Value::Integer(integer) => *integer as f64,
+        toml::Value::Float(float) => *float,
+        toml::Value::Table(table) => {
+            for (key, child) in table {
+                path.push(key.to_owned());
+                collect_entries(template, property, child, path, entries)?;
+                path.pop();
+            }
+            return Ok(());
+        }
+        toml::Value::Array(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                path.push(idx.to_string());
+                collect_entries(template, property, child, path, entries)?;
+                path.pop();
+            }
+            return Ok(());
+        }
+        _ => {
+            return Err(format!(
+                "{}.{}.{}: expected a number",
+                template,
+                property,
+                path.join(".")
+            ));
+        }
+    };
+
+    entries.push(PropertyOverride {
+        template: template.to_owned(),
+        property: property.to_owned(),
+        path: path.clone(),
+        value: number,
+    });
+    Ok(())
+}
+
+///
+/// apply_to_entity
+///
+/// Patch a newly created entity with the overrides for its template, if the world has any
+pub fn apply_to_entity(world: &mut World, entity_id: EntityId, ancestors: &[i32]) {
+    let matching = {
+        let Ok(overrides) = world.borrow::<UniqueView<TemplateOverrides>>() else {
+            return;
+        };
+        if overrides.is_empty() {
+            return;
+        }
+        let metadata = world.borrow::<UniqueView<GlobalEntityMetadata>>().ok();
+        overrides.matching(ancestors, metadata.as_deref())
+    };
+    if matching.is_empty() {
+        return;
+    }
+
+    for failure in apply_entries(world, &DEFINITIONS, entity_id, &matching) {
+        debug!("Unable to apply override {} to {:?}", failure, entity_id);
+    }
+}
+
+///
+/// apply_to_all
+///
+/// Patch every entity created from a template with `overrides` - returns the number of entities
+/// changed
+pub fn apply_to_all(
+    world: &mut World,
+    entity_info: &SystemShock2EntityInfo,
+    overrides: &TemplateOverrides,
+) -> usize {
+    if overrides.is_empty() {
+        return 0;
+    }
+
+    let entities: Vec<(EntityId, i32)> = world
+        .borrow::<View<PropTemplateId>>()
+        .unwrap()
+        .iter()
+        .with_id()
+        .map(|(entity_id, template_id)| (entity_id, template_id.template_id))
+        .collect();
+
+    let metadata = world
+        .borrow::<UniqueView<GlobalEntityMetadata>>()
+        .ok()
+        .map(|metadata| (*metadata).clone());
+    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+
+    let mut patched = 0;
+    let mut failures = HashSet::new();
+    for (entity_id, template_id) in entities {
+        let mut ancestors = ss2_entity_info::get_ancestors(hierarchy, &template_id);
+        ancestors.push(template_id);
+
+        let matching = overrides.matching(&ancestors, metadata.as_ref());
+        if matching.is_empty() {
+            continue;
+        }
+
+        let entity_failures = apply_entries(world, &DEFINITIONS, entity_id, &matching);
+        if entity_failures.len() < matching.len() {
+            patched += 1;
+        }
+        failures.extend(entity_failures);
+    }
+
+    for failure in failures {
+        warn!("Unable to apply override {}", failure);
+    }
+    patched
+}
+
+/// Write each override into the entity's property - returns the labels of the ones that didn't
+/// apply (the field doesn't exist, or the value doesn't fit it)
+fn apply_entries(
+    world: &mut World,
+    definitions: &[Box<dyn PropertyDefinition<File>>],
+    entity_id: EntityId,
+    entries: &[PropertyOverride],
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    for entry in entries {
+        let Some(definition) = definitions
+            .iter()
+            .find(|prop| prop.name() == entry.property)
+        else {
+            failures.push(entry.label());
+            continue;
+        };
+
+        // Templates without the property are left alone - overrides only change values
+        let Some(mut value) = definition.serialize_entity(world, entity_id) else {
+            continue;
+        };
+
+        let maybe_field = entity_inspector::collect_fields(&entry.property, &value)
+            .into_iter()
+            .find(|field| field.path == entry.path);
+        let Some(field) = maybe_field else {
+            failures.push(entry.label());
+            continue;
+        };
+
+        let new_value = if field.is_integer {
+            entry.value.round()
+        } else {
+            entry.value
+        };
+        if !entity_inspector::set_field(&mut value, &entry.path, new_value, field.is_integer)
+            || definition
+                .deserialize_entity(value, world, entity_id)
+                .is_err()
+        {
+            failures.push(entry.label());
+        }
+    }
+    failures
+}
+
+///
+/// OverridesFile
+///
+/// Watches `overrides.toml` in the game data, reloading it when it changes
+pub struct OverridesFile {
+    path: String,
+    source: Option<String>,
+    last_poll: Option<Instant>,
+}
+
+impl OverridesFile {
+    pub fn new(path: &str) -> OverridesFile {
+        OverridesFile {
+            path: path.to_owned(),
+            source: None,
+            last_poll: None,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    ///
+    /// load
+    ///
+    /// Read the overrides - a missing file is the same as an empty one
+    pub fn load(&mut self) -> Result<TemplateOverrides, String> {
+        self.last_poll = Some(Instant::now());
+        self.source = read_source(&self.path)?;
+        let Some(source) = &self.source else {
+            return Ok(TemplateOverrides::default());
+        };
+
+        let mut overrides = TemplateOverrides::parse(source)?;
+        overrides.retain_known_properties(&DEFINITIONS);
+
+        info!(
+            "Loaded {} template overrides from {}",
+            overrides.entries.len(),
+            self.path
+        );
+        Ok(overrides)
+    }
+
+    ///
+    /// poll
+    ///
+    /// Reload the overrides if the file has changed since it was last loaded - checks the file at
+    /// most every `POLL_INTERVAL`
+    pub fn poll(&mut self) -> Option<Result<TemplateOverrides, String>> {
+        let now = Instant::now();
+        if let Some(last_poll) = self.last_poll {
+            if now.duration_since(last_poll) < POLL_INTERVAL {
+                return None;
+            }
+        }
+        self.last_poll = Some(now);
+
+        // The data file system has no modification times, so compare the contents instead
+        match read_source(&self.path) {
+            Ok(source) if source == self.source => None,
+            _ => Some(self.load()),
+        }
+    }
+}
+
+/// Read the overrides file from the game data - `None` if there isn't one
+fn read_source(path: &str) -> Result<Option<String>, String> {
+    if !paths::data_file_exists(path) {
+        return Ok(None);
+    }
+
+    let mut reader =
+        paths::open_data_file(path).ok_or_else(|| format!("unable to open {}", path))?;
+    let mut source = String::new();
+    reader
+        .read_to_string(&mut source)
+        .map_err(|err| err.to_string())?;
+    Ok(Some(source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dark::properties::PropHitPoints;
+    use shipyard::Get;
+
+    #[test]
+    fn test_parse() {
+        let overrides = TemplateOverrides::parse(
+            "[Midwife.\"P$HitPoints\"]\nhit_points = 40\n\n[-1234.PhysAttr]\nmass = 2\ncog = { y = 0.5 }",
+        )
+        .unwrap();
+
+        assert!(overrides.entries().contains(&PropertyOverride {
+            template: "midwife".to_owned(),
+            property: "P$HitPoints".to_owned(),
+            path: vec!["hit_points".to_owned()],
+            value: 40.0,
+        }));
+        assert!(overrides.entries().contains(&PropertyOverride {
+            template: "-1234".to_owned(),
+            property: "P$PhysAttr".to_owned(),
+            path: vec!["cog".to_owned(), "y".to_owned()],
+            value: 0.5,
+        }));
+        assert_eq!(overrides.entries().len(), 3);
+
+        assert!(TemplateOverrides::parse("[midwife.\"P$HitPoints\"]\nhit_points = \"x\"").is_err());
+        assert!(TemplateOverrides::parse("midwife = 1").is_err());
+    }
+
+    #[test]
+    fn test_changed_since() {
+        let previous = TemplateOverrides::parse(
+            "[a.HitPoints]\nhit_points = 1\n[b.HitPoints]\nhit_points = 2",
+        )
+        .unwrap();
+        let current = TemplateOverrides::parse(
+            "[a.HitPoints]\nhit_points = 1\n[b.HitPoints]\nhit_points = 3",
+        )
+        .unwrap();
+
+        let changed = current.changed_since(&previous);
+        assert_eq!(changed.entries().len(), 1);
+        assert_eq!(changed.entries()[0].template, "b");
+    }
+
+    #[test]
+    fn test_most_derived_template_wins() {
+        let mut world = World::new();
+        let entity_id = world.add_entity(PropHitPoints { hit_points: 10 });
+        world.add_unique(
+            TemplateOverrides::parse(
+                "[-1.HitPoints]\nhit_points = 20\n[-2.HitPoints]\nhit_points = 30.4",
+            )
+            .unwrap(),
+        );
+
+        apply_to_entity(&mut world, entity_id, &[-1, -2]);
+        let v_hit_points = world.borrow::<View<PropHitPoints>>().unwrap();
+        assert_eq!(v_hit_points.get(entity_id).unwrap().hit_points, 30);
+    }
+}
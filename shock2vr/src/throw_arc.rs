@@ -0,0 +1,105 @@
+///
+/// throw_arc.rs
+///
+/// Throwing grenades in VR - with a throwable held and the trigger primed, letting go launches
+/// it along the hand's aim at a fixed speed, rather than at whatever speed the controller
+/// happened to be moving. That makes the throw predictable, so it can be previewed: the
+/// teleport arc math traces where it'll go, clipped against the physics world.
+///
+use cgmath::{InnerSpace, Quaternion, Rotation, Vector3, vec3};
+use engine::scene::SceneObject;
+use shipyard::{EntityId, World};
+
+use crate::{
+    entity_tags::{self, EntityTags},
+    physics::{InternalCollisionGroups, PhysicsWorld},
+    teleport::{ArcRenderConfig, ArcRenderer, ArcTrajectory},
+    util::{point3_to_vec3, vec3_to_point3},
+};
+
+// Speed a primed throwable leaves the hand at
+const THROW_SPEED: f32 = 8.0;
+
+// Matches the physics world's gravity
+const GRAVITY: f32 = 9.81;
+
+// How far along the arc the preview follows it
+const MAX_DISTANCE: f32 = 25.0;
+
+const ARC_SEGMENTS: usize = 60;
+
+const ARC_COLOR: Vector3<f32> = vec3(1.0, 0.55, 0.1);
+const LANDING_COLOR: Vector3<f32> = vec3(1.0, 0.3, 0.1);
+
+pub fn is_throwable(world: &World, entity_id: EntityId) -> bool {
+    entity_tags::has_tag(world, entity_id, EntityTags::EXPLOSIVE)
+}
+
+/// Velocity a throwable is launched with, from the rotation of the hand holding it
+pub fn throw_velocity(hand_rotation: Quaternion<f32>) -> Vector3<f32> {
+    hand_rotation.rotate_vector(vec3(0.0, 0.0, -1.0)) * THROW_SPEED
+}
+
+///
+/// predict
+///
+/// Trace the arc of an item thrown from `start`, up to where it first hits something in the
+/// physics world (other than itself) - the landing position is None if it flies out of range
+pub fn predict(
+    physics: &PhysicsWorld,
+    thrown_entity_id: EntityId,
+    start: Vector3<f32>,
+    velocity: Vector3<f32>,
+) -> ArcTrajectory {
+    // The teleport arc stops at a flat ground plane - put it out of reach, and let the
+    // raycasts below find what the item actually hits
+    let trajectory = ArcTrajectory::calculate(
+        start,
+        velocity,
+        velocity.magnitude(),
+        GRAVITY,
+        MAX_DISTANCE,
+        ARC_SEGMENTS,
+        start.y - MAX_DISTANCE,
+    );
+
+    let mut points = vec![start];
+    let mut landing_position = None;
+    for segment in trajectory.points.windows(2) {
+        let hit = physics.ray_cast3(
+            vec3_to_point3(segment[0]),
+            vec3_to_point3(segment[1]),
+            InternalCollisionGroups::WORLD | InternalCollisionGroups::ENTITY,
+            Some(thrown_entity_id),
+            true,
+        );
+
+        if let Some(hit) = hit {
+            let hit_point = point3_to_vec3(hit.hit_point);
+            points.push(hit_point);
+            landing_position = Some(hit_point);
+            break;
+        }
+        points.push(segment[1]);
+    }
+
+    ArcTrajectory {
+        points,
+        landing_position,
+        is_valid: landing_position.is_some(),
+        num_segments: trajectory.num_segments,
+    }
+}
+
+pub fn render(trajectory: &ArcTrajectory, animation_time: f32) -> Vec<SceneObject> {
+    let mut visuals = ArcRenderer::create_particle_arc(trajectory, ARC_COLOR);
+    if let Some(landing_position) = trajectory.landing_position {
+        visuals.push(ArcRenderer::create_target_indicator(
+            landing_position,
+            LANDING_COLOR,
+            ArcRenderConfig::default(),
+            animation_time,
+        ));
+    }
+    visuals
+}
@@ -94,6 +94,16 @@ pub fn get_position_from_transform(
     }
 }
 
+/// The entity's position, from PropPosition - None if it doesn't have one
+pub fn get_entity_position(world: &World, entity_id: EntityId) -> Option<Vector3<f32>> {
+    if let Ok(positions) = world.borrow::<View<PropPosition>>() {
+        if let Ok(prop) = positions.get(entity_id) {
+            return Some(prop.position);
+        }
+    }
+    None
+}
+
 pub fn has_refs(world: &World, entity_id: EntityId) -> bool {
     let v_has_refs = world.borrow::<View<PropHasRefs>>().unwrap();
 
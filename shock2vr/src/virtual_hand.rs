@@ -11,14 +11,26 @@ use tracing::{self, trace};
 
 use crate::{
     input_context::Hand,
+    interaction::{InteractionTarget, InteractionVerb},
     physics::{InternalCollisionGroups, PhysicsWorld, RayCastResult},
-    scripts::{Message, MessagePayload},
+    runtime_props::RuntimePropClimbable,
+    scripts::{Message, MessagePayload, internal_lock_script::is_blocked_by_lock},
+    throw_arc,
     util::{self, point3_to_vec3},
     vr_config::{self, Handedness},
 };
 
 const HAND_OFFSET: Vector3<f32> = vec3(0.0, 0.0, 0.0);
 
+// Where the fingertip sits relative to the hand, for pressing buttons
+const FINGERTIP_OFFSET: Vector3<f32> = vec3(0.0, 0.0, -0.08);
+
+// Size of the hand's physical collider, centered on the fingertip
+pub const HAND_COLLIDER_RADIUS: f32 = 0.04;
+
+// How close the hand needs to be to a climbable surface to grip it
+const CLIMB_GRIP_RADIUS: f32 = 0.1;
+
 #[derive(Clone)]
 pub struct VirtualHand {
     position: Vector3<f32>,
@@ -30,6 +42,9 @@ pub struct VirtualHand {
     // Keep track of last frobbed entity so frobbing is 'semi-auto'
     last_frobbed_entity: Option<EntityId>,
 
+    // Entities the fingertip is pushed into - a press frobs once, on contact
+    pressed_entities: Vec<EntityId>,
+
     hand_state: HandState,
 
     handedness: Handedness,
@@ -65,6 +80,11 @@ pub enum VirtualHandEffect {
     DropItem {
         entity_id: EntityId,
     },
+    // Let go of a primed throwable - it's launched, rather than dropped
+    ThrowItem {
+        entity_id: EntityId,
+        velocity: Vector3<f32>,
+    },
 }
 
 // All the details we need for the item we are grabbing..
@@ -77,6 +97,12 @@ pub enum HandState {
         entity_id: EntityId,
         // rigid_body_handle: RigidBodyHandle,
     },
+
+    // Hand is gripping a climbable surface - the hand stays put at the anchor, and the player moves instead
+    Climbing {
+        entity_id: EntityId,
+        anchor: Vector3<f32>,
+    },
 }
 
 impl VirtualHand {
@@ -91,6 +117,7 @@ impl VirtualHand {
             squeeze_value: 0.0,
             raytrace_hit: None,
             last_frobbed_entity: None,
+            pressed_entities: Vec::new(),
             hand_state: HandState::Empty,
             handedness,
         }
@@ -99,7 +126,7 @@ impl VirtualHand {
         match self.hand_state {
             // Nothing to do here!
             HandState::Empty => self.clone(),
-            HandState::Grabbing { entity_id } => {
+            HandState::Grabbing { entity_id } | HandState::Climbing { entity_id, .. } => {
                 if entity_id == entity_to_destroy_id {
                     VirtualHand {
                         hand_state: HandState::Empty,
@@ -114,11 +141,23 @@ impl VirtualHand {
 
     pub fn get_held_entity(&self) -> Option<EntityId> {
         match self.hand_state {
-            HandState::Empty => None,
+            HandState::Empty | HandState::Climbing { .. } => None,
             HandState::Grabbing { entity_id, .. } => Some(entity_id),
         }
     }
 
+    ///
+    /// get_climb_movement
+    ///
+    /// While gripping a climbable surface, how far the player needs to move to bring the hand back to
+    /// where it grabbed on - so pulling the hand down lifts the player up
+    pub fn get_climb_movement(&self) -> Option<Vector3<f32>> {
+        match self.hand_state {
+            HandState::Climbing { anchor, .. } => Some(anchor - self.position),
+            HandState::Empty | HandState::Grabbing { .. } => None,
+        }
+    }
+
     pub fn get_raytraced_entity(&self) -> Option<EntityId> {
         match &self.raytrace_hit {
             None => None,
@@ -138,6 +177,10 @@ impl VirtualHand {
         self.rotation
     }
 
+    pub fn get_fingertip_position(&self) -> Vector3<f32> {
+        self.position + self.rotation.rotate_vector(FINGERTIP_OFFSET)
+    }
+
     pub fn grab_entity(
         &self,
         _world: &World,
@@ -162,7 +205,7 @@ impl VirtualHand {
         _new_rigid_body: RigidBodyHandle,
     ) -> VirtualHand {
         match self.hand_state {
-            HandState::Empty => self.clone(),
+            HandState::Empty | HandState::Climbing { .. } => self.clone(),
             HandState::Grabbing { entity_id } => {
                 if entity_id == old_entity_id {
                     VirtualHand {
@@ -178,6 +221,35 @@ impl VirtualHand {
         }
     }
 
+    ///
+    /// world_pose
+    ///
+    /// Position and rotation of the hand in the world, from the player's pose and the tracked hand
+    pub fn world_pose(
+        pawn_pos: Vector3<f32>,
+        pawn_rot: Quaternion<f32>,
+        input_hand: &Hand,
+    ) -> (Vector3<f32>, Quaternion<f32>) {
+        (
+            pawn_pos + HAND_OFFSET + pawn_rot.rotate_vector(input_hand.position),
+            pawn_rot * input_hand.rotation,
+        )
+    }
+
+    /// Whether the hand is holding a throwable with the trigger pulled, ready to throw
+    pub fn is_primed_throwable(&self, world: &World) -> bool {
+        match self.hand_state {
+            HandState::Grabbing { entity_id } => {
+                self.trigger_value > 0.5 && throw_arc::is_throwable(world, entity_id)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        matches!(self.hand_state, HandState::Empty)
+    }
+
     pub fn update(
         prev: &VirtualHand,
         physics: &PhysicsWorld,
@@ -185,10 +257,12 @@ impl VirtualHand {
         pawn_pos: Vector3<f32>,
         pawn_rot: Quaternion<f32>,
         input_hand: &Hand,
+        target: Option<InteractionTarget>,
+        touch_enabled: bool,
     ) -> (VirtualHand, Vec<VirtualHandEffect>) {
         let handedness = prev.handedness;
-        let hand_position = pawn_pos + HAND_OFFSET + pawn_rot.rotate_vector(input_hand.position);
-        let hand_rotation = pawn_rot * input_hand.rotation;
+        let (hand_position, hand_rotation) =
+            VirtualHand::world_pose(pawn_pos, pawn_rot, input_hand);
 
         // Also do a raycast to provide the 'Hover' effect
         let ray_start = point3(hand_position.x, hand_position.y, hand_position.z);
@@ -218,7 +292,15 @@ impl VirtualHand {
 
                 // If we're holding onto something, but not grabbing, we can drop it
                 if input_hand.squeeze_value < 0.5 {
-                    let mut msgs = vec![VirtualHandEffect::DropItem { entity_id }];
+                    let drop_effect = if prev.is_primed_throwable(world) {
+                        VirtualHandEffect::ThrowItem {
+                            entity_id,
+                            velocity: throw_arc::throw_velocity(hand_rotation),
+                        }
+                    } else {
+                        VirtualHandEffect::DropItem { entity_id }
+                    };
+                    let mut msgs = vec![drop_effect];
 
                     let result_copy = result.clone();
                     if let Some(ray_cast_result) = result_copy {
@@ -241,6 +323,7 @@ impl VirtualHand {
                         squeeze_value: input_hand.squeeze_value,
                         raytrace_hit: None,
                         last_frobbed_entity: None,
+                        pressed_entities: Vec::new(),
                         hand_state: HandState::Empty,
                         handedness,
                     };
@@ -290,20 +373,43 @@ impl VirtualHand {
                         squeeze_value: input_hand.squeeze_value,
                         raytrace_hit: None,
                         last_frobbed_entity: None,
+                        pressed_entities: Vec::new(),
                         hand_state: next_hand_state,
                         handedness,
                     };
                     (updated_hand, msgs)
                 }
             }
+            HandState::Climbing { .. } => {
+                let hand_state = if input_hand.squeeze_value < 0.5 {
+                    HandState::Empty
+                } else {
+                    prev.hand_state
+                };
+                let updated_hand = VirtualHand {
+                    position: hand_position,
+                    rotation: hand_rotation,
+                    trigger_value: input_hand.trigger_value,
+                    squeeze_value: input_hand.squeeze_value,
+                    raytrace_hit: None,
+                    last_frobbed_entity: None,
+                    pressed_entities: Vec::new(),
+                    hand_state,
+                    handedness,
+                };
+                (updated_hand, Vec::new())
+            }
             HandState::Empty => handle_empty_hand_state(
                 handedness,
                 hand_position,
                 hand_rotation,
                 prev.last_frobbed_entity,
+                &prev.pressed_entities,
                 world,
                 physics,
                 input_hand,
+                target,
+                touch_enabled,
             ),
         };
 
@@ -379,9 +485,12 @@ fn handle_empty_hand_state(
     hand_position: Vector3<f32>,
     hand_rotation: Quaternion<f32>,
     frobbed_entity: Option<EntityId>,
+    pressed_entities: &[EntityId],
     world: &World,
     physics: &PhysicsWorld,
     input_hand: &Hand,
+    target: Option<InteractionTarget>,
+    touch_enabled: bool,
 ) -> (VirtualHand, Vec<VirtualHandEffect>) {
     let ray_start = point3(hand_position.x, hand_position.y, hand_position.z);
     let forward = hand_rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
@@ -405,20 +514,24 @@ fn handle_empty_hand_state(
     let mut last_frobbed_entity = frobbed_entity;
     let mut next_hand_state = HandState::Empty;
     if input_hand.trigger_value > 0.5 || input_hand.a_value > 0.5 {
-        if let Some(RayCastResult {
-            hit_point: _,
-            hit_normal: _,
-            maybe_entity_id: Some(entity),
-            maybe_rigid_body_handle: _,
-            is_sensor: _,
-        }) = result
-        {
+        let ray_entity = result.as_ref().and_then(|hit| hit.maybe_entity_id);
+        // Frob the interaction target, so we act on what's outlined - entities that aren't frobbable
+        // (no frob info) can still get the message by pointing right at them
+        let maybe_entity = if input_hand.trigger_value > 0.5 {
+            target.map(|target| target.entity_id).or(ray_entity)
+        } else {
+            ray_entity
+        };
+        if let Some(entity) = maybe_entity {
             if last_frobbed_entity.is_none() {
                 msgs.push(VirtualHandEffect::OutMessage {
                     message: Message {
                         to: entity,
                         payload: {
-                            if input_hand.trigger_value > 0.5 {
+                            // Without the key, pulling the trigger on a lock picks it
+                            if input_hand.trigger_value > 0.5 && is_blocked_by_lock(world, entity) {
+                                MessagePayload::Hack
+                            } else if input_hand.trigger_value > 0.5 {
                                 MessagePayload::Frob
                             } else {
                                 MessagePayload::Slay
@@ -436,22 +549,45 @@ fn handle_empty_hand_state(
         last_frobbed_entity = None
     }
 
-    if input_hand.squeeze_value > 0.5 {
-        if let Some(RayCastResult {
-            hit_point: _,
-            hit_normal: _,
-            maybe_entity_id: Some(entity_id),
-            maybe_rigid_body_handle: Some(rigid_body_handle),
-            is_sensor: _,
-        }) = result
+    // Physical presses, as an alternative to pointing at a button and pulling the trigger
+    let fingertip_position = hand_position + hand_rotation.rotate_vector(FINGERTIP_OFFSET);
+    let now_pressed_entities = if touch_enabled {
+        get_pressed_entities(world, physics, fingertip_position)
+    } else {
+        Vec::new()
+    };
+    for entity_id in &now_pressed_entities {
+        if !pressed_entities.contains(entity_id) {
+            msgs.push(VirtualHandEffect::OutMessage {
+                message: Message {
+                    to: *entity_id,
+                    payload: MessagePayload::Frob,
+                },
+            });
+        }
+    }
+
+    let climbable_entity = if input_hand.squeeze_value > 0.5 {
+        get_climbable_entity(world, physics, hand_position)
+    } else {
+        None
+    };
+
+    if let Some(entity_id) = climbable_entity {
+        next_hand_state = HandState::Climbing {
+            entity_id,
+            anchor: hand_position,
+        };
+    } else if input_hand.squeeze_value > 0.5 {
+        if let Some(InteractionTarget {
+            entity_id,
+            verb: InteractionVerb::PickUp,
+            ..
+        }) = target
         {
-            if can_grab_item(world, entity_id) {
-                let position = &physics.get_position(rigid_body_handle).unwrap();
-                let _dir = hand_position - position;
-                msgs.push(VirtualHandEffect::HoldItem { entity_id });
+            msgs.push(VirtualHandEffect::HoldItem { entity_id });
 
-                next_hand_state = HandState::Grabbing { entity_id };
-            }
+            next_hand_state = HandState::Grabbing { entity_id };
         }
     }
 
@@ -462,6 +598,7 @@ fn handle_empty_hand_state(
         squeeze_value: input_hand.squeeze_value,
         raytrace_hit: result,
         last_frobbed_entity,
+        pressed_entities: now_pressed_entities,
         hand_state: next_hand_state,
         handedness,
     };
@@ -482,18 +619,59 @@ fn get_held_position_orientation(
     vr_config::get_vr_hand_model_adjustments_from_entity(entity_id, world, handedness)
 }
 
-fn can_grab_item(world: &World, entity_id: EntityId) -> bool {
+///
+/// get_pressed_entities
+///
+/// Frobbable fixtures (buttons, switches, keypads) the fingertip is pushed into. Movable items
+/// are left out - the hand collider pushes those around instead.
+fn get_pressed_entities(
+    world: &World,
+    physics: &PhysicsWorld,
+    fingertip_position: Vector3<f32>,
+) -> Vec<EntityId> {
     let v_prop_frobinfo = world.borrow::<View<PropFrobInfo>>().unwrap();
 
-    if let Ok(frob_info) = v_prop_frobinfo.get(entity_id) {
-        if frob_info.world_action.contains(FrobFlag::MOVE)
-            || frob_info.world_action.contains(FrobFlag::USE_AMMO)
-        {
-            return true;
+    let mut pressed_entities = Vec::new();
+    let touched_entities = physics.entities_at_point(
+        fingertip_position,
+        InternalCollisionGroups::ENTITY | InternalCollisionGroups::SELECTABLE,
+    );
+    for touched_entity in touched_entities {
+        let entity_id = util::resolve_proxy_entity(world, touched_entity);
+        let is_pressable = v_prop_frobinfo
+            .get(entity_id)
+            .map(|frob_info| {
+                frob_info.world_action.contains(FrobFlag::SCRIPT)
+                    && !frob_info.world_action.contains(FrobFlag::MOVE)
+            })
+            .unwrap_or(false);
+
+        if is_pressable && !pressed_entities.contains(&entity_id) {
+            pressed_entities.push(entity_id);
         }
     }
+    pressed_entities
+}
 
-    false
+///
+/// get_climbable_entity
+///
+/// A climbable surface (ladder, pipe, ledge) within reach of the hand, if any
+fn get_climbable_entity(
+    world: &World,
+    physics: &PhysicsWorld,
+    hand_position: Vector3<f32>,
+) -> Option<EntityId> {
+    let v_climbable = world.borrow::<View<RuntimePropClimbable>>().unwrap();
+
+    physics
+        .entities_in_sphere(
+            hand_position,
+            CLIMB_GRIP_RADIUS,
+            InternalCollisionGroups::ENTITY | InternalCollisionGroups::SELECTABLE,
+        )
+        .into_iter()
+        .find(|entity_id| v_climbable.contains(*entity_id))
 }
 
 ///
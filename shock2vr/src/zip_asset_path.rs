@@ -9,8 +9,10 @@ use std::{
 use engine::assets::asset_paths::{AbstractAssetPath, ReadableAndSeekable};
 use zip::ZipArchive;
 
+use crate::paths;
+
 pub struct ZipAssetPath {
-    archive: Mutex<ZipArchive<BufReader<File>>>,
+    archive: Mutex<ZipArchive<Box<dyn ReadableAndSeekable>>>,
     asset_to_path: HashMap<String, String>,
 }
 
@@ -21,8 +23,24 @@ impl ZipAssetPath {
 
     pub fn new2(zip_path: String, collapse_paths: bool) -> Box<ZipAssetPath> {
         let file = File::open(zip_path).unwrap();
-        let reader = BufReader::new(file);
+        Self::from_reader(Box::new(BufReader::new(file)), collapse_paths)
+    }
+
+    ///
+    /// from_data_file
+    ///
+    /// Open an archive from the game data (see `paths::data_file_system`), relative to the data
+    /// root
+    pub fn from_data_file(path: &str, collapse_paths: bool) -> Box<ZipAssetPath> {
+        let reader = paths::open_data_file(path)
+            .unwrap_or_else(|| panic!("Unable to open archive: {}", path));
+        Self::from_reader(reader, collapse_paths)
+    }
 
+    pub fn from_reader(
+        reader: Box<dyn ReadableAndSeekable>,
+        collapse_paths: bool,
+    ) -> Box<ZipAssetPath> {
         let mut archive = zip::ZipArchive::new(reader).unwrap();
         let mut asset_to_path = HashMap::new();
         for i in 0..archive.len() {
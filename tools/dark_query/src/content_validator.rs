@@ -0,0 +1,365 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use dark::{
+    gamesys::Gamesys,
+    properties::{
+        PoseType, PropAmbientHacked, PropCreaturePose, PropModelName, PropObjectSound, Property,
+    },
+    ss2_bin_ai_loader, ss2_bin_header, ss2_bin_obj_loader,
+    ss2_entity_info::SystemShock2EntityInfo,
+};
+use engine::assets::asset_paths::{AbstractAssetPath, AssetPath};
+use serde::Serialize;
+use shipyard::{Get, View, World};
+use shock2vr::{paths, zip_asset_path::ZipAssetPath};
+
+use crate::entity_analyzer::extract_names_with_inheritance;
+
+// Resource archives the runtime loads content from (see shock2vr's asset paths)
+const RESOURCE_ARCHIVES: [&str; 11] = [
+    "res/obj.crf",
+    "res/bitmap.crf",
+    "res/fam.crf",
+    "res/iface.crf",
+    "res/intrface.crf",
+    "res/mesh.crf",
+    "res/motions.crf",
+    "res/objicon.crf",
+    "res/snd.crf",
+    "res/snd2.crf",
+    "res/song.crf",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Model,
+    Texture,
+    Skeleton,
+    Motion,
+    SoundSchema,
+    Sound,
+    // The file is there, but it couldn't be parsed
+    UnreadableModel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingAsset {
+    pub entity_id: i32,
+    pub entity_name: String,
+    pub property: String,
+    pub kind: AssetKind,
+    pub asset: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnparsedChunk {
+    pub chunk: String,
+    pub count: usize,
+}
+
+///
+/// ValidationReport
+///
+/// Result of a lint pass over a mission's content - every asset referenced by an entity or template's
+/// properties that can't be resolved, along with the property and link chunks we don't parse yet
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+    pub mission: String,
+    pub entities_checked: usize,
+    pub missing_assets: Vec<MissingAsset>,
+    pub unparsed_properties: Vec<UnparsedChunk>,
+    pub unparsed_links: Vec<UnparsedChunk>,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.missing_assets.is_empty()
+    }
+
+    pub fn missing_by_kind(&self) -> BTreeMap<AssetKind, usize> {
+        let mut counts = BTreeMap::new();
+        for missing in &self.missing_assets {
+            *counts.entry(missing.kind).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+// An asset referenced by a property, before it's been checked
+struct AssetReference {
+    property: &'static str,
+    kind: AssetKind,
+    asset: String,
+}
+
+pub struct ContentValidator<'a> {
+    base_path: String,
+    assets: Box<dyn AbstractAssetPath>,
+    gamesys: &'a Gamesys,
+    // Models are shared between lots of entities - only parse each once
+    model_dependencies: HashMap<String, Vec<(AssetKind, String)>>,
+}
+
+impl<'a> ContentValidator<'a> {
+    pub fn new(gamesys: &'a Gamesys) -> ContentValidator<'a> {
+        let data_root = paths::data_root();
+        let mut asset_paths: Vec<Box<dyn AbstractAssetPath>> = vec![
+            AssetPath::folder("res/mesh".to_owned()),
+            AssetPath::folder("res/obj".to_owned()),
+        ];
+        for archive in RESOURCE_ARCHIVES {
+            let archive_path = data_root.join(archive);
+            if archive_path.exists() {
+                asset_paths.push(ZipAssetPath::new(
+                    archive_path.to_string_lossy().into_owned(),
+                ));
+            }
+        }
+
+        ContentValidator {
+            base_path: data_root.to_string_lossy().into_owned(),
+            assets: AssetPath::combine(asset_paths),
+            gamesys,
+            model_dependencies: HashMap::new(),
+        }
+    }
+
+    pub fn validate(
+        &mut self,
+        mission: &str,
+        entity_info: &SystemShock2EntityInfo,
+    ) -> ValidationReport {
+        let mut entity_ids: Vec<i32> = entity_info.entity_to_properties.keys().copied().collect();
+        entity_ids.sort();
+
+        let mut missing_assets = Vec::new();
+        for entity_id in &entity_ids {
+            let properties = &entity_info.entity_to_properties[entity_id];
+            let missing = self.validate_entity(properties);
+            if missing.is_empty() {
+                continue;
+            }
+
+            let entity_name =
+                extract_names_with_inheritance(*entity_id, entity_info).display_names();
+            missing_assets.extend(missing.into_iter().map(|reference| MissingAsset {
+                entity_id: *entity_id,
+                entity_name: entity_name.clone(),
+                property: reference.property.to_owned(),
+                kind: reference.kind,
+                asset: reference.asset,
+            }));
+        }
+
+        ValidationReport {
+            mission: mission.to_owned(),
+            entities_checked: entity_ids.len(),
+            missing_assets,
+            unparsed_properties: count_chunks(&entity_info.unparsed_properties),
+            unparsed_links: count_chunks(&entity_info.unparsed_links),
+        }
+    }
+
+    fn validate_entity(&mut self, properties: &[Arc<Box<dyn Property>>]) -> Vec<AssetReference> {
+        let references = collect_references(properties);
+
+        let mut missing = Vec::new();
+        for reference in references {
+            match reference.kind {
+                AssetKind::Model => {
+                    let model_file = format!("{}.bin", reference.asset);
+                    if !self.exists(&model_file) {
+                        missing.push(reference);
+                        continue;
+                    }
+
+                    for (kind, asset) in self.model_dependencies(&model_file) {
+                        if kind == AssetKind::UnreadableModel || !self.exists(&asset) {
+                            missing.push(AssetReference {
+                                property: reference.property,
+                                kind,
+                                asset,
+                            });
+                        }
+                    }
+                }
+                AssetKind::Motion => {
+                    if !self.exists(&format!("{}_.mc", reference.asset)) {
+                        missing.push(reference);
+                    }
+                }
+                AssetKind::SoundSchema => {
+                    // Sound properties can name a schema, or a sample directly
+                    match self.gamesys.sound_schema.get_samples(&reference.asset) {
+                        Some(samples) => {
+                            missing.extend(
+                                samples
+                                    .into_iter()
+                                    .map(|sample| format!("{sample}.wav"))
+                                    .filter(|sample_file| !self.exists(sample_file))
+                                    .map(|sample_file| AssetReference {
+                                        property: reference.property,
+                                        kind: AssetKind::Sound,
+                                        asset: sample_file,
+                                    }),
+                            );
+                        }
+                        None => {
+                            if !self.exists(&format!("{}.wav", reference.asset)) {
+                                missing.push(reference);
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    if !self.exists(&reference.asset) {
+                        missing.push(reference);
+                    }
+                }
+            }
+        }
+        missing
+    }
+
+    fn exists(&self, asset_name: &str) -> bool {
+        self.assets
+            .exists(self.base_path.clone(), asset_name.to_ascii_lowercase())
+    }
+
+    // Textures (and the skeleton, for AI meshes) a model needs
+    fn model_dependencies(&mut self, model_file: &str) -> Vec<(AssetKind, String)> {
+        if let Some(dependencies) = self.model_dependencies.get(model_file) {
+            return dependencies.clone();
+        }
+
+        let dependencies = self.read_model_dependencies(model_file);
+        self.model_dependencies
+            .insert(model_file.to_owned(), dependencies.clone());
+        dependencies
+    }
+
+    fn read_model_dependencies(&self, model_file: &str) -> Vec<(AssetKind, String)> {
+        let Some(reader) = self
+            .assets
+            .get_reader(self.base_path.clone(), model_file.to_ascii_lowercase())
+        else {
+            return vec![(AssetKind::UnreadableModel, model_file.to_owned())];
+        };
+        let mut reader = reader.into_inner();
+
+        // The bin loaders panic on data they don't understand - report it instead
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let header = ss2_bin_header::read(&mut reader);
+            match header.bin_type {
+                ss2_bin_header::BinFileType::Obj => {
+                    let mesh = ss2_bin_obj_loader::read(&mut reader, &header);
+                    mesh.materials
+                        .iter()
+                        .filter(|material| material.is_texture_map())
+                        .map(|material| (AssetKind::Texture, material.name.clone()))
+                        .collect::<Vec<_>>()
+                }
+                ss2_bin_header::BinFileType::Mesh => {
+                    let mesh = ss2_bin_ai_loader::read(&mut reader, &header);
+                    let skeleton = model_file
+                        .rsplit_once('.')
+                        .map(|(name, _)| format!("{name}.cal"))
+                        .unwrap_or_else(|| format!("{model_file}.cal"));
+                    std::iter::once((AssetKind::Skeleton, skeleton))
+                        .chain(
+                            mesh.materials
+                                .iter()
+                                .map(|material| (AssetKind::Texture, material.name().to_owned())),
+                        )
+                        .collect::<Vec<_>>()
+                }
+            }
+        }));
+
+        match result {
+            Ok(mut dependencies) => {
+                dependencies.sort();
+                dependencies.dedup();
+                dependencies
+            }
+            Err(_) => vec![(AssetKind::UnreadableModel, model_file.to_owned())],
+        }
+    }
+}
+
+fn collect_references(properties: &[Arc<Box<dyn Property>>]) -> Vec<AssetReference> {
+    let mut world = World::new();
+    let entity = world.add_entity(());
+    for prop in properties {
+        prop.initialize(&mut world, entity);
+    }
+
+    let mut references = Vec::new();
+
+    if let Ok(view) = world.borrow::<View<PropModelName>>() {
+        if let Ok(model) = view.get(entity) {
+            references.push(AssetReference {
+                property: "P$ModelName",
+                kind: AssetKind::Model,
+                asset: model.0.clone(),
+            });
+        }
+    }
+
+    // Only motion-name poses refer to a motion directly - tag poses go through the motion db
+    if let Ok(view) = world.borrow::<View<PropCreaturePose>>() {
+        if let Ok(pose) = view.get(entity) {
+            if pose.pose_type.contains(PoseType::MOTION_NAME) {
+                references.push(AssetReference {
+                    property: "P$CretPose",
+                    kind: AssetKind::Motion,
+                    asset: pose.motion_or_tag_name.clone(),
+                });
+            }
+        }
+    }
+
+    if let Ok(view) = world.borrow::<View<PropObjectSound>>() {
+        if let Ok(sound) = view.get(entity) {
+            references.push(AssetReference {
+                property: "P$ObjSoundN",
+                kind: AssetKind::SoundSchema,
+                asset: sound.name.clone(),
+            });
+        }
+    }
+
+    if let Ok(view) = world.borrow::<View<PropAmbientHacked>>() {
+        if let Ok(ambient) = view.get(entity) {
+            for schema in [&ambient.schema, &ambient.aux1, &ambient.aux2] {
+                if !schema.is_empty() {
+                    references.push(AssetReference {
+                        property: "P$AmbientHa",
+                        kind: AssetKind::SoundSchema,
+                        asset: schema.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    references
+}
+
+fn count_chunks<T>(chunks: &HashMap<String, Vec<T>>) -> Vec<UnparsedChunk> {
+    let mut counts: Vec<UnparsedChunk> = chunks
+        .iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(chunk, entries)| UnparsedChunk {
+            chunk: chunk.clone(),
+            count: entries.len(),
+        })
+        .collect();
+    counts.sort_by(|a, b| a.chunk.cmp(&b.chunk));
+    counts
+}
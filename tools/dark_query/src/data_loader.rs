@@ -1,11 +1,16 @@
 use anyhow::{Context, Result};
 use dark::{
     gamesys::{self},
+    mission::{self, SystemShock2Level},
     properties::get,
-    ss2_chunk_file_reader,
+    ss2_chunk_file_reader::{self, ChunkReport},
     ss2_entity_info::{self, SystemShock2EntityInfo, merge_with_gamesys},
 };
-use shock2vr::paths;
+use engine::assets::{
+    asset_cache::AssetCache,
+    asset_paths::{AbstractAssetPath, AssetPath},
+};
+use shock2vr::{paths, zip_asset_path::ZipAssetPath};
 use std::{fs::File, io::BufReader};
 use tracing::info;
 
@@ -47,6 +52,14 @@ pub fn load_gamesys_only() -> Result<SystemShock2EntityInfo> {
 
 /// Load entity data from shock2.gam + specified mission file
 pub fn load_gamesys_with_mission(mission_name: &str) -> Result<SystemShock2EntityInfo> {
+    let (_gamesys, entity_info) = load_gamesys_and_mission(mission_name)?;
+    Ok(entity_info)
+}
+
+/// Load the gamesys, along with the entity data merged from shock2.gam + specified mission file
+pub fn load_gamesys_and_mission(
+    mission_name: &str,
+) -> Result<(gamesys::Gamesys, SystemShock2EntityInfo)> {
     info!(
         "Loading gamesys + mission data from shock2.gam and {}",
         mission_name
@@ -93,7 +106,7 @@ pub fn load_gamesys_with_mission(mission_name: &str) -> Result<SystemShock2Entit
         merged_entity_info.entity_to_properties.len()
     );
 
-    Ok(merged_entity_info)
+    Ok((gamesys, merged_entity_info))
 }
 
 /// Load entity data based on optional mission parameter
@@ -103,3 +116,66 @@ pub fn load_entity_data(mission: Option<&str>) -> Result<SystemShock2EntityInfo>
         None => load_gamesys_only(),
     }
 }
+
+/// Parse shock2.gam, or a mission file, in strict mode - reporting which chunks our parsers cover
+pub fn load_chunk_report(mission: Option<&str>) -> Result<ChunkReport> {
+    let (properties, links, links_with_data) = get();
+
+    Ok(match mission {
+        None => {
+            info!("Reading shock2.gam in strict mode");
+            let mut reader = open_data_file("shock2.gam")?;
+            gamesys::read_chunk_report(&mut reader, &links, &links_with_data, &properties)
+        }
+        Some(mission_name) => {
+            let gamesys = load_gamesys()?;
+            info!("Reading {} in strict mode", mission_name);
+            let mut reader = open_data_file(mission_name)?;
+            mission::read_chunk_report(&mut reader, &gamesys, &links, &links_with_data, &properties)
+        }
+    })
+}
+
+/// Asset cache over the level textures - the `res/fam` folder, if it's been extracted, and `res/fam.crf`
+pub fn load_texture_asset_cache() -> AssetCache {
+    let data_root = paths::data_root();
+    let mut asset_paths: Vec<Box<dyn AbstractAssetPath>> =
+        vec![AssetPath::folder("res/fam".to_owned())];
+    let archive_path = data_root.join("res/fam.crf");
+    if archive_path.exists() {
+        asset_paths.push(ZipAssetPath::new(
+            archive_path.to_string_lossy().into_owned(),
+        ));
+    }
+
+    AssetCache::new(
+        data_root.to_string_lossy().into_owned(),
+        AssetPath::combine(asset_paths),
+    )
+}
+
+/// Load a mission's level - cells, geometry and textures - without needing a GL context
+pub fn load_level(mission_name: &str, asset_cache: &mut AssetCache) -> Result<SystemShock2Level> {
+    let gamesys = load_gamesys()?;
+    let (properties, links, links_with_data) = get();
+
+    info!("Reading level geometry from {}", mission_name);
+    let mut reader = open_data_file(mission_name)?;
+    Ok(mission::read_headless(
+        asset_cache,
+        &mut reader,
+        &gamesys,
+        &links,
+        &links_with_data,
+        &properties,
+    ))
+}
+
+fn open_data_file(name: &str) -> Result<BufReader<File>> {
+    let path = paths::data_root().join(name);
+    if !path.exists() {
+        return Err(anyhow::anyhow!("{} not found.", path.display()));
+    }
+    let file = File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
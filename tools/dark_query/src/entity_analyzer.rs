@@ -1,12 +1,13 @@
 use dark::{
     properties::{
-        Link, PropObjName, PropObjShortName, PropScripts, PropSymName, PropTemplateId, Property,
+        Link, PropObjName, PropObjShortName, PropPosition, PropScripts, PropStartLoc, PropSymName,
+        PropTemplateId, Property,
     },
     ss2_entity_info::{self, SystemShock2EntityInfo},
 };
 use glob::Pattern;
 use shipyard::{Get, View, World};
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub enum EntityType {
@@ -66,7 +67,7 @@ pub struct FilterCriteria {
 }
 
 /// Extract name properties by creating a temporary world and reading components
-pub fn extract_names_public(properties: &[Rc<Box<dyn Property>>]) -> EntityNames {
+pub fn extract_names_public(properties: &[Arc<Box<dyn Property>>]) -> EntityNames {
     extract_names(properties)
 }
 
@@ -132,7 +133,7 @@ pub fn extract_template_id_with_inheritance(
     ancestors.last().copied()
 }
 
-fn extract_names(properties: &[Rc<Box<dyn Property>>]) -> EntityNames {
+fn extract_names(properties: &[Arc<Box<dyn Property>>]) -> EntityNames {
     let mut world = World::new();
     let entity = world.add_entity(());
 
@@ -172,7 +173,7 @@ fn extract_names(properties: &[Rc<Box<dyn Property>>]) -> EntityNames {
     }
 }
 
-fn extract_template_id(properties: &[Rc<Box<dyn Property>>]) -> Option<i32> {
+fn extract_template_id(properties: &[Arc<Box<dyn Property>>]) -> Option<i32> {
     let mut world = World::new();
     let entity = world.add_entity(());
 
@@ -294,7 +295,7 @@ fn pattern_matches_simple_glob(text: &str, pattern: &str) -> bool {
 }
 
 /// Get property type names from the property list
-fn get_property_names(properties: &[Rc<Box<dyn Property>>]) -> Vec<String> {
+fn get_property_names(properties: &[Arc<Box<dyn Property>>]) -> Vec<String> {
     properties
         .iter()
         .map(|prop| {
@@ -419,7 +420,7 @@ fn get_link_types_with_inheritance(
 }
 
 /// Extract script names from properties
-fn get_script_names(properties: &[Rc<Box<dyn Property>>]) -> Vec<String> {
+fn get_script_names(properties: &[Arc<Box<dyn Property>>]) -> Vec<String> {
     // Create a temporary world to extract script information
     let mut world = World::new();
     let entity = world.add_entity(());
@@ -538,6 +539,83 @@ pub fn analyze_entities(entity_info: &SystemShock2EntityInfo) -> Vec<EntitySumma
     summaries
 }
 
+#[derive(Debug, Clone)]
+pub struct SpawnPointSummary {
+    pub id: i32,
+    pub name: String,
+    /// The start location number, for markers that have one
+    pub start_loc: Option<i32>,
+    pub position: Option<(f32, f32, f32)>,
+}
+
+/// Find the named spawn points in a mission - start location markers, and the landing points
+/// they link to - sorted by name. These are the names accepted by `mission.mis:<name>`.
+pub fn find_spawn_points(entity_info: &SystemShock2EntityInfo) -> Vec<SpawnPointSummary> {
+    let mut spawn_entity_ids = Vec::new();
+    for (entity_id, properties) in &entity_info.entity_to_properties {
+        if *entity_id < 0 || extract_start_loc(properties).is_none() {
+            continue;
+        }
+
+        spawn_entity_ids.push(*entity_id);
+        if let Some(links) = entity_info.template_to_links.get(entity_id) {
+            spawn_entity_ids.extend(
+                links
+                    .to_links
+                    .iter()
+                    .filter(|link| link.link == Link::LandingPoint)
+                    .map(|link| link.to_template_id),
+            );
+        }
+    }
+
+    let mut spawn_points: Vec<SpawnPointSummary> = spawn_entity_ids
+        .into_iter()
+        .filter_map(|entity_id| {
+            let properties = entity_info.entity_to_properties.get(&entity_id)?;
+            // Only the entity's own name counts - inherited names belong to the archetype
+            let name = extract_names(properties).sym_name?;
+            let position = extract_position(properties);
+            Some(SpawnPointSummary {
+                id: entity_id,
+                name,
+                start_loc: extract_start_loc(properties),
+                position,
+            })
+        })
+        .collect();
+
+    spawn_points.sort_by_key(|spawn_point| (spawn_point.name.to_ascii_lowercase(), spawn_point.id));
+    spawn_points.dedup_by_key(|spawn_point| spawn_point.id);
+    spawn_points
+}
+
+fn extract_start_loc(properties: &[Arc<Box<dyn Property>>]) -> Option<i32> {
+    let mut world = World::new();
+    let entity = world.add_entity(());
+
+    for prop in properties {
+        prop.initialize(&mut world, entity);
+    }
+
+    let view = world.borrow::<View<PropStartLoc>>().ok()?;
+    view.get(entity).ok().map(|start_loc| start_loc.0)
+}
+
+fn extract_position(properties: &[Arc<Box<dyn Property>>]) -> Option<(f32, f32, f32)> {
+    let mut world = World::new();
+    let entity = world.add_entity(());
+
+    for prop in properties {
+        prop.initialize(&mut world, entity);
+    }
+
+    let view = world.borrow::<View<PropPosition>>().ok()?;
+    view.get(entity)
+        .ok()
+        .map(|pos| (pos.position.x, pos.position.y, pos.position.z))
+}
+
 /// Apply filters to entity summaries
 pub fn filter_entities(
     summaries: &[EntitySummary],
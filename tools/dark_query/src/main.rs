@@ -1,15 +1,22 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use dark::motion::MotionDbOverlay;
 use shock2vr::zip_asset_path::ZipAssetPath;
 use tracing::info;
 
+mod content_validator;
 mod data_loader;
 mod entity_analyzer;
 mod motion_analyzer;
 mod speech_analyzer;
 
+use content_validator::ContentValidator;
 use data_loader::load_entity_data;
-use entity_analyzer::{EntityType, FilterCriteria, analyze_entities, filter_entities};
+use entity_analyzer::{
+    EntityType, FilterCriteria, analyze_entities, filter_entities, find_spawn_points,
+};
 use motion_analyzer::MotionAnalyzer;
 use speech_analyzer::SpeechAnalyzer;
 
@@ -80,6 +87,16 @@ enum Commands {
         #[arg(long)]
         limit: Option<usize>,
     },
+    /// Export the motion database as JSON (the form documented in `dark::motion::MotionDbExport`)
+    MotionExport {
+        /// Overlay to apply first (see `motiondb_overlay.json`)
+        #[arg(long)]
+        overlay: Option<PathBuf>,
+
+        /// Write the export to a file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
     /// Query map chunk data from interface files
     Maps {
         /// Mission name to load map data for (e.g., "MEDSCI1", "MEDSCI2")
@@ -107,6 +124,49 @@ enum Commands {
         #[arg(long)]
         limit: Option<usize>,
     },
+    /// List the named spawn points (landing points and start markers) in a mission - usable as
+    /// `--mission medsci1.mis:<name>` in the runtimes
+    SpawnPoints {
+        /// Mission file to load (e.g., "medsci1.mis")
+        mission: String,
+    },
+    /// Check that every model, texture, motion and sound referenced by a mission's entities exists,
+    /// and list unparsed property / link chunks - prints a JSON report
+    /// Report which chunks of shock2.gam or a mission our parsers read, and how much of each
+    Chunks {
+        /// Mission file to report on (e.g., "medsci1.mis") - shock2.gam if not specified
+        mission: Option<String>,
+
+        /// Write the report to a file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Exit with an error if any parsed chunk wasn't read to its declared length
+        #[arg(long)]
+        strict: bool,
+    },
+    Validate {
+        /// Mission file to validate (e.g., "medsci1.mis")
+        mission: String,
+
+        /// Write the report to a file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Exit with an error if any assets are missing
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Export a mission's world geometry, with its UVs and textures, as glTF - writes the .gltf,
+    /// a .bin buffer and a textures/ folder of PNGs
+    ExportGltf {
+        /// Mission file to export (e.g., "medsci1.mis")
+        mission: String,
+
+        /// Where to write the document - <mission>.gltf in the current directory by default
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
 }
 
 fn init_logging(verbose: bool) -> Result<()> {
@@ -116,9 +176,11 @@ fn init_logging(verbose: bool) -> Result<()> {
         tracing::Level::INFO
     };
 
+    // Log to stderr, so reports printed to stdout stay machine-readable
     tracing_subscriber::fmt()
         .with_max_level(level)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .init();
 
     Ok(())
@@ -174,6 +236,9 @@ fn main() -> Result<()> {
         } => {
             handle_motion_command(&creature_type, &tags, limit)?;
         }
+        Commands::MotionExport { overlay, output } => {
+            handle_motion_export_command(overlay.as_deref(), output.as_deref())?;
+        }
         Commands::Maps { mission } => {
             handle_maps_command(&mission)?;
         }
@@ -186,6 +251,26 @@ fn main() -> Result<()> {
         Commands::Aipath { mission, limit } => {
             handle_aipath_command(&mission, limit)?;
         }
+        Commands::SpawnPoints { mission } => {
+            handle_spawn_points_command(&mission)?;
+        }
+        Commands::Chunks {
+            mission,
+            output,
+            strict,
+        } => {
+            handle_chunks_command(mission.as_deref(), output.as_deref(), strict)?;
+        }
+        Commands::Validate {
+            mission,
+            output,
+            strict,
+        } => {
+            handle_validate_command(&mission, output.as_deref(), strict)?;
+        }
+        Commands::ExportGltf { mission, output } => {
+            handle_export_gltf_command(&mission, output.as_deref())?;
+        }
     }
 
     Ok(())
@@ -456,7 +541,7 @@ fn show_inheritance_tree(
 
 fn show_properties_for_entity(
     _entity_id: i32,
-    properties: &[std::rc::Rc<Box<dyn dark::properties::Property>>],
+    properties: &[std::sync::Arc<Box<dyn dark::properties::Property>>],
     depth: usize,
 ) {
     let indent = "  ".repeat(depth);
@@ -602,6 +687,34 @@ fn handle_motion_command(creature_type: &str, tags: &[String], limit: Option<usi
     Ok(())
 }
 
+fn handle_motion_export_command(overlay: Option<&Path>, output: Option<&Path>) -> Result<()> {
+    info!("Loading motion database...");
+    let mut motion_analyzer = MotionAnalyzer::new()?;
+
+    if let Some(path) = overlay {
+        let json = std::fs::read_to_string(path)?;
+        let overlay = MotionDbOverlay::from_json(&json)?;
+        info!(
+            "Applying overlay {}: {} bindings, {} removed",
+            path.display(),
+            overlay.bindings.len(),
+            overlay.remove.len()
+        );
+        motion_analyzer.apply_overlay(overlay);
+    }
+
+    let json = motion_analyzer.export_json()?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, json)?;
+            info!("Wrote motion database to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
 fn handle_maps_command(mission: &str) -> Result<()> {
     info!("Loading map data for {}...", mission);
 
@@ -680,6 +793,137 @@ fn handle_maps_command(mission: &str) -> Result<()> {
     Ok(())
 }
 
+fn handle_spawn_points_command(mission: &str) -> Result<()> {
+    info!("Loading entity data...");
+    let entity_info = load_entity_data(Some(mission))?;
+
+    let spawn_points = find_spawn_points(&entity_info);
+    if spawn_points.is_empty() {
+        println!("No named spawn points in {}", mission);
+        return Ok(());
+    }
+
+    println!("=== Spawn points in {} ===", mission);
+    for spawn_point in &spawn_points {
+        let start_loc = spawn_point
+            .start_loc
+            .map(|start_loc| format!(" start_loc={}", start_loc))
+            .unwrap_or_default();
+        let position = spawn_point
+            .position
+            .map(|(x, y, z)| format!(" at ({:.1}, {:.1}, {:.1})", x, y, z))
+            .unwrap_or_default();
+        println!(
+            "  {} (entity {}){}{}",
+            spawn_point.name, spawn_point.id, start_loc, position
+        );
+    }
+    println!();
+    println!("Usage: --mission {}:<name>", mission);
+
+    Ok(())
+}
+
+fn handle_chunks_command(mission: Option<&str>, output: Option<&Path>, strict: bool) -> Result<()> {
+    let report = data_loader::load_chunk_report(mission)?;
+    let file_name = mission.unwrap_or("shock2.gam");
+
+    info!(
+        "{}: {:.1}% of chunk bytes parsed, {} unknown chunks",
+        file_name,
+        report.coverage() * 100.0,
+        report.unknown_chunks.len()
+    );
+    for chunk in report.mismatched() {
+        info!(
+            "Chunk {} - consumed {:?} of {} bytes",
+            chunk.name, chunk.consumed_bytes, chunk.declared_bytes
+        );
+    }
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, json)?;
+            info!("Wrote report to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+
+    if strict && !report.is_clean() {
+        anyhow::bail!(
+            "{} chunks in {} weren't read to their declared length",
+            report.mismatched().count(),
+            file_name
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_validate_command(mission: &str, output: Option<&Path>, strict: bool) -> Result<()> {
+    let (gamesys, entity_info) = data_loader::load_gamesys_and_mission(mission)?;
+
+    info!("Validating content...");
+    let mut validator = ContentValidator::new(&gamesys);
+    let report = validator.validate(mission, &entity_info);
+
+    for (kind, count) in report.missing_by_kind() {
+        info!("Missing {:?}: {}", kind, count);
+    }
+    info!(
+        "{} unparsed property chunks, {} unparsed link chunks",
+        report.unparsed_properties.len(),
+        report.unparsed_links.len()
+    );
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, json)?;
+            info!("Wrote report to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+
+    if strict && !report.is_clean() {
+        anyhow::bail!(
+            "{} missing assets in {}",
+            report.missing_assets.len(),
+            mission
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_export_gltf_command(mission: &str, output: Option<&Path>) -> Result<()> {
+    let output = match output {
+        Some(path) => path.to_path_buf(),
+        None => Path::new(mission).with_extension("gltf"),
+    };
+    let name = output
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid output path {}", output.display()))?;
+    let directory = output.parent().unwrap_or(Path::new(""));
+
+    let mut asset_cache = data_loader::load_texture_asset_cache();
+    let level = data_loader::load_level(mission, &mut asset_cache)?;
+
+    info!("Exporting {} polygons...", level.all_geometry.len());
+    let export = dark::mission::gltf_export::export(&level, name)
+        .ok_or_else(|| anyhow::anyhow!("{} has no textured geometry to export", mission))?;
+    let (document_path, texture_count) = export.write(&mut asset_cache, directory)?;
+    info!(
+        "Wrote {} ({} textures)",
+        document_path.display(),
+        texture_count
+    );
+
+    Ok(())
+}
+
 fn handle_speech_command(voice: Option<&str>, tags: &[String]) -> Result<()> {
     let analyzer = SpeechAnalyzer::new()?;
 
@@ -1,5 +1,7 @@
 use anyhow::Result;
-use dark::motion::{MotionDB, MotionQuery, MotionQueryItem, MotionQuerySelectionStrategy};
+use dark::motion::{
+    MotionDB, MotionDbOverlay, MotionQuery, MotionQueryItem, MotionQuerySelectionStrategy,
+};
 use shock2vr::paths;
 use std::collections::HashMap;
 use std::fs::File;
@@ -36,6 +38,14 @@ impl MotionAnalyzer {
         })
     }
 
+    pub fn apply_overlay(&mut self, overlay: MotionDbOverlay) {
+        self.motion_db.apply_overlay(overlay);
+    }
+
+    pub fn export_json(&self) -> Result<String> {
+        Ok(self.motion_db.to_json()?)
+    }
+
     pub fn list_all_tags_and_animations(
         &self,
         creature_type: u32,
@@ -31,6 +31,7 @@ use engine::assets::asset_paths::AssetPath;
 use engine::scene::Scene;
 use engine::scene::SceneObject;
 use engine::scene::TextVertex;
+use runtime_core::{DesktopWindow, WindowOptions};
 use shock2vr::GameOptions;
 use shock2vr::command::Command;
 use shock2vr::command::SaveCommand;
@@ -306,39 +307,12 @@ pub fn main() {
     let mut audio_context: AudioContext<(), String> = AudioContext::new();
 
     tracing_subscriber::fmt::init();
-    let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
-    glfw.window_hint(glfw::WindowHint::ContextVersion(4, 1));
-    glfw.window_hint(glfw::WindowHint::OpenGlProfile(
-        glfw::OpenGlProfileHint::Core,
-    ));
-    #[cfg(target_os = "macos")]
-    glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-    let (mut window, events) = glfw
-        .create_window(
-            SCR_WIDTH,
-            SCR_HEIGHT,
-            "Shock Engine - Viewer",
-            glfw::WindowMode::Windowed,
-        )
-        .expect("Failed to create GLFW window");
-
-    window.make_current();
-    window.set_key_polling(true);
-    window.set_scroll_polling(true);
-    window.set_cursor_pos_polling(true);
-    window.set_framebuffer_size_polling(true);
-    window.set_cursor_mode(glfw::CursorMode::Disabled);
-
-    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-    println!(
-        "es2 extension supported: {}",
-        glfw.extension_supported("GL_ARB_ES2_compatibility")
-    );
-    println!(
-        "es3 extension supported: {}",
-        glfw.extension_supported("GL_ARB_ES3_compatibility")
+    let DesktopWindow {
+        mut glfw,
+        mut window,
+        events,
+    } = DesktopWindow::create(
+        &WindowOptions::new("Shock Engine - Viewer", SCR_WIDTH, SCR_HEIGHT).with_captured_cursor(),
     );
 
     let engine = engine::opengl();
@@ -55,7 +55,9 @@ impl BinAiViewerScene {
         asset_cache: &mut AssetCache,
         debug_skeletons: bool,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let model = asset_cache.get(&MODELS_IMPORTER, mesh_file_path.as_str());
+        let model = asset_cache
+            .get(&MODELS_IMPORTER, mesh_file_path.as_str())
+            .unwrap();
 
         let mut controller = load_animation_controller(clip_names, asset_cache)?;
         if controller.is_empty() {
@@ -1,9 +1,9 @@
 use super::ToolScene;
-use cgmath::{vec3, Deg, Matrix4, Quaternion, Rad};
+use cgmath::{Deg, Matrix4, Quaternion, Rad, vec3};
 use dark::importers::MODELS_IMPORTER;
 use dark::motion::AnimationPlayer;
 use engine::assets::asset_cache::AssetCache;
-use engine::scene::{color_material, Scene, SceneObject};
+use engine::scene::{Scene, SceneObject, color_material};
 use std::time::Duration;
 
 pub struct CustomizableBonesScene {
@@ -52,7 +52,7 @@ impl ToolScene for CustomizableBonesScene {
     }
 
     fn render(&self, asset_cache: &mut AssetCache) -> Scene {
-        let turret = asset_cache.get(&MODELS_IMPORTER, &self.model_name);
+        let turret = asset_cache.get(&MODELS_IMPORTER, &self.model_name).unwrap();
         let turret_scene_objects = turret.to_animated_scene_objects(&self.animation_player);
 
         Scene::from_objects(turret_scene_objects)
@@ -42,7 +42,9 @@ impl ToolScene for GlbViewerScene {
     }
 
     fn render(&self, asset_cache: &mut AssetCache) -> Scene {
-        let model = asset_cache.get(&GLB_MODELS_IMPORTER, &self.model_name);
+        let model = asset_cache
+            .get(&GLB_MODELS_IMPORTER, &self.model_name)
+            .unwrap();
         let mut scene_objects = model.clone_scene_objects();
 
         // Apply scale transformation to all scene objects
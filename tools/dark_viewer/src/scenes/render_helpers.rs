@@ -38,7 +38,7 @@ pub fn build_model_scene_with_debug_skeletons(
 /// Create a ground plane SceneObject with grid texture and proper scaling
 pub fn create_ground_plane(asset_cache: &mut AssetCache) -> SceneObject {
     // Load grid texture and create material with 100% emissivity and 50% transparency
-    let grid_texture = asset_cache.get(&TEXTURE_IMPORTER, "grid.png");
+    let grid_texture = asset_cache.get(&TEXTURE_IMPORTER, "grid.png").unwrap();
     let texture_trait: std::rc::Rc<dyn engine::texture::TextureTrait> = grid_texture;
     let ground_material = basic_material::create(texture_trait, 1.0, 0.5);
 
@@ -26,6 +26,13 @@ struct Cli {
 enum Commands {
     /// Test connectivity to debug runtime
     Health,
+    /// Run a developer console command, ie `console spawn pistol` - lists the commands if empty
+    Console {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        line: Vec<String>,
+    },
+    /// Get, or set, the game speed (0.1 - 8, 1 is normal speed)
+    TimeScale { time_scale: Option<f32> },
 }
 
 #[tokio::main]
@@ -37,6 +44,43 @@ async fn main() -> anyhow::Result<()> {
             println!("Health check placeholder - implementation coming in Phase 1.4");
             println!("Would connect to: {}", cli.host);
         }
+        Some(Commands::Console { line }) => {
+            let client = reqwest::Client::new();
+            let response: serde_json::Value = if line.is_empty() {
+                client
+                    .get(format!("{}/v1/console", cli.host))
+                    .send()
+                    .await?
+                    .json()
+                    .await?
+            } else {
+                client
+                    .post(format!("{}/v1/console", cli.host))
+                    .json(&serde_json::json!({ "command": line.join(" ") }))
+                    .send()
+                    .await?
+                    .json()
+                    .await?
+            };
+            print_response(&response, cli.raw)?;
+        }
+        Some(Commands::TimeScale { time_scale }) => {
+            let client = reqwest::Client::new();
+            let url = format!("{}/v1/control/time_scale", cli.host);
+            let response: serde_json::Value = match time_scale {
+                None => client.get(url).send().await?.json().await?,
+                Some(time_scale) => {
+                    client
+                        .post(url)
+                        .json(&serde_json::json!({ "time_scale": time_scale }))
+                        .send()
+                        .await?
+                        .json()
+                        .await?
+                }
+            };
+            print_response(&response, cli.raw)?;
+        }
         None => {
             println!("Debug command placeholder - implementation coming in Phase 1.4");
             println!("Usage: cargo dbgc health");
@@ -45,3 +89,17 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn print_response(response: &serde_json::Value, raw: bool) -> anyhow::Result<()> {
+    if raw {
+        println!("{}", serde_json::to_string(response)?);
+        return Ok(());
+    }
+
+    // Console results read better as plain text
+    match response.get("message").and_then(|message| message.as_str()) {
+        Some(message) => println!("{}", message),
+        None => println!("{}", serde_json::to_string_pretty(response)?),
+    }
+    Ok(())
+}